@@ -0,0 +1,233 @@
+//! `Store::dispatch` 单个 action 的微基准。
+//!
+//! 每个用例在 `iter_batched` 的 setup 闭包里完成 `Store::new` 与预置状态，计时只覆盖一次
+//! `dispatch`。运行：`cargo bench --bench store_dispatch`。
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use zcode::core::Command;
+use zcode::kernel::services::ports::{
+    EditorConfig, FileMatches, GlobalSearchMessage, LspCompletionItem, LspInlayHint,
+    LspInsertTextFormat, LspPosition, LspRange, Match,
+};
+use zcode::kernel::{Action, AppState, EditorAction, Store};
+use zcode::models::{build_file_tree, LoadState, NodeKind};
+
+const LARGE_FILE_LINES: usize = 5000;
+const SEARCH_MATCHES: usize = 1000;
+const COMPLETION_ITEMS: usize = 200;
+const EXPLORER_DIRS: usize = 100;
+const EXPLORER_FILES_PER_DIR: usize = 100;
+
+fn new_store(root: &Path) -> Store {
+    let tree = build_file_tree(root).expect("build file tree");
+    Store::new(AppState::new(
+        root.to_path_buf(),
+        tree,
+        EditorConfig::default(),
+    ))
+}
+
+fn open_file(store: &mut Store, path: PathBuf, content: String) {
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path,
+        content,
+    }));
+}
+
+fn large_rust_source() -> String {
+    let mut out = String::with_capacity(LARGE_FILE_LINES * 40);
+    for i in 0..LARGE_FILE_LINES / 5 {
+        out.push_str(&format!("fn item_{i}(value: usize) -> usize {{\n"));
+        out.push_str("    let doubled = value * 2;\n");
+        out.push_str("    let shifted = doubled + 1;\n");
+        out.push_str("    shifted\n");
+        out.push_str("}\n");
+    }
+    out
+}
+
+fn bench_inlay_hints_large_file(c: &mut Criterion, root: &Path) {
+    let path = root.join("large.rs");
+    let content = large_rust_source();
+    let hints: Vec<LspInlayHint> = (0..LARGE_FILE_LINES as u32)
+        .map(|line| LspInlayHint {
+            position: LspPosition {
+                line,
+                character: 12,
+            },
+            label: ": usize".to_string(),
+            padding_left: false,
+            padding_right: false,
+        })
+        .collect();
+    let range = LspRange {
+        start: LspPosition {
+            line: 0,
+            character: 0,
+        },
+        end: LspPosition {
+            line: LARGE_FILE_LINES as u32,
+            character: 0,
+        },
+    };
+
+    c.bench_function("dispatch/lsp_inlay_hints_5000_lines", |b| {
+        b.iter_batched(
+            || {
+                let mut store = new_store(root);
+                open_file(&mut store, path.clone(), content.clone());
+                let action = Action::LspInlayHints {
+                    path: path.clone(),
+                    version: 0,
+                    range,
+                    hints: hints.clone(),
+                };
+                (store, action)
+            },
+            |(mut store, action)| {
+                let result = store.dispatch(action);
+                (store, result)
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+fn bench_search_matches(c: &mut Criterion, root: &Path) {
+    let path = root.join("search.rs");
+    let matches: Vec<Match> = (0..SEARCH_MATCHES)
+        .map(|i| Match::new(i * 16, i * 16 + 6, i, 0))
+        .collect();
+
+    c.bench_function("dispatch/search_message_1000_matches", |b| {
+        b.iter_batched(
+            || {
+                let mut store = new_store(root);
+                let _ = store.dispatch(Action::SearchStarted { search_id: 1 });
+                let action = Action::SearchMessage(GlobalSearchMessage::FileMatches {
+                    search_id: 1,
+                    file_matches: FileMatches {
+                        path: path.clone(),
+                        matches: matches.clone(),
+                    },
+                });
+                (store, action)
+            },
+            |(mut store, action)| {
+                let result = store.dispatch(action);
+                (store, result)
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+fn completion_item(id: u64) -> LspCompletionItem {
+    let label = format!("item_{id}");
+    LspCompletionItem {
+        id,
+        label: label.clone(),
+        detail: Some("fn(usize) -> usize".to_string()),
+        label_details: None,
+        kind: Some(3),
+        documentation: None,
+        insert_text: label,
+        insert_text_format: LspInsertTextFormat::PlainText,
+        insert_range: None,
+        replace_range: None,
+        sort_text: None,
+        filter_text: None,
+        additional_text_edits: Vec::new(),
+        command: None,
+        data: None,
+    }
+}
+
+fn bench_completion(c: &mut Criterion, root: &Path) {
+    let path = root.join("main.rs");
+    let items: Vec<LspCompletionItem> = (0..COMPLETION_ITEMS as u64).map(completion_item).collect();
+
+    c.bench_function("dispatch/lsp_completion_200_items", |b| {
+        b.iter_batched(
+            || {
+                let mut store = new_store(root);
+                let _ = store.dispatch(Action::RunCommand(Command::FocusEditor));
+                open_file(&mut store, path.clone(), "fn main() { ite }\n".to_string());
+                let _ = store.dispatch(Action::RunCommand(Command::CursorLineEnd));
+                let _ = store.dispatch(Action::RunCommand(Command::LspCompletion));
+                let action = Action::LspCompletion {
+                    items: items.clone(),
+                    is_incomplete: false,
+                };
+                (store, action)
+            },
+            |(mut store, action)| {
+                let result = store.dispatch(action);
+                (store, result)
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+fn bench_explorer_path_created(c: &mut Criterion, root: &Path) {
+    let created = root
+        .join(format!("dir_{}", EXPLORER_DIRS / 2))
+        .join("new.rs");
+
+    c.bench_function("dispatch/explorer_path_created_10000_nodes", |b| {
+        b.iter_batched(
+            || {
+                let mut tree = build_file_tree(root).expect("build file tree");
+                let tree_root = tree.root();
+                for d in 0..EXPLORER_DIRS {
+                    let dir = tree
+                        .insert_child_with_state(
+                            tree_root,
+                            OsString::from(format!("dir_{d}")),
+                            NodeKind::Dir,
+                            LoadState::Loaded,
+                        )
+                        .expect("insert dir");
+                    for f in 0..EXPLORER_FILES_PER_DIR {
+                        let _ = tree.insert_child(
+                            dir,
+                            OsString::from(format!("file_{f}.rs")),
+                            NodeKind::File,
+                        );
+                    }
+                    tree.expand(dir);
+                }
+                let store = Store::new(AppState::new(
+                    root.to_path_buf(),
+                    tree,
+                    EditorConfig::default(),
+                ));
+                let action = Action::ExplorerPathCreated {
+                    path: created.clone(),
+                    is_dir: false,
+                };
+                (store, action)
+            },
+            |(mut store, action)| {
+                let result = store.dispatch(action);
+                (store, result)
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+fn store_dispatch(c: &mut Criterion) {
+    let root = tempfile::tempdir().expect("tempdir");
+    bench_inlay_hints_large_file(c, root.path());
+    bench_search_matches(c, root.path());
+    bench_completion(c, root.path());
+    bench_explorer_path_created(c, root.path());
+}
+
+criterion_group!(benches, store_dispatch);
+criterion_main!(benches);
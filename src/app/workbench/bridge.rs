@@ -1,34 +1,48 @@
 use super::Workbench;
+use crate::kernel::git::diff::hunk_patch_body;
 use crate::kernel::lsp_registry;
-use crate::kernel::services::adapters::perf;
 use crate::kernel::services::adapters::{
-    ClipboardService, GlobalSearchService, LspService, SearchService,
+    get_trash_dir, trash_entry_path, ClipboardService, FilePart, GlobalSearchFilter,
+    GlobalSearchService, LspService, SearchService,
 };
+use crate::kernel::services::adapters::{perf, settings};
 use crate::kernel::services::ports::lsp::column_for_chars;
 use crate::kernel::services::ports::{LspPosition, LspPositionEncoding, LspRange, LspTextChange};
-use crate::kernel::state::PendingAction;
+use crate::kernel::state::{ExplorerOpOrigin, PendingAction};
 use crate::kernel::{Action as KernelAction, EditorAction, Effect as KernelEffect};
 use crate::models::OpKind;
 use ropey::Rope;
 use rustc_hash::FxHashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc;
 use std::time::Instant;
 
 impl Workbench {
     pub(super) fn dispatch_kernel(&mut self, action: KernelAction) -> bool {
-        if let KernelAction::LspDefinition { path, line, .. } = &action {
+        if let KernelAction::LspDefinition { path, line, .. }
+        | KernelAction::LspTypeDefinition { path, line, .. } = &action
+        {
             self.pending_definition_highlight = Some(super::PendingDefinitionHighlight {
                 path: path.clone(),
                 row: *line as usize,
                 armed_at: Instant::now(),
             });
         }
+        if let KernelAction::LspImplementation { items } = &action {
+            if let [item] = items.as_slice() {
+                self.pending_definition_highlight = Some(super::PendingDefinitionHighlight {
+                    path: item.path.clone(),
+                    row: item.line as usize,
+                    armed_at: Instant::now(),
+                });
+            }
+        }
 
         let _scope = perf::scope("kernel.dispatch");
         let result = {
             let _scope = perf::scope("kernel.reduce");
-            self.store.dispatch(action)
+            self.store
+                .dispatch_through(self.kernel_services.middleware_mut(), action)
         };
         self.sync_editor_search_slots();
         self.sync_lsp();
@@ -116,74 +130,41 @@ impl Workbench {
         self.sync_markdown_views();
     }
 
-    fn run_effect(&mut self, effect: KernelEffect) {
+    pub(super) fn run_effect(&mut self, effect: KernelEffect) {
         match effect {
             KernelEffect::LoadFile(path) => {
                 let _scope = perf::scope("effect.load_file");
-                self.runtime.load_file(path)
+                self.load_file(path)
+            }
+            KernelEffect::LoadFileWithoutLimit(path) => {
+                let _scope = perf::scope("effect.load_file");
+                self.runtime.load_file(path, None)
+            }
+            KernelEffect::LoadFileRange {
+                path,
+                start_byte,
+                end_byte,
+            } => {
+                let _scope = perf::scope("effect.load_file");
+                self.runtime.load_file_range(path, start_byte, end_byte)
+            }
+            KernelEffect::BackupEditHistory { path, backup } => {
+                if super::settings_enabled() {
+                    self.runtime.backup_edit_history(path, backup);
+                }
             }
             KernelEffect::LoadDir(path) => {
                 let _scope = perf::scope("effect.load_dir");
                 self.runtime.load_dir(path)
             }
-            KernelEffect::CreateFile(path) => {
-                let _scope = perf::scope("effect.create_file");
-                self.runtime.create_file(path)
-            }
-            KernelEffect::CreateDir(path) => {
-                let _scope = perf::scope("effect.create_dir");
-                self.runtime.create_dir(path)
-            }
-            KernelEffect::RenamePath {
-                from,
-                to,
-                overwrite,
-            } => {
-                let _scope = perf::scope("effect.rename_path");
-                let root = self.store.state().workspace_root.clone();
-                let root = root.as_path();
-                if from.as_path() == root
-                    || to.as_path() == root
-                    || !from.starts_with(root)
-                    || !to.starts_with(root)
-                {
-                    self.push_log_line(format!(
-                        "[fs:rename_path] rejected out-of-workspace path: {} -> {}",
-                        from.display(),
-                        to.display()
-                    ));
-                    return;
-                }
-
-                // Default behavior: do not overwrite the destination.
-                if !overwrite {
-                    if let Ok(meta) = std::fs::symlink_metadata(&to) {
-                        let rel = to
-                            .strip_prefix(root)
-                            .ok()
-                            .map(|p| p.to_string_lossy().to_string())
-                            .unwrap_or_else(|| to.to_string_lossy().to_string());
-
-                        let message = if meta.is_dir() {
-                            format!("Overwrite folder \"{}\" and all contents?", rel)
-                        } else {
-                            format!("Overwrite file \"{}\"?", rel)
-                        };
-
-                        let _ = self.dispatch_kernel(KernelAction::ShowConfirmDialog {
-                            message,
-                            on_confirm: PendingAction::RenamePath {
-                                from,
-                                to,
-                                overwrite: true,
-                            },
-                        });
-                        return;
-                    }
-                }
-
-                self.runtime.rename_path(from, to, overwrite)
+            effect @ (KernelEffect::CreateFile(_)
+            | KernelEffect::CreateDir(_)
+            | KernelEffect::RenamePath { .. }
+            | KernelEffect::DeletePath { .. }
+            | KernelEffect::RestoreFromTrash { .. }) => {
+                self.run_explorer_op(effect, ExplorerOpOrigin::User)
             }
+            KernelEffect::ReplayExplorerOp { op, origin } => self.run_explorer_op(*op, origin),
             KernelEffect::CopyPath {
                 from,
                 to,
@@ -233,33 +214,127 @@ impl Workbench {
 
                 self.runtime.copy_path(from, to, overwrite)
             }
-            KernelEffect::DeletePath { path, is_dir } => {
-                let _scope = perf::scope("effect.delete_path");
-                let root = self.store.state().workspace_root.clone();
-                let root = root.as_path();
-                if path.as_path() == root || !path.starts_with(root) {
-                    self.push_log_line(format!(
-                        "[fs:delete_path] rejected out-of-workspace path: {}",
-                        path.display()
-                    ));
-                    return;
-                }
-
-                self.runtime.delete_path(path, is_dir)
-            }
             KernelEffect::ReloadSettings => {
                 let _scope = perf::scope("effect.reload_settings");
                 self.reload_settings();
             }
+            KernelEffect::ReconfigureLsp { settings } => {
+                let _scope = perf::scope("effect.reconfigure_lsp");
+                let (settings_override, server_overrides) =
+                    super::settings_parse::parse_lsp_settings(&settings);
+                // 环境变量里的命令覆盖优先于设置文件。
+                let global_override = match super::lsp_command_override() {
+                    Some((command, args)) => Some((command, args, None)),
+                    None => settings_override,
+                };
+                if let Some(service) = self.kernel_services.get_mut::<LspService>() {
+                    if service.reconfigure(global_override, server_overrides) {
+                        self.lsp_sync.open_paths.clear();
+                        self.lsp_sync.open_paths_version = 0;
+                    }
+                }
+            }
             KernelEffect::OpenSettings => {
                 let _scope = perf::scope("effect.open_settings");
                 self.open_settings();
             }
+            KernelEffect::ExportKeybindings { path } => {
+                let _scope = perf::scope("effect.export_keybindings");
+                self.export_keybindings(&path);
+            }
+            KernelEffect::ImportKeybindings { path } => {
+                let _scope = perf::scope("effect.import_keybindings");
+                self.import_keybindings(&path);
+            }
+            KernelEffect::ExportAsHtml { path } => {
+                let _scope = perf::scope("effect.export_as_html");
+                self.export_as_html(&path);
+            }
+            KernelEffect::ShowMetrics => {
+                let _scope = perf::scope("effect.show_metrics");
+                self.show_metrics();
+            }
+            KernelEffect::SpawnTerminal { cwd } => {
+                self.pending_shell = Some(cwd);
+            }
+            KernelEffect::AddWorkspaceFolder { path } => {
+                let _scope = perf::scope("effect.add_workspace_folder");
+                self.add_workspace_folder(&path);
+            }
+            KernelEffect::RemoveWorkspaceFolder { path } => {
+                let _scope = perf::scope("effect.remove_workspace_folder");
+                if let Some(service) = self.kernel_services.get_mut::<LspService>() {
+                    service.remove_workspace_folder(&path);
+                }
+            }
+            KernelEffect::ListWorkspaceFiles => {
+                let _scope = perf::scope("effect.list_workspace_files");
+                let root = self.store.state().workspace_root.clone();
+                self.runtime.list_workspace_files(root);
+            }
+            KernelEffect::GitBlame { path, line } => {
+                let _scope = perf::scope("effect.git_blame");
+                self.runtime.git_blame(path, line);
+            }
+            KernelEffect::GitStashList => {
+                let _scope = perf::scope("effect.git_stash_list");
+                let root = self.store.state().workspace_root.clone();
+                self.runtime.git_stash_list(root);
+            }
+            KernelEffect::GitStashPush { include_untracked } => {
+                let _scope = perf::scope("effect.git_stash_push");
+                let root = self.store.state().workspace_root.clone();
+                self.runtime.git_stash_push(root, include_untracked);
+            }
+            KernelEffect::GitStashApply { index } => {
+                let _scope = perf::scope("effect.git_stash_apply");
+                let root = self.store.state().workspace_root.clone();
+                self.runtime.git_stash_apply(root, index);
+            }
+            KernelEffect::GitStashDrop { index } => {
+                let _scope = perf::scope("effect.git_stash_drop");
+                let root = self.store.state().workspace_root.clone();
+                self.runtime.git_stash_drop(root, index);
+            }
+            KernelEffect::GitBranchList => {
+                let _scope = perf::scope("effect.git_branch_list");
+                let root = self.store.state().workspace_root.clone();
+                self.runtime.git_branch_list(root);
+            }
+            KernelEffect::GitCheckout { branch, create } => {
+                let _scope = perf::scope("effect.git_checkout");
+                let root = self.store.state().workspace_root.clone();
+                self.runtime.git_checkout(root, branch, create);
+            }
+            KernelEffect::GitLoadDiffBase { path } => {
+                let _scope = perf::scope("effect.git_load_diff_base");
+                self.runtime.git_load_diff_base(path);
+            }
+            KernelEffect::GitStageHunk { path, hunk_index } => {
+                let _scope = perf::scope("effect.git_stage_hunk");
+                let body = self
+                    .store
+                    .state()
+                    .git
+                    .diff
+                    .as_ref()
+                    .filter(|view| view.path == path)
+                    .and_then(|view| {
+                        let hunk = view.diff.hunks.get(hunk_index)?;
+                        Some(hunk_patch_body(&view.original, &view.current, hunk))
+                    });
+                if let Some(body) = body {
+                    self.runtime.git_stage_hunk(path, body);
+                }
+            }
             KernelEffect::StartGlobalSearch {
                 root,
                 pattern,
                 case_sensitive,
                 use_regex,
+                skip_binary,
+                include_glob,
+                exclude_glob,
             } => {
                 let _scope = perf::scope("effect.global_search");
                 if let Some(task) = self.global_search_task.take() {
@@ -270,7 +345,55 @@ impl Workbench {
                 self.global_search_rx = Some(rx);
 
                 if let Some(service) = self.kernel_services.get::<GlobalSearchService>() {
-                    let task = service.search_in_dir(root, pattern, case_sensitive, use_regex, tx);
+                    let task = service.search_in_dir_filtered(
+                        root,
+                        pattern,
+                        case_sensitive,
+                        use_regex,
+                        skip_binary,
+                        GlobalSearchFilter {
+                            include_glob,
+                            exclude_glob,
+                        },
+                        tx,
+                    );
+                    let search_id = task.id();
+                    self.global_search_task = Some(task);
+                    let _ = self.dispatch_kernel(KernelAction::SearchStarted { search_id });
+                }
+            }
+            KernelEffect::StartGlobalReplace {
+                root,
+                pattern,
+                replacement,
+                case_sensitive,
+                use_regex,
+                skip_binary,
+                include_glob,
+                exclude_glob,
+            } => {
+                let _scope = perf::scope("effect.global_replace");
+                if let Some(task) = self.global_search_task.take() {
+                    task.cancel();
+                }
+
+                let (tx, rx) = mpsc::sync_channel(super::GLOBAL_SEARCH_CHANNEL_CAP);
+                self.global_search_rx = Some(rx);
+
+                if let Some(service) = self.kernel_services.get::<GlobalSearchService>() {
+                    let task = service.replace_in_dir_filtered(
+                        root,
+                        pattern,
+                        replacement,
+                        case_sensitive,
+                        use_regex,
+                        skip_binary,
+                        GlobalSearchFilter {
+                            include_glob,
+                            exclude_glob,
+                        },
+                        tx,
+                    );
                     let search_id = task.id();
                     self.global_search_task = Some(task);
                     let _ = self.dispatch_kernel(KernelAction::SearchStarted { search_id });
@@ -282,6 +405,7 @@ impl Workbench {
                 pattern,
                 case_sensitive,
                 use_regex,
+                whole_word,
             } => {
                 let _scope = perf::scope("effect.editor_search");
                 self.sync_editor_search_slots();
@@ -298,7 +422,14 @@ impl Workbench {
                 self.editor_search_rx[pane] = Some(rx);
 
                 if let Some(service) = self.kernel_services.get::<SearchService>() {
-                    let task = service.search_in_rope(rope, pattern, case_sensitive, use_regex, tx);
+                    let task = service.search_in_rope(
+                        rope,
+                        pattern,
+                        case_sensitive,
+                        use_regex,
+                        whole_word,
+                        tx,
+                    );
                     let search_id = task.id();
                     self.editor_search_tasks[pane] = Some(task);
                     let _ =
@@ -350,26 +481,77 @@ impl Workbench {
                 };
 
                 let rope = tab.buffer.rope().clone();
-                self.runtime.write_file(pane, path, version, head, rope);
+                self.runtime
+                    .write_file(pane, path, version, head, rope, None);
+            }
+            KernelEffect::WriteFilePart {
+                pane,
+                path,
+                byte_range,
+                replacement,
+                disk_len,
+                version,
+                head,
+            } => {
+                let _scope = perf::scope("effect.write_file_part");
+                let Some(tab) = self.store.state().editor.pane(pane).and_then(|pane_state| {
+                    pane_state
+                        .tabs
+                        .iter()
+                        .find(|t| t.path.as_deref() == Some(path.as_path()))
+                }) else {
+                    return;
+                };
+
+                // 整份 rope 仅作为磁盘文件被外部改动时的退路。
+                let rope = tab.buffer.rope().clone();
+                let part = FilePart {
+                    byte_range,
+                    replacement,
+                    disk_len,
+                };
+                self.runtime
+                    .write_file(pane, path, version, head, rope, Some(part));
             }
             KernelEffect::SetClipboardText(text) => {
                 let _scope = perf::scope("effect.clipboard_set");
                 self.set_clipboard_text(&text);
             }
+            KernelEffect::OpenEditorContextMenu { pane } => {
+                self.open_editor_context_menu_at_cursor(pane);
+            }
+            KernelEffect::OpenTerminalUrl(url) => {
+                let _scope = perf::scope("effect.open_url");
+                if let Err(err) = crate::kernel::services::adapters::url::open_url(&url) {
+                    self.push_log_line(format!("[open] {url}: {err}"));
+                }
+            }
             KernelEffect::RequestClipboardText { pane } => {
                 let _scope = perf::scope("effect.clipboard_get");
                 let get_result = self
                     .kernel_services
                     .get_mut::<ClipboardService>()
-                    .map(|svc| svc.get_text());
+                    .map(|svc| svc.paste_text());
 
                 match get_result {
                     Some(Ok(text)) if !text.is_empty() => {
+                        // 粘贴的正是历史里最新一条时，紧接着的循环粘贴从它往前换。
+                        let latest = self
+                            .kernel_services
+                            .get_mut::<ClipboardService>()
+                            .is_some_and(|svc| {
+                                svc.reset_history_index();
+                                svc.history().front() == Some(&text)
+                            });
                         let _ =
                             self.dispatch_kernel(KernelAction::Editor(EditorAction::InsertText {
                                 pane,
-                                text,
+                                text: text.clone(),
                             }));
+                        self.clipboard_paste = None;
+                        if latest {
+                            self.record_clipboard_paste(pane, &text);
+                        }
                     }
                     Some(Ok(_)) => {}
                     Some(Err(err)) => {
@@ -379,6 +561,18 @@ impl Workbench {
                     None => self.maybe_warn_clipboard_unavailable(),
                 }
             }
+            KernelEffect::CycleClipboardHistory { pane, delta } => {
+                let _scope = perf::scope("effect.clipboard_cycle");
+                self.cycle_clipboard_history(pane, delta);
+            }
+            KernelEffect::RequestClipboardHistory => {
+                let entries = self
+                    .kernel_services
+                    .get::<ClipboardService>()
+                    .map(|svc| svc.history().iter().cloned().collect())
+                    .unwrap_or_default();
+                let _ = self.dispatch_kernel(KernelAction::ClipboardHistoryLoaded { entries });
+            }
             KernelEffect::LspHoverRequest { path, line, column } => {
                 let _scope = perf::scope("effect.lsp_hover");
                 if let Some(service) = self.kernel_services.get_mut::<LspService>() {
@@ -408,6 +602,30 @@ impl Workbench {
                     );
                 }
             }
+            KernelEffect::LspTypeDefinitionRequest { path, line, column } => {
+                let _scope = perf::scope("effect.lsp_type_definition");
+                if let Some(service) = self.kernel_services.get_mut::<LspService>() {
+                    service.request_type_definition(
+                        &path,
+                        LspPosition {
+                            line,
+                            character: column,
+                        },
+                    );
+                }
+            }
+            KernelEffect::LspImplementationRequest { path, line, column } => {
+                let _scope = perf::scope("effect.lsp_implementation");
+                if let Some(service) = self.kernel_services.get_mut::<LspService>() {
+                    service.request_implementation(
+                        &path,
+                        LspPosition {
+                            line,
+                            character: column,
+                        },
+                    );
+                }
+            }
             KernelEffect::LspReferencesRequest { path, line, column } => {
                 let _scope = perf::scope("effect.lsp_references");
                 if let Some(service) = self.kernel_services.get_mut::<LspService>() {
@@ -420,6 +638,30 @@ impl Workbench {
                     );
                 }
             }
+            KernelEffect::LspPrepareCallHierarchyRequest { path, line, column } => {
+                let _scope = perf::scope("effect.lsp_prepare_call_hierarchy");
+                if let Some(service) = self.kernel_services.get_mut::<LspService>() {
+                    service.request_prepare_call_hierarchy(
+                        &path,
+                        LspPosition {
+                            line,
+                            character: column,
+                        },
+                    );
+                }
+            }
+            KernelEffect::LspCallHierarchyIncomingRequest { item_index, item } => {
+                let _scope = perf::scope("effect.lsp_call_hierarchy_incoming");
+                if let Some(service) = self.kernel_services.get_mut::<LspService>() {
+                    service.request_call_hierarchy_incoming(item_index, *item);
+                }
+            }
+            KernelEffect::LspCallHierarchyOutgoingRequest { item_index, item } => {
+                let _scope = perf::scope("effect.lsp_call_hierarchy_outgoing");
+                if let Some(service) = self.kernel_services.get_mut::<LspService>() {
+                    service.request_call_hierarchy_outgoing(item_index, *item);
+                }
+            }
             KernelEffect::LspCodeActionRequest { path, line, column } => {
                 let _scope = perf::scope("effect.lsp_code_action");
                 if let Some(service) = self.kernel_services.get_mut::<LspService>() {
@@ -472,6 +714,40 @@ impl Workbench {
                     service.request_folding_range(&path, version);
                 }
             }
+            KernelEffect::LspDocumentLinkRequest { path, version } => {
+                let _scope = perf::scope("effect.lsp_document_link");
+                if let Some(service) = self.kernel_services.get_mut::<LspService>() {
+                    service.request_document_link(&path, version);
+                }
+            }
+            KernelEffect::LspDocumentLinkResolveRequest {
+                path,
+                version,
+                link,
+            } => {
+                let _scope = perf::scope("effect.lsp_document_link_resolve");
+                if let Some(service) = self.kernel_services.get_mut::<LspService>() {
+                    service.request_document_link_resolve(&path, version, link);
+                }
+            }
+            KernelEffect::LspDocumentHighlightRequest {
+                path,
+                line,
+                column,
+                version,
+            } => {
+                let _scope = perf::scope("effect.lsp_document_highlight");
+                if let Some(service) = self.kernel_services.get_mut::<LspService>() {
+                    service.request_document_highlight(
+                        &path,
+                        LspPosition {
+                            line,
+                            character: column,
+                        },
+                        version,
+                    );
+                }
+            }
             KernelEffect::LspSignatureHelpRequest { path, line, column } => {
                 let _scope = perf::scope("effect.lsp_signature_help");
                 if let Some(service) = self.kernel_services.get_mut::<LspService>() {
@@ -484,6 +760,18 @@ impl Workbench {
                     );
                 }
             }
+            KernelEffect::LspPrepareRenameRequest { path, line, column } => {
+                let _scope = perf::scope("effect.lsp_prepare_rename");
+                if let Some(service) = self.kernel_services.get_mut::<LspService>() {
+                    service.request_prepare_rename(
+                        &path,
+                        LspPosition {
+                            line,
+                            character: column,
+                        },
+                    );
+                }
+            }
             KernelEffect::LspRenameRequest {
                 path,
                 line,
@@ -555,6 +843,162 @@ impl Workbench {
             }
         }
     }
+
+    /// 执行资源管理器的新建/重命名/删除/还原；逆操作先挂起，等运行时报告成功再记进撤销栈。
+    fn run_explorer_op(&mut self, effect: KernelEffect, origin: ExplorerOpOrigin) {
+        let inverse = match effect {
+            KernelEffect::CreateFile(path) => {
+                let _scope = perf::scope("effect.create_file");
+                let template = super::settings_enabled()
+                    .then(|| settings::load_file_template(&path))
+                    .flatten()
+                    .unwrap_or_default();
+                self.runtime
+                    .create_file_with_template(path.clone(), template);
+                Some(KernelEffect::DeletePath {
+                    path,
+                    is_dir: false,
+                })
+            }
+            KernelEffect::CreateDir(path) => {
+                let _scope = perf::scope("effect.create_dir");
+                self.runtime.create_dir(path.clone());
+                Some(KernelEffect::DeletePath { path, is_dir: true })
+            }
+            KernelEffect::RenamePath {
+                from,
+                to,
+                overwrite,
+            } => {
+                let _scope = perf::scope("effect.rename_path");
+                let root = self.store.state().workspace_root.clone();
+                let root = root.as_path();
+                if from.as_path() == root
+                    || to.as_path() == root
+                    || !from.starts_with(root)
+                    || !to.starts_with(root)
+                {
+                    self.push_log_line(format!(
+                        "[fs:rename_path] rejected out-of-workspace path: {} -> {}",
+                        from.display(),
+                        to.display()
+                    ));
+                    return;
+                }
+
+                // Default behavior: do not overwrite the destination.
+                if !overwrite {
+                    if let Ok(meta) = std::fs::symlink_metadata(&to) {
+                        let rel = to
+                            .strip_prefix(root)
+                            .ok()
+                            .map(|p| p.to_string_lossy().to_string())
+                            .unwrap_or_else(|| to.to_string_lossy().to_string());
+
+                        let message = if meta.is_dir() {
+                            format!("Overwrite folder \"{}\" and all contents?", rel)
+                        } else {
+                            format!("Overwrite file \"{}\"?", rel)
+                        };
+
+                        let _ = self.dispatch_kernel(KernelAction::ShowConfirmDialog {
+                            message,
+                            on_confirm: PendingAction::RenamePath {
+                                from,
+                                to,
+                                overwrite: true,
+                            },
+                        });
+                        return;
+                    }
+                }
+
+                self.runtime
+                    .rename_path(from.clone(), to.clone(), overwrite);
+                // 覆盖掉的目标已经没了，改名回去也找不回它，不记逆操作。
+                (!overwrite).then_some(KernelEffect::RenamePath {
+                    from: to,
+                    to: from,
+                    overwrite: false,
+                })
+            }
+            KernelEffect::DeletePath { path, is_dir } => {
+                let _scope = perf::scope("effect.delete_path");
+                let root = self.store.state().workspace_root.clone();
+                let root = root.as_path();
+                if path.as_path() == root || !path.starts_with(root) {
+                    self.push_log_line(format!(
+                        "[fs:delete_path] rejected out-of-workspace path: {}",
+                        path.display()
+                    ));
+                    return;
+                }
+
+                // 拿不到回收站目录（或测试里不碰用户目录）时只能直接删除，也就无从撤销。
+                let trash_dir = super::settings_enabled().then(get_trash_dir).flatten();
+                let Some(trash_dir) = trash_dir else {
+                    self.runtime.delete_path(path, is_dir);
+                    return;
+                };
+                let trashed = trash_entry_path(&trash_dir, &path);
+                self.runtime.trash_path(path.clone(), trashed.clone());
+                Some(KernelEffect::RestoreFromTrash {
+                    trashed,
+                    path,
+                    is_dir,
+                })
+            }
+            KernelEffect::RestoreFromTrash {
+                trashed,
+                path,
+                is_dir,
+            } => {
+                let _scope = perf::scope("effect.restore_from_trash");
+                self.runtime
+                    .restore_from_trash(trashed, path.clone(), is_dir);
+                Some(KernelEffect::DeletePath { path, is_dir })
+            }
+            _ => None,
+        };
+
+        if let Some(inverse) = inverse {
+            self.pending_explorer_ops.push((inverse, origin));
+        }
+    }
+
+    /// 运行时报告了一次文件操作的结果：取出 `matches` 命中的挂起操作，成功才记进撤销栈。
+    pub(super) fn finish_explorer_op(
+        &mut self,
+        succeeded: bool,
+        matches: impl Fn(&KernelEffect) -> bool,
+    ) {
+        let Some(index) = self
+            .pending_explorer_ops
+            .iter()
+            .position(|(inverse, _)| matches(inverse))
+        else {
+            return;
+        };
+        let (inverse, origin) = self.pending_explorer_ops.remove(index);
+        if succeeded {
+            let _ = self.dispatch_kernel(KernelAction::ExplorerOpApplied { inverse, origin });
+        }
+    }
+}
+
+/// `FsOpError` 的 `path` / `to` 是否对应挂起的逆操作 `inverse`：新建 / 还原失败对应
+/// `DeletePath`，改名失败对应反向的 `RenamePath`，移进回收站失败对应 `RestoreFromTrash`。
+pub(super) fn explorer_inverse_matches_error(
+    inverse: &KernelEffect,
+    path: &Path,
+    to: Option<&Path>,
+) -> bool {
+    match inverse {
+        KernelEffect::DeletePath { path: p, .. } => p == path || Some(p.as_path()) == to,
+        KernelEffect::RenamePath { from, to: t, .. } => t == path && Some(from.as_path()) == to,
+        KernelEffect::RestoreFromTrash { path: p, .. } => p == path,
+        _ => false,
+    }
 }
 
 impl Workbench {
@@ -580,7 +1024,7 @@ impl Workbench {
         self.lsp_sync.file_watcher_open_paths_version = open_paths_version;
     }
 
-    fn sync_lsp(&mut self) {
+    pub(super) fn sync_lsp(&mut self) {
         let Some(service) = self.kernel_services.get_mut::<LspService>() else {
             return;
         };
@@ -609,6 +1053,10 @@ impl Workbench {
                 service.close_document(path);
             }
 
+            if !newly_open.is_empty() {
+                self.lsp_sync.debounce.document_link =
+                    Some(Instant::now() + super::interaction::DOCUMENT_LINK_DEBOUNCE);
+            }
             for (path, tab) in newly_open {
                 service.sync_document(&path, tab.edit_version, None, || tab.buffer.text());
             }
@@ -641,7 +1089,7 @@ impl Workbench {
     }
 }
 
-fn lsp_position_encoding_for_path(
+pub(super) fn lsp_position_encoding_for_path(
     state: &crate::kernel::AppState,
     path: &std::path::Path,
 ) -> LspPositionEncoding {
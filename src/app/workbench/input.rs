@@ -34,6 +34,7 @@ fn dispatch_by_target(
         MouseTarget::SidebarSplitter => workbench
             .handle_sidebar_split_mouse(mouse_event, ui_out)
             .unwrap_or(EventResult::Ignored),
+        MouseTarget::StatusBar => workbench.handle_status_bar_mouse(mouse_event),
         MouseTarget::Explorer => workbench.handle_explorer_mouse(mouse_event, ui_out),
         MouseTarget::Editor => workbench.handle_editor_mouse(mouse_event, ui_out),
         MouseTarget::Overlay => workbench.handle_overlay_mouse(mouse_event),
@@ -85,10 +85,11 @@ impl Workbench {
             return;
         }
 
+        // 走 OSC 52 时复制不依赖系统剪贴板工具，不必提示安装。
         let available = self
             .kernel_services
             .get::<crate::kernel::services::adapters::ClipboardService>()
-            .is_some_and(|svc| svc.is_available());
+            .is_some_and(|svc| svc.is_available() || svc.prefers_osc52());
         if available {
             return;
         }
@@ -100,6 +101,26 @@ impl Workbench {
     }
 
     pub(in super::super) fn set_clipboard_text(&mut self, text: &str) {
+        let prefers_osc52 = self
+            .kernel_services
+            .get::<crate::kernel::services::adapters::ClipboardService>()
+            .is_some_and(|svc| svc.prefers_osc52());
+        if prefers_osc52 {
+            match crate::tui::osc52::copy_to_clipboard(text) {
+                Ok(()) => {
+                    let mirrored = self
+                        .kernel_services
+                        .get_mut::<crate::kernel::services::adapters::ClipboardService>()
+                        .map(|svc| svc.copied_via_osc52(text));
+                    if let Some(Err(err)) = mirrored {
+                        self.push_log_line(format!("[clipboard] {err}"));
+                    }
+                    return;
+                }
+                Err(err) => self.push_log_line(format!("[clipboard:osc52] {err}")),
+            }
+        }
+
         let set_result = self
             .kernel_services
             .get_mut::<crate::kernel::services::adapters::ClipboardService>()
@@ -107,6 +128,10 @@ impl Workbench {
 
         match set_result {
             Some(Ok(())) => {}
+            Some(Err(err)) if prefers_osc52 => {
+                self.maybe_warn_clipboard_unavailable();
+                self.push_log_line(format!("[clipboard] {err}"));
+            }
             Some(Err(err)) => {
                 self.maybe_warn_clipboard_unavailable();
 
@@ -127,6 +152,85 @@ impl Workbench {
         }
     }
 
+    /// 剪贴板历史里环形移动 `delta` 条再粘贴：紧接上次历史粘贴（之后没有编辑、光标没动）时
+    /// 替换掉那段文本，否则在光标处插入。
+    pub(in super::super) fn cycle_clipboard_history(&mut self, pane: usize, delta: isize) {
+        let replace = self
+            .clipboard_paste
+            .filter(|paste| self.clipboard_paste_is_current(paste, pane));
+        let Some(svc) = self
+            .kernel_services
+            .get_mut::<crate::kernel::services::adapters::ClipboardService>()
+        else {
+            return;
+        };
+        if replace.is_none() {
+            svc.reset_history_index();
+        }
+        let Some(text) = svc.cycle_history(delta).map(str::to_string) else {
+            return;
+        };
+
+        let action = match replace {
+            Some(paste) => EditorAction::ReplaceRangeChars {
+                pane,
+                start_char: paste.start_char,
+                end_char: paste.end_char,
+                text: text.clone(),
+            },
+            None => EditorAction::InsertText {
+                pane,
+                text: text.clone(),
+            },
+        };
+        let _ = self.dispatch_kernel(KernelAction::Editor(action));
+        self.record_clipboard_paste(pane, &text);
+
+        let refresh = Command::Paste;
+        self.maybe_schedule_inlay_hints_debounce(&refresh);
+        self.maybe_schedule_folding_range_debounce(&refresh);
+    }
+
+    /// 记下刚粘贴进 `pane` 的 `text` 的区间（光标停在其末尾）；多光标粘贴不记。
+    pub(in super::super) fn record_clipboard_paste(&mut self, pane: usize, text: &str) {
+        self.clipboard_paste = self
+            .store
+            .state()
+            .editor
+            .pane(pane)
+            .and_then(|pane_state| pane_state.active_tab())
+            .filter(|tab| !tab.is_multi_cursor())
+            .map(|tab| {
+                let end_char = tab.buffer.pos_to_char(tab.buffer.cursor());
+                super::super::ClipboardPaste {
+                    pane,
+                    start_char: end_char.saturating_sub(text.chars().count()),
+                    end_char,
+                    edit_version: tab.edit_version,
+                }
+            });
+    }
+
+    fn clipboard_paste_is_current(
+        &self,
+        paste: &super::super::ClipboardPaste,
+        pane: usize,
+    ) -> bool {
+        paste.pane == pane
+            && self
+                .store
+                .state()
+                .editor
+                .pane(pane)
+                .and_then(|pane_state| pane_state.active_tab())
+                .is_some_and(|tab| {
+                    tab.edit_version == paste.edit_version
+                        && !tab.is_multi_cursor()
+                        && !tab.buffer.has_selection()
+                        && tab.buffer.pos_to_char(tab.buffer.cursor()) == paste.end_char
+                })
+    }
+
     /// 用户可见的诊断面包屑（剪贴板不可用、越界 fs 操作等）。Logs 视图已删除，
     /// 这些条目改写入日志文件，避免静默吞掉错误。
     pub(in super::super) fn push_log_line(&self, line: String) {
@@ -14,10 +14,11 @@ use crate::ui::core::tree::NodeKind;
 use crate::views::editor::coord;
 use crate::views::editor::markdown::MarkdownDocument;
 use crate::views::{
-    compute_editor_pane_layout, hit_test_editor_mouse, hit_test_editor_mouse_drag,
-    hit_test_editor_tab, hit_test_editor_vertical_scrollbar, hit_test_search_bar,
-    hit_test_tab_hover, tab_insertion_index, vertical_scrollbar_metrics,
-    EditorVerticalScrollbarHitResult, SearchBarHitResult, TabHitResult,
+    compute_editor_pane_layout, cursor_position_editor, hit_test_breadcrumbs,
+    hit_test_editor_gutter, hit_test_editor_mouse, hit_test_editor_mouse_drag, hit_test_editor_tab,
+    hit_test_editor_vertical_scrollbar, hit_test_search_bar, hit_test_tab_hover, minimap_metrics,
+    tab_insertion_index, vertical_scrollbar_metrics, EditorVerticalScrollbarHitResult,
+    SearchBarHitResult, TabHitResult,
 };
 use std::time::Instant;
 
@@ -47,7 +48,7 @@ impl Workbench {
         let Some(pane_state) = self.store.state().editor.pane(pane) else {
             return EventResult::Ignored;
         };
-        let config = &self.store.state().editor.config;
+        let config = &self.store.state().editor_config_for_pane(pane);
         let tab_size = config.tab_size;
         let click_slop = config.click_slop;
         let triple_click_ms = config.triple_click_ms;
@@ -62,6 +63,15 @@ impl Workbench {
             )
         });
 
+        let minimap = pane_state.active_tab().and_then(|tab| {
+            minimap_metrics(
+                &layout,
+                tab.buffer.len_lines().max(1),
+                layout.editor_area.h as usize,
+                tab.viewport.line_offset,
+            )
+        });
+
         let hovered_idx = self
             .store
             .state()
@@ -91,6 +101,17 @@ impl Workbench {
             MouseEventKind::Down(MouseButton::Left) => {
                 self.interaction.editor_scrollbar_drag = None;
 
+                if let Some(index) =
+                    hit_test_breadcrumbs(&layout, pane_state, event.column, event.row)
+                {
+                    if let Some(index) = index {
+                        let _ = self.dispatch_kernel(KernelAction::Editor(
+                            EditorAction::SelectBreadcrumb { pane, index },
+                        ));
+                    }
+                    return EventResult::Consumed;
+                }
+
                 if let Some(result) =
                     hit_test_search_bar(&layout, &pane_state.search_bar, event.column, event.row)
                 {
@@ -169,71 +190,119 @@ impl Workbench {
                     }
                 }
 
+                if let Some(metrics) = minimap {
+                    if metrics.area.contains(Pos::new(event.column, event.row)) {
+                        let _ = self.scroll_editor_to_line_offset(
+                            pane,
+                            metrics.line_offset_for_row(event.row),
+                        );
+                        return EventResult::Consumed;
+                    }
+                }
+
+                if let Some(y) = hit_test_editor_gutter(&layout, event.column, event.row) {
+                    let line = pane_state.active_tab().and_then(|tab| {
+                        let line = coord::line_starting_at_row(tab, y)?;
+                        tab.fold_marker_char(line.min(u32::MAX as usize) as u32)
+                            .map(|_| line)
+                    });
+                    if let Some(line) = line {
+                        let _ =
+                            self.dispatch_kernel(KernelAction::Editor(EditorAction::ToggleFold {
+                                pane,
+                                line,
+                            }));
+                    }
+                    return EventResult::Consumed;
+                }
+
                 if let Some((x, y)) = hit_test_editor_mouse(&layout, event.column, event.row) {
                     if let Some(tab) = pane_state.active_tab() {
-                        let visible = tab.visible_lines_in_viewport(
-                            tab.viewport.line_offset,
-                            tab.viewport.height.max(1),
-                        );
-                        if let Some(&row) = visible.get(y as usize) {
-                            let col = {
-                                let md = active_md_tab_id
-                                    .and_then(|tab_id| self.markdown_doc_for_tab(tab_id));
-                                coord::resolve_source_col(tab, md, row, x, tab_size)
-                            };
-                            if let Some(col) = col {
-                                // Ensure per-pane tracker exists
-                                while self.interaction.editor_mouse.len() <= pane {
-                                    self.interaction.editor_mouse.push(
-                                        super::super::mouse_tracker::EditorMouseTracker::new(),
-                                    );
+                        let pos = {
+                            let md = active_md_tab_id
+                                .and_then(|tab_id| self.markdown_doc_for_tab(tab_id));
+                            coord::resolve_source_pos(tab, md, x, y, tab_size)
+                        };
+                        if let Some((row, col)) = pos {
+                            // Ensure per-pane tracker exists
+                            while self.interaction.editor_mouse.len() <= pane {
+                                self.interaction
+                                    .editor_mouse
+                                    .push(super::super::mouse_tracker::EditorMouseTracker::new());
+                            }
+                            let granularity = self.interaction.editor_mouse[pane].click(
+                                event.column,
+                                event.row,
+                                Instant::now(),
+                                click_slop,
+                                triple_click_ms,
+                            );
+
+                            if event.modifiers.contains(KeyModifiers::CONTROL) {
+                                let byte = tab
+                                    .buffer
+                                    .rope()
+                                    .char_to_byte(tab.buffer.pos_to_char((row, col)));
+                                if tab.document_link_at(byte).is_some() {
+                                    let _ =
+                                        self.dispatch_kernel(KernelAction::OpenDocumentLinkAt {
+                                            pane,
+                                            row,
+                                            col,
+                                        });
+                                    self.interaction.editor_mouse[pane].stop_drag();
+                                    return EventResult::Consumed;
                                 }
-                                let granularity = self.interaction.editor_mouse[pane].click(
-                                    event.column,
-                                    event.row,
-                                    Instant::now(),
-                                    click_slop,
-                                    triple_click_ms,
-                                );
-
-                                if event.modifiers.contains(KeyModifiers::ALT) {
+                            }
+
+                            // Alt+单击加光标；按住继续拖动则改为列选区。
+                            if event.modifiers.contains(KeyModifiers::ALT) {
+                                let _ = self.dispatch_kernel(KernelAction::Editor(
+                                    EditorAction::AddCursorAt { pane, row, col },
+                                ));
+                                self.interaction.editor_mouse[pane].start_block((row, col));
+                                return EventResult::Consumed;
+                            }
+
+                            if granularity == Granularity::Char {
+                                let toggle = {
+                                    let md = active_md_tab_id
+                                        .and_then(|tab_id| self.markdown_doc_for_tab(tab_id));
+                                    md.and_then(|doc| markdown_task_toggle_edit(tab, doc, row, col))
+                                };
+                                if let Some((start_char, text)) = toggle {
                                     let _ = self.dispatch_kernel(KernelAction::Editor(
-                                        EditorAction::AddCursorAt { pane, row, col },
+                                        EditorAction::ReplaceRangeChars {
+                                            pane,
+                                            start_char,
+                                            end_char: start_char.saturating_add(1),
+                                            text: text.to_string(),
+                                        },
                                     ));
                                     self.interaction.editor_mouse[pane].stop_drag();
                                     return EventResult::Consumed;
                                 }
+                            }
 
-                                if granularity == Granularity::Char {
-                                    let toggle = {
-                                        let md = active_md_tab_id
-                                            .and_then(|tab_id| self.markdown_doc_for_tab(tab_id));
-                                        md.and_then(|doc| {
-                                            markdown_task_toggle_edit(tab, doc, row, col)
-                                        })
-                                    };
-                                    if let Some((start_char, text)) = toggle {
-                                        let _ = self.dispatch_kernel(KernelAction::Editor(
-                                            EditorAction::ReplaceRangeChars {
-                                                pane,
-                                                start_char,
-                                                end_char: start_char.saturating_add(1),
-                                                text: text.to_string(),
-                                            },
-                                        ));
-                                        self.interaction.editor_mouse[pane].stop_drag();
-                                        return EventResult::Consumed;
-                                    }
-                                }
+                            let _ = self.dispatch_kernel(KernelAction::Editor(
+                                EditorAction::PlaceCursor {
+                                    pane,
+                                    row,
+                                    col,
+                                    granularity,
+                                },
+                            ));
 
-                                let _ = self.dispatch_kernel(KernelAction::Editor(
-                                    EditorAction::PlaceCursor {
-                                        pane,
-                                        row,
-                                        col,
-                                        granularity,
-                                    },
-                                ));
+                            // 单击诊断下划线：在该处打开 code action 菜单。
+                            if granularity == Granularity::Char
+                                && event.modifiers.is_empty()
+                                && self.dispatch_kernel(KernelAction::DiagnosticClicked {
+                                    pane,
+                                    row,
+                                    col,
+                                })
+                            {
+                                self.interaction.editor_mouse[pane].stop_drag();
                             }
                         }
                     }
@@ -338,24 +407,34 @@ impl Workbench {
                         .pane(pane)
                         .and_then(|p| p.active_tab())
                     {
-                        let visible = tab.visible_lines_in_viewport(
-                            tab.viewport.line_offset,
-                            tab.viewport.height.max(1),
-                        );
-                        if let Some(&row) = visible.get(hit.y as usize) {
-                            let col = if hit.past_right {
-                                tab.buffer.line_grapheme_len(row)
-                            } else {
-                                let md = active_md_tab_id
-                                    .and_then(|tab_id| self.markdown_doc_for_tab(tab_id));
-                                match coord::resolve_source_col(tab, md, row, hit.x, tab_size) {
-                                    Some(c) => c,
-                                    None => return EventResult::Consumed,
-                                }
-                            };
-                            let _ = self.dispatch_kernel(KernelAction::Editor(
-                                EditorAction::ExtendSelection { pane, row, col },
-                            ));
+                        let pos = if hit.past_right {
+                            coord::resolve_row_end(tab, hit.y)
+                        } else {
+                            let md = active_md_tab_id
+                                .and_then(|tab_id| self.markdown_doc_for_tab(tab_id));
+                            coord::resolve_source_pos(tab, md, hit.x, hit.y, tab_size)
+                        };
+                        let block_anchor = self.interaction.editor_mouse[pane].block_anchor();
+                        match (pos, block_anchor) {
+                            // 还没拖离按下的位置时保留 Alt+单击加出的光标。
+                            (Some(pos), Some(anchor)) if pos == anchor => {}
+                            (Some((row, col)), Some((anchor_row, anchor_col))) => {
+                                let _ = self.dispatch_kernel(KernelAction::Editor(
+                                    EditorAction::SelectBlock {
+                                        pane,
+                                        anchor_row,
+                                        anchor_col,
+                                        row,
+                                        col,
+                                    },
+                                ));
+                            }
+                            (Some((row, col)), None) => {
+                                let _ = self.dispatch_kernel(KernelAction::Editor(
+                                    EditorAction::ExtendSelection { pane, row, col },
+                                ));
+                            }
+                            (None, _) => {}
                         }
                     }
                     return EventResult::Consumed;
@@ -423,7 +502,7 @@ impl Workbench {
                                 let Some(to_area) = self.frame_layout.editor.inner(to_pane) else {
                                     continue;
                                 };
-                                let config = &self.store.state().editor.config;
+                                let config = &self.store.state().editor_config_for_pane(to_pane);
                                 let to_layout =
                                     compute_editor_pane_layout(to_area, to_pane_state, config);
 
@@ -530,33 +609,26 @@ impl Workbench {
                                     .pane(pane)
                                     .and_then(|p| p.active_tab())
                                 {
-                                    let visible = tab.visible_lines_in_viewport(
-                                        tab.viewport.line_offset,
-                                        tab.viewport.height.max(1),
-                                    );
-                                    if let Some(&row) = visible.get(y as usize) {
-                                        let col = {
-                                            let md = md_tab_id.and_then(|tab_id| {
-                                                self.markdown_doc_for_tab(tab_id)
+                                    let pos = {
+                                        let md = md_tab_id
+                                            .and_then(|tab_id| self.markdown_doc_for_tab(tab_id));
+                                        coord::resolve_source_pos(tab, md, x, y, tab_size)
+                                    };
+                                    if let Some((row, col)) = pos {
+                                        // If right-click is inside existing non-empty selection, keep it
+                                        let inside_selection =
+                                            tab.buffer.selection().is_some_and(|sel| {
+                                                !sel.is_empty() && sel.contains((row, col))
                                             });
-                                            coord::resolve_source_col(tab, md, row, x, tab_size)
-                                        };
-                                        if let Some(col) = col {
-                                            // If right-click is inside existing non-empty selection, keep it
-                                            let inside_selection =
-                                                tab.buffer.selection().is_some_and(|sel| {
-                                                    !sel.is_empty() && sel.contains((row, col))
-                                                });
-                                            if !inside_selection {
-                                                let _ = self.dispatch_kernel(KernelAction::Editor(
-                                                    EditorAction::PlaceCursor {
-                                                        pane,
-                                                        row,
-                                                        col,
-                                                        granularity: Granularity::Word,
-                                                    },
-                                                ));
-                                            }
+                                        if !inside_selection {
+                                            let _ = self.dispatch_kernel(KernelAction::Editor(
+                                                EditorAction::PlaceCursor {
+                                                    pane,
+                                                    row,
+                                                    col,
+                                                    granularity: Granularity::Word,
+                                                },
+                                            ));
                                         }
                                     }
                                 }
@@ -587,14 +659,7 @@ impl Workbench {
                 let idle_target =
                     if let Some((x, y)) = hit_test_editor_mouse(&layout, event.column, event.row) {
                         pane_state.active_tab().and_then(|tab| {
-                            let visible_lines = tab.visible_lines_in_viewport(
-                                tab.viewport.line_offset,
-                                tab.viewport.height.max(1),
-                            );
-                            let row = visible_lines.get(y as usize).copied()?;
-
-                            let col =
-                                coord::screen_to_col(&tab.viewport, &tab.buffer, tab_size, row, x)?;
+                            let (row, col) = coord::resolve_source_pos(tab, None, x, y, tab_size)?;
 
                             Some(super::super::IdleHoverTarget {
                                 pane,
@@ -658,10 +723,8 @@ impl Workbench {
                     return EventResult::Consumed;
                 }
 
-                let _ = self.scroll_editor_horizontally(
-                    pane,
-                    -Self::mouse_horizontal_step(scroll_step, tab_size),
-                );
+                let delta = -(scroll_step.max(1).min(i32::MAX as usize) as i32);
+                let _ = self.dispatch_kernel(KernelAction::EditorScrollHorizontal { pane, delta });
                 EventResult::Consumed
             }
             MouseEventKind::ScrollRight => {
@@ -669,10 +732,8 @@ impl Workbench {
                     return EventResult::Consumed;
                 }
 
-                let _ = self.scroll_editor_horizontally(
-                    pane,
-                    Self::mouse_horizontal_step(scroll_step, tab_size),
-                );
+                let delta = scroll_step.max(1).min(i32::MAX as usize) as i32;
+                let _ = self.dispatch_kernel(KernelAction::EditorScrollHorizontal { pane, delta });
                 EventResult::Consumed
             }
             _ => EventResult::Ignored,
@@ -765,6 +826,27 @@ impl Workbench {
             delta_columns,
         }))
     }
+
+    /// Ctrl+. 唤起的编辑区菜单锚在光标下一行；光标不在可见区时退回编辑区左上角。
+    pub(in super::super) fn open_editor_context_menu_at_cursor(&mut self, pane: usize) {
+        let Some(area) = self.frame_layout.editor.inner(pane) else {
+            return;
+        };
+        let state = self.store.state();
+        let anchor = state.editor.pane(pane).and_then(|pane_state| {
+            let config = &state.editor.config;
+            let layout = compute_editor_pane_layout(area, pane_state, config);
+            cursor_position_editor(&layout, pane_state, config)
+        });
+        let (x, y) = anchor
+            .map(|(x, y)| (x, y.saturating_add(1)))
+            .unwrap_or((area.x, area.y));
+        let _ = self.dispatch_kernel(KernelAction::ContextMenuOpen {
+            request: crate::kernel::state::ContextMenuRequest::EditorArea { pane },
+            x,
+            y,
+        });
+    }
 }
 
 fn markdown_task_toggle_edit(
@@ -8,7 +8,6 @@ use crate::tui::view::EventResult;
 use crate::ui::core::input::{DragPayload, UiEvent};
 use crate::ui::core::runtime::UiRuntimeOutput;
 use crate::ui::core::tree::NodeKind;
-use std::path::Path;
 use std::time::Instant;
 
 impl Workbench {
@@ -42,6 +41,33 @@ impl Workbench {
                     .and_then(|id| self.ui_tree.node(id))
                     .is_some_and(|n| matches!(n.kind, NodeKind::ExplorerRow { .. }));
 
+                for ev in &ui_out.events {
+                    let UiEvent::DragStart { id, .. } = ev else {
+                        continue;
+                    };
+                    if let Some(NodeKind::ExplorerRow { node_id }) =
+                        self.ui_tree.node(*id).map(|n| n.kind)
+                    {
+                        let _ = self.dispatch_kernel(KernelAction::ExplorerDragStart {
+                            node_id: NodeId::from_raw(node_id),
+                        });
+                    }
+                }
+                if self.store.state().explorer.drag.is_some() {
+                    let target = self
+                        .ui_runtime
+                        .drag_over()
+                        .and_then(|id| self.ui_tree.node(id))
+                        .and_then(|n| match n.kind {
+                            NodeKind::ExplorerRow { node_id }
+                            | NodeKind::ExplorerFolderDrop { node_id } => {
+                                Some(NodeId::from_raw(node_id))
+                            }
+                            _ => None,
+                        });
+                    let _ = self.dispatch_kernel(KernelAction::ExplorerDragHover { target });
+                }
+
                 if ui_out.needs_redraw || captured_is_explorer_row {
                     return EventResult::Consumed;
                 }
@@ -110,78 +136,21 @@ impl Workbench {
                                     let _ = self.dispatch_kernel(KernelAction::OpenPath(path));
                                     handled = true;
                                 }
-                                DropIntent::ExplorerToExplorerFolder { to_dir_id } => {
-                                    let to_dir_id = NodeId::from_raw(to_dir_id);
-                                    let Some((from_path, from_is_dir)) =
-                                        self.store.state().explorer.path_and_kind_for(node_id)
-                                    else {
-                                        continue;
-                                    };
-
-                                    let Some((to_dir_path, to_is_dir)) =
-                                        self.store.state().explorer.path_and_kind_for(to_dir_id)
-                                    else {
-                                        continue;
-                                    };
-                                    if !to_is_dir {
-                                        continue;
-                                    }
-
-                                    let Some(to) = compute_explorer_move_target(
-                                        from_path.as_path(),
-                                        from_is_dir,
-                                        to_dir_path.as_path(),
-                                    ) else {
-                                        continue;
-                                    };
-
-                                    let _ = self.dispatch_kernel(KernelAction::ExplorerMovePath {
-                                        from: from_path,
-                                        to,
-                                    });
-                                    handled = true;
-                                }
-                                DropIntent::ExplorerToExplorerRow { to_row_id } => {
-                                    let to_row_id = NodeId::from_raw(to_row_id);
-                                    let state = self.store.state();
-                                    let explorer = &state.explorer;
-
-                                    let Some((from_path, from_is_dir)) =
-                                        explorer.path_and_kind_for(node_id)
-                                    else {
-                                        continue;
-                                    };
-
-                                    let Some((to_path, to_is_dir)) =
-                                        explorer.path_and_kind_for(to_row_id)
-                                    else {
-                                        continue;
-                                    };
-
-                                    let to_dir = if to_is_dir {
-                                        to_path.as_path()
-                                    } else {
-                                        to_path.parent().unwrap_or(state.workspace_root.as_path())
-                                    };
-
-                                    let Some(to) = compute_explorer_move_target(
-                                        from_path.as_path(),
-                                        from_is_dir,
-                                        to_dir,
-                                    ) else {
-                                        continue;
-                                    };
-
-                                    let _ = self.dispatch_kernel(KernelAction::ExplorerMovePath {
-                                        from: from_path,
-                                        to,
+                                DropIntent::ExplorerToExplorerFolder { to_dir_id: target }
+                                | DropIntent::ExplorerToExplorerRow { to_row_id: target } => {
+                                    let _ = self.dispatch_kernel(KernelAction::ExplorerDrop {
+                                        target: NodeId::from_raw(target),
                                     });
                                     handled = true;
                                 }
                                 _ => {}
                             }
                         }
-                        UiEvent::DragEnd { .. } => handled = true,
+                        UiEvent::DragEnd { .. } => {
+                            // 放在侧栏外或不可放的位置：收起拖动标记。
+                            let _ = self.dispatch_kernel(KernelAction::ExplorerDragCancel);
+                            handled = true;
+                        }
                         _ => {}
                     }
                 }
@@ -240,21 +209,3 @@ impl Workbench {
         }
     }
 }
-
-fn compute_explorer_move_target(
-    from: &Path,
-    from_is_dir: bool,
-    to_dir: &Path,
-) -> Option<std::path::PathBuf> {
-    if from_is_dir && (to_dir == from || to_dir.starts_with(from)) {
-        return None;
-    }
-
-    let name = from.file_name()?;
-    let to = to_dir.join(name);
-    (to != from).then_some(to)
-}
-
-#[cfg(test)]
-#[path = "../../../../tests/unit/app/workbench/explorer_dnd.rs"]
-mod tests;
@@ -1,6 +1,7 @@
 use super::super::Workbench;
 use crate::core::event::{InputEvent, Key, KeyCode, KeyEvent, KeyModifiers, MouseEventKind};
 use crate::core::Command;
+use crate::kernel::editor::EditorMode;
 use crate::kernel::services::adapters::perf;
 use crate::kernel::services::adapters::{KeybindingContext, KeybindingService};
 use crate::kernel::{Action as KernelAction, EditorAction, FocusTarget, OverlayKind};
@@ -29,6 +30,7 @@ impl Workbench {
         self.last_input_at = Instant::now();
         if !preserve_hover {
             self.ui.hover_popup.last_request = None;
+            self.ui.hover_popup.last_diagnostic_target = None;
             self.ui.hover_popup.last_anchor = None;
         }
         self.lsp_sync.debounce.inlay_hints = None;
@@ -109,11 +111,11 @@ impl Workbench {
 
         if self.store.state().ui.confirm_dialog.visible {
             match key_event.code {
-                KeyCode::Enter => {
+                KeyCode::Enter | KeyCode::Char('y' | 'Y') => {
                     let _ = self.dispatch_kernel(KernelAction::ConfirmDialogAccept);
                     return EventResult::Consumed;
                 }
-                KeyCode::Esc => {
+                KeyCode::Esc | KeyCode::Char('n' | 'N') => {
                     let _ = self.dispatch_kernel(KernelAction::ConfirmDialogCancel);
                     return EventResult::Consumed;
                 }
@@ -140,6 +142,24 @@ impl Workbench {
                     self.reset_completion_doc_scroll();
                     return EventResult::Consumed;
                 }
+                // Shift+PageUp / PageDown 整页翻补全列表；不带 Shift 的翻文档。
+                (KeyCode::PageUp | KeyCode::PageDown, KeyModifiers::SHIFT) => {
+                    let page = self
+                        .store
+                        .state()
+                        .editor
+                        .config
+                        .completion_popup_max_lines
+                        .max(1) as isize;
+                    let delta = if key_event.code == KeyCode::PageUp {
+                        -page
+                    } else {
+                        page
+                    };
+                    let _ = self.dispatch_kernel(KernelAction::CompletionMoveSelection { delta });
+                    self.reset_completion_doc_scroll();
+                    return EventResult::Consumed;
+                }
                 (KeyCode::PageUp, _) => {
                     let step = self.completion_doc_view_height().max(1) as isize;
                     let _ = self.scroll_completion_doc_by(-step);
@@ -242,6 +262,13 @@ impl Workbench {
                 }
                 _ => EventResult::Ignored,
             },
+            // Normal / Visual 模式下未绑定的字符不插入文本。
+            KeybindingContext::Vim => match (key_event.code, key_event.modifiers) {
+                (KeyCode::Char(_), mods) if mods.is_empty() || mods == KeyModifiers::SHIFT => {
+                    EventResult::Consumed
+                }
+                _ => EventResult::Ignored,
+            },
             KeybindingContext::CommandLine => match (key_event.code, key_event.modifiers) {
                 (KeyCode::Char(ch), mods) if mods.is_empty() || mods == KeyModifiers::SHIFT => {
                     let _ = self.dispatch_kernel(KernelAction::CommandLineAppend(ch));
@@ -256,15 +283,21 @@ impl Workbench {
                 match (key_event.code, key_event.modifiers) {
                     (KeyCode::Char(ch), mods) if mods.is_empty() || mods == KeyModifiers::SHIFT => {
                         let _ = self.dispatch_kernel(KernelAction::SearchAppend(ch));
-                        // telescope 风格：边输入边搜。
-                        let _ = self
-                            .dispatch_kernel(KernelAction::RunCommand(Command::GlobalSearchStart));
+                        // telescope 风格：边输入边搜；编辑替换文本时不重新搜索。
+                        if !self.store.state().search.replace_focused {
+                            let _ = self.dispatch_kernel(KernelAction::RunCommand(
+                                Command::GlobalSearchStart,
+                            ));
+                        }
                         EventResult::Consumed
                     }
                     (KeyCode::Backspace, _) => {
                         let _ = self.dispatch_kernel(KernelAction::SearchBackspace);
-                        let _ = self
-                            .dispatch_kernel(KernelAction::RunCommand(Command::GlobalSearchStart));
+                        if !self.store.state().search.replace_focused {
+                            let _ = self.dispatch_kernel(KernelAction::RunCommand(
+                                Command::GlobalSearchStart,
+                            ));
+                        }
                         EventResult::Consumed
                     }
                     _ => EventResult::Ignored,
@@ -283,14 +316,14 @@ impl Workbench {
             FocusTarget::CommandLine => KeybindingContext::CommandLine,
             FocusTarget::Editor => {
                 let pane = ui.editor_layout.active_pane;
-                let visible = self
-                    .store
-                    .state()
-                    .editor
-                    .pane(pane)
-                    .is_some_and(|p| p.search_bar.visible);
-                if visible {
+                let editor = &self.store.state().editor;
+                let Some(pane) = editor.pane(pane) else {
+                    return KeybindingContext::Editor;
+                };
+                if pane.search_bar.visible {
                     KeybindingContext::EditorSearchBar
+                } else if editor.config.vim_mode && pane.mode != EditorMode::Insert {
+                    KeybindingContext::Vim
                 } else {
                     KeybindingContext::Editor
                 }
@@ -13,8 +13,12 @@ mod overlay;
 pub(super) enum LspDebouncePipeline {
     InlayHints,
     FoldingRange,
+    DocumentLink,
 }
 
+/// 文档链接不随输入刷新（编辑即作废），只在打开 / 保存后延迟请求一次。
+pub(super) const DOCUMENT_LINK_DEBOUNCE: Duration = Duration::from_millis(500);
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(super) enum LspDebounceTrigger {
     Immediate,
@@ -38,6 +42,9 @@ pub(super) fn classify_lsp_edit_trigger(
         Command::InsertNewline
         | Command::InsertTab
         | Command::DeleteLine
+        | Command::ToggleLineComment
+        | Command::SortLines { .. }
+        | Command::DuplicateLine
         | Command::DeleteToLineEnd
         | Command::DeleteSelection
         | Command::Undo
@@ -58,10 +65,12 @@ pub(super) fn lsp_debounce_duration(
         LspDebounceTrigger::Identifier => match pipeline {
             LspDebouncePipeline::InlayHints => timing.identifier_debounce_ms.inlay_hints,
             LspDebouncePipeline::FoldingRange => timing.identifier_debounce_ms.folding_range,
+            LspDebouncePipeline::DocumentLink => DOCUMENT_LINK_DEBOUNCE.as_millis() as u64,
         },
         LspDebounceTrigger::Delete => match pipeline {
             LspDebouncePipeline::InlayHints => timing.delete_debounce_ms.inlay_hints,
             LspDebouncePipeline::FoldingRange => timing.delete_debounce_ms.folding_range,
+            LspDebouncePipeline::DocumentLink => DOCUMENT_LINK_DEBOUNCE.as_millis() as u64,
         },
     };
     Duration::from_millis(millis)
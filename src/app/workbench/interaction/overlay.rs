@@ -2,8 +2,10 @@ use super::super::util;
 use super::super::Workbench;
 use crate::core::event::{MouseButton, MouseEvent, MouseEventKind};
 use crate::core::Command;
-use crate::kernel::{Action as KernelAction, OverlayKind, SearchResultItem};
+use crate::kernel::state::ContextMenuRequest;
+use crate::kernel::{Action as KernelAction, GitPanelRow, OverlayKind, SearchResultItem};
 use crate::tui::view::EventResult;
+use crate::ui::core::geom::Rect;
 
 impl Workbench {
     /// 居中浮层鼠标：点击框外即关闭；点击结果行选中并打开；滚轮滚动列表。
@@ -23,23 +25,42 @@ impl Workbench {
                     return EventResult::Consumed;
                 }
 
-                // 内层：去掉边框；标题占一行；search 额外有 query + summary 两行。
-                let inner_top = popup.y.saturating_add(1);
-                let list_top = match kind {
-                    OverlayKind::Search => inner_top.saturating_add(3),
-                    _ => inner_top.saturating_add(1),
-                };
-                if event.row < list_top {
+                if kind == OverlayKind::Search
+                    && self
+                        .frame_layout
+                        .search_filters_button_area
+                        .is_some_and(|area| util::rect_contains(area, event.column, event.row))
+                {
+                    let _ = self.dispatch_kernel(KernelAction::RunCommand(
+                        Command::GlobalSearchToggleFilters,
+                    ));
                     return EventResult::Consumed;
                 }
-                let visible_row = (event.row - list_top) as usize;
+
+                let list_top = self.overlay_list_top(popup, kind);
+                let Some(visible_row) = overlay_list_row(list_top, event.row) else {
+                    return EventResult::Consumed;
+                };
 
                 match kind {
                     OverlayKind::Search => self.click_search_row(visible_row),
+                    OverlayKind::Git => self.click_git_row(visible_row),
+                    OverlayKind::CallHierarchy => {
+                        self.click_call_hierarchy_row(popup, visible_row, event.column)
+                    }
                     _ => self.click_flat_row(visible_row, self.flat_scroll_offset(kind), kind),
                 }
                 EventResult::Consumed
             }
+            MouseEventKind::Down(MouseButton::Right) => {
+                if kind == OverlayKind::Git && util::rect_contains(popup, event.column, event.row) {
+                    let list_top = self.overlay_list_top(popup, kind);
+                    if let Some(visible_row) = overlay_list_row(list_top, event.row) {
+                        self.open_git_stash_menu(visible_row, event.column, event.row);
+                    }
+                }
+                EventResult::Consumed
+            }
             MouseEventKind::ScrollUp => {
                 let _ =
                     self.dispatch_kernel(KernelAction::RunCommand(Command::SearchResultsScrollUp));
@@ -54,13 +75,25 @@ impl Workbench {
         }
     }
 
+    /// 列表首行的屏幕行：边框与标题之下；搜索浮层还要跳过输入行与摘要行。
+    fn overlay_list_top(&self, popup: Rect, kind: OverlayKind) -> u16 {
+        let inner_top = popup.y.saturating_add(1);
+        let header_rows = match kind {
+            OverlayKind::Search => self.store.state().search.input_rows() + 1,
+            _ => 0,
+        };
+        inner_top.saturating_add(1 + header_rows.min(u16::MAX as usize) as u16)
+    }
+
     fn flat_scroll_offset(&self, kind: OverlayKind) -> usize {
         match kind {
             OverlayKind::Problems => self.store.state().problems.scroll_offset(),
             OverlayKind::CodeActions => self.store.state().code_actions.scroll_offset(),
             OverlayKind::Locations => self.store.state().locations.scroll_offset(),
             OverlayKind::Symbols => self.store.state().symbols.scroll_offset(),
-            OverlayKind::Search => 0,
+            OverlayKind::Git => self.store.state().git.scroll_offset(),
+            OverlayKind::CallHierarchy => self.store.state().call_hierarchy.scroll_offset(),
+            OverlayKind::Search | OverlayKind::GitDiff => 0,
         }
     }
 
@@ -71,7 +104,10 @@ impl Workbench {
             OverlayKind::CodeActions => self.store.state().code_actions.items().len(),
             OverlayKind::Locations => self.store.state().locations.items().len(),
             OverlayKind::Symbols => self.store.state().symbols.items().len(),
-            OverlayKind::Search => 0,
+            OverlayKind::Search
+            | OverlayKind::Git
+            | OverlayKind::GitDiff
+            | OverlayKind::CallHierarchy => 0,
         };
         if row >= items_len {
             return;
@@ -82,7 +118,10 @@ impl Workbench {
             OverlayKind::CodeActions => KernelAction::CodeActionsClickRow { row },
             OverlayKind::Locations => KernelAction::LocationsClickRow { row },
             OverlayKind::Symbols => KernelAction::SymbolsClickRow { row },
-            OverlayKind::Search => return,
+            OverlayKind::Search
+            | OverlayKind::Git
+            | OverlayKind::GitDiff
+            | OverlayKind::CallHierarchy => return,
         };
         let _ = self.dispatch_kernel(click);
         let _ = self.dispatch_kernel(KernelAction::RunCommand(Command::SearchResultsOpenSelected));
@@ -110,4 +149,60 @@ impl Workbench {
             None => {}
         }
     }
+
+    /// 点分区标题切换折叠；点 stash 行只选中，apply / drop 走右键菜单或 Enter。
+    fn click_git_row(&mut self, visible_row: usize) {
+        let row = visible_row + self.store.state().git.scroll_offset();
+        let Some(item) = self.store.state().git.rows().get(row).copied() else {
+            return;
+        };
+
+        let _ = self.dispatch_kernel(KernelAction::GitClickRow { row });
+        if item == GitPanelRow::StashesHeader {
+            let _ =
+                self.dispatch_kernel(KernelAction::RunCommand(Command::SearchResultsToggleExpand));
+        }
+    }
+
+    /// 点展开图标只展开 / 折叠该节点；点行内其它位置选中并跳到调用方源码。
+    fn click_call_hierarchy_row(&mut self, popup: Rect, visible_row: usize, column: u16) {
+        let tree = &self.store.state().call_hierarchy;
+        let row = visible_row + tree.scroll_offset();
+        let Some(item_index) = tree.item_index_at_row(row) else {
+            return;
+        };
+        let depth = tree.node(item_index).map_or(0, |node| node.depth);
+
+        // 行布局：边框 + 选中标记 + 空格 + 每层两格缩进 + 图标。
+        let icon_x = popup
+            .x
+            .saturating_add(3)
+            .saturating_add((depth.min(32) * 2) as u16);
+        let _ = self.dispatch_kernel(KernelAction::CallHierarchyClickRow { row });
+        if column == icon_x {
+            let _ = self.dispatch_kernel(KernelAction::CallHierarchyExpand { item_index });
+        } else {
+            let _ =
+                self.dispatch_kernel(KernelAction::RunCommand(Command::SearchResultsOpenSelected));
+        }
+    }
+
+    fn open_git_stash_menu(&mut self, visible_row: usize, x: u16, y: u16) {
+        let row = visible_row + self.store.state().git.scroll_offset();
+        let Some(index) = self.store.state().git.stash_at_row(row).map(|s| s.index) else {
+            return;
+        };
+
+        let _ = self.dispatch_kernel(KernelAction::GitClickRow { row });
+        let _ = self.dispatch_kernel(KernelAction::ContextMenuOpen {
+            request: ContextMenuRequest::GitStash { index },
+            x,
+            y,
+        });
+    }
+}
+
+/// 浮层内屏幕行 → 列表可见行；`list_top` 见 `overlay_list_top`。
+fn overlay_list_row(list_top: u16, row: u16) -> Option<usize> {
+    (row >= list_top).then(|| (row - list_top) as usize)
 }
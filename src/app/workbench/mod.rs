@@ -6,16 +6,22 @@ use crate::core::Command;
 use crate::kernel::editor::TabId;
 use crate::kernel::services::adapters::lsp::LspServerCommandOverride;
 use crate::kernel::services::adapters::perf;
-use crate::kernel::services::adapters::{AppMessage, AsyncRuntime};
+use crate::kernel::services::adapters::settings::{SessionOpenFile, WorkspaceSession};
+use crate::kernel::services::adapters::{AppMessage, AsyncRuntime, FileError};
 use crate::kernel::services::adapters::{
     ClipboardService, ConfigService, FileWatcherService, GlobalSearchService, GlobalSearchTask,
     KeybindingService, LspService, SearchService, SearchTask,
 };
 use crate::kernel::services::ports::{
-    EditorConfig, GlobalSearchMessage, LspServerKind, SearchMessage,
+    EditorConfig, GlobalSearchMessage, LspServerKind, LspSettings, SearchMessage, StatusBarConfig,
+    StatusBarSegment,
+};
+use crate::kernel::services::{KernelServiceHost, MetricsMiddleware};
+use crate::kernel::state::{ExplorerOpOrigin, PendingAction};
+use crate::kernel::{
+    Action as KernelAction, AppStateSnapshot, EditorAction, Effect as KernelEffect, FocusTarget,
+    GitBlameLine, Store,
 };
-use crate::kernel::services::KernelServiceHost;
-use crate::kernel::{Action as KernelAction, EditorAction, FocusTarget, Store};
 use crate::models::build_file_tree;
 use crate::tui::view::{EventResult, View};
 use crate::ui::backend::Backend;
@@ -24,6 +30,7 @@ use crate::ui::core::geom::Rect;
 use crate::views::doc::RenderCache as DocRenderCache;
 use crate::views::ExplorerView;
 use rustc_hash::{FxHashMap, FxHashSet};
+use std::collections::VecDeque;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::Receiver;
 use std::time::{Duration, Instant, SystemTime};
@@ -56,9 +63,20 @@ const EDITOR_SEARCH_CHANNEL_CAP: usize = 64;
 const GLOBAL_SEARCH_CHANNEL_CAP: usize = 64;
 const SETTINGS_CHECK_INTERVAL: Duration = Duration::from_millis(500);
 const HOVER_IDLE_DELAY: Duration = Duration::from_millis(500);
+const GIT_BLAME_IDLE_DELAY: Duration = Duration::from_secs(1);
+const SESSION_SAVE_INTERVAL: Duration = Duration::from_secs(5);
 const DEFINITION_JUMP_HIGHLIGHT_DURATION: Duration = Duration::from_millis(1100);
 const DEFINITION_JUMP_PENDING_TIMEOUT: Duration = Duration::from_secs(10);
 
+/// 上一次从剪贴板历史粘贴进来的文本位置；编辑版本与光标都没变时，循环粘贴替换它。
+#[derive(Debug, Clone, Copy)]
+struct ClipboardPaste {
+    pane: usize,
+    start_char: usize,
+    end_char: usize,
+    edit_version: u64,
+}
+
 #[derive(Debug, Clone)]
 struct PendingRestart {
     path: PathBuf,
@@ -129,9 +147,38 @@ fn lsp_command_override() -> Option<(String, Vec<String>)> {
     Some((command, args))
 }
 
+/// 各 pane 里有路径的标签页及其光标字节偏移，写进会话文件。
+fn session_open_files(state: &crate::kernel::AppState) -> Vec<SessionOpenFile> {
+    state
+        .editor
+        .panes
+        .iter()
+        .enumerate()
+        .flat_map(|(pane, pane_state)| {
+            pane_state
+                .tabs
+                .iter()
+                .enumerate()
+                .filter_map(move |(index, tab)| {
+                    let path = tab.path.clone()?;
+                    let rope = tab.buffer.rope();
+                    let char_offset = tab.buffer.pos_to_char(tab.buffer.cursor());
+                    Some(SessionOpenFile {
+                        pane,
+                        path,
+                        cursor_byte: rope.char_to_byte(char_offset.min(rope.len_chars())),
+                        active: index == pane_state.active,
+                    })
+                })
+        })
+        .collect()
+}
+
 #[derive(Debug, Default)]
 struct HoverPopupRenderState {
     last_request: Option<(PathBuf, u32, u32, u64)>,
+    /// 上次查过诊断的 `(pane, row, col)`，同一位置只查一次。
+    last_diagnostic_target: Option<(usize, usize, usize)>,
     last_anchor: Option<(u16, u16)>,
     target: Option<IdleHoverTarget>,
     scroll: usize,
@@ -171,6 +218,10 @@ struct ViewportCache {
     applied_code_actions_panel_height: Option<u16>,
     symbols_panel_height: Option<u16>,
     applied_symbols_panel_height: Option<u16>,
+    git_panel_height: Option<u16>,
+    applied_git_panel_height: Option<u16>,
+    call_hierarchy_panel_height: Option<u16>,
+    applied_call_hierarchy_panel_height: Option<u16>,
 }
 
 pub struct Workbench {
@@ -179,6 +230,7 @@ pub struct Workbench {
     editor_search_tasks: Vec<Option<SearchTask>>,
     editor_search_rx: Vec<Option<Receiver<SearchMessage>>>,
     clipboard_unavailable_warned: bool,
+    clipboard_paste: Option<ClipboardPaste>,
     settings_path: Option<PathBuf>,
     last_settings_check: Instant,
     last_settings_modified: Option<SystemTime>,
@@ -198,8 +250,22 @@ pub struct Workbench {
     pending_definition_highlight: Option<PendingDefinitionHighlight>,
     definition_jump_highlight: Option<DefinitionJumpHighlight>,
     pending_restart: Option<PendingRestart>,
+    /// 已交给运行时、还没回结果的资源管理器操作的逆操作；成功消息到了才进撤销栈。
+    pending_explorer_ops: Vec<(KernelEffect, ExplorerOpOrigin)>,
+    /// `Effect::SpawnTerminal` 请求的 shell 目录，由 `handle_input` 交给主循环挂起界面去跑。
+    pending_shell: Option<Option<PathBuf>>,
     pending_completion_rank_save_deadline: Option<Instant>,
+    /// 最近一次行内 blame 请求 (path, line, edit_version)，避免同一位置反复起进程（含失败的）。
+    last_git_blame_request: Option<(PathBuf, usize, u64)>,
+    /// 上次落盘的会话内容；退出时只有变化了才重写会话文件。
+    saved_session: WorkspaceSession,
+    /// 运行中定期写会话文件，进程被杀掉时最多丢掉 `SESSION_SAVE_INTERVAL` 内的光标位置。
+    last_session_save: Instant,
+    /// 上次落盘的最近文件列表，和会话文件一起定期检查、变了才重写。
+    saved_recent_files: VecDeque<PathBuf>,
     file_watcher: Option<FileWatcherService>,
+    /// `ui.status_bar` 设置里的状态栏段布局。
+    status_bar: StatusBarConfig,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -232,6 +298,9 @@ impl Workbench {
             None;
         let mut lsp_server_overrides: FxHashMap<LspServerKind, LspServerCommandOverride> =
             FxHashMap::default();
+        let mut lsp_settings = LspSettings::default();
+        let mut sidebar_width = None;
+        let mut status_bar = StatusBarConfig::default();
 
         let settings_path = if !settings_enabled() {
             None
@@ -251,6 +320,9 @@ impl Workbench {
                 editor_config = parsed.editor_config;
                 lsp_settings_override = parsed.lsp_settings_override;
                 lsp_server_overrides = parsed.lsp_server_overrides;
+                lsp_settings = parsed.lsp;
+                sidebar_width = parsed.sidebar_width;
+                status_bar = parsed.status_bar;
             }
         }
 
@@ -260,11 +332,18 @@ impl Workbench {
         if let Some(wakeup) = wakeup {
             kernel_services.set_wakeup(wakeup);
         }
-        let _ = kernel_services.register(ClipboardService::new());
+        let mut clipboard = ClipboardService::new();
+        clipboard.set_backend(editor_config.clipboard_backend);
+        let _ = kernel_services.register(clipboard);
         let _ = kernel_services.register(SearchService::new(runtime.tokio_handle().clone()));
         let _ = kernel_services.register(GlobalSearchService::new(runtime.tokio_handle().clone()));
         let _ = kernel_services.register(ConfigService::with_editor_config(editor_config.clone()));
         let _ = kernel_services.register(keybindings);
+        kernel_services.register_middleware(Box::new(MetricsMiddleware::new()));
+        #[cfg(feature = "debug")]
+        kernel_services.register_middleware(Box::new(
+            crate::kernel::services::LoggingMiddleware::default(),
+        ));
         if lsp_enabled() {
             let ctx = kernel_services.context();
             let mut service = LspService::new(absolute_root.clone(), ctx);
@@ -281,11 +360,17 @@ impl Workbench {
 
         let completion_ranker =
             crate::kernel::services::adapters::settings::load_completion_ranker();
+        let saved_session =
+            crate::kernel::services::adapters::settings::load_workspace_session(&absolute_root);
+        let recent_files = crate::kernel::services::adapters::settings::load_recent_files();
         let watcher_root = absolute_root.clone();
-        let store = Store::new_with_ranker(
-            crate::kernel::AppState::new(absolute_root, file_tree, editor_config),
-            completion_ranker,
-        );
+        let mut state = crate::kernel::AppState::new(absolute_root, file_tree, editor_config);
+        state.lsp.settings = lsp_settings;
+        state.recently_closed_tabs = saved_session.recently_closed_tabs.clone();
+        state.restore_file_positions(&saved_session.file_positions);
+        state.recent_files = recent_files.clone();
+        state.ui.sidebar_width = sidebar_width;
+        let store = Store::new_with_ranker(state, completion_ranker);
         let panes = store.state().ui.editor_layout.panes.max(1);
         let lsp_open_paths_version = store.state().editor.open_paths_version;
         let file_watcher_open_paths_version = store.state().editor.open_paths_version;
@@ -296,6 +381,7 @@ impl Workbench {
             editor_search_tasks: std::iter::repeat_with(|| None).take(panes).collect(),
             editor_search_rx: std::iter::repeat_with(|| None).take(panes).collect(),
             clipboard_unavailable_warned: false,
+            clipboard_paste: None,
             settings_path,
             last_settings_check: Instant::now(),
             last_settings_modified,
@@ -331,7 +417,13 @@ impl Workbench {
             pending_definition_highlight: None,
             definition_jump_highlight: None,
             pending_restart: None,
+            pending_explorer_ops: Vec::new(),
+            pending_shell: None,
             pending_completion_rank_save_deadline: None,
+            last_git_blame_request: None,
+            saved_session,
+            last_session_save: Instant::now(),
+            saved_recent_files: recent_files,
             file_watcher: match FileWatcherService::new(watcher_root.as_path()) {
                 Ok(w) => Some(w),
                 Err(e) => {
@@ -339,15 +431,30 @@ impl Workbench {
                     None
                 }
             },
+            status_bar: StatusBarConfig::default(),
         };
 
+        workbench.set_status_bar_config(status_bar);
         workbench.maybe_warn_clipboard_unavailable();
         Ok(workbench)
     }
 
+    /// 换上新的状态栏布局；用到 Git 分支段时先列一次分支，好知道当前分支。
+    fn set_status_bar_config(&mut self, status_bar: StatusBarConfig) {
+        if status_bar.segments().contains(&StatusBarSegment::GitBranch)
+            && self.store.state().git.branches.is_empty()
+        {
+            self.runtime
+                .git_branch_list(self.store.state().workspace_root.clone());
+        }
+        self.status_bar = status_bar;
+    }
+
     pub fn take_pending_restart(&mut self) -> Option<(PathBuf, bool)> {
         if self.pending_restart.is_some() {
             self.flush_completion_rank_save();
+            self.flush_session_save();
+            self.flush_edit_history_backups();
         }
         self.pending_restart.take().map(|req| (req.path, req.hard))
     }
@@ -366,6 +473,65 @@ impl Workbench {
         }
     }
 
+    /// 退出 / 重启前同步写出仍打开着的干净标签页的撤销历史（此时异步 runtime 即将销毁）。
+    fn flush_edit_history_backups(&self) {
+        if !settings_enabled() || crate::kernel::services::adapters::ensure_backup_dir().is_err() {
+            return;
+        }
+        let backups = self
+            .store
+            .state()
+            .editor
+            .panes
+            .iter()
+            .flat_map(|pane| pane.tabs.iter())
+            .filter_map(|tab| tab.edit_history_backup());
+        for (path, backup) in backups {
+            let Some(ops_path) = crate::kernel::services::adapters::get_ops_file_path(&path) else {
+                continue;
+            };
+            if let Err(e) = backup.write_to(&ops_path) {
+                tracing::warn!(path = %path.display(), error = %e, "edit history backup failed");
+            }
+        }
+    }
+
+    fn flush_session_save(&mut self) {
+        self.last_session_save = Instant::now();
+        self.store.remember_open_file_positions();
+        let state = self.store.state();
+        let session = WorkspaceSession {
+            recently_closed_tabs: state.recently_closed_tabs.clone(),
+            file_positions: state.file_positions.clone(),
+            open_files: if state.editor.config.restore_session {
+                session_open_files(state)
+            } else {
+                Vec::new()
+            },
+        };
+        self.flush_recent_files_save();
+        if session == self.saved_session {
+            return;
+        }
+
+        if crate::kernel::services::adapters::settings::save_workspace_session(
+            &self.store.state().workspace_root,
+            &session,
+        ) {
+            self.saved_session = session;
+        }
+    }
+
+    fn flush_recent_files_save(&mut self) {
+        let recent_files = &self.store.state().recent_files;
+        if *recent_files == self.saved_recent_files {
+            return;
+        }
+        if crate::kernel::services::adapters::settings::save_recent_files(recent_files) {
+            self.saved_recent_files = recent_files.clone();
+        }
+    }
+
     fn hover_popup_view_height(&self) -> usize {
         self.ui
             .hover_popup
@@ -457,11 +623,294 @@ impl Workbench {
 
         self.settings_path = Some(path.clone());
         self.last_settings_modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
-        self.runtime.load_file(path);
+        self.runtime.load_file(path, None);
+    }
+
+    /// 按 `EditorConfig::max_file_size_bytes` 读入文件；超限时由 `handle_message` 询问是否仍要打开。
+    /// 达到 `large_file_threshold_mb` 的文件也先询问，确认后分块读入。
+    pub fn load_file(&self, path: PathBuf) {
+        let limit = (self.store.state().editor.config.max_file_size_bytes as u64)
+            .min(self.large_file_threshold_bytes().saturating_sub(1));
+        self.runtime.load_file(path, Some(limit));
+    }
+
+    fn large_file_threshold_bytes(&self) -> u64 {
+        (self.store.state().editor.config.large_file_threshold_mb as u64)
+            .saturating_mul(1024 * 1024)
+    }
+
+    pub fn snapshot_state(&self) -> AppStateSnapshot {
+        AppStateSnapshot::capture(self.store.state())
+    }
+
+    /// 按快照恢复布局、焦点、搜索词，并从磁盘重新打开各 pane 的文件、放回光标。
+    /// 未保存的修改不在快照里，恢复出来的标签页都是干净的；读不到的文件记一条日志后跳过。
+    pub fn restore_state(&mut self, snapshot: AppStateSnapshot) {
+        let AppStateSnapshot {
+            panes,
+            focus,
+            editor_layout,
+            search_query,
+        } = snapshot;
+        let _ = self.dispatch_kernel(KernelAction::RestoreUiSnapshot {
+            focus,
+            editor_layout,
+            search_query,
+        });
+
+        for (pane, pane_snapshot) in panes.into_iter().enumerate() {
+            let active_path = pane_snapshot
+                .tabs
+                .get(pane_snapshot.active)
+                .and_then(|tab| tab.path.clone());
+            for tab in pane_snapshot.tabs {
+                let Some(path) = tab.path else {
+                    continue;
+                };
+                let content = match std::fs::read_to_string(&path) {
+                    Ok(content) => content,
+                    Err(err) => {
+                        self.push_log_line(format!(
+                            "[restore] cannot reopen {}: {err}",
+                            path.display()
+                        ));
+                        continue;
+                    }
+                };
+                let _ = self.dispatch_kernel(KernelAction::Editor(EditorAction::OpenFile {
+                    pane,
+                    path,
+                    content,
+                }));
+                let byte_offset = self
+                    .store
+                    .state()
+                    .editor
+                    .pane(pane)
+                    .and_then(|pane_state| pane_state.active_tab())
+                    .map(|tab_state| {
+                        let buffer = &tab_state.buffer;
+                        let rope = buffer.rope();
+                        let row = tab.cursor.0.min(rope.len_lines().saturating_sub(1));
+                        rope.char_to_byte(buffer.pos_to_char((row, tab.cursor.1)))
+                    })
+                    .unwrap_or(0);
+                let _ = self.dispatch_kernel(KernelAction::Editor(EditorAction::GotoByteOffset {
+                    pane,
+                    byte_offset,
+                }));
+            }
+
+            let active_index = self.store.state().editor.pane(pane).and_then(|pane_state| {
+                pane_state
+                    .tabs
+                    .iter()
+                    .position(|tab| tab.path.is_some() && tab.path == active_path)
+            });
+            if let Some(index) = active_index {
+                let _ = self.dispatch_kernel(KernelAction::Editor(EditorAction::SetActiveTab {
+                    pane,
+                    index,
+                }));
+            }
+        }
+    }
+
+    /// 重新打开上次退出时记录的文件并放回光标；多出来的 pane 编号落到最后一个 pane。
+    /// 只在冷启动时调用，重启走 `restore_state`。
+    pub fn restore_session(&mut self) {
+        if !self.store.state().editor.config.restore_session {
+            return;
+        }
+        let open_files = self.saved_session.open_files.clone();
+        let panes = self.store.state().editor.panes.len().max(1);
+        let mut active = Vec::new();
+        for file in open_files {
+            let pane = file.pane.min(panes - 1);
+            let content = match std::fs::read_to_string(&file.path) {
+                Ok(content) => content,
+                Err(err) => {
+                    self.push_log_line(format!(
+                        "[session] cannot reopen {}: {err}",
+                        file.path.display()
+                    ));
+                    continue;
+                }
+            };
+            let _ = self.dispatch_kernel(KernelAction::Editor(EditorAction::OpenFile {
+                pane,
+                path: file.path.clone(),
+                content,
+            }));
+            let _ = self.dispatch_kernel(KernelAction::Editor(EditorAction::GotoByteOffset {
+                pane,
+                byte_offset: file.cursor_byte,
+            }));
+            if file.active {
+                active.push((pane, file.path));
+            }
+        }
+
+        for (pane, path) in active {
+            let index = self.store.state().editor.pane(pane).and_then(|pane_state| {
+                pane_state
+                    .tabs
+                    .iter()
+                    .position(|tab| tab.path.as_ref() == Some(&path))
+            });
+            if let Some(index) = index {
+                let _ = self.dispatch_kernel(KernelAction::Editor(EditorAction::SetActiveTab {
+                    pane,
+                    index,
+                }));
+            }
+        }
+    }
+
+    /// 拖拽结束时把侧边栏宽度写回 `setting.json`，下次启动沿用。
+    pub(super) fn persist_sidebar_width(&mut self) {
+        let Some(width) = self.store.state().ui.sidebar_width else {
+            return;
+        };
+        let Some(path) = self.settings_path.clone() else {
+            return;
+        };
+        if let Err(err) =
+            crate::kernel::services::adapters::settings::save_sidebar_width(&path, width)
+        {
+            self.push_log_line(format!(
+                "[settings] saving sidebar width to {} failed: {err}",
+                path.display()
+            ));
+            return;
+        }
+        // 自己写的文件不必再被 poll_settings 当作外部修改热重载一遍。
+        self.last_settings_modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+    }
+
+    /// 只导出与默认不同的绑定，文件格式同 settings 的 `keybindings` 数组。
+    pub(super) fn export_keybindings(&mut self, path: &Path) {
+        let Some(service) = self.kernel_services.get::<KeybindingService>() else {
+            return;
+        };
+        let count = service.non_default_rules().len();
+        match std::fs::write(path, service.export_to_json()) {
+            Ok(()) => self.push_log_line(format!(
+                "[keybindings] exported {count} rules to {}",
+                path.display()
+            )),
+            Err(err) => self.push_log_line(format!(
+                "[keybindings] export to {} failed: {err}",
+                path.display()
+            )),
+        }
+    }
+
+    /// 导入后把完整的非默认绑定写回 settings，重启或热重载后仍然生效。
+    pub(super) fn import_keybindings(&mut self, path: &Path) {
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(err) => {
+                self.push_log_line(format!(
+                    "[keybindings] reading {} failed: {err}",
+                    path.display()
+                ));
+                return;
+            }
+        };
+        let Some(service) = self.kernel_services.get_mut::<KeybindingService>() else {
+            return;
+        };
+        let warnings = match service.import_from_json(&json) {
+            Ok(warnings) => warnings,
+            Err(err) => {
+                self.push_log_line(format!(
+                    "[keybindings] {} is not a keybindings file: {err}",
+                    path.display()
+                ));
+                return;
+            }
+        };
+        let rules = service.non_default_rules();
+        for warning in warnings {
+            self.push_log_line(format!("[keybindings] {warning}"));
+        }
+
+        if let Some(settings_path) = self.settings_path.clone() {
+            if let Err(err) = crate::kernel::services::adapters::settings::save_keybindings(
+                &settings_path,
+                &rules,
+            ) {
+                self.push_log_line(format!(
+                    "[settings] saving keybindings to {} failed: {err}",
+                    settings_path.display()
+                ));
+            } else {
+                self.last_settings_modified = std::fs::metadata(&settings_path)
+                    .and_then(|m| m.modified())
+                    .ok();
+            }
+        }
+        self.push_log_line(format!("[keybindings] imported {}", path.display()));
+    }
+
+    pub(super) fn export_as_html(&mut self, path: &Path) {
+        let state = self.store.state();
+        let Some(tab) = state
+            .editor
+            .pane(state.ui.editor_layout.active_pane)
+            .and_then(|pane| pane.active_tab())
+        else {
+            return;
+        };
+        let html = crate::views::export::export_as_html(tab, &self.theme.core);
+        match std::fs::write(path, html) {
+            Ok(()) => self.push_log_line(format!("[export] wrote {}", path.display())),
+            Err(err) => {
+                self.push_log_line(format!("[export] writing {} failed: {err}", path.display()))
+            }
+        }
+    }
+
+    pub(super) fn show_metrics(&mut self) {
+        let Some(metrics) = self.kernel_services.middleware::<MetricsMiddleware>() else {
+            return;
+        };
+        let mut lines = vec![format!("[metrics] {} dispatches", metrics.total())];
+        lines.extend(
+            metrics
+                .sorted_counts()
+                .into_iter()
+                .map(|(label, count)| format!("[metrics] {count:>8}  {label}")),
+        );
+        for line in lines {
+            self.push_log_line(line);
+        }
+    }
+
+    pub(super) fn add_workspace_folder(&mut self, path: &Path) {
+        let path = match path.canonicalize() {
+            Ok(path) if path.is_dir() => path,
+            Ok(_) => {
+                self.push_log_line(format!("[workspace] {} is not a directory", path.display()));
+                return;
+            }
+            Err(err) => {
+                self.push_log_line(format!("[workspace] cannot add {}: {err}", path.display()));
+                return;
+            }
+        };
+        let _ = self.dispatch_kernel(KernelAction::WorkspaceFolderAdded { path: path.clone() });
+        if let Some(service) = self.kernel_services.get_mut::<LspService>() {
+            service.add_workspace_folder(path);
+        }
     }
 
     pub fn handle_message(&mut self, msg: AppMessage) {
         match msg {
+            AppMessage::DirBatchLoaded { path, entries } => {
+                let _ = self.dispatch_kernel(KernelAction::DirBatchLoaded { path, entries });
+            }
             AppMessage::DirLoaded { path, entries } => {
                 let _ = self.dispatch_kernel(KernelAction::DirLoaded { path, entries });
             }
@@ -470,6 +919,14 @@ impl Workbench {
                 let _ = self.dispatch_kernel(KernelAction::DirLoadError { path });
             }
             AppMessage::FileLoaded { path, content } => {
+                if let Some(ops_path) = settings_enabled()
+                    .then(|| crate::kernel::services::adapters::get_ops_file_path(&path))
+                    .flatten()
+                    .filter(|ops_path| ops_path.is_file())
+                {
+                    self.runtime
+                        .load_edit_history(path.clone(), ops_path, content.clone());
+                }
                 let pane = self
                     .store
                     .state()
@@ -512,6 +969,8 @@ impl Workbench {
                     if let Some(service) = self.kernel_services.get_mut::<LspService>() {
                         service.save_document(&path);
                     }
+                    self.lsp_sync.debounce.document_link =
+                        Some(Instant::now() + interaction::DOCUMENT_LINK_DEBOUNCE);
 
                     if self
                         .settings_path
@@ -529,16 +988,78 @@ impl Workbench {
                     head,
                 }));
             }
+            AppMessage::FileError {
+                path,
+                error: FileError::TooLarge { size, limit },
+            } => {
+                tracing::warn!(path = %path.display(), size, limit, "load_file over size limit");
+                let message = if size >= self.large_file_threshold_bytes() {
+                    format!(
+                        "Large file ({}). It will be loaded in chunks and cannot be saved until fully loaded. Open? [Y/n]",
+                        crate::models::format_file_size(size)
+                    )
+                } else {
+                    format!(
+                        "File too large ({}). Limit is {}. Open anyway? [Y/n]",
+                        crate::models::format_file_size(size),
+                        crate::models::format_file_size(limit)
+                    )
+                };
+                let _ = self.dispatch_kernel(KernelAction::ShowConfirmDialog {
+                    message,
+                    on_confirm: PendingAction::OpenLargeFile { path, size },
+                });
+            }
+            AppMessage::FileRangeLoaded {
+                path,
+                start_byte,
+                end_byte,
+                file_size,
+                content,
+            } => {
+                if start_byte > 0 {
+                    let _ =
+                        self.dispatch_kernel(KernelAction::Editor(EditorAction::AppendFileRange {
+                            path,
+                            start_byte,
+                            end_byte,
+                            file_size,
+                            content,
+                        }));
+                    return;
+                }
+                let pane = self.active_editor_pane();
+                let _ = self.dispatch_kernel(KernelAction::Editor(EditorAction::OpenFileRange {
+                    pane,
+                    path,
+                    content,
+                    loaded_bytes: end_byte,
+                    file_size,
+                }));
+                let _ = self.dispatch_kernel(KernelAction::RunCommand(Command::FocusEditor));
+            }
             AppMessage::FileError { path, error } => {
                 tracing::error!(path = %path.display(), error = %error, "load_file failed");
             }
             AppMessage::PathCreated { path, is_dir } => {
+                self.finish_explorer_op(true, |inverse| {
+                    matches!(inverse, KernelEffect::DeletePath { path: p, .. } if *p == path)
+                });
                 let _ = self.dispatch_kernel(KernelAction::ExplorerPathCreated { path, is_dir });
             }
             AppMessage::PathDeleted { path } => {
+                self.finish_explorer_op(true, |inverse| {
+                    matches!(inverse, KernelEffect::RestoreFromTrash { path: p, .. } if *p == path)
+                });
                 let _ = self.dispatch_kernel(KernelAction::ExplorerPathDeleted { path });
             }
             AppMessage::PathRenamed { from, to } => {
+                self.finish_explorer_op(true, |inverse| {
+                    matches!(
+                        inverse,
+                        KernelEffect::RenamePath { from: f, to: t, .. } if *f == to && *t == from
+                    )
+                });
                 let _ = self.dispatch_kernel(KernelAction::ExplorerPathRenamed { from, to });
             }
             AppMessage::FsOpError {
@@ -546,21 +1067,65 @@ impl Workbench {
                 path,
                 to,
                 error,
-            } => match to {
-                Some(to) => tracing::warn!(
-                    op = %op,
-                    path = %path.display(),
-                    to = %to.display(),
-                    error = %error,
-                    "workbench.fs_op_failed"
-                ),
-                None => tracing::warn!(
-                    op = %op,
-                    path = %path.display(),
-                    error = %error,
-                    "workbench.fs_op_failed"
-                ),
-            },
+            } => {
+                self.finish_explorer_op(false, |inverse| {
+                    bridge::explorer_inverse_matches_error(inverse, &path, to.as_deref())
+                });
+                match to {
+                    Some(to) => tracing::warn!(
+                        op = %op,
+                        path = %path.display(),
+                        to = %to.display(),
+                        error = %error,
+                        "workbench.fs_op_failed"
+                    ),
+                    None => tracing::warn!(
+                        op = %op,
+                        path = %path.display(),
+                        error = %error,
+                        "workbench.fs_op_failed"
+                    ),
+                }
+            }
+            AppMessage::GitStashList { stashes } => {
+                let _ = self.dispatch_kernel(KernelAction::GitStashList { stashes });
+            }
+            AppMessage::GitBranchesUpdated { branches } => {
+                let _ = self.dispatch_kernel(KernelAction::GitBranchesUpdated { branches });
+            }
+            AppMessage::GitBlameResult {
+                path,
+                line,
+                author,
+                timestamp,
+                commit,
+            } => {
+                let _ = self.dispatch_kernel(KernelAction::GitBlameResult {
+                    path,
+                    line,
+                    blame: GitBlameLine {
+                        commit,
+                        author,
+                        timestamp,
+                    },
+                });
+            }
+            AppMessage::GitDiffBaseLoaded { path, content } => {
+                let _ = self.dispatch_kernel(KernelAction::GitDiffBaseLoaded { path, content });
+            }
+            AppMessage::WorkspaceFiles { files } => {
+                let _ = self.dispatch_kernel(KernelAction::CommandLineFilesListed { files });
+            }
+            AppMessage::GitOpError { op, error } => {
+                self.push_log_line(format!("[git:{op}] {error}"));
+            }
+            AppMessage::EditHistoryLoaded { path, history } => {
+                let _ =
+                    self.dispatch_kernel(KernelAction::Editor(EditorAction::RestoreEditHistory {
+                        path,
+                        history,
+                    }));
+            }
             AppMessage::FileReloaded { request, content } => {
                 let _ = self.dispatch_kernel(KernelAction::Editor(EditorAction::FileReloaded {
                     content,
@@ -702,11 +1267,16 @@ impl View for Workbench {
         let result = input::handle_input(self, event);
         if matches!(result, EventResult::Quit) {
             self.flush_completion_rank_save();
+            self.flush_session_save();
+            self.flush_edit_history_backups();
             return result;
         }
         if let Some((path, hard)) = self.take_pending_restart() {
             return EventResult::Restart { path, hard };
         }
+        if let Some(cwd) = self.pending_shell.take() {
+            return EventResult::Shell { cwd };
+        }
         result
     }
 
@@ -40,6 +40,29 @@ impl Workbench {
         false
     }
 
+    /// 点击状态栏的缩进段：当前 pane 的 tab 宽度在 2 → 4 → 8 之间循环。
+    pub(super) fn handle_status_bar_mouse(&mut self, event: &MouseEvent) -> EventResult {
+        const COMMON_TAB_SIZES: [u8; 3] = [2, 4, 8];
+
+        if !matches!(event.kind, MouseEventKind::Down(MouseButton::Left)) {
+            return EventResult::Ignored;
+        }
+        let pane = self.store.state().ui.editor_layout.active_pane;
+        let current = self.store.state().editor_config_for_pane(pane).tab_size;
+        let tab_size = COMMON_TAB_SIZES
+            .into_iter()
+            .find(|&size| size > current)
+            .unwrap_or(COMMON_TAB_SIZES[0]);
+        if self.dispatch_kernel(KernelAction::RunCommand(Command::SetPaneTabSize {
+            pane,
+            tab_size,
+        })) {
+            EventResult::Consumed
+        } else {
+            EventResult::Ignored
+        }
+    }
+
     pub(super) fn handle_sidebar_split_mouse(
         &mut self,
         event: &MouseEvent,
@@ -94,6 +117,7 @@ impl Workbench {
 
         if matches!(event.kind, MouseEventKind::Up(MouseButton::Left)) {
             self.interaction.sidebar_split_dragging = false;
+            self.persist_sidebar_width();
             handled = true;
         }
 
@@ -12,6 +12,7 @@ pub(super) enum MouseTarget {
     CommandLine,
     Overlay,
     SidebarSplitter,
+    StatusBar,
     ByFocus,
     Explorer,
     Editor,
@@ -107,6 +108,15 @@ pub(super) fn plan_mouse_dispatch(workbench: &Workbench, event: &MouseEvent) ->
         return MouseDispatchPlan::modal(target);
     }
 
+    if matches!(event.kind, MouseEventKind::Down(MouseButton::Left))
+        && workbench
+            .frame_layout
+            .status_indentation_area
+            .is_some_and(|a| util::rect_contains(a, event.column, event.row))
+    {
+        return MouseDispatchPlan::modal(MouseTarget::StatusBar);
+    }
+
     let focus_plan = focus_plan_for_area(workbench, event);
     if focus_plan.is_some() {
         MouseDispatchPlan::with_focus(MouseTarget::ByFocus, focus_plan)
@@ -11,6 +11,8 @@ pub(crate) struct EditorMouseTracker {
     last_click: Option<(u16, u16, Instant)>,
     click_count: u8,
     dragging: bool,
+    /// Alt+press position (source row, col); while set, dragging draws a column selection.
+    block_anchor: Option<(usize, usize)>,
 }
 
 impl EditorMouseTracker {
@@ -19,6 +21,7 @@ impl EditorMouseTracker {
             last_click: None,
             click_count: 0,
             dragging: false,
+            block_anchor: None,
         }
     }
 
@@ -47,6 +50,7 @@ impl EditorMouseTracker {
 
         self.last_click = Some((x, y, now));
         self.dragging = true;
+        self.block_anchor = None;
 
         match self.click_count {
             1 => Granularity::Char,
@@ -61,5 +65,15 @@ impl EditorMouseTracker {
 
     pub fn stop_drag(&mut self) {
         self.dragging = false;
+        self.block_anchor = None;
+    }
+
+    /// Turn the current drag into a column selection anchored at `pos`.
+    pub fn start_block(&mut self, pos: (usize, usize)) {
+        self.block_anchor = Some(pos);
+    }
+
+    pub fn block_anchor(&self) -> Option<(usize, usize)> {
+        self.block_anchor
     }
 }
@@ -1,22 +1,38 @@
 use super::super::Workbench;
 use crate::core::text_window;
-use crate::kernel::palette::match_items;
+use crate::kernel::palette::{
+    clipboard_preview, file_candidates, match_branches, match_clipboard, match_files, match_items,
+    parse_line_number, BranchCandidate,
+};
+use crate::kernel::CommandLineMode;
 use crate::ui::core::geom::{Pos, Rect as UiRect};
 use crate::ui::core::painter::Painter;
 use crate::ui::core::style::Style as UiStyle;
 use unicode_width::UnicodeWidthStr;
 
 const MAX_COMPLETIONS: usize = 8;
-const PROMPT: &str = ":";
+
+/// 行首的模式指示：`>` 开头过滤命令，`:` 开头跳转行，否则模糊搜索文件。
+fn prompt(mode: CommandLineMode) -> &'static str {
+    match mode {
+        CommandLineMode::Command => "cmd: ",
+        CommandLineMode::File => "file: ",
+        CommandLineMode::Line => "line: ",
+        CommandLineMode::Branch => "branch: ",
+        CommandLineMode::Clipboard => "clipboard: ",
+    }
+}
 
 impl Workbench {
-    /// vim 风格 `:` 命令行，占据状态栏那一行；上方浮出命令名补全列表。
-    /// 由 F1 / Ctrl+Shift+P 唤起（无模态编辑时 `:` 不能当全局触发键）。
+    /// vim 风格 `:` 命令行，占据状态栏那一行；上方浮出命令名 / 文件补全列表。
+    /// F1 / Ctrl+Shift+P 以命令模式唤起，Ctrl+P 以文件模式唤起，Ctrl+G 以跳转行模式唤起
+    /// （无模态编辑时 `:` 不能当全局触发键）。
     pub(super) fn paint_command_line(&self, painter: &mut Painter, status_area: UiRect) {
         if status_area.is_empty() {
             return;
         }
         let line = &self.store.state().ui.command_line;
+        let prompt = prompt(line.mode());
 
         let base = UiStyle::default()
             .bg(self.theme.core.statusbar_bg)
@@ -28,12 +44,12 @@ impl Workbench {
             .fg(self.theme.core.accent_fg);
         painter.text_clipped(
             Pos::new(status_area.x, status_area.y),
-            PROMPT,
+            prompt,
             prompt_style,
             status_area,
         );
 
-        let prompt_w = PROMPT.width() as u16;
+        let prompt_w = prompt.width() as u16;
         let avail = status_area.w.saturating_sub(prompt_w) as usize;
         let (start, end) = text_window::window(&line.input, line.cursor, avail);
         let visible = line.input.get(start..end).unwrap_or_default();
@@ -48,8 +64,66 @@ impl Workbench {
     }
 
     fn paint_command_line_completions(&self, painter: &mut Painter, status_area: UiRect) {
-        let line = &self.store.state().ui.command_line;
-        let matches = match_items(&line.input);
+        let state = self.store.state();
+        let line = &state.ui.command_line;
+        // (标签, 淡色的补充说明)：文件显示文件名，后跟所在目录。
+        let matches: Vec<(std::borrow::Cow<'_, str>, std::borrow::Cow<'_, str>)> = match line.mode()
+        {
+            CommandLineMode::Line => {
+                self.paint_goto_line_hint(painter, status_area);
+                return;
+            }
+            CommandLineMode::Command => match_items(line.query())
+                .into_iter()
+                .map(|item| (item.label.into(), "".into()))
+                .collect(),
+            CommandLineMode::File => {
+                let candidates =
+                    file_candidates(&state.recent_files, &line.files, &state.workspace_root);
+                match_files(line.query(), &candidates)
+                    .into_iter()
+                    .map(|path| {
+                        let name = path
+                            .file_name()
+                            .map(|name| name.to_string_lossy().into_owned())
+                            .unwrap_or_else(|| path.to_string_lossy().into_owned());
+                        let dir = path
+                            .parent()
+                            .map(|dir| dir.to_string_lossy().into_owned())
+                            .unwrap_or_default();
+                        (name.into(), dir.into())
+                    })
+                    .collect()
+            }
+            CommandLineMode::Branch => match_branches(line.query(), &state.git.branches)
+                .into_iter()
+                .map(|candidate| match candidate {
+                    BranchCandidate::Checkout(branch) => {
+                        let detail = match (branch.current, branch.remote) {
+                            (true, _) => "current",
+                            (false, true) => "remote",
+                            (false, false) => "",
+                        };
+                        (branch.name.as_str().into(), detail.into())
+                    }
+                    BranchCandidate::Create(name) => {
+                        (format!("+ Create branch '{name}'").into(), "".into())
+                    }
+                })
+                .collect(),
+            CommandLineMode::Clipboard => match_clipboard(line.query(), &line.clipboard_entries)
+                .into_iter()
+                .map(|entry| {
+                    let (first, more) = clipboard_preview(entry);
+                    let detail = match more {
+                        0 => String::new(),
+                        1 => "+1 line".to_string(),
+                        n => format!("+{n} lines"),
+                    };
+                    (first.into(), detail.into())
+                })
+                .collect(),
+        };
         if matches.is_empty() {
             return;
         }
@@ -72,7 +146,7 @@ impl Workbench {
             .bg(self.theme.core.palette_selected_bg)
             .fg(self.theme.core.palette_selected_fg);
 
-        for (row, item) in matches.iter().skip(start).take(count).enumerate() {
+        for (row, (item, detail)) in matches.iter().skip(start).take(count).enumerate() {
             let y = top.saturating_add(row as u16);
             if y >= status_area.y {
                 break;
@@ -88,20 +162,72 @@ impl Workbench {
 
             let prefix_w = prefix.width() as u16;
             let max_w = status_area.w.saturating_sub(prefix_w) as usize;
-            let mut label = item.label.to_string();
+            let mut label = item.to_string();
             if label.width() > max_w {
                 let trunc = text_window::truncate_to_width(&label, max_w);
                 label.truncate(trunc);
             }
+            let label_w = label.width() as u16;
             painter.text_clipped(
                 Pos::new(status_area.x.saturating_add(prefix_w), y),
                 label,
                 style,
                 row_rect,
             );
+
+            if !detail.is_empty() {
+                let detail_style = style.fg(self.theme.core.palette_muted_fg);
+                painter.text_clipped(
+                    Pos::new(
+                        status_area
+                            .x
+                            .saturating_add(prefix_w)
+                            .saturating_add(label_w)
+                            .saturating_add(1),
+                        y,
+                    ),
+                    detail.as_ref(),
+                    detail_style,
+                    row_rect,
+                );
+            }
         }
     }
 
+    /// 跳转行模式没有候选列表，只在上方浮一行可跳范围；输入不在范围内时标红。
+    fn paint_goto_line_hint(&self, painter: &mut Painter, status_area: UiRect) {
+        let state = self.store.state();
+        let Some(line_count) = state
+            .editor
+            .pane(state.ui.editor_layout.active_pane)
+            .and_then(|pane| pane.active_tab())
+            .map(|tab| tab.buffer.rope().len_lines())
+        else {
+            return;
+        };
+        if status_area.y == 0 {
+            return;
+        }
+
+        let query = state.ui.command_line.query();
+        let valid = query.is_empty() || parse_line_number(query, line_count).is_some();
+        let fg = if valid {
+            self.theme.core.palette_fg
+        } else {
+            self.theme.core.error_fg
+        };
+        let style = UiStyle::default().bg(self.theme.core.popup_bg).fg(fg);
+        let hint = format!("  Line 1\u{2013}{line_count}");
+        let row_rect = UiRect::new(
+            status_area.x,
+            status_area.y - 1,
+            (hint.width() as u16 + 2).min(status_area.w),
+            1,
+        );
+        painter.fill_rect(row_rect, style);
+        painter.text_clipped(Pos::new(row_rect.x, row_rect.y), hint, style, row_rect);
+    }
+
     pub(super) fn command_line_cursor(&self) -> Option<(u16, u16)> {
         if !self.store.state().ui.command_line.active {
             return None;
@@ -112,8 +238,9 @@ impl Workbench {
         }
         let status_y = area.bottom().saturating_sub(super::super::STATUS_HEIGHT);
         let line = &self.store.state().ui.command_line;
+        let prompt = prompt(line.mode());
 
-        let prompt_w = PROMPT.width() as u16;
+        let prompt_w = prompt.width() as u16;
         let avail = area.w.saturating_sub(prompt_w) as usize;
         let (start, _end) = text_window::window(&line.input, line.cursor, avail);
         let before = line.input.get(start..line.cursor).unwrap_or_default();
@@ -15,7 +15,9 @@ pub(super) fn render_confirm_dialog(workbench: &Workbench, painter: &mut Painter
         return;
     }
 
-    let width = 50.min(area.w.saturating_sub(4));
+    // 默认 50 列；较长的提示（如大文件确认）放宽到 80 列以内，避免截断。
+    let desired = (dialog.message.width() + 4).clamp(50, 80) as u16;
+    let width = desired.min(area.w.saturating_sub(4));
     let height = 5.min(area.h.saturating_sub(2));
     if width < 20 || height < 3 {
         return;
@@ -1,5 +1,7 @@
 use super::super::{CompletionDocKey, Workbench};
 use crate::kernel::editor::EditorPaneState;
+use crate::kernel::services::ports::LspPositionEncoding;
+use crate::kernel::ProblemItem;
 use crate::ui::backend::Backend;
 use crate::ui::core::geom::Pos;
 use crate::ui::core::geom::Rect as UiRect;
@@ -14,6 +16,7 @@ use crate::views::{
     compute_editor_pane_layout, compute_pane_rects, compute_tab_row_layout, cursor_position_editor,
     paint_editor_pane, EditorPaneLayout, EditorPaneRenderOptions, TransientRowHighlight,
 };
+use std::time::{SystemTime, UNIX_EPOCH};
 use unicode_width::UnicodeWidthStr;
 
 pub(super) const MAX_DOC_RENDER_LINES: usize = doc::MAX_RENDER_LINES;
@@ -37,15 +40,19 @@ impl Workbench {
         let Some(pane_state) = self.store.state().editor.pane(active_pane) else {
             return;
         };
-        let tab_size = self.store.state().editor.config.tab_size;
+        let tab_size = self
+            .store
+            .state()
+            .editor_config_for_pane(active_pane)
+            .tab_size;
         let layout = {
-            let config = &self.store.state().editor.config;
+            let config = &self.store.state().editor_config_for_pane(active_pane);
             compute_editor_pane_layout(pane_area, pane_state, config)
         };
         let (cx, cy) = if let Some((x, y)) = self.ui.hover_popup.last_anchor {
             (x, y)
         } else {
-            let config = &self.store.state().editor.config;
+            let config = &self.store.state().editor_config_for_pane(active_pane);
             let Some((cx, cy)) = cursor_position_editor(&layout, pane_state, config) else {
                 return;
             };
@@ -172,7 +179,7 @@ impl Workbench {
         let Some(pane_state) = self.store.state().editor.pane(active_pane) else {
             return;
         };
-        let config = &self.store.state().editor.config;
+        let config = &self.store.state().editor_config_for_pane(active_pane);
         let layout = compute_editor_pane_layout(pane_area, pane_state, config);
         let Some((cx, cy)) = cursor_position_editor(&layout, pane_state, config) else {
             return;
@@ -283,22 +290,27 @@ impl Workbench {
         let Some(pane_state) = self.store.state().editor.pane(pane) else {
             return;
         };
-        let tab_size = self.store.state().editor.config.tab_size;
+        let tab_size = self.store.state().editor_config_for_pane(pane).tab_size;
         let layout = {
-            let config = &self.store.state().editor.config;
+            let config = &self.store.state().editor_config_for_pane(pane);
             compute_editor_pane_layout(pane_area, pane_state, config)
         };
-        let config = &self.store.state().editor.config;
+        let config = &self.store.state().editor_config_for_pane(pane);
         let Some((cx, cy)) = cursor_position_editor(&layout, pane_state, config) else {
             return;
         };
 
-        let max_items = 8usize;
+        let max_items = config.completion_popup_max_lines.max(1);
         let selected = completion
             .selected
             .min(completion.visible_len().saturating_sub(1));
-        let mut start = 0usize;
-        if selected >= max_items {
+        // 过滤后选中项可能落在 scroll_offset 那一屏之外，这里兜底校正。
+        let mut start = completion
+            .scroll_offset
+            .min(completion.visible_len().saturating_sub(1));
+        if selected < start {
+            start = selected;
+        } else if selected >= start + max_items {
             start = selected + 1 - max_items;
         }
         let end = (start + max_items).min(completion.visible_len());
@@ -311,34 +323,66 @@ impl Workbench {
             };
             let is_selected = i == selected;
             let marker = if is_selected { ">" } else { " " };
+            let scroll_hint = if i == start && start > 0 {
+                Some("\u{2191}")
+            } else if i + 1 == end && end < completion.visible_len() {
+                Some("\u{2193}")
+            } else {
+                None
+            };
 
             let text = item.label.as_str();
+            let label_details = item.label_details.as_ref();
             let mut detail = String::new();
-            if let Some(d) = item.detail.as_deref() {
-                if !d.trim().is_empty() {
-                    detail = d.to_string();
+            // 有 labelDetails 时由它提供签名与说明，不再把 detail 挤在标签后面。
+            if label_details.is_none() {
+                if let Some(d) = item.detail.as_deref() {
+                    if !d.trim().is_empty() {
+                        detail = d.to_string();
+                    }
                 }
-            }
-            // When detail is empty and insert_text differs from label, show
-            // a simplified insert_text so the user can distinguish items with
-            // the same label (e.g. Java's two "class" completions).
-            if detail.is_empty() && item.commit.insert.text != text {
-                let preview = strip_snippet_markers(&item.commit.insert.text);
-                if preview != text {
-                    detail = preview;
+                // When detail is empty and insert_text differs from label, show
+                // a simplified insert_text so the user can distinguish items with
+                // the same label (e.g. Java's two "class" completions).
+                if detail.is_empty() && item.commit.insert.text != text {
+                    let preview = strip_snippet_markers(&item.commit.insert.text);
+                    if preview != text {
+                        detail = preview;
+                    }
                 }
             }
-
-            let width = if detail.is_empty() {
-                UnicodeWidthStr::width(text)
-            } else {
-                UnicodeWidthStr::width(text)
-                    .saturating_add(1 + UnicodeWidthStr::width(detail.as_str()))
-            };
-            // marker + space + text + optional (space + detail)
+            let right_detail = label_details
+                .and_then(|details| details.detail.clone())
+                .unwrap_or_default();
+            let description = label_details.and_then(|details| details.description.clone());
+
+            let mut width = UnicodeWidthStr::width(text);
+            if !detail.is_empty() {
+                width = width.saturating_add(1 + UnicodeWidthStr::width(detail.as_str()));
+            }
+            if !right_detail.is_empty() {
+                width = width.saturating_add(2 + UnicodeWidthStr::width(right_detail.as_str()));
+            }
+            if scroll_hint.is_some() {
+                width = width.saturating_add(2);
+            }
+            // marker + space + text + optional (space + detail) + optional (gap + right detail)
+            // + optional (gap + scroll hint)
             let inner_w = 2usize.saturating_add(width);
             max_inner_width = max_inner_width.max(inner_w);
-            rows.push((is_selected, marker, text.to_string(), detail));
+            if let Some(description) = description.as_deref() {
+                let description_w = 2usize.saturating_add(UnicodeWidthStr::width(description));
+                max_inner_width = max_inner_width.max(description_w);
+            }
+            rows.push(CompletionPopupRow {
+                is_selected,
+                marker,
+                scroll_hint,
+                label: text.to_string(),
+                detail,
+                right_detail,
+                description,
+            });
         }
 
         if area.is_empty() {
@@ -346,7 +390,7 @@ impl Workbench {
         }
 
         let desired_width = max_inner_width;
-        let desired_height = rows.len();
+        let desired_height = rows.iter().map(CompletionPopupRow::height).sum::<usize>();
 
         let width = desired_width.max(6).min(area.w as usize).max(1) as u16;
         let height = desired_height.max(1).min(area.h as usize).max(1) as u16;
@@ -398,11 +442,31 @@ impl Workbench {
             .fg(self.theme.core.palette_muted_fg)
             .bg(self.theme.core.palette_selected_bg);
 
-        for (idx, (is_selected, marker, label, detail)) in rows.into_iter().enumerate() {
-            let y = inner.y.saturating_add(idx.min(u16::MAX as usize) as u16);
+        let right_detail_style = |is_selected: bool| {
+            UiStyle::default()
+                .fg(self.theme.core.completion_detail_fg)
+                .bg(if is_selected {
+                    self.theme.core.palette_selected_bg
+                } else {
+                    self.theme.core.popup_bg
+                })
+        };
+        let description_style = |is_selected: bool| {
+            UiStyle::default()
+                .fg(self.theme.core.completion_description_fg)
+                .bg(if is_selected {
+                    self.theme.core.palette_selected_bg
+                } else {
+                    self.theme.core.popup_bg
+                })
+        };
+
+        let mut y = inner.y;
+        for row in rows {
             if y >= inner.bottom() {
                 break;
             }
+            let is_selected = row.is_selected;
             let row_area = UiRect::new(inner.x, y, inner.w, 1);
             painter.fill_rect(row_area, base_style);
             if is_selected {
@@ -421,7 +485,7 @@ impl Workbench {
             } else {
                 marker_normal
             };
-            painter.text_clipped(Pos::new(x, y), marker, marker_style, row_area);
+            painter.text_clipped(Pos::new(x, y), row.marker, marker_style, row_area);
             x = x.saturating_add(1);
             painter.text_clipped(
                 Pos::new(x, y),
@@ -430,10 +494,11 @@ impl Workbench {
                 row_area,
             );
             x = x.saturating_add(1);
-            let label_w = label.width().min(u16::MAX as usize) as u16;
-            painter.text_clipped(Pos::new(x, y), label, label_style, row_area);
+            let label_x = x;
+            let label_w = row.label.width().min(u16::MAX as usize) as u16;
+            painter.text_clipped(Pos::new(x, y), &row.label, label_style, row_area);
             x = x.saturating_add(label_w);
-            if !detail.trim().is_empty() {
+            if !row.detail.trim().is_empty() {
                 painter.text_clipped(
                     Pos::new(x, y),
                     " ",
@@ -441,7 +506,46 @@ impl Workbench {
                     row_area,
                 );
                 x = x.saturating_add(1);
-                painter.text_clipped(Pos::new(x, y), detail, detail_style, row_area);
+                let detail_w = row.detail.width().min(u16::MAX as usize) as u16;
+                painter.text_clipped(Pos::new(x, y), &row.detail, detail_style, row_area);
+                x = x.saturating_add(detail_w);
+            }
+            // 列表上下还有条目时，首 / 末可见行最右列画 ↑ / ↓。
+            let mut right = inner.right();
+            if let Some(hint) = row.scroll_hint {
+                right = right.saturating_sub(1);
+                painter.text_clipped(Pos::new(right, y), hint, detail_style, row_area);
+                right = right.saturating_sub(1);
+            }
+            if !row.right_detail.is_empty() {
+                // 右对齐；popup 太窄时紧跟在标签后，由行裁剪截断。
+                let right_w = row.right_detail.width().min(u16::MAX as usize) as u16;
+                let right_x = right.saturating_sub(right_w).max(x.saturating_add(1));
+                painter.text_clipped(
+                    Pos::new(right_x, y),
+                    &row.right_detail,
+                    right_detail_style(is_selected),
+                    row_area,
+                );
+            }
+            y = y.saturating_add(1);
+
+            if let Some(description) = row.description.as_deref() {
+                if y >= inner.bottom() {
+                    break;
+                }
+                let row_area = UiRect::new(inner.x, y, inner.w, 1);
+                painter.fill_rect(row_area, base_style);
+                if is_selected {
+                    painter.fill_rect(row_area, selected_bg);
+                }
+                painter.text_clipped(
+                    Pos::new(label_x, y),
+                    description,
+                    description_style(is_selected),
+                    row_area,
+                );
+                y = y.saturating_add(1);
             }
         }
 
@@ -560,10 +664,10 @@ impl Workbench {
         layout: &EditorPaneLayout,
         pane_state: &EditorPaneState,
         markdown: Option<&MarkdownDocument>,
-        mut options: EditorPaneRenderOptions,
+        mut options: EditorPaneRenderOptions<'_>,
     ) {
         let mut painter = Painter::new();
-        let config = &self.store.state().editor.config;
+        let config = &self.store.state().editor_config_for_pane(pane);
         options.show_vertical_scrollbar = self.show_editor_vertical_scrollbar(pane, layout);
         paint_editor_pane(
             &mut painter,
@@ -595,6 +699,42 @@ impl Workbench {
             && self.interaction.editor_scrollbar_hover == Some(pane)
     }
 
+    /// 光标行已缓存的 blame 结论；未保存的缓冲区不显示（行号与磁盘不一致）。
+    fn inline_blame_for_pane(&self, pane: usize) -> Option<String> {
+        let state = self.store.state();
+        if !state.editor_config_for_pane(pane).git_blame_inline {
+            return None;
+        }
+        let tab = state.editor.pane(pane)?.active_tab()?;
+        if tab.dirty {
+            return None;
+        }
+        let blame = state
+            .git
+            .blame_for(tab.path.as_ref()?, tab.buffer.cursor().0)?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        Some(blame.annotation(now))
+    }
+
+    /// 活动标签页文件在 Problems 里的诊断，以及换算其列号用的位置编码。
+    fn diagnostics_for_pane(&self, pane: usize) -> (&[ProblemItem], LspPositionEncoding) {
+        let state = self.store.state();
+        let Some(path) = state
+            .editor
+            .pane(pane)
+            .and_then(|pane_state| pane_state.active_tab())
+            .and_then(|tab| tab.path.as_deref())
+        else {
+            return (&[], LspPositionEncoding::Utf16);
+        };
+        (
+            state.problems.items_for_path(path),
+            super::super::bridge::lsp_position_encoding_for_path(state, path),
+        )
+    }
+
     fn definition_jump_row_highlight_for_pane(&self, pane: usize) -> Option<TransientRowHighlight> {
         let highlight = self.definition_jump_highlight?;
         if highlight.pane != pane {
@@ -655,7 +795,7 @@ impl Workbench {
             let Some(pane_state) = self.store.state().editor.pane(pane) else {
                 return;
             };
-            let config = &self.store.state().editor.config;
+            let config = &self.store.state().editor_config_for_pane(pane);
             compute_editor_pane_layout(inner, pane_state, config)
         };
         self.sync_editor_viewport_size(pane, &layout);
@@ -670,11 +810,16 @@ impl Workbench {
         if let Some(pane_state) = self.store.state().editor.pane(pane) {
             push_editor_area_node(&mut self.ui_tree, pane, &layout);
             push_editor_tab_nodes(&mut self.ui_tree, pane, &layout, pane_state, hovered_tab);
+            let inline_blame = self.inline_blame_for_pane(pane);
+            let (diagnostics, diagnostics_encoding) = self.diagnostics_for_pane(pane);
             let options = EditorPaneRenderOptions {
                 hovered_tab,
                 workspace_empty: self.store.state().explorer.rows.is_empty(),
                 show_vertical_scrollbar: false,
                 transient_row_highlight: self.definition_jump_row_highlight_for_pane(pane),
+                inline_blame: inline_blame.as_deref(),
+                diagnostics,
+                diagnostics_encoding,
             };
             let markdown = md_tab_id.and_then(|tab_id| self.markdown_doc_for_tab(tab_id));
             self.draw_editor_pane(backend, pane, &layout, pane_state, markdown, options);
@@ -705,6 +850,26 @@ fn wrap_lines(lines: &[&str], width: u16, max_lines: usize) -> Vec<String> {
     out
 }
 
+/// 补全列表中的一项：标签行，有 `labelDetails.description` 时下方再占一行。
+struct CompletionPopupRow {
+    is_selected: bool,
+    marker: &'static str,
+    /// 首 / 末可见行上方 / 下方还有条目时的 `↑` / `↓`。
+    scroll_hint: Option<&'static str>,
+    label: String,
+    /// 紧跟标签的 detail（没有 labelDetails 时的旧式展示）。
+    detail: String,
+    /// `labelDetails.detail`，右对齐。
+    right_detail: String,
+    description: Option<String>,
+}
+
+impl CompletionPopupRow {
+    fn height(&self) -> usize {
+        1 + usize::from(self.description.is_some())
+    }
+}
+
 fn completion_doc_area(
     screen: UiRect,
     popup: UiRect,
@@ -24,10 +24,12 @@ pub(super) fn render(workbench: &mut Workbench, backend: &mut dyn Backend, area:
 
     let (body_area, status_area) = area.split_bottom(super::super::STATUS_HEIGHT);
 
+    workbench.frame_layout.status_indentation_area = None;
     if !status_area.is_empty() {
         let _scope = perf::scope("render.status");
         let mut painter = Painter::new();
-        workbench.paint_status(&mut painter, status_area);
+        workbench.frame_layout.status_indentation_area =
+            workbench.paint_status(&mut painter, status_area);
         backend.draw(status_area, painter.cmds());
     }
 
@@ -245,8 +247,9 @@ fn render_drag_preview(workbench: &Workbench, backend: &mut dyn Backend, area: R
                     }
                 }
             }
-            DropIntent::ExplorerToExplorerFolder { .. }
-            | DropIntent::ExplorerToExplorerRow { .. } => {
+            // 文件行不可放入；目录行的高亮带由 ExplorerView 按 `ExplorerDragState` 画。
+            DropIntent::ExplorerToExplorerRow { .. } => {}
+            DropIntent::ExplorerToExplorerFolder { .. } => {
                 let highlight = UiStyle::default()
                     .bg(workbench.theme.core.palette_selected_bg)
                     .fg(workbench.theme.core.palette_selected_fg);
@@ -103,6 +103,26 @@ impl Workbench {
         self.render_cache.viewport.symbols_panel_height = Some(height);
     }
 
+    fn sync_git_view_height(&mut self, height: u16) {
+        if height == 0 {
+            return;
+        }
+        if self.render_cache.viewport.git_panel_height == Some(height) {
+            return;
+        }
+        self.render_cache.viewport.git_panel_height = Some(height);
+    }
+
+    fn sync_call_hierarchy_view_height(&mut self, height: u16) {
+        if height == 0 {
+            return;
+        }
+        if self.render_cache.viewport.call_hierarchy_panel_height == Some(height) {
+            return;
+        }
+        self.render_cache.viewport.call_hierarchy_panel_height = Some(height);
+    }
+
     pub fn flush_post_render_sync(&mut self) -> bool {
         let mut changed = false;
 
@@ -201,6 +221,31 @@ impl Workbench {
             }
         }
 
+        if let Some(height) = self.render_cache.viewport.git_panel_height {
+            if self.render_cache.viewport.applied_git_panel_height != Some(height) {
+                self.render_cache.viewport.applied_git_panel_height = Some(height);
+                changed |= self.dispatch_kernel(KernelAction::GitSetViewHeight {
+                    height: height as usize,
+                });
+            }
+        }
+
+        if let Some(height) = self.render_cache.viewport.call_hierarchy_panel_height {
+            if self
+                .render_cache
+                .viewport
+                .applied_call_hierarchy_panel_height
+                != Some(height)
+            {
+                self.render_cache
+                    .viewport
+                    .applied_call_hierarchy_panel_height = Some(height);
+                changed |= self.dispatch_kernel(KernelAction::CallHierarchySetViewHeight {
+                    height: height as usize,
+                });
+            }
+        }
+
         changed
     }
 }
@@ -1,6 +1,9 @@
 use super::super::util::centered_rect;
 use super::super::Workbench;
-use crate::kernel::{OverlayKind, ProblemSeverity, SearchResultItem};
+use crate::kernel::{
+    CallHierarchyDirection, GitDiffView, GitPanelRow, OverlayKind, ProblemSeverity, ReplaceSummary,
+    SearchFilterField, SearchResultItem,
+};
 use crate::ui::core::geom::{Pos, Rect as UiRect};
 use crate::ui::core::painter::{BorderKind, Painter};
 use crate::ui::core::style::{Mod, Style as UiStyle};
@@ -15,9 +18,10 @@ impl Workbench {
             return;
         };
 
-        // 高度取可用区域的 70%，但留出边框与标题。
+        // 高度取可用区域的 70%，但留出边框与标题；并排 diff 需要更宽。
         let height = (area.h.saturating_mul(70) / 100).max(6).min(area.h);
-        let popup = centered_rect(70, height, area);
+        let width_percent = if kind == OverlayKind::GitDiff { 90 } else { 70 };
+        let popup = centered_rect(width_percent, height, area);
         if popup.w < 3 || popup.h < 3 {
             self.frame_layout.overlay_area = None;
             return;
@@ -44,8 +48,21 @@ impl Workbench {
             return;
         }
 
-        // 标题行。
-        let title = overlay_title(kind);
+        // 标题行。workspace/symbol 仍在分批返回时在标题后挂上 `…`；diff 标出文件名与当前块序号。
+        let diff_title = (kind == OverlayKind::GitDiff)
+            .then(|| self.store.state().git.diff.as_ref().map(git_diff_title))
+            .flatten();
+        let title = if let Some(title) = diff_title.as_deref() {
+            title
+        } else if kind == OverlayKind::Symbols && self.store.state().symbols.is_streaming() {
+            "Symbols …"
+        } else if kind == OverlayKind::CallHierarchy
+            && self.store.state().call_hierarchy.direction() == CallHierarchyDirection::Outgoing
+        {
+            "Outgoing Calls"
+        } else {
+            overlay_title(kind)
+        };
         let title_style = UiStyle::default()
             .fg(self.theme.core.header_fg)
             .add_mod(Mod::BOLD);
@@ -67,6 +84,9 @@ impl Workbench {
             OverlayKind::Locations => self.paint_overlay_locations(painter, content),
             OverlayKind::Symbols => self.paint_overlay_symbols(painter, content),
             OverlayKind::Search => self.paint_overlay_search(painter, content),
+            OverlayKind::Git => self.paint_overlay_git(painter, content),
+            OverlayKind::GitDiff => self.paint_overlay_git_diff(painter, content),
+            OverlayKind::CallHierarchy => self.paint_overlay_call_hierarchy(painter, content),
         }
     }
 
@@ -350,59 +370,364 @@ impl Workbench {
         }
     }
 
+    fn paint_overlay_git(&mut self, painter: &mut Painter, area: UiRect) {
+        if area.is_empty() {
+            return;
+        }
+
+        let height = area.h as usize;
+        self.sync_git_view_height(area.h);
+
+        let git_state = &self.store.state().git;
+        let rows = git_state.rows();
+        let start = git_state.scroll_offset().min(rows.len());
+        let end = (start + height).min(rows.len());
+        let selected = git_state.selected_index().min(rows.len().saturating_sub(1));
+
+        for (row, (i, item)) in rows.iter().enumerate().take(end).skip(start).enumerate() {
+            let y = area.y.saturating_add(row.min(u16::MAX as usize) as u16);
+            if y >= area.bottom() {
+                break;
+            }
+            let is_selected = i == selected;
+            let marker = if is_selected { ">" } else { " " };
+            let marker_style = UiStyle::default().fg(if is_selected {
+                self.theme.core.focus_border
+            } else {
+                self.theme.core.palette_muted_fg
+            });
+
+            let row_clip = UiRect::new(area.x, y, area.w, 1);
+            let mut x = area.x;
+            painter.text_clipped(Pos::new(x, y), marker, marker_style, row_clip);
+            x = x.saturating_add(marker.width().min(u16::MAX as usize) as u16);
+            painter.text_clipped(Pos::new(x, y), " ", UiStyle::default(), row_clip);
+            x = x.saturating_add(1);
+
+            match *item {
+                GitPanelRow::StashesHeader => {
+                    let icon = if git_state.stashes_expanded() {
+                        "▼"
+                    } else {
+                        "▶"
+                    };
+                    let header = format!("{} Stashes ({})", icon, git_state.stashes.len());
+                    let header_style = UiStyle::default()
+                        .fg(self.theme.core.header_fg)
+                        .add_mod(Mod::BOLD);
+                    painter.text_clipped(Pos::new(x, y), header.as_str(), header_style, row_clip);
+                }
+                GitPanelRow::Stash(index) => {
+                    let Some(stash) = git_state.stashes.get(index) else {
+                        continue;
+                    };
+                    x = x.saturating_add(2);
+
+                    let label = format!("stash@{{{}}} ", stash.index);
+                    let label_style = UiStyle::default().fg(self.theme.core.accent_fg);
+                    painter.text_clipped(Pos::new(x, y), label.as_str(), label_style, row_clip);
+                    x = x.saturating_add(label.width().min(u16::MAX as usize) as u16);
+
+                    let message_style = UiStyle::default().fg(self.theme.core.palette_fg);
+                    painter.text_clipped(
+                        Pos::new(x, y),
+                        stash.message.as_str(),
+                        message_style,
+                        row_clip,
+                    );
+                    x = x.saturating_add(stash.message.width().min(u16::MAX as usize) as u16);
+
+                    let datetime = format!("  {}", stash.datetime);
+                    let datetime_style = UiStyle::default().fg(self.theme.core.palette_muted_fg);
+                    painter.text_clipped(
+                        Pos::new(x, y),
+                        datetime.as_str(),
+                        datetime_style,
+                        row_clip,
+                    );
+                }
+            }
+        }
+    }
+
+    /// 并排 diff：左半是暂存区版本、右半是当前缓冲区，删除行用红色、新增行用绿色，
+    /// 选中改动块的行首标 `>`。
+    fn paint_overlay_git_diff(&mut self, painter: &mut Painter, area: UiRect) {
+        if area.is_empty() {
+            return;
+        }
+        self.sync_git_view_height(area.h);
+
+        let Some(view) = self.store.state().git.diff.as_ref() else {
+            return;
+        };
+        if view.diff.hunks.is_empty() {
+            let style = UiStyle::default().fg(self.theme.core.palette_muted_fg);
+            painter.text_clipped(Pos::new(area.x, area.y), "No changes", style, area);
+            return;
+        }
+
+        let tab = " ".repeat(self.store.state().editor.config.tab_size.max(1) as usize);
+        let number_w = view
+            .original
+            .len_lines()
+            .max(view.current.len_lines())
+            .to_string()
+            .len();
+        let selected_rows = view
+            .selected_hunk()
+            .and_then(|i| view.diff.hunks.get(i))
+            .map(|hunk| hunk.rows.clone());
+        let muted = UiStyle::default().fg(self.theme.core.palette_muted_fg);
+        let plain = UiStyle::default().fg(self.theme.core.palette_fg);
+        let removed = UiStyle::default().fg(self.theme.core.diff_deleted_fg);
+        let added = UiStyle::default().fg(self.theme.core.diff_added_fg);
+
+        // 行首一列放选中标记，其余平分给两侧，中间一列画分隔线。
+        let side_w = area.w.saturating_sub(2) / 2;
+        let left_x = area.x.saturating_add(1);
+        let sep_x = left_x.saturating_add(side_w);
+        let right_x = sep_x.saturating_add(1);
+
+        let start = view.scroll_offset().min(view.diff.rows.len());
+        for (offset, (row_index, row)) in view
+            .diff
+            .rows
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(area.h as usize)
+            .enumerate()
+        {
+            let y = area.y.saturating_add(offset.min(u16::MAX as usize) as u16);
+            let row_clip = UiRect::new(area.x, y, area.w, 1);
+            if selected_rows
+                .as_ref()
+                .is_some_and(|rows| rows.contains(&row_index))
+            {
+                let marker_style = UiStyle::default().fg(self.theme.core.focus_border);
+                painter.text_clipped(Pos::new(area.x, y), ">", marker_style, row_clip);
+            }
+            painter.text_clipped(
+                Pos::new(sep_x, y),
+                "│",
+                UiStyle::default().fg(self.theme.core.separator),
+                row_clip,
+            );
+
+            let sides = [
+                (left_x, &view.original, row.old, removed),
+                (right_x, &view.current, row.new, added),
+            ];
+            for (x, rope, line, changed_style) in sides {
+                let Some(line) = line else {
+                    continue;
+                };
+                let clip = UiRect::new(x, y, side_w.min(area.right().saturating_sub(x)), 1);
+                let number = format!("{:>number_w$} ", line + 1);
+                painter.text_clipped(Pos::new(x, y), number.as_str(), muted, clip);
+                let text = rope
+                    .line(line)
+                    .to_string()
+                    .trim_end_matches(['\n', '\r'])
+                    .replace('\t', &tab);
+                let style = if row.changed { changed_style } else { plain };
+                let text_x = x.saturating_add(number.len().min(u16::MAX as usize) as u16);
+                painter.text_clipped(Pos::new(text_x, y), text.as_str(), style, clip);
+            }
+        }
+    }
+
+    fn paint_overlay_call_hierarchy(&mut self, painter: &mut Painter, area: UiRect) {
+        if area.is_empty() {
+            return;
+        }
+
+        let height = area.h as usize;
+        self.sync_call_hierarchy_view_height(area.h);
+
+        let tree = &self.store.state().call_hierarchy;
+        let rows = tree.rows();
+        if rows.is_empty() {
+            let style = UiStyle::default().fg(self.theme.core.palette_muted_fg);
+            painter.text_clipped(Pos::new(area.x, area.y), "No call hierarchy", style, area);
+            return;
+        }
+
+        let start = tree.scroll_offset().min(rows.len());
+        let end = (start + height).min(rows.len());
+        let selected = tree.selected_index().min(rows.len().saturating_sub(1));
+
+        for (row, (i, &item_index)) in rows.iter().enumerate().take(end).skip(start).enumerate() {
+            let y = area.y.saturating_add(row.min(u16::MAX as usize) as u16);
+            if y >= area.bottom() {
+                break;
+            }
+            let Some(node) = tree.node(item_index) else {
+                continue;
+            };
+            let is_selected = i == selected;
+            let marker = if is_selected { ">" } else { " " };
+            let marker_style = UiStyle::default().fg(if is_selected {
+                self.theme.core.focus_border
+            } else {
+                self.theme.core.palette_muted_fg
+            });
+
+            let row_clip = UiRect::new(area.x, y, area.w, 1);
+            let mut x = area.x;
+            painter.text_clipped(Pos::new(x, y), marker, marker_style, row_clip);
+            x = x.saturating_add(marker.width().min(u16::MAX as usize) as u16);
+            painter.text_clipped(Pos::new(x, y), " ", UiStyle::default(), row_clip);
+            x = x.saturating_add(1);
+
+            let indent = "  ".repeat(node.depth.min(32));
+            x = x.saturating_add(indent.width().min(u16::MAX as usize) as u16);
+
+            // 未请求过的节点一律显示可展开；请求返回空列表后才知道是叶子。
+            let icon = if node.loading {
+                "…"
+            } else if node.children.as_ref().is_some_and(|c| c.is_empty()) {
+                "·"
+            } else if node.expanded {
+                "▼"
+            } else {
+                "▶"
+            };
+            let icon_style = UiStyle::default().fg(self.theme.core.palette_muted_fg);
+            painter.text_clipped(Pos::new(x, y), icon, icon_style, row_clip);
+            x = x.saturating_add(2);
+
+            let name_style = UiStyle::default().fg(self.theme.core.palette_fg);
+            let name = node.item.name.as_str();
+            painter.text_clipped(Pos::new(x, y), name, name_style, row_clip);
+            x = x.saturating_add(name.width().min(u16::MAX as usize) as u16);
+
+            if let Some(detail) = node.item.detail.as_deref().filter(|s| !s.is_empty()) {
+                let detail = format!(" {}", detail);
+                let detail_style = UiStyle::default().fg(self.theme.core.palette_muted_fg);
+                painter.text_clipped(Pos::new(x, y), detail.as_str(), detail_style, row_clip);
+                x = x.saturating_add(detail.width().min(u16::MAX as usize) as u16);
+            }
+
+            let file_name = node
+                .item
+                .path
+                .file_name()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| node.item.path.to_string_lossy().to_string());
+            let file_info = format!("  {}:{}", file_name, node.item.line.saturating_add(1));
+            let file_style = UiStyle::default().fg(self.theme.core.accent_fg);
+            painter.text_clipped(Pos::new(x, y), file_info.as_str(), file_style, row_clip);
+        }
+    }
+
     fn paint_overlay_search(&mut self, painter: &mut Painter, area: UiRect) {
         if area.is_empty() {
             return;
         }
 
-        // 顶部 query 行（telescope 风格），下方层级结果。
-        let query_h = 1.min(area.h);
-        let query_area = UiRect::new(area.x, area.y, area.w, query_h);
-        let summary_h = 1.min(area.h.saturating_sub(query_h));
-        let summary_area = UiRect::new(area.x, area.y.saturating_add(query_h), area.w, summary_h);
+        // 顶部 query 行（telescope 风格），展开替换时其下为替换行，展开过滤时再下为
+        // include / exclude 两行，再下方层级结果。
+        let search = &self.store.state().search;
+        let replace_focused = search.replace_visible && search.replace_focused;
+        let filter_focused = search.filter_focused.filter(|_| search.filters_visible);
+        let mut rows = vec![(
+            "/ ",
+            search.query.clone(),
+            !replace_focused && filter_focused.is_none(),
+        )];
+        if search.replace_visible {
+            rows.push(("→ ", search.replacement.clone(), replace_focused));
+        }
+        if search.filters_visible {
+            rows.push((
+                "+ ",
+                search.include_glob.clone(),
+                filter_focused == Some(SearchFilterField::Include),
+            ));
+            rows.push((
+                "- ",
+                search.exclude_glob.clone(),
+                filter_focused == Some(SearchFilterField::Exclude),
+            ));
+        }
+
+        let header_h = (search.input_rows().min(u16::MAX as usize) as u16).min(area.h);
+        let summary_h = 1.min(area.h.saturating_sub(header_h));
+        let summary_area = UiRect::new(area.x, area.y.saturating_add(header_h), area.w, summary_h);
         let list_area = UiRect::new(
             area.x,
-            area.y.saturating_add(query_h + summary_h),
+            area.y.saturating_add(header_h + summary_h),
             area.w,
-            area.h.saturating_sub(query_h + summary_h),
+            area.h.saturating_sub(header_h + summary_h),
         );
 
-        let prompt = "/ ";
-        let query = self.store.state().search.query.clone();
-        let prompt_style = UiStyle::default().fg(self.theme.core.accent_fg);
-        painter.text_clipped(
-            Pos::new(query_area.x, query_area.y),
-            prompt,
-            prompt_style,
-            query_area,
-        );
-        painter.text_clipped(
-            Pos::new(
-                query_area.x.saturating_add(prompt.width() as u16),
-                query_area.y,
-            ),
-            query.as_str(),
-            UiStyle::default().fg(self.theme.core.palette_fg),
-            query_area,
-        );
+        // `[⚙]` 按钮贴在 query 行右端，查询文本在它左侧截断。
+        let button = "[⚙]";
+        let button_w = (button.width().min(u16::MAX as usize) as u16).min(area.w);
+        let button_area = UiRect::new(area.right().saturating_sub(button_w), area.y, button_w, 1);
+        for (i, (prompt, text, focused)) in rows.iter().enumerate().take(header_h as usize) {
+            let y = area.y.saturating_add(i as u16);
+            let w = if i == 0 {
+                area.w.saturating_sub(button_w)
+            } else {
+                area.w
+            };
+            self.paint_search_input_row(
+                painter,
+                UiRect::new(area.x, y, w, 1),
+                prompt,
+                text,
+                *focused,
+            );
+        }
+        self.frame_layout.search_filters_button_area = None;
+        if header_h > 0 && !button_area.is_empty() {
+            let button_style =
+                UiStyle::default().fg(if self.store.state().search.filters_visible {
+                    self.theme.core.accent_fg
+                } else {
+                    self.theme.core.palette_muted_fg
+                });
+            painter.text_clipped(
+                Pos::new(button_area.x, button_area.y),
+                button,
+                button_style,
+                button_area,
+            );
+            self.frame_layout.search_filters_button_area = Some(button_area);
+        }
 
         self.sync_search_view_height(list_area.h);
         let snapshot = self.store.state().search.snapshot();
 
-        let summary = if snapshot.searching {
-            format!(
-                "Searching... {} files ({} with matches)",
-                snapshot.files_searched, snapshot.files_with_matches
+        let summary = if let Some(replaced) = snapshot.replace_summary {
+            replace_summary_text(replaced, snapshot.searching)
+        } else if snapshot.searching {
+            search_progress_summary(
+                snapshot.files_searched,
+                snapshot.files_total,
+                snapshot.total_matches,
             )
         } else if let Some(err) = snapshot.last_error {
             format!("Error: {}", err)
         } else if snapshot.total_matches > 0 {
-            format!(
+            let results = format!(
                 "{} results in {} files",
                 snapshot.total_matches, snapshot.file_count
+            );
+            with_binary_skip_summary(
+                results,
+                snapshot.files_searched,
+                snapshot.binary_files_skipped,
             )
         } else if !snapshot.search_text.is_empty() {
-            "No results".to_string()
+            with_binary_skip_summary(
+                "No results".to_string(),
+                snapshot.files_searched,
+                snapshot.binary_files_skipped,
+            )
         } else {
             "Type to search".to_string()
         };
@@ -519,6 +844,42 @@ impl Workbench {
             }
         }
     }
+
+    /// 查询 / 替换输入行；当前接收键入的一行用强调色提示符。
+    fn paint_search_input_row(
+        &self,
+        painter: &mut Painter,
+        area: UiRect,
+        prompt: &str,
+        text: &str,
+        focused: bool,
+    ) {
+        let prompt_style = UiStyle::default().fg(if focused {
+            self.theme.core.accent_fg
+        } else {
+            self.theme.core.palette_muted_fg
+        });
+        painter.text_clipped(Pos::new(area.x, area.y), prompt, prompt_style, area);
+        painter.text_clipped(
+            Pos::new(area.x.saturating_add(prompt.width() as u16), area.y),
+            text,
+            UiStyle::default().fg(self.theme.core.palette_fg),
+            area,
+        );
+    }
+}
+
+/// `Diff: main.rs  (2/5)`：文件名与选中块序号。
+fn git_diff_title(view: &GitDiffView) -> String {
+    let name = view
+        .path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| view.path.display().to_string());
+    match view.selected_hunk() {
+        Some(i) => format!("Diff: {name}  ({}/{})", i + 1, view.diff.hunks.len()),
+        None => format!("Diff: {name}"),
+    }
 }
 
 fn overlay_title(kind: OverlayKind) -> &'static str {
@@ -528,6 +889,9 @@ fn overlay_title(kind: OverlayKind) -> &'static str {
         OverlayKind::CodeActions => "Code Actions",
         OverlayKind::Locations => "References",
         OverlayKind::Symbols => "Symbols",
+        OverlayKind::Git => "Git",
+        OverlayKind::GitDiff => "Diff",
+        OverlayKind::CallHierarchy => "Incoming Calls",
     }
 }
 
@@ -562,3 +926,57 @@ fn symbol_kind_label(kind: u32) -> &'static str {
         _ => "?",
     }
 }
+
+const SEARCH_PROGRESS_BAR_WIDTH: usize = 12;
+
+/// `▕████▌░░░░░░▏ Searching... 1234/5678 files (42 matches)`；总数未知时只显示已搜文件数。
+fn search_progress_summary(files_searched: usize, files_total: usize, matches: usize) -> String {
+    if files_total == 0 {
+        return format!("Searching... {files_searched} files ({matches} matches)");
+    }
+    format!(
+        "▕{}▏ Searching... {files_searched}/{files_total} files ({matches} matches)",
+        block_progress_bar(files_searched, files_total, SEARCH_PROGRESS_BAR_WIDTH)
+    )
+}
+
+/// `Replaced 12 occurrences in 3 files · 1 files skipped`；替换进行中前缀为 `Replacing...`。
+fn replace_summary_text(summary: ReplaceSummary, running: bool) -> String {
+    let verb = if running { "Replacing..." } else { "Replaced" };
+    let text = format!(
+        "{verb} {} occurrences in {} files",
+        summary.occurrences, summary.files
+    );
+    if summary.failures == 0 {
+        return text;
+    }
+    format!("{text} · {} files skipped", summary.failures)
+}
+
+/// 有二进制文件被跳过时追加 `· Searched 1234 files, skipped 5 binary files`。
+fn with_binary_skip_summary(summary: String, files_searched: usize, skipped: usize) -> String {
+    if skipped == 0 {
+        return summary;
+    }
+    format!("{summary} · Searched {files_searched} files, skipped {skipped} binary files")
+}
+
+/// 用八分块字符画进度条，精度为 1/8 格。
+fn block_progress_bar(done: usize, total: usize, width: usize) -> String {
+    const PARTIAL: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+    let eighths = (done.min(total) * width * 8)
+        .checked_div(total)
+        .unwrap_or(0);
+    let full = eighths / 8;
+    let mut bar = "█".repeat(full);
+    if full < width {
+        let rem = eighths % 8;
+        if rem > 0 {
+            bar.push(PARTIAL[rem]);
+        } else {
+            bar.push('░');
+        }
+        bar.extend(std::iter::repeat_n('░', width - full - 1));
+    }
+    bar
+}
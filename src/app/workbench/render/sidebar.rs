@@ -106,7 +106,12 @@ impl Workbench {
                 rows: &explorer_state.rows,
                 selected_id: explorer_state.selected(),
                 active_open_file_id,
+                dragging_id: explorer_state.drag.map(|drag| drag.dragging_node),
+                drop_target_id: explorer_state.drag.and_then(|drag| drag.hover_target),
+                drop_error: explorer_state.drop_error.as_deref(),
                 scroll_offset: explorer_state.scroll_offset,
+                large_file_bytes: (state.editor.config.large_file_threshold_mb as u64)
+                    .saturating_mul(1024 * 1024),
                 theme: &self.theme.core,
             },
         );
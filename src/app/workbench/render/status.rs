@@ -1,9 +1,14 @@
 use super::super::Workbench;
-use crate::kernel::editor::DiskState;
+use crate::core::Command;
+use crate::kernel::editor::{DiskState, EditorMode};
+use crate::kernel::services::adapters::settings::format_keybinding;
+use crate::kernel::services::adapters::{perf, KeybindingContext, KeybindingService};
+use crate::kernel::services::ports::{LspConnectionStatus, StatusBarSegment, TabType};
 use crate::kernel::FocusTarget;
 use crate::ui::core::geom::{Pos, Rect as UiRect};
 use crate::ui::core::painter::Painter;
 use crate::ui::core::style::{Mod, Style as UiStyle};
+use std::time::Instant;
 use unicode_width::UnicodeWidthStr;
 
 impl Workbench {
@@ -16,75 +21,216 @@ impl Workbench {
         }
     }
 
-    /// demo 风格状态栏：左侧模式块 + `focus · 文件名` + 右侧 `行:列`。
-    /// 模式块暂为静态 INSERT（绿）——模态编辑落地后变活。命令行激活时整条状态栏
+    /// `reloadFromDisk` 绑定的按键（编辑区优先于全局），用于「Reload?」提示。
+    fn reload_key_label(&self) -> Option<String> {
+        let keybindings = self.kernel_services.get::<KeybindingService>()?;
+        [KeybindingContext::Editor, KeybindingContext::Global]
+            .into_iter()
+            .find_map(|context| {
+                keybindings
+                    .bindings(context)
+                    .iter()
+                    .find(|(_, command)| **command == Command::ReloadFromDisk)
+                    .map(|(key, _)| format_keybinding(key))
+            })
+    }
+
+    /// 状态栏按 `ui.status_bar.segments` 从左到右排列，第一个 `Spacer` 之后的段靠右（淡色）；
+    /// 未配置时为 demo 风格：模式块 + `focus · 文件名` + 右侧 `行:列`。
+    /// 模式块随 pane 的 Vim 模式变化（未开 `vim_mode` 时恒为 INSERT）。命令行激活时整条状态栏
     /// 由 `:` 命令行覆盖（见 layout），与 demo 的「命令模式状态栏变命令行」一致。
-    pub(super) fn paint_status(&self, painter: &mut Painter, area: UiRect) {
+    /// 返回 `Indentation` 段所占的矩形，供鼠标点击切换 tab 宽度。
+    pub(super) fn paint_status(&self, painter: &mut Painter, area: UiRect) -> Option<UiRect> {
         if area.is_empty() {
-            return;
+            return None;
+        }
+
+        let base = UiStyle::default()
+            .bg(self.theme.core.statusbar_bg)
+            .fg(self.theme.core.palette_fg);
+        painter.fill_rect(area, base);
+
+        let segments = self.status_bar.segments();
+        let (left, right) = match segments
+            .iter()
+            .position(|segment| *segment == StatusBarSegment::Spacer)
+        {
+            Some(i) => (&segments[..i], &segments[i + 1..]),
+            None => (segments, &[][..]),
+        };
+
+        let mut indentation_area = None;
+        let mut x = area.x;
+        for segment in left {
+            let Some((text, style)) = self.status_segment(segment, base) else {
+                continue;
+            };
+            let w = text.width().min(u16::MAX as usize) as u16;
+            if *segment == StatusBarSegment::Indentation {
+                indentation_area = Some(UiRect::new(x, area.y, w, 1));
+            }
+            painter.text_clipped(Pos::new(x, area.y), text.as_str(), style, area);
+            x = x.saturating_add(w);
+        }
+
+        // 右：`--perf` 时的帧耗时摘要 + 其余段。
+        let muted = UiStyle::default()
+            .bg(self.theme.core.statusbar_bg)
+            .fg(self.theme.core.palette_muted_fg);
+        let mut right_parts = Vec::new();
+        if perf::frame_stats_visible() {
+            right_parts.push((None, format!(" {} ", perf::frame_stats().summary()), muted));
         }
+        right_parts.extend(right.iter().filter_map(|segment| {
+            self.status_segment(segment, muted)
+                .map(|(text, style)| (Some(segment), text, style))
+        }));
+        let right_w: usize = right_parts.iter().map(|(_, text, _)| text.width()).sum();
+        let mut rx = area
+            .right()
+            .saturating_sub(right_w.min(u16::MAX as usize) as u16);
+        for (segment, text, style) in right_parts {
+            let w = text.width().min(u16::MAX as usize) as u16;
+            if segment == Some(&StatusBarSegment::Indentation) {
+                indentation_area = Some(UiRect::new(rx, area.y, w, 1));
+            }
+            painter.text_clipped(Pos::new(rx, area.y), text.as_str(), style, area);
+            rx = rx.saturating_add(w);
+        }
+        indentation_area.filter(|rect| !rect.is_empty())
+    }
 
-        let active_pane = self.store.state().ui.editor_layout.active_pane;
-        let (file_name, dirty, disk_indicator, cursor) = self
-            .store
-            .state()
+    /// 单个段的文本与样式；当前没有内容可显示（如无活动文件）时为 `None`。
+    fn status_segment(
+        &self,
+        segment: &StatusBarSegment,
+        style: UiStyle,
+    ) -> Option<(String, UiStyle)> {
+        let state = self.store.state();
+        let active_pane = state.ui.editor_layout.active_pane;
+        let tab = state
             .editor
             .pane(active_pane)
-            .and_then(|pane| pane.active_tab())
-            .map(|tab| {
-                let (row, col) = tab.buffer.cursor();
+            .and_then(|pane| pane.active_tab());
+        match segment {
+            StatusBarSegment::Mode => {
+                let mode = state
+                    .editor
+                    .pane(active_pane)
+                    .map(|pane| pane.mode)
+                    .unwrap_or_default();
+                let chip_bg = match mode {
+                    EditorMode::Insert => self.theme.core.mode_insert_bg,
+                    EditorMode::Normal => self.theme.core.mode_normal_bg,
+                    EditorMode::Visual | EditorMode::VisualLine => self.theme.core.mode_visual_bg,
+                };
+                let chip_style = UiStyle::default()
+                    .bg(chip_bg)
+                    .fg(self.theme.core.mode_text_fg)
+                    .add_mod(Mod::BOLD);
+                Some((format!(" {} ", mode.label()), chip_style))
+            }
+            StatusBarSegment::FilePath => {
+                let Some(tab) = tab else {
+                    return Some((format!("  {}  ·  No file ", self.focus_label()), style));
+                };
                 let dirty = if tab.dirty { " [+]" } else { "" };
-                let disk = match &tab.disk_state {
-                    DiskState::ConflictExternalModified => " [CONFLICT]",
-                    DiskState::MissingOnDisk => " [DELETED]",
-                    DiskState::ReloadedFromDisk { .. } => " [RELOADED]",
-                    DiskState::InSync => "",
+                let mut disk = match &tab.disk_state {
+                    DiskState::ChangedOnDisk => match self.reload_key_label() {
+                        Some(key) => format!(" [CHANGED · Reload? {key}]"),
+                        None => " [CHANGED · Reload?]".to_string(),
+                    },
+                    DiskState::ConflictExternalModified => " [CONFLICT]".to_string(),
+                    DiskState::MissingOnDisk => " [DELETED]".to_string(),
+                    DiskState::ReloadedFromDisk { .. } => " [RELOADED]".to_string(),
+                    DiskState::InSync => String::new(),
                 };
+                if let Some(partial) = tab.partial_load {
+                    let saving = if partial.save_when_loaded {
+                        " · saving after load"
+                    } else {
+                        ""
+                    };
+                    disk.push_str(&format!(
+                        " [PARTIAL {}/{}{saving}]",
+                        crate::models::format_file_size(partial.loaded_bytes),
+                        crate::models::format_file_size(partial.file_size)
+                    ));
+                }
                 let name = tab
                     .path
                     .as_ref()
                     .and_then(|p| p.file_name())
                     .map(|s| s.to_string_lossy().to_string())
                     .unwrap_or_else(|| tab.title.clone());
-                (name, dirty, disk, Some((row, col)))
-            })
-            .unwrap_or_else(|| ("No file".to_string(), "", "", None));
-
-        let base = UiStyle::default()
-            .bg(self.theme.core.statusbar_bg)
-            .fg(self.theme.core.palette_fg);
-        painter.fill_rect(area, base);
-
-        // 左：模式块。
-        let chip = " INSERT ";
-        let chip_style = UiStyle::default()
-            .bg(self.theme.core.mode_insert_bg)
-            .fg(self.theme.core.mode_text_fg)
-            .add_mod(Mod::BOLD);
-        painter.text_clipped(Pos::new(area.x, area.y), chip, chip_style, area);
-        let x = area
-            .x
-            .saturating_add(chip.width().min(u16::MAX as usize) as u16);
-
-        // 中：focus · 文件名。
-        let mid = format!(
-            "  {}  ·  {}{}{} ",
-            self.focus_label(),
-            file_name,
-            dirty,
-            disk_indicator
-        );
-        painter.text_clipped(Pos::new(x, area.y), mid.as_str(), base, area);
-
-        // 右：行:列。
-        if let Some((row, col)) = cursor {
-            let right = format!(" {}:{} ", row + 1, col + 1);
-            let right_w = right.width().min(u16::MAX as usize) as u16;
-            let rx = area.right().saturating_sub(right_w);
-            let muted = UiStyle::default()
-                .bg(self.theme.core.statusbar_bg)
-                .fg(self.theme.core.palette_muted_fg);
-            painter.text_clipped(Pos::new(rx, area.y), right.as_str(), muted, area);
+                Some((
+                    format!("  {}  ·  {}{}{} ", self.focus_label(), name, dirty, disk),
+                    style,
+                ))
+            }
+            StatusBarSegment::CursorPosition => {
+                let tab = tab?;
+                let (row, col) = tab.buffer.cursor();
+                let config = state.editor_config_for_pane(active_pane);
+                let mut text = String::new();
+                if let Some(percent) = (config.horiz_scroll_indicator_enabled
+                    && tab.viewport.horiz_offset > 0)
+                    .then(|| tab.horiz_scroll_percent(config.tab_size))
+                    .flatten()
+                {
+                    text.push_str(&format!(" \u{27f5} {percent}% "));
+                }
+                text.push_str(&format!(" {}:{} ", row + 1, col + 1));
+                Some((text, style))
+            }
+            StatusBarSegment::Language => {
+                let language = tab?.language()?;
+                Some((format!(" {} ", language.display_name()), style))
+            }
+            StatusBarSegment::LspStatus => {
+                let server = tab?.language()?.server_kind()?;
+                let connection = state
+                    .lsp
+                    .connection
+                    .iter()
+                    .find(|(key, _)| key.server == server)
+                    .map(|(_, status)| *status);
+                let text = match connection {
+                    Some(status @ LspConnectionStatus::Reconnecting { .. }) => {
+                        let secs = status.retry_in_secs(Instant::now()).unwrap_or_default();
+                        format!(" LSP: Reconnecting\u{2026} {secs}s ")
+                    }
+                    Some(LspConnectionStatus::Failed) => " LSP: Failed ".to_string(),
+                    None => {
+                        let ready = state
+                            .lsp
+                            .server_capabilities
+                            .keys()
+                            .any(|key| key.server == server);
+                        format!(" LSP {} ", if ready { "●" } else { "○" })
+                    }
+                };
+                Some((text, style))
+            }
+            StatusBarSegment::GitBranch => {
+                let branch = state.git.branches.iter().find(|branch| branch.current)?;
+                Some((format!(" \u{2387} {} ", branch.name), style))
+            }
+            StatusBarSegment::Encoding => tab.map(|_| (" UTF-8 ".to_string(), style)),
+            StatusBarSegment::LineEnding => {
+                Some((format!(" {} ", tab?.line_ending.label()), style))
+            }
+            StatusBarSegment::Indentation => {
+                let tab = tab?;
+                let tab_size = state.editor_config_for_pane(active_pane).tab_size;
+                let text = match tab.tab_type {
+                    TabType::Spaces => format!(" Spaces: {tab_size} "),
+                    TabType::Tabs => format!(" Tab Width: {tab_size} "),
+                };
+                Some((text, style))
+            }
+            StatusBarSegment::Spacer => Some((" ".to_string(), style)),
+            StatusBarSegment::Custom(text) => Some((text.clone(), style)),
         }
     }
 }
@@ -1,52 +1,60 @@
-//! 把 `Settings` 解析为键位 / 编辑器 / LSP 覆盖配置的纯函数。`Workbench::new`（首次注册
+//! 把 `Settings` 解析为键位 / 编辑器 / LSP 覆盖 / 侧边栏宽度 / 状态栏配置的纯函数。`Workbench::new`（首次注册
 //! 服务）与 `tick::reload_settings`（热重载）共消费此结果，各自保留分歧副作用（前者注册
-//! 新服务 + env override 优先，后者 dispatch + reconfigure），避免两份解析逐字漂移。
+//! 新服务 + env override 优先，后者 dispatch，由 `Effect::ReconfigureLsp` 重设 LSP），避免两份解析逐字漂移。
 
-use crate::core::Command;
 use crate::kernel::services::adapters::lsp::LspServerCommandOverride;
-use crate::kernel::services::adapters::settings::parse_keybinding;
-use crate::kernel::services::adapters::{KeybindingContext, KeybindingService};
-use crate::kernel::services::ports::{EditorConfig, LspServerKind, Settings};
+use crate::kernel::services::adapters::KeybindingService;
+use crate::kernel::services::ports::{
+    EditorConfig, LspServerKind, LspSettings, Settings, StatusBarConfig,
+};
 use rustc_hash::FxHashMap;
 
-/// `Settings` 解析结果：键位绑定、编辑器配置、全局 LSP 覆盖、按 server 覆盖。
+/// 全局 LSP 覆盖：命令、参数、initialize options。
+pub(super) type LspGlobalOverride = (String, Vec<String>, Option<serde_json::Value>);
+pub(super) type LspServerOverrides = FxHashMap<LspServerKind, LspServerCommandOverride>;
+
+/// `Settings` 解析结果：键位绑定、编辑器配置、全局 LSP 覆盖、按 server 覆盖（及其原始 `settings.lsp`）、
+/// 侧边栏宽度、状态栏布局。
 pub(super) struct ParsedSettings {
     pub keybindings: KeybindingService,
     pub editor_config: EditorConfig,
-    pub lsp_settings_override: Option<(String, Vec<String>, Option<serde_json::Value>)>,
-    pub lsp_server_overrides: FxHashMap<LspServerKind, LspServerCommandOverride>,
+    pub lsp_settings_override: Option<LspGlobalOverride>,
+    pub lsp_server_overrides: LspServerOverrides,
+    pub lsp: LspSettings,
+    pub sidebar_width: Option<u16>,
+    pub status_bar: StatusBarConfig,
 }
 
 pub(super) fn parse_settings(settings: Settings) -> ParsedSettings {
     let mut keybindings = KeybindingService::new();
-    let mut lsp_settings_override = None;
-    let mut lsp_server_overrides: FxHashMap<LspServerKind, LspServerCommandOverride> =
-        FxHashMap::default();
+    keybindings.import(&settings.keybindings);
+    let (lsp_settings_override, lsp_server_overrides) = parse_lsp_settings(&settings.lsp);
 
-    for rule in settings.keybindings {
-        if let Some(key) = parse_keybinding(&rule.key) {
-            let context = rule
-                .context
-                .as_deref()
-                .and_then(KeybindingContext::parse)
-                .unwrap_or(KeybindingContext::Global);
-            if rule.command.trim().is_empty() {
-                let _ = keybindings.unbind(context, &key);
-            } else {
-                keybindings.bind(context, key, Command::from_name(&rule.command));
-            }
-        }
+    ParsedSettings {
+        keybindings,
+        editor_config: settings.editor,
+        lsp_settings_override,
+        lsp_server_overrides,
+        lsp: settings.lsp,
+        sidebar_width: settings.ui.sidebar_width.filter(|width| *width > 0),
+        status_bar: settings.ui.status_bar,
     }
+}
 
-    if let Some(command) = settings
-        .lsp
+/// `settings.lsp` → 全局命令覆盖 + 按 server 覆盖；热重载的 `Effect::ReconfigureLsp` 也走这里。
+pub(super) fn parse_lsp_settings(
+    lsp: &LspSettings,
+) -> (Option<LspGlobalOverride>, LspServerOverrides) {
+    let mut lsp_settings_override = None;
+    let mut lsp_server_overrides = LspServerOverrides::default();
+
+    if let Some(command) = lsp
         .command
         .as_deref()
         .map(str::trim)
         .filter(|v| !v.is_empty())
     {
-        let args = settings
-            .lsp
+        let args = lsp
             .args
             .iter()
             .map(|s| s.trim())
@@ -56,7 +64,7 @@ pub(super) fn parse_settings(settings: Settings) -> ParsedSettings {
         lsp_settings_override = Some((command.to_string(), args, None));
     }
 
-    for (name, cfg) in &settings.lsp.servers {
+    for (name, cfg) in &lsp.servers {
         let Some(kind) = LspServerKind::from_settings_key(name) else {
             continue;
         };
@@ -87,12 +95,7 @@ pub(super) fn parse_settings(settings: Settings) -> ParsedSettings {
         }
     }
 
-    ParsedSettings {
-        keybindings,
-        editor_config: settings.editor,
-        lsp_settings_override,
-        lsp_server_overrides,
-    }
+    (lsp_settings_override, lsp_server_overrides)
 }
 
 #[cfg(test)]
@@ -29,5 +29,9 @@ pub(in crate::app::workbench) struct FrameLayout {
     pub(in crate::app::workbench) sidebar_area: Option<Rect>,
     pub(in crate::app::workbench) sidebar_container_area: Option<Rect>,
     pub(in crate::app::workbench) overlay_area: Option<Rect>,
+    /// 搜索浮层 query 行末尾的 `[⚙]`，点击展开 / 收起过滤行。
+    pub(in crate::app::workbench) search_filters_button_area: Option<Rect>,
+    /// 状态栏 `Indentation` 段的位置，点击切换 tab 宽度。
+    pub(in crate::app::workbench) status_indentation_area: Option<Rect>,
     pub(in crate::app::workbench) editor: EditorFrameLayout,
 }
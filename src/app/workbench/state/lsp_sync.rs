@@ -4,11 +4,12 @@ use rustc_hash::{FxHashMap, FxHashSet};
 use std::path::PathBuf;
 use std::time::Instant;
 
-/// 三类按需 LSP 请求的去抖截止时间。到点后由 tick 触发一次请求。
+/// 按需 LSP 请求的去抖截止时间。到点后由 tick 触发一次请求。
 #[derive(Debug, Default)]
 pub(in crate::app::workbench) struct LspDebounceState {
     pub(in crate::app::workbench) inlay_hints: Option<Instant>,
     pub(in crate::app::workbench) folding_range: Option<Instant>,
+    pub(in crate::app::workbench) document_link: Option<Instant>,
 }
 
 /// LSP 与文件监听共享的同步状态。记录已打开路径，避免对同一路径重复 open/close。
@@ -19,4 +20,8 @@ pub(in crate::app::workbench) struct LspSyncState {
     pub(in crate::app::workbench) open_paths: FxHashSet<PathBuf>,
     pub(in crate::app::workbench) open_paths_version: u64,
     pub(in crate::app::workbench) file_watcher_open_paths_version: u64,
+    /// 上一次空闲 documentHighlight 的 `(path, cursor, edit_version)`，光标不动时不重复请求。
+    pub(in crate::app::workbench) last_document_highlight: Option<(PathBuf, (usize, usize), u64)>,
+    /// 上一帧状态栏显示的重连倒数（各服务端的整秒数），变了才需要重绘。
+    pub(in crate::app::workbench) reconnect_countdown: Vec<u64>,
 }
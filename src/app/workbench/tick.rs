@@ -3,14 +3,14 @@ use super::settings_parse::{parse_settings, ParsedSettings};
 use super::Workbench;
 use crate::core::Command;
 use crate::kernel::services::adapters::{
-    ConfigService, FileWatchEvent, KeybindingService, LspService,
+    ClipboardService, ConfigService, FileWatchEvent, KeybindingService, LspService,
 };
 use crate::kernel::services::ports::lsp::column_for_chars;
 use crate::kernel::services::ports::{
     GlobalSearchMessage, LspPosition, LspPositionEncoding, SearchMessage,
 };
 use crate::kernel::services::KernelMessagePayload;
-use crate::kernel::{Action as KernelAction, EditorAction, FocusTarget};
+use crate::kernel::{Action as KernelAction, EditorAction, Effect as KernelEffect, FocusTarget};
 use std::sync::mpsc;
 use std::time::Instant;
 
@@ -23,12 +23,18 @@ impl Workbench {
         changed |= self.poll_global_search();
         changed |= self.poll_kernel_bus();
         changed |= self.poll_settings();
-        self.store.tick();
+        changed |= self.store.tick();
+        changed |= self.poll_lsp_restarts();
         changed |= self.poll_lsp_debounce(LspDebouncePipeline::InlayHints);
         changed |= self.poll_lsp_debounce(LspDebouncePipeline::FoldingRange);
+        changed |= self.poll_lsp_debounce(LspDebouncePipeline::DocumentLink);
         changed |= self.poll_idle_hover();
+        changed |= self.poll_idle_document_highlight();
+        changed |= self.poll_idle_breadcrumbs();
         changed |= self.poll_definition_jump_highlight();
+        self.poll_git_blame();
         self.poll_completion_rank_save();
+        self.poll_session_save();
 
         changed
     }
@@ -269,6 +275,21 @@ impl Workbench {
         let pane = target.pane;
         let pos = (target.row, target.col);
         let anchor = Some(target.anchor);
+
+        // 诊断提示不要求停在标识符上，也不依赖服务端的 hover 能力。
+        let mut changed = false;
+        let diagnostic_target = (pane, target.row, target.col);
+        if self.ui.hover_popup.last_diagnostic_target != Some(diagnostic_target) {
+            self.ui.hover_popup.last_diagnostic_target = Some(diagnostic_target);
+            if self.dispatch_kernel(KernelAction::ShowDiagnosticHover {
+                pane,
+                row: target.row,
+                col: target.col,
+            }) {
+                self.ui.hover_popup.last_anchor = anchor;
+                changed = true;
+            }
+        }
         let Some(tab) = self
             .store
             .state()
@@ -276,14 +297,14 @@ impl Workbench {
             .pane(pane)
             .and_then(|pane| pane.active_tab())
         else {
-            return false;
+            return changed;
         };
 
         let Some(path) = tab.path.as_ref() else {
-            return false;
+            return changed;
         };
         if !crate::kernel::lsp_registry::is_lsp_source_path(path) {
-            return false;
+            return changed;
         }
 
         // Resolve server capabilities/encoding for this path.
@@ -294,7 +315,7 @@ impl Workbench {
         .and_then(|(_, key)| self.store.state().lsp.server_capabilities.get(&key));
         let supports_hover = caps.map(|c| c.hover).unwrap_or(true);
         if !supports_hover {
-            return false;
+            return changed;
         }
         let encoding = caps
             .map(|c| c.position_encoding)
@@ -306,18 +327,18 @@ impl Workbench {
         let buf_pos = (row, col);
 
         let Some(buf_pos) = tab.identifier_pos_at(buf_pos) else {
-            return false;
+            return changed;
         };
 
         let char_offset = tab.buffer.pos_to_char(buf_pos);
         if tab.is_in_string_or_comment_at_char(char_offset) {
-            return false;
+            return changed;
         }
 
         let (line, column) = lsp_position_from_buffer_pos(tab, buf_pos, encoding);
         let key = (path.clone(), line, column, tab.edit_version);
         if self.ui.hover_popup.last_request.as_ref() == Some(&key) {
-            return false;
+            return changed;
         }
         self.ui.hover_popup.last_request = Some(key);
         self.ui.hover_popup.last_anchor = anchor;
@@ -339,15 +360,102 @@ impl Workbench {
                 },
             );
         }
-        false
+        changed
+    }
+
+    /// 光标停留 `HOVER_IDLE_DELAY` 后请求当前符号的 documentHighlight；同一位置同一版本只发一次。
+    fn poll_idle_document_highlight(&mut self) -> bool {
+        if self.last_input_at.elapsed() < super::HOVER_IDLE_DELAY {
+            return false;
+        }
+
+        let state = self.store.state();
+        if state.ui.focus != FocusTarget::Editor
+            || state.ui.command_line.active
+            || state.ui.input_dialog.visible
+            || state.ui.confirm_dialog.visible
+        {
+            return false;
+        }
+
+        let pane = state.ui.editor_layout.active_pane;
+        let Some(tab) = state.editor.pane(pane).and_then(|pane| pane.active_tab()) else {
+            return false;
+        };
+        let Some(path) = tab.path.as_ref() else {
+            return false;
+        };
+
+        let key = (path.clone(), tab.buffer.cursor(), tab.edit_version);
+        if self.lsp_sync.last_document_highlight.as_ref() == Some(&key) {
+            return false;
+        }
+        self.lsp_sync.last_document_highlight = Some(key);
+
+        self.dispatch_kernel(KernelAction::RunCommand(Command::LspDocumentHighlight))
     }
 
-    /// 三条 LSP debounce（语义高亮 / inlay / 折叠）到点后下发对应刷新命令，共享
+    /// 光标停留 `HOVER_IDLE_DELAY` 后按新位置重算活动标签页的面包屑；光标和内容没变时不重算。
+    fn poll_idle_breadcrumbs(&mut self) -> bool {
+        if self.last_input_at.elapsed() < super::HOVER_IDLE_DELAY {
+            return false;
+        }
+
+        let state = self.store.state();
+        let pane = state.ui.editor_layout.active_pane;
+        if !state.editor_config_for_pane(pane).breadcrumbs_enabled {
+            return false;
+        }
+        let stale = state
+            .editor
+            .pane(pane)
+            .and_then(|pane| pane.active_tab())
+            .is_some_and(|tab| tab.breadcrumbs_stale());
+        if !stale {
+            return false;
+        }
+
+        self.dispatch_kernel(KernelAction::Editor(EditorAction::RefreshBreadcrumbs {
+            pane,
+        }))
+    }
+
+    /// 崩溃的服务端退避期满后重新拉起，并把已打开的文档重新 `didOpen` 过去。
+    /// 重连倒计时期间每帧都算有变化，状态栏的倒计时才会走动。
+    fn poll_lsp_restarts(&mut self) -> bool {
+        let Some(service) = self.kernel_services.get_mut::<LspService>() else {
+            return false;
+        };
+        let restarted = service.poll_restarts();
+        if restarted {
+            self.lsp_sync.open_paths.clear();
+            self.lsp_sync.open_paths_version = 0;
+            self.sync_lsp();
+        }
+
+        // 连接状态本身的变化由 `LspConnectionChanged` 触发重绘；这里只管整秒倒数走字。
+        let now = Instant::now();
+        let mut countdown: Vec<u64> = self
+            .store
+            .state()
+            .lsp
+            .connection
+            .values()
+            .filter_map(|status| status.retry_in_secs(now))
+            .collect();
+        countdown.sort_unstable();
+        let countdown_changed = countdown != self.lsp_sync.reconnect_countdown;
+        self.lsp_sync.reconnect_countdown = countdown;
+        restarted || countdown_changed
+    }
+
+    /// 各条 LSP debounce（inlay / 折叠 / 文档链接）到点后下发对应刷新命令，共享
     /// 同一抑制块（焦点不在编辑器、命令行 / 对话框可见时跳过）。pipeline 选 slot + 命令。
     fn poll_lsp_debounce(&mut self, pipeline: LspDebouncePipeline) -> bool {
         let deadline = match pipeline {
             LspDebouncePipeline::InlayHints => self.lsp_sync.debounce.inlay_hints,
             LspDebouncePipeline::FoldingRange => self.lsp_sync.debounce.folding_range,
+            LspDebouncePipeline::DocumentLink => self.lsp_sync.debounce.document_link,
         };
         let Some(deadline) = deadline else {
             return false;
@@ -359,6 +467,7 @@ impl Workbench {
         match pipeline {
             LspDebouncePipeline::InlayHints => self.lsp_sync.debounce.inlay_hints = None,
             LspDebouncePipeline::FoldingRange => self.lsp_sync.debounce.folding_range = None,
+            LspDebouncePipeline::DocumentLink => self.lsp_sync.debounce.document_link = None,
         }
 
         if self.store.state().ui.focus != FocusTarget::Editor {
@@ -374,6 +483,7 @@ impl Workbench {
         let command = match pipeline {
             LspDebouncePipeline::InlayHints => Command::LspInlayHints,
             LspDebouncePipeline::FoldingRange => Command::LspFoldingRange,
+            LspDebouncePipeline::DocumentLink => Command::LspDocumentLink,
         };
         let _ = self.dispatch_kernel(KernelAction::RunCommand(command));
         false
@@ -391,12 +501,20 @@ impl Workbench {
         let ParsedSettings {
             keybindings,
             editor_config,
-            lsp_settings_override,
-            lsp_server_overrides,
+            lsp,
+            sidebar_width,
+            status_bar,
+            ..
         } = parse_settings(settings);
 
-        let _ = self.store.dispatch(KernelAction::EditorConfigUpdated {
+        if let Some(width) = sidebar_width {
+            let _ = self.store.dispatch(KernelAction::SidebarSetWidth { width });
+        }
+        self.set_status_bar_config(status_bar);
+
+        let _ = self.dispatch_kernel(KernelAction::EditorConfigUpdated {
             config: editor_config.clone(),
+            lsp,
         });
 
         if let Some(service) = self.kernel_services.get_mut::<KeybindingService>() {
@@ -404,6 +522,9 @@ impl Workbench {
         } else {
             let _ = self.kernel_services.register(keybindings);
         }
+        if let Some(service) = self.kernel_services.get_mut::<ClipboardService>() {
+            service.set_backend(editor_config.clipboard_backend);
+        }
         if let Some(service) = self.kernel_services.get_mut::<ConfigService>() {
             *service.editor_mut() = editor_config;
         } else {
@@ -412,18 +533,6 @@ impl Workbench {
                 .register(ConfigService::with_editor_config(editor_config));
         }
 
-        if let Some(service) = self.kernel_services.get_mut::<LspService>() {
-            let mut global_override = None;
-            if super::lsp_command_override().is_none() {
-                global_override = lsp_settings_override;
-            }
-
-            if service.reconfigure(global_override, lsp_server_overrides) {
-                self.lsp_sync.open_paths.clear();
-                self.lsp_sync.open_paths_version = 0;
-            }
-        }
-
         self.last_settings_modified = self
             .settings_path
             .as_ref()
@@ -454,6 +563,52 @@ impl Workbench {
         false
     }
 
+    /// 光标停稳 1 秒后为当前行请求一次 blame；缓存命中或同一位置已请求过就不再起进程。
+    fn poll_git_blame(&mut self) {
+        if !self.store.state().editor.config.git_blame_inline
+            || self.last_input_at.elapsed() < super::GIT_BLAME_IDLE_DELAY
+        {
+            return;
+        }
+
+        let state = self.store.state();
+        if state.ui.focus != FocusTarget::Editor {
+            return;
+        }
+        let pane = state.ui.editor_layout.active_pane;
+        let Some(tab) = state.editor.pane(pane).and_then(|pane| pane.active_tab()) else {
+            return;
+        };
+        // 有未保存修改时缓冲区行号和磁盘上的文件对不上。
+        if tab.dirty {
+            return;
+        }
+        let Some(path) = tab.path.as_ref() else {
+            return;
+        };
+        let line = tab.buffer.cursor().0;
+        if state.git.blame_for(path, line).is_some() {
+            return;
+        }
+
+        let key = (path.clone(), line, tab.edit_version);
+        if self.last_git_blame_request.as_ref() == Some(&key) {
+            return;
+        }
+        let path = path.clone();
+        self.last_git_blame_request = Some(key);
+        self.run_effect(KernelEffect::GitBlame { path, line });
+    }
+
+    fn poll_session_save(&mut self) {
+        if !super::settings_enabled()
+            || self.last_session_save.elapsed() < super::SESSION_SAVE_INTERVAL
+        {
+            return;
+        }
+        self.flush_session_save();
+    }
+
     fn poll_completion_rank_save(&mut self) {
         let Some(deadline) = self.pending_completion_rank_save_deadline else {
             return;
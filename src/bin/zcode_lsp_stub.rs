@@ -251,6 +251,9 @@ fn handle_request(
                 )),
                 hover_provider: Some(lsp_types::HoverProviderCapability::Simple(true)),
                 definition_provider: Some(lsp_types::OneOf::Left(true)),
+                type_definition_provider: Some(
+                    lsp_types::TypeDefinitionProviderCapability::Simple(true),
+                ),
                 implementation_provider: Some(lsp_types::ImplementationProviderCapability::Simple(
                     true,
                 )),
@@ -342,6 +345,24 @@ fn handle_request(
 
             (Response::new_ok(req.id, resp), None, Vec::new())
         }
+        m if m == lsp_types::request::GotoTypeDefinition::METHOD => {
+            let target = root
+                .cloned()
+                .map(|root| root.join("type_definition_target.rs"))
+                .and_then(|path| lsp_types::Url::from_file_path(path).ok());
+
+            let resp = target.map(|uri| {
+                lsp_types::GotoDefinitionResponse::Scalar(lsp_types::Location {
+                    uri,
+                    range: lsp_types::Range::new(
+                        lsp_types::Position::new(0, 0),
+                        lsp_types::Position::new(0, 0),
+                    ),
+                })
+            });
+
+            (Response::new_ok(req.id, resp), None, Vec::new())
+        }
         m if m == lsp_types::request::GotoDefinition::METHOD => {
             let target = root
                 .cloned()
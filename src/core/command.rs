@@ -28,6 +28,11 @@ pub enum Command {
     DeleteBackward,
     DeleteForward,
     DeleteLine,
+    DuplicateLine,
+    ToggleLineComment,
+    SortLines {
+        descending: bool,
+    },
     DeleteToLineEnd,
     DeleteSelection,
 
@@ -62,6 +67,7 @@ pub enum Command {
     Save,
     OpenFile,
     CloseTab,
+    ReopenClosedTab,
     NextTab,
     PrevTab,
     ReloadFromDisk,
@@ -88,6 +94,11 @@ pub enum Command {
     Copy,
     Cut,
     Paste,
+    /// 剪贴板历史里更早 / 更新的一条：紧接上次历史粘贴时替换它，否则在光标处插入。
+    PastePrevious,
+    PasteNext,
+    /// 在命令行里按首行预览挑一条剪贴板历史粘贴。
+    ClipboardHistory,
 
     // ==================== 查找替换 ====================
     Find,
@@ -100,6 +111,7 @@ pub enum Command {
     EditorSearchBarSwitchField,
     EditorSearchBarToggleCaseSensitive,
     EditorSearchBarToggleRegex,
+    EditorSearchBarToggleWholeWord,
     EditorSearchBarToggleReplaceMode,
     EditorSearchBarCursorLeft,
     EditorSearchBarCursorRight,
@@ -112,6 +124,10 @@ pub enum Command {
 
     // ==================== Global Search（居中浮层 Search） ====================
     GlobalSearchStart,
+    GlobalSearchToggleReplace,
+    GlobalSearchToggleFilters,
+    GlobalSearchSwitchField,
+    GlobalSearchReplaceAll,
 
     // ==================== Search Results（居中浮层） ====================
     SearchResultsMoveUp,
@@ -124,30 +140,62 @@ pub enum Command {
     // ==================== LSP ====================
     LspHover,
     LspDefinition,
+    LspTypeDefinition,
+    LspImplementation,
     LspCompletion,
     LspSignatureHelp,
     LspFormat,
     LspFormatSelection,
     LspRename,
     LspReferences,
+    LspCallHierarchy,
+    LspCallHierarchyOutgoing,
     LspCodeAction,
     LspDocumentSymbols,
     LspWorkspaceSymbols,
     LspInlayHints,
     LspFoldingRange,
+    LspDocumentLink,
+    LspDocumentHighlight,
+
+    // ==================== Git ====================
+    OpenGitPanel,
+    GitStashPush {
+        include_untracked: bool,
+    },
+    GitCheckoutBranch,
+    ShowGitDiff,
 
     // ==================== Folding ====================
     EditorFoldToggle,
     EditorFold,
     EditorUnfold,
 
+    // ==================== Vim 模态编辑 ====================
+    VimNormalMode,
+    VimInsert,
+    VimAppend,
+    VimOpenLineBelow,
+    VimOpenLineAbove,
+    VimVisual,
+    VimVisualLine,
+    VimDelete,
+    VimChange,
+    VimYank,
+    VimGoto,
+    VimWordEnd,
+
     // ==================== `:` 命令行 ====================
     OpenCommandLine,
+    QuickOpen,
+    GotoLine,
     CommandLineClose,
     CommandLineMoveUp,
     CommandLineMoveDown,
     CommandLineBackspace,
     CommandLineConfirm,
+    CommandLineHistoryPrev,
+    CommandLineHistoryNext,
 
     // ==================== 视图操作 ====================
     ToggleSidebar,
@@ -158,7 +206,22 @@ pub enum Command {
     CloseOverlay,
     ReloadSettings,
     OpenSettings,
+    ExportKeybindings,
+    ImportKeybindings,
+    ExportAsHtml,
+    CopyRelativePath,
+    EditorContextMenu,
     HardReload,
+    ShowMetrics,
+    OpenTerminalAtCurrentFilePath,
+    /// 覆盖某个编辑器 pane 的 tab 宽度；`tab_size` 为 0 时清除覆盖。
+    SetPaneTabSize {
+        pane: usize,
+        tab_size: u8,
+    },
+    AddWorkspaceFolder,
+    /// 按 `AppState::workspace_folders` 的下标移除；下标 0（主工作区）不可移除。
+    RemoveWorkspaceFolder(usize),
 
     // 未知 `:command` 的兜底 sink（见模块文档）。
     Custom(String),
@@ -184,6 +247,10 @@ impl Command {
             Command::DeleteBackward => "deleteBackward",
             Command::DeleteForward => "deleteForward",
             Command::DeleteLine => "deleteLine",
+            Command::DuplicateLine => "duplicateLine",
+            Command::ToggleLineComment => "toggleLineComment",
+            Command::SortLines { descending: false } => "sortLinesAscending",
+            Command::SortLines { descending: true } => "sortLinesDescending",
             Command::DeleteToLineEnd => "deleteToLineEnd",
             Command::DeleteSelection => "deleteSelection",
             Command::ClearSelection => "clearSelection",
@@ -210,6 +277,7 @@ impl Command {
             Command::Save => "save",
             Command::OpenFile => "openFile",
             Command::CloseTab => "closeTab",
+            Command::ReopenClosedTab => "reopenClosedTab",
             Command::NextTab => "nextTab",
             Command::PrevTab => "prevTab",
             Command::ReloadFromDisk => "reloadFromDisk",
@@ -232,6 +300,9 @@ impl Command {
             Command::Copy => "copy",
             Command::Cut => "cut",
             Command::Paste => "paste",
+            Command::PastePrevious => "pastePrevious",
+            Command::PasteNext => "pasteNext",
+            Command::ClipboardHistory => "clipboardHistory",
             Command::Find => "find",
             Command::FindNext => "findNext",
             Command::FindPrev => "findPrev",
@@ -240,6 +311,7 @@ impl Command {
             Command::EditorSearchBarSwitchField => "editorSearchBarSwitchField",
             Command::EditorSearchBarToggleCaseSensitive => "editorSearchBarToggleCaseSensitive",
             Command::EditorSearchBarToggleRegex => "editorSearchBarToggleRegex",
+            Command::EditorSearchBarToggleWholeWord => "editorSearchBarToggleWholeWord",
             Command::EditorSearchBarToggleReplaceMode => "editorSearchBarToggleReplaceMode",
             Command::EditorSearchBarCursorLeft => "editorSearchBarCursorLeft",
             Command::EditorSearchBarCursorRight => "editorSearchBarCursorRight",
@@ -250,6 +322,10 @@ impl Command {
             Command::EditorSearchBarReplaceCurrent => "editorSearchBarReplaceCurrent",
             Command::EditorSearchBarReplaceAll => "editorSearchBarReplaceAll",
             Command::GlobalSearchStart => "globalSearchStart",
+            Command::GlobalSearchToggleReplace => "globalSearchToggleReplace",
+            Command::GlobalSearchToggleFilters => "globalSearchToggleFilters",
+            Command::GlobalSearchSwitchField => "globalSearchSwitchField",
+            Command::GlobalSearchReplaceAll => "globalSearchReplaceAll",
             Command::SearchResultsMoveUp => "searchResultsMoveUp",
             Command::SearchResultsMoveDown => "searchResultsMoveDown",
             Command::SearchResultsScrollUp => "searchResultsScrollUp",
@@ -258,26 +334,45 @@ impl Command {
             Command::SearchResultsOpenSelected => "searchResultsOpenSelected",
             Command::LspHover => "lspHover",
             Command::LspDefinition => "lspDefinition",
+            Command::LspTypeDefinition => "lspTypeDefinition",
+            Command::LspImplementation => "lspImplementation",
             Command::LspCompletion => "lspCompletion",
             Command::LspSignatureHelp => "lspSignatureHelp",
             Command::LspFormat => "lspFormat",
             Command::LspFormatSelection => "lspFormatSelection",
             Command::LspRename => "lspRename",
             Command::LspReferences => "lspReferences",
+            Command::LspCallHierarchy => "lspCallHierarchy",
+            Command::LspCallHierarchyOutgoing => "lspCallHierarchyOutgoing",
             Command::LspCodeAction => "lspCodeAction",
             Command::LspDocumentSymbols => "lspDocumentSymbols",
             Command::LspWorkspaceSymbols => "lspWorkspaceSymbols",
             Command::LspInlayHints => "lspInlayHints",
             Command::LspFoldingRange => "lspFoldingRange",
+            Command::LspDocumentLink => "lspDocumentLink",
+            Command::LspDocumentHighlight => "lspDocumentHighlight",
+            Command::OpenGitPanel => "openGitPanel",
+            Command::GitStashPush {
+                include_untracked: false,
+            } => "gitStashPush",
+            Command::GitStashPush {
+                include_untracked: true,
+            } => "gitStashPushIncludeUntracked",
+            Command::GitCheckoutBranch => "gitCheckoutBranch",
+            Command::ShowGitDiff => "showGitDiff",
             Command::EditorFoldToggle => "editorFoldToggle",
             Command::EditorFold => "editorFold",
             Command::EditorUnfold => "editorUnfold",
             Command::OpenCommandLine => "openCommandLine",
+            Command::QuickOpen => "quickOpen",
+            Command::GotoLine => "gotoLine",
             Command::CommandLineClose => "commandLineClose",
             Command::CommandLineMoveUp => "commandLineMoveUp",
             Command::CommandLineMoveDown => "commandLineMoveDown",
             Command::CommandLineBackspace => "commandLineBackspace",
             Command::CommandLineConfirm => "commandLineConfirm",
+            Command::CommandLineHistoryPrev => "commandLineHistoryPrev",
+            Command::CommandLineHistoryNext => "commandLineHistoryNext",
             Command::ToggleSidebar => "toggleSidebar",
             Command::FocusExplorer => "focusExplorer",
             Command::FocusSearch => "focusSearch",
@@ -286,7 +381,29 @@ impl Command {
             Command::CloseOverlay => "closeOverlay",
             Command::ReloadSettings => "reloadSettings",
             Command::OpenSettings => "openSettings",
+            Command::ExportKeybindings => "exportKeybindings",
+            Command::ImportKeybindings => "importKeybindings",
+            Command::ExportAsHtml => "exportAsHtml",
+            Command::CopyRelativePath => "copyRelativePath",
+            Command::EditorContextMenu => "editorContextMenu",
             Command::HardReload => "hardReload",
+            Command::ShowMetrics => "showMetrics",
+            Command::VimNormalMode => "vimNormalMode",
+            Command::VimInsert => "vimInsert",
+            Command::VimAppend => "vimAppend",
+            Command::VimOpenLineBelow => "vimOpenLineBelow",
+            Command::VimOpenLineAbove => "vimOpenLineAbove",
+            Command::VimVisual => "vimVisual",
+            Command::VimVisualLine => "vimVisualLine",
+            Command::VimDelete => "vimDelete",
+            Command::VimChange => "vimChange",
+            Command::VimYank => "vimYank",
+            Command::VimGoto => "vimGoto",
+            Command::VimWordEnd => "vimWordEnd",
+            Command::OpenTerminalAtCurrentFilePath => "openTerminalAtCurrentFilePath",
+            Command::SetPaneTabSize { .. } => "setPaneTabSize",
+            Command::AddWorkspaceFolder => "addWorkspaceFolder",
+            Command::RemoveWorkspaceFolder(_) => "removeWorkspaceFolder",
             Command::Escape => "escape",
             Command::Custom(name) => name,
         }
@@ -311,6 +428,10 @@ impl Command {
             "deleteBackward" => Command::DeleteBackward,
             "deleteForward" => Command::DeleteForward,
             "deleteLine" => Command::DeleteLine,
+            "duplicateLine" => Command::DuplicateLine,
+            "toggleLineComment" => Command::ToggleLineComment,
+            "sortLinesAscending" => Command::SortLines { descending: false },
+            "sortLinesDescending" => Command::SortLines { descending: true },
             "deleteToLineEnd" => Command::DeleteToLineEnd,
             "deleteSelection" => Command::DeleteSelection,
             "clearSelection" => Command::ClearSelection,
@@ -337,6 +458,7 @@ impl Command {
             "save" => Command::Save,
             "openFile" => Command::OpenFile,
             "closeTab" => Command::CloseTab,
+            "reopenClosedTab" => Command::ReopenClosedTab,
             "nextTab" => Command::NextTab,
             "prevTab" => Command::PrevTab,
             "reloadFromDisk" => Command::ReloadFromDisk,
@@ -359,6 +481,9 @@ impl Command {
             "copy" => Command::Copy,
             "cut" => Command::Cut,
             "paste" => Command::Paste,
+            "pastePrevious" => Command::PastePrevious,
+            "pasteNext" => Command::PasteNext,
+            "clipboardHistory" => Command::ClipboardHistory,
             "find" => Command::Find,
             "findNext" => Command::FindNext,
             "findPrev" => Command::FindPrev,
@@ -367,6 +492,7 @@ impl Command {
             "editorSearchBarSwitchField" => Command::EditorSearchBarSwitchField,
             "editorSearchBarToggleCaseSensitive" => Command::EditorSearchBarToggleCaseSensitive,
             "editorSearchBarToggleRegex" => Command::EditorSearchBarToggleRegex,
+            "editorSearchBarToggleWholeWord" => Command::EditorSearchBarToggleWholeWord,
             "editorSearchBarToggleReplaceMode" => Command::EditorSearchBarToggleReplaceMode,
             "editorSearchBarCursorLeft" => Command::EditorSearchBarCursorLeft,
             "editorSearchBarCursorRight" => Command::EditorSearchBarCursorRight,
@@ -377,6 +503,10 @@ impl Command {
             "editorSearchBarReplaceCurrent" => Command::EditorSearchBarReplaceCurrent,
             "editorSearchBarReplaceAll" => Command::EditorSearchBarReplaceAll,
             "globalSearchStart" => Command::GlobalSearchStart,
+            "globalSearchToggleReplace" => Command::GlobalSearchToggleReplace,
+            "globalSearchToggleFilters" => Command::GlobalSearchToggleFilters,
+            "globalSearchSwitchField" => Command::GlobalSearchSwitchField,
+            "globalSearchReplaceAll" => Command::GlobalSearchReplaceAll,
             "searchResultsMoveUp" => Command::SearchResultsMoveUp,
             "searchResultsMoveDown" => Command::SearchResultsMoveDown,
             "searchResultsScrollUp" => Command::SearchResultsScrollUp,
@@ -385,26 +515,45 @@ impl Command {
             "searchResultsOpenSelected" => Command::SearchResultsOpenSelected,
             "lspHover" => Command::LspHover,
             "lspDefinition" => Command::LspDefinition,
+            "lspTypeDefinition" => Command::LspTypeDefinition,
+            "lspImplementation" => Command::LspImplementation,
             "lspCompletion" => Command::LspCompletion,
             "lspSignatureHelp" => Command::LspSignatureHelp,
             "lspFormat" => Command::LspFormat,
             "lspFormatSelection" => Command::LspFormatSelection,
             "lspRename" => Command::LspRename,
             "lspReferences" => Command::LspReferences,
+            "lspCallHierarchy" => Command::LspCallHierarchy,
+            "lspCallHierarchyOutgoing" => Command::LspCallHierarchyOutgoing,
             "lspCodeAction" => Command::LspCodeAction,
             "lspDocumentSymbols" => Command::LspDocumentSymbols,
             "lspWorkspaceSymbols" => Command::LspWorkspaceSymbols,
             "lspInlayHints" => Command::LspInlayHints,
             "lspFoldingRange" => Command::LspFoldingRange,
+            "lspDocumentLink" => Command::LspDocumentLink,
+            "lspDocumentHighlight" => Command::LspDocumentHighlight,
+            "openGitPanel" => Command::OpenGitPanel,
+            "gitStashPush" => Command::GitStashPush {
+                include_untracked: false,
+            },
+            "gitStashPushIncludeUntracked" => Command::GitStashPush {
+                include_untracked: true,
+            },
+            "gitCheckoutBranch" => Command::GitCheckoutBranch,
+            "showGitDiff" => Command::ShowGitDiff,
             "editorFoldToggle" => Command::EditorFoldToggle,
             "editorFold" => Command::EditorFold,
             "editorUnfold" => Command::EditorUnfold,
             "openCommandLine" => Command::OpenCommandLine,
+            "quickOpen" => Command::QuickOpen,
+            "gotoLine" => Command::GotoLine,
             "commandLineClose" => Command::CommandLineClose,
             "commandLineMoveUp" => Command::CommandLineMoveUp,
             "commandLineMoveDown" => Command::CommandLineMoveDown,
             "commandLineBackspace" => Command::CommandLineBackspace,
             "commandLineConfirm" => Command::CommandLineConfirm,
+            "commandLineHistoryPrev" => Command::CommandLineHistoryPrev,
+            "commandLineHistoryNext" => Command::CommandLineHistoryNext,
             "toggleSidebar" => Command::ToggleSidebar,
             "focusExplorer" => Command::FocusExplorer,
             "focusSearch" => Command::FocusSearch,
@@ -413,7 +562,27 @@ impl Command {
             "closeOverlay" => Command::CloseOverlay,
             "reloadSettings" => Command::ReloadSettings,
             "openSettings" => Command::OpenSettings,
+            "exportKeybindings" => Command::ExportKeybindings,
+            "importKeybindings" => Command::ImportKeybindings,
+            "exportAsHtml" => Command::ExportAsHtml,
+            "copyRelativePath" => Command::CopyRelativePath,
+            "editorContextMenu" => Command::EditorContextMenu,
             "hardReload" => Command::HardReload,
+            "showMetrics" => Command::ShowMetrics,
+            "vimNormalMode" => Command::VimNormalMode,
+            "vimInsert" => Command::VimInsert,
+            "vimAppend" => Command::VimAppend,
+            "vimOpenLineBelow" => Command::VimOpenLineBelow,
+            "vimOpenLineAbove" => Command::VimOpenLineAbove,
+            "vimVisual" => Command::VimVisual,
+            "vimVisualLine" => Command::VimVisualLine,
+            "vimDelete" => Command::VimDelete,
+            "vimChange" => Command::VimChange,
+            "vimYank" => Command::VimYank,
+            "vimGoto" => Command::VimGoto,
+            "vimWordEnd" => Command::VimWordEnd,
+            "openTerminalAtCurrentFilePath" => Command::OpenTerminalAtCurrentFilePath,
+            "addWorkspaceFolder" => Command::AddWorkspaceFolder,
             other => Command::Custom(other.to_string()),
         }
     }
@@ -430,6 +599,8 @@ impl Command {
                 | Command::DeleteToLineEnd
                 | Command::DeleteSelection
                 | Command::Paste
+                | Command::PastePrevious
+                | Command::PasteNext
                 | Command::Cut
         )
     }
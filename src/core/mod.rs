@@ -13,4 +13,4 @@ pub mod wakeup;
 
 pub use command::Command;
 pub use event::{InputEvent, Key, MousePosition};
-pub use service::{Service, ServiceError, ServiceRegistry};
+pub use service::{Service, ServiceError, ServiceMiddleware, ServiceRegistry};
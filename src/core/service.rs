@@ -42,6 +42,18 @@ impl dyn Service {
     }
 }
 
+/// 分发中间件：挂在 store 的 dispatch 管线上，按注册顺序在每次分发前后被调用，
+/// 让日志、统计这类横切逻辑不必塞进 reducer。`A` / `R` 是动作与分发结果类型
+/// （内核里是 `Action` / `DispatchResult`，见 `kernel::services::KernelMiddleware`）。
+pub trait ServiceMiddleware<A, R>: Any {
+    /// 分发前观察动作。返回 `Some` 时用新动作替换原动作继续往后传；`None` 原样放行。
+    fn before_dispatch(&mut self, _action: &A) -> Option<A> {
+        None
+    }
+
+    fn after_dispatch(&mut self, _result: &R) {}
+}
+
 pub struct ServiceRegistry {
     services: HashMap<TypeId, Box<dyn Service>>,
 }
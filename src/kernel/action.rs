@@ -3,23 +3,29 @@ use std::time::Instant;
 
 use crate::core::Command;
 use crate::kernel::editor::EditorAction;
+use crate::kernel::panel::git::{GitBlameLine, GitBranch, GitStash};
 use crate::kernel::panel::locations::LocationItem;
 use crate::kernel::panel::problems::ProblemItem;
 use crate::kernel::panel::symbols::SymbolItem;
 use crate::kernel::services::ports::DirEntryInfo;
 use crate::kernel::services::ports::EditorConfig;
 use crate::kernel::services::ports::GlobalSearchMessage;
+use crate::kernel::services::ports::LspCallHierarchyItem;
 use crate::kernel::services::ports::LspCodeAction;
 use crate::kernel::services::ports::LspCommand;
 use crate::kernel::services::ports::LspCompletionItem;
+use crate::kernel::services::ports::LspConnectionStatus;
+use crate::kernel::services::ports::LspDocumentLink;
 use crate::kernel::services::ports::LspFoldingRange;
 use crate::kernel::services::ports::LspInlayHint;
 use crate::kernel::services::ports::LspServerCapabilities;
 use crate::kernel::services::ports::LspServerKind;
+use crate::kernel::services::ports::LspSettings;
 use crate::kernel::services::ports::LspSignatureHelpPayload;
 use crate::kernel::services::ports::LspTextEdit;
 use crate::kernel::services::ports::LspWorkspaceEdit;
 use crate::kernel::services::ports::{LspHoverPayload, LspHoverPreviewPayload};
+use crate::models::NodeId;
 
 #[derive(Debug, Clone)]
 pub enum Action {
@@ -27,8 +33,10 @@ pub enum Action {
     Editor(EditorAction),
     OpenPath(PathBuf),
     Tick,
+    /// 设置热重载：新的编辑器配置与 `settings.lsp`。
     EditorConfigUpdated {
         config: EditorConfig,
+        lsp: LspSettings,
     },
     InputDialogAppend(char),
     InputDialogBackspace,
@@ -37,9 +45,27 @@ pub enum Action {
     InputDialogAccept,
     InputDialogCancel,
     CommandLineAppend(char),
+    /// 文件模式的候选：工作区内全部文件，路径相对 `workspace_root`。
+    CommandLineFilesListed {
+        files: Vec<PathBuf>,
+    },
+    /// 剪贴板历史选择器的候选，新的在前。
+    ClipboardHistoryLoaded {
+        entries: Vec<String>,
+    },
     EditorSetActivePane {
         pane: usize,
     },
+    /// 以 `EditorConfig::horizontal_scroll_step` 列为单位的平滑水平滚动。
+    EditorScrollHorizontal {
+        pane: usize,
+        delta: i32,
+    },
+    /// 在光标处插入 VS Code 风格的 snippet 模板，有选区时替换选区；整个插入是一步撤销。
+    EditorInsertSnippet {
+        pane: usize,
+        template: String,
+    },
     SidebarSetWidth {
         width: u16,
     },
@@ -75,6 +101,19 @@ pub enum Action {
         from: PathBuf,
         to: PathBuf,
     },
+    /// 侧栏内开始拖动一行。
+    ExplorerDragStart {
+        node_id: NodeId,
+    },
+    /// 拖动中指针下方的行（`ExplorerState::set_drag_hover` 只保留目录）。
+    ExplorerDragHover {
+        target: Option<NodeId>,
+    },
+    /// 放到 `target` 上：目录则移动过去，文件行上不做任何事。
+    ExplorerDrop {
+        target: NodeId,
+    },
+    ExplorerDragCancel,
     SearchSetViewHeight {
         height: usize,
     },
@@ -111,6 +150,39 @@ pub enum Action {
     SymbolsSetViewHeight {
         height: usize,
     },
+    GitClickRow {
+        row: usize,
+    },
+    GitSetViewHeight {
+        height: usize,
+    },
+    GitStashList {
+        stashes: Vec<GitStash>,
+    },
+    GitBranchesUpdated {
+        branches: Vec<GitBranch>,
+    },
+    GitBlameResult {
+        path: PathBuf,
+        line: usize,
+        blame: GitBlameLine,
+    },
+    /// `path` 在暂存区里的内容，与打开的缓冲区比较后填进 diff 视图。
+    GitDiffBaseLoaded {
+        path: PathBuf,
+        content: String,
+    },
+    CallHierarchyClickRow {
+        row: usize,
+    },
+    CallHierarchySetViewHeight {
+        height: usize,
+    },
+    /// 展开 / 折叠调用树节点；子节点未加载时按树的方向发起
+    /// `callHierarchy/incomingCalls` 或 `callHierarchy/outgoingCalls`。
+    CallHierarchyExpand {
+        item_index: usize,
+    },
     LspDiagnostics {
         path: PathBuf,
         items: Vec<ProblemItem>,
@@ -133,20 +205,63 @@ pub enum Action {
         line: u32,
         column: u32,
     },
+    /// `textDocument/typeDefinition` 的结果；跳转方式与 `LspDefinition` 相同。
+    LspTypeDefinition {
+        path: PathBuf,
+        line: u32,
+        column: u32,
+    },
+    /// `textDocument/implementation` 的结果：只有一处时直接跳转，多处时列进位置面板。
+    LspImplementation {
+        items: Vec<LocationItem>,
+    },
     LspReferences {
         items: Vec<LocationItem>,
     },
+    /// `textDocument/prepareRename` 的结果：`valid` 时以 `placeholder`（服务端给出时）预填改名框。
+    LspPrepareRenameResponse {
+        path: PathBuf,
+        line: u32,
+        column: u32,
+        valid: bool,
+        placeholder: Option<String>,
+    },
+    LspCallHierarchyPrepared {
+        items: Vec<LspCallHierarchyItem>,
+    },
+    LspCallHierarchyIncoming {
+        item_index: usize,
+        items: Vec<LspCallHierarchyItem>,
+    },
+    LspCallHierarchyOutgoing {
+        item_index: usize,
+        items: Vec<LspCallHierarchyItem>,
+    },
     LspCodeActions {
         items: Vec<LspCodeAction>,
     },
     LspSymbols {
         items: Vec<SymbolItem>,
     },
+    /// `workspace/symbol` 经 `$/progress` 推来的一批部分结果。
+    LspWorkspaceSymbolsPartial {
+        items: Vec<SymbolItem>,
+    },
+    /// `workspace/symbol` 的最终响应，与已流入的部分结果合并。
+    LspWorkspaceSymbols {
+        items: Vec<SymbolItem>,
+    },
     LspServerCapabilities {
         server: LspServerKind,
         root: PathBuf,
         capabilities: LspServerCapabilities,
     },
+    /// 服务端进程崩溃后的重连进度；重新拿到能力时由 `LspServerCapabilities` 清除。
+    LspConnectionChanged {
+        server: LspServerKind,
+        root: PathBuf,
+        status: LspConnectionStatus,
+    },
     LspInlayHints {
         path: PathBuf,
         version: u64,
@@ -158,6 +273,41 @@ pub enum Action {
         version: u64,
         ranges: Vec<LspFoldingRange>,
     },
+    LspDocumentLinks {
+        path: PathBuf,
+        version: u64,
+        links: Vec<LspDocumentLink>,
+    },
+    LspDocumentLinkResolved {
+        path: PathBuf,
+        version: u64,
+        range: crate::kernel::services::ports::LspRange,
+        target: String,
+    },
+    /// `textDocument/documentHighlight` 结果；只在 `version` 仍等于标签页 `edit_version` 时生效。
+    LspDocumentHighlights {
+        path: PathBuf,
+        version: u64,
+        ranges: Vec<crate::kernel::services::ports::LspRange>,
+    },
+    /// 鼠标停在 `(row, col)`：该处有诊断时把消息放进悬停提示。
+    ShowDiagnosticHover {
+        pane: usize,
+        row: usize,
+        col: usize,
+    },
+    /// 单击落在诊断下划线上（光标已移到该处）：按光标位置请求 code action。
+    DiagnosticClicked {
+        pane: usize,
+        row: usize,
+        col: usize,
+    },
+    /// Ctrl+Click：打开 `(row, col)` 处的文档链接，未解析的先走 `documentLink/resolve`。
+    OpenDocumentLinkAt {
+        pane: usize,
+        row: usize,
+        col: usize,
+    },
     LspCompletion {
         items: Vec<LspCompletionItem>,
         is_incomplete: bool,
@@ -179,12 +329,21 @@ pub enum Action {
     LspApplyWorkspaceEdit {
         edit: LspWorkspaceEdit,
     },
+    /// 整份 / 区间格式化的返回；服务端出错或无改动时 `edits` 为空。
+    LspFormatCompleted {
+        path: PathBuf,
+        edits: Vec<LspTextEdit>,
+    },
     LspProgressEnd,
     CompletionClose,
     CompletionMoveSelection {
         delta: isize,
     },
     CompletionConfirm,
+    DirBatchLoaded {
+        path: PathBuf,
+        entries: Vec<DirEntryInfo>,
+    },
     DirLoaded {
         path: PathBuf,
         entries: Vec<DirEntryInfo>,
@@ -192,6 +351,16 @@ pub enum Action {
     DirLoadError {
         path: PathBuf,
     },
+    /// `Effect::AddWorkspaceFolder` 校验通过（目录存在，已规范化）后加入工作区。
+    WorkspaceFolderAdded {
+        path: PathBuf,
+    },
+    /// 重载工作台后恢复快照里的界面部分；标签页由 Workbench 读盘后逐个 `OpenFile` 恢复。
+    RestoreUiSnapshot {
+        focus: crate::kernel::FocusTarget,
+        editor_layout: crate::kernel::EditorLayoutState,
+        search_query: String,
+    },
     SetHoveredTab {
         pane: usize,
         index: usize,
@@ -217,4 +386,9 @@ pub enum Action {
     ExplorerDirChanged {
         path: PathBuf,
     },
+    /// 一次资源管理器文件操作已交给运行时执行，`inverse` 是撤销它要做的操作。
+    ExplorerOpApplied {
+        inverse: crate::kernel::Effect,
+        origin: crate::kernel::state::ExplorerOpOrigin,
+    },
 }
@@ -1,5 +1,5 @@
-use crate::kernel::services::ports::SearchMessage;
-use crate::models::{Granularity, OpId};
+use crate::kernel::services::ports::{LspPositionEncoding, LspTextEdit, SearchMessage};
+use crate::models::{EditHistory, Granularity, OpId};
 use std::path::PathBuf;
 
 use super::ReloadRequest;
@@ -13,6 +13,22 @@ pub enum EditorAction {
         path: PathBuf,
         content: String,
     },
+    /// 大文件按块读入的第一块：以文件开头 `loaded_bytes` 字节打开标签页。
+    OpenFileRange {
+        pane: usize,
+        path: PathBuf,
+        content: String,
+        loaded_bytes: u64,
+        file_size: u64,
+    },
+    /// 后续块，接到该路径上未读完的标签页末尾。
+    AppendFileRange {
+        path: PathBuf,
+        start_byte: u64,
+        end_byte: u64,
+        file_size: u64,
+        content: String,
+    },
     GotoByteOffset {
         pane: usize,
         byte_offset: usize,
@@ -43,6 +59,13 @@ pub enum EditorAction {
         end_byte: usize,
         text: String,
     },
+    /// 一个文件的全部 LSP 编辑，整组作为一个撤销步应用到指定 tab。
+    ApplyLspEditsToTab {
+        pane: usize,
+        tab_index: usize,
+        edits: Vec<LspTextEdit>,
+        encoding: LspPositionEncoding,
+    },
     ReplaceRangeChars {
         pane: usize,
         start_char: usize,
@@ -60,6 +83,14 @@ pub enum EditorAction {
         row: usize,
         col: usize,
     },
+    /// Alt+拖拽的列选区：按下处 `anchor_*` 到当前位置围成的矩形，每行一个光标。
+    SelectBlock {
+        pane: usize,
+        anchor_row: usize,
+        anchor_col: usize,
+        row: usize,
+        col: usize,
+    },
     ExtendSelection {
         pane: usize,
         row: usize,
@@ -68,6 +99,20 @@ pub enum EditorAction {
     EndSelectionGesture {
         pane: usize,
     },
+    /// 行号栏点击折叠标记：切换以 `line` 开头的折叠。
+    ToggleFold {
+        pane: usize,
+        line: usize,
+    },
+    /// 光标停下后按当前位置重算活动标签页的面包屑。
+    RefreshBreadcrumbs {
+        pane: usize,
+    },
+    /// 点击面包屑第 `index` 段：选中该符号的整个范围。
+    SelectBreadcrumb {
+        pane: usize,
+        index: usize,
+    },
     Scroll {
         pane: usize,
         delta_lines: isize,
@@ -76,6 +121,10 @@ pub enum EditorAction {
         pane: usize,
         delta_columns: isize,
     },
+    ScrollHorizontalAnimated {
+        pane: usize,
+        delta_columns: isize,
+    },
     SearchBarAppend {
         pane: usize,
         ch: char,
@@ -107,6 +156,9 @@ pub enum EditorAction {
     SearchBarToggleRegex {
         pane: usize,
     },
+    SearchBarToggleWholeWord {
+        pane: usize,
+    },
     SearchBarToggleReplaceMode {
         pane: usize,
     },
@@ -168,4 +220,66 @@ pub enum EditorAction {
     KeepMemoryVersion {
         pane: usize,
     },
+    /// 从 `.ops` 备份读回的撤销历史，接到该路径上刚打开、尚未编辑的标签页。
+    RestoreEditHistory {
+        path: PathBuf,
+        history: EditHistory,
+    },
+}
+
+impl EditorAction {
+    /// 动作作用的 pane；按 tab / 路径定位的动作返回 `None`。
+    pub fn pane(&self) -> Option<usize> {
+        match self {
+            EditorAction::OpenFile { pane, .. }
+            | EditorAction::OpenFileRange { pane, .. }
+            | EditorAction::GotoByteOffset { pane, .. }
+            | EditorAction::SetActiveTab { pane, .. }
+            | EditorAction::SetViewportSize { pane, .. }
+            | EditorAction::InsertText { pane, .. }
+            | EditorAction::ApplyTextEdit { pane, .. }
+            | EditorAction::ApplyTextEditToTab { pane, .. }
+            | EditorAction::ApplyLspEditsToTab { pane, .. }
+            | EditorAction::ReplaceRangeChars { pane, .. }
+            | EditorAction::PlaceCursor { pane, .. }
+            | EditorAction::AddCursorAt { pane, .. }
+            | EditorAction::SelectBlock { pane, .. }
+            | EditorAction::ExtendSelection { pane, .. }
+            | EditorAction::EndSelectionGesture { pane }
+            | EditorAction::ToggleFold { pane, .. }
+            | EditorAction::RefreshBreadcrumbs { pane }
+            | EditorAction::SelectBreadcrumb { pane, .. }
+            | EditorAction::Scroll { pane, .. }
+            | EditorAction::ScrollHorizontal { pane, .. }
+            | EditorAction::ScrollHorizontalAnimated { pane, .. }
+            | EditorAction::SearchBarAppend { pane, .. }
+            | EditorAction::SearchBarBackspace { pane }
+            | EditorAction::SearchBarDeleteForward { pane }
+            | EditorAction::SearchBarCursorLeft { pane }
+            | EditorAction::SearchBarCursorRight { pane }
+            | EditorAction::SearchBarCursorHome { pane }
+            | EditorAction::SearchBarCursorEnd { pane }
+            | EditorAction::SearchBarSwitchField { pane }
+            | EditorAction::SearchBarToggleCaseSensitive { pane }
+            | EditorAction::SearchBarToggleRegex { pane }
+            | EditorAction::SearchBarToggleWholeWord { pane }
+            | EditorAction::SearchBarToggleReplaceMode { pane }
+            | EditorAction::ReplaceCurrent { pane }
+            | EditorAction::ReplaceAll { pane }
+            | EditorAction::SearchStarted { pane, .. }
+            | EditorAction::SearchMessage { pane, .. }
+            | EditorAction::Saved { pane, .. }
+            | EditorAction::CloseTabAt { pane, .. }
+            | EditorAction::CloseTabsById { pane, .. }
+            | EditorAction::AcceptDiskVersion { pane, .. }
+            | EditorAction::KeepMemoryVersion { pane } => Some(*pane),
+            EditorAction::MoveTab { to_pane, .. } => Some(*to_pane),
+            EditorAction::AppendFileRange { .. }
+            | EditorAction::FileReloaded { .. }
+            | EditorAction::ApplySyntaxHighlightPatches { .. }
+            | EditorAction::FileExternallyModified { .. }
+            | EditorAction::FileExternallyDeleted { .. }
+            | EditorAction::RestoreEditHistory { .. } => None,
+        }
+    }
 }
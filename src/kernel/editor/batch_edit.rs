@@ -0,0 +1,156 @@
+//! 多处编辑一次提交：整组编辑作为一个 `OpKind::Batch` 进历史，光标 / 选区按编辑映射，撤销一次即还原。
+
+use super::EditorTabState;
+use crate::kernel::services::ports::lsp::lsp_text_edits_to_char_ranges;
+use crate::kernel::services::ports::{LspPositionEncoding, LspTextEdit};
+use crate::models::cursor_set;
+use crate::models::edit_op::BatchEdit;
+use crate::models::{stray_line_endings, EditOp, Granularity, OpId, OpKind, Selection, TextBuffer};
+
+/// 把编辑前的字符偏移映射到应用 `edits`（降序）之后；落在被删区间里的偏移移到区间原起点。
+fn map_char_offset(offset: usize, edits: &[BatchEdit]) -> usize {
+    let mut delta = 0isize;
+    for edit in edits.iter().rev() {
+        if offset < edit.start {
+            break;
+        }
+        if offset < edit.end {
+            return edit.start.saturating_add_signed(delta);
+        }
+        delta += edit.inserted.chars().count() as isize - (edit.end - edit.start) as isize;
+    }
+    offset.saturating_add_signed(delta)
+}
+
+impl EditorTabState {
+    /// 应用一组按起点降序、互不重叠的编辑（偏移按编辑前的文本计），作为一个历史节点提交。
+    pub(super) fn apply_batch_edits(&mut self, edits: Vec<BatchEdit>, tab_size: u8) -> bool {
+        if edits.is_empty() {
+            return false;
+        }
+
+        self.cancel_snippet_session();
+        let cursor_before = self.buffer.cursor();
+        let extra_before = cursor_set::secondary_cursor_positions(&self.secondary_cursors);
+        let map_pos = |buffer: &TextBuffer, pos| map_char_offset(buffer.pos_to_char(pos), &edits);
+        let primary_char = map_pos(&self.buffer, cursor_before);
+        let primary_selection = self.buffer.selection().filter(|s| !s.is_empty()).map(|s| {
+            (
+                map_pos(&self.buffer, s.anchor()),
+                map_pos(&self.buffer, s.cursor()),
+            )
+        });
+        let secondary = self
+            .secondary_cursors
+            .iter()
+            .map(|cursor| {
+                (
+                    map_pos(&self.buffer, cursor.pos),
+                    cursor
+                        .selection
+                        .as_ref()
+                        .filter(|s| !s.is_empty())
+                        .map(|s| {
+                            (
+                                map_pos(&self.buffer, s.anchor()),
+                                map_pos(&self.buffer, s.cursor()),
+                            )
+                        }),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let parent = self.history.head();
+        let kind = OpKind::Batch { edits };
+        let mut rope = self.buffer.rope().clone();
+        kind.apply(&mut rope);
+        self.buffer.set_rope(rope);
+
+        let selection_at = |buffer: &TextBuffer, (anchor, cursor): (usize, usize)| {
+            let mut selection = Selection::new(
+                buffer.cursor_pos_from_char_offset(anchor),
+                Granularity::Char,
+            );
+            selection.update_cursor(buffer.cursor_pos_from_char_offset(cursor), buffer.rope());
+            selection
+        };
+        let primary_pos = self.buffer.cursor_pos_from_char_offset(primary_char);
+        self.buffer.set_cursor(primary_pos.0, primary_pos.1);
+        self.buffer.set_cursor_char_offset_cache(primary_char);
+        self.buffer
+            .set_selection(primary_selection.map(|s| selection_at(&self.buffer, s)));
+        for (cursor, (pos_char, selection)) in self.secondary_cursors.iter_mut().zip(secondary) {
+            cursor.pos = self.buffer.cursor_pos_from_char_offset(pos_char);
+            cursor.selection = selection.map(|s| selection_at(&self.buffer, s));
+        }
+
+        let op = EditOp {
+            id: OpId::new(),
+            parent,
+            kind,
+            cursor_before,
+            cursor_after: self.buffer.cursor(),
+            extra_cursors_before: Some(extra_before),
+            extra_cursors_after: Some(cursor_set::secondary_cursor_positions(
+                &self.secondary_cursors,
+            )),
+        };
+        self.apply_edit_op(op, tab_size);
+        true
+    }
+
+    /// 保存前把与 `line_ending` 不同的换行统一过来，作为一个撤销步，缓冲区与写出的文件保持一致。
+    /// 只扫自上次保存以来改过的区间；读入时就不统一的文件才扫全文。
+    pub(super) fn normalize_line_endings(&mut self, tab_size: u8) -> bool {
+        let Some(newline) = self.line_ending.as_str() else {
+            return false;
+        };
+        let rope = self.buffer.rope();
+        let range = if self.foreign_line_endings {
+            0..rope.len_chars()
+        } else if !self.history.is_dirty() {
+            return false;
+        } else {
+            match self.history.dirty_byte_range(rope) {
+                Some(dirty) => {
+                    rope.byte_to_char(dirty.buffer.start)..rope.byte_to_char(dirty.buffer.end)
+                }
+                None => 0..rope.len_chars(),
+            }
+        };
+        let edits: Vec<BatchEdit> = stray_line_endings(rope, range, self.line_ending)
+            .map(|range| BatchEdit {
+                deleted: rope.slice(range.clone()).chars().collect::<String>().into(),
+                inserted: newline.into(),
+                start: range.start,
+                end: range.end,
+            })
+            .collect();
+        self.foreign_line_endings = false;
+        self.apply_batch_edits(edits.into_iter().rev().collect(), tab_size)
+    }
+
+    /// 应用一个文件的全部 LSP 编辑（格式化、重命名等），整组只占一个撤销步。
+    pub(super) fn apply_lsp_edits(
+        &mut self,
+        edits: &[LspTextEdit],
+        encoding: LspPositionEncoding,
+        tab_size: u8,
+    ) -> bool {
+        let rope = self.buffer.rope();
+        let edits = lsp_text_edits_to_char_ranges(rope, edits, encoding)
+            .into_iter()
+            .map(|(start, end, text)| BatchEdit {
+                start,
+                end,
+                deleted: rope.slice(start..end).chars().collect::<String>().into(),
+                inserted: text.into(),
+            })
+            .collect();
+        self.apply_batch_edits(edits, tab_size)
+    }
+}
+
+#[cfg(test)]
+#[path = "../../../tests/unit/kernel/editor/batch_edit.rs"]
+mod tests;
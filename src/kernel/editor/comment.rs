@@ -0,0 +1,163 @@
+//! 行注释切换（Ctrl+/）：按语言查行注释记号，整次切换作为一个 `OpKind::Batch` 提交，撤销一次即还原。
+
+use ropey::Rope;
+
+use super::EditorTabState;
+use crate::kernel::language::LanguageId;
+use crate::models::edit_op::BatchEdit;
+use crate::models::Selection;
+
+/// 语言的行注释记号；只有块注释（或没有注释）的语言返回 `None`。
+pub fn line_comment_token(language: LanguageId) -> Option<&'static str> {
+    match language {
+        LanguageId::Rust
+        | LanguageId::Go
+        | LanguageId::JavaScript
+        | LanguageId::TypeScript
+        | LanguageId::Jsx
+        | LanguageId::Tsx
+        | LanguageId::C
+        | LanguageId::Cpp
+        | LanguageId::Java
+        | LanguageId::Protobuf
+        | LanguageId::Groovy
+        | LanguageId::Solidity
+        | LanguageId::Cairo
+        | LanguageId::Kotlin
+        | LanguageId::Swift => Some("//"),
+        LanguageId::Python
+        | LanguageId::Yaml
+        | LanguageId::Toml
+        | LanguageId::Bash
+        | LanguageId::Rego
+        | LanguageId::Makefile
+        | LanguageId::Dockerfile
+        | LanguageId::Ruby
+        | LanguageId::Elixir
+        | LanguageId::Nix => Some("#"),
+        LanguageId::Sql | LanguageId::Lua | LanguageId::Dhall => Some("--"),
+        LanguageId::Wat => Some(";;"),
+        LanguageId::Erlang => Some("%"),
+        LanguageId::Json
+        | LanguageId::Html
+        | LanguageId::Xml
+        | LanguageId::Css
+        | LanguageId::Markdown => None,
+    }
+}
+
+/// 在 `rows`（升序、去重）上切换行注释，返回按起点降序排列的编辑，偏移都按编辑前的文本计，
+/// 可直接作为 `OpKind::Batch` 的 edits。
+/// 空白行不参与判断也不改动；只要有一行已注释就全部取消注释，否则在最小缩进处全部加上 `token `。
+pub(crate) fn toggle_line_comment_edits(
+    rope: &Rope,
+    rows: &[usize],
+    token: &str,
+) -> Vec<BatchEdit> {
+    let lines = rows
+        .iter()
+        .filter(|&&row| row < rope.len_lines())
+        .filter_map(|&row| {
+            let line = rope.line(row);
+            let indent = line
+                .chars()
+                .take_while(|ch| *ch == ' ' || *ch == '\t')
+                .count();
+            let rest: String = line.chars().skip(indent).collect();
+            let rest = rest.trim_end_matches(['\n', '\r']);
+            (!rest.is_empty()).then(|| (rope.line_to_char(row), indent, rest.to_string()))
+        })
+        .collect::<Vec<_>>();
+    if lines.is_empty() {
+        return Vec::new();
+    }
+
+    let uncomment = lines.iter().any(|(_, _, rest)| rest.starts_with(token));
+    let mut edits = Vec::with_capacity(lines.len());
+    if uncomment {
+        for (line_start, indent, rest) in &lines {
+            let Some(after) = rest.strip_prefix(token) else {
+                continue;
+            };
+            let mut len = token.chars().count();
+            if after.starts_with(' ') {
+                len += 1;
+            }
+            let start = line_start + indent;
+            let deleted: String = rope.slice(start..start + len).chars().collect();
+            edits.push(BatchEdit {
+                start,
+                end: start + len,
+                deleted: deleted.into(),
+                inserted: Default::default(),
+            });
+        }
+    } else {
+        let indent = lines
+            .iter()
+            .map(|(_, indent, _)| *indent)
+            .min()
+            .unwrap_or(0);
+        let inserted = format!("{token} ");
+        for (line_start, _, _) in &lines {
+            let start = line_start + indent;
+            edits.push(BatchEdit {
+                start,
+                end: start,
+                deleted: Default::default(),
+                inserted: inserted.as_str().into(),
+            });
+        }
+    }
+    edits.reverse();
+    edits
+}
+
+/// 选区覆盖的行；选区止于下一行行首时不算那一行。
+pub(super) fn selection_rows(
+    cursor: (usize, usize),
+    selection: Option<&Selection>,
+) -> (usize, usize) {
+    let Some(selection) = selection.filter(|s| !s.is_empty()) else {
+        return (cursor.0, cursor.0);
+    };
+    let (start, end) = if selection.anchor() <= selection.cursor() {
+        (selection.anchor(), selection.cursor())
+    } else {
+        (selection.cursor(), selection.anchor())
+    };
+    let last = if end.1 == 0 && end.0 > start.0 {
+        end.0 - 1
+    } else {
+        end.0
+    };
+    (start.0, last)
+}
+
+impl EditorTabState {
+    /// 在所有光标 / 选区覆盖的行上切换行注释；语言没有行注释或全是空白行时不动。
+    pub(super) fn toggle_line_comment(&mut self, tab_size: u8) -> bool {
+        let Some(token) = self.language().and_then(line_comment_token) else {
+            return false;
+        };
+
+        let mut rows = Vec::new();
+        let mut push_rows = |(first, last): (usize, usize)| rows.extend(first..=last);
+        push_rows(selection_rows(
+            self.buffer.cursor(),
+            self.buffer.selection(),
+        ));
+        for cursor in &self.secondary_cursors {
+            push_rows(selection_rows(cursor.pos, cursor.selection.as_ref()));
+        }
+        rows.sort_unstable();
+        rows.dedup();
+
+        let edits = toggle_line_comment_edits(self.buffer.rope(), &rows, token);
+        self.apply_batch_edits(edits, tab_size)
+    }
+}
+
+#[cfg(test)]
+#[path = "../../../tests/unit/kernel/editor/comment.rs"]
+mod tests;
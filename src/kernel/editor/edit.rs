@@ -3,15 +3,20 @@ use crate::kernel::language::adapter_for;
 use crate::kernel::language::IndentUnit;
 use crate::kernel::services::ports::EditorConfig;
 use crate::kernel::services::ports::Match;
+use crate::kernel::services::ports::TabType;
 use crate::models::cursor_set;
 use crate::models::edit_op::BatchEdit;
 use crate::models::{
     slice_to_cow, EditOp, Granularity, OpId, OpKind, SecondaryCursor, Selection, TextBuffer,
 };
 use compact_str::CompactString;
+use ropey::Rope;
+use std::borrow::Cow;
+use tree_sitter::Tree;
 use unicode_segmentation::UnicodeSegmentation;
 
 use super::state::EditorTabState;
+use super::syntax::IndentRules;
 use super::viewport;
 
 #[derive(Default)]
@@ -60,7 +65,7 @@ fn empty_pair_replace_plan(
     cursor: (usize, usize),
     open: &str,
     close: &str,
-    indent: IndentUnit,
+    indent_str: &str,
 ) -> Option<EmptyPairReplacePlan> {
     let (row, col) = cursor;
     let slice = buffer.line_slice(row)?;
@@ -98,8 +103,7 @@ fn empty_pair_replace_plan(
         .unwrap_or(line.len());
     let base_indent = &line[..indent_end];
     let base_indent_chars = base_indent.chars().count();
-    let indent_str = indent.as_str();
-    // 缩进单位是 1~2 个 `\t`，每个 Tab 占一个 grapheme 列。
+    // 缩进单位全是 `\t` 或全是空格，每个字符占一个 grapheme 列。
     let indent_cols = indent_str.chars().count();
 
     let mut inserted =
@@ -205,6 +209,75 @@ fn apply_edit_to_records(records: &mut [CursorEditRecord], current: usize, op: &
     }
 }
 
+/// 行排序：所有行（去掉首尾空白后）都能解析为数字时按数值，否则按字典序；相等的行保持原顺序。
+/// 按语法树推算在 `char_offset` 处换行后新行的缩进。找包住光标的最内层缩进节点：
+/// 光标后紧跟它的闭合符时新行与节点起始行对齐；节点从本行开始时多缩进一级 `unit`。
+/// 其余情况（续行、树里有错误节点）返回 `None`，交给按行尾后缀的规则。
+pub(crate) fn smart_indent(
+    rope: &Rope,
+    tree: &Tree,
+    rules: &IndentRules,
+    char_offset: usize,
+    unit: &str,
+) -> Option<String> {
+    let byte = rope.char_to_byte(char_offset);
+    let mut node = tree.root_node().descendant_for_byte_range(byte, byte)?;
+    let anchor = loop {
+        if node.is_error() {
+            return None;
+        }
+        // 缺闭合符的节点（语法树补了 MISSING）在光标处结束，也算包住光标。
+        let unclosed = node
+            .child(node.child_count().saturating_sub(1))
+            .is_some_and(|last| last.is_missing());
+        let contains = node.start_byte() < byte
+            && (byte < node.end_byte() || (unclosed && byte == node.end_byte()));
+        if contains && rules.indent_nodes.contains(&node.kind()) {
+            break node;
+        }
+        node = node.parent()?;
+    };
+
+    let anchor_row = anchor.start_position().row;
+    let mut indent: String = rope
+        .line(anchor_row)
+        .chars()
+        .take_while(|c| matches!(c, ' ' | '\t'))
+        .collect();
+
+    let closing_char = char_offset
+        + rope
+            .chars_at(char_offset)
+            .take_while(|c| matches!(c, ' ' | '\t'))
+            .count();
+    let closes_anchor = matches!(rope.get_char(closing_char), Some('}' | ')' | ']'))
+        && rope.char_to_byte(closing_char) + 1 == anchor.end_byte();
+    if closes_anchor {
+        return Some(indent);
+    }
+    if anchor_row != rope.char_to_line(char_offset) {
+        return None;
+    }
+    indent.push_str(unit);
+    Some(indent)
+}
+
+fn sort_line_texts(lines: &[String], descending: bool) -> Vec<String> {
+    let numbers: Option<Vec<f64>> = lines
+        .iter()
+        .map(|line| line.trim().parse::<f64>().ok())
+        .collect();
+    let mut order: Vec<usize> = (0..lines.len()).collect();
+    match numbers {
+        Some(numbers) => order.sort_by(|&a, &b| numbers[a].total_cmp(&numbers[b])),
+        None => order.sort_by(|&a, &b| lines[a].cmp(&lines[b])),
+    }
+    if descending {
+        order.reverse();
+    }
+    order.into_iter().map(|i| lines[i].clone()).collect()
+}
+
 fn is_word_boundary_char(c: char) -> bool {
     c.is_ascii_punctuation()
         || matches!(
@@ -292,10 +365,30 @@ impl EditorTabState {
             Command::Copy => self.copy(),
             Command::Cut => self.cut(config),
             Command::Paste => (false, vec![Effect::RequestClipboardText { pane }]),
+            Command::PastePrevious => (
+                false,
+                vec![Effect::CycleClipboardHistory { pane, delta: 1 }],
+            ),
+            Command::PasteNext => (
+                false,
+                vec![Effect::CycleClipboardHistory { pane, delta: -1 }],
+            ),
             Command::EditorFoldToggle | Command::EditorFold | Command::EditorUnfold => {
                 let changed = self.execute(command, config);
                 (changed, Vec::new())
             }
+            Command::ToggleLineComment => {
+                let changed = self.toggle_line_comment(tab_size);
+                (changed, Vec::new())
+            }
+            Command::DuplicateLine => {
+                let changed = self.duplicate_line(tab_size);
+                (changed, Vec::new())
+            }
+            Command::SortLines { descending } => {
+                let changed = self.sort_lines(descending, tab_size);
+                (changed, Vec::new())
+            }
             cmd if cmd.is_cursor_command() => {
                 if self.is_multi_cursor() {
                     let changed = self.execute_on_all_cursors(cmd, config);
@@ -818,11 +911,11 @@ impl EditorTabState {
                     self.buffer.clear_selection();
                 }
 
-                if !had_selection && self.try_skip_closing(c, tab_size) {
+                if config.auto_pairs && !had_selection && self.try_skip_closing(c, tab_size) {
                     return DryExecution { changed: true, ops };
                 }
 
-                if config.auto_indent && !self.in_string_or_comment() {
+                if config.auto_pairs && !had_selection && !self.in_string_or_comment() {
                     if let Some(close) = self.editing_policy().auto_pair_closing_for(c) {
                         let op = self.insert_pair_op(c, close, parent);
                         ops.push(op);
@@ -837,7 +930,7 @@ impl EditorTabState {
             }
             Command::InsertNewline => {
                 if config.auto_indent && !self.in_string_or_comment() {
-                    if let Some(op) = self.expand_electric_enter_op(parent) {
+                    if let Some(op) = self.expand_electric_enter_op(parent, tab_size) {
                         ops.push(op);
                         self.reset_cursor_goal_col();
                         return DryExecution { changed: true, ops };
@@ -852,7 +945,7 @@ impl EditorTabState {
                 }
 
                 let op = if config.auto_indent {
-                    self.insert_newline_with_indent_op(parent)
+                    self.insert_newline_with_indent_op(parent, tab_size)
                 } else {
                     self.buffer.insert_char_op('\n', parent)
                 };
@@ -872,8 +965,8 @@ impl EditorTabState {
                     self.buffer.clear_selection();
                 }
 
-                let unit = self.indent_unit();
-                ops.push(self.buffer.insert_str_op(unit.as_str(), parent));
+                let unit = self.indent_str(tab_size);
+                ops.push(self.buffer.insert_str_op(&unit, parent));
                 self.reset_cursor_goal_col();
                 changed = true;
             }
@@ -884,6 +977,13 @@ impl EditorTabState {
                     changed = true;
                 } else {
                     self.buffer.clear_selection();
+                    if config.auto_pairs {
+                        if let Some(op) = self.delete_empty_pair_op(parent) {
+                            ops.push(op);
+                            self.reset_cursor_goal_col();
+                            return DryExecution { changed: true, ops };
+                        }
+                    }
                     if let Some(op) = self.buffer.delete_backward_op(parent) {
                         ops.push(op);
                         self.reset_cursor_goal_col();
@@ -1145,7 +1245,7 @@ impl EditorTabState {
                 Command::InsertNewline => {
                     if config.auto_indent && !self.in_string_or_comment_at(record.cursor_char) {
                         let cursor = self.buffer.cursor_pos_from_char_offset(record.cursor_char);
-                        if let Some(plan) = self.electric_enter_plan(cursor) {
+                        if let Some(plan) = self.electric_enter_plan(cursor, config.tab_size) {
                             return plan.start_char;
                         }
                     }
@@ -1180,9 +1280,10 @@ impl EditorTabState {
         cursor: (usize, usize),
         open: &str,
         close: &str,
+        tab_size: u8,
     ) -> Option<EmptyPairReplacePlan> {
-        let indent = self.indent_unit();
-        empty_pair_replace_plan(&self.buffer, cursor, open, close, indent)
+        let indent = self.indent_str(tab_size);
+        empty_pair_replace_plan(&self.buffer, cursor, open, close, &indent)
     }
 
     fn delete_to_line_end_op(&mut self, parent: OpId) -> Option<EditOp> {
@@ -1271,14 +1372,19 @@ impl EditorTabState {
     }
 
     fn cursor_up(&mut self, tab_size: u8) -> bool {
-        let (row, col) = self.buffer.cursor();
-        let goal_col = self.cursor_goal_col_or_current();
-        let Some(prev_row) = self.prev_visible_row_before(row) else {
-            return false;
+        let prev = self.buffer.cursor();
+        let (target, goal_col) = match self.wrapped_vertical_target(true) {
+            Some(target) => target,
+            None => {
+                let goal_col = self.cursor_goal_col_or_current();
+                let Some(prev_row) = self.prev_visible_row_before(prev.0) else {
+                    return false;
+                };
+                let new_len = self.buffer.line_grapheme_len(prev_row);
+                ((prev_row, goal_col.min(new_len)), goal_col)
+            }
         };
-        let prev = (row, col);
-        let new_len = self.buffer.line_grapheme_len(prev_row);
-        self.buffer.set_cursor(prev_row, goal_col.min(new_len));
+        self.buffer.set_cursor(target.0, target.1);
         let changed = self.buffer.cursor() != prev;
         if changed {
             self.set_cursor_goal_col(goal_col);
@@ -1289,14 +1395,19 @@ impl EditorTabState {
     }
 
     fn cursor_down(&mut self, tab_size: u8) -> bool {
-        let (row, col) = self.buffer.cursor();
-        let goal_col = self.cursor_goal_col_or_current();
-        let Some(next_row) = self.next_visible_row_after(row) else {
-            return false;
+        let prev = self.buffer.cursor();
+        let (target, goal_col) = match self.wrapped_vertical_target(false) {
+            Some(target) => target,
+            None => {
+                let goal_col = self.cursor_goal_col_or_current();
+                let Some(next_row) = self.next_visible_row_after(prev.0) else {
+                    return false;
+                };
+                let new_len = self.buffer.line_grapheme_len(next_row);
+                ((next_row, goal_col.min(new_len)), goal_col)
+            }
         };
-        let prev = (row, col);
-        let new_len = self.buffer.line_grapheme_len(next_row);
-        self.buffer.set_cursor(next_row, goal_col.min(new_len));
+        self.buffer.set_cursor(target.0, target.1);
         let changed = self.buffer.cursor() != prev;
         if changed {
             self.set_cursor_goal_col(goal_col);
@@ -1306,6 +1417,48 @@ impl EditorTabState {
         changed
     }
 
+    /// 软换行下按显示行上下移动：目标位置与目标列（段内偏移）。
+    /// 不在换行模式、已到首尾或映射里找不到对应行时返回 `None`，交给按逻辑行移动处理。
+    fn wrapped_vertical_target(&self, up: bool) -> Option<((usize, usize), usize)> {
+        if !self.viewport.word_wrap {
+            return None;
+        }
+        let (row, col) = self.buffer.cursor();
+        let map = &self.viewport.display_to_logical;
+        let idx = self.viewport.display_row_of((row, col))?;
+        let goal_col = self.cursor_goal_col.unwrap_or(col - map[idx].1);
+
+        let target = if up {
+            match idx.checked_sub(1).filter(|&i| map[i].0 == row) {
+                Some(i) => i,
+                None => {
+                    let prev_row = self.prev_visible_row_before(row)?;
+                    self.viewport
+                        .first_display_row_of(prev_row + 1)
+                        .checked_sub(1)
+                        .filter(|&i| map[i].0 == prev_row)?
+                }
+            }
+        } else {
+            match map.get(idx + 1).filter(|(r, _)| *r == row) {
+                Some(_) => idx + 1,
+                None => {
+                    let next_row = self.next_visible_row_after(row)?;
+                    let i = self.viewport.first_display_row_of(next_row);
+                    map.get(i).filter(|(r, _)| *r == next_row)?;
+                    i
+                }
+            }
+        };
+
+        let (target_row, start) = map[target];
+        let end = match self.viewport.wrap_segment_end(target) {
+            Some(next_start) => next_start - 1,
+            None => self.buffer.line_grapheme_len(target_row),
+        };
+        Some(((target_row, (start + goal_col).min(end)), goal_col))
+    }
+
     fn cursor_word_left(&mut self, tab_size: u8) -> bool {
         let (row, col) = self.buffer.cursor();
         let prev = (row, col);
@@ -1671,6 +1824,127 @@ impl EditorTabState {
         self.commit_op(op, tab_size);
     }
 
+    /// 以单个 `OpKind::Batch` 把 `[start_char, end_char)` 替换为 `text`，光标落在插入末尾。
+    /// snippet 展开时触发词的删除与模板插入同属这一步，撤销一次即完整还原。
+    pub(crate) fn replace_range_as_batch(
+        &mut self,
+        start_char: usize,
+        end_char: usize,
+        text: &str,
+        tab_size: u8,
+    ) -> bool {
+        let end_char = end_char.min(self.buffer.len_chars());
+        let start_char = start_char.min(end_char);
+        if start_char == end_char && text.is_empty() {
+            return false;
+        }
+
+        let parent = self.history.head();
+        let op = self
+            .buffer
+            .replace_range_op_auto_cursor(start_char, end_char, text, parent);
+        let batch_op = EditOp {
+            id: op.id,
+            parent: op.parent,
+            kind: OpKind::Batch {
+                edits: vec![batch_edit_from_op(&op)],
+            },
+            cursor_before: op.cursor_before,
+            cursor_after: op.cursor_after,
+            extra_cursors_before: None,
+            extra_cursors_after: None,
+        };
+        self.commit_op(batch_op, tab_size);
+        true
+    }
+
+    /// 把光标所在行（有选区时为选区覆盖的整块行）复制一份插到其下方，作为一个 `OpKind::Insert` 提交；
+    /// 光标与选区随之移到副本上，列不变。
+    fn duplicate_line(&mut self, tab_size: u8) -> bool {
+        let (first, last) =
+            super::comment::selection_rows(self.buffer.cursor(), self.buffer.selection());
+        let rope = self.buffer.rope();
+        let start_char = rope.line_to_char(first);
+        let (insert_at, text) = if last + 1 < rope.len_lines() {
+            let end_char = rope.line_to_char(last + 1);
+            (end_char, rope.slice(start_char..end_char).to_string())
+        } else {
+            // 末行没有换行符：在行尾补一个再接上副本。
+            let end_char = rope.len_chars();
+            let line_ending = if rope.line(first).chars().any(|ch| ch == '\r') {
+                "\r\n"
+            } else {
+                "\n"
+            };
+            let block = rope.slice(start_char..end_char).to_string();
+            (end_char, format!("{line_ending}{block}"))
+        };
+
+        let shift = last - first + 1;
+        let cursor = self.buffer.cursor();
+        let selection = self.buffer.selection().cloned();
+        let cursor_after = (cursor.0 + shift, cursor.1);
+        let parent = self.history.head();
+        let op = self
+            .buffer
+            .insert_str_at_op(insert_at, &text, cursor_after, parent);
+        self.commit_op(op, tab_size);
+        self.secondary_cursors.clear();
+
+        if let Some(selection) = selection.filter(|s| !s.is_empty()) {
+            let anchor = selection.anchor();
+            let mut shifted = Selection::new((anchor.0 + shift, anchor.1), Granularity::Char);
+            shifted.update_cursor(cursor_after, self.buffer.rope());
+            self.buffer.set_selection(Some(shifted));
+        }
+        true
+    }
+
+    /// 对选区覆盖的行排序（全是数字时按数值，否则按字典序），整块作为一个 `OpKind::Replace` 提交，
+    /// 撤销一次即还原；排序后重新选中这些行。
+    fn sort_lines(&mut self, descending: bool, tab_size: u8) -> bool {
+        let (first, last) =
+            super::comment::selection_rows(self.buffer.cursor(), self.buffer.selection());
+        if first >= last {
+            return false;
+        }
+
+        let rope = self.buffer.rope();
+        let lines: Vec<String> = (first..=last)
+            .map(|row| {
+                let line: String = rope.line(row).chars().collect();
+                line.trim_end_matches(['\n', '\r']).to_string()
+            })
+            .collect();
+        let line_ending = if rope.line(first).chars().any(|ch| ch == '\r') {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let start_char = rope.line_to_char(first);
+        let end_char = rope.line_to_char(last) + lines[lines.len() - 1].chars().count();
+
+        let sorted = sort_line_texts(&lines, descending);
+        if sorted == lines {
+            return false;
+        }
+
+        let text = sorted.join(line_ending);
+        let parent = self.history.head();
+        let op = self
+            .buffer
+            .replace_range_op_auto_cursor(start_char, end_char, &text, parent);
+        self.commit_op(op, tab_size);
+        self.secondary_cursors.clear();
+
+        let end = (last, self.buffer.line_grapheme_len(last));
+        let mut selection = Selection::new((first, 0), Granularity::Char);
+        selection.update_cursor(end, self.buffer.rope());
+        self.buffer.set_selection(Some(selection));
+        self.buffer.set_cursor(end.0, end.1);
+        true
+    }
+
     fn editing_policy(&self) -> &'static dyn crate::kernel::language::LanguageEditingPolicy {
         adapter_for(self.language()).editing()
     }
@@ -1682,7 +1956,20 @@ impl EditorTabState {
             .unwrap_or(IndentUnit::OneTab)
     }
 
-    fn electric_enter_plan(&self, cursor: (usize, usize)) -> Option<EmptyPairReplacePlan> {
+    /// 一级缩进实际插入的文本：`TabType::Spaces` 时每个 Tab 换成 `tab_size` 个空格。
+    fn indent_str(&self, tab_size: u8) -> Cow<'static, str> {
+        let unit = self.indent_unit().as_str();
+        match self.tab_type {
+            TabType::Tabs => Cow::Borrowed(unit),
+            TabType::Spaces => Cow::Owned(" ".repeat(unit.len() * tab_size.max(1) as usize)),
+        }
+    }
+
+    fn electric_enter_plan(
+        &self,
+        cursor: (usize, usize),
+        tab_size: u8,
+    ) -> Option<EmptyPairReplacePlan> {
         self.editing_policy()
             .delimiter_rules()
             .iter()
@@ -1694,11 +1981,12 @@ impl EditorTabState {
                     cursor,
                     rule.open.encode_utf8(&mut open_buf),
                     rule.close.encode_utf8(&mut close_buf),
+                    tab_size,
                 )
             })
     }
 
-    fn expand_electric_enter_op(&mut self, parent: OpId) -> Option<EditOp> {
+    fn expand_electric_enter_op(&mut self, parent: OpId, tab_size: u8) -> Option<EditOp> {
         for rule in self
             .editing_policy()
             .delimiter_rules()
@@ -1711,6 +1999,7 @@ impl EditorTabState {
                 rule.open.encode_utf8(&mut open_buf),
                 rule.close.encode_utf8(&mut close_buf),
                 parent,
+                tab_size,
             ) {
                 return Some(op);
             }
@@ -1738,6 +2027,26 @@ impl EditorTabState {
         self.cursor_right(tab_size)
     }
 
+    /// 光标夹在刚补全的空括号 / 引号对中间（`(|)`）时，Backspace 连同右侧闭合符一起删。
+    fn delete_empty_pair_op(&mut self, parent: OpId) -> Option<EditOp> {
+        let cursor_char_offset = self.buffer.cursor_char_offset();
+        let rope = self.buffer.rope();
+        if cursor_char_offset == 0 || cursor_char_offset >= rope.len_chars() {
+            return None;
+        }
+        let open = rope.char(cursor_char_offset - 1);
+        let close = rope.char(cursor_char_offset);
+        if self.editing_policy().auto_pair_closing_for(open) != Some(close) {
+            return None;
+        }
+        Some(self.buffer.replace_range_op_auto_cursor(
+            cursor_char_offset - 1,
+            cursor_char_offset + 1,
+            "",
+            parent,
+        ))
+    }
+
     fn insert_pair_op(&mut self, open: char, close: char, parent: OpId) -> EditOp {
         let (row, col) = self.buffer.cursor();
         let cursor_char_offset = self.buffer.cursor_char_offset();
@@ -1754,10 +2063,31 @@ impl EditorTabState {
         )
     }
 
-    fn insert_newline_with_indent_op(&mut self, parent: OpId) -> EditOp {
+    fn insert_newline_with_indent_op(&mut self, parent: OpId, tab_size: u8) -> EditOp {
         let row = self.buffer.cursor().0;
         let cursor_char_offset = self.buffer.cursor_char_offset();
         let in_string_or_comment = self.in_string_or_comment();
+
+        if !in_string_or_comment {
+            let unit = self.indent_str(tab_size);
+            let smart = self.syntax().and_then(|syntax| {
+                let rules = syntax.indent_rules()?;
+                smart_indent(
+                    self.buffer.rope(),
+                    syntax.tree(),
+                    rules,
+                    cursor_char_offset,
+                    &unit,
+                )
+            });
+            if let Some(indent) = smart {
+                let mut text = String::with_capacity(1 + indent.len());
+                text.push('\n');
+                text.push_str(&indent);
+                return self.buffer.insert_str_op(&text, parent);
+            }
+        }
+
         let rope = self.buffer.rope();
         let line_start = rope.line_to_char(row);
         let before_cursor = slice_to_cow(rope.slice(line_start..cursor_char_offset));
@@ -1776,8 +2106,8 @@ impl EditorTabState {
         if !in_string_or_comment {
             let extra_levels = self.editing_policy().newline_indent_extra_levels(trimmed);
             if extra_levels > 0 {
-                let unit = self.indent_unit();
-                indent.push_str(&unit.as_str().repeat(extra_levels as usize));
+                let unit = self.indent_str(tab_size);
+                indent.push_str(&unit.repeat(extra_levels as usize));
             }
         }
 
@@ -1788,14 +2118,20 @@ impl EditorTabState {
         self.buffer.insert_str_op(&text, parent)
     }
 
-    fn expand_empty_pair_op(&mut self, open: &str, close: &str, parent: OpId) -> Option<EditOp> {
+    fn expand_empty_pair_op(
+        &mut self,
+        open: &str,
+        close: &str,
+        parent: OpId,
+        tab_size: u8,
+    ) -> Option<EditOp> {
         if self.buffer.has_selection() {
             return None;
         }
 
-        let indent = self.indent_unit();
+        let indent = self.indent_str(tab_size);
         let (row, col) = self.buffer.cursor();
-        let plan = empty_pair_replace_plan(&self.buffer, (row, col), open, close, indent)?;
+        let plan = empty_pair_replace_plan(&self.buffer, (row, col), open, close, &indent)?;
         Some(self.buffer.replace_range_op(
             plan.start_char,
             plan.end_char,
@@ -1,7 +1,9 @@
 //! Editor domain: headless state + actions.
 
 mod action;
+mod batch_edit;
 mod bracket;
+mod comment;
 mod edit;
 mod mouse;
 mod reducer;
@@ -9,19 +11,25 @@ mod search;
 mod state;
 pub(crate) mod syntax;
 mod syntax_highlight_cache;
+mod unsaved_diff;
 mod viewport;
+mod vim;
 
 pub use crate::kernel::language::LanguageId;
 pub use action::EditorAction;
+pub use comment::line_comment_token;
 pub(crate) use state::SnippetTabstop;
 pub use state::{
-    DiskSnapshot, DiskState, EditorPaneState, EditorState, EditorTabState, EditorViewportState,
-    ReloadCause, ReloadRequest, SearchBarField, SearchBarMode, SearchBarState, TabId,
+    DiskSnapshot, DiskState, DisplayRow, EditorPaneState, EditorState, EditorTabState,
+    EditorViewportState, PartialLoadState, ReloadCause, ReloadRequest, SearchBarField,
+    SearchBarMode, SearchBarState, TabId, LARGE_FILE_CHUNK_BYTES,
 };
 pub(crate) use syntax::compute_highlight_patches;
 pub use syntax::{
-    highlight_snippet, HighlightKind, HighlightSpan, SyntaxColorGroup, SyntaxHighlightPatch,
-    DEFAULT_CONFIGURABLE_SYNTAX_RGB_HEX,
+    highlight_snippet, Breadcrumb, HighlightKind, HighlightSpan, SyntaxColorGroup,
+    SyntaxHighlightPatch, DEFAULT_CONFIGURABLE_SYNTAX_RGB_HEX,
 };
+pub use unsaved_diff::GutterMarkKind;
 pub(crate) use viewport::clamp_and_follow;
-pub use viewport::cursor_display_x_abs;
+pub use viewport::{cursor_display_x_abs, display_x_abs};
+pub use vim::{EditorMode, VimOperator, VimPending};
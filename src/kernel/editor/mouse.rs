@@ -56,6 +56,61 @@ impl EditorTabState {
         true
     }
 
+    /// 列选区（Alt+拖拽）：`anchor` 与 `cursor` 两角按显示列围成矩形，切到每一行成为一个光标，
+    /// 行内选中矩形覆盖的部分；之后的输入、删除都按多光标逐行进行。
+    /// 矩形有宽度时跳过够不着左边的短行；主光标留在 `cursor` 所在行。
+    pub fn select_block(
+        &mut self,
+        anchor: (usize, usize),
+        cursor: (usize, usize),
+        tab_size: u8,
+    ) -> bool {
+        self.cancel_snippet_session();
+        self.viewport.follow_cursor = true;
+
+        let last_row = self.buffer.len_lines().saturating_sub(1);
+        let anchor_row = anchor.0.min(last_row);
+        let cursor_row = cursor.0.min(last_row);
+        let anchor_x = viewport::display_x_abs(&self.buffer, anchor_row, anchor.1, tab_size);
+        let cursor_x = viewport::display_x_abs(&self.buffer, cursor_row, cursor.1, tab_size);
+        let left_x = anchor_x.min(cursor_x);
+
+        let mut cursors: Vec<SecondaryCursor> = Vec::new();
+        for row in anchor_row.min(cursor_row)..=anchor_row.max(cursor_row) {
+            if anchor_x != cursor_x
+                && row != cursor_row
+                && viewport::line_display_width(&self.buffer, row, tab_size) < left_x
+            {
+                continue;
+            }
+            let from = viewport::col_at_display_x_abs(&self.buffer, row, anchor_x, tab_size);
+            let to = viewport::col_at_display_x_abs(&self.buffer, row, cursor_x, tab_size);
+            let selection = (from != to).then(|| {
+                let mut selection = Selection::new((row, from), Granularity::Char);
+                selection.update_cursor((row, to), self.buffer.rope());
+                selection
+            });
+            cursors.push(SecondaryCursor {
+                pos: (row, to),
+                selection,
+                goal_col: None,
+            });
+        }
+
+        let primary_index = cursors
+            .iter()
+            .position(|c| c.pos.0 == cursor_row)
+            .unwrap_or(0);
+        let primary = cursors.remove(primary_index);
+        self.secondary_cursors = cursors;
+        self.buffer.set_cursor(primary.pos.0, primary.pos.1);
+        self.buffer.set_selection(primary.selection);
+        self.reset_cursor_goal_col();
+
+        viewport::clamp_and_follow(&mut self.viewport, &self.buffer, tab_size);
+        true
+    }
+
     pub fn extend_selection(&mut self, row: usize, col: usize, tab_size: u8) -> bool {
         self.cancel_snippet_session();
         self.viewport.follow_cursor = true;
@@ -1,12 +1,12 @@
 use crate::core::Command;
-use crate::kernel::services::ports::SearchMessage;
+use crate::kernel::services::ports::{LspPositionEncoding, LspTextEdit, SearchMessage};
 use crate::kernel::Effect;
-use crate::models::{cursor_set, Granularity, SecondaryCursor, Selection};
+use crate::models::{cursor_set, EditHistory, Granularity, SecondaryCursor, Selection};
 
 use super::action::EditorAction;
 use super::state::{
-    DiskState, EditorPaneState, EditorState, EditorTabState, ReloadCause, ReloadRequest,
-    SearchBarMode, TabId,
+    DiskState, EditorPaneState, EditorState, EditorTabState, PartialLoadState, ReloadCause,
+    ReloadRequest, SearchBarMode, TabId, LARGE_FILE_CHUNK_BYTES,
 };
 use super::syntax_highlight_cache::AsyncSyntaxHighlightCache;
 use super::viewport;
@@ -113,6 +113,20 @@ impl EditorState {
                 path,
                 content,
             } => self.open_file(pane, path, content),
+            EditorAction::OpenFileRange {
+                pane,
+                path,
+                content,
+                loaded_bytes,
+                file_size,
+            } => self.open_file_range(pane, path, content, loaded_bytes, file_size),
+            EditorAction::AppendFileRange {
+                path,
+                start_byte,
+                end_byte,
+                file_size,
+                content,
+            } => self.append_file_range(&path, start_byte, end_byte, file_size, &content),
             EditorAction::GotoByteOffset { pane, byte_offset } => {
                 self.goto_byte_offset(pane, byte_offset)
             }
@@ -136,6 +150,12 @@ impl EditorState {
                 end_byte,
                 text,
             } => self.apply_text_edit_to_tab(pane, tab_index, start_byte, end_byte, &text),
+            EditorAction::ApplyLspEditsToTab {
+                pane,
+                tab_index,
+                edits,
+                encoding,
+            } => self.apply_lsp_edits_to_tab(pane, tab_index, &edits, encoding),
             EditorAction::ReplaceRangeChars {
                 pane,
                 start_char,
@@ -149,15 +169,29 @@ impl EditorState {
                 granularity,
             } => self.place_cursor(pane, row, col, granularity),
             EditorAction::AddCursorAt { pane, row, col } => self.add_cursor_at(pane, row, col),
+            EditorAction::SelectBlock {
+                pane,
+                anchor_row,
+                anchor_col,
+                row,
+                col,
+            } => self.select_block(pane, (anchor_row, anchor_col), (row, col)),
             EditorAction::ExtendSelection { pane, row, col } => {
                 self.extend_selection(pane, row, col)
             }
             EditorAction::EndSelectionGesture { pane } => self.end_selection_gesture(pane),
+            EditorAction::ToggleFold { pane, line } => self.toggle_fold(pane, line),
+            EditorAction::RefreshBreadcrumbs { pane } => self.refresh_breadcrumbs(pane),
+            EditorAction::SelectBreadcrumb { pane, index } => self.select_breadcrumb(pane, index),
             EditorAction::Scroll { pane, delta_lines } => self.scroll(pane, delta_lines),
             EditorAction::ScrollHorizontal {
                 pane,
                 delta_columns,
             } => self.scroll_horizontal(pane, delta_columns),
+            EditorAction::ScrollHorizontalAnimated {
+                pane,
+                delta_columns,
+            } => self.scroll_horizontal_animated(pane, delta_columns),
             EditorAction::SearchBarAppend { pane, ch } => self.search_bar_append(pane, ch),
             EditorAction::SearchBarBackspace { pane } => self.search_bar_backspace(pane),
             EditorAction::SearchBarDeleteForward { pane } => self.search_bar_delete_forward(pane),
@@ -170,6 +204,9 @@ impl EditorState {
                 self.search_bar_toggle_case_sensitive(pane)
             }
             EditorAction::SearchBarToggleRegex { pane } => self.search_bar_toggle_regex(pane),
+            EditorAction::SearchBarToggleWholeWord { pane } => {
+                self.search_bar_toggle_whole_word(pane)
+            }
             EditorAction::SearchBarToggleReplaceMode { pane } => {
                 self.search_bar_toggle_replace_mode(pane)
             }
@@ -205,9 +242,13 @@ impl EditorState {
                 content,
             } => self.accept_disk_version(pane, path, content),
             EditorAction::KeepMemoryVersion { pane } => self.keep_memory_version(pane),
+            EditorAction::RestoreEditHistory { path, history } => {
+                self.restore_edit_history(&path, history)
+            }
         };
 
         changed |= self.maybe_schedule_syntax_highlights(&mut effects);
+        changed |= self.maybe_request_file_ranges(&mut effects);
         (changed, effects)
     }
 
@@ -231,6 +272,9 @@ impl EditorState {
             Command::EditorSearchBarToggleRegex => {
                 self.dispatch_action(EditorAction::SearchBarToggleRegex { pane })
             }
+            Command::EditorSearchBarToggleWholeWord => {
+                self.dispatch_action(EditorAction::SearchBarToggleWholeWord { pane })
+            }
             Command::EditorSearchBarToggleReplaceMode => {
                 self.dispatch_action(EditorAction::SearchBarToggleReplaceMode { pane })
             }
@@ -262,6 +306,7 @@ impl EditorState {
         };
 
         changed |= self.maybe_schedule_syntax_highlights(&mut effects);
+        changed |= self.maybe_request_file_ranges(&mut effects);
         (changed, effects)
     }
 
@@ -326,6 +371,72 @@ impl EditorState {
         (changed, effects)
     }
 
+    fn open_file_range(
+        &mut self,
+        pane: usize,
+        path: std::path::PathBuf,
+        content: String,
+        loaded_bytes: u64,
+        file_size: u64,
+    ) -> (bool, Vec<Effect>) {
+        let already_open = self
+            .panes
+            .iter()
+            .flat_map(|pane| pane.tabs.iter())
+            .any(|tab| tab.path.as_ref() == Some(&path));
+        let (changed, effects) = self.open_file(pane, path.clone(), content);
+        // 已打开的标签页只是被激活，保留它现有的内容与读取进度。
+        if already_open || loaded_bytes >= file_size {
+            return (changed, effects);
+        }
+        if let Some(tab) = self
+            .panes
+            .get_mut(pane)
+            .and_then(|pane_state| pane_state.active_tab_mut())
+            .filter(|tab| tab.path.as_ref() == Some(&path))
+        {
+            tab.partial_load = Some(PartialLoadState {
+                file_size,
+                loaded_bytes,
+                pending: false,
+                save_when_loaded: false,
+            });
+        }
+        (changed, effects)
+    }
+
+    fn append_file_range(
+        &mut self,
+        path: &std::path::Path,
+        start_byte: u64,
+        end_byte: u64,
+        file_size: u64,
+        content: &str,
+    ) -> (bool, Vec<Effect>) {
+        let tab_size = self.config.tab_size;
+        let mut changed = false;
+        let mut saves = Vec::new();
+        for (pane, pane_state) in self.panes.iter_mut().enumerate() {
+            for (index, tab) in pane_state.tabs.iter_mut().enumerate() {
+                if tab.path.as_deref() == Some(path) {
+                    let save_when_loaded = tab.partial_load.is_some_and(|p| p.save_when_loaded);
+                    changed |=
+                        tab.append_loaded_range(start_byte, end_byte, file_size, content, tab_size);
+                    if save_when_loaded && tab.partial_load.is_none() {
+                        saves.push((pane, index));
+                    }
+                }
+            }
+        }
+        let mut effects = Vec::new();
+        for (pane, index) in saves {
+            let (saved_changed, save_effects) = self.save_tab_at(pane, index);
+            changed |= saved_changed;
+            effects.extend(save_effects);
+        }
+        (changed, effects)
+    }
+
     fn goto_byte_offset(&mut self, pane: usize, byte_offset: usize) -> (bool, Vec<Effect>) {
         let tab_size = self.config.tab_size;
         let Some(pane_state) = self.panes.get_mut(pane) else {
@@ -751,16 +862,51 @@ impl EditorState {
         let Some(pane_state) = self.panes.get(pane) else {
             return (false, Vec::new());
         };
-        let Some(tab) = pane_state.active_tab() else {
+        self.save_tab_at(pane, pane_state.active)
+    }
+
+    /// 保存指定 tab（不要求是活动 tab），供保存前格式化返回后补写盘。
+    pub fn save_tab_at(&mut self, pane: usize, index: usize) -> (bool, Vec<Effect>) {
+        let Some(tab) = self.panes.get(pane).and_then(|pane| pane.tabs.get(index)) else {
             return (false, Vec::new());
         };
         let Some(path) = tab.path.clone() else {
             return (false, Vec::new());
         };
+        // 只读入了开头一段时整体写回会截断文件：先把剩下的块读完，读完再写盘。
+        if tab.partial_load.is_some() {
+            if let Some(partial) = self.panes[pane].tabs[index].partial_load.as_mut() {
+                partial.save_when_loaded = true;
+            }
+            let mut effects = Vec::new();
+            self.maybe_request_file_ranges(&mut effects);
+            return (true, effects);
+        }
+        let tab_size = self.config.tab_size;
+        let tab = &mut self.panes[pane].tabs[index];
+        let normalized = tab.normalize_line_endings(tab_size);
         let version = tab.edit_version;
         let head = tab.history.head();
+        let rope = tab.buffer.rope();
+        if rope.len_bytes() >= self.config.partial_write_threshold_bytes {
+            if let Some(dirty) = tab.history.dirty_byte_range(rope) {
+                let replacement = rope.byte_slice(dirty.buffer.clone()).to_string();
+                return (
+                    normalized,
+                    vec![Effect::WriteFilePart {
+                        pane,
+                        path,
+                        byte_range: dirty.disk,
+                        replacement,
+                        disk_len: dirty.disk_len,
+                        version,
+                        head,
+                    }],
+                );
+            }
+        }
         (
-            false,
+            normalized,
             vec![Effect::WriteFile {
                 pane,
                 path,
@@ -827,6 +973,24 @@ impl EditorState {
         Self::apply_text_edit_to_tab_state(tab_size, tab, start_byte, end_byte, text)
     }
 
+    fn apply_lsp_edits_to_tab(
+        &mut self,
+        pane: usize,
+        tab_index: usize,
+        edits: &[LspTextEdit],
+        encoding: LspPositionEncoding,
+    ) -> (bool, Vec<Effect>) {
+        let tab_size = self.config.tab_size;
+        let Some(tab) = self
+            .panes
+            .get_mut(pane)
+            .and_then(|pane_state| pane_state.tabs.get_mut(tab_index))
+        else {
+            return (false, Vec::new());
+        };
+        (tab.apply_lsp_edits(edits, encoding, tab_size), Vec::new())
+    }
+
     fn apply_text_edit_to_tab_state(
         tab_size: u8,
         tab: &mut super::state::EditorTabState,
@@ -971,6 +1135,23 @@ impl EditorState {
         (changed, Vec::new())
     }
 
+    fn select_block(
+        &mut self,
+        pane: usize,
+        anchor: (usize, usize),
+        cursor: (usize, usize),
+    ) -> (bool, Vec<Effect>) {
+        let tab_size = self.config.tab_size;
+        let Some(pane_state) = self.panes.get_mut(pane) else {
+            return (false, Vec::new());
+        };
+        let Some(tab) = pane_state.active_tab_mut() else {
+            return (false, Vec::new());
+        };
+        let changed = tab.select_block(anchor, cursor, tab_size);
+        (changed, Vec::new())
+    }
+
     fn extend_selection(&mut self, pane: usize, row: usize, col: usize) -> (bool, Vec<Effect>) {
         let tab_size = self.config.tab_size;
         let Some(pane_state) = self.panes.get_mut(pane) else {
@@ -983,6 +1164,41 @@ impl EditorState {
         (changed, Vec::new())
     }
 
+    fn toggle_fold(&mut self, pane: usize, line: usize) -> (bool, Vec<Effect>) {
+        let tab_size = self.config.tab_size;
+        let Some(pane_state) = self.panes.get_mut(pane) else {
+            return (false, Vec::new());
+        };
+        let Some(tab) = pane_state.active_tab_mut() else {
+            return (false, Vec::new());
+        };
+        let changed = tab.fold_toggle_at_line(line, tab_size);
+        (changed, Vec::new())
+    }
+
+    fn refresh_breadcrumbs(&mut self, pane: usize) -> (bool, Vec<Effect>) {
+        let Some(tab) = self
+            .panes
+            .get_mut(pane)
+            .and_then(|pane_state| pane_state.active_tab_mut())
+        else {
+            return (false, Vec::new());
+        };
+        (tab.refresh_breadcrumbs(), Vec::new())
+    }
+
+    fn select_breadcrumb(&mut self, pane: usize, index: usize) -> (bool, Vec<Effect>) {
+        let tab_size = self.config.tab_size;
+        let Some(tab) = self
+            .panes
+            .get_mut(pane)
+            .and_then(|pane_state| pane_state.active_tab_mut())
+        else {
+            return (false, Vec::new());
+        };
+        (tab.select_breadcrumb(index, tab_size), Vec::new())
+    }
+
     fn end_selection_gesture(&mut self, pane: usize) -> (bool, Vec<Effect>) {
         let Some(pane_state) = self.panes.get_mut(pane) else {
             return (false, Vec::new());
@@ -1025,6 +1241,7 @@ impl EditorState {
     }
 
     fn scroll_horizontal(&mut self, pane: usize, delta_columns: isize) -> (bool, Vec<Effect>) {
+        let tab_size = self.config.tab_size;
         let Some(pane_state) = self.panes.get_mut(pane) else {
             return (false, Vec::new());
         };
@@ -1037,16 +1254,9 @@ impl EditorState {
         }
 
         tab.viewport.follow_cursor = false;
+        tab.horiz_scroll_animation = None;
         let prev = tab.viewport.horiz_offset;
-        let visible_lines =
-            tab.visible_lines_in_viewport(tab.viewport.line_offset, tab.viewport.height.max(1));
-        let max_visible_width = visible_lines
-            .iter()
-            .map(|&row| viewport::line_display_width(&tab.buffer, row, self.config.tab_size))
-            .max()
-            .unwrap_or(0);
-        let width = tab.viewport.width.max(1) as u32;
-        let max_offset = max_visible_width.saturating_sub(width);
+        let max_offset = tab.max_horiz_offset(tab_size);
 
         if delta_columns > 0 {
             tab.viewport.horiz_offset =
@@ -1061,6 +1271,42 @@ impl EditorState {
         (tab.viewport.horiz_offset != prev, Vec::new())
     }
 
+    fn scroll_horizontal_animated(
+        &mut self,
+        pane: usize,
+        delta_columns: isize,
+    ) -> (bool, Vec<Effect>) {
+        let tab_size = self.config.tab_size;
+        let Some(pane_state) = self.panes.get_mut(pane) else {
+            return (false, Vec::new());
+        };
+        let Some(tab) = pane_state.active_tab_mut() else {
+            return (false, Vec::new());
+        };
+
+        if delta_columns == 0 {
+            return (false, Vec::new());
+        }
+
+        tab.viewport.follow_cursor = false;
+        // 连续滚动时在上一次的目标上累加，而不是从动画中途的位置重新起算。
+        let base = tab
+            .horiz_scroll_animation
+            .map(|animation| animation.target)
+            .unwrap_or(tab.viewport.horiz_offset);
+        let max_offset = tab.max_horiz_offset(tab_size);
+        let target = if delta_columns > 0 {
+            base.saturating_add(delta_columns as u32).min(max_offset)
+        } else {
+            base.saturating_sub((-delta_columns) as u32)
+        };
+
+        let prev = tab.horiz_scroll_animation;
+        tab.horiz_scroll_animation = (target != tab.viewport.horiz_offset)
+            .then_some(viewport::AnimatedHorizontalScroll { target });
+        (tab.horiz_scroll_animation != prev, Vec::new())
+    }
+
     fn search_started(&mut self, pane: usize, search_id: u64) -> (bool, Vec<Effect>) {
         let Some(pane_state) = self.panes.get_mut(pane) else {
             return (false, Vec::new());
@@ -1125,13 +1371,21 @@ impl EditorState {
         let old_search = pane_state.search_bar.search_text.clone();
         let old_case = pane_state.search_bar.case_sensitive;
         let old_regex = pane_state.search_bar.use_regex;
+        let old_whole_word = pane_state.search_bar.whole_word;
 
         let changed = pane_state.search_bar.insert_char(ch);
         if !changed {
             return (false, Vec::new());
         }
 
-        Self::restart_search_if_needed(pane, pane_state, old_search, old_case, old_regex)
+        Self::restart_search_if_needed(
+            pane,
+            pane_state,
+            old_search,
+            old_case,
+            old_regex,
+            old_whole_word,
+        )
     }
 
     fn search_bar_backspace(&mut self, pane: usize) -> (bool, Vec<Effect>) {
@@ -1144,13 +1398,21 @@ impl EditorState {
         let old_search = pane_state.search_bar.search_text.clone();
         let old_case = pane_state.search_bar.case_sensitive;
         let old_regex = pane_state.search_bar.use_regex;
+        let old_whole_word = pane_state.search_bar.whole_word;
 
         let changed = pane_state.search_bar.delete_backward();
         if !changed {
             return (false, Vec::new());
         }
 
-        Self::restart_search_if_needed(pane, pane_state, old_search, old_case, old_regex)
+        Self::restart_search_if_needed(
+            pane,
+            pane_state,
+            old_search,
+            old_case,
+            old_regex,
+            old_whole_word,
+        )
     }
 
     fn search_bar_delete_forward(&mut self, pane: usize) -> (bool, Vec<Effect>) {
@@ -1163,13 +1425,21 @@ impl EditorState {
         let old_search = pane_state.search_bar.search_text.clone();
         let old_case = pane_state.search_bar.case_sensitive;
         let old_regex = pane_state.search_bar.use_regex;
+        let old_whole_word = pane_state.search_bar.whole_word;
 
         let changed = pane_state.search_bar.delete_forward();
         if !changed {
             return (false, Vec::new());
         }
 
-        Self::restart_search_if_needed(pane, pane_state, old_search, old_case, old_regex)
+        Self::restart_search_if_needed(
+            pane,
+            pane_state,
+            old_search,
+            old_case,
+            old_regex,
+            old_whole_word,
+        )
     }
 
     fn search_bar_cursor_left(&mut self, pane: usize) -> (bool, Vec<Effect>) {
@@ -1233,9 +1503,17 @@ impl EditorState {
         let old_search = pane_state.search_bar.search_text.clone();
         let old_case = pane_state.search_bar.case_sensitive;
         let old_regex = pane_state.search_bar.use_regex;
+        let old_whole_word = pane_state.search_bar.whole_word;
 
         pane_state.search_bar.case_sensitive = !pane_state.search_bar.case_sensitive;
-        Self::restart_search_if_needed(pane, pane_state, old_search, old_case, old_regex)
+        Self::restart_search_if_needed(
+            pane,
+            pane_state,
+            old_search,
+            old_case,
+            old_regex,
+            old_whole_word,
+        )
     }
 
     fn search_bar_toggle_regex(&mut self, pane: usize) -> (bool, Vec<Effect>) {
@@ -1249,9 +1527,41 @@ impl EditorState {
         let old_search = pane_state.search_bar.search_text.clone();
         let old_case = pane_state.search_bar.case_sensitive;
         let old_regex = pane_state.search_bar.use_regex;
+        let old_whole_word = pane_state.search_bar.whole_word;
 
         pane_state.search_bar.use_regex = !pane_state.search_bar.use_regex;
-        Self::restart_search_if_needed(pane, pane_state, old_search, old_case, old_regex)
+        Self::restart_search_if_needed(
+            pane,
+            pane_state,
+            old_search,
+            old_case,
+            old_regex,
+            old_whole_word,
+        )
+    }
+
+    fn search_bar_toggle_whole_word(&mut self, pane: usize) -> (bool, Vec<Effect>) {
+        let Some(pane_state) = self.panes.get_mut(pane) else {
+            return (false, Vec::new());
+        };
+        if !pane_state.search_bar.visible {
+            return (false, Vec::new());
+        }
+
+        let old_search = pane_state.search_bar.search_text.clone();
+        let old_case = pane_state.search_bar.case_sensitive;
+        let old_regex = pane_state.search_bar.use_regex;
+        let old_whole_word = pane_state.search_bar.whole_word;
+
+        pane_state.search_bar.whole_word = !pane_state.search_bar.whole_word;
+        Self::restart_search_if_needed(
+            pane,
+            pane_state,
+            old_search,
+            old_case,
+            old_regex,
+            old_whole_word,
+        )
     }
 
     fn search_bar_toggle_replace_mode(&mut self, pane: usize) -> (bool, Vec<Effect>) {
@@ -1270,10 +1580,12 @@ impl EditorState {
         old_search: String,
         old_case: bool,
         old_regex: bool,
+        old_whole_word: bool,
     ) -> (bool, Vec<Effect>) {
         let search_changed = pane_state.search_bar.search_text != old_search
             || pane_state.search_bar.case_sensitive != old_case
-            || pane_state.search_bar.use_regex != old_regex;
+            || pane_state.search_bar.use_regex != old_regex
+            || pane_state.search_bar.whole_word != old_whole_word;
 
         if !search_changed {
             return (true, Vec::new());
@@ -1422,6 +1734,49 @@ impl EditorState {
         (false, Vec::new())
     }
 
+    /// 未读完的大文件：活动标签页的视口或光标离已读内容末尾不足两屏时请求下一块；
+    /// 等着保存的标签页不看位置，一直读到文末。
+    fn maybe_request_file_ranges(&mut self, effects: &mut Vec<Effect>) -> bool {
+        let mut state_changed = false;
+        for pane_state in &mut self.panes {
+            let active = pane_state.active;
+            for (index, tab) in pane_state.tabs.iter_mut().enumerate() {
+                let Some(path) = tab.path.clone() else {
+                    continue;
+                };
+                let Some(partial) = tab.partial_load.as_mut() else {
+                    continue;
+                };
+                if partial.pending {
+                    continue;
+                }
+                let margin = tab.viewport.height.max(1).saturating_mul(2);
+                let near_end = index == active
+                    && tab
+                        .viewport
+                        .line_offset
+                        .max(tab.buffer.cursor().0)
+                        .saturating_add(margin)
+                        >= tab.buffer.len_lines();
+                if !near_end && !partial.save_when_loaded {
+                    continue;
+                }
+                let start_byte = partial.loaded_bytes;
+                let end_byte = start_byte
+                    .saturating_add(LARGE_FILE_CHUNK_BYTES)
+                    .min(partial.file_size);
+                partial.pending = true;
+                effects.push(Effect::LoadFileRange {
+                    path,
+                    start_byte,
+                    end_byte,
+                });
+                state_changed = true;
+            }
+        }
+        state_changed
+    }
+
     fn maybe_schedule_syntax_highlights(&mut self, effects: &mut Vec<Effect>) -> bool {
         let mut state_changed = false;
         for pane_state in &mut self.panes {
@@ -1486,6 +1841,7 @@ impl EditorState {
     }
 
     fn file_externally_modified(&mut self, path: std::path::PathBuf) -> (bool, Vec<Effect>) {
+        let auto_reload = self.config.auto_reload_on_disk_change;
         let mut changed = false;
         let mut effects = Vec::new();
         for (pane, pane_state) in self.panes.iter_mut().enumerate() {
@@ -1496,7 +1852,10 @@ impl EditorState {
                 if !paths_equivalent(tab_path.as_path(), path.as_path()) {
                     continue;
                 }
-                if !tab.dirty {
+                // 分块读入的大文件不自动整体重载，交给用户决定。
+                if !tab.dirty && (!auto_reload || tab.partial_load.is_some()) {
+                    tab.disk_state = DiskState::ChangedOnDisk;
+                } else if !tab.dirty {
                     if let Some(request) = tab.issue_reload_request(pane, ReloadCause::ExternalSync)
                     {
                         effects.push(Effect::ReloadFile(request));
@@ -1550,6 +1909,24 @@ impl EditorState {
         (true, Vec::new())
     }
 
+    fn restore_edit_history(
+        &mut self,
+        path: &std::path::Path,
+        history: EditHistory,
+    ) -> (bool, Vec<Effect>) {
+        let mut changed = false;
+        for tab in self.panes.iter_mut().flat_map(|pane| pane.tabs.iter_mut()) {
+            if tab
+                .path
+                .as_ref()
+                .is_some_and(|tab_path| paths_equivalent(tab_path, path))
+            {
+                changed |= tab.restore_edit_history(history.clone());
+            }
+        }
+        (changed, Vec::new())
+    }
+
     fn keep_memory_version(&mut self, pane: usize) -> (bool, Vec<Effect>) {
         let Some(pane_state) = self.panes.get_mut(pane) else {
             return (false, Vec::new());
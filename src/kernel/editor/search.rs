@@ -287,6 +287,7 @@ impl EditorPaneState {
             pattern: self.search_bar.search_text.clone(),
             case_sensitive: self.search_bar.case_sensitive,
             use_regex: self.search_bar.use_regex,
+            whole_word: self.search_bar.whole_word,
         })
     }
 }
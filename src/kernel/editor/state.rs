@@ -1,27 +1,83 @@
-use crate::kernel::services::ports::{EditorConfig, LspFoldingRange, Match};
+use crate::kernel::services::ports::{
+    EditorConfig, LspDocumentLink, LspFoldingRange, LspRange, Match, TabType,
+};
 use crate::models::{
-    EditHistory, EditOp, Granularity, OpId, OpKind, SecondaryCursor, Selection, TextBuffer,
+    is_word_char, scan_line_endings, stray_line_endings, EditHistory, EditOp, Granularity,
+    HistoryBackup, LineEndingStyle, OpId, OpKind, SecondaryCursor, Selection, TextBuffer,
 };
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Instant, SystemTime};
-use unicode_xid::UnicodeXID;
 
-use super::syntax::SyntaxDocument;
+use super::syntax::{Breadcrumb, SyntaxDocument};
 use super::syntax_highlight_cache::AsyncSyntaxHighlightCache;
-use super::{viewport, HighlightSpan, LanguageId};
+use super::unsaved_diff::{self, GutterMarkKind};
+use super::viewport::AnimatedHorizontalScroll;
+use super::vim::{EditorMode, VimPending};
+use super::{viewport, HighlightKind, HighlightSpan, LanguageId};
 
 type SharedSyntaxHighlightLines = Arc<Vec<Arc<Vec<HighlightSpan>>>>;
 
 #[derive(Debug, Clone)]
 pub enum DiskState {
     InSync,
-    ReloadedFromDisk { at: Instant },
+    ReloadedFromDisk {
+        at: Instant,
+    },
+    /// 未修改的标签页在磁盘上变了，但关闭了 `auto_reload_on_disk_change`，等用户确认重新加载。
+    ChangedOnDisk,
     ConflictExternalModified,
     MissingOnDisk,
 }
 
+/// 大文件分块读入时每次读取的字节数。
+pub const LARGE_FILE_CHUNK_BYTES: u64 = 4 * 1024 * 1024;
+
+fn has_stray_line_endings(
+    rope: &ropey::Rope,
+    char_range: std::ops::Range<usize>,
+    target: LineEndingStyle,
+) -> bool {
+    stray_line_endings(rope, char_range, target)
+        .next()
+        .is_some()
+}
+
+/// 自动判断缩进风格时扫描的行数。
+const TAB_TYPE_DETECT_LINES: usize = 200;
+
+/// 按前 200 行里以 Tab 开头与以至少两个空格开头的行数判断缩进风格；持平或都没有时为 `None`。
+/// 单个空格开头的行（如块注释的 ` *`）不计。
+fn detect_tab_type(content: &str) -> Option<TabType> {
+    let mut tabs = 0usize;
+    let mut spaces = 0usize;
+    for line in content.lines().take(TAB_TYPE_DETECT_LINES) {
+        if line.starts_with('\t') {
+            tabs += 1;
+        } else if line.starts_with("  ") {
+            spaces += 1;
+        }
+    }
+    match tabs.cmp(&spaces) {
+        std::cmp::Ordering::Greater => Some(TabType::Tabs),
+        std::cmp::Ordering::Less => Some(TabType::Spaces),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+/// 缓冲区只是文件开头的一段（`large_file_threshold_mb` 以上的大文件按块读入）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialLoadState {
+    pub file_size: u64,
+    /// 已读入的字节数，即下一块的起点。
+    pub loaded_bytes: u64,
+    /// 已发出 `Effect::LoadFileRange`，等待结果。
+    pub pending: bool,
+    /// 读完之前按了保存：不再等视口靠近文末，连续读完剩下的块后再写盘。
+    pub save_when_loaded: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct DiskSnapshot {
     pub modified: Option<SystemTime>,
@@ -70,6 +126,8 @@ pub struct SearchBarState {
     pub cursor_pos: usize,
     pub case_sensitive: bool,
     pub use_regex: bool,
+    /// 全词匹配：模式两侧加 `\b` 边界。
+    pub whole_word: bool,
     pub matches: Vec<Match>,
     pub current_match_index: Option<usize>,
     pub searching: bool,
@@ -88,6 +146,7 @@ impl Default for SearchBarState {
             cursor_pos: 0,
             case_sensitive: false,
             use_regex: false,
+            whole_word: false,
             matches: Vec::new(),
             current_match_index: None,
             searching: false,
@@ -97,13 +156,39 @@ impl Default for SearchBarState {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+/// 屏幕上的一个显示行：逻辑行 `line` 中 `[start_col, end_col)` 的 grapheme；
+/// `end_col` 为 `None` 表示直到行尾（不换行时即整行）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayRow {
+    pub line: usize,
+    pub start_col: usize,
+    pub end_col: Option<usize>,
+}
+
+impl DisplayRow {
+    /// 是否为该逻辑行的最后一段（行尾虚拟文本只画在这里）。
+    pub fn is_last_segment(&self) -> bool {
+        self.end_col.is_none()
+    }
+
+    /// 列 `col` 是否落在这一段；行尾列属于最后一段。
+    pub fn contains_col(&self, col: usize) -> bool {
+        col >= self.start_col && self.end_col.is_none_or(|end| col < end)
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct EditorViewportState {
     pub line_offset: usize,
     pub height: usize,
     pub horiz_offset: u32,
     pub width: usize,
     pub follow_cursor: bool,
+    /// 软换行：长行在视口右边界折成多个显示行，不再横向滚动。Markdown 标签页不换行。
+    pub word_wrap: bool,
+    /// 软换行时全篇每个显示行对应的 `(逻辑行, 段首 grapheme 列)`，按文档顺序排列；
+    /// 由 `clamp_and_follow` 在编辑、视口尺寸变化后重建，不换行时为空。
+    pub display_to_logical: Vec<(usize, usize)>,
 }
 
 impl Default for EditorViewportState {
@@ -114,6 +199,8 @@ impl Default for EditorViewportState {
             horiz_offset: 0,
             width: 80,
             follow_cursor: true,
+            word_wrap: false,
+            display_to_logical: Vec::new(),
         }
     }
 }
@@ -196,6 +283,7 @@ pub struct EditorTabState {
     pub path: Option<PathBuf>,
     pub buffer: TextBuffer,
     pub viewport: EditorViewportState,
+    pub(super) horiz_scroll_animation: Option<AnimatedHorizontalScroll>,
     pub history: EditHistory,
     pub dirty: bool,
     pub edit_version: u64,
@@ -205,10 +293,32 @@ pub struct EditorTabState {
     snippet_session: Option<SnippetSession>,
     pub disk_state: DiskState,
     pub saved_snapshot: Option<DiskSnapshot>,
+    /// `Some` 表示文件尚未读完；读完之前禁止保存。
+    pub partial_load: Option<PartialLoadState>,
+    /// 本标签页的缩进风格：打开时由 `detect_tab_type` 判断，判断不出时取 `EditorConfig::tab_type`。
+    pub tab_type: TabType,
+    /// 打开（或从磁盘重载）时检测到的换行风格，保存时全文统一成它。
+    pub line_ending: LineEndingStyle,
+    /// 从磁盘读入的内容里就有与 `line_ending` 不同的换行：下次保存要扫全文，否则只扫改过的区间。
+    pub(super) foreign_line_endings: bool,
     pub last_reload_request_id: u64,
     pub last_applied_reload_request_id: u64,
     inlay_hints: Option<InlayHintsState>,
     folding: Option<FoldingState>,
+    /// `textDocument/documentLink` 结果：`(start_byte, end_byte, target)`，编辑后整体作废。
+    pub document_links: Vec<(usize, usize, String)>,
+    /// 服务端未给出 target 的链接，点击时再走 `documentLink/resolve`。
+    pub(crate) unresolved_document_links: Vec<(usize, usize, LspDocumentLink)>,
+    /// `textDocument/documentHighlight` 结果，对应当前 `edit_version`；列为行内 UTF-8 字节偏移，编辑后清空。
+    pub document_highlights: Vec<LspRange>,
+    /// 光标处由外到内的符号作用域链，光标停下后才刷新（见 `refresh_breadcrumbs`）。
+    pub breadcrumbs: Vec<Breadcrumb>,
+    /// `breadcrumbs` 对应的 `(cursor, edit_version)`，与当前不符即过期。
+    breadcrumbs_key: Option<((usize, usize), u64)>,
+    /// 相对已保存内容的行级改动标记，按行号升序；由 `Store::tick` 按需重算。
+    unsaved_marks: Vec<(usize, GutterMarkKind)>,
+    /// `unsaved_marks` 对应的 `(edit_version, head, saved_head)`，不变时跳过重算。
+    unsaved_marks_key: Option<(u64, OpId, OpId)>,
     syntax: Option<SyntaxDocument>,
     pub(super) syntax_highlight_cache: Option<AsyncSyntaxHighlightCache>,
     pub(super) syntax_highlight_last_requested_version: u64,
@@ -240,8 +350,10 @@ impl EditorTabState {
             buffer,
             viewport: EditorViewportState {
                 height: config.default_viewport_height,
+                word_wrap: config.word_wrap,
                 ..EditorViewportState::default()
             },
+            horiz_scroll_animation: None,
             history,
             dirty: false,
             edit_version: 0,
@@ -251,10 +363,21 @@ impl EditorTabState {
             snippet_session: None,
             disk_state: DiskState::InSync,
             saved_snapshot: None,
+            partial_load: None,
+            tab_type: config.tab_type,
+            line_ending: config.default_line_ending,
+            foreign_line_endings: false,
             last_reload_request_id: 0,
             last_applied_reload_request_id: 0,
             inlay_hints: None,
             folding: None,
+            document_links: Vec::new(),
+            unresolved_document_links: Vec::new(),
+            document_highlights: Vec::new(),
+            breadcrumbs: Vec::new(),
+            breadcrumbs_key: None,
+            unsaved_marks: Vec::new(),
+            unsaved_marks_key: None,
             syntax: None,
             syntax_highlight_cache: None,
             syntax_highlight_last_requested_version: u64::MAX,
@@ -270,13 +393,16 @@ impl EditorTabState {
             .unwrap_or_else(|| "Untitled".to_string());
 
         let buffer = TextBuffer::from_text(content);
+        let line_ending = scan_line_endings(buffer.rope()).unwrap_or(config.default_line_ending);
+        let foreign_line_endings =
+            has_stray_line_endings(buffer.rope(), 0..buffer.len_chars(), line_ending);
         let history = EditHistory::new();
         let syntax = SyntaxDocument::for_path(&path, buffer.rope());
         let syntax_highlight_cache = syntax
             .as_ref()
             .map(|_| AsyncSyntaxHighlightCache::new_for_rope(buffer.rope()));
 
-        Self {
+        let mut tab = Self {
             id,
             title,
             path: Some(path),
@@ -285,6 +411,7 @@ impl EditorTabState {
                 height: config.default_viewport_height,
                 ..EditorViewportState::default()
             },
+            horiz_scroll_animation: None,
             history,
             dirty: false,
             edit_version: 0,
@@ -294,16 +421,35 @@ impl EditorTabState {
             snippet_session: None,
             disk_state: DiskState::InSync,
             saved_snapshot: None,
+            partial_load: None,
+            tab_type: detect_tab_type(content).unwrap_or(config.tab_type),
+            line_ending,
+            foreign_line_endings,
             last_reload_request_id: 0,
             last_applied_reload_request_id: 0,
             inlay_hints: None,
             folding: None,
+            document_links: Vec::new(),
+            unresolved_document_links: Vec::new(),
+            document_highlights: Vec::new(),
+            breadcrumbs: Vec::new(),
+            breadcrumbs_key: None,
+            unsaved_marks: Vec::new(),
+            unsaved_marks_key: None,
             syntax,
             syntax_highlight_cache,
             syntax_highlight_last_requested_version: u64::MAX,
             syntax_highlight_inflight_version: None,
             syntax_highlight_pending_version: None,
-        }
+        };
+        tab.set_word_wrap(config.word_wrap, config.tab_size);
+        tab
+    }
+
+    /// 开关软换行（Markdown 标签页有自己的所见即所得排版，始终不换行）并重建显示行映射。
+    pub(crate) fn set_word_wrap(&mut self, enabled: bool, tab_size: u8) {
+        self.viewport.word_wrap = enabled && !self.is_markdown();
+        viewport::clamp_and_follow(&mut self.viewport, &self.buffer, tab_size);
     }
 
     pub fn set_path(&mut self, path: PathBuf) {
@@ -322,6 +468,8 @@ impl EditorTabState {
         self.syntax_highlight_pending_version = None;
         self.inlay_hints = None;
         self.clear_folding();
+        self.clear_document_links();
+        self.document_highlights.clear();
         self.snippet_session = None;
     }
 
@@ -454,11 +602,16 @@ impl EditorTabState {
     }
 
     fn apply_snippet_tabstop(&mut self, tabstop: SnippetTabstop, tab_size: u8) {
+        self.select_char_range(tabstop.start, tabstop.end, tab_size);
+    }
+
+    /// 选中 `[start_char, end_char)`（空区间时只放光标）并让视口跟随。
+    fn select_char_range(&mut self, start_char: usize, end_char: usize, tab_size: u8) {
         self.viewport.follow_cursor = true;
 
         let len_chars = self.buffer.len_chars();
-        let start_char = tabstop.start.min(len_chars);
-        let end_char = tabstop.end.min(len_chars).max(start_char);
+        let start_char = start_char.min(len_chars);
+        let end_char = end_char.min(len_chars).max(start_char);
 
         if start_char < end_char {
             let start = self.buffer.cursor_pos_from_char_offset(start_char);
@@ -478,6 +631,92 @@ impl EditorTabState {
         viewport::clamp_and_follow(&mut self.viewport, &self.buffer, tab_size);
     }
 
+    pub fn breadcrumbs_stale(&self) -> bool {
+        self.breadcrumbs_key != Some((self.buffer.cursor(), self.edit_version))
+    }
+
+    /// 按当前光标重算面包屑；没有语法树的文件为空。
+    pub fn refresh_breadcrumbs(&mut self) -> bool {
+        if !self.breadcrumbs_stale() {
+            return false;
+        }
+        let cursor = self.buffer.cursor();
+        self.breadcrumbs_key = Some((cursor, self.edit_version));
+        let breadcrumbs = match self.syntax.as_ref() {
+            Some(syntax) => {
+                let rope = self.buffer.rope();
+                let char_offset = self.buffer.pos_to_char(cursor).min(rope.len_chars());
+                syntax.breadcrumbs_at(rope, rope.char_to_byte(char_offset))
+            }
+            None => Vec::new(),
+        };
+        if breadcrumbs == self.breadcrumbs {
+            return false;
+        }
+        self.breadcrumbs = breadcrumbs;
+        true
+    }
+
+    /// 选中第 `index` 段面包屑覆盖的整个符号。
+    pub fn select_breadcrumb(&mut self, index: usize, tab_size: u8) -> bool {
+        let Some(crumb) = self.breadcrumbs.get(index) else {
+            return false;
+        };
+        let (start, end) = (crumb.start_char, crumb.end_char);
+        self.select_char_range(start, end, tab_size);
+        true
+    }
+
+    /// 当前可见行中最宽一行超出视口的列数，即 `horiz_offset` 的合法上限；软换行时恒为 0。
+    pub fn max_horiz_offset(&self, tab_size: u8) -> u32 {
+        if self.viewport.word_wrap {
+            return 0;
+        }
+        let visible_lines =
+            self.visible_lines_in_viewport(self.viewport.line_offset, self.viewport.height.max(1));
+        let max_visible_width = visible_lines
+            .iter()
+            .map(|&row| viewport::line_display_width(&self.buffer, row, tab_size))
+            .max()
+            .unwrap_or(0);
+        max_visible_width.saturating_sub(self.viewport.width.max(1) as u32)
+    }
+
+    /// 横向滚动进度（0–100）；内容没有超出视口时为 `None`。
+    pub fn horiz_scroll_percent(&self, tab_size: u8) -> Option<u32> {
+        let max_offset = self
+            .max_horiz_offset(tab_size)
+            .max(self.viewport.horiz_offset);
+        if max_offset == 0 {
+            return None;
+        }
+        Some(self.viewport.horiz_offset.saturating_mul(100) / max_offset)
+    }
+
+    pub fn is_horiz_scroll_animating(&self) -> bool {
+        self.horiz_scroll_animation.is_some()
+    }
+
+    /// 推进一帧水平滚动动画，返回 `horiz_offset` 或动画状态是否变化。
+    pub(super) fn step_horiz_scroll_animation(&mut self) -> bool {
+        let Some(animation) = self.horiz_scroll_animation else {
+            return false;
+        };
+        if self.viewport.follow_cursor {
+            self.horiz_scroll_animation = None;
+            return true;
+        }
+
+        let next = animation.step(self.viewport.horiz_offset);
+        let changed = next != self.viewport.horiz_offset;
+        self.viewport.horiz_offset = next;
+        if next == animation.target {
+            self.horiz_scroll_animation = None;
+            return true;
+        }
+        changed
+    }
+
     pub(crate) fn reset_cursor_goal_col(&mut self) {
         self.cursor_goal_col = None;
     }
@@ -533,9 +772,29 @@ impl EditorTabState {
         true
     }
 
+    /// 关闭 / 退出时要写进 `.ops` 的撤销历史。只备份干净的标签页：此时缓冲区就是磁盘内容，
+    /// 下次打开能据此校验；没有任何编辑的标签页也不备份。
+    pub fn edit_history_backup(&self) -> Option<(PathBuf, HistoryBackup)> {
+        let path = self.path.clone()?;
+        if self.dirty || !(self.history.can_undo() || self.history.can_redo()) {
+            return None;
+        }
+        Some((path, self.history.to_backup(self.buffer.rope())))
+    }
+
+    /// 接上备份的撤销历史；标签页在备份读回之前已经有了编辑时放弃，以免两条历史交错。
+    pub fn restore_edit_history(&mut self, history: EditHistory) -> bool {
+        if self.dirty || self.history.can_undo() || self.history.can_redo() {
+            return false;
+        }
+        self.history = history;
+        true
+    }
+
     pub fn display_title(&self) -> String {
         let prefix = match &self.disk_state {
             DiskState::ConflictExternalModified => "\u{26a0} ",
+            DiskState::ChangedOnDisk => "\u{21bb} ",
             DiskState::MissingOnDisk => "\u{2717} ",
             _ if self.dirty => "\u{25cf} ",
             _ => "",
@@ -591,20 +850,40 @@ impl EditorTabState {
             return false;
         }
 
-        let ch = rope.char(char_offset);
-        ch == '_' || UnicodeXID::is_xid_continue(ch)
+        is_word_char(rope.char(char_offset))
     }
 
     pub fn is_in_string_or_comment_at_char(&self, char_offset: usize) -> bool {
-        let Some(syntax) = self.syntax.as_ref() else {
-            return false;
-        };
         let rope = self.buffer.rope();
         let char_offset = char_offset.min(rope.len_chars());
         let byte_offset = rope.char_to_byte(char_offset);
+        let Some(syntax) = self.syntax.as_ref() else {
+            return self.is_in_lexical_string_or_comment(byte_offset);
+        };
         syntax.is_in_string_or_comment(byte_offset)
     }
 
+    fn is_in_lexical_string_or_comment(&self, byte_offset: usize) -> bool {
+        let Some(language) = self.language() else {
+            return false;
+        };
+        let rope = self.buffer.rope();
+        let line = rope.byte_to_line(byte_offset);
+        let Some(lines) = super::syntax::lexical_highlight_lines(language, rope, line, line + 1)
+        else {
+            return false;
+        };
+        let column = byte_offset - rope.line_to_byte(line);
+        // 行注释延伸到行尾，光标停在行尾仍算注释内；字符串则以闭合引号为界。
+        lines.first().is_some_and(|spans| {
+            spans.iter().any(|span| match span.kind {
+                HighlightKind::Comment => span.start < column && column <= span.end,
+                HighlightKind::String => span.start < column && column < span.end,
+                _ => false,
+            })
+        })
+    }
+
     pub fn is_in_string_or_comment_at_cursor(&self) -> bool {
         let (row, col) = self.buffer.cursor();
         let char_offset = self.buffer.pos_to_char((row, col));
@@ -616,7 +895,15 @@ impl EditorTabState {
         start_line: usize,
         end_line_exclusive: usize,
     ) -> Option<SharedSyntaxHighlightLines> {
-        let syntax = self.syntax.as_ref()?;
+        let Some(syntax) = self.syntax.as_ref() else {
+            let lines = super::syntax::lexical_highlight_lines(
+                self.language()?,
+                self.buffer.rope(),
+                start_line,
+                end_line_exclusive,
+            )?;
+            return Some(Arc::new(lines.into_iter().map(Arc::new).collect()));
+        };
         let cache = self.syntax_highlight_cache.as_ref()?;
 
         if start_line >= end_line_exclusive {
@@ -630,21 +917,26 @@ impl EditorTabState {
             return Some(Arc::new(Vec::new()));
         }
 
-        let any_dirty = (start_line..end_line_exclusive).any(|line| cache.is_line_dirty(line));
-        let full_fallback_lines = any_dirty.then(|| {
+        // 同步兜底只算首个到末个脏行之间的部分，其余行直接用缓存。
+        let first_dirty = (start_line..end_line_exclusive).find(|&line| cache.is_line_dirty(line));
+        let last_dirty = (start_line..end_line_exclusive)
+            .rev()
+            .find(|&line| cache.is_line_dirty(line));
+        let fallback_start = first_dirty.unwrap_or(start_line);
+        let full_fallback_lines = first_dirty.zip(last_dirty).map(|(first, last)| {
             super::syntax::highlight_lines_for_range(
                 syntax.language(),
                 syntax.tree(),
                 self.buffer.rope(),
-                start_line,
-                end_line_exclusive,
+                first,
+                last + 1,
             )
         });
 
         let empty: Arc<Vec<HighlightSpan>> = Arc::new(Vec::new());
         let mut out: Vec<Arc<Vec<HighlightSpan>>> =
             Vec::with_capacity(end_line_exclusive - start_line);
-        for (idx, line) in (start_line..end_line_exclusive).enumerate() {
+        for line in start_line..end_line_exclusive {
             let cached = cache
                 .line(line)
                 .map(Arc::clone)
@@ -656,7 +948,7 @@ impl EditorTabState {
 
             let fallback = full_fallback_lines
                 .as_ref()
-                .and_then(|lines| lines.get(idx))
+                .and_then(|lines| lines.get(line - fallback_start))
                 .cloned()
                 .unwrap_or_default();
             out.push(Arc::new(fallback));
@@ -664,6 +956,17 @@ impl EditorTabState {
         Some(Arc::new(out))
     }
 
+    /// 只读语法高亮缓存里已算好的行，不触发任何重算；脏行、未缓存或没有语法树时返回 `None`。
+    /// 供 minimap 这类整篇概览使用，可以接受看不到颜色但不能每帧跑查询。
+    pub fn cached_highlight_line(&self, line: usize) -> Option<&[HighlightSpan]> {
+        self.syntax.as_ref()?;
+        let cache = self.syntax_highlight_cache.as_ref()?;
+        if cache.is_line_dirty(line) {
+            return None;
+        }
+        cache.line(line).map(|spans| spans.as_slice())
+    }
+
     pub fn inlay_hint_lines(
         &self,
         start_line: usize,
@@ -769,6 +1072,44 @@ impl EditorTabState {
         out
     }
 
+    /// 视口内自上而下的显示行：不换行时每个可见逻辑行一行；
+    /// 软换行时按 `display_to_logical` 把每行展开成各段。折叠隐藏的行不出现。
+    pub fn display_rows_in_viewport(&self, start_line: usize, height: usize) -> Vec<DisplayRow> {
+        let lines = self.visible_lines_in_viewport(start_line, height);
+        let rows = &self.viewport.display_to_logical;
+        let whole_line = |line| DisplayRow {
+            line,
+            start_col: 0,
+            end_col: None,
+        };
+        if !self.viewport.word_wrap || rows.is_empty() {
+            return lines.into_iter().map(whole_line).collect();
+        }
+
+        let mut out = Vec::with_capacity(height);
+        for line in lines {
+            if out.len() >= height {
+                break;
+            }
+            let first = self.viewport.first_display_row_of(line);
+            let before = out.len();
+            for idx in (first..rows.len()).take_while(|&idx| rows[idx].0 == line) {
+                if out.len() >= height {
+                    break;
+                }
+                out.push(DisplayRow {
+                    line,
+                    start_col: rows[idx].1,
+                    end_col: self.viewport.wrap_segment_end(idx),
+                });
+            }
+            if out.len() == before {
+                out.push(whole_line(line));
+            }
+        }
+        out
+    }
+
     pub(crate) fn next_visible_row_after(&self, row: usize) -> Option<usize> {
         let total_lines = self.buffer.len_lines().max(1);
         if row + 1 >= total_lines {
@@ -860,6 +1201,33 @@ impl EditorTabState {
         true
     }
 
+    /// 切换以 `line` 开头的折叠（行号栏点击）。`line` 不是折叠起始行时不做任何事；
+    /// 折起后光标若落在被隐藏的行里，移回起始行。
+    pub(crate) fn fold_toggle_at_line(&mut self, line: usize, tab_size: u8) -> bool {
+        let start_line = line.min(u32::MAX as usize) as u32;
+        let Some(state) = self.folding.as_mut() else {
+            return false;
+        };
+        let Some(end_line) = state.fold_end(start_line) else {
+            return false;
+        };
+        if !state.toggle(start_line) {
+            return false;
+        }
+
+        let (row, col) = self.buffer.cursor();
+        let cursor_line = row.min(u32::MAX as usize) as u32;
+        if state.is_folded(start_line) && cursor_line > start_line && cursor_line <= end_line {
+            let len = self.buffer.line_grapheme_len(line);
+            self.buffer.set_cursor(line, col.min(len));
+            self.set_cursor_goal_col(col);
+            self.buffer.update_selection_cursor(self.buffer.cursor());
+        }
+
+        viewport::clamp_and_follow(&mut self.viewport, &self.buffer, tab_size);
+        true
+    }
+
     pub(crate) fn fold_close_at_cursor(&mut self, tab_size: u8) -> bool {
         let (row, col) = self.buffer.cursor();
         let cursor_line = row.min(u32::MAX as usize) as u32;
@@ -939,6 +1307,108 @@ impl EditorTabState {
         true
     }
 
+    pub fn set_document_links(
+        &mut self,
+        links: Vec<(usize, usize, String)>,
+        unresolved: Vec<(usize, usize, LspDocumentLink)>,
+    ) -> bool {
+        let same_unresolved = self.unresolved_document_links.len() == unresolved.len()
+            && self
+                .unresolved_document_links
+                .iter()
+                .zip(&unresolved)
+                .all(|(a, b)| a.0 == b.0 && a.1 == b.1);
+        if self.document_links == links && same_unresolved {
+            return false;
+        }
+        self.document_links = links;
+        self.unresolved_document_links = unresolved;
+        true
+    }
+
+    /// 覆盖 `byte` 的链接：已知 target 返回 `Ok`，需要 resolve 的返回 `Err`。
+    pub(crate) fn document_link_at(&self, byte: usize) -> Option<Result<&str, &LspDocumentLink>> {
+        if let Some((_, _, target)) = self
+            .document_links
+            .iter()
+            .find(|(start, end, _)| *start <= byte && byte < *end)
+        {
+            return Some(Ok(target.as_str()));
+        }
+        self.unresolved_document_links
+            .iter()
+            .find(|(start, end, _)| *start <= byte && byte < *end)
+            .map(|(_, _, link)| Err(link))
+    }
+
+    /// `documentLink/resolve` 返回后把对应区间移入已解析列表。
+    pub(crate) fn resolve_document_link(
+        &mut self,
+        start: usize,
+        end: usize,
+        target: String,
+    ) -> bool {
+        let Some(idx) = self
+            .unresolved_document_links
+            .iter()
+            .position(|(s, e, _)| *s == start && *e == end)
+        else {
+            return false;
+        };
+        self.unresolved_document_links.remove(idx);
+        self.document_links.push((start, end, target));
+        self.document_links.sort_by_key(|(s, e, _)| (*s, *e));
+        true
+    }
+
+    pub fn set_document_highlights(&mut self, highlights: Vec<LspRange>) -> bool {
+        if self.document_highlights == highlights {
+            return false;
+        }
+        self.document_highlights = highlights;
+        true
+    }
+
+    /// 已保存内容或缓冲区变了才重算未保存改动标记；返回标记是否有变化。
+    pub fn refresh_unsaved_marks(&mut self) -> bool {
+        let key = (
+            self.edit_version,
+            self.history.head(),
+            self.history.saved_head(),
+        );
+        if self.unsaved_marks_key == Some(key) {
+            return false;
+        }
+        self.unsaved_marks_key = Some(key);
+        let marks = unsaved_diff::unsaved_marks(&self.history, self.buffer.rope());
+        if marks == self.unsaved_marks {
+            return false;
+        }
+        self.unsaved_marks = marks;
+        true
+    }
+
+    pub fn clear_unsaved_marks(&mut self) -> bool {
+        self.unsaved_marks_key = None;
+        if self.unsaved_marks.is_empty() {
+            return false;
+        }
+        self.unsaved_marks.clear();
+        true
+    }
+
+    pub fn unsaved_mark(&self, line: usize) -> Option<GutterMarkKind> {
+        self.unsaved_marks
+            .binary_search_by_key(&line, |(l, _)| *l)
+            .ok()
+            .map(|idx| self.unsaved_marks[idx].1)
+    }
+
+    pub(super) fn clear_document_links(&mut self) {
+        self.document_links.clear();
+        self.unresolved_document_links.clear();
+    }
+
     pub(crate) fn syntax(&self) -> Option<&SyntaxDocument> {
         self.syntax.as_ref()
     }
@@ -954,24 +1424,16 @@ impl EditorTabState {
         let rope = self.buffer.rope();
         let delta = syntax.apply_edit(rope, op);
 
-        let cache = self
-            .syntax_highlight_cache
-            .get_or_insert_with(|| AsyncSyntaxHighlightCache::new_for_rope(rope));
-
-        if delta.reparsed || delta.input_edit.is_none() {
-            cache.reset_for_rope(rope);
-            return;
-        }
-
-        if let Some(edit) = delta.input_edit.as_ref() {
-            cache.apply_edit_shape_shift(rope, edit);
-        }
-        cache.mark_dirty_from_changed_ranges(rope, &delta.changed_ranges);
+        self.syntax_highlight_cache
+            .get_or_insert_with(|| AsyncSyntaxHighlightCache::new_for_rope(rope))
+            .invalidate_ranges(rope, &delta);
     }
 
     pub(super) fn bump_version(&mut self) {
         self.edit_version = self.edit_version.saturating_add(1);
         self.syntax_highlight_pending_version = None;
+        self.clear_document_links();
+        self.document_highlights.clear();
     }
 
     pub(super) fn reparse_syntax(&mut self) {
@@ -984,9 +1446,52 @@ impl EditorTabState {
         }
     }
 
+    /// 把分块读入的下一段接到文末；`start_byte` 与已读位置不符（重复或乱序的结果）时忽略。
+    pub fn append_loaded_range(
+        &mut self,
+        start_byte: u64,
+        end_byte: u64,
+        file_size: u64,
+        content: &str,
+        tab_size: u8,
+    ) -> bool {
+        let Some(partial) = self.partial_load.as_mut() else {
+            return false;
+        };
+        if partial.loaded_bytes != start_byte {
+            return false;
+        }
+        partial.pending = false;
+        partial.loaded_bytes = end_byte;
+        partial.file_size = file_size;
+        if end_byte >= file_size {
+            self.partial_load = None;
+        }
+        if !content.is_empty() {
+            let appended_from = self.buffer.len_chars().saturating_sub(1);
+            let op = self.buffer.append_str_op(content);
+            self.bump_version();
+            self.apply_syntax_edit(&op);
+            self.foreign_line_endings |= has_stray_line_endings(
+                self.buffer.rope(),
+                appended_from..self.buffer.len_chars(),
+                self.line_ending,
+            );
+        }
+        viewport::clamp_and_follow(&mut self.viewport, &self.buffer, tab_size);
+        true
+    }
+
     pub fn reload_from_content(&mut self, content: &str, config: &EditorConfig) {
         use crate::models::{EditHistory, TextBuffer};
         self.buffer = TextBuffer::from_text(content);
+        self.line_ending =
+            scan_line_endings(self.buffer.rope()).unwrap_or(config.default_line_ending);
+        self.foreign_line_endings = has_stray_line_endings(
+            self.buffer.rope(),
+            0..self.buffer.len_chars(),
+            self.line_ending,
+        );
         self.history = EditHistory::new();
         self.dirty = false;
         self.edit_version = self.edit_version.saturating_add(1);
@@ -994,6 +1499,7 @@ impl EditorTabState {
         self.secondary_cursors.clear();
         self.snippet_session = None;
         self.disk_state = DiskState::ReloadedFromDisk { at: Instant::now() };
+        self.partial_load = None;
         self.syntax = self
             .path
             .as_ref()
@@ -1007,6 +1513,8 @@ impl EditorTabState {
         self.syntax_highlight_pending_version = None;
         self.inlay_hints = None;
         self.clear_folding();
+        self.clear_document_links();
+        self.document_highlights.clear();
         viewport::clamp_and_follow(&mut self.viewport, &self.buffer, config.tab_size);
     }
 }
@@ -1190,6 +1698,9 @@ pub struct EditorPaneState {
     pub tabs: Vec<EditorTabState>,
     pub active: usize,
     pub search_bar: SearchBarState,
+    /// Vim 模式（`EditorConfig::vim_mode` 关闭时恒为 `Insert`）。
+    pub mode: EditorMode,
+    pub vim_pending: Option<VimPending>,
 }
 
 impl Default for EditorPaneState {
@@ -1204,9 +1715,27 @@ impl EditorPaneState {
             tabs: Vec::new(),
             active: 0,
             search_bar: SearchBarState::default(),
+            mode: EditorMode::Insert,
+            vim_pending: None,
         }
     }
 
+    fn with_vim_mode(vim_mode: bool) -> Self {
+        let mut pane = Self::new();
+        pane.set_vim_mode(vim_mode);
+        pane
+    }
+
+    /// 打开 Vim 模式时回到 Normal，关闭时回到 Insert；都会丢掉未完成的前缀。
+    pub fn set_vim_mode(&mut self, vim_mode: bool) {
+        self.mode = if vim_mode {
+            EditorMode::Normal
+        } else {
+            EditorMode::Insert
+        };
+        self.vim_pending = None;
+    }
+
     pub fn active_tab(&self) -> Option<&EditorTabState> {
         self.tabs.get(self.active)
     }
@@ -1331,8 +1860,8 @@ pub struct EditorState {
 impl EditorState {
     pub fn new(config: EditorConfig) -> Self {
         Self {
+            panes: vec![EditorPaneState::with_vim_mode(config.vim_mode)],
             config,
-            panes: vec![EditorPaneState::new()],
             open_paths_version: 0,
             next_tab_id: 1,
         }
@@ -1352,6 +1881,17 @@ impl EditorState {
         self.panes.get(pane)
     }
 
+    /// 推进所有标签页的平滑滚动动画（由 tick 驱动），返回是否有视口变化。
+    pub fn tick_scroll_animations(&mut self) -> bool {
+        let mut changed = false;
+        for pane in &mut self.panes {
+            for tab in &mut pane.tabs {
+                changed |= tab.step_horiz_scroll_animation();
+            }
+        }
+        changed
+    }
+
     pub fn ensure_panes(&mut self, desired: usize) -> bool {
         let desired = desired.max(1);
         let current = self.panes.len();
@@ -1372,7 +1912,8 @@ impl EditorState {
             std::cmp::Ordering::Greater => {
                 self.panes.reserve(desired - current);
                 for _ in current..desired {
-                    self.panes.push(EditorPaneState::new());
+                    self.panes
+                        .push(EditorPaneState::with_vim_mode(self.config.vim_mode));
                 }
                 true
             }
@@ -0,0 +1,78 @@
+use super::util::node_text_trimmed;
+use ropey::Rope;
+use tree_sitter::{Node, Tree};
+
+/// 面包屑的一段：符号名 + 它在缓冲区里覆盖的字符区间（点击时整段选中）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breadcrumb {
+    pub label: String,
+    pub start_char: usize,
+    pub end_char: usize,
+}
+
+/// 作用域节点的显示前缀；不在表里的节点不进面包屑。
+fn scope_prefix(kind: &str) -> Option<&'static str> {
+    Some(match kind {
+        "mod_item" => "mod",
+        "impl_item" => "impl",
+        "trait_item" => "trait",
+        "struct_item" => "struct",
+        "enum_item" => "enum",
+        "function_item"
+        | "function_definition"
+        | "function_declaration"
+        | "method_definition"
+        | "method_declaration"
+        | "method" => "fn",
+        "class_definition" | "class_declaration" | "class" => "class",
+        "interface_declaration" => "interface",
+        "module" => "module",
+        _ => return None,
+    })
+}
+
+fn scope_name(rope: &Rope, node: Node<'_>) -> Option<String> {
+    if node.kind() == "impl_item" {
+        let ty = node_text_trimmed(rope, node.child_by_field_name("type")?)?;
+        return Some(match node.child_by_field_name("trait") {
+            Some(trait_node) => format!("{} for {ty}", node_text_trimmed(rope, trait_node)?),
+            None => ty,
+        });
+    }
+    if let Some(name) = node.child_by_field_name("name") {
+        return node_text_trimmed(rope, name);
+    }
+    // C/C++：函数名藏在层层 `declarator` 里。
+    let mut declarator = node.child_by_field_name("declarator")?;
+    while let Some(inner) = declarator.child_by_field_name("declarator") {
+        declarator = inner;
+    }
+    node_text_trimmed(rope, declarator)
+}
+
+/// `byte_offset` 处由外到内的作用域链。
+pub(super) fn breadcrumbs_at(tree: &Tree, rope: &Rope, byte_offset: usize) -> Vec<Breadcrumb> {
+    let root = tree.root_node();
+    let Some(mut node) = root.descendant_for_byte_range(byte_offset, byte_offset) else {
+        return Vec::new();
+    };
+
+    let mut crumbs = Vec::new();
+    loop {
+        if let Some(prefix) = scope_prefix(node.kind()) {
+            if let Some(name) = scope_name(rope, node) {
+                crumbs.push(Breadcrumb {
+                    label: format!("{prefix} {name}"),
+                    start_char: rope.byte_to_char(node.start_byte().min(rope.len_bytes())),
+                    end_char: rope.byte_to_char(node.end_byte().min(rope.len_bytes())),
+                });
+            }
+        }
+        match node.parent() {
+            Some(parent) => node = parent,
+            None => break,
+        }
+    }
+    crumbs.reverse();
+    crumbs
+}
@@ -0,0 +1,129 @@
+//! Cairo（Starknet）词法高亮。crates.io 上的 `tree-sitter-cairo` 还停在 `func` 语法的
+//! 早期原型，解析不了现行 Cairo（`fn` / `loop` / `pub` / `#[...]`），因此走通用扫描器。
+
+use super::lexical::{is_pascal_case, is_screaming_case, LexicalLanguage, WordContext};
+use super::HighlightKind;
+
+pub(super) static LEXICAL: LexicalLanguage = LexicalLanguage {
+    line_comments: &["//"],
+    line_comments_leading_only: false,
+    block_comment: None,
+    nested_block_comments: false,
+    // `'abc'` 是 felt252 短字符串，`"abc"` 是 ByteArray。
+    quotes: b"\"'",
+    interpolated_quotes: &[],
+    multiline_strings: &[],
+    word_extra: &[],
+    variable_sigils: &[],
+    attribute_prefix: None,
+    bracket_attribute: Some("#["),
+    atom_prefix: None,
+    sigil_prefix: None,
+    classify_word,
+};
+
+fn classify_word(word: &str, ctx: &WordContext<'_>) -> Option<HighlightKind> {
+    if is_cairo_control_keyword(word) {
+        return Some(HighlightKind::KeywordControl);
+    }
+    if is_cairo_keyword(word) {
+        return Some(HighlightKind::Keyword);
+    }
+    if is_cairo_builtin_type(word) {
+        return Some(HighlightKind::TypeBuiltin);
+    }
+    if matches!(word, "true" | "false") {
+        return Some(HighlightKind::Boolean);
+    }
+
+    match ctx.prev_word {
+        Some("struct" | "enum" | "trait" | "impl" | "type" | "of") => {
+            return Some(HighlightKind::Type)
+        }
+        Some("fn") => return Some(HighlightKind::Function),
+        Some("mod" | "use") => return Some(HighlightKind::Namespace),
+        _ => {}
+    }
+
+    // `array![...]` / `panic!(...)` / `assert!(...)`。
+    if ctx.next == Some(b'!') {
+        return Some(HighlightKind::Macro);
+    }
+    if ctx.prev == Some(b'.') {
+        return Some(if ctx.next == Some(b'(') {
+            HighlightKind::Method
+        } else {
+            HighlightKind::Property
+        });
+    }
+    if is_screaming_case(word) {
+        return Some(HighlightKind::Constant);
+    }
+    if is_pascal_case(word) {
+        return Some(HighlightKind::Type);
+    }
+    // `starknet::storage::Map` 中间的路径段。
+    if ctx.prev == Some(b':') && ctx.next == Some(b':') {
+        return Some(HighlightKind::Namespace);
+    }
+    if ctx.next == Some(b'(') {
+        return Some(HighlightKind::Function);
+    }
+    None
+}
+
+fn is_cairo_control_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "if" | "else" | "match" | "loop" | "while" | "for" | "break" | "continue" | "return"
+    )
+}
+
+fn is_cairo_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "fn" | "mod"
+            | "use"
+            | "struct"
+            | "enum"
+            | "trait"
+            | "impl"
+            | "of"
+            | "let"
+            | "const"
+            | "mut"
+            | "ref"
+            | "pub"
+            | "as"
+            | "in"
+            | "type"
+            | "extern"
+            | "nopanic"
+            | "implicits"
+            | "self"
+            | "Self"
+            | "super"
+            | "crate"
+    )
+}
+
+fn is_cairo_builtin_type(word: &str) -> bool {
+    matches!(
+        word,
+        "felt252"
+            | "bool"
+            | "u8"
+            | "u16"
+            | "u32"
+            | "u64"
+            | "u128"
+            | "u256"
+            | "usize"
+            | "i8"
+            | "i16"
+            | "i32"
+            | "i64"
+            | "i128"
+            | "bytes31"
+    )
+}
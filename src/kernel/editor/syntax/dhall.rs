@@ -0,0 +1,71 @@
+//! Dhall 词法高亮。crates.io 上没有与 tree-sitter 0.20 配套的 Dhall 语法包，因此走通用扫描器。
+
+use super::lexical::{is_pascal_case, LexicalLanguage, WordContext};
+use super::HighlightKind;
+
+pub(super) static LEXICAL: LexicalLanguage = LexicalLanguage {
+    line_comments: &["--"],
+    line_comments_leading_only: false,
+    // Dhall 的块注释可以嵌套。
+    block_comment: Some(("{-", "-}")),
+    nested_block_comments: true,
+    quotes: b"\"",
+    interpolated_quotes: b"\"",
+    multiline_strings: &[("''", "''")],
+    // 内置函数写作 `Natural/show`、`List/length`；标签里也允许 `-`。
+    word_extra: b"-/",
+    variable_sigils: &[],
+    attribute_prefix: None,
+    bracket_attribute: None,
+    atom_prefix: None,
+    sigil_prefix: None,
+    classify_word,
+};
+
+fn classify_word(word: &str, ctx: &WordContext<'_>) -> Option<HighlightKind> {
+    match word {
+        "if" | "then" | "else" => return Some(HighlightKind::KeywordControl),
+        "let" | "in" | "forall" | "merge" | "assert" | "using" | "as" | "with" | "toMap"
+        | "showConstructor" | "missing" | "Some" => return Some(HighlightKind::Keyword),
+        "True" | "False" => return Some(HighlightKind::Boolean),
+        "None" => return Some(HighlightKind::Constant),
+        _ => {}
+    }
+    if is_dhall_builtin_type(word) {
+        return Some(HighlightKind::TypeBuiltin);
+    }
+    // `Natural/show`、`Text/replace`：内置类型名加 `/` 的内置函数。
+    if let Some((head, _)) = word.split_once('/') {
+        if is_dhall_builtin_type(head) {
+            return Some(HighlightKind::Function);
+        }
+    }
+
+    if ctx.prev == Some(b'.') {
+        return Some(HighlightKind::Property);
+    }
+    if is_pascal_case(word) {
+        return Some(HighlightKind::Type);
+    }
+    None
+}
+
+fn is_dhall_builtin_type(word: &str) -> bool {
+    matches!(
+        word,
+        "Bool"
+            | "Natural"
+            | "Integer"
+            | "Double"
+            | "Text"
+            | "List"
+            | "Optional"
+            | "Date"
+            | "Time"
+            | "TimeZone"
+            | "Bytes"
+            | "Type"
+            | "Kind"
+            | "Sort"
+    )
+}
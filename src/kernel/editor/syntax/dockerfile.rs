@@ -0,0 +1,194 @@
+//! Dockerfile 手写分词：行首第一个词是指令（不区分大小写），其余是参数；参数里的
+//! `${VAR}` / `$VAR` 替换单独标出。`#` 只在行首算注释。`\` 续行只回看上一行，
+//! 续行上的第一个词不当作指令。
+
+use ropey::Rope;
+
+use super::lexical::{line_text, push};
+use super::{merge_adjacent_highlight_spans, HighlightKind, HighlightSpan};
+
+pub(super) fn highlight_lines(
+    rope: &Rope,
+    start_line: usize,
+    end_line_exclusive: usize,
+) -> Vec<Vec<HighlightSpan>> {
+    let total_lines = rope.len_lines().max(1);
+    let start_line = start_line.min(total_lines);
+    let end_line_exclusive = end_line_exclusive.min(total_lines);
+    (start_line..end_line_exclusive)
+        .map(|line| {
+            let text = line_text(rope, line);
+            let mut spans = Vec::new();
+            scan_line(text.as_ref(), is_continuation(rope, line), &mut spans);
+            merge_adjacent_highlight_spans(&mut spans);
+            spans
+        })
+        .collect()
+}
+
+/// 上一条非注释行以 `\` 结尾时，本行是同一条指令的续行（指令中间的注释行会被 Docker 跳过）。
+fn is_continuation(rope: &Rope, line: usize) -> bool {
+    let mut prev = line;
+    while prev > 0 {
+        prev -= 1;
+        let text = line_text(rope, prev);
+        let trimmed = text.trim();
+        if trimmed.starts_with('#') {
+            continue;
+        }
+        return trimmed.ends_with('\\');
+    }
+    false
+}
+
+fn scan_line(line: &str, continuation: bool, out: &mut Vec<HighlightSpan>) {
+    let bytes = line.as_bytes();
+    let start = skip_whitespace(bytes, 0);
+    if start == bytes.len() {
+        return;
+    }
+    if bytes[start] == b'#' {
+        push(out, start, bytes.len(), HighlightKind::Comment);
+        return;
+    }
+    if continuation {
+        scan_arguments(bytes, start, out);
+        return;
+    }
+
+    let word_end = word_end(bytes, start);
+    if !is_instruction(&line[start..word_end]) {
+        scan_arguments(bytes, start, out);
+        return;
+    }
+    push(out, start, word_end, HighlightKind::Keyword);
+
+    let mut args_start = skip_whitespace(bytes, word_end);
+    // `ONBUILD RUN make`：触发器后面紧跟的是另一条指令。
+    if line[start..word_end].eq_ignore_ascii_case("ONBUILD") {
+        let trigger_end = self::word_end(bytes, args_start);
+        if is_instruction(&line[args_start..trigger_end]) {
+            push(out, args_start, trigger_end, HighlightKind::Keyword);
+            args_start = trigger_end;
+        }
+    }
+    // `FROM image AS builder`：阶段名前的 `AS`。
+    if line[start..word_end].eq_ignore_ascii_case("FROM") {
+        let mut i = args_start;
+        while i < bytes.len() {
+            let end = self::word_end(bytes, i);
+            if line[i..end].eq_ignore_ascii_case("AS") {
+                push(out, i, end, HighlightKind::Keyword);
+            } else {
+                scan_arguments(&bytes[..end], i, out);
+            }
+            i = skip_whitespace(bytes, end);
+        }
+        return;
+    }
+    scan_arguments(bytes, args_start, out);
+}
+
+/// 指令参数：双引号 / 单引号字符串，以及字符串内外的变量替换（单引号内不展开）。
+fn scan_arguments(bytes: &[u8], start: usize, out: &mut Vec<HighlightSpan>) {
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => i = scan_string(bytes, i, b'"', out),
+            b'\'' => i = scan_string(bytes, i, b'\'', out),
+            b'\\' => i += 2,
+            b'$' => match variable_end(bytes, i) {
+                Some(end) => {
+                    push(out, i, end, HighlightKind::Variable);
+                    i = end;
+                }
+                None => i += 1,
+            },
+            _ => i += 1,
+        }
+    }
+}
+
+/// 从开引号扫到闭引号（未闭合时到行尾），返回字符串之后的位置。
+fn scan_string(bytes: &[u8], open: usize, quote: u8, out: &mut Vec<HighlightSpan>) -> usize {
+    let mut segment_start = open;
+    let mut i = open + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b if b == quote => {
+                push(out, segment_start, i + 1, HighlightKind::String);
+                return i + 1;
+            }
+            b'$' if quote == b'"' => match variable_end(bytes, i) {
+                Some(end) => {
+                    // 变量把字符串切成前后两段。
+                    push(out, segment_start, i, HighlightKind::String);
+                    push(out, i, end, HighlightKind::Variable);
+                    segment_start = end;
+                    i = end;
+                }
+                None => i += 1,
+            },
+            _ => i += 1,
+        }
+    }
+    let end = bytes.len();
+    push(out, segment_start, end, HighlightKind::String);
+    end
+}
+
+/// `${VAR}`、`${VAR:-default}` 或 `$VAR` 的结束位置；`$` 后面不是变量名时返回 `None`。
+fn variable_end(bytes: &[u8], dollar: usize) -> Option<usize> {
+    Some(match bytes.get(dollar + 1)? {
+        b'{' => bytes[dollar + 2..]
+            .iter()
+            .position(|&b| b == b'}')
+            .map_or(bytes.len(), |offset| dollar + 2 + offset + 1),
+        b if b.is_ascii_alphabetic() || *b == b'_' => bytes[dollar + 1..]
+            .iter()
+            .position(|b| !(b.is_ascii_alphanumeric() || *b == b'_'))
+            .map_or(bytes.len(), |offset| dollar + 1 + offset),
+        _ => return None,
+    })
+}
+
+fn word_end(bytes: &[u8], start: usize) -> usize {
+    bytes[start..]
+        .iter()
+        .position(u8::is_ascii_whitespace)
+        .map_or(bytes.len(), |offset| start + offset)
+}
+
+fn skip_whitespace(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    i
+}
+
+fn is_instruction(word: &str) -> bool {
+    const INSTRUCTIONS: &[&str] = &[
+        "FROM",
+        "RUN",
+        "CMD",
+        "COPY",
+        "ADD",
+        "EXPOSE",
+        "ENV",
+        "ARG",
+        "LABEL",
+        "WORKDIR",
+        "USER",
+        "VOLUME",
+        "ONBUILD",
+        "STOPSIGNAL",
+        "HEALTHCHECK",
+        "SHELL",
+        "ENTRYPOINT",
+        "MAINTAINER",
+    ];
+    INSTRUCTIONS
+        .iter()
+        .any(|instruction| word.eq_ignore_ascii_case(instruction))
+}
@@ -0,0 +1,121 @@
+//! Elixir 词法高亮。缓存里的 `tree-sitter-elixir` 只有绑定 tree-sitter 0.23 的版本，
+//! 与本仓库的 0.20 运行时不兼容，因此走通用扫描器。
+
+use super::lexical::{LexicalLanguage, WordContext};
+use super::HighlightKind;
+
+pub(super) static LEXICAL: LexicalLanguage = LexicalLanguage {
+    line_comments: &["#"],
+    line_comments_leading_only: false,
+    block_comment: None,
+    nested_block_comments: false,
+    // `'abc'` 是 charlist。
+    quotes: b"\"'",
+    interpolated_quotes: &[],
+    // `@doc """ ... """` heredoc。
+    multiline_strings: &[("\"\"\"", "\"\"\""), ("'''", "'''")],
+    // `valid?` / `fetch!`。
+    word_extra: b"?!",
+    variable_sigils: &[],
+    // `@moduledoc` / `@spec` 模块属性。
+    attribute_prefix: Some(b'@'),
+    bracket_attribute: None,
+    atom_prefix: Some(b':'),
+    sigil_prefix: Some(b'~'),
+    classify_word,
+};
+
+fn classify_word(word: &str, ctx: &WordContext<'_>) -> Option<HighlightKind> {
+    if is_elixir_control_keyword(word) {
+        return Some(HighlightKind::KeywordControl);
+    }
+    if is_elixir_keyword(word) {
+        return Some(HighlightKind::Keyword);
+    }
+    match word {
+        "true" | "false" => return Some(HighlightKind::Boolean),
+        "nil" => return Some(HighlightKind::Constant),
+        _ => {}
+    }
+
+    // 模块名（含 `Foo.Bar` 的每一段）；Elixir 里首字母大写的标识符只能是 alias。
+    if word.as_bytes().first().is_some_and(u8::is_ascii_uppercase) {
+        return Some(HighlightKind::Namespace);
+    }
+    if matches!(
+        ctx.prev_word,
+        Some("def" | "defp" | "defmacro" | "defmacrop" | "defguard" | "defguardp" | "defdelegate")
+    ) {
+        return Some(HighlightKind::Function);
+    }
+    // `Enum.map(...)` / `conn.assigns`；`.` 之后不带括号的是 map / struct 字段。
+    if ctx.prev == Some(b'.') {
+        return Some(if ctx.next == Some(b'(') {
+            HighlightKind::Function
+        } else {
+            HighlightKind::Property
+        });
+    }
+    if ctx.next == Some(b'(') {
+        return Some(HighlightKind::Function);
+    }
+    None
+}
+
+fn is_elixir_control_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "if" | "unless"
+            | "else"
+            | "case"
+            | "cond"
+            | "with"
+            | "for"
+            | "try"
+            | "catch"
+            | "rescue"
+            | "after"
+            | "receive"
+            | "raise"
+            | "reraise"
+            | "throw"
+    )
+}
+
+fn is_elixir_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "def"
+            | "defp"
+            | "defmodule"
+            | "defmacro"
+            | "defmacrop"
+            | "defstruct"
+            | "defprotocol"
+            | "defimpl"
+            | "defguard"
+            | "defguardp"
+            | "defdelegate"
+            | "defexception"
+            | "defoverridable"
+            | "do"
+            | "end"
+            | "fn"
+            | "when"
+            | "in"
+            | "and"
+            | "or"
+            | "not"
+            | "alias"
+            | "import"
+            | "require"
+            | "use"
+            | "quote"
+            | "unquote"
+            | "unquote_splicing"
+            | "__MODULE__"
+            | "__DIR__"
+            | "__ENV__"
+            | "__CALLER__"
+    )
+}
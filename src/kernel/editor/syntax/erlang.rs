@@ -0,0 +1,90 @@
+//! Erlang 词法高亮。crates.io 上没有与 tree-sitter 0.20 配套的 Erlang 语法包，因此走通用扫描器。
+
+use super::lexical::{LexicalLanguage, WordContext};
+use super::HighlightKind;
+
+pub(super) static LEXICAL: LexicalLanguage = LexicalLanguage {
+    line_comments: &["%"],
+    line_comments_leading_only: false,
+    block_comment: None,
+    nested_block_comments: false,
+    // `'quoted atom'` 与字符串同样按引号整体着色。
+    quotes: b"\"'",
+    interpolated_quotes: &[],
+    multiline_strings: &[],
+    word_extra: &[],
+    variable_sigils: &[],
+    attribute_prefix: None,
+    bracket_attribute: None,
+    atom_prefix: None,
+    sigil_prefix: None,
+    classify_word,
+};
+
+fn classify_word(word: &str, ctx: &WordContext<'_>) -> Option<HighlightKind> {
+    // `-module(foo).` / `-spec ...`：行首 `-` 之后的是模块属性。
+    if ctx.prev == Some(b'-') && ctx.start == 1 {
+        return Some(HighlightKind::Attribute);
+    }
+    // `?MODULE` / `?LINE` 宏。
+    if ctx.prev == Some(b'?') {
+        return Some(HighlightKind::Constant);
+    }
+    if is_erlang_control_keyword(word) {
+        return Some(HighlightKind::KeywordControl);
+    }
+    if is_erlang_keyword(word) {
+        return Some(HighlightKind::Keyword);
+    }
+    if matches!(word, "true" | "false") {
+        return Some(HighlightKind::Boolean);
+    }
+
+    let first = word.as_bytes()[0];
+    // 变量以大写字母或 `_` 开头。
+    if first.is_ascii_uppercase() || first == b'_' {
+        return Some(HighlightKind::Variable);
+    }
+    // `lists:map(...)` 的模块名。
+    if ctx.next == Some(b':') {
+        return Some(HighlightKind::Namespace);
+    }
+    // 顶格的 `name(...) ->` 是函数子句头。
+    if ctx.next == Some(b'(') {
+        return Some(HighlightKind::Function);
+    }
+    // 其余小写标识符都是 atom。
+    Some(HighlightKind::Constant)
+}
+
+fn is_erlang_control_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "if" | "case" | "of" | "receive" | "after" | "try" | "catch" | "when"
+    )
+}
+
+fn is_erlang_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "begin"
+            | "end"
+            | "fun"
+            | "maybe"
+            | "else"
+            | "and"
+            | "andalso"
+            | "or"
+            | "orelse"
+            | "not"
+            | "xor"
+            | "div"
+            | "rem"
+            | "band"
+            | "bor"
+            | "bxor"
+            | "bnot"
+            | "bsl"
+            | "bsr"
+    )
+}
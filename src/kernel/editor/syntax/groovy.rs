@@ -0,0 +1,127 @@
+//! Groovy 词法高亮。构建所用的 registry 里没有 `tree-sitter-groovy` 语法包，
+//! 也就没有与 tree-sitter 0.20 配套的版本，因此走通用扫描器。
+
+use super::lexical::{is_pascal_case, is_screaming_case, LexicalLanguage, WordContext};
+use super::HighlightKind;
+
+pub(super) static LEXICAL: LexicalLanguage = LexicalLanguage {
+    line_comments: &["//"],
+    line_comments_leading_only: false,
+    block_comment: Some(("/*", "*/")),
+    nested_block_comments: false,
+    quotes: b"\"'",
+    interpolated_quotes: b"\"",
+    multiline_strings: &[("\"\"\"", "\"\"\""), ("'''", "'''")],
+    word_extra: &[],
+    variable_sigils: &[],
+    attribute_prefix: Some(b'@'),
+    bracket_attribute: None,
+    atom_prefix: None,
+    sigil_prefix: None,
+    classify_word,
+};
+
+fn classify_word(word: &str, ctx: &WordContext<'_>) -> Option<HighlightKind> {
+    if is_groovy_control_keyword(word) {
+        return Some(HighlightKind::KeywordControl);
+    }
+    if is_groovy_keyword(word) {
+        return Some(HighlightKind::Keyword);
+    }
+    if is_groovy_primitive_type(word) {
+        return Some(HighlightKind::TypeBuiltin);
+    }
+    match word {
+        "true" | "false" => return Some(HighlightKind::Boolean),
+        "null" => return Some(HighlightKind::Constant),
+        "this" | "super" => return Some(HighlightKind::Keyword),
+        _ => {}
+    }
+
+    match ctx.prev_word {
+        Some("class" | "interface" | "trait" | "enum" | "extends" | "implements" | "new") => {
+            return Some(HighlightKind::Type)
+        }
+        Some("package" | "import") => return Some(HighlightKind::Namespace),
+        _ => {}
+    }
+
+    if ctx.prev == Some(b'.') {
+        return Some(if ctx.next == Some(b'(') {
+            HighlightKind::Method
+        } else {
+            HighlightKind::Property
+        });
+    }
+    if is_screaming_case(word) {
+        return Some(HighlightKind::Constant);
+    }
+    if is_pascal_case(word) {
+        return Some(HighlightKind::Type);
+    }
+    // `foo(...)`、`stage('Build') {`、`steps {`、`sh 'make'`：方法调用（含 Jenkins DSL 的
+    // 闭包块与省略括号的命令调用）。
+    if matches!(ctx.next, Some(b'(' | b'{' | b'\'' | b'"')) {
+        return Some(HighlightKind::Function);
+    }
+    None
+}
+
+fn is_groovy_control_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "if" | "else"
+            | "for"
+            | "while"
+            | "do"
+            | "switch"
+            | "case"
+            | "default"
+            | "try"
+            | "catch"
+            | "finally"
+            | "throw"
+            | "return"
+            | "break"
+            | "continue"
+    )
+}
+
+fn is_groovy_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "def"
+            | "var"
+            | "class"
+            | "interface"
+            | "trait"
+            | "enum"
+            | "extends"
+            | "implements"
+            | "new"
+            | "package"
+            | "import"
+            | "as"
+            | "in"
+            | "instanceof"
+            | "throws"
+            | "assert"
+            | "static"
+            | "final"
+            | "abstract"
+            | "public"
+            | "private"
+            | "protected"
+            | "synchronized"
+            | "transient"
+            | "volatile"
+            | "native"
+    )
+}
+
+fn is_groovy_primitive_type(word: &str) -> bool {
+    matches!(
+        word,
+        "void" | "boolean" | "byte" | "char" | "short" | "int" | "long" | "float" | "double"
+    )
+}
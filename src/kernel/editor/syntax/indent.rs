@@ -0,0 +1,100 @@
+use crate::kernel::language::LanguageId;
+
+/// 一种语言的缩进规则：`indent_nodes` 里的节点，内容比节点起始行多缩进一级。
+#[derive(Debug)]
+pub struct IndentRules {
+    pub indent_nodes: &'static [&'static str],
+}
+
+static RUST_RULES: IndentRules = IndentRules {
+    indent_nodes: &[
+        "block",
+        "declaration_list",
+        "field_declaration_list",
+        "enum_variant_list",
+        "field_initializer_list",
+        "match_block",
+        "arguments",
+        "parameters",
+        "array_expression",
+        "tuple_expression",
+        "use_list",
+        "token_tree",
+    ],
+};
+
+static GO_RULES: IndentRules = IndentRules {
+    indent_nodes: &[
+        "block",
+        "literal_value",
+        "argument_list",
+        "parameter_list",
+        "field_declaration_list",
+        "interface_type",
+        "import_spec_list",
+        "expression_case",
+        "type_case",
+        "default_case",
+        "communication_case",
+    ],
+};
+
+static C_FAMILY_RULES: IndentRules = IndentRules {
+    indent_nodes: &[
+        "compound_statement",
+        "field_declaration_list",
+        "enumerator_list",
+        "initializer_list",
+        "argument_list",
+        "parameter_list",
+        "case_statement",
+    ],
+};
+
+static JAVA_RULES: IndentRules = IndentRules {
+    indent_nodes: &[
+        "block",
+        "class_body",
+        "interface_body",
+        "enum_body",
+        "constructor_body",
+        "argument_list",
+        "formal_parameters",
+        "array_initializer",
+        "switch_block",
+        "switch_block_statement_group",
+    ],
+};
+
+static JS_RULES: IndentRules = IndentRules {
+    indent_nodes: &[
+        "statement_block",
+        "class_body",
+        "object",
+        "object_pattern",
+        "array",
+        "arguments",
+        "formal_parameters",
+        "named_imports",
+        "switch_body",
+        "switch_case",
+        "switch_default",
+        "object_type",
+        "interface_body",
+        "enum_body",
+    ],
+};
+
+/// 没有规则的语言（如 Python，空块在语法树里是错误节点）换行时只按行尾后缀缩进。
+pub(super) fn indent_rules(language: LanguageId) -> Option<&'static IndentRules> {
+    match language {
+        LanguageId::Rust => Some(&RUST_RULES),
+        LanguageId::Go => Some(&GO_RULES),
+        LanguageId::C | LanguageId::Cpp => Some(&C_FAMILY_RULES),
+        LanguageId::Java => Some(&JAVA_RULES),
+        LanguageId::JavaScript | LanguageId::TypeScript | LanguageId::Jsx | LanguageId::Tsx => {
+            Some(&JS_RULES)
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,135 @@
+//! Kotlin 词法高亮。crates.io 上没有与 tree-sitter 0.20 配套的 Kotlin 语法包，因此走通用扫描器。
+
+use super::lexical::{is_pascal_case, is_screaming_case, LexicalLanguage, WordContext};
+use super::HighlightKind;
+
+pub(super) static LEXICAL: LexicalLanguage = LexicalLanguage {
+    line_comments: &["//"],
+    line_comments_leading_only: false,
+    // Kotlin 的块注释可以嵌套。
+    block_comment: Some(("/*", "*/")),
+    nested_block_comments: true,
+    quotes: b"\"'",
+    interpolated_quotes: b"\"",
+    multiline_strings: &[("\"\"\"", "\"\"\"")],
+    word_extra: &[],
+    variable_sigils: &[],
+    attribute_prefix: Some(b'@'),
+    bracket_attribute: None,
+    atom_prefix: None,
+    sigil_prefix: None,
+    classify_word,
+};
+
+fn classify_word(word: &str, ctx: &WordContext<'_>) -> Option<HighlightKind> {
+    if is_kotlin_control_keyword(word) {
+        return Some(HighlightKind::KeywordControl);
+    }
+    if is_kotlin_keyword(word) {
+        return Some(HighlightKind::Keyword);
+    }
+    match word {
+        "true" | "false" => return Some(HighlightKind::Boolean),
+        "null" => return Some(HighlightKind::Constant),
+        "this" | "super" => return Some(HighlightKind::Keyword),
+        _ => {}
+    }
+
+    match ctx.prev_word {
+        Some("class" | "interface" | "object" | "typealias") => return Some(HighlightKind::Type),
+        Some("fun") => return Some(HighlightKind::Function),
+        Some("package" | "import") => return Some(HighlightKind::Namespace),
+        _ => {}
+    }
+
+    if ctx.prev == Some(b'.') {
+        return Some(if matches!(ctx.next, Some(b'(' | b'{')) {
+            HighlightKind::Method
+        } else {
+            HighlightKind::Property
+        });
+    }
+    if is_screaming_case(word) {
+        return Some(HighlightKind::Constant);
+    }
+    if is_pascal_case(word) {
+        return Some(HighlightKind::Type);
+    }
+    // `listOf(...)`、`apply { ... }`：尾随 lambda 也是调用。
+    if matches!(ctx.next, Some(b'(' | b'{')) {
+        return Some(HighlightKind::Function);
+    }
+    None
+}
+
+fn is_kotlin_control_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "if" | "else"
+            | "when"
+            | "for"
+            | "while"
+            | "do"
+            | "try"
+            | "catch"
+            | "finally"
+            | "throw"
+            | "return"
+            | "break"
+            | "continue"
+    )
+}
+
+fn is_kotlin_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "fun"
+            | "val"
+            | "var"
+            | "class"
+            | "interface"
+            | "object"
+            | "companion"
+            | "typealias"
+            | "package"
+            | "import"
+            | "as"
+            | "is"
+            | "in"
+            | "out"
+            | "by"
+            | "where"
+            | "init"
+            | "constructor"
+            | "get"
+            | "set"
+            | "data"
+            | "sealed"
+            | "enum"
+            | "annotation"
+            | "inner"
+            | "value"
+            | "open"
+            | "abstract"
+            | "final"
+            | "override"
+            | "lateinit"
+            | "const"
+            | "inline"
+            | "noinline"
+            | "crossinline"
+            | "reified"
+            | "suspend"
+            | "operator"
+            | "infix"
+            | "tailrec"
+            | "external"
+            | "vararg"
+            | "public"
+            | "private"
+            | "protected"
+            | "internal"
+            | "expect"
+            | "actual"
+    )
+}
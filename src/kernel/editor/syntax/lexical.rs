@@ -0,0 +1,577 @@
+//! 手写词法高亮：用于没有可用 tree-sitter 语法包的语言。
+//!
+//! 按行扫描字节流，跨行结构（块注释 / 多行字符串）的状态由 `LineState` 逐行携带；
+//! 标识符的归类交给各语言的 `classify_word` 钩子，扫描器本身与语言无关。
+
+use ropey::Rope;
+
+use crate::models::slice_to_cow;
+
+use super::{merge_adjacent_highlight_spans, HighlightKind, HighlightSpan};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(super) enum LineState {
+    #[default]
+    Normal,
+    BlockComment {
+        depth: u16,
+    },
+    MultilineString {
+        close: &'static str,
+    },
+}
+
+/// 标识符所处的行内上下文，供 `classify_word` 判断调用 / 声明 / 成员访问等。
+#[derive(Debug, Clone, Copy)]
+pub(super) struct WordContext<'a> {
+    /// 前一个非空白字节。
+    pub(super) prev: Option<u8>,
+    /// 后一个非空白字节。
+    pub(super) next: Option<u8>,
+    /// 同一行内紧邻的上一个标识符（中间只隔空白）。
+    pub(super) prev_word: Option<&'a str>,
+    /// 标识符在行内的字节起点；0 表示顶格（无缩进）。
+    pub(super) start: usize,
+}
+
+pub(super) struct LexicalLanguage {
+    pub(super) line_comments: &'static [&'static str],
+    /// 行注释只在行首（忽略缩进）生效，例如 Dockerfile 的 `#`。
+    pub(super) line_comments_leading_only: bool,
+    pub(super) block_comment: Option<(&'static str, &'static str)>,
+    pub(super) nested_block_comments: bool,
+    pub(super) quotes: &'static [u8],
+    /// 支持 `$name` / `${expr}` 插值的字符串定界符首字节（Groovy GString 的 `"`）。
+    pub(super) interpolated_quotes: &'static [u8],
+    /// 可跨行的字符串定界符 `(open, close)`，优先于 `quotes` 匹配。
+    pub(super) multiline_strings: &'static [(&'static str, &'static str)],
+    /// 除字母、数字、`_` 外允许出现在标识符内部的字节。
+    pub(super) word_extra: &'static [u8],
+    /// `$VAR` / `${VAR}` / `$(VAR)` 形式的变量引用前缀。
+    pub(super) variable_sigils: &'static [u8],
+    /// 注解 / 装饰器前缀（`@Override`），其后的限定名整体高亮为 Attribute。
+    pub(super) attribute_prefix: Option<u8>,
+    /// 方括号属性的起始符（Rust / Cairo 的 `#[`），到配对的 `]` 整体高亮为 Attribute。
+    pub(super) bracket_attribute: Option<&'static str>,
+    /// 符号字面量前缀（Elixir 的 `:ok` / `:"quoted"`），整体高亮为 Constant。
+    /// 前一个字节是标识符字符或同一前缀时不算（`key: value`、`::`）。
+    pub(super) atom_prefix: Option<u8>,
+    /// sigil 前缀（Elixir 的 `~r/.../i`、`~w[...]`），字母 + 定界符 + 修饰符整体高亮为 String。
+    pub(super) sigil_prefix: Option<u8>,
+    pub(super) classify_word: fn(&str, &WordContext<'_>) -> Option<HighlightKind>,
+}
+
+impl LexicalLanguage {
+    fn carries_state(&self) -> bool {
+        self.block_comment.is_some() || !self.multiline_strings.is_empty()
+    }
+}
+
+pub(super) fn highlight_lines(
+    language: &LexicalLanguage,
+    rope: &Rope,
+    start_line: usize,
+    end_line_exclusive: usize,
+) -> Vec<Vec<HighlightSpan>> {
+    let total_lines = rope.len_lines().max(1);
+    let start_line = start_line.min(total_lines);
+    let end_line_exclusive = end_line_exclusive.min(total_lines);
+    if start_line >= end_line_exclusive {
+        return Vec::new();
+    }
+
+    let mut state = LineState::Normal;
+    let mut scratch = Vec::new();
+    if language.carries_state() {
+        for line in 0..start_line {
+            scratch.clear();
+            let text = line_text(rope, line);
+            scan_line(language, text.as_ref(), &mut state, &mut scratch);
+        }
+    }
+
+    let mut out = Vec::with_capacity(end_line_exclusive - start_line);
+    for line in start_line..end_line_exclusive {
+        let text = line_text(rope, line);
+        let mut spans = Vec::new();
+        scan_line(language, text.as_ref(), &mut state, &mut spans);
+        merge_adjacent_highlight_spans(&mut spans);
+        out.push(spans);
+    }
+    out
+}
+
+pub(super) fn line_text(rope: &Rope, line: usize) -> std::borrow::Cow<'_, str> {
+    let text = slice_to_cow(rope.line(line));
+    match text {
+        std::borrow::Cow::Borrowed(s) => {
+            std::borrow::Cow::Borrowed(s.trim_end_matches(['\n', '\r']))
+        }
+        std::borrow::Cow::Owned(mut s) => {
+            let len = s.trim_end_matches(['\n', '\r']).len();
+            s.truncate(len);
+            std::borrow::Cow::Owned(s)
+        }
+    }
+}
+
+pub(super) fn scan_line(
+    language: &LexicalLanguage,
+    line: &str,
+    state: &mut LineState,
+    out: &mut Vec<HighlightSpan>,
+) {
+    let bytes = line.as_bytes();
+    let len = bytes.len();
+    let mut i = 0usize;
+
+    match *state {
+        LineState::Normal => {}
+        LineState::BlockComment { depth } => {
+            let end = scan_block_comment(language, bytes, 0, depth, state);
+            push(out, 0, end, HighlightKind::Comment);
+            i = end;
+        }
+        LineState::MultilineString { close } => {
+            let end = match find(bytes, 0, close) {
+                Some(pos) => {
+                    *state = LineState::Normal;
+                    pos + close.len()
+                }
+                None => len,
+            };
+            push_string(language, bytes, 0, end, close.as_bytes()[0], out);
+            i = end;
+        }
+    }
+
+    let mut first_token = true;
+    let mut prev_byte: Option<u8> = None;
+    let mut prev_word: Option<(usize, usize)> = None;
+
+    while i < len {
+        let b = bytes[i];
+        if b.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let token_start = i;
+        let was_first = first_token;
+        first_token = false;
+
+        // 块注释先于行注释：Lua 的 `--[[` 以行注释前缀 `--` 开头。
+        if let Some((open, _)) = language.block_comment {
+            if starts_with_at(bytes, i, open) {
+                let end = scan_block_comment(language, bytes, i + open.len(), 1, state);
+                push(out, i, end, HighlightKind::Comment);
+                i = end;
+                prev_byte = None;
+                prev_word = None;
+                continue;
+            }
+        }
+
+        if language
+            .line_comments
+            .iter()
+            .any(|prefix| starts_with_at(bytes, i, prefix))
+            && (!language.line_comments_leading_only || was_first)
+        {
+            push(out, i, len, HighlightKind::Comment);
+            return;
+        }
+
+        if let Some(&(open, close)) = language
+            .multiline_strings
+            .iter()
+            .find(|(open, _)| starts_with_at(bytes, i, open))
+        {
+            let end = match find(bytes, i + open.len(), close) {
+                Some(pos) => pos + close.len(),
+                None => {
+                    *state = LineState::MultilineString { close };
+                    len
+                }
+            };
+            push_string(language, bytes, i, end, b, out);
+            i = end;
+            prev_byte = Some(b'"');
+            prev_word = None;
+            continue;
+        }
+
+        if language.quotes.contains(&b) {
+            let end = scan_quoted(bytes, i, b);
+            push_string(language, bytes, i, end, b, out);
+            i = end;
+            prev_byte = Some(b);
+            prev_word = None;
+            continue;
+        }
+
+        if language.atom_prefix == Some(b) {
+            let after_word = i > 0 && (is_word_continue(bytes[i - 1]) || bytes[i - 1] == b);
+            if !after_word {
+                if let Some(end) = scan_atom(language, bytes, i) {
+                    push(out, i, end, HighlightKind::Constant);
+                    i = end;
+                    prev_byte = Some(b'"');
+                    prev_word = None;
+                    continue;
+                }
+            }
+        }
+
+        if language.sigil_prefix == Some(b) {
+            if let Some(end) = scan_sigil(bytes, i) {
+                push(out, i, end, HighlightKind::String);
+                i = end;
+                prev_byte = Some(b'"');
+                prev_word = None;
+                continue;
+            }
+        }
+
+        if language.variable_sigils.contains(&b) {
+            if let Some(end) = scan_variable(bytes, i) {
+                push(out, i, end, HighlightKind::Variable);
+                i = end;
+                prev_byte = Some(b);
+                prev_word = None;
+                continue;
+            }
+        }
+
+        if language.attribute_prefix == Some(b) && bytes.get(i + 1).is_some_and(is_word_start) {
+            let mut end = i + 1;
+            while end < len && (is_word_continue(bytes[end]) || bytes[end] == b'.') {
+                end += 1;
+            }
+            push(out, i, end, HighlightKind::Attribute);
+            i = end;
+            prev_byte = Some(b);
+            prev_word = None;
+            continue;
+        }
+
+        if let Some(open) = language.bracket_attribute {
+            if starts_with_at(bytes, i, open) {
+                let end = scan_bracket_attribute(bytes, i + open.len());
+                push(out, i, end, HighlightKind::Attribute);
+                i = end;
+                prev_byte = Some(b']');
+                prev_word = None;
+                continue;
+            }
+        }
+
+        if b.is_ascii_digit() {
+            let end = scan_number(bytes, i);
+            push(out, i, end, HighlightKind::Number);
+            i = end;
+            prev_byte = Some(b'0');
+            prev_word = None;
+            continue;
+        }
+
+        if is_word_start(&b) {
+            let mut end = i + 1;
+            while end < len
+                && (is_word_continue(bytes[end]) || language.word_extra.contains(&bytes[end]))
+            {
+                end += 1;
+            }
+            let word = &line[token_start..end];
+            let ctx = WordContext {
+                prev: prev_byte,
+                next: next_non_space(bytes, end),
+                prev_word: prev_word.map(|(s, e)| &line[s..e]),
+                start: token_start,
+            };
+            if let Some(kind) = (language.classify_word)(word, &ctx) {
+                push(out, token_start, end, kind);
+            }
+            i = end;
+            prev_byte = Some(bytes[end - 1]);
+            prev_word = Some((token_start, end));
+            continue;
+        }
+
+        // 标点 / 运算符：只推进并记录上下文。
+        i += utf8_len(b);
+        prev_byte = Some(b);
+        prev_word = None;
+    }
+}
+
+fn scan_block_comment(
+    language: &LexicalLanguage,
+    bytes: &[u8],
+    mut i: usize,
+    mut depth: u16,
+    state: &mut LineState,
+) -> usize {
+    let Some((open, close)) = language.block_comment else {
+        *state = LineState::Normal;
+        return bytes.len();
+    };
+
+    while i < bytes.len() {
+        if starts_with_at(bytes, i, close) {
+            depth = depth.saturating_sub(1);
+            i += close.len();
+            if depth == 0 {
+                *state = LineState::Normal;
+                return i;
+            }
+            continue;
+        }
+        if language.nested_block_comments && starts_with_at(bytes, i, open) {
+            depth = depth.saturating_add(1);
+            i += open.len();
+            continue;
+        }
+        i += 1;
+    }
+
+    *state = LineState::BlockComment {
+        depth: depth.max(1),
+    };
+    bytes.len()
+}
+
+fn scan_quoted(bytes: &[u8], start: usize, quote: u8) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b if b == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// 字符串片段：定界符属于 `interpolated_quotes` 时，`$name` / `${expr}` 切成 Variable，
+/// 其余仍是 String。`\$` 是转义，不算插值。
+fn push_string(
+    language: &LexicalLanguage,
+    bytes: &[u8],
+    start: usize,
+    end: usize,
+    quote: u8,
+    out: &mut Vec<HighlightSpan>,
+) {
+    if !language.interpolated_quotes.contains(&quote) {
+        push(out, start, end, HighlightKind::String);
+        return;
+    }
+
+    let mut segment = start;
+    let mut i = start;
+    while i < end {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'$' if bytes.get(i + 1) != Some(&b'(') => match scan_variable(bytes, i) {
+                Some(var_end) => {
+                    let var_end = var_end.min(end);
+                    push(out, segment, i, HighlightKind::String);
+                    push(out, i, var_end, HighlightKind::Variable);
+                    segment = var_end;
+                    i = var_end;
+                }
+                None => i += 1,
+            },
+            _ => i += 1,
+        }
+    }
+    push(out, segment, end, HighlightKind::String);
+}
+
+fn scan_variable(bytes: &[u8], start: usize) -> Option<usize> {
+    let open = *bytes.get(start + 1)?;
+    let close = match open {
+        b'{' => b'}',
+        b'(' => b')',
+        b if is_word_start(&b) => {
+            let mut end = start + 2;
+            while end < bytes.len() && is_word_continue(bytes[end]) {
+                end += 1;
+            }
+            return Some(end);
+        }
+        _ => return None,
+    };
+
+    let mut depth = 0usize;
+    for (offset, &b) in bytes[start + 1..].iter().enumerate() {
+        if b == open {
+            depth += 1;
+        } else if b == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(start + 1 + offset + 1);
+            }
+        }
+    }
+    Some(bytes.len())
+}
+
+/// `:name` / `:name?` / `:"quoted"`；前缀后既不是标识符也不是引号时返回 `None`。
+fn scan_atom(language: &LexicalLanguage, bytes: &[u8], start: usize) -> Option<usize> {
+    let next = *bytes.get(start + 1)?;
+    if next == b'"' || next == b'\'' {
+        return Some(scan_quoted(bytes, start + 1, next));
+    }
+    if !is_word_start(&next) {
+        return None;
+    }
+    let mut end = start + 2;
+    while end < bytes.len()
+        && (is_word_continue(bytes[end]) || language.word_extra.contains(&bytes[end]))
+    {
+        end += 1;
+    }
+    Some(end)
+}
+
+/// `~r/.../i`：前缀后一个或多个字母，再接定界符；成对括号按配对闭合，其余定界符首尾相同。
+/// 定界符是三引号时只吃到字母为止，后面的 heredoc 交给 `multiline_strings` 跨行处理。
+fn scan_sigil(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut i = start + 1;
+    while i < bytes.len() && bytes[i].is_ascii_alphabetic() {
+        i += 1;
+    }
+    if i == start + 1 {
+        return None;
+    }
+    let open = *bytes.get(i)?;
+    if starts_with_at(bytes, i, "\"\"\"") || starts_with_at(bytes, i, "'''") {
+        return Some(i);
+    }
+    let close = match open {
+        b'(' => b')',
+        b'[' => b']',
+        b'{' => b'}',
+        b'<' => b'>',
+        b'/' | b'|' | b'"' | b'\'' => open,
+        _ => return None,
+    };
+
+    i += 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b if b == close => {
+                i += 1;
+                while i < bytes.len() && bytes[i].is_ascii_alphabetic() {
+                    i += 1;
+                }
+                return Some(i);
+            }
+            _ => i += 1,
+        }
+    }
+    Some(bytes.len())
+}
+
+/// 从 `[` 之后扫到配对的 `]`（跳过字符串里的括号），未闭合时到行尾。
+fn scan_bracket_attribute(bytes: &[u8], start: usize) -> usize {
+    let mut depth = 1usize;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' | b'\'' => {
+                i = scan_quoted(bytes, i, bytes[i]);
+                continue;
+            }
+            b'[' => depth += 1,
+            b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+fn scan_number(bytes: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if b.is_ascii_alphanumeric() || b == b'_' {
+            i += 1;
+        } else if b == b'.' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit) {
+            i += 2;
+        } else {
+            break;
+        }
+    }
+    i
+}
+
+fn next_non_space(bytes: &[u8], from: usize) -> Option<u8> {
+    bytes[from.min(bytes.len())..]
+        .iter()
+        .copied()
+        .find(|b| !b.is_ascii_whitespace())
+}
+
+fn find(bytes: &[u8], from: usize, needle: &str) -> Option<usize> {
+    let needle = needle.as_bytes();
+    if needle.is_empty() || from > bytes.len() {
+        return None;
+    }
+    bytes[from..]
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|pos| pos + from)
+}
+
+fn starts_with_at(bytes: &[u8], at: usize, prefix: &str) -> bool {
+    bytes
+        .get(at..)
+        .is_some_and(|rest| rest.starts_with(prefix.as_bytes()))
+}
+
+fn is_word_start(b: &u8) -> bool {
+    b.is_ascii_alphabetic() || *b == b'_' || *b >= 0x80
+}
+
+fn is_word_continue(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b >= 0x80
+}
+
+fn utf8_len(first: u8) -> usize {
+    match first {
+        0xF0..=0xFF => 4,
+        0xE0..=0xEF => 3,
+        0xC0..=0xDF => 2,
+        _ => 1,
+    }
+}
+
+pub(super) fn push(out: &mut Vec<HighlightSpan>, start: usize, end: usize, kind: HighlightKind) {
+    if start < end {
+        out.push(HighlightSpan { start, end, kind });
+    }
+}
+
+/// 首字母大写、含小写字母的标识符（`HttpRequest`）——各语言约定俗成的类型名。
+pub(super) fn is_pascal_case(word: &str) -> bool {
+    word.as_bytes().first().is_some_and(u8::is_ascii_uppercase)
+        && word.bytes().any(|b| b.is_ascii_lowercase())
+}
+
+/// 全大写常量（`MAX_SIZE`、`OK`）。
+pub(super) fn is_screaming_case(word: &str) -> bool {
+    word.as_bytes().first().is_some_and(u8::is_ascii_uppercase)
+        && word
+            .bytes()
+            .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'_')
+}
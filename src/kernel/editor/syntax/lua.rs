@@ -0,0 +1,77 @@
+//! Lua 词法高亮。crates.io 上没有与 tree-sitter 0.20 配套的 Lua 语法包，因此走通用扫描器。
+
+use super::lexical::{is_screaming_case, LexicalLanguage, WordContext};
+use super::HighlightKind;
+
+pub(super) static LEXICAL: LexicalLanguage = LexicalLanguage {
+    line_comments: &["--"],
+    line_comments_leading_only: false,
+    block_comment: Some(("--[[", "]]")),
+    nested_block_comments: false,
+    quotes: b"\"'",
+    interpolated_quotes: &[],
+    multiline_strings: &[("[[", "]]")],
+    word_extra: &[],
+    variable_sigils: &[],
+    attribute_prefix: None,
+    bracket_attribute: None,
+    atom_prefix: None,
+    sigil_prefix: None,
+    classify_word,
+};
+
+fn classify_word(word: &str, ctx: &WordContext<'_>) -> Option<HighlightKind> {
+    if is_lua_control_keyword(word) {
+        return Some(HighlightKind::KeywordControl);
+    }
+    if is_lua_keyword(word) {
+        return Some(HighlightKind::Keyword);
+    }
+    match word {
+        "true" | "false" => return Some(HighlightKind::Boolean),
+        "nil" => return Some(HighlightKind::Constant),
+        _ => {}
+    }
+
+    // `function M.setup(...)` 的 `M` 只是表名，函数名在 `.` / `:` 之后。
+    if ctx.prev_word == Some("function") && !matches!(ctx.next, Some(b'.' | b':')) {
+        return Some(HighlightKind::Function);
+    }
+    // `t.field` / `obj:method(...)`；Lua 允许 `f"str"` / `f{...}` 省略括号调用。
+    if matches!(ctx.prev, Some(b'.' | b':')) {
+        return Some(if matches!(ctx.next, Some(b'(' | b'"' | b'\'' | b'{')) {
+            HighlightKind::Method
+        } else {
+            HighlightKind::Property
+        });
+    }
+    if is_screaming_case(word) {
+        return Some(HighlightKind::Constant);
+    }
+    if ctx.next == Some(b'(') {
+        return Some(HighlightKind::Function);
+    }
+    None
+}
+
+fn is_lua_control_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "if" | "elseif"
+            | "else"
+            | "while"
+            | "repeat"
+            | "until"
+            | "for"
+            | "break"
+            | "return"
+            | "goto"
+    )
+}
+
+fn is_lua_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "function" | "local" | "end" | "do" | "then" | "in" | "and" | "or" | "not" | "self"
+    )
+}
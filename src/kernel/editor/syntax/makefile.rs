@@ -0,0 +1,351 @@
+//! Makefile 手写分词：先按行首结构区分指令 / 变量赋值 / 规则 / 配方，再扫描行内的
+//! `$(...)` 引用与 `#` 注释。没有跨行状态：`\` 续行与 `define` 块体按普通行处理。
+
+use ropey::Rope;
+
+use super::lexical::{line_text, push};
+use super::{merge_adjacent_highlight_spans, HighlightKind, HighlightSpan};
+
+pub(super) fn highlight_lines(
+    rope: &Rope,
+    start_line: usize,
+    end_line_exclusive: usize,
+) -> Vec<Vec<HighlightSpan>> {
+    let total_lines = rope.len_lines().max(1);
+    let start_line = start_line.min(total_lines);
+    let end_line_exclusive = end_line_exclusive.min(total_lines);
+    (start_line..end_line_exclusive)
+        .map(|line| {
+            let text = line_text(rope, line);
+            let mut spans = Vec::new();
+            scan_line(text.as_ref(), &mut spans);
+            merge_adjacent_highlight_spans(&mut spans);
+            spans
+        })
+        .collect()
+}
+
+fn scan_line(line: &str, out: &mut Vec<HighlightSpan>) {
+    let bytes = line.as_bytes();
+    let len = bytes.len();
+
+    // 配方行：Tab 开头的命令交给 shell，只标出 make 自己展开的引用；
+    // 整行以 `#` 开头时是 shell 注释。
+    if bytes.first() == Some(&b'\t') {
+        let start = skip_whitespace(bytes, 1);
+        if bytes.get(start) == Some(&b'#') {
+            push(out, start, len, HighlightKind::Comment);
+        } else {
+            scan_references(bytes, start, len, out);
+        }
+        return;
+    }
+
+    let start = skip_whitespace(bytes, 0);
+    if start == len {
+        return;
+    }
+    if bytes[start] == b'#' {
+        push(out, start, len, HighlightKind::Comment);
+        return;
+    }
+
+    let word_end = bytes[start..]
+        .iter()
+        .position(u8::is_ascii_whitespace)
+        .map_or(len, |offset| start + offset);
+    if let Some(kind) = directive_kind(&line[start..word_end]) {
+        push(out, start, word_end, kind);
+        // `export CC = gcc` / `override CFLAGS += -O2`：修饰符后面接的是一条赋值。
+        if kind == HighlightKind::Keyword && is_assignment_modifier(&line[start..word_end]) {
+            scan_statement(bytes, skip_whitespace(bytes, word_end), out);
+        } else {
+            scan_text(bytes, word_end, out);
+        }
+        return;
+    }
+
+    scan_statement(bytes, start, out);
+}
+
+/// 非配方行：`NAME op value` 赋值、`targets: prerequisites` 规则，或两者都不是的普通文本。
+fn scan_statement(bytes: &[u8], start: usize, out: &mut Vec<HighlightSpan>) {
+    match find_operator(bytes, start) {
+        Some(Operator::Assign { op_start, op_end }) => {
+            let name_end = trim_end(bytes, start, op_start);
+            if bytes[start..name_end].contains(&b'$') {
+                scan_references(bytes, start, name_end, out);
+            } else {
+                push(out, start, name_end, HighlightKind::Variable);
+            }
+            push(out, op_start, op_end, HighlightKind::Operator);
+            scan_text(bytes, op_end, out);
+        }
+        Some(Operator::Rule {
+            colon_start,
+            colon_end,
+        }) => {
+            push_targets(bytes, start, colon_start, out);
+            push(out, colon_start, colon_end, HighlightKind::Operator);
+            scan_text(bytes, colon_end, out);
+        }
+        None => scan_text(bytes, start, out),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Operator {
+    Assign {
+        op_start: usize,
+        op_end: usize,
+    },
+    Rule {
+        colon_start: usize,
+        colon_end: usize,
+    },
+}
+
+/// 找出行内第一个顶层（不在 `$(...)` 里）的赋值运算符或规则冒号；遇到注释即停止。
+fn find_operator(bytes: &[u8], start: usize) -> Option<Operator> {
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'#' if !is_escaped(bytes, i) => return None,
+            b'$' => i = reference_end(bytes, i).unwrap_or(i + 1),
+            b'=' => {
+                // `:=`、`::=`、`?=`、`+=`、`!=` 的前缀字节属于运算符本身。
+                let mut op_start = i;
+                while op_start > start && matches!(bytes[op_start - 1], b':' | b'?' | b'+' | b'!') {
+                    op_start -= 1;
+                }
+                return Some(Operator::Assign {
+                    op_start,
+                    op_end: i + 1,
+                });
+            }
+            b':' => {
+                let mut end = i + 1;
+                while bytes.get(end) == Some(&b':') {
+                    end += 1;
+                }
+                if bytes.get(end) == Some(&b'=') {
+                    i = end;
+                    continue;
+                }
+                return Some(Operator::Rule {
+                    colon_start: i,
+                    colon_end: end,
+                });
+            }
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// 规则目标：普通目标是 Function，`.PHONY` 这类特殊目标是 Keyword。
+fn push_targets(bytes: &[u8], start: usize, end: usize, out: &mut Vec<HighlightSpan>) {
+    let mut i = start;
+    while i < end {
+        if bytes[i].is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+        let target_start = i;
+        while i < end && !bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let target = &bytes[target_start..i];
+        if target.contains(&b'$') {
+            scan_references(bytes, target_start, i, out);
+        } else if is_special_target(target) {
+            push(out, target_start, i, HighlightKind::Keyword);
+        } else {
+            push(out, target_start, i, HighlightKind::Function);
+        }
+    }
+}
+
+/// 赋值右侧 / 先决条件等普通文本：引用 + 行尾注释。
+fn scan_text(bytes: &[u8], start: usize, out: &mut Vec<HighlightSpan>) {
+    let end = (start..bytes.len())
+        .find(|&i| bytes[i] == b'#' && !is_escaped(bytes, i))
+        .unwrap_or(bytes.len());
+    scan_references(bytes, start, end, out);
+    push(out, end, bytes.len(), HighlightKind::Comment);
+}
+
+/// `$(VAR)` / `${VAR}` / `$x` 是 Variable，`$@` 等自动变量是 Constant；
+/// `$(patsubst ...)` 这类函数调用只把 `$(name` 与闭合括号标成 Function，参数继续扫描。
+fn scan_references(bytes: &[u8], start: usize, end: usize, out: &mut Vec<HighlightSpan>) {
+    let mut i = start;
+    while i < end {
+        if bytes[i] != b'$' {
+            i += 1;
+            continue;
+        }
+        let Some(next) = bytes.get(i + 1).copied() else {
+            break;
+        };
+        match next {
+            b'$' => i += 2,
+            b'(' | b'{' => {
+                let close = reference_end(bytes, i).unwrap_or(end).min(end);
+                let inner_start = i + 2;
+                let name_end = bytes[inner_start..close]
+                    .iter()
+                    .position(|b| !(b.is_ascii_lowercase() || *b == b'-'))
+                    .map_or(close, |offset| inner_start + offset);
+                let is_call = is_function_name(&bytes[inner_start..name_end])
+                    && bytes.get(name_end).is_some_and(u8::is_ascii_whitespace);
+                if is_call {
+                    push(out, i, name_end, HighlightKind::Function);
+                    let args_end = if bytes.get(close - 1) == Some(&close_of(next)) {
+                        close - 1
+                    } else {
+                        close
+                    };
+                    scan_references(bytes, name_end, args_end, out);
+                    push(out, args_end, close, HighlightKind::Function);
+                } else if bytes
+                    .get(inner_start)
+                    .is_some_and(|b| is_automatic_variable(*b))
+                {
+                    // `$(@D)`、`$(<F)`：自动变量的目录 / 文件名变体。
+                    push(out, i, close, HighlightKind::Constant);
+                } else {
+                    push(out, i, close, HighlightKind::Variable);
+                }
+                i = close;
+            }
+            b if is_automatic_variable(b) => {
+                push(out, i, i + 2, HighlightKind::Constant);
+                i += 2;
+            }
+            b if b.is_ascii_alphanumeric() || b == b'_' => {
+                push(out, i, i + 2, HighlightKind::Variable);
+                i += 2;
+            }
+            _ => i += 1,
+        }
+    }
+}
+
+/// `$(`/`${` 引用的结束位置（闭合括号之后），按同种括号计深度；未闭合时延伸到行尾。
+fn reference_end(bytes: &[u8], dollar: usize) -> Option<usize> {
+    let open = *bytes.get(dollar + 1)?;
+    if !matches!(open, b'(' | b'{') {
+        return Some((dollar + 2).min(bytes.len()));
+    }
+    let close = close_of(open);
+    let mut depth = 0usize;
+    for (offset, &b) in bytes[dollar + 1..].iter().enumerate() {
+        if b == open {
+            depth += 1;
+        } else if b == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(dollar + 1 + offset + 1);
+            }
+        }
+    }
+    Some(bytes.len())
+}
+
+fn close_of(open: u8) -> u8 {
+    if open == b'{' {
+        b'}'
+    } else {
+        b')'
+    }
+}
+
+fn skip_whitespace(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    i
+}
+
+fn trim_end(bytes: &[u8], start: usize, mut end: usize) -> usize {
+    while end > start && bytes[end - 1].is_ascii_whitespace() {
+        end -= 1;
+    }
+    end
+}
+
+fn is_escaped(bytes: &[u8], i: usize) -> bool {
+    i > 0 && bytes[i - 1] == b'\\'
+}
+
+fn directive_kind(word: &str) -> Option<HighlightKind> {
+    match word {
+        "ifeq" | "ifneq" | "ifdef" | "ifndef" | "else" | "endif" => {
+            Some(HighlightKind::KeywordControl)
+        }
+        "include" | "-include" | "sinclude" | "define" | "endef" | "undefine" | "export"
+        | "unexport" | "override" | "private" | "vpath" => Some(HighlightKind::Keyword),
+        _ => None,
+    }
+}
+
+fn is_assignment_modifier(word: &str) -> bool {
+    matches!(word, "export" | "override" | "private")
+}
+
+/// `.PHONY`、`.SUFFIXES`、`.DEFAULT_GOAL` 等：`.` 后全是大写字母或 `_`。
+fn is_special_target(target: &[u8]) -> bool {
+    target.len() > 1
+        && target[0] == b'.'
+        && target[1..]
+            .iter()
+            .all(|b| b.is_ascii_uppercase() || *b == b'_')
+}
+
+fn is_automatic_variable(b: u8) -> bool {
+    matches!(b, b'@' | b'<' | b'^' | b'*' | b'?' | b'+' | b'|' | b'%')
+}
+
+fn is_function_name(name: &[u8]) -> bool {
+    matches!(
+        name,
+        b"subst"
+            | b"patsubst"
+            | b"strip"
+            | b"findstring"
+            | b"filter"
+            | b"filter-out"
+            | b"sort"
+            | b"word"
+            | b"wordlist"
+            | b"words"
+            | b"firstword"
+            | b"lastword"
+            | b"dir"
+            | b"notdir"
+            | b"suffix"
+            | b"basename"
+            | b"addsuffix"
+            | b"addprefix"
+            | b"join"
+            | b"wildcard"
+            | b"realpath"
+            | b"abspath"
+            | b"error"
+            | b"warning"
+            | b"info"
+            | b"shell"
+            | b"origin"
+            | b"flavor"
+            | b"foreach"
+            | b"file"
+            | b"call"
+            | b"eval"
+            | b"value"
+            | b"if"
+            | b"or"
+            | b"and"
+            | b"let"
+            | b"intcmp"
+    )
+}
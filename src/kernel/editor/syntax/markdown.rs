@@ -0,0 +1,73 @@
+//! Markdown（tree-sitter-md）节点着色。
+//!
+//! 块级：标题、围栏 / 缩进代码块、引用 / 列表标记、分隔线、HTML 块；
+//! 行内：代码段、强调 / 粗体、链接与图片、自动链接。
+//! 编辑器里非光标行的所见即所得渲染仍由 `views::editor::markdown` 负责，这里只给源码着色。
+
+use ropey::Rope;
+use tree_sitter::Node;
+
+use super::util::node_text;
+use super::HighlightKind;
+
+pub(super) fn classify(node: Node<'_>, rope: &Rope) -> Option<HighlightKind> {
+    match node.kind() {
+        "atx_heading" | "setext_heading" => Some(HighlightKind::Keyword),
+        "atx_h1_marker"
+        | "atx_h2_marker"
+        | "atx_h3_marker"
+        | "atx_h4_marker"
+        | "atx_h5_marker"
+        | "atx_h6_marker"
+        | "setext_h1_underline"
+        | "setext_h2_underline"
+        | "block_quote_marker"
+        | "list_marker_plus"
+        | "list_marker_minus"
+        | "list_marker_star"
+        | "list_marker_dot"
+        | "list_marker_parenthesis"
+        | "thematic_break"
+        | "fenced_code_block_delimiter" => Some(HighlightKind::Operator),
+        "info_string" => Some(HighlightKind::Type),
+        "code_span" | "code_fence_content" | "indented_code_block" | "link_title" => {
+            Some(HighlightKind::String)
+        }
+        "strong_emphasis" => Some(HighlightKind::Constant),
+        "emphasis" => Some(HighlightKind::Parameter),
+        "shortcut_link" if is_task_checkbox(node, rope) => Some(HighlightKind::Operator),
+        "inline_link"
+        | "full_reference_link"
+        | "collapsed_reference_link"
+        | "shortcut_link"
+        | "image" => Some(HighlightKind::Tag),
+        "link_destination" | "link_label" | "uri_autolink" | "email_autolink" => {
+            Some(HighlightKind::TagAttribute)
+        }
+        "html_block" | "html_tag" => Some(classify_html(node, rope)),
+        _ => None,
+    }
+}
+
+/// 任务列表的 `[ ]` / `[x]`：语法上是紧跟列表标记的 shortcut link。
+fn is_task_checkbox(node: Node<'_>, rope: &Rope) -> bool {
+    let Some(paragraph) = node.parent() else {
+        return false;
+    };
+    node.prev_sibling().is_none()
+        && paragraph
+            .prev_sibling()
+            .is_some_and(|marker| marker.kind().starts_with("list_marker"))
+        && node_text(rope, node).is_some_and(|text| matches!(text.as_str(), "[ ]" | "[x]" | "[X]"))
+}
+
+/// `<!-- … -->` 按注释着色，其余 HTML 按标签。
+fn classify_html(node: Node<'_>, rope: &Rope) -> HighlightKind {
+    let is_comment =
+        node_text(rope, node).is_some_and(|text| text.trim_start().starts_with("<!--"));
+    if is_comment {
+        HighlightKind::Comment
+    } else {
+        HighlightKind::Tag
+    }
+}
@@ -1,19 +1,41 @@
 //! Syntax support (in-process): parsing + highlighting helpers.
 
+mod breadcrumbs;
 mod c;
+mod cairo;
 mod data;
+mod dhall;
+mod dockerfile;
+mod elixir;
+mod erlang;
 mod go;
+mod groovy;
+mod indent;
 mod js;
+mod kotlin;
+mod lexical;
+mod lua;
+mod makefile;
+mod markdown;
 mod markup;
+mod nix;
+mod proto;
 mod python;
+mod rego;
+mod ruby;
 mod rust;
+mod solidity;
 mod sql;
+mod swift;
 mod util;
+mod wat;
 
+pub use self::breadcrumbs::Breadcrumb;
+pub use self::indent::IndentRules;
 pub(crate) use self::util::{is_comment_kind, is_regex_kind, is_string_kind};
 use crate::kernel::language::LanguageId;
 use crate::kernel::services::adapters::perf;
-use crate::models::EditOp;
+use crate::models::{word_range_at_byte, EditOp};
 use ropey::Rope;
 use std::cmp::Ordering;
 use std::collections::BTreeSet;
@@ -44,7 +66,7 @@ pub enum SyntaxColorGroup {
 impl SyntaxColorGroup {
     pub const COUNT: usize = 15;
 
-    pub const CONFIGURABLE: [Self; 13] = [
+    pub const CONFIGURABLE: [Self; 15] = [
         Self::Comment,
         Self::Keyword,
         Self::KeywordControl,
@@ -58,6 +80,8 @@ impl SyntaxColorGroup {
         Self::Variable,
         Self::Constant,
         Self::Regex,
+        Self::Operator,
+        Self::Tag,
     ];
 }
 
@@ -75,6 +99,8 @@ pub const DEFAULT_CONFIGURABLE_SYNTAX_RGB_HEX: [u32; SyntaxColorGroup::CONFIGURA
     0x9CDCFE, // Variable
     0x4FC1FF, // Constant
     0xD16969, // Regex
+    0xD4D4D4, // Operator
+    0x4EC9B0, // Tag
 ];
 
 const _: () = assert!(SyntaxColorGroup::COUNT == 15);
@@ -241,6 +267,10 @@ impl SyntaxDocument {
         &self.tree
     }
 
+    pub fn indent_rules(&self) -> Option<&'static IndentRules> {
+        indent::indent_rules(self.language)
+    }
+
     pub fn reparse(&mut self, rope: &Rope) {
         if let Some(tree) = parse_rope(&mut self.parser, rope, None) {
             self.tree = tree;
@@ -278,6 +308,11 @@ impl SyntaxDocument {
         }
     }
 
+    /// 光标处由外到内的符号作用域（模块 / impl / 函数 …），供面包屑栏显示。
+    pub fn breadcrumbs_at(&self, rope: &Rope, byte_offset: usize) -> Vec<Breadcrumb> {
+        breadcrumbs::breadcrumbs_at(&self.tree, rope, byte_offset)
+    }
+
     pub fn is_in_string_or_comment(&self, byte_offset: usize) -> bool {
         let root = self.tree.root_node();
         let Some(mut node) = root.descendant_for_byte_range(byte_offset, byte_offset) else {
@@ -318,33 +353,12 @@ impl SyntaxDocument {
 }
 
 pub(crate) fn identifier_bounds_at(rope: &Rope, char_offset: usize) -> Option<(usize, usize)> {
-    let len_chars = rope.len_chars();
-    let cursor = char_offset.min(len_chars);
-
-    let mut start = cursor;
-    while start > 0 {
-        let ch = rope.char(start - 1);
-        if ch == '_' || UnicodeXID::is_xid_continue(ch) {
-            start = start.saturating_sub(1);
-        } else {
-            break;
-        }
-    }
-
-    let mut end = cursor;
-    while end < len_chars {
-        let ch = rope.char(end);
-        if ch == '_' || UnicodeXID::is_xid_continue(ch) {
-            end = end.saturating_add(1);
-        } else {
-            break;
-        }
-    }
-
-    if start == end {
-        return None;
-    }
+    let byte = rope.char_to_byte(char_offset.min(rope.len_chars()));
+    let range = word_range_at_byte(rope, byte)?;
+    let start = rope.byte_to_char(range.start);
+    let end = rope.byte_to_char(range.end);
 
+    // 单词可以以数字开头（`123`），标识符不行。
     let first = rope.char(start);
     if !(first == '_' || UnicodeXID::is_xid_start(first)) {
         return None;
@@ -568,7 +582,8 @@ pub fn highlight_snippet(language: LanguageId, text: &str) -> Vec<Vec<HighlightS
     let total_lines = rope.len_lines().max(1);
 
     let Some(tree) = parse_tree(language, &rope) else {
-        return vec![Vec::new(); total_lines];
+        return lexical_highlight_lines(language, &rope, 0, total_lines)
+            .unwrap_or_else(|| vec![Vec::new(); total_lines]);
     };
 
     let start_byte = 0;
@@ -596,10 +611,81 @@ fn parser_language(language: LanguageId) -> Option<TsLanguage> {
         LanguageId::Toml => Some(tree_sitter_toml::language()),
         LanguageId::Sql => Some(db3_sqlparser::language()),
         LanguageId::Bash => Some(tree_sitter_bash::language()),
-        LanguageId::Markdown => None,
+        LanguageId::Solidity => Some(tree_sitter_solidity::language()),
+        LanguageId::Markdown => Some(tree_sitter_md::language()),
+        LanguageId::Protobuf
+        | LanguageId::Groovy
+        | LanguageId::Wat
+        | LanguageId::Rego
+        | LanguageId::Makefile
+        | LanguageId::Dockerfile
+        | LanguageId::Cairo
+        | LanguageId::Ruby
+        | LanguageId::Lua
+        | LanguageId::Elixir
+        | LanguageId::Erlang
+        | LanguageId::Kotlin
+        | LanguageId::Swift
+        | LanguageId::Nix
+        | LanguageId::Dhall => None,
     }
 }
 
+/// 没有 tree-sitter 语法包、改用手写词法扫描的语言。
+fn lexical_language(language: LanguageId) -> Option<&'static lexical::LexicalLanguage> {
+    match language {
+        LanguageId::Protobuf => Some(&proto::LEXICAL),
+        LanguageId::Groovy => Some(&groovy::LEXICAL),
+        LanguageId::Wat => Some(&wat::LEXICAL),
+        LanguageId::Rego => Some(&rego::LEXICAL),
+        LanguageId::Cairo => Some(&cairo::LEXICAL),
+        LanguageId::Ruby => Some(&ruby::LEXICAL),
+        LanguageId::Lua => Some(&lua::LEXICAL),
+        LanguageId::Elixir => Some(&elixir::LEXICAL),
+        LanguageId::Erlang => Some(&erlang::LEXICAL),
+        LanguageId::Kotlin => Some(&kotlin::LEXICAL),
+        LanguageId::Swift => Some(&swift::LEXICAL),
+        LanguageId::Nix => Some(&nix::LEXICAL),
+        LanguageId::Dhall => Some(&dhall::LEXICAL),
+        _ => None,
+    }
+}
+
+/// 词法高亮：逐行扫描，返回值与 `highlight_lines_for_range` 同形（行内字节偏移）。
+pub(crate) fn lexical_highlight_lines(
+    language: LanguageId,
+    rope: &Rope,
+    start_line: usize,
+    end_line_exclusive: usize,
+) -> Option<Vec<Vec<HighlightSpan>>> {
+    // Makefile 的规则 / 赋值 / 配方行按行首结构区分，通用扫描器表达不了，走专用分词。
+    if language == LanguageId::Makefile {
+        let _scope = perf::scope("syntax.highlight.lexical");
+        return Some(makefile::highlight_lines(
+            rope,
+            start_line,
+            end_line_exclusive,
+        ));
+    }
+    // Dockerfile 只认行首的指令词，续行要回看上一行。
+    if language == LanguageId::Dockerfile {
+        let _scope = perf::scope("syntax.highlight.lexical");
+        return Some(dockerfile::highlight_lines(
+            rope,
+            start_line,
+            end_line_exclusive,
+        ));
+    }
+    let lexical = lexical_language(language)?;
+    let _scope = perf::scope("syntax.highlight.lexical");
+    Some(lexical::highlight_lines(
+        lexical,
+        rope,
+        start_line,
+        end_line_exclusive,
+    ))
+}
+
 fn configure_parser_language(parser: &mut Parser, language: LanguageId) -> bool {
     let Some(grammar) = parser_language(language) else {
         return false;
@@ -1098,6 +1184,10 @@ fn merge_adjacent_abs_spans(spans: &mut Vec<AbsHighlightSpan>) {
 fn classify_node(language: LanguageId, node: Node<'_>, rope: &Rope) -> Option<HighlightKind> {
     let kind = node.kind();
 
+    // Markdown 的节点名（`info_string`、`link_title` …）会误中下面按名字猜的通用规则。
+    if language == LanguageId::Markdown {
+        return markdown::classify(node, rope);
+    }
     if is_comment_kind(kind) {
         return Some(HighlightKind::Comment);
     }
@@ -1160,6 +1250,11 @@ fn classify_node(language: LanguageId, node: Node<'_>, rope: &Rope) -> Option<Hi
             }
         }
         LanguageId::Java => {}
+        LanguageId::Solidity => {
+            if let Some(kind) = solidity::classify(node, rope, language) {
+                return Some(kind);
+            }
+        }
         LanguageId::Sql => {
             if let Some(kind) = sql::classify(kind) {
                 return Some(kind);
@@ -1170,7 +1265,25 @@ fn classify_node(language: LanguageId, node: Node<'_>, rope: &Rope) -> Option<Hi
                 return Some(kind);
             }
         }
-        LanguageId::Json | LanguageId::Yaml | LanguageId::Toml | LanguageId::Markdown => {}
+        LanguageId::Json
+        | LanguageId::Yaml
+        | LanguageId::Toml
+        | LanguageId::Markdown
+        | LanguageId::Protobuf
+        | LanguageId::Groovy
+        | LanguageId::Wat
+        | LanguageId::Rego
+        | LanguageId::Makefile
+        | LanguageId::Dockerfile
+        | LanguageId::Cairo
+        | LanguageId::Ruby
+        | LanguageId::Lua
+        | LanguageId::Elixir
+        | LanguageId::Erlang
+        | LanguageId::Kotlin
+        | LanguageId::Swift
+        | LanguageId::Nix
+        | LanguageId::Dhall => {}
         LanguageId::Html | LanguageId::Xml => {
             if let Some(kind) = markup::classify_markup(node, rope, language) {
                 return Some(kind);
@@ -1211,7 +1324,23 @@ fn is_keyword(language: LanguageId, kind: &str) -> bool {
         LanguageId::Html | LanguageId::Xml => false,
         LanguageId::Css => false,
         LanguageId::Bash => markup::is_bash_keyword(kind),
-        LanguageId::Markdown => false,
+        LanguageId::Solidity => solidity::is_keyword(kind),
+        LanguageId::Markdown
+        | LanguageId::Protobuf
+        | LanguageId::Groovy
+        | LanguageId::Wat
+        | LanguageId::Rego
+        | LanguageId::Makefile
+        | LanguageId::Dockerfile
+        | LanguageId::Cairo
+        | LanguageId::Ruby
+        | LanguageId::Lua
+        | LanguageId::Elixir
+        | LanguageId::Erlang
+        | LanguageId::Kotlin
+        | LanguageId::Swift
+        | LanguageId::Nix
+        | LanguageId::Dhall => false,
     }
 }
 
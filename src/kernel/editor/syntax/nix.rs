@@ -0,0 +1,48 @@
+//! Nix 词法高亮。crates.io 上没有与 tree-sitter 0.20 配套的 Nix 语法包，因此走通用扫描器。
+
+use super::lexical::{LexicalLanguage, WordContext};
+use super::HighlightKind;
+
+pub(super) static LEXICAL: LexicalLanguage = LexicalLanguage {
+    line_comments: &["#"],
+    line_comments_leading_only: false,
+    block_comment: Some(("/*", "*/")),
+    nested_block_comments: false,
+    quotes: b"\"",
+    interpolated_quotes: b"\"",
+    // indented string：`'' ... ''`。
+    multiline_strings: &[("''", "''")],
+    // `mkDerivation'`、`foo-bar` 都是合法标识符。
+    word_extra: b"-'",
+    variable_sigils: &[],
+    attribute_prefix: None,
+    bracket_attribute: None,
+    atom_prefix: None,
+    sigil_prefix: None,
+    classify_word,
+};
+
+fn classify_word(word: &str, ctx: &WordContext<'_>) -> Option<HighlightKind> {
+    match word {
+        "if" | "then" | "else" | "assert" => return Some(HighlightKind::KeywordControl),
+        "let" | "in" | "rec" | "with" | "inherit" | "or" => return Some(HighlightKind::Keyword),
+        "true" | "false" => return Some(HighlightKind::Boolean),
+        "null" => return Some(HighlightKind::Constant),
+        "builtins" => return Some(HighlightKind::Namespace),
+        "import" | "throw" | "abort" | "derivation" | "toString" | "map" | "baseNameOf"
+        | "dirOf" | "isNull" | "removeAttrs" | "fetchTarball" => {
+            return Some(HighlightKind::Function)
+        }
+        _ => {}
+    }
+
+    // `pkgs.hello`、`lib.mkIf`：属性路径上的成员。
+    if ctx.prev == Some(b'.') {
+        return Some(HighlightKind::Property);
+    }
+    // `name = value;`：attrset 的键。
+    if ctx.next == Some(b'=') {
+        return Some(HighlightKind::Property);
+    }
+    None
+}
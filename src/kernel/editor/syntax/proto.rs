@@ -0,0 +1,116 @@
+//! Protobuf 词法高亮。构建所用的 registry 里没有 `tree-sitter-proto` 之类的语法包，
+//! 也就没有与 tree-sitter 0.20 配套的版本，因此走通用扫描器。
+
+use super::lexical::{is_pascal_case, is_screaming_case, LexicalLanguage, WordContext};
+use super::HighlightKind;
+
+pub(super) static LEXICAL: LexicalLanguage = LexicalLanguage {
+    line_comments: &["//"],
+    line_comments_leading_only: false,
+    block_comment: Some(("/*", "*/")),
+    nested_block_comments: false,
+    quotes: b"\"'",
+    interpolated_quotes: &[],
+    multiline_strings: &[],
+    word_extra: &[],
+    variable_sigils: &[],
+    attribute_prefix: None,
+    bracket_attribute: None,
+    atom_prefix: None,
+    sigil_prefix: None,
+    classify_word,
+};
+
+fn classify_word(word: &str, ctx: &WordContext<'_>) -> Option<HighlightKind> {
+    if is_proto_keyword(word) {
+        return Some(HighlightKind::Keyword);
+    }
+    if is_proto_field_label(word) {
+        return Some(HighlightKind::KeywordControl);
+    }
+    if is_proto_scalar_type(word) {
+        return Some(HighlightKind::TypeBuiltin);
+    }
+    if matches!(word, "true" | "false") {
+        return Some(HighlightKind::Boolean);
+    }
+    // `reserved 2 to 5;` / `extensions 100 to max;`：只在范围语境里才是关键字。
+    if (word == "to" && ctx.prev == Some(b'0')) || (word == "max" && ctx.prev_word == Some("to")) {
+        return Some(HighlightKind::Keyword);
+    }
+    if matches!(word, "inf" | "nan") {
+        return Some(HighlightKind::Number);
+    }
+
+    match ctx.prev_word {
+        Some("message" | "enum" | "service" | "extend") => return Some(HighlightKind::Type),
+        Some("rpc") => return Some(HighlightKind::Function),
+        Some("package" | "syntax" | "edition") => return Some(HighlightKind::Namespace),
+        Some("option") => return Some(HighlightKind::Attribute),
+        _ => {}
+    }
+
+    // `[deprecated = true, json_name = "x"]` 形式的字段选项名。
+    if matches!(ctx.prev, Some(b'[' | b',')) && ctx.next == Some(b'=') {
+        return Some(HighlightKind::Attribute);
+    }
+    if is_screaming_case(word) {
+        return Some(HighlightKind::EnumMember);
+    }
+    if is_pascal_case(word) {
+        return Some(HighlightKind::Type);
+    }
+    if ctx.next == Some(b'=') {
+        return Some(HighlightKind::Property);
+    }
+    None
+}
+
+fn is_proto_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "syntax"
+            | "edition"
+            | "package"
+            | "import"
+            | "public"
+            | "weak"
+            | "option"
+            | "message"
+            | "enum"
+            | "service"
+            | "rpc"
+            | "returns"
+            | "reserved"
+            | "extensions"
+            | "extend"
+            | "oneof"
+            | "map"
+            | "group"
+    )
+}
+
+fn is_proto_field_label(word: &str) -> bool {
+    matches!(word, "repeated" | "required" | "optional" | "stream")
+}
+
+fn is_proto_scalar_type(word: &str) -> bool {
+    matches!(
+        word,
+        "double"
+            | "float"
+            | "int32"
+            | "int64"
+            | "uint32"
+            | "uint64"
+            | "sint32"
+            | "sint64"
+            | "fixed32"
+            | "fixed64"
+            | "sfixed32"
+            | "sfixed64"
+            | "bool"
+            | "string"
+            | "bytes"
+    )
+}
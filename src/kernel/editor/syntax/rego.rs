@@ -0,0 +1,56 @@
+//! Rego 词法高亮。构建所用的 registry 里没有 `tree-sitter-rego` 语法包，
+//! 也就没有与 tree-sitter 0.20 配套的版本，因此走通用扫描器。
+
+use super::lexical::{LexicalLanguage, WordContext};
+use super::HighlightKind;
+
+/// OPA Rego 策略语言。反引号是不转义的原始字符串，可以跨行。
+pub(super) static LEXICAL: LexicalLanguage = LexicalLanguage {
+    line_comments: &["#"],
+    line_comments_leading_only: false,
+    block_comment: None,
+    nested_block_comments: false,
+    quotes: b"\"",
+    interpolated_quotes: &[],
+    multiline_strings: &[("`", "`")],
+    word_extra: &[],
+    variable_sigils: &[],
+    attribute_prefix: None,
+    bracket_attribute: None,
+    atom_prefix: None,
+    sigil_prefix: None,
+    classify_word,
+};
+
+fn classify_word(word: &str, ctx: &WordContext<'_>) -> Option<HighlightKind> {
+    // `package authz`、`import data.lib`：路径首段（含 `data`）按命名空间着色。
+    if matches!(ctx.prev_word, Some("package" | "import")) {
+        return Some(HighlightKind::Namespace);
+    }
+    match word {
+        "package" | "import" | "default" | "as" | "contains" => {
+            return Some(HighlightKind::Keyword)
+        }
+        "if" | "else" | "some" | "every" => return Some(HighlightKind::KeywordControl),
+        "not" | "in" | "with" => return Some(HighlightKind::KeywordOperator),
+        "true" | "false" => return Some(HighlightKind::Boolean),
+        "null" => return Some(HighlightKind::Constant),
+        // 两个根文档：外部输入与已加载的数据。
+        "input" | "data" => return Some(HighlightKind::Variable),
+        _ => {}
+    }
+
+    // 内置函数调用：`count(x)`、`startswith(s, "a")`、`time.now_ns()`。
+    if ctx.next == Some(b'(') {
+        return Some(HighlightKind::Function);
+    }
+    if ctx.prev == Some(b'.') {
+        return Some(HighlightKind::Property);
+    }
+    // 规则头：顶格的名字（`allow if {`、`deny contains msg if`、`violations[x] {`），
+    // 以及 `default allow := false`。
+    if ctx.start == 0 || ctx.prev_word == Some("default") {
+        return Some(HighlightKind::Function);
+    }
+    None
+}
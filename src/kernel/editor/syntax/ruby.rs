@@ -0,0 +1,119 @@
+//! Ruby 词法高亮。缓存里的 `tree-sitter-ruby` 只有绑定 tree-sitter 0.19 / 0.23 的版本，
+//! 与本仓库的 0.20 运行时不兼容，因此走通用扫描器。
+
+use super::lexical::{is_pascal_case, is_screaming_case, LexicalLanguage, WordContext};
+use super::HighlightKind;
+
+pub(super) static LEXICAL: LexicalLanguage = LexicalLanguage {
+    line_comments: &["#"],
+    line_comments_leading_only: false,
+    block_comment: Some(("=begin", "=end")),
+    nested_block_comments: false,
+    quotes: b"\"'`",
+    interpolated_quotes: &[],
+    multiline_strings: &[],
+    // `empty?` / `save!` 这类谓词 / 破坏性方法名。
+    word_extra: b"?!",
+    // `@ivar` / `$global`。
+    variable_sigils: b"@$",
+    attribute_prefix: None,
+    bracket_attribute: None,
+    atom_prefix: None,
+    sigil_prefix: None,
+    classify_word,
+};
+
+fn classify_word(word: &str, ctx: &WordContext<'_>) -> Option<HighlightKind> {
+    if is_ruby_control_keyword(word) {
+        return Some(HighlightKind::KeywordControl);
+    }
+    if is_ruby_keyword(word) {
+        return Some(HighlightKind::Keyword);
+    }
+    match word {
+        "true" | "false" => return Some(HighlightKind::Boolean),
+        "nil" => return Some(HighlightKind::Constant),
+        _ => {}
+    }
+
+    match ctx.prev_word {
+        Some("class") => return Some(HighlightKind::Type),
+        Some("module") => return Some(HighlightKind::Namespace),
+        Some("def") => return Some(HighlightKind::Function),
+        _ => {}
+    }
+
+    // Ruby 的成员访问都是方法调用，括号可省略。
+    if ctx.prev == Some(b'.') {
+        return Some(HighlightKind::Method);
+    }
+    if is_screaming_case(word) {
+        return Some(HighlightKind::Constant);
+    }
+    if is_pascal_case(word) {
+        return Some(HighlightKind::Type);
+    }
+    if ctx.next == Some(b'(') {
+        return Some(HighlightKind::Function);
+    }
+    None
+}
+
+fn is_ruby_control_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "if" | "elsif"
+            | "else"
+            | "unless"
+            | "while"
+            | "until"
+            | "for"
+            | "case"
+            | "when"
+            | "break"
+            | "next"
+            | "redo"
+            | "retry"
+            | "return"
+            | "yield"
+            | "begin"
+            | "rescue"
+            | "ensure"
+    )
+}
+
+fn is_ruby_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "def"
+            | "class"
+            | "module"
+            | "end"
+            | "do"
+            | "then"
+            | "in"
+            | "and"
+            | "or"
+            | "not"
+            | "alias"
+            | "undef"
+            | "defined?"
+            | "self"
+            | "super"
+            | "BEGIN"
+            | "END"
+            | "__FILE__"
+            | "__LINE__"
+            | "__method__"
+            | "require"
+            | "require_relative"
+            | "include"
+            | "extend"
+            | "attr_reader"
+            | "attr_writer"
+            | "attr_accessor"
+            | "private"
+            | "protected"
+            | "public"
+    )
+}
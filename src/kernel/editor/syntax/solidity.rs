@@ -0,0 +1,141 @@
+use crate::kernel::language::LanguageId;
+use ropey::Rope;
+use tree_sitter::Node;
+
+use super::util::{node_is_field, node_text, same_node};
+use super::HighlightKind;
+
+pub(super) fn classify(node: Node<'_>, rope: &Rope, _lang: LanguageId) -> Option<HighlightKind> {
+    match node.kind() {
+        "identifier" => classify_solidity_identifier(node, rope),
+        "enum_value" => Some(HighlightKind::Constant),
+        "if" | "else" | "for" | "while" | "do" | "break" | "continue" | "return" | "try"
+        | "catch" | "emit" | "revert" => Some(HighlightKind::KeywordControl),
+        _ => None,
+    }
+}
+
+pub(super) fn is_keyword(kind: &str) -> bool {
+    is_solidity_keyword(kind)
+}
+
+fn classify_solidity_identifier(node: Node<'_>, rope: &Rope) -> Option<HighlightKind> {
+    let parent = node.parent()?;
+    // 表达式位置的标识符外面总包着一层 `expression`，要看再上一层。
+    let (parent, anchor) = if parent.kind() == "expression" {
+        (parent.parent()?, parent)
+    } else {
+        (parent, node)
+    };
+    match parent.kind() {
+        "contract_declaration"
+        | "library_declaration"
+        | "interface_declaration"
+        | "struct_declaration"
+        | "enum_declaration"
+            if node_is_field(parent, "name", anchor) || is_first_identifier(parent, node) =>
+        {
+            Some(HighlightKind::Type)
+        }
+        "user_defined_type" => Some(HighlightKind::Type),
+        "function_definition"
+        | "modifier_definition"
+        | "event_definition"
+        | "error_declaration"
+            if node_is_field(parent, "name", anchor) || is_first_identifier(parent, node) =>
+        {
+            Some(HighlightKind::Function)
+        }
+        "modifier_invocation" => Some(HighlightKind::Attribute),
+        // `emit Transfer(...)` / `revert Unauthorized(...)`：事件与自定义错误按调用处理。
+        "emit_statement" | "revert_statement" => Some(HighlightKind::Function),
+        "call_expression" if node_is_field(parent, "function", anchor) => {
+            if matches!(node_text(rope, node).as_deref(), Some("require" | "assert")) {
+                Some(HighlightKind::KeywordControl)
+            } else {
+                Some(HighlightKind::Function)
+            }
+        }
+        "member_expression" if node_is_field(parent, "property", node) => {
+            let is_call = parent.parent().is_some_and(|expr| {
+                expr.kind() == "expression"
+                    && expr.parent().is_some_and(|call| {
+                        call.kind() == "call_expression" && node_is_field(call, "function", expr)
+                    })
+            });
+            Some(if is_call {
+                HighlightKind::Method
+            } else {
+                HighlightKind::Property
+            })
+        }
+        "parameter" | "event_parameter" | "error_parameter" => Some(HighlightKind::Parameter),
+        "state_variable_declaration" | "struct_member" => Some(HighlightKind::Property),
+        "variable_declaration" if node_is_field(parent, "name", node) => {
+            Some(HighlightKind::Variable)
+        }
+        _ => None,
+    }
+}
+
+/// 有些声明没有 `name` 字段（如 `library` / `error`），名字就是第一个具名的 `identifier` 子节点。
+fn is_first_identifier(parent: Node<'_>, node: Node<'_>) -> bool {
+    let mut cursor = parent.walk();
+    let first = parent
+        .named_children(&mut cursor)
+        .find(|child| child.kind() == "identifier");
+    first.is_some_and(|first| same_node(first, node))
+}
+
+fn is_solidity_keyword(kind: &str) -> bool {
+    matches!(
+        kind,
+        "pragma"
+            | "solidity"
+            | "import"
+            | "from"
+            | "as"
+            | "contract"
+            | "library"
+            | "interface"
+            | "abstract"
+            | "is"
+            | "function"
+            | "modifier"
+            | "constructor"
+            | "fallback"
+            | "receive"
+            | "event"
+            | "error"
+            | "struct"
+            | "enum"
+            | "mapping"
+            | "returns"
+            | "public"
+            | "private"
+            | "internal"
+            | "external"
+            | "pure"
+            | "view"
+            | "payable"
+            | "constant"
+            | "immutable"
+            | "transient"
+            | "override"
+            | "virtual"
+            | "indexed"
+            | "anonymous"
+            | "memory"
+            | "storage"
+            | "calldata"
+            | "using"
+            | "new"
+            | "delete"
+            | "unchecked"
+            | "assembly"
+            | "let"
+            | "type"
+            | "true"
+            | "false"
+    )
+}
@@ -0,0 +1,142 @@
+//! Swift 词法高亮。缓存里的 `tree-sitter-swift` 只有绑定新版 tree-sitter-language ABI 的版本，
+//! 与本仓库的 0.20 运行时不兼容，因此走通用扫描器。
+
+use super::lexical::{is_pascal_case, is_screaming_case, LexicalLanguage, WordContext};
+use super::HighlightKind;
+
+pub(super) static LEXICAL: LexicalLanguage = LexicalLanguage {
+    line_comments: &["//"],
+    line_comments_leading_only: false,
+    // Swift 的块注释可以嵌套。
+    block_comment: Some(("/*", "*/")),
+    nested_block_comments: true,
+    quotes: b"\"",
+    // 插值是 `\(expr)`，不走 `$` 插值。
+    interpolated_quotes: &[],
+    multiline_strings: &[("\"\"\"", "\"\"\"")],
+    word_extra: &[],
+    variable_sigils: &[],
+    // `@MainActor` / `@escaping` / `@propertyWrapper`。
+    attribute_prefix: Some(b'@'),
+    bracket_attribute: None,
+    atom_prefix: None,
+    sigil_prefix: None,
+    classify_word,
+};
+
+fn classify_word(word: &str, ctx: &WordContext<'_>) -> Option<HighlightKind> {
+    if is_swift_control_keyword(word) {
+        return Some(HighlightKind::KeywordControl);
+    }
+    if is_swift_keyword(word) {
+        return Some(HighlightKind::Keyword);
+    }
+    match word {
+        "true" | "false" => return Some(HighlightKind::Boolean),
+        "nil" => return Some(HighlightKind::Constant),
+        "self" | "Self" | "super" => return Some(HighlightKind::Keyword),
+        _ => {}
+    }
+
+    match ctx.prev_word {
+        Some("struct" | "class" | "enum" | "protocol" | "actor" | "extension" | "typealias") => {
+            return Some(HighlightKind::Type)
+        }
+        Some("func") => return Some(HighlightKind::Function),
+        Some("import") => return Some(HighlightKind::Namespace),
+        _ => {}
+    }
+
+    if ctx.prev == Some(b'.') {
+        return Some(if matches!(ctx.next, Some(b'(' | b'{')) {
+            HighlightKind::Method
+        } else {
+            HighlightKind::Property
+        });
+    }
+    if is_screaming_case(word) {
+        return Some(HighlightKind::Constant);
+    }
+    if is_pascal_case(word) {
+        return Some(HighlightKind::Type);
+    }
+    if ctx.next == Some(b'(') {
+        return Some(HighlightKind::Function);
+    }
+    None
+}
+
+fn is_swift_control_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "if" | "else"
+            | "guard"
+            | "switch"
+            | "case"
+            | "default"
+            | "for"
+            | "while"
+            | "repeat"
+            | "do"
+            | "try"
+            | "catch"
+            | "throw"
+            | "return"
+            | "break"
+            | "continue"
+            | "fallthrough"
+            | "defer"
+            | "await"
+    )
+}
+
+fn is_swift_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "func"
+            | "let"
+            | "var"
+            | "struct"
+            | "class"
+            | "enum"
+            | "protocol"
+            | "actor"
+            | "extension"
+            | "typealias"
+            | "associatedtype"
+            | "import"
+            | "init"
+            | "deinit"
+            | "subscript"
+            | "where"
+            | "in"
+            | "is"
+            | "as"
+            | "some"
+            | "any"
+            | "inout"
+            | "throws"
+            | "rethrows"
+            | "async"
+            | "static"
+            | "final"
+            | "override"
+            | "mutating"
+            | "nonmutating"
+            | "lazy"
+            | "weak"
+            | "unowned"
+            | "public"
+            | "private"
+            | "fileprivate"
+            | "internal"
+            | "open"
+            | "convenience"
+            | "required"
+            | "indirect"
+            | "get"
+            | "set"
+            | "willSet"
+            | "didSet"
+    )
+}
@@ -0,0 +1,100 @@
+//! WAT 词法高亮。构建所用的 registry 里没有 `tree-sitter-wat` / `tree-sitter-wast` 语法包，
+//! 也就没有与 tree-sitter 0.20 配套的版本，因此走通用扫描器。
+
+use super::lexical::{LexicalLanguage, WordContext};
+use super::HighlightKind;
+
+/// WebAssembly 文本格式（`.wat` / `.wast`）。指令名带 `.`（`i32.const`、`local.get`），
+/// 所以 `.` 算作标识符内部字节；`$name` 标识符走变量前缀。
+pub(super) static LEXICAL: LexicalLanguage = LexicalLanguage {
+    line_comments: &[";;"],
+    line_comments_leading_only: false,
+    block_comment: Some(("(;", ";)")),
+    nested_block_comments: true,
+    quotes: b"\"",
+    interpolated_quotes: &[],
+    multiline_strings: &[],
+    word_extra: b".",
+    variable_sigils: b"$",
+    attribute_prefix: None,
+    bracket_attribute: None,
+    atom_prefix: None,
+    sigil_prefix: None,
+    classify_word,
+};
+
+fn classify_word(word: &str, ctx: &WordContext<'_>) -> Option<HighlightKind> {
+    if is_wat_control_keyword(word) {
+        return Some(HighlightKind::KeywordControl);
+    }
+    if is_wat_keyword(word) {
+        return Some(HighlightKind::Keyword);
+    }
+    if is_wat_value_type(word) {
+        return Some(HighlightKind::TypeBuiltin);
+    }
+    if matches!(word, "inf" | "nan") {
+        return Some(HighlightKind::Number);
+    }
+    // `(i32.add ...)`、`local.get $x`、`drop`：其余的词都是指令。折叠写法里指令
+    // 前面没有 `(`，但带 `.` 的词只可能是指令。
+    if ctx.prev == Some(b'(') || word.contains('.') || is_wat_plain_instruction(word) {
+        return Some(HighlightKind::Function);
+    }
+    None
+}
+
+fn is_wat_control_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "block"
+            | "loop"
+            | "if"
+            | "then"
+            | "else"
+            | "end"
+            | "br"
+            | "br_if"
+            | "br_table"
+            | "return"
+            | "unreachable"
+    )
+}
+
+fn is_wat_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "module"
+            | "func"
+            | "type"
+            | "param"
+            | "result"
+            | "local"
+            | "memory"
+            | "table"
+            | "import"
+            | "export"
+            | "data"
+            | "elem"
+            | "global"
+            | "start"
+            | "mut"
+            | "offset"
+            | "align"
+    )
+}
+
+fn is_wat_value_type(word: &str) -> bool {
+    matches!(
+        word,
+        "i32" | "i64" | "f32" | "f64" | "v128" | "funcref" | "externref" | "anyfunc"
+    )
+}
+
+/// 不带 `.` 的指令，出现在折叠写法（前面没有 `(`）时也要识别。
+fn is_wat_plain_instruction(word: &str) -> bool {
+    matches!(
+        word,
+        "call" | "call_indirect" | "drop" | "select" | "nop" | "return_call"
+    )
+}
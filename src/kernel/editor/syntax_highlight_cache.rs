@@ -2,7 +2,7 @@ use ropey::Rope;
 use std::sync::Arc;
 use tree_sitter::InputEdit;
 
-use super::syntax::{merge_adjacent_highlight_spans, HighlightSpan};
+use super::syntax::{merge_adjacent_highlight_spans, HighlightSpan, SyntaxEditDelta};
 
 #[derive(Debug, Clone)]
 pub(crate) struct AsyncSyntaxHighlightCache {
@@ -38,6 +38,22 @@ impl AsyncSyntaxHighlightCache {
         *self = Self::new_for_rope(rope);
     }
 
+    /// 按一次语法树编辑的结果失效：挪好行对齐后只把 `changed_ranges` 覆盖的行标脏，
+    /// 其余行保留已算好的高亮。整篇重解析时行号对不上，保留旧高亮但全部标脏，
+    /// 在后台补丁回来之前渲染仍走同步兜底，不会整屏闪成无高亮。
+    pub(crate) fn invalidate_ranges(&mut self, rope: &Rope, delta: &SyntaxEditDelta) {
+        match delta.input_edit.as_ref() {
+            Some(edit) if !delta.reparsed => {
+                self.apply_edit_shape_shift(rope, edit);
+                self.mark_dirty_from_changed_ranges(rope, &delta.changed_ranges);
+            }
+            _ => {
+                self.ensure_shape_for_rope(rope);
+                self.dirty.fill(true);
+            }
+        }
+    }
+
     pub(crate) fn apply_edit_shape_shift(&mut self, rope: &Rope, edit: &InputEdit) {
         if self.lines.len() != self.dirty.len() || self.lines.is_empty() {
             self.reset_for_rope(rope);
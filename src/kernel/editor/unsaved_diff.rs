@@ -0,0 +1,109 @@
+//! 未保存改动的行级 diff：已保存内容 vs 当前缓冲区，产出 gutter 标记。
+//!
+//! 已保存内容由 [`EditHistory::saved_rope`] 从当前文本倒推，只对
+//! [`EditHistory::dirty_byte_range`] 覆盖到的那几行跑 Myers（编辑距离过大时整段标成 Modified），
+//! 前后未改动的行不参与比较。
+
+use crate::kernel::git::diff::{myers_steps, Step};
+use crate::models::EditHistory;
+use ropey::Rope;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GutterMarkKind {
+    Added,
+    Modified,
+    /// 标在被删除内容之后的那一行（删到文件末尾时标在最后一行）。
+    Deleted,
+}
+
+/// 按行号升序返回 `(line, kind)`；未修改或历史与文本对不上时返回空。
+pub(crate) fn unsaved_marks(history: &EditHistory, rope: &Rope) -> Vec<(usize, GutterMarkKind)> {
+    let Some(dirty) = history.dirty_byte_range(rope) else {
+        return Vec::new();
+    };
+    let Some(saved) = history.saved_rope(rope) else {
+        return Vec::new();
+    };
+    if saved.len_bytes() != dirty.disk_len {
+        return Vec::new();
+    }
+
+    // 差异段之前的行两侧完全相同；差异段末尾所在行之后的内容也一一对应。
+    let start = rope.byte_to_line(dirty.buffer.start);
+    let buffer_end = rope.byte_to_line(dirty.buffer.end);
+    let disk_end = saved.byte_to_line(dirty.disk.end);
+    let old = line_texts(&saved, start, disk_end);
+    let new = line_texts(rope, start, buffer_end);
+
+    let last_line = rope.len_lines().saturating_sub(1);
+    let mut marks = Vec::new();
+    let mut push = |line: usize, kind: GutterMarkKind| {
+        let line = line.min(last_line);
+        if marks.last().map(|(l, _)| *l) != Some(line) {
+            marks.push((line, kind));
+        }
+    };
+
+    let Some(steps) = myers_steps(&old, &new) else {
+        if new.is_empty() {
+            push(start, GutterMarkKind::Deleted);
+        }
+        for line in start..start + new.len() {
+            push(line, GutterMarkKind::Modified);
+        }
+        return marks;
+    };
+
+    let mut line = start;
+    let mut i = 0;
+    while i < steps.len() {
+        if steps[i] == Step::Equal {
+            line += 1;
+            i += 1;
+            continue;
+        }
+        let mut deleted = 0;
+        let mut inserted = 0;
+        while let Some(step) = steps.get(i).filter(|step| **step != Step::Equal) {
+            match step {
+                Step::Delete => deleted += 1,
+                Step::Insert => inserted += 1,
+                Step::Equal => {}
+            }
+            i += 1;
+        }
+        if inserted == 0 {
+            push(line, GutterMarkKind::Deleted);
+            continue;
+        }
+        let modified = deleted.min(inserted);
+        for offset in 0..inserted {
+            let kind = if offset < modified {
+                GutterMarkKind::Modified
+            } else {
+                GutterMarkKind::Added
+            };
+            push(line + offset, kind);
+        }
+        line += inserted;
+    }
+    marks
+}
+
+/// `first..=last` 行的文本，去掉行尾换行符。
+fn line_texts(rope: &Rope, first: usize, last: usize) -> Vec<String> {
+    let last = last.min(rope.len_lines().saturating_sub(1));
+    (first..=last)
+        .map(|line| {
+            let mut text = rope.line(line).to_string();
+            while text.ends_with(['\n', '\r']) {
+                text.pop();
+            }
+            text
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "../../../tests/unit/kernel/editor/unsaved_diff.rs"]
+mod tests;
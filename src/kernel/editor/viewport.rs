@@ -1,11 +1,15 @@
 use crate::models::{slice_to_cow, TextBuffer};
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::UnicodeWidthStr;
 
 use super::state::EditorViewportState;
 
 pub fn cursor_display_x_abs(buffer: &TextBuffer, tab_size: u8) -> u32 {
     let (row, col) = buffer.cursor();
+    display_x_abs(buffer, row, col, tab_size)
+}
+
+/// 第 `row` 行第 `col` 个 grapheme 左边界的显示列（从行首算，Tab 按 `tab_size` 展开）。
+pub fn display_x_abs(buffer: &TextBuffer, row: usize, col: usize, tab_size: u8) -> u32 {
     let Some(slice) = buffer.line_slice(row) else {
         return 0;
     };
@@ -26,7 +30,7 @@ pub fn cursor_display_x_abs(buffer: &TextBuffer, tab_size: u8) -> u32 {
         } else if g == "\n" || g == "\r" {
             break;
         } else {
-            display_col += g.width() as u32;
+            display_col += TextBuffer::display_width_of_grapheme(g) as u32;
         }
     }
 
@@ -50,13 +54,40 @@ pub fn line_display_width(buffer: &TextBuffer, row: usize, tab_size: u8) -> u32
             let rem = display_col % tab;
             display_col += if rem == 0 { tab } else { tab - rem };
         } else {
-            display_col = display_col.saturating_add(g.width() as u32);
+            display_col =
+                display_col.saturating_add(TextBuffer::display_width_of_grapheme(g) as u32);
         }
     }
 
     display_col
 }
 
+/// 第 `row` 行左边界不早于显示列 `target_x` 的第一个 grapheme 列；整行都在其左侧时为行尾。
+pub fn col_at_display_x_abs(buffer: &TextBuffer, row: usize, target_x: u32, tab_size: u8) -> usize {
+    let Some(slice) = buffer.line_slice(row) else {
+        return 0;
+    };
+    let line = slice_to_cow(slice);
+    let tab = tab_size.max(1) as u32;
+    let mut display_col = 0u32;
+    let mut col = 0usize;
+
+    for g in line.graphemes(true) {
+        if g == "\n" || g == "\r" || display_col >= target_x {
+            break;
+        }
+        if g == "\t" {
+            let rem = display_col % tab;
+            display_col += if rem == 0 { tab } else { tab - rem };
+        } else {
+            display_col =
+                display_col.saturating_add(TextBuffer::display_width_of_grapheme(g) as u32);
+        }
+        col += 1;
+    }
+    col
+}
+
 fn cursor_display_x_abs_ascii(slice: &ropey::RopeSlice<'_>, col: usize, tab_size: u8) -> u32 {
     if col == 0 {
         return 0;
@@ -88,6 +119,124 @@ fn cursor_display_x_abs_ascii(slice: &ropey::RopeSlice<'_>, col: usize, tab_size
     display_col
 }
 
+/// 水平平滑滚动：每个 tick 推进剩余距离的一半（至少一列），抵达 `target` 即结束。
+/// 仅在 `follow_cursor == false` 时推进；光标移动重新接管视口时动画随之作废，
+/// 因此不会与 `clamp_and_follow` 的横向跟随互相拉扯。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnimatedHorizontalScroll {
+    pub target: u32,
+}
+
+impl AnimatedHorizontalScroll {
+    pub fn step(self, current: u32) -> u32 {
+        let advance = current.abs_diff(self.target).div_ceil(2);
+        if current < self.target {
+            current.saturating_add(advance)
+        } else {
+            current.saturating_sub(advance)
+        }
+    }
+}
+
+/// 软换行：第 `row` 行在 `width` 列内折成的各显示行的段首 grapheme 列，首段恒为 0。
+/// 按字符折行；放不下的字形（含跨界的 Tab）整体挪到下一段，每段至少一个字形。
+pub fn wrap_line_starts(buffer: &TextBuffer, row: usize, width: usize, tab_size: u8) -> Vec<usize> {
+    let mut starts = vec![0];
+    let Some(slice) = buffer.line_slice(row) else {
+        return starts;
+    };
+    let width = width.max(1);
+
+    let is_plain_ascii = slice.len_bytes() == slice.len_chars()
+        && slice
+            .chunks()
+            .all(|chunk| memchr::memchr(b'\t', chunk.as_bytes()).is_none());
+    if is_plain_ascii {
+        let len = slice.len_chars() - trailing_newline_len(&slice);
+        starts.extend((width..len).step_by(width));
+        return starts;
+    }
+
+    let tab = tab_size.max(1) as usize;
+    let line = slice_to_cow(slice);
+    let mut display_col = 0usize;
+    let mut segment_x = 0usize;
+    for (i, g) in line.graphemes(true).enumerate() {
+        if g == "\n" || g == "\r" || g == "\r\n" {
+            break;
+        }
+        let w = if g == "\t" {
+            tab - display_col % tab
+        } else {
+            TextBuffer::display_width_of_grapheme(g)
+        };
+        if display_col > segment_x && display_col + w - segment_x > width {
+            starts.push(i);
+            segment_x = display_col;
+        }
+        display_col += w;
+    }
+    starts
+}
+
+fn trailing_newline_len(slice: &ropey::RopeSlice<'_>) -> usize {
+    let len = slice.len_chars();
+    match (
+        len.checked_sub(2).map(|i| slice.char(i)),
+        len.checked_sub(1).map(|i| slice.char(i)),
+    ) {
+        (Some('\r'), Some('\n')) => 2,
+        (_, Some('\n' | '\r')) => 1,
+        _ => 0,
+    }
+}
+
+/// 按当前宽度重建全篇的 `display_to_logical`；不换行时清空。
+pub fn rebuild_display_to_logical(
+    viewport: &mut EditorViewportState,
+    buffer: &TextBuffer,
+    tab_size: u8,
+) {
+    viewport.display_to_logical.clear();
+    if !viewport.word_wrap {
+        return;
+    }
+    let width = viewport.width.max(1);
+    for row in 0..buffer.len_lines().max(1) {
+        viewport.display_to_logical.extend(
+            wrap_line_starts(buffer, row, width, tab_size)
+                .into_iter()
+                .map(|col| (row, col)),
+        );
+    }
+}
+
+impl EditorViewportState {
+    /// 软换行下 `(row, col)` 所在显示行在 `display_to_logical` 中的下标；恰在段尾的列归下一段。
+    pub fn display_row_of(&self, (row, col): (usize, usize)) -> Option<usize> {
+        let idx = self
+            .display_to_logical
+            .partition_point(|&pos| pos <= (row, col))
+            .checked_sub(1)?;
+        (self.display_to_logical[idx].0 == row).then_some(idx)
+    }
+
+    /// 第 `row` 行的首个显示行下标。
+    pub fn first_display_row_of(&self, row: usize) -> usize {
+        self.display_to_logical
+            .partition_point(|&(line, _)| line < row)
+    }
+
+    /// 显示行 `idx` 所在段的结束列（不含）：后面还有同一行的段时为下一段段首，否则为 `None`（行尾）。
+    pub fn wrap_segment_end(&self, idx: usize) -> Option<usize> {
+        let (row, _) = *self.display_to_logical.get(idx)?;
+        self.display_to_logical
+            .get(idx + 1)
+            .filter(|(next_row, _)| *next_row == row)
+            .map(|&(_, col)| col)
+    }
+}
+
 pub fn clamp_and_follow(viewport: &mut EditorViewportState, buffer: &TextBuffer, tab_size: u8) {
     let total_lines = buffer.len_lines().max(1);
     let height = viewport.height.max(1);
@@ -95,6 +244,16 @@ pub fn clamp_and_follow(viewport: &mut EditorViewportState, buffer: &TextBuffer,
     let max_offset = total_lines.saturating_sub(1);
     viewport.line_offset = viewport.line_offset.min(max_offset);
 
+    if viewport.word_wrap {
+        rebuild_display_to_logical(viewport, buffer, tab_size);
+        viewport.horiz_offset = 0;
+        if viewport.follow_cursor {
+            follow_cursor_wrapped(viewport, buffer.cursor());
+        }
+        return;
+    }
+    viewport.display_to_logical.clear();
+
     if !viewport.follow_cursor {
         return;
     }
@@ -117,6 +276,25 @@ pub fn clamp_and_follow(viewport: &mut EditorViewportState, buffer: &TextBuffer,
     }
 }
 
+/// 软换行下的纵向跟随：光标所在显示行落在视口之下时，逐行下移 `line_offset` 直到能看见。
+/// 按全篇显示行计数，不扣除折叠隐藏的行，结果只会偏保守（多滚一点）。
+fn follow_cursor_wrapped(viewport: &mut EditorViewportState, cursor: (usize, usize)) {
+    let (row, _) = cursor;
+    if row < viewport.line_offset {
+        viewport.line_offset = row;
+        return;
+    }
+    let Some(cursor_idx) = viewport.display_row_of(cursor) else {
+        return;
+    };
+    let height = viewport.height.max(1);
+    while viewport.line_offset < row
+        && cursor_idx - viewport.first_display_row_of(viewport.line_offset) >= height
+    {
+        viewport.line_offset += 1;
+    }
+}
+
 #[cfg(test)]
 pub fn screen_to_pos(
     viewport: &EditorViewportState,
@@ -177,7 +355,7 @@ pub fn screen_to_col(
                 tab - rem
             }
         } else {
-            g.width() as u32
+            TextBuffer::display_width_of_grapheme(g) as u32
         };
 
         // 与 views::editor::coord::screen_to_col 对齐：按单元格中心归最近边界，
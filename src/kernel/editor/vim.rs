@@ -0,0 +1,129 @@
+//! Vim 模态编辑：每个 pane 的模式，以及 Normal / Visual 模式下用到的选区与动作辅助。
+//!
+//! 只在 `EditorConfig::vim_mode` 打开时生效；关闭时 pane 恒为 `Insert`，行为与普通编辑器一致。
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::models::{Granularity, Selection};
+
+use super::{viewport, EditorTabState};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EditorMode {
+    #[default]
+    Insert,
+    Normal,
+    Visual,
+    VisualLine,
+}
+
+impl EditorMode {
+    /// 状态栏模式块上的文字。
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Insert => "INSERT",
+            Self::Normal => "NORMAL",
+            Self::Visual => "VISUAL",
+            Self::VisualLine => "V-LINE",
+        }
+    }
+
+    pub fn is_visual(self) -> bool {
+        matches!(self, Self::Visual | Self::VisualLine)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VimOperator {
+    Delete,
+    Change,
+    Yank,
+}
+
+/// Normal 模式下等待第二个键的前缀：`g`（`gg`）或操作符（`d` / `c` / `y`）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VimPending {
+    Goto,
+    Operator(VimOperator),
+}
+
+impl EditorTabState {
+    /// 选中 `[start, end)`（行、字素列），光标落在 `end`。
+    pub(crate) fn vim_select(&mut self, start: (usize, usize), end: (usize, usize)) {
+        self.buffer.set_cursor(end.0, end.1);
+        let mut selection = Selection::new(start, Granularity::Char);
+        selection.update_cursor(end, self.buffer.rope());
+        self.buffer.set_selection(Some(selection));
+    }
+
+    /// `first..=last` 整行（含末尾换行）的范围，供 `dd` / `yy` / 行 Visual 的删除与复制。
+    /// 最后一行没有换行可吃时改吃前一行的换行，删除后不留空行。
+    pub(crate) fn vim_linewise_range(
+        &self,
+        first: usize,
+        last: usize,
+    ) -> ((usize, usize), (usize, usize)) {
+        let last_row = self.buffer.len_lines().saturating_sub(1);
+        let last = last.min(last_row);
+        if last < last_row {
+            ((first, 0), (last + 1, 0))
+        } else if first > 0 {
+            let prev_len = self.buffer.line_grapheme_len(first - 1);
+            (
+                (first - 1, prev_len),
+                (last, self.buffer.line_grapheme_len(last)),
+            )
+        } else {
+            ((0, 0), (last, self.buffer.line_grapheme_len(last)))
+        }
+    }
+
+    /// `e`：移到当前或下一个单词的最后一个字素上，必要时跨行。
+    pub(crate) fn vim_word_end(&mut self, tab_size: u8) -> bool {
+        let prev = self.buffer.cursor();
+        let (mut row, col) = prev;
+        // 先离开当前字素，已经在词尾时才会跳到下一个词。
+        let mut pos = col + 1;
+        loop {
+            let Some(line) = self.buffer.line(row) else {
+                return false;
+            };
+            let graphemes: Vec<&str> = line
+                .trim_end_matches(['\n', '\r'])
+                .graphemes(true)
+                .collect();
+            while pos < graphemes.len() && !is_vim_word(graphemes[pos]) {
+                pos += 1;
+            }
+            if pos < graphemes.len() {
+                while pos + 1 < graphemes.len() && is_vim_word(graphemes[pos + 1]) {
+                    pos += 1;
+                }
+                break;
+            }
+            if row + 1 >= self.buffer.len_lines() {
+                return false;
+            }
+            row += 1;
+            pos = 0;
+        }
+
+        self.buffer.set_cursor(row, pos);
+        let changed = self.buffer.cursor() != prev;
+        if changed {
+            self.reset_cursor_goal_col();
+            viewport::clamp_and_follow(&mut self.viewport, &self.buffer, tab_size);
+        }
+        changed
+    }
+}
+
+fn is_vim_word(grapheme: &str) -> bool {
+    grapheme
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || !c.is_ascii() && !c.is_whitespace())
+}
+
+#[cfg(test)]
+#[path = "../../../tests/unit/kernel/editor/vim.rs"]
+mod tests;
@@ -1,20 +1,35 @@
 use ropey::Rope;
 use serde_json::Value;
+use std::ops::Range;
 use std::path::PathBuf;
 use tree_sitter::Tree;
 
 use crate::kernel::editor::{ReloadRequest, TabId};
 use crate::kernel::language::LanguageId;
 use crate::kernel::services::ports::{
-    LspCompletionItem, LspCompletionTriggerContext, LspPositionEncoding, LspRange, LspResourceOp,
-    LspWorkspaceFileEdit,
+    LspCallHierarchyItem, LspCompletionItem, LspCompletionTriggerContext, LspDocumentLink,
+    LspPositionEncoding, LspRange, LspResourceOp, LspSettings, LspWorkspaceFileEdit,
 };
-use crate::models::OpId;
+use crate::kernel::state::ExplorerOpOrigin;
+use crate::models::{HistoryBackup, OpId};
 
 #[derive(Debug, Clone)]
 pub enum Effect {
     LoadFile(PathBuf),
+    /// 用户确认后打开超出 `max_file_size_bytes` 的文件：跳过大小检查。
+    LoadFileWithoutLimit(PathBuf),
+    /// 读取大文件的 `[start_byte, end_byte)`，结果接到该路径的标签页（分块读入）。
+    LoadFileRange {
+        path: PathBuf,
+        start_byte: u64,
+        end_byte: u64,
+    },
     LoadDir(PathBuf),
+    /// 关闭干净的标签页时把撤销历史写进该文件的 `.ops` 备份。
+    BackupEditHistory {
+        path: PathBuf,
+        backup: HistoryBackup,
+    },
     CreateFile(PathBuf),
     CreateDir(PathBuf),
     RenamePath {
@@ -27,17 +42,104 @@ pub enum Effect {
         to: PathBuf,
         overwrite: bool,
     },
+    /// 删除：移进回收站（`~/.config/zcode/trash/`），撤销时可还原。
     DeletePath {
         path: PathBuf,
         is_dir: bool,
     },
+    /// 把回收站里的 `trashed` 挪回原位置 `path`。
+    RestoreFromTrash {
+        trashed: PathBuf,
+        path: PathBuf,
+        is_dir: bool,
+    },
+    /// 撤销/重做资源管理器操作：执行 `op`，其逆操作按 `origin` 进对应的栈。
+    ReplayExplorerOp {
+        op: Box<Effect>,
+        origin: ExplorerOpOrigin,
+    },
     ReloadSettings,
+    /// `settings.lsp` 变了：按新配置重设语言服务器，只有命令 / 参数变化才重启进程。
+    ReconfigureLsp {
+        settings: LspSettings,
+    },
     OpenSettings,
+    ExportKeybindings {
+        path: PathBuf,
+    },
+    /// 读取导出的键位文件，叠加到当前绑定并写回 settings。
+    ImportKeybindings {
+        path: PathBuf,
+    },
+    /// 把活动标签页连同语法高亮写成独立的 HTML 文件。
+    ExportAsHtml {
+        path: PathBuf,
+    },
+    /// 把 `MetricsMiddleware` 的分发统计写进日志。
+    ShowMetrics,
+    /// 挂起界面，在 `cwd`（缺省为工作区根目录）里起一个交互 shell，退出后回到编辑器。
+    SpawnTerminal {
+        cwd: Option<PathBuf>,
+    },
+    /// 校验目录后回到 `Action::WorkspaceFolderAdded`，并通知已启动的 LSP 客户端。
+    AddWorkspaceFolder {
+        path: PathBuf,
+    },
+    RemoveWorkspaceFolder {
+        path: PathBuf,
+    },
+    /// 列出工作区文件供命令行文件模式模糊搜索（结果回到 `Action::CommandLineFilesListed`）。
+    ListWorkspaceFiles,
+    GitStashList,
+    GitStashPush {
+        include_untracked: bool,
+    },
+    GitStashApply {
+        index: usize,
+    },
+    GitStashDrop {
+        index: usize,
+    },
+    /// 列出本地与远程分支，结果回到 `Action::GitBranchesUpdated`。
+    GitBranchList,
+    /// `git checkout [-b] <branch>`；完成后重新列出分支。
+    GitCheckout {
+        branch: String,
+        create: bool,
+    },
+    /// 单行 blame（`line` 为 0 基），结果回到 `Action::GitBlameResult`。
+    GitBlame {
+        path: PathBuf,
+        line: usize,
+    },
+    /// 读 `path` 在暂存区里的版本，结果回到 `Action::GitDiffBaseLoaded`。
+    GitLoadDiffBase {
+        path: PathBuf,
+    },
+    /// 把 `GitState::diff` 的第 `hunk_index` 块写进暂存区，完成后重读暂存区版本刷新 diff。
+    GitStageHunk {
+        path: PathBuf,
+        hunk_index: usize,
+    },
     StartGlobalSearch {
         root: PathBuf,
         pattern: String,
         case_sensitive: bool,
         use_regex: bool,
+        skip_binary: bool,
+        /// 逗号分隔的 include / exclude glob，`None` 表示不过滤。
+        include_glob: Option<String>,
+        exclude_glob: Option<String>,
+    },
+    StartGlobalReplace {
+        root: PathBuf,
+        pattern: String,
+        replacement: String,
+        case_sensitive: bool,
+        use_regex: bool,
+        skip_binary: bool,
+        include_glob: Option<String>,
+        exclude_glob: Option<String>,
     },
     StartEditorSearch {
         pane: usize,
@@ -45,6 +147,7 @@ pub enum Effect {
         pattern: String,
         case_sensitive: bool,
         use_regex: bool,
+        whole_word: bool,
     },
     CancelEditorSearch {
         pane: usize,
@@ -67,10 +170,35 @@ pub enum Effect {
         // 计数器误判（undo/redo 会前进 version 却不改变内容）。
         head: OpId,
     },
+    /// 大文件保存：只把磁盘上的 `byte_range` 换成 `replacement`（其后内容顺移）。
+    /// 磁盘文件长度已不是 `disk_len`（被外部改过）时退回整体写入。
+    WriteFilePart {
+        pane: usize,
+        path: PathBuf,
+        byte_range: Range<usize>,
+        replacement: String,
+        disk_len: usize,
+        version: u64,
+        head: OpId,
+    },
     SetClipboardText(String),
+    /// 键盘唤起编辑区右键菜单；屏幕坐标只有 workbench 知道，由它按光标位置定锚点。
+    OpenEditorContextMenu {
+        pane: usize,
+    },
+    /// 交给系统默认程序打开（文档链接 Ctrl+Click）。
+    OpenTerminalUrl(String),
     RequestClipboardText {
         pane: usize,
     },
+    /// 在剪贴板历史里环形移动 `delta` 条（正数为更早）并粘贴到 `pane`；
+    /// 紧接上次历史粘贴时替换掉那段文本。
+    CycleClipboardHistory {
+        pane: usize,
+        delta: isize,
+    },
+    /// 读取剪贴板历史供选择器显示（结果回到 `Action::ClipboardHistoryLoaded`）。
+    RequestClipboardHistory,
     LspHoverRequest {
         path: PathBuf,
         line: u32,
@@ -81,11 +209,34 @@ pub enum Effect {
         line: u32,
         column: u32,
     },
+    LspTypeDefinitionRequest {
+        path: PathBuf,
+        line: u32,
+        column: u32,
+    },
+    LspImplementationRequest {
+        path: PathBuf,
+        line: u32,
+        column: u32,
+    },
     LspReferencesRequest {
         path: PathBuf,
         line: u32,
         column: u32,
     },
+    LspPrepareCallHierarchyRequest {
+        path: PathBuf,
+        line: u32,
+        column: u32,
+    },
+    LspCallHierarchyIncomingRequest {
+        item_index: usize,
+        item: Box<LspCallHierarchyItem>,
+    },
+    LspCallHierarchyOutgoingRequest {
+        item_index: usize,
+        item: Box<LspCallHierarchyItem>,
+    },
     LspDocumentSymbolsRequest {
         path: PathBuf,
     },
@@ -111,6 +262,12 @@ pub enum Effect {
         line: u32,
         column: u32,
     },
+    /// 改名前的 `textDocument/prepareRename` 校验；响应回来后才弹改名输入框。
+    LspPrepareRenameRequest {
+        path: PathBuf,
+        line: u32,
+        column: u32,
+    },
     LspRenameRequest {
         path: PathBuf,
         line: u32,
@@ -133,6 +290,21 @@ pub enum Effect {
         path: PathBuf,
         version: u64,
     },
+    LspDocumentLinkRequest {
+        path: PathBuf,
+        version: u64,
+    },
+    LspDocumentLinkResolveRequest {
+        path: PathBuf,
+        version: u64,
+        link: LspDocumentLink,
+    },
+    LspDocumentHighlightRequest {
+        path: PathBuf,
+        line: u32,
+        column: u32,
+        version: u64,
+    },
     LspExecuteCommand {
         command: String,
         arguments: Vec<Value>,
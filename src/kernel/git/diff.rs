@@ -0,0 +1,234 @@
+//! 行级 diff：Myers O(ND) 最短编辑脚本。未保存改动的 gutter 标记与 git diff 视图共用。
+
+use ropey::Rope;
+use std::ops::Range;
+
+/// 编辑距离超过该值时放弃 Myers；回溯轨迹是 O(D²) 的。
+const MAX_EDIT_DISTANCE: usize = 2048;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Step {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// 并排视图的一行：左侧原文行、右侧当前行（0 基），改动块里较短的一侧为 `None`。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiffRow {
+    pub old: Option<usize>,
+    pub new: Option<usize>,
+    pub changed: bool,
+}
+
+/// 一个改动块：原文 `old_start..old_start + old_len` 行被换成当前 `new_start..new_start + new_len` 行；
+/// `rows` 是它在 [`LineDiff::rows`] 里占的区间。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub old_len: usize,
+    pub new_start: usize,
+    pub new_len: usize,
+    pub rows: Range<usize>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LineDiff {
+    pub rows: Vec<DiffRow>,
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// `old` → `new` 的并排行与改动块。行按 git 的口径切分（行尾换行符算在行内），
+/// 所以只差末尾换行的最后一行也算改动。
+pub fn line_diff(old: &Rope, new: &Rope) -> LineDiff {
+    let old = git_lines(old);
+    let new = git_lines(new);
+
+    // 先剥掉首尾相同的行，Myers 只跑中间那段。
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut steps = vec![Step::Equal; prefix];
+    match myers_steps(old_mid, new_mid) {
+        Some(mid) => steps.extend(mid),
+        None => {
+            steps.extend(std::iter::repeat_n(Step::Delete, old_mid.len()));
+            steps.extend(std::iter::repeat_n(Step::Insert, new_mid.len()));
+        }
+    }
+    steps.extend(std::iter::repeat_n(Step::Equal, suffix));
+
+    let mut diff = LineDiff::default();
+    let (mut old_line, mut new_line) = (0, 0);
+    let mut i = 0;
+    while i < steps.len() {
+        if steps[i] == Step::Equal {
+            diff.rows.push(DiffRow {
+                old: Some(old_line),
+                new: Some(new_line),
+                changed: false,
+            });
+            old_line += 1;
+            new_line += 1;
+            i += 1;
+            continue;
+        }
+
+        let (mut deleted, mut inserted) = (0, 0);
+        while let Some(step) = steps.get(i).filter(|step| **step != Step::Equal) {
+            match step {
+                Step::Delete => deleted += 1,
+                Step::Insert => inserted += 1,
+                Step::Equal => {}
+            }
+            i += 1;
+        }
+        let first_row = diff.rows.len();
+        for offset in 0..deleted.max(inserted) {
+            diff.rows.push(DiffRow {
+                old: (offset < deleted).then_some(old_line + offset),
+                new: (offset < inserted).then_some(new_line + offset),
+                changed: true,
+            });
+        }
+        diff.hunks.push(DiffHunk {
+            old_start: old_line,
+            old_len: deleted,
+            new_start: new_line,
+            new_len: inserted,
+            rows: first_row..diff.rows.len(),
+        });
+        old_line += deleted;
+        new_line += inserted;
+    }
+    diff
+}
+
+/// 只含 `hunk` 一块、不带上下文的补丁正文（从 `@@` 行起），文件头由调用方按仓库相对路径补上，
+/// 供 `git apply --cached --unidiff-zero` 把这一块写进暂存区。`old` 须是暂存区里的版本。
+pub fn hunk_patch_body(old: &Rope, new: &Rope, hunk: &DiffHunk) -> String {
+    let old_lines = git_lines(old);
+    let new_lines = git_lines(new);
+
+    // 只应用这一块，所以新文件里它前面的行数与原文相同。
+    let range = |start: usize, len: usize| {
+        if len == 0 {
+            format!("{start},0")
+        } else {
+            format!("{},{len}", start + 1)
+        }
+    };
+    let mut patch = format!(
+        "@@ -{} +{} @@\n",
+        range(hunk.old_start, hunk.old_len),
+        range(hunk.old_start, hunk.new_len)
+    );
+    let mut push_lines = |marker: char, lines: &[String]| {
+        for line in lines {
+            patch.push(marker);
+            patch.push_str(line);
+            if !line.ends_with('\n') {
+                patch.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+    };
+    push_lines(
+        '-',
+        &old_lines[hunk.old_start..hunk.old_start + hunk.old_len],
+    );
+    push_lines(
+        '+',
+        &new_lines[hunk.new_start..hunk.new_start + hunk.new_len],
+    );
+    patch
+}
+
+/// 按 git 的口径切行：每行带着行尾换行符，文本以换行结尾时不产生末尾的空行。
+fn git_lines(rope: &Rope) -> Vec<String> {
+    let mut lines: Vec<String> = rope.lines().map(|line| line.to_string()).collect();
+    if lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Myers O(ND) 最短编辑脚本；编辑距离超过 [`MAX_EDIT_DISTANCE`] 时返回 `None`。
+pub(crate) fn myers_steps(old: &[String], new: &[String]) -> Option<Vec<Step>> {
+    let n = old.len() as isize;
+    let m = new.len() as isize;
+    let max = (old.len() + new.len()).min(MAX_EDIT_DISTANCE);
+    let offset = max as isize + 1;
+    let mut v = vec![0isize; 2 * max + 3];
+    // trace[d] 是第 d 轮开始前 `v[-d..=d]` 的快照。
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    for d in 0..=max as isize {
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+        let mut k = -d;
+        while k <= d {
+            let idx = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+                v[idx + 1]
+            } else {
+                v[idx - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[idx] = x;
+            if x >= n && y >= m {
+                return Some(backtrack(&trace, n, m));
+            }
+            k += 2;
+        }
+    }
+    None
+}
+
+fn backtrack(trace: &[Vec<isize>], n: isize, m: isize) -> Vec<Step> {
+    let mut steps = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        if d == 0 {
+            steps.extend(std::iter::repeat_n(Step::Equal, x as usize));
+            break;
+        }
+        let at = |k: isize| v[(k + d) as usize];
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = at(prev_k);
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            steps.push(Step::Equal);
+            x -= 1;
+            y -= 1;
+        }
+        steps.push(if x == prev_x {
+            Step::Insert
+        } else {
+            Step::Delete
+        });
+        x = prev_x;
+        y = prev_y;
+    }
+    steps.reverse();
+    steps
+}
+
+#[cfg(test)]
+#[path = "../../../tests/unit/kernel/git/diff.rs"]
+mod tests;
@@ -0,0 +1,5 @@
+//! 与界面无关的 git 逻辑：行级 diff 与按块暂存用的补丁。
+
+pub mod diff;
+
+pub use diff::{line_diff, DiffHunk, DiffRow, LineDiff};
@@ -392,6 +392,7 @@ fn synthetic_completion_item(spec: SyntheticCompletionSpec) -> LspCompletionItem
         id: spec.id,
         label: spec.label,
         detail: spec.detail,
+        label_details: None,
         kind: spec.kind,
         documentation: spec.documentation,
         insert_text: spec.insert_text,
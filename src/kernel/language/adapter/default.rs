@@ -50,7 +50,14 @@ impl LanguageAdapter for DefaultLanguageAdapter {
 
     fn editing(&self) -> &dyn LanguageEditingPolicy {
         match self.language {
-            Some(LanguageId::Java) => &BRACE_LANGUAGE_EDITING_POLICY,
+            Some(
+                LanguageId::Java
+                | LanguageId::Protobuf
+                | LanguageId::Groovy
+                | LanguageId::Rego
+                | LanguageId::Solidity
+                | LanguageId::Cairo,
+            ) => &BRACE_LANGUAGE_EDITING_POLICY,
             _ => &DEFAULT_EDITING_POLICY,
         }
     }
@@ -81,3 +88,35 @@ pub(crate) static BASH_ADAPTER: DefaultLanguageAdapter =
     DefaultLanguageAdapter::new(Some(LanguageId::Bash));
 pub(crate) static MARKDOWN_ADAPTER: DefaultLanguageAdapter =
     DefaultLanguageAdapter::new(Some(LanguageId::Markdown));
+pub(crate) static PROTOBUF_ADAPTER: DefaultLanguageAdapter =
+    DefaultLanguageAdapter::new(Some(LanguageId::Protobuf));
+pub(crate) static GROOVY_ADAPTER: DefaultLanguageAdapter =
+    DefaultLanguageAdapter::new(Some(LanguageId::Groovy));
+pub(crate) static WAT_ADAPTER: DefaultLanguageAdapter =
+    DefaultLanguageAdapter::new(Some(LanguageId::Wat));
+pub(crate) static REGO_ADAPTER: DefaultLanguageAdapter =
+    DefaultLanguageAdapter::new(Some(LanguageId::Rego));
+pub(crate) static MAKEFILE_ADAPTER: DefaultLanguageAdapter =
+    DefaultLanguageAdapter::new(Some(LanguageId::Makefile));
+pub(crate) static DOCKERFILE_ADAPTER: DefaultLanguageAdapter =
+    DefaultLanguageAdapter::new(Some(LanguageId::Dockerfile));
+pub(crate) static SOLIDITY_ADAPTER: DefaultLanguageAdapter =
+    DefaultLanguageAdapter::new(Some(LanguageId::Solidity));
+pub(crate) static CAIRO_ADAPTER: DefaultLanguageAdapter =
+    DefaultLanguageAdapter::new(Some(LanguageId::Cairo));
+pub(crate) static RUBY_ADAPTER: DefaultLanguageAdapter =
+    DefaultLanguageAdapter::new(Some(LanguageId::Ruby));
+pub(crate) static LUA_ADAPTER: DefaultLanguageAdapter =
+    DefaultLanguageAdapter::new(Some(LanguageId::Lua));
+pub(crate) static ELIXIR_ADAPTER: DefaultLanguageAdapter =
+    DefaultLanguageAdapter::new(Some(LanguageId::Elixir));
+pub(crate) static ERLANG_ADAPTER: DefaultLanguageAdapter =
+    DefaultLanguageAdapter::new(Some(LanguageId::Erlang));
+pub(crate) static KOTLIN_ADAPTER: DefaultLanguageAdapter =
+    DefaultLanguageAdapter::new(Some(LanguageId::Kotlin));
+pub(crate) static SWIFT_ADAPTER: DefaultLanguageAdapter =
+    DefaultLanguageAdapter::new(Some(LanguageId::Swift));
+pub(crate) static NIX_ADAPTER: DefaultLanguageAdapter =
+    DefaultLanguageAdapter::new(Some(LanguageId::Nix));
+pub(crate) static DHALL_ADAPTER: DefaultLanguageAdapter =
+    DefaultLanguageAdapter::new(Some(LanguageId::Dhall));
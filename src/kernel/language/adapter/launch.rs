@@ -118,6 +118,218 @@ impl LspLaunchPolicy for JdtlsLspLaunchPolicy {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+struct ProtoLanguageServerLaunchPolicy;
+
+impl LspLaunchPolicy for ProtoLanguageServerLaunchPolicy {
+    fn default_launch_plan(&self, _ctx: &LspLaunchContext<'_>) -> LspLaunchPlan {
+        launch_plan(
+            resolve_protols_command(),
+            &[],
+            None,
+            "install protols (e.g. `cargo install protols`)",
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct GroovyLanguageServerLaunchPolicy;
+
+impl LspLaunchPolicy for GroovyLanguageServerLaunchPolicy {
+    fn default_launch_plan(&self, _ctx: &LspLaunchContext<'_>) -> LspLaunchPlan {
+        launch_plan(
+            resolve_groovy_language_server_command(),
+            &[],
+            None,
+            "install groovy-language-server and ensure `groovy-language-server` is in PATH",
+        )
+    }
+}
+
+/// Regal（OPA 的 linter）自带语言服务器：`regal language-server`。
+#[derive(Debug, Clone, Copy)]
+struct RegalLspLaunchPolicy;
+
+impl LspLaunchPolicy for RegalLspLaunchPolicy {
+    fn default_launch_plan(&self, _ctx: &LspLaunchContext<'_>) -> LspLaunchPlan {
+        launch_plan(
+            resolve_regal_command(),
+            &["language-server"],
+            None,
+            "install regal (https://github.com/StyraInc/regal) and ensure `regal` is in PATH",
+        )
+    }
+}
+
+/// Solidity：优先用 Nomic Foundation 的语言服务器（项目 `node_modules/.bin` 或 PATH），
+/// 找不到时退回编译器自带的 `solc --lsp`（PATH 或 foundry 工具链目录）。
+#[derive(Debug, Clone, Copy)]
+struct NomicFoundryVsCodeLaunchPolicy;
+
+impl LspLaunchPolicy for NomicFoundryVsCodeLaunchPolicy {
+    fn default_launch_plan(&self, ctx: &LspLaunchContext<'_>) -> LspLaunchPlan {
+        const INSTALL_HINT: &str = "install @nomicfoundation/solidity-language-server (e.g. `npm i -g @nomicfoundation/solidity-language-server`) or put `solc` in PATH";
+        if let Some(command) =
+            resolve_nomicfoundation_solidity_command(ctx.workspace_root, ctx.language_root)
+        {
+            return launch_plan(Some(command), &["--stdio"], None, INSTALL_HINT);
+        }
+        launch_plan(resolve_solc_command(), &["--lsp"], None, INSTALL_HINT)
+    }
+}
+
+/// Cairo：优先独立的 `cairo-language-server`，否则用 Scarb 自带的
+/// `scarb cairo-language-server`（PATH 或 `~/.local/bin`，scarb 安装脚本的默认位置）。
+#[derive(Debug, Clone, Copy)]
+struct CairoLsLaunchPolicy;
+
+impl LspLaunchPolicy for CairoLsLaunchPolicy {
+    fn default_launch_plan(&self, _ctx: &LspLaunchContext<'_>) -> LspLaunchPlan {
+        const INSTALL_HINT: &str =
+            "install Scarb (https://docs.swmansion.com/scarb) or put `cairo-language-server` in PATH";
+        if let Some(command) = find_in_path("cairo-language-server") {
+            return launch_plan(
+                Some(command.to_string_lossy().to_string()),
+                &[],
+                None,
+                INSTALL_HINT,
+            );
+        }
+        launch_plan(
+            resolve_scarb_command(),
+            &["cairo-language-server"],
+            None,
+            INSTALL_HINT,
+        )
+    }
+}
+
+/// Ruby：`solargraph stdio`。
+#[derive(Debug, Clone, Copy)]
+struct SolargraphLspLaunchPolicy;
+
+impl LspLaunchPolicy for SolargraphLspLaunchPolicy {
+    fn default_launch_plan(&self, _ctx: &LspLaunchContext<'_>) -> LspLaunchPlan {
+        launch_plan(
+            find_in_path("solargraph").map(|path| path.to_string_lossy().to_string()),
+            &["stdio"],
+            None,
+            "install solargraph (`gem install solargraph`) and ensure it is in PATH",
+        )
+    }
+}
+
+/// Lua：sumneko 的 `lua-language-server`，默认即走 stdio。
+#[derive(Debug, Clone, Copy)]
+struct LuaLanguageServerLaunchPolicy;
+
+impl LspLaunchPolicy for LuaLanguageServerLaunchPolicy {
+    fn default_launch_plan(&self, _ctx: &LspLaunchContext<'_>) -> LspLaunchPlan {
+        launch_plan(
+            find_in_path("lua-language-server").map(|path| path.to_string_lossy().to_string()),
+            &[],
+            None,
+            "install lua-language-server (https://luals.github.io) and ensure it is in PATH",
+        )
+    }
+}
+
+/// Elixir：elixir-ls 发行包里的启动脚本是 `language_server.sh`，包管理器多装成 `elixir-ls`。
+#[derive(Debug, Clone, Copy)]
+struct ElixirLsLaunchPolicy;
+
+impl LspLaunchPolicy for ElixirLsLaunchPolicy {
+    fn default_launch_plan(&self, _ctx: &LspLaunchContext<'_>) -> LspLaunchPlan {
+        launch_plan(
+            find_in_path("elixir-ls")
+                .or_else(|| find_in_path("language_server.sh"))
+                .map(|path| path.to_string_lossy().to_string()),
+            &[],
+            None,
+            "install elixir-ls (https://github.com/elixir-lsp/elixir-ls) and ensure `elixir-ls` is in PATH",
+        )
+    }
+}
+
+/// Erlang：`erlang_ls`，默认走 stdio。
+#[derive(Debug, Clone, Copy)]
+struct ErlangLsLaunchPolicy;
+
+impl LspLaunchPolicy for ErlangLsLaunchPolicy {
+    fn default_launch_plan(&self, _ctx: &LspLaunchContext<'_>) -> LspLaunchPlan {
+        launch_plan(
+            find_in_path("erlang_ls").map(|path| path.to_string_lossy().to_string()),
+            &[],
+            None,
+            "install erlang_ls (https://erlang-ls.github.io) and ensure it is in PATH",
+        )
+    }
+}
+
+/// Kotlin：`kotlin-language-server`（fwcd），默认走 stdio。
+#[derive(Debug, Clone, Copy)]
+struct KotlinLsLaunchPolicy;
+
+impl LspLaunchPolicy for KotlinLsLaunchPolicy {
+    fn default_launch_plan(&self, _ctx: &LspLaunchContext<'_>) -> LspLaunchPlan {
+        launch_plan(
+            find_in_path("kotlin-language-server").map(|path| path.to_string_lossy().to_string()),
+            &[],
+            None,
+            "install kotlin-language-server (https://github.com/fwcd/kotlin-language-server) and ensure it is in PATH",
+        )
+    }
+}
+
+/// Swift：`sourcekit-lsp` 随 Swift 工具链发布；macOS 上不在 PATH 时经 `xcrun` 调起 Xcode 自带的那份。
+#[derive(Debug, Clone, Copy)]
+struct SourcekitLspLaunchPolicy;
+
+impl LspLaunchPolicy for SourcekitLspLaunchPolicy {
+    fn default_launch_plan(&self, _ctx: &LspLaunchContext<'_>) -> LspLaunchPlan {
+        let hint = "install Xcode (or the Swift toolchain from https://swift.org) and ensure `sourcekit-lsp` is in PATH";
+        if let Some(path) = find_in_path("sourcekit-lsp") {
+            return launch_plan(Some(path.to_string_lossy().to_string()), &[], None, hint);
+        }
+        launch_plan(
+            find_in_path("xcrun").map(|path| path.to_string_lossy().to_string()),
+            &["sourcekit-lsp"],
+            None,
+            hint,
+        )
+    }
+}
+
+/// Nix：oxalica 的 `nil`，默认走 stdio。
+#[derive(Debug, Clone, Copy)]
+struct NilLaunchPolicy;
+
+impl LspLaunchPolicy for NilLaunchPolicy {
+    fn default_launch_plan(&self, _ctx: &LspLaunchContext<'_>) -> LspLaunchPlan {
+        launch_plan(
+            find_in_path("nil").map(|path| path.to_string_lossy().to_string()),
+            &[],
+            None,
+            "install nil (https://github.com/oxalica/nil, e.g. `nix profile install nixpkgs#nil`) and ensure it is in PATH",
+        )
+    }
+}
+
+/// Dhall：`dhall-lsp-server`，默认走 stdio。
+#[derive(Debug, Clone, Copy)]
+struct DhallLspLaunchPolicy;
+
+impl LspLaunchPolicy for DhallLspLaunchPolicy {
+    fn default_launch_plan(&self, _ctx: &LspLaunchContext<'_>) -> LspLaunchPlan {
+        launch_plan(
+            find_in_path("dhall-lsp-server").map(|path| path.to_string_lossy().to_string()),
+            &[],
+            None,
+            "install dhall-lsp-server (https://github.com/dhall-lang/dhall-haskell) and ensure it is in PATH",
+        )
+    }
+}
+
 pub(crate) static EMPTY_LSP_LAUNCH_POLICY: EmptyLspLaunchPolicy = EmptyLspLaunchPolicy;
 static RUST_ANALYZER_LSP_LAUNCH_POLICY: RustAnalyzerLspLaunchPolicy = RustAnalyzerLspLaunchPolicy;
 static GOPLS_LSP_LAUNCH_POLICY: GoplsLspLaunchPolicy = GoplsLspLaunchPolicy;
@@ -126,6 +338,23 @@ static TYPESCRIPT_LANGUAGE_SERVER_LSP_LAUNCH_POLICY: TypeScriptLanguageServerLau
     TypeScriptLanguageServerLaunchPolicy;
 static CLANGD_LSP_LAUNCH_POLICY: ClangdLspLaunchPolicy = ClangdLspLaunchPolicy;
 static JDTLS_LSP_LAUNCH_POLICY: JdtlsLspLaunchPolicy = JdtlsLspLaunchPolicy;
+static PROTO_LANGUAGE_SERVER_LSP_LAUNCH_POLICY: ProtoLanguageServerLaunchPolicy =
+    ProtoLanguageServerLaunchPolicy;
+static GROOVY_LANGUAGE_SERVER_LSP_LAUNCH_POLICY: GroovyLanguageServerLaunchPolicy =
+    GroovyLanguageServerLaunchPolicy;
+static REGAL_LSP_LAUNCH_POLICY: RegalLspLaunchPolicy = RegalLspLaunchPolicy;
+static NOMIC_FOUNDRY_VSCODE_LSP_LAUNCH_POLICY: NomicFoundryVsCodeLaunchPolicy =
+    NomicFoundryVsCodeLaunchPolicy;
+static CAIRO_LS_LSP_LAUNCH_POLICY: CairoLsLaunchPolicy = CairoLsLaunchPolicy;
+static SOLARGRAPH_LSP_LAUNCH_POLICY: SolargraphLspLaunchPolicy = SolargraphLspLaunchPolicy;
+static LUA_LANGUAGE_SERVER_LSP_LAUNCH_POLICY: LuaLanguageServerLaunchPolicy =
+    LuaLanguageServerLaunchPolicy;
+static ELIXIR_LS_LSP_LAUNCH_POLICY: ElixirLsLaunchPolicy = ElixirLsLaunchPolicy;
+static ERLANG_LS_LSP_LAUNCH_POLICY: ErlangLsLaunchPolicy = ErlangLsLaunchPolicy;
+static KOTLIN_LS_LSP_LAUNCH_POLICY: KotlinLsLaunchPolicy = KotlinLsLaunchPolicy;
+static SOURCEKIT_LSP_LAUNCH_POLICY: SourcekitLspLaunchPolicy = SourcekitLspLaunchPolicy;
+static NIL_LSP_LAUNCH_POLICY: NilLaunchPolicy = NilLaunchPolicy;
+static DHALL_LSP_LAUNCH_POLICY: DhallLspLaunchPolicy = DhallLspLaunchPolicy;
 
 const CLANGD_FALLBACK_STYLE_ARG: &str = "--fallback-style=WebKit";
 
@@ -139,6 +368,19 @@ pub(crate) fn launch_policy_for(server: Option<LspServerKind>) -> &'static dyn L
         }
         Some(LspServerKind::Clangd) => &CLANGD_LSP_LAUNCH_POLICY,
         Some(LspServerKind::Jdtls) => &JDTLS_LSP_LAUNCH_POLICY,
+        Some(LspServerKind::ProtoLanguageServer) => &PROTO_LANGUAGE_SERVER_LSP_LAUNCH_POLICY,
+        Some(LspServerKind::GroovyLanguageServer) => &GROOVY_LANGUAGE_SERVER_LSP_LAUNCH_POLICY,
+        Some(LspServerKind::Regal) => &REGAL_LSP_LAUNCH_POLICY,
+        Some(LspServerKind::NomicFoundryVsCode) => &NOMIC_FOUNDRY_VSCODE_LSP_LAUNCH_POLICY,
+        Some(LspServerKind::CairoLs) => &CAIRO_LS_LSP_LAUNCH_POLICY,
+        Some(LspServerKind::Solargraph) => &SOLARGRAPH_LSP_LAUNCH_POLICY,
+        Some(LspServerKind::LuaLanguageServer) => &LUA_LANGUAGE_SERVER_LSP_LAUNCH_POLICY,
+        Some(LspServerKind::ElixirLs) => &ELIXIR_LS_LSP_LAUNCH_POLICY,
+        Some(LspServerKind::ErlangLs) => &ERLANG_LS_LSP_LAUNCH_POLICY,
+        Some(LspServerKind::KotlinLs) => &KOTLIN_LS_LSP_LAUNCH_POLICY,
+        Some(LspServerKind::Sourcekit) => &SOURCEKIT_LSP_LAUNCH_POLICY,
+        Some(LspServerKind::Nil) => &NIL_LSP_LAUNCH_POLICY,
+        Some(LspServerKind::DhallLsp) => &DHALL_LSP_LAUNCH_POLICY,
         None => &EMPTY_LSP_LAUNCH_POLICY,
     }
 }
@@ -185,6 +427,45 @@ fn resolve_jdtls_command() -> Option<String> {
     find_in_path("jdtls").map(|path| path.to_string_lossy().to_string())
 }
 
+fn resolve_groovy_language_server_command() -> Option<String> {
+    find_in_path("groovy-language-server").map(|path| path.to_string_lossy().to_string())
+}
+
+fn resolve_regal_command() -> Option<String> {
+    find_in_path("regal").map(|path| path.to_string_lossy().to_string())
+}
+
+fn resolve_nomicfoundation_solidity_command(workspace_root: &Path, root: &Path) -> Option<String> {
+    const NAME: &str = "nomicfoundation-solidity-language-server";
+    resolve_node_modules_bin(workspace_root, root, NAME)
+        .or_else(|| find_in_path(NAME))
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+fn resolve_solc_command() -> Option<String> {
+    find_in_path("solc")
+        .or_else(|| {
+            let home = std::env::var_os("HOME")?;
+            executable_in_dir(&PathBuf::from(home).join(".foundry").join("bin"), "solc")
+        })
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+fn resolve_scarb_command() -> Option<String> {
+    find_in_path("scarb")
+        .or_else(|| {
+            let home = std::env::var_os("HOME")?;
+            executable_in_dir(&PathBuf::from(home).join(".local").join("bin"), "scarb")
+        })
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+fn resolve_protols_command() -> Option<String> {
+    find_in_path("protols")
+        .or_else(|| cargo_home_bin_path("protols").filter(|p| is_executable_file(p)))
+        .map(|path| path.to_string_lossy().to_string())
+}
+
 fn resolve_gopls_command() -> Option<String> {
     find_in_path("gopls")
         .or_else(|| resolve_gobin_command("gopls"))
@@ -15,15 +15,18 @@ use crate::core::Command;
 use crate::kernel::editor::{EditorTabState, HighlightKind};
 use crate::kernel::language::LanguageId;
 use crate::kernel::services::ports::{
-    LspCommand, LspCompletionItem, LspHoverBlock, LspHoverPayload, LspInsertTextFormat, LspMarkup,
-    LspRange, LspServerCapabilities, LspServerKind, LspSignatureHelpPayload,
-    LspSignatureParameterLabel, LspTextEdit,
+    LspCommand, LspCompletionItem, LspCompletionLabelDetails, LspHoverBlock, LspHoverPayload,
+    LspInsertTextFormat, LspMarkup, LspRange, LspServerCapabilities, LspServerKind,
+    LspSignatureHelpPayload, LspSignatureParameterLabel, LspTextEdit,
 };
 
 use c_family::{CPP_ADAPTER, C_ADAPTER};
 use default::{
-    BASH_ADAPTER, CSS_ADAPTER, DEFAULT_ADAPTER, HTML_ADAPTER, JAVA_ADAPTER, JSON_ADAPTER,
-    MARKDOWN_ADAPTER, SQL_ADAPTER, TOML_ADAPTER, XML_ADAPTER, YAML_ADAPTER,
+    BASH_ADAPTER, CAIRO_ADAPTER, CSS_ADAPTER, DEFAULT_ADAPTER, DHALL_ADAPTER, DOCKERFILE_ADAPTER,
+    ELIXIR_ADAPTER, ERLANG_ADAPTER, GROOVY_ADAPTER, HTML_ADAPTER, JAVA_ADAPTER, JSON_ADAPTER,
+    KOTLIN_ADAPTER, LUA_ADAPTER, MAKEFILE_ADAPTER, MARKDOWN_ADAPTER, NIX_ADAPTER, PROTOBUF_ADAPTER,
+    REGO_ADAPTER, RUBY_ADAPTER, SOLIDITY_ADAPTER, SQL_ADAPTER, SWIFT_ADAPTER, TOML_ADAPTER,
+    WAT_ADAPTER, XML_ADAPTER, YAML_ADAPTER,
 };
 use go::GO_ADAPTER;
 use js::{JSX_ADAPTER, JS_ADAPTER, TSX_ADAPTER, TS_ADAPTER};
@@ -314,6 +317,7 @@ pub struct CompletionEntry {
     pub id: u64,
     pub label: String,
     pub detail: Option<String>,
+    pub label_details: Option<LspCompletionLabelDetails>,
     pub documentation: Option<String>,
     pub filter_text: Option<String>,
     pub sort_text: Option<String>,
@@ -437,6 +441,7 @@ impl CompletionRecord {
             id: raw.id,
             label: raw.label.clone(),
             detail: raw.detail.clone(),
+            label_details: raw.label_details.clone(),
             documentation: raw.documentation.clone(),
             filter_text: raw.filter_text.clone(),
             sort_text: raw.sort_text.clone(),
@@ -770,6 +775,22 @@ pub fn adapter_for(language: Option<LanguageId>) -> &'static dyn LanguageAdapter
         Some(LanguageId::Sql) => &SQL_ADAPTER,
         Some(LanguageId::Bash) => &BASH_ADAPTER,
         Some(LanguageId::Markdown) => &MARKDOWN_ADAPTER,
+        Some(LanguageId::Protobuf) => &PROTOBUF_ADAPTER,
+        Some(LanguageId::Groovy) => &GROOVY_ADAPTER,
+        Some(LanguageId::Wat) => &WAT_ADAPTER,
+        Some(LanguageId::Rego) => &REGO_ADAPTER,
+        Some(LanguageId::Makefile) => &MAKEFILE_ADAPTER,
+        Some(LanguageId::Dockerfile) => &DOCKERFILE_ADAPTER,
+        Some(LanguageId::Solidity) => &SOLIDITY_ADAPTER,
+        Some(LanguageId::Cairo) => &CAIRO_ADAPTER,
+        Some(LanguageId::Ruby) => &RUBY_ADAPTER,
+        Some(LanguageId::Lua) => &LUA_ADAPTER,
+        Some(LanguageId::Elixir) => &ELIXIR_ADAPTER,
+        Some(LanguageId::Erlang) => &ERLANG_ADAPTER,
+        Some(LanguageId::Kotlin) => &KOTLIN_ADAPTER,
+        Some(LanguageId::Swift) => &SWIFT_ADAPTER,
+        Some(LanguageId::Nix) => &NIX_ADAPTER,
+        Some(LanguageId::Dhall) => &DHALL_ADAPTER,
         None => &DEFAULT_ADAPTER,
     }
 }
@@ -816,7 +837,10 @@ pub(crate) fn default_should_close_on_command<S: LanguageInteractionPolicy + ?Si
 ) -> bool {
     match cmd {
         Command::LspCompletion => false,
-        Command::LspInlayHints | Command::LspFoldingRange => false,
+        Command::LspInlayHints
+        | Command::LspFoldingRange
+        | Command::LspDocumentLink
+        | Command::LspDocumentHighlight => false,
         Command::InsertChar(ch) => !behavior.keeps_open_on_char(*ch),
         Command::DeleteBackward | Command::DeleteForward | Command::DeleteSelection => false,
         _ => true,
@@ -1038,6 +1062,7 @@ pub(crate) fn default_normalize_completion(
         id: context.item.id,
         label: context.item.label.clone(),
         detail: context.item.detail.clone(),
+        label_details: context.item.label_details.clone(),
         documentation: context.item.documentation.clone(),
         filter_text: context.item.filter_text.clone(),
         sort_text: context.item.sort_text.clone(),
@@ -1190,6 +1215,22 @@ fn language_code_fence(language: LanguageId) -> &'static str {
         LanguageId::Sql => "sql",
         LanguageId::Bash => "bash",
         LanguageId::Markdown => "markdown",
+        LanguageId::Protobuf => "proto",
+        LanguageId::Groovy => "groovy",
+        LanguageId::Wat => "wat",
+        LanguageId::Rego => "rego",
+        LanguageId::Makefile => "makefile",
+        LanguageId::Dockerfile => "dockerfile",
+        LanguageId::Solidity => "solidity",
+        LanguageId::Cairo => "cairo",
+        LanguageId::Ruby => "ruby",
+        LanguageId::Lua => "lua",
+        LanguageId::Elixir => "elixir",
+        LanguageId::Erlang => "erlang",
+        LanguageId::Kotlin => "kotlin",
+        LanguageId::Swift => "swift",
+        LanguageId::Nix => "nix",
+        LanguageId::Dhall => "dhall",
     }
 }
 
@@ -37,6 +37,22 @@ pub enum LanguageId {
     Sql,
     Bash,
     Markdown,
+    Protobuf,
+    Groovy,
+    Wat,
+    Rego,
+    Makefile,
+    Dockerfile,
+    Solidity,
+    Cairo,
+    Ruby,
+    Lua,
+    Elixir,
+    Erlang,
+    Kotlin,
+    Swift,
+    Nix,
+    Dhall,
 }
 
 /// 一个缩进层级对应的硬 Tab 数量。缩进统一用 `\t`，显示宽度由 tab_size 在显示层决定。
@@ -68,6 +84,14 @@ impl LanguageId {
     }
 
     pub fn from_path(path: &Path) -> Option<Self> {
+        // 没有扩展名、靠约定文件名识别的文件。
+        match path.file_name().and_then(|s| s.to_str()) {
+            Some("Jenkinsfile") => return Some(Self::Groovy),
+            Some("Makefile" | "makefile" | "GNUmakefile") => return Some(Self::Makefile),
+            Some("Dockerfile" | "Containerfile") => return Some(Self::Dockerfile),
+            Some("Gemfile" | "Rakefile") => return Some(Self::Ruby),
+            _ => {}
+        }
         match path.extension().and_then(|s| s.to_str())? {
             "rs" => Some(Self::Rust),
             "go" => Some(Self::Go),
@@ -93,6 +117,22 @@ impl LanguageId {
             "sql" => Some(Self::Sql),
             "sh" | "bash" | "zsh" => Some(Self::Bash),
             "md" | "markdown" | "mdx" => Some(Self::Markdown),
+            "proto" => Some(Self::Protobuf),
+            "groovy" | "gvy" => Some(Self::Groovy),
+            "wat" | "wast" => Some(Self::Wat),
+            "rego" => Some(Self::Rego),
+            "mk" | "mak" => Some(Self::Makefile),
+            "dockerfile" | "containerfile" => Some(Self::Dockerfile),
+            "sol" => Some(Self::Solidity),
+            "cairo" => Some(Self::Cairo),
+            "rb" | "rake" | "gemspec" | "ru" => Some(Self::Ruby),
+            "lua" => Some(Self::Lua),
+            "ex" | "exs" => Some(Self::Elixir),
+            "erl" | "hrl" => Some(Self::Erlang),
+            "kt" | "kts" => Some(Self::Kotlin),
+            "swift" => Some(Self::Swift),
+            "nix" => Some(Self::Nix),
+            "dhall" => Some(Self::Dhall),
             _ => None,
         }
     }
@@ -120,6 +160,22 @@ impl LanguageId {
             "sql" => Some(Self::Sql),
             "bash" | "sh" | "shell" | "zsh" => Some(Self::Bash),
             "markdown" | "md" => Some(Self::Markdown),
+            "proto" | "protobuf" | "proto3" => Some(Self::Protobuf),
+            "groovy" | "gvy" | "jenkinsfile" => Some(Self::Groovy),
+            "wat" | "wast" | "wasm" | "webassembly" => Some(Self::Wat),
+            "rego" | "opa" => Some(Self::Rego),
+            "make" | "makefile" | "mk" => Some(Self::Makefile),
+            "dockerfile" | "docker" | "containerfile" => Some(Self::Dockerfile),
+            "solidity" | "sol" => Some(Self::Solidity),
+            "cairo" => Some(Self::Cairo),
+            "ruby" | "rb" => Some(Self::Ruby),
+            "lua" => Some(Self::Lua),
+            "elixir" | "ex" | "exs" => Some(Self::Elixir),
+            "erlang" | "erl" => Some(Self::Erlang),
+            "kotlin" | "kt" | "kts" => Some(Self::Kotlin),
+            "swift" => Some(Self::Swift),
+            "nix" => Some(Self::Nix),
+            "dhall" => Some(Self::Dhall),
             _ => None,
         }
     }
@@ -134,6 +190,19 @@ impl LanguageId {
             }
             Self::C | Self::Cpp => Some(LspServerKind::Clangd),
             Self::Java => Some(LspServerKind::Jdtls),
+            Self::Protobuf => Some(LspServerKind::ProtoLanguageServer),
+            Self::Groovy => Some(LspServerKind::GroovyLanguageServer),
+            Self::Rego => Some(LspServerKind::Regal),
+            Self::Solidity => Some(LspServerKind::NomicFoundryVsCode),
+            Self::Cairo => Some(LspServerKind::CairoLs),
+            Self::Ruby => Some(LspServerKind::Solargraph),
+            Self::Lua => Some(LspServerKind::LuaLanguageServer),
+            Self::Elixir => Some(LspServerKind::ElixirLs),
+            Self::Erlang => Some(LspServerKind::ErlangLs),
+            Self::Kotlin => Some(LspServerKind::KotlinLs),
+            Self::Swift => Some(LspServerKind::Sourcekit),
+            Self::Nix => Some(LspServerKind::Nil),
+            Self::Dhall => Some(LspServerKind::DhallLsp),
             Self::Json
             | Self::Yaml
             | Self::Html
@@ -142,7 +211,10 @@ impl LanguageId {
             | Self::Toml
             | Self::Sql
             | Self::Bash
-            | Self::Markdown => None,
+            | Self::Markdown
+            | Self::Wat
+            | Self::Makefile
+            | Self::Dockerfile => None,
         }
     }
 
@@ -167,6 +239,22 @@ impl LanguageId {
             Self::Sql => "sql",
             Self::Bash => "shellscript",
             Self::Markdown => "markdown",
+            Self::Protobuf => "proto",
+            Self::Groovy => "groovy",
+            Self::Wat => "wat",
+            Self::Rego => "rego",
+            Self::Makefile => "makefile",
+            Self::Dockerfile => "dockerfile",
+            Self::Solidity => "solidity",
+            Self::Cairo => "cairo",
+            Self::Ruby => "ruby",
+            Self::Lua => "lua",
+            Self::Elixir => "elixir",
+            Self::Erlang => "erlang",
+            Self::Kotlin => "kotlin",
+            Self::Swift => "swift",
+            Self::Nix => "nix",
+            Self::Dhall => "dhall",
         }
     }
 
@@ -200,6 +288,30 @@ impl LanguageId {
                 "gradlew",
                 ".project",
             ],
+            Self::Protobuf => &["buf.work.yaml", "buf.yaml", "protols.toml"],
+            Self::Groovy => &["build.gradle", "settings.gradle", "Jenkinsfile", "pom.xml"],
+            Self::Rego => &[".regal", ".manifest"],
+            Self::Solidity => &[
+                "foundry.toml",
+                "hardhat.config.ts",
+                "hardhat.config.js",
+                "remappings.txt",
+            ],
+            Self::Cairo => &["Scarb.toml", "cairo_project.toml"],
+            Self::Ruby => &["Gemfile", ".solargraph.yml"],
+            Self::Lua => &[".luarc.json", ".luarc.jsonc", ".stylua.toml"],
+            Self::Elixir => &["mix.exs"],
+            Self::Erlang => &["rebar.config", "erlang.mk"],
+            Self::Kotlin => &[
+                "settings.gradle.kts",
+                "build.gradle.kts",
+                "settings.gradle",
+                "build.gradle",
+                "pom.xml",
+            ],
+            Self::Swift => &["Package.swift", "buildServer.json"],
+            Self::Nix => &["flake.nix", "default.nix", "shell.nix"],
+            Self::Dhall => &["package.dhall"],
             Self::Json
             | Self::Yaml
             | Self::Html
@@ -208,7 +320,10 @@ impl LanguageId {
             | Self::Toml
             | Self::Sql
             | Self::Bash
-            | Self::Markdown => &[],
+            | Self::Markdown
+            | Self::Wat
+            | Self::Makefile
+            | Self::Dockerfile => &[],
         }
     }
 
@@ -233,6 +348,22 @@ impl LanguageId {
             Self::Sql => "SQL",
             Self::Bash => "Bash",
             Self::Markdown => "Markdown",
+            Self::Protobuf => "Protobuf",
+            Self::Groovy => "Groovy",
+            Self::Wat => "WebAssembly",
+            Self::Rego => "Rego",
+            Self::Makefile => "Makefile",
+            Self::Dockerfile => "Dockerfile",
+            Self::Solidity => "Solidity",
+            Self::Cairo => "Cairo",
+            Self::Ruby => "Ruby",
+            Self::Lua => "Lua",
+            Self::Elixir => "Elixir",
+            Self::Erlang => "Erlang",
+            Self::Kotlin => "Kotlin",
+            Self::Swift => "Swift",
+            Self::Nix => "Nix",
+            Self::Dhall => "Dhall",
         }
     }
 }
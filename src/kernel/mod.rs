@@ -3,25 +3,34 @@
 pub mod action;
 pub mod editor;
 pub mod effect;
+pub mod git;
 pub mod language;
 pub mod lsp_registry;
 pub mod palette;
 pub mod panel;
 pub mod search;
 pub mod services;
+pub mod snapshot;
 pub mod state;
 pub mod store;
 
 pub use action::Action;
 pub use editor::{EditorAction, EditorState};
 pub use effect::Effect;
+pub use panel::call_hierarchy::{CallHierarchyDirection, CallHierarchyNode, CallHierarchyState};
 pub use panel::code_actions::CodeActionsState;
+pub use panel::git::{GitBlameLine, GitBranch, GitDiffView, GitPanelRow, GitStash, GitState};
 pub use panel::locations::{LocationItem, LocationsState};
 pub use panel::problems::{ProblemItem, ProblemRange, ProblemSeverity, ProblemsState};
 pub use panel::symbols::{SymbolItem, SymbolsState};
-pub use search::{SearchResultItem, SearchResultsSnapshot, SearchState};
+pub use search::{
+    ReplaceSummary, SearchFilterField, SearchResultItem, SearchResultsSnapshot, SearchState,
+};
+pub use snapshot::AppStateSnapshot;
 pub use state::{
-    AppState, CommandLineState, ConfirmDialogState, EditorLayoutState, ExplorerState, FocusTarget,
-    InputDialogKind, InputDialogState, LspState, OverlayKind, OverlayState, PendingAction, UiState,
+    AppState, CommandLineMode, CommandLineState, ConfirmDialogState, EditorLayoutState,
+    ExplorerDragState, ExplorerHistory, ExplorerOpOrigin, ExplorerState, FilePathPurpose,
+    FocusTarget, InputDialogKind, InputDialogState, LspState, OverlayKind, OverlayState,
+    PendingAction, UiState,
 };
 pub use store::{CompletionRanker, DispatchResult, Store};
@@ -1,4 +1,9 @@
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
 use crate::core::Command;
+use crate::kernel::state::CommandLineMode;
+use crate::kernel::GitBranch;
 
 pub struct PaletteMatch<'a> {
     pub label: &'a str,
@@ -73,6 +78,16 @@ pub static PALETTE_ITEMS: &[PaletteItem] = &[
         label_lc: "lsp: go to definition",
         command: Command::LspDefinition,
     },
+    PaletteItem {
+        label: "LSP: Go to Type Definition",
+        label_lc: "lsp: go to type definition",
+        command: Command::LspTypeDefinition,
+    },
+    PaletteItem {
+        label: "LSP: Go to Implementation",
+        label_lc: "lsp: go to implementation",
+        command: Command::LspImplementation,
+    },
     PaletteItem {
         label: "LSP: Completion",
         label_lc: "lsp: completion",
@@ -103,6 +118,16 @@ pub static PALETTE_ITEMS: &[PaletteItem] = &[
         label_lc: "lsp: find references",
         command: Command::LspReferences,
     },
+    PaletteItem {
+        label: "LSP: Show Incoming Calls",
+        label_lc: "lsp: show incoming calls",
+        command: Command::LspCallHierarchy,
+    },
+    PaletteItem {
+        label: "LSP: Show Outgoing Calls",
+        label_lc: "lsp: show outgoing calls",
+        command: Command::LspCallHierarchyOutgoing,
+    },
     PaletteItem {
         label: "LSP: Document Symbols",
         label_lc: "lsp: document symbols",
@@ -118,6 +143,60 @@ pub static PALETTE_ITEMS: &[PaletteItem] = &[
         label_lc: "lsp: code action",
         command: Command::LspCodeAction,
     },
+    PaletteItem {
+        label: "Git: Show Stashes",
+        label_lc: "git: show stashes",
+        command: Command::OpenGitPanel,
+    },
+    PaletteItem {
+        label: "Git: Stash Changes",
+        label_lc: "git: stash changes",
+        command: Command::GitStashPush {
+            include_untracked: false,
+        },
+    },
+    PaletteItem {
+        label: "Git: Stash Changes (Include Untracked)",
+        label_lc: "git: stash changes (include untracked)",
+        command: Command::GitStashPush {
+            include_untracked: true,
+        },
+    },
+    PaletteItem {
+        label: "Git: Checkout Branch...",
+        label_lc: "git: checkout branch...",
+        command: Command::GitCheckoutBranch,
+    },
+    PaletteItem {
+        label: "Git: Show Diff of Active File",
+        label_lc: "git: show diff of active file",
+        command: Command::ShowGitDiff,
+    },
+    PaletteItem {
+        label: "Go to Line",
+        label_lc: "go to line",
+        command: Command::GotoLine,
+    },
+    PaletteItem {
+        label: "Editor: Toggle Line Comment",
+        label_lc: "editor: toggle line comment",
+        command: Command::ToggleLineComment,
+    },
+    PaletteItem {
+        label: "Editor: Duplicate Line",
+        label_lc: "editor: duplicate line",
+        command: Command::DuplicateLine,
+    },
+    PaletteItem {
+        label: "Editor: Sort Lines Ascending",
+        label_lc: "editor: sort lines ascending",
+        command: Command::SortLines { descending: false },
+    },
+    PaletteItem {
+        label: "Editor: Sort Lines Descending",
+        label_lc: "editor: sort lines descending",
+        command: Command::SortLines { descending: true },
+    },
     PaletteItem {
         label: "Editor: Fold",
         label_lc: "editor: fold",
@@ -153,6 +232,21 @@ pub static PALETTE_ITEMS: &[PaletteItem] = &[
         label_lc: "editor: remove secondary cursors",
         command: Command::RemoveSecondaryCursors,
     },
+    PaletteItem {
+        label: "Editor: Paste from Clipboard History",
+        label_lc: "editor: paste from clipboard history",
+        command: Command::ClipboardHistory,
+    },
+    PaletteItem {
+        label: "Editor: Paste Previous Clipboard Entry",
+        label_lc: "editor: paste previous clipboard entry",
+        command: Command::PastePrevious,
+    },
+    PaletteItem {
+        label: "Editor: Paste Next Clipboard Entry",
+        label_lc: "editor: paste next clipboard entry",
+        command: Command::PasteNext,
+    },
     PaletteItem {
         label: "View: Diagnostics",
         label_lc: "view: diagnostics",
@@ -168,11 +262,56 @@ pub static PALETTE_ITEMS: &[PaletteItem] = &[
         label_lc: "preferences: open settings (json)",
         command: Command::OpenSettings,
     },
+    PaletteItem {
+        label: "Preferences: Export Keybindings",
+        label_lc: "preferences: export keybindings",
+        command: Command::ExportKeybindings,
+    },
+    PaletteItem {
+        label: "Preferences: Import Keybindings",
+        label_lc: "preferences: import keybindings",
+        command: Command::ImportKeybindings,
+    },
+    PaletteItem {
+        label: "Workspaces: Add Folder to Workspace",
+        label_lc: "workspaces: add folder to workspace",
+        command: Command::AddWorkspaceFolder,
+    },
     PaletteItem {
         label: "App: Hard Reload",
         label_lc: "app: hard reload",
         command: Command::HardReload,
     },
+    PaletteItem {
+        label: "Developer: Show Dispatch Metrics",
+        label_lc: "developer: show dispatch metrics",
+        command: Command::ShowMetrics,
+    },
+    PaletteItem {
+        label: "Terminal: Open Shell at Current File Path",
+        label_lc: "terminal: open shell at current file path",
+        command: Command::OpenTerminalAtCurrentFilePath,
+    },
+    PaletteItem {
+        label: "View: Reopen Closed Editor",
+        label_lc: "view: reopen closed editor",
+        command: Command::ReopenClosedTab,
+    },
+    PaletteItem {
+        label: "File: Export as HTML",
+        label_lc: "file: export as html",
+        command: Command::ExportAsHtml,
+    },
+    PaletteItem {
+        label: "File: Copy Relative Path",
+        label_lc: "file: copy relative path",
+        command: Command::CopyRelativePath,
+    },
+    PaletteItem {
+        label: "Editor: Open Context Menu",
+        label_lc: "editor: open context menu",
+        command: Command::EditorContextMenu,
+    },
     PaletteItem {
         label: "File: Reload from Disk",
         label_lc: "file: reload from disk",
@@ -185,46 +324,206 @@ pub static PALETTE_ITEMS: &[PaletteItem] = &[
     },
 ];
 
-pub fn match_indices(query: &str) -> Vec<usize> {
+/// 命令行输入以它开头时进入命令模式（`> ` 同样有效），否则是文件模式。
+pub const COMMAND_PREFIX: &str = ">";
+/// 以它开头时进入跳转行模式（Ctrl+G / vim 的 `:`）。
+pub const LINE_PREFIX: &str = ":";
+
+/// 拆出模式与去掉前缀、首尾空白后的查询。
+pub fn split_query(input: &str) -> (CommandLineMode, &str) {
+    if let Some(rest) = input.strip_prefix(COMMAND_PREFIX) {
+        return (CommandLineMode::Command, rest.trim());
+    }
+    match input.strip_prefix(LINE_PREFIX) {
+        Some(rest) => (CommandLineMode::Line, rest.trim()),
+        None => (CommandLineMode::File, input.trim()),
+    }
+}
+
+/// 跳转行模式的输入 → 0 起的行号；不是 `1..=line_count` 内的整数时返回 `None`。
+pub fn parse_line_number(query: &str, line_count: usize) -> Option<usize> {
+    let line = query.trim().parse::<usize>().ok()?;
+    (1..=line_count).contains(&line).then(|| line - 1)
+}
+
+/// 子序列模糊匹配（ASCII 大小写不敏感），不匹配返回 `None`。
+/// 连续命中与词首命中加分；整段作为子串出现时额外加分，保证精确输入排在前面。
+pub fn fuzzy_score(query_lc: &str, candidate: &str) -> Option<i64> {
+    if query_lc.is_empty() {
+        return Some(0);
+    }
+
+    let mut query = query_lc.chars().peekable();
+    let mut score = 0i64;
+    let mut prev: Option<char> = None;
+    let mut prev_matched = false;
+    for ch in candidate.chars() {
+        let Some(&want) = query.peek() else {
+            break;
+        };
+        let matched = ch.to_ascii_lowercase() == want;
+        if matched {
+            score += 1;
+            if prev_matched {
+                score += 5;
+            }
+            let word_start = match prev {
+                None => true,
+                Some(p) => !p.is_alphanumeric() || (p.is_lowercase() && ch.is_uppercase()),
+            };
+            if word_start {
+                score += 3;
+            }
+            query.next();
+        }
+        prev_matched = matched;
+        prev = Some(ch);
+    }
+    if query.peek().is_some() {
+        return None;
+    }
+
+    if candidate.to_ascii_lowercase().contains(query_lc) {
+        score += 100;
+    }
+    Some(score)
+}
+
+fn item_score(item: &PaletteItem, query_lc: &str) -> Option<i64> {
+    let by_label = fuzzy_score(query_lc, item.label);
+    let by_name = fuzzy_score(query_lc, item.command.name());
+    by_label.max(by_name)
+}
+
+/// 按分数降序排序，同分保持原顺序。
+fn ranked_command_indices(query: &str) -> Vec<usize> {
     let query = query.trim();
     if query.is_empty() {
         return (0..PALETTE_ITEMS.len()).collect();
     }
 
     let query_lc = query.to_ascii_lowercase();
-    let mut matches = Vec::with_capacity(PALETTE_ITEMS.len());
-    for (i, item) in PALETTE_ITEMS.iter().enumerate() {
-        if item.label_lc.contains(&query_lc) {
-            matches.push(i);
-        }
-    }
-    matches
+    let mut scored: Vec<(i64, usize)> = PALETTE_ITEMS
+        .iter()
+        .enumerate()
+        .filter_map(|(i, item)| item_score(item, &query_lc).map(|score| (score, i)))
+        .collect();
+    scored.sort_by_key(|&(score, i)| (std::cmp::Reverse(score), i));
+    scored.into_iter().map(|(_, i)| i).collect()
 }
 
+pub fn match_indices(query: &str) -> Vec<usize> {
+    ranked_command_indices(query)
+}
+
+/// 命令模式的候选：同时按显示名与命令名（`Command::name`）模糊匹配。
 pub fn match_items(query: &str) -> Vec<PaletteMatch<'static>> {
-    let query = query.trim();
-    if query.is_empty() {
-        let mut items = Vec::with_capacity(PALETTE_ITEMS.len());
-        for item in PALETTE_ITEMS {
-            items.push(PaletteMatch {
+    ranked_command_indices(query)
+        .into_iter()
+        .map(|i| {
+            let item = &PALETTE_ITEMS[i];
+            PaletteMatch {
                 label: item.label,
                 command: &item.command,
-            });
-        }
-        return items;
+            }
+        })
+        .collect()
+}
+
+/// 文件模式的全部候选：最近打开的文件排在前面（工作区内的换成相对路径），其后是其余工作区文件。
+pub fn file_candidates<'a>(
+    recent: &'a VecDeque<PathBuf>,
+    files: &'a [PathBuf],
+    workspace_root: &Path,
+) -> Vec<&'a Path> {
+    let mut seen = HashSet::new();
+    recent
+        .iter()
+        .map(|path| path.strip_prefix(workspace_root).unwrap_or(path))
+        .chain(files.iter().map(PathBuf::as_path))
+        .filter(|path| seen.insert(*path))
+        .collect()
+}
+
+/// 文件模式的候选：按路径模糊匹配，同分时短路径优先，再同分保持原顺序（最近打开的在前）。
+pub fn match_files<'a, P: AsRef<Path>>(query: &str, files: &'a [P]) -> Vec<&'a Path> {
+    let query = query.trim();
+    if query.is_empty() {
+        return files.iter().map(AsRef::as_ref).collect();
     }
 
     let query_lc = query.to_ascii_lowercase();
-    let mut matches = Vec::with_capacity(PALETTE_ITEMS.len());
+    let mut scored: Vec<(i64, usize, &Path)> = files
+        .iter()
+        .filter_map(|path| {
+            let path = path.as_ref();
+            let text = path.to_string_lossy();
+            let score = fuzzy_score(&query_lc, &text)?;
+            Some((score, text.len(), path))
+        })
+        .collect();
+    scored.sort_by_key(|&(score, len, _)| (std::cmp::Reverse(score), len));
+    scored.into_iter().map(|(_, _, path)| path).collect()
+}
+
+/// 分支模式的一项候选：切到已有分支，或以输入为名新建。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BranchCandidate<'a> {
+    Checkout(&'a GitBranch),
+    Create(&'a str),
+}
+
+/// 分支模式的候选：按分支名模糊匹配，同分保持原顺序（本地在前）。
+/// 输入不是任何分支的名字时，在末尾追加一项"新建分支"。
+pub fn match_branches<'a>(query: &'a str, branches: &'a [GitBranch]) -> Vec<BranchCandidate<'a>> {
+    let query = query.trim();
+    let query_lc = query.to_ascii_lowercase();
+    let mut scored: Vec<(i64, usize)> = branches
+        .iter()
+        .enumerate()
+        .filter_map(|(i, branch)| fuzzy_score(&query_lc, &branch.name).map(|score| (score, i)))
+        .collect();
+    scored.sort_by_key(|&(score, i)| (std::cmp::Reverse(score), i));
 
-    for item in PALETTE_ITEMS {
-        if item.label_lc.contains(&query_lc) {
-            matches.push(PaletteMatch {
-                label: item.label,
-                command: &item.command,
-            });
-        }
+    let mut candidates: Vec<BranchCandidate<'a>> = scored
+        .into_iter()
+        .map(|(_, i)| BranchCandidate::Checkout(&branches[i]))
+        .collect();
+    let exists = branches
+        .iter()
+        .any(|branch| branch.name == query || branch.local_name() == query);
+    if !query.is_empty() && !exists {
+        candidates.push(BranchCandidate::Create(query));
     }
+    candidates
+}
 
-    matches
+/// 剪贴板模式的候选：按全文模糊匹配，同分保持原顺序（新的在前）。
+pub fn match_clipboard<'a>(query: &str, entries: &'a [String]) -> Vec<&'a str> {
+    let query_lc = query.trim().to_ascii_lowercase();
+    let mut scored: Vec<(i64, usize)> = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(i, entry)| fuzzy_score(&query_lc, entry).map(|score| (score, i)))
+        .collect();
+    scored.sort_by_key(|&(score, i)| (std::cmp::Reverse(score), i));
+    scored
+        .into_iter()
+        .map(|(_, i)| entries[i].as_str())
+        .collect()
 }
+
+/// 剪贴板条目的预览：首个非空行（去掉缩进）与其余行数。
+pub fn clipboard_preview(entry: &str) -> (&str, usize) {
+    let mut lines = entry.lines();
+    let first = lines
+        .by_ref()
+        .find(|line| !line.trim().is_empty())
+        .map(str::trim)
+        .unwrap_or_default();
+    (first, lines.count())
+}
+
+#[cfg(test)]
+#[path = "../../tests/unit/kernel/palette.rs"]
+mod tests;
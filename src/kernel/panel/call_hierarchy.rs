@@ -0,0 +1,173 @@
+use crate::kernel::panel::list_selection::ListSelectionState;
+use crate::kernel::services::ports::LspCallHierarchyItem;
+
+/// 调用树的展开方向：incoming 的子节点是调用方，outgoing 的子节点是被调用方。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CallHierarchyDirection {
+    #[default]
+    Incoming,
+    Outgoing,
+}
+
+/// 调用树的一个节点。`children` 为 `None` 表示还没向服务端要过子节点。
+#[derive(Debug, Clone)]
+pub struct CallHierarchyNode {
+    pub item: LspCallHierarchyItem,
+    pub depth: usize,
+    pub children: Option<Vec<usize>>,
+    pub expanded: bool,
+    pub loading: bool,
+}
+
+/// 调用层级面板态。节点按插入顺序存放在 `nodes` 里，下标即 `item_index`，
+/// 展开 / 追加子节点不会移动已有节点；可见行由各节点的展开状态派生。
+#[derive(Debug, Default)]
+pub struct CallHierarchyState {
+    direction: CallHierarchyDirection,
+    nodes: Vec<CallHierarchyNode>,
+    roots: Vec<usize>,
+    rows: ListSelectionState<usize>,
+}
+
+impl CallHierarchyState {
+    pub fn direction(&self) -> CallHierarchyDirection {
+        self.direction
+    }
+
+    pub fn nodes(&self) -> &[CallHierarchyNode] {
+        &self.nodes
+    }
+
+    pub fn node(&self, item_index: usize) -> Option<&CallHierarchyNode> {
+        self.nodes.get(item_index)
+    }
+
+    /// 可见行，每行是一个节点下标。
+    pub fn rows(&self) -> &[usize] {
+        self.rows.items()
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.rows.selected_index()
+    }
+
+    pub fn scroll_offset(&self) -> usize {
+        self.rows.scroll_offset()
+    }
+
+    pub fn selected_item_index(&self) -> Option<usize> {
+        self.rows.selected().copied()
+    }
+
+    pub fn item_index_at_row(&self, row: usize) -> Option<usize> {
+        self.rows.items().get(row).copied()
+    }
+
+    pub fn set_roots(&mut self, items: Vec<LspCallHierarchyItem>) -> bool {
+        self.nodes = items
+            .into_iter()
+            .map(|item| CallHierarchyNode {
+                item,
+                depth: 0,
+                children: None,
+                expanded: false,
+                loading: false,
+            })
+            .collect();
+        self.roots = (0..self.nodes.len()).collect();
+        self.rows.clear();
+        self.rebuild_rows();
+        true
+    }
+
+    /// 清空树并切换展开方向，等待新的 prepare 结果。
+    pub fn reset(&mut self, direction: CallHierarchyDirection) -> bool {
+        let changed = self.direction != direction;
+        self.direction = direction;
+        self.clear() || changed
+    }
+
+    pub fn clear(&mut self) -> bool {
+        if self.nodes.is_empty() {
+            return false;
+        }
+        self.nodes.clear();
+        self.roots.clear();
+        self.rows.clear();
+        true
+    }
+
+    /// 展开节点。子节点未加载时标记为加载中并返回 `true`，由调用方发起请求；
+    /// 已加载（或正在加载）时只切换可见性。
+    pub fn begin_expand(&mut self, item_index: usize) -> bool {
+        let Some(node) = self.nodes.get_mut(item_index) else {
+            return false;
+        };
+        if node.children.is_some() || node.loading {
+            node.expanded = !node.expanded;
+            self.rebuild_rows();
+            return false;
+        }
+        node.expanded = true;
+        node.loading = true;
+        true
+    }
+
+    /// 把 incoming / outgoing calls 作为子节点挂到 `item_index` 下。只接受仍在加载中的节点，
+    /// 树被重建后迟到的响应会被丢弃。
+    pub fn set_children(&mut self, item_index: usize, items: Vec<LspCallHierarchyItem>) -> bool {
+        let Some(node) = self.nodes.get_mut(item_index) else {
+            return false;
+        };
+        if !node.loading {
+            return false;
+        }
+        node.loading = false;
+        let depth = node.depth + 1;
+
+        let start = self.nodes.len();
+        let children = (start..start + items.len()).collect();
+        self.nodes[item_index].children = Some(children);
+        self.nodes
+            .extend(items.into_iter().map(|item| CallHierarchyNode {
+                item,
+                depth,
+                children: None,
+                expanded: false,
+                loading: false,
+            }));
+        self.rebuild_rows();
+        true
+    }
+
+    pub fn set_view_height(&mut self, height: usize) -> bool {
+        self.rows.set_view_height(height)
+    }
+
+    pub fn move_selection(&mut self, delta: isize) -> bool {
+        self.rows.move_selection(delta)
+    }
+
+    pub fn scroll(&mut self, delta: isize) -> bool {
+        self.rows.scroll(delta)
+    }
+
+    pub fn click_row(&mut self, row: usize) -> bool {
+        self.rows.click_row(row)
+    }
+
+    fn rebuild_rows(&mut self) {
+        let mut rows = Vec::with_capacity(self.nodes.len());
+        let mut stack: Vec<usize> = self.roots.iter().rev().copied().collect();
+        while let Some(index) = stack.pop() {
+            rows.push(index);
+            let node = &self.nodes[index];
+            if node.expanded {
+                if let Some(children) = node.children.as_ref() {
+                    stack.extend(children.iter().rev().copied());
+                }
+            }
+        }
+        self.rows.replace_items(rows);
+    }
+}
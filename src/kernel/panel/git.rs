@@ -0,0 +1,313 @@
+use std::path::{Path, PathBuf};
+
+use ropey::Rope;
+use rustc_hash::FxHashMap;
+
+use crate::kernel::git::{line_diff, LineDiff};
+use crate::kernel::panel::list_selection::ListSelectionState;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitStash {
+    /// `stash@{N}` 里的 N；apply / drop 都按它寻址。
+    pub index: usize,
+    pub message: String,
+    pub datetime: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitBranch {
+    /// 本地分支为 `feature/x`，远程分支带远程名：`origin/feature/x`。
+    pub name: String,
+    pub remote: bool,
+    /// 是否为当前 HEAD 所在分支。
+    pub current: bool,
+}
+
+impl GitBranch {
+    /// checkout 用的本地名：远程分支去掉远程名。
+    pub fn local_name(&self) -> &str {
+        if self.remote {
+            self.name
+                .split_once('/')
+                .map_or(self.name.as_str(), |(_, name)| name)
+        } else {
+            &self.name
+        }
+    }
+}
+
+/// `git blame --porcelain` 对单行的结论。未提交的行 `commit` 全为 0。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitBlameLine {
+    pub commit: String,
+    pub author: String,
+    /// author-time，Unix 秒。
+    pub timestamp: i64,
+}
+
+impl GitBlameLine {
+    pub fn is_uncommitted(&self) -> bool {
+        self.commit.bytes().all(|b| b == b'0')
+    }
+
+    /// 行尾虚拟文本：`⏎ Jane Doe • 3 hours ago`。
+    pub fn annotation(&self, now: i64) -> String {
+        if self.is_uncommitted() {
+            return "⏎ You • Uncommitted changes".to_string();
+        }
+        format!(
+            "⏎ {} • {}",
+            self.author,
+            format_relative_time(now.saturating_sub(self.timestamp))
+        )
+    }
+}
+
+fn format_relative_time(elapsed_secs: i64) -> String {
+    const UNITS: [(i64, &str); 6] = [
+        (365 * 24 * 3600, "year"),
+        (30 * 24 * 3600, "month"),
+        (7 * 24 * 3600, "week"),
+        (24 * 3600, "day"),
+        (3600, "hour"),
+        (60, "minute"),
+    ];
+    for (secs, unit) in UNITS {
+        let count = elapsed_secs / secs;
+        if count > 0 {
+            let plural = if count == 1 { "" } else { "s" };
+            return format!("{count} {unit}{plural} ago");
+        }
+    }
+    "just now".to_string()
+}
+
+/// `ShowGitDiff` 打开的并排 diff：左侧是暂存区里的版本（没有暂存过改动时即 HEAD），
+/// 右侧是当前缓冲区。上下键在改动块之间跳，Enter 暂存选中的块。
+#[derive(Debug)]
+pub struct GitDiffView {
+    pub path: PathBuf,
+    pub original: Rope,
+    pub current: Rope,
+    pub diff: LineDiff,
+    selected_hunk: usize,
+    scroll_offset: usize,
+    view_height: usize,
+}
+
+impl GitDiffView {
+    pub fn new(path: PathBuf, original: Rope, current: Rope) -> Self {
+        let diff = line_diff(&original, &current);
+        let mut view = Self {
+            path,
+            original,
+            current,
+            diff,
+            selected_hunk: 0,
+            scroll_offset: 0,
+            view_height: 1,
+        };
+        view.reveal_selected_hunk();
+        view
+    }
+
+    /// 暂存一块后暂存区变了：重算 diff，选中项停在原下标（越界则取最后一块）。
+    pub fn refresh(&mut self, original: Rope, current: Rope) {
+        self.diff = line_diff(&original, &current);
+        self.original = original;
+        self.current = current;
+        self.selected_hunk = self
+            .selected_hunk
+            .min(self.diff.hunks.len().saturating_sub(1));
+        self.scroll_offset = self
+            .scroll_offset
+            .min(self.diff.rows.len().saturating_sub(1));
+        self.reveal_selected_hunk();
+    }
+
+    pub fn selected_hunk(&self) -> Option<usize> {
+        (self.selected_hunk < self.diff.hunks.len()).then_some(self.selected_hunk)
+    }
+
+    pub fn scroll_offset(&self) -> usize {
+        self.scroll_offset
+    }
+
+    pub fn move_hunk(&mut self, delta: isize) -> bool {
+        let Some(last) = self.diff.hunks.len().checked_sub(1) else {
+            return false;
+        };
+        let next = self.selected_hunk.saturating_add_signed(delta).min(last);
+        if next == self.selected_hunk {
+            return false;
+        }
+        self.selected_hunk = next;
+        self.reveal_selected_hunk();
+        true
+    }
+
+    pub fn scroll(&mut self, delta: isize) -> bool {
+        let max = self.diff.rows.len().saturating_sub(self.view_height);
+        let next = self.scroll_offset.saturating_add_signed(delta).min(max);
+        let changed = next != self.scroll_offset;
+        self.scroll_offset = next;
+        changed
+    }
+
+    pub fn set_view_height(&mut self, height: usize) -> bool {
+        let height = height.max(1);
+        if self.view_height == height {
+            return false;
+        }
+        self.view_height = height;
+        self.reveal_selected_hunk();
+        true
+    }
+
+    /// 选中块不在视口内时滚到它上方留两行上下文的位置。
+    fn reveal_selected_hunk(&mut self) {
+        let Some(hunk) = self.diff.hunks.get(self.selected_hunk) else {
+            return;
+        };
+        let visible = self.scroll_offset..self.scroll_offset + self.view_height;
+        if !visible.contains(&hunk.rows.start) || hunk.rows.end > visible.end {
+            self.scroll_offset = hunk.rows.start.saturating_sub(2);
+        }
+    }
+}
+
+/// Git 面板的一行：可折叠的分区标题，或分区内的条目（存 `stashes` 下标）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitPanelRow {
+    StashesHeader,
+    Stash(usize),
+}
+
+/// Git 面板态。`stashes` 是 `git stash list` 的最新快照；可见行由折叠状态派生，
+/// 选择 / 滚动复用 `ListSelectionState`。
+#[derive(Debug)]
+pub struct GitState {
+    pub stashes: Vec<GitStash>,
+    /// `git for-each-ref` 的最新快照，供分支选择器使用。
+    pub branches: Vec<GitBranch>,
+    stashes_expanded: bool,
+    rows: ListSelectionState<GitPanelRow>,
+    /// 行内 blame 缓存：路径 -> (0 基行号 -> 结论)。文件落盘内容变化（保存、外部修改）时
+    /// 整个路径失效，否则同一行不会重复起 `git blame` 进程。
+    blame: FxHashMap<PathBuf, FxHashMap<usize, GitBlameLine>>,
+    /// `GitDiff` 浮层的内容；每次 `ShowGitDiff` 先清空，等暂存区版本读回后重建。
+    pub diff: Option<GitDiffView>,
+}
+
+impl Default for GitState {
+    fn default() -> Self {
+        let mut state = Self {
+            stashes: Vec::new(),
+            branches: Vec::new(),
+            stashes_expanded: true,
+            rows: ListSelectionState::default(),
+            blame: FxHashMap::default(),
+            diff: None,
+        };
+        state.rebuild_rows();
+        state
+    }
+}
+
+impl GitState {
+    pub fn rows(&self) -> &[GitPanelRow] {
+        self.rows.items()
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.rows.selected_index()
+    }
+
+    pub fn scroll_offset(&self) -> usize {
+        self.rows.scroll_offset()
+    }
+
+    pub fn selected_row(&self) -> Option<GitPanelRow> {
+        self.rows.selected().copied()
+    }
+
+    pub fn stashes_expanded(&self) -> bool {
+        self.stashes_expanded
+    }
+
+    pub fn stash_at_row(&self, row: usize) -> Option<&GitStash> {
+        match self.rows.items().get(row)? {
+            GitPanelRow::Stash(i) => self.stashes.get(*i),
+            GitPanelRow::StashesHeader => None,
+        }
+    }
+
+    pub fn set_stashes(&mut self, stashes: Vec<GitStash>) -> bool {
+        if self.stashes == stashes {
+            return false;
+        }
+        self.stashes = stashes;
+        self.rebuild_rows();
+        true
+    }
+
+    pub fn set_branches(&mut self, branches: Vec<GitBranch>) -> bool {
+        if self.branches == branches {
+            return false;
+        }
+        self.branches = branches;
+        true
+    }
+
+    pub fn toggle_stashes_expanded(&mut self) -> bool {
+        self.stashes_expanded = !self.stashes_expanded;
+        self.rebuild_rows();
+        true
+    }
+
+    /// diff 视图与面板共用同一浮层高度。
+    pub fn set_view_height(&mut self, height: usize) -> bool {
+        let diff_changed = self
+            .diff
+            .as_mut()
+            .is_some_and(|diff| diff.set_view_height(height));
+        self.rows.set_view_height(height) | diff_changed
+    }
+
+    pub fn move_selection(&mut self, delta: isize) -> bool {
+        self.rows.move_selection(delta)
+    }
+
+    pub fn scroll(&mut self, delta: isize) -> bool {
+        self.rows.scroll(delta)
+    }
+
+    pub fn click_row(&mut self, row: usize) -> bool {
+        self.rows.click_row(row)
+    }
+
+    pub fn blame_for(&self, path: &Path, line: usize) -> Option<&GitBlameLine> {
+        self.blame.get(path)?.get(&line)
+    }
+
+    pub fn set_blame(&mut self, path: PathBuf, line: usize, blame: GitBlameLine) -> bool {
+        let lines = self.blame.entry(path).or_default();
+        if lines.get(&line) == Some(&blame) {
+            return false;
+        }
+        lines.insert(line, blame);
+        true
+    }
+
+    pub fn invalidate_blame(&mut self, path: &Path) -> bool {
+        self.blame.remove(path).is_some()
+    }
+
+    fn rebuild_rows(&mut self) {
+        let mut rows = vec![GitPanelRow::StashesHeader];
+        if self.stashes_expanded {
+            rows.extend((0..self.stashes.len()).map(GitPanelRow::Stash));
+        }
+        self.rows.replace_items(rows);
+    }
+}
@@ -1,4 +1,6 @@
+pub mod call_hierarchy;
 pub mod code_actions;
+pub mod git;
 pub mod list_selection;
 pub mod locations;
 pub mod problems;
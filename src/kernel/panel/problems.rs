@@ -34,6 +34,20 @@ pub struct ProblemRange {
     pub end_col: u32,
 }
 
+impl ProblemRange {
+    /// `(line, character)`（LSP 坐标）是否落在区间内；空区间按一个字符算，与编辑器里的下划线一致。
+    pub fn contains(&self, line: u32, character: u32) -> bool {
+        let start = (self.start_line, self.start_col);
+        let end = (self.end_line, self.end_col);
+        let pos = (line, character);
+        if end <= start {
+            pos == start
+        } else {
+            start <= pos && pos < end
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ProblemItem {
     pub path: PathBuf,
@@ -55,6 +69,25 @@ impl ProblemsState {
         self.inner.items()
     }
 
+    /// 某个文件的全部诊断（按位置排好序）；编辑器据此画下划线。
+    pub fn items_for_path(&self, path: &Path) -> &[ProblemItem] {
+        self.ranges_by_path
+            .get(path)
+            .map_or(&[], |range| &self.inner.items()[range.clone()])
+    }
+
+    /// 某个文件里覆盖 `(line, character)` 的诊断（LSP 坐标）。
+    pub fn items_at<'a>(
+        &'a self,
+        path: &Path,
+        line: u32,
+        character: u32,
+    ) -> impl Iterator<Item = &'a ProblemItem> {
+        self.items_for_path(path)
+            .iter()
+            .filter(move |item| item.range.contains(line, character))
+    }
+
     pub fn selected_index(&self) -> usize {
         self.inner.selected_index()
     }
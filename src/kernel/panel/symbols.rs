@@ -1,5 +1,7 @@
+use std::cmp::Reverse;
 use std::path::PathBuf;
 
+use crate::kernel::palette::fuzzy_score;
 use crate::kernel::panel::list_selection::ListSelectionState;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -13,9 +15,14 @@ pub struct SymbolItem {
     pub column: u32,
 }
 
+/// 符号面板态。`workspace/symbol` 可以经 `$/progress` 分批返回部分结果：
+/// `streaming` 期间每批追加后重新排序，最终响应到达时合并去重并结束流式状态。
+/// 带查询的 `workspace/symbol` 结果按模糊分数重排，不沿用服务端顺序。
 #[derive(Debug, Default)]
 pub struct SymbolsState {
     inner: ListSelectionState<SymbolItem>,
+    streaming: bool,
+    query: String,
 }
 
 impl SymbolsState {
@@ -43,8 +50,59 @@ impl SymbolsState {
         true
     }
 
+    pub fn is_streaming(&self) -> bool {
+        self.streaming
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// 记录本次 `workspace/symbol` 的查询，之后到达的结果据此排序。
+    pub fn set_query(&mut self, query: String) {
+        self.query = query;
+    }
+
+    /// 追加一批部分结果。流式开始前的旧条目（上一次查询的结果）被整体替换。
+    pub fn extend_streaming(&mut self, items: Vec<SymbolItem>) -> bool {
+        let was_streaming = std::mem::replace(&mut self.streaming, true);
+        self.merge_workspace_items(items, was_streaming) || !was_streaming
+    }
+
+    /// 最终响应：与已流入的部分结果合并去重，结束流式状态。
+    pub fn finish_streaming(&mut self, items: Vec<SymbolItem>) -> bool {
+        let was_streaming = std::mem::replace(&mut self.streaming, false);
+        self.merge_workspace_items(items, was_streaming) || was_streaming
+    }
+
+    fn merge_workspace_items(&mut self, items: Vec<SymbolItem>, append: bool) -> bool {
+        let mut merged = if append {
+            let mut merged = self.inner.items().to_vec();
+            merged.extend(items);
+            merged
+        } else {
+            items
+        };
+        merged.sort_by(|a, b| {
+            a.name
+                .to_lowercase()
+                .cmp(&b.name.to_lowercase())
+                .then_with(|| a.path.cmp(&b.path))
+                .then_with(|| (a.line, a.column).cmp(&(b.line, b.column)))
+        });
+        merged.dedup();
+        if !self.query.is_empty() {
+            // 稳定排序：同分（含都不匹配）保持上面的名称序。
+            let query_lc = self.query.to_ascii_lowercase();
+            merged.sort_by_cached_key(|item| Reverse(fuzzy_score(&query_lc, &item.name)));
+        }
+        self.set_items(merged)
+    }
+
     pub fn clear(&mut self) -> bool {
-        self.inner.clear()
+        let was_streaming = std::mem::replace(&mut self.streaming, false);
+        self.query.clear();
+        self.inner.clear() || was_streaming
     }
 
     pub fn set_view_height(&mut self, height: usize) -> bool {
@@ -63,3 +121,7 @@ impl SymbolsState {
         self.inner.click_row(row)
     }
 }
+
+#[cfg(test)]
+#[path = "../../../tests/unit/kernel/symbols.rs"]
+mod tests;
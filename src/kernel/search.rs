@@ -34,20 +34,48 @@ pub enum SearchResultItem {
     },
 }
 
+/// `[⚙]` 展开的两个过滤输入行。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchFilterField {
+    Include,
+    Exclude,
+}
+
+/// 一次全局替换的累计结果；替换完成后在摘要行显示。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplaceSummary {
+    pub files: usize,
+    pub occurrences: usize,
+    pub failures: usize,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct SearchState {
     pub query: String,
     pub searching: bool,
     pub active_search_id: Option<u64>,
     pub files_searched: usize,
-    pub files_with_matches: usize,
+    pub files_total: usize,
     pub total_matches: usize,
     pub file_count: usize,
+    pub binary_files_skipped: usize,
     pub files: Vec<SearchFileResult>,
     pub items: Vec<SearchResultItem>,
     pub selected_index: usize,
     pub panel_view: SearchViewportState,
     pub last_error: Option<String>,
+    /// 替换输入行是否展开；展开后 Tab 在查询 / 替换两栏间切换输入焦点。
+    pub replace_visible: bool,
+    pub replace_focused: bool,
+    pub replacement: String,
+    /// `Some` 表示当前（或最近一次）运行的是替换而非搜索。
+    pub replace_summary: Option<ReplaceSummary>,
+    /// include / exclude glob 输入行是否展开；收起后已填的 glob 仍然生效。
+    pub filters_visible: bool,
+    /// `Some` 时输入焦点在过滤行上（此时 `replace_focused` 为 false）。
+    pub filter_focused: Option<SearchFilterField>,
+    pub include_glob: String,
+    pub exclude_glob: String,
 }
 
 pub struct SearchResultsSnapshot<'a> {
@@ -56,11 +84,13 @@ pub struct SearchResultsSnapshot<'a> {
     pub total_matches: usize,
     pub file_count: usize,
     pub files_searched: usize,
-    pub files_with_matches: usize,
+    pub files_total: usize,
+    pub binary_files_skipped: usize,
     pub items: &'a [SearchResultItem],
     pub selected_index: usize,
     pub scroll_offset: usize,
     pub last_error: Option<&'a str>,
+    pub replace_summary: Option<ReplaceSummary>,
 }
 
 impl SearchState {
@@ -72,11 +102,13 @@ impl SearchState {
             total_matches: self.total_matches,
             file_count: self.file_count,
             files_searched: self.files_searched,
-            files_with_matches: self.files_with_matches,
+            files_total: self.files_total,
+            binary_files_skipped: self.binary_files_skipped,
             items: &self.items,
             selected_index: self.selected_index,
             scroll_offset,
             last_error: self.last_error.as_deref(),
+            replace_summary: self.replace_summary,
         }
     }
 
@@ -88,18 +120,76 @@ impl SearchState {
         self.searching = true;
         self.active_search_id = None;
         self.files_searched = 0;
-        self.files_with_matches = 0;
+        self.files_total = 0;
         self.total_matches = 0;
         self.file_count = 0;
+        self.binary_files_skipped = 0;
         self.files.clear();
         self.items.clear();
         self.selected_index = 0;
         self.panel_view.scroll_offset = 0;
         self.last_error = None;
+        self.replace_summary = None;
+
+        true
+    }
+
+    /// 替换复用搜索的运行状态（`searching` / `active_search_id`）；结果列表随之清空，
+    /// 因为替换后原匹配位置已失效。
+    pub fn begin_replace(&mut self) -> bool {
+        if !self.replace_visible || !self.begin_search() {
+            return false;
+        }
+        self.replace_summary = Some(ReplaceSummary::default());
+        true
+    }
 
+    pub fn toggle_replace(&mut self) -> bool {
+        self.replace_visible = !self.replace_visible;
+        self.replace_focused = self.replace_visible;
+        self.filter_focused = None;
         true
     }
 
+    pub fn toggle_filters(&mut self) -> bool {
+        self.filters_visible = !self.filters_visible;
+        self.replace_focused = false;
+        self.filter_focused = self.filters_visible.then_some(SearchFilterField::Include);
+        true
+    }
+
+    /// 焦点按 查询 → 替换 → include → exclude 的顺序循环，跳过未展开的行。
+    pub fn switch_field(&mut self) -> bool {
+        if !self.replace_visible && !self.filters_visible {
+            return false;
+        }
+        let (replace, filter) = match (self.replace_focused, self.filter_focused) {
+            (false, None) if self.replace_visible => (true, None),
+            (false, None) | (true, _) if self.filters_visible => {
+                (false, Some(SearchFilterField::Include))
+            }
+            (_, Some(SearchFilterField::Include)) => (false, Some(SearchFilterField::Exclude)),
+            _ => (false, None),
+        };
+        self.replace_focused = replace;
+        self.filter_focused = filter;
+        true
+    }
+
+    /// 查询输入行、展开的替换行与过滤行一共占的行数。
+    pub fn input_rows(&self) -> usize {
+        1 + usize::from(self.replace_visible) + 2 * usize::from(self.filters_visible)
+    }
+
+    /// 非空的 include / exclude glob，作为 `Effect::StartGlobalSearch` 的过滤条件。
+    pub fn path_filters(&self) -> (Option<String>, Option<String>) {
+        let non_empty = |glob: &str| {
+            let glob = glob.trim();
+            (!glob.is_empty()).then(|| glob.to_string())
+        };
+        (non_empty(&self.include_glob), non_empty(&self.exclude_glob))
+    }
+
     pub fn set_active_search_id(&mut self, search_id: u64) -> bool {
         if self.active_search_id == Some(search_id) {
             return false;
@@ -118,6 +208,31 @@ impl SearchState {
         self.query.pop().is_some()
     }
 
+    pub fn append_replacement_char(&mut self, ch: char) -> bool {
+        self.replacement.push(ch);
+        true
+    }
+
+    pub fn backspace_replacement(&mut self) -> bool {
+        self.replacement.pop().is_some()
+    }
+
+    pub fn append_filter_char(&mut self, field: SearchFilterField, ch: char) -> bool {
+        self.filter_text_mut(field).push(ch);
+        true
+    }
+
+    pub fn backspace_filter(&mut self, field: SearchFilterField) -> bool {
+        self.filter_text_mut(field).pop().is_some()
+    }
+
+    fn filter_text_mut(&mut self, field: SearchFilterField) -> &mut String {
+        match field {
+            SearchFilterField::Include => &mut self.include_glob,
+            SearchFilterField::Exclude => &mut self.exclude_glob,
+        }
+    }
+
     pub fn set_view_height(&mut self, height: usize) -> bool {
         let height = height.max(1);
         if self.panel_view.view_height == height {
@@ -190,6 +305,9 @@ impl SearchState {
         let search_id = match &msg {
             GlobalSearchMessage::FileMatches { search_id, .. }
             | GlobalSearchMessage::Progress { search_id, .. }
+            | GlobalSearchMessage::BinaryFileSkipped { search_id, .. }
+            | GlobalSearchMessage::Replaced { search_id, .. }
+            | GlobalSearchMessage::ReplaceError { search_id, .. }
             | GlobalSearchMessage::Complete { search_id, .. }
             | GlobalSearchMessage::Cancelled { search_id }
             | GlobalSearchMessage::Error { search_id, .. } => *search_id,
@@ -236,15 +354,41 @@ impl SearchState {
 
                 true
             }
+            // `matches_so_far` 可能领先于已送达的 FileMatches；结果计数仍以后者为准，
+            // 避免列表与摘要不一致。
             GlobalSearchMessage::Progress {
                 files_searched,
-                files_with_matches,
+                files_total,
                 ..
             } => {
-                let changed = self.files_searched != files_searched
-                    || self.files_with_matches != files_with_matches;
+                let changed =
+                    self.files_searched != files_searched || self.files_total != files_total;
                 self.files_searched = files_searched;
-                self.files_with_matches = files_with_matches;
+                self.files_total = files_total;
+                changed
+            }
+            // 只计数，不进结果列表；完成后在摘要里显示。
+            GlobalSearchMessage::BinaryFileSkipped { .. } => {
+                self.binary_files_skipped += 1;
+                !self.searching
+            }
+            // 编辑本身由 store 应用；这里只累计摘要。
+            GlobalSearchMessage::Replaced { count, .. } => {
+                let summary = self.replace_summary.get_or_insert_with(Default::default);
+                summary.files += 1;
+                summary.occurrences += count;
+                true
+            }
+            GlobalSearchMessage::ReplaceError { .. } => {
+                self.replace_summary
+                    .get_or_insert_with(Default::default)
+                    .failures += 1;
+                true
+            }
+            GlobalSearchMessage::Complete { .. } if self.replace_summary.is_some() => {
+                let changed = self.searching;
+                self.searching = false;
+                self.files_total = 0;
                 changed
             }
             GlobalSearchMessage::Complete {
@@ -257,18 +401,21 @@ impl SearchState {
                     || self.total_matches != total_matches;
                 self.searching = false;
                 self.files_searched = total_files;
+                self.files_total = 0;
                 self.total_matches = total_matches;
                 changed
             }
             GlobalSearchMessage::Cancelled { .. } => {
                 let changed = self.searching;
                 self.searching = false;
+                self.files_total = 0;
                 changed
             }
             GlobalSearchMessage::Error { message, .. } => {
                 let changed =
                     self.searching || self.last_error.as_deref() != Some(message.as_str());
                 self.searching = false;
+                self.files_total = 0;
                 self.last_error = Some(message);
                 changed
             }
@@ -12,6 +12,7 @@ use std::path::PathBuf;
 const APP_NAME: &str = "zcode";
 const BACKUP_DIR: &str = "backups";
 const LOG_DIR: &str = "logs";
+const TRASH_DIR: &str = "trash";
 
 /// 获取应用数据目录
 fn get_app_data_dir() -> Option<PathBuf> {
@@ -81,6 +82,66 @@ pub fn get_log_dir() -> Option<PathBuf> {
     get_app_data_dir().map(|p| p.join(LOG_DIR))
 }
 
+/// 资源管理器删除文件时的回收站：`$XDG_CONFIG_HOME/zcode/trash`，缺省 `~/.config/zcode/trash`
+pub fn get_trash_dir() -> Option<PathBuf> {
+    std::env::var("XDG_CONFIG_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var("HOME")
+                .ok()
+                .map(|home| PathBuf::from(home).join(".config"))
+        })
+        .map(|dir| dir.join(APP_NAME).join(TRASH_DIR))
+}
+
+/// `path` 在回收站里的位置：`<时间戳>-<文件名>`，同名文件多次删除互不覆盖
+pub fn trash_entry_path(trash_dir: &std::path::Path, path: &std::path::Path) -> PathBuf {
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    trash_dir.join(format!("{stamp:x}-{name}"))
+}
+
+/// 回收站最多保留的条目数。撤销栈只存 `MAX_EXPLORER_HISTORY` 步，多留的余量给同时开着的其它实例。
+pub const MAX_TRASH_ENTRIES: usize = 200;
+
+/// 回收站超过 `keep` 条时按时间戳删掉最早的条目；不是 `trash_entry_path` 生成的名字不动。
+pub fn prune_trash_dir(trash_dir: &std::path::Path, keep: usize) -> std::io::Result<()> {
+    let mut entries = Vec::new();
+    for entry in std::fs::read_dir(trash_dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let stamp = name
+            .to_str()
+            .and_then(|name| name.split_once('-'))
+            .and_then(|(stamp, _)| u128::from_str_radix(stamp, 16).ok());
+        if let Some(stamp) = stamp {
+            entries.push((stamp, entry.path()));
+        }
+    }
+    if entries.len() <= keep {
+        return Ok(());
+    }
+    entries.sort_unstable_by_key(|(stamp, _)| *stamp);
+    let excess = entries.len() - keep;
+    for (_, path) in entries.into_iter().take(excess) {
+        let is_dir = std::fs::symlink_metadata(&path).is_ok_and(|meta| meta.is_dir());
+        if is_dir {
+            std::fs::remove_dir_all(&path)?;
+        } else {
+            std::fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
 /// 获取指定文件的 .ops 备份文件路径
 pub fn get_ops_file_path(file_path: &std::path::Path) -> Option<PathBuf> {
     // 获取绝对路径
@@ -4,9 +4,13 @@
 //! TODO: 大文本粘贴优化（>10MB 时考虑分块处理或警告）
 
 use crate::core::Service;
+use crate::kernel::services::ports::config::ClipboardBackend;
+use std::collections::VecDeque;
 use std::path::Path;
 
 const PASTE_MAX_SIZE: usize = 10 * 1024 * 1024; // 10MB
+/// 剪贴板历史（kill ring）最多保留的条数。
+pub const CLIPBOARD_HISTORY_LIMIT: usize = 20;
 
 #[derive(Debug, Clone, Copy)]
 enum ClipboardProvider {
@@ -23,6 +27,12 @@ enum ClipboardProvider {
 
 pub struct ClipboardService {
     provider: ClipboardProvider,
+    /// 复制 / 剪切过的文本，新的在前；各编辑器窗格共用。
+    history: VecDeque<String>,
+    /// 上一次循环粘贴落到的条目下标。
+    history_index: usize,
+    /// 复制时是否先写 OSC 52；由 `set_backend` 按配置与是否处于 SSH 会话算出。
+    prefers_osc52: bool,
 }
 
 #[derive(Debug)]
@@ -88,7 +98,23 @@ impl ClipboardService {
             }
         };
 
-        Self { provider }
+        Self {
+            provider,
+            history: VecDeque::new(),
+            history_index: 0,
+            prefers_osc52: false,
+        }
+    }
+
+    pub fn set_backend(&mut self, backend: ClipboardBackend) {
+        let in_ssh = env_is_set("SSH_TTY") || env_is_set("SSH_CONNECTION");
+        self.prefers_osc52 = prefers_osc52(backend, in_ssh);
+    }
+
+    /// 复制应先写 OSC 52 序列（由终端代为复制），失败再退回系统剪贴板。
+    /// OSC 52 只能写不能读，编辑器内粘贴见 `paste_text`。
+    pub fn prefers_osc52(&self) -> bool {
+        self.prefers_osc52
     }
 
     pub fn is_available(&self) -> bool {
@@ -109,7 +135,33 @@ impl ClipboardService {
         }
     }
 
+    /// 编辑器内粘贴：读系统剪贴板；走 OSC 52 又没有剪贴板工具时读不回终端那一侧，
+    /// 改用历史里最新一条，即最近一次在编辑器里复制的内容。
+    pub fn paste_text(&mut self) -> Result<String, ClipboardError> {
+        if self.prefers_osc52 && !self.is_available() {
+            return self
+                .history
+                .front()
+                .cloned()
+                .ok_or(ClipboardError::NotAvailable);
+        }
+        self.get_text()
+    }
+
+    /// OSC 52 写成功之后调用：记入历史，系统剪贴板可用时同步写一份，
+    /// 免得随后的编辑器内粘贴读到旧内容。
+    pub fn copied_via_osc52(&mut self, text: &str) -> Result<(), ClipboardError> {
+        if self.is_available() {
+            self.set_text(text)
+        } else {
+            self.remember(text);
+            Ok(())
+        }
+    }
+
     pub fn set_text(&mut self, text: &str) -> Result<(), ClipboardError> {
+        // 系统剪贴板不可用时历史照记，循环粘贴与历史选择器仍然可用。
+        self.remember(text);
         match self.provider {
             #[cfg(target_os = "macos")]
             ClipboardProvider::MacOsPasteboard => macos::pbcopy_text(text),
@@ -124,7 +176,47 @@ impl ClipboardService {
     }
 }
 
-#[cfg(target_os = "linux")]
+impl ClipboardService {
+    /// 记一条复制 / 剪切的文本：重复的条目移到最前，超出上限丢掉最旧的。
+    pub fn remember(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        self.history.retain(|entry| entry != text);
+        self.history.push_front(text.to_string());
+        self.history.truncate(CLIPBOARD_HISTORY_LIMIT);
+        self.history_index = 0;
+    }
+
+    pub fn history(&self) -> &VecDeque<String> {
+        &self.history
+    }
+
+    /// 循环粘贴从最新一条重新数起。
+    pub fn reset_history_index(&mut self) {
+        self.history_index = 0;
+    }
+
+    /// 在历史里环形移动（`delta > 0` 为更早），返回落到的条目。
+    pub fn cycle_history(&mut self, delta: isize) -> Option<&str> {
+        let len = self.history.len();
+        if len == 0 {
+            return None;
+        }
+        let index = (self.history_index as isize + delta).rem_euclid(len as isize) as usize;
+        self.history_index = index;
+        self.history.get(index).map(String::as_str)
+    }
+}
+
+fn prefers_osc52(backend: ClipboardBackend, in_ssh: bool) -> bool {
+    match backend {
+        ClipboardBackend::Auto => in_ssh,
+        ClipboardBackend::System => false,
+        ClipboardBackend::Osc52 => true,
+    }
+}
+
 fn env_is_set(name: &str) -> bool {
     std::env::var_os(name).is_some()
 }
@@ -3,7 +3,7 @@
 //! 提供统一的配置管理，支持运行时修改
 
 use crate::core::Service;
-use crate::kernel::services::ports::config::EditorConfig;
+use crate::kernel::services::ports::config::{EditorConfig, PartialEditorConfig};
 
 pub struct ConfigService {
     editor: EditorConfig,
@@ -23,6 +23,11 @@ impl ConfigService {
     pub fn editor_mut(&mut self) -> &mut EditorConfig {
         &mut self.editor
     }
+
+    /// 某个 pane 的有效配置：全局配置叠加该 pane 的覆盖项。
+    pub fn editor_for_pane(&self, overrides: Option<&PartialEditorConfig>) -> EditorConfig {
+        self.editor.merged_with(overrides)
+    }
 }
 
 impl Default for ConfigService {
@@ -0,0 +1,322 @@
+//! 本地文件系统上的移动 / 拷贝。
+//!
+//! 同设备移动直接 `rename`；跨设备时先拷到目标旁的临时名再 rename 到位。覆盖已有目标时，
+//! 旧目标在新内容就位之后才删除，中途失败会还原，目标路径上不会出现半截或空缺。
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub fn copy_path(from: &Path, to: &Path, overwrite: bool) -> io::Result<()> {
+    if from == to {
+        return Ok(());
+    }
+
+    let from_meta = std::fs::symlink_metadata(from)?;
+    if from_meta.is_dir() && to.starts_with(from) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "cannot copy a directory into itself",
+        ));
+    }
+
+    let Some(parent) = to.parent() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "destination has no parent directory",
+        ));
+    };
+    if !std::fs::metadata(parent).is_ok_and(|m| m.is_dir()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "destination parent is not a directory",
+        ));
+    }
+
+    if !overwrite && std::fs::symlink_metadata(to).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "destination exists",
+        ));
+    }
+
+    // 与跨设备移动一样先拷到临时名再就位，覆盖失败时旧目标原样保留。
+    let staged = stage_copy(from, to)?;
+    install(&staged, to, overwrite, |src, to| std::fs::rename(src, to)).inspect_err(|_| {
+        let _ = remove_existing_path(&staged);
+    })
+}
+
+pub fn move_path(from: &Path, to: &Path, overwrite: bool) -> io::Result<()> {
+    move_path_impl(from, to, overwrite, |from, to| std::fs::rename(from, to))
+}
+
+fn move_path_impl(
+    from: &Path,
+    to: &Path,
+    overwrite: bool,
+    rename_fn: impl FnOnce(&Path, &Path) -> io::Result<()>,
+) -> io::Result<()> {
+    if from == to {
+        return Ok(());
+    }
+
+    let from_meta = std::fs::symlink_metadata(from)?;
+    if from_meta.is_dir() && to.starts_with(from) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "cannot move a directory into itself",
+        ));
+    }
+
+    let Some(parent) = to.parent() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "destination has no parent directory",
+        ));
+    };
+    if !std::fs::metadata(parent).is_ok_and(|m| m.is_dir()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "destination parent is not a directory",
+        ));
+    }
+
+    if !overwrite && std::fs::symlink_metadata(to).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "destination exists",
+        ));
+    }
+
+    // 明知跨设备就不必先试一次 rename；探测不出来时仍靠 rename 的 `CrossesDevices` 兜底。
+    if !same_device(from, parent) {
+        return move_across_devices(from, to, overwrite);
+    }
+    match install(from, to, overwrite, rename_fn) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            move_across_devices(from, to, overwrite)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// 把同设备上的 `src` rename 到 `to`。要覆盖的目标与 `src` 都不是目录时，rename 本身就原子地替换；
+/// 涉及目录时 rename 不能直接覆盖，先把旧目标挪进一个独占创建的临时目录，新内容就位后再删，
+/// rename 失败则把旧目标挪回原处。
+fn install(
+    src: &Path,
+    to: &Path,
+    overwrite: bool,
+    rename_fn: impl FnOnce(&Path, &Path) -> io::Result<()>,
+) -> io::Result<()> {
+    let replaces_dir = overwrite
+        && match std::fs::symlink_metadata(to) {
+            Ok(to_meta) => to_meta.is_dir() || std::fs::symlink_metadata(src)?.is_dir(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => false,
+            Err(e) => return Err(e),
+        };
+    if !replaces_dir {
+        return rename_fn(src, to);
+    }
+
+    let aside_dir = reserve_staging(to, |path| std::fs::create_dir(path))?;
+    let aside = aside_dir.join("previous");
+    if let Err(e) = std::fs::rename(to, &aside) {
+        let _ = std::fs::remove_dir(&aside_dir);
+        return Err(e);
+    }
+    match rename_fn(src, to) {
+        Ok(()) => {
+            let _ = remove_existing_path(&aside_dir);
+            Ok(())
+        }
+        Err(e) => {
+            if std::fs::rename(&aside, to).is_ok() {
+                let _ = std::fs::remove_dir(&aside_dir);
+            }
+            Err(e)
+        }
+    }
+}
+
+/// `from` 与目标目录 `to_dir` 是否在同一设备上；取不到元数据时按同设备处理，错误交给 rename 报告。
+#[cfg(unix)]
+fn same_device(from: &Path, to_dir: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (std::fs::symlink_metadata(from), std::fs::metadata(to_dir)) {
+        (Ok(from_meta), Ok(dir_meta)) => from_meta.dev() == dir_meta.dev(),
+        _ => true,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_device(_from: &Path, _to_dir: &Path) -> bool {
+    true
+}
+
+fn remove_existing_path(path: &Path) -> io::Result<()> {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) => {
+            if meta.is_dir() {
+                std::fs::remove_dir_all(path)
+            } else {
+                std::fs::remove_file(path)
+            }
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// 跨设备移动：先完整拷到目标目录下的临时名，再同设备 rename 到位，目标路径上不会出现半截内容；
+/// 要覆盖的旧目标在拷贝成功之前不会被动到。最后才删除源。
+fn move_across_devices(from: &Path, to: &Path, overwrite: bool) -> io::Result<()> {
+    let describe = |e: io::Error| {
+        io::Error::new(
+            e.kind(),
+            format!(
+                "cross-device move of {} to {} failed: {e}",
+                from.display(),
+                to.display()
+            ),
+        )
+    };
+
+    let staged = stage_copy(from, to).map_err(describe)?;
+    install(&staged, to, overwrite, |src, to| std::fs::rename(src, to)).map_err(|e| {
+        let _ = remove_existing_path(&staged);
+        describe(e)
+    })?;
+
+    remove_existing_path(from).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!(
+                "copied {} to {} across devices but could not remove the source: {e}",
+                from.display(),
+                to.display()
+            ),
+        )
+    })
+}
+
+/// 占一个尚不存在的临时名时最多换这么多个后缀。
+const MAX_STAGING_ATTEMPTS: u32 = 64;
+
+/// 与 `to` 同目录的第 `attempt` 个隐藏临时名，保证最后一步 rename 不跨设备。
+fn staging_path(to: &Path, attempt: u32) -> PathBuf {
+    let name = to
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    to.with_file_name(format!(
+        ".{name}.zcode-move-{}-{attempt}",
+        std::process::id()
+    ))
+}
+
+/// 在 `to` 旁边用 `reserve` 排他地占一个临时名（`create_new` / `create_dir` / `symlink`）并返回它；
+/// 已存在就换下一个后缀，绝不覆盖或删除别人的文件。
+fn reserve_staging(
+    to: &Path,
+    mut reserve: impl FnMut(&Path) -> io::Result<()>,
+) -> io::Result<PathBuf> {
+    for attempt in 0..MAX_STAGING_ATTEMPTS {
+        let staged = staging_path(to, attempt);
+        match reserve(&staged) {
+            Ok(()) => return Ok(staged),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        "no free staging name next to the destination",
+    ))
+}
+
+/// 把 `from` 拷到 `to` 旁边一个独占创建的临时名下并返回它；拷贝中途失败时只清理自己创建的临时项。
+fn stage_copy(from: &Path, to: &Path) -> io::Result<PathBuf> {
+    let from_meta = std::fs::symlink_metadata(from)?;
+    let staged = reserve_staging(to, |staged| {
+        if from_meta.file_type().is_symlink() {
+            copy_symlink(from, staged)
+        } else if from_meta.is_dir() {
+            std::fs::create_dir(staged)
+        } else {
+            std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(staged)
+                .map(|_| ())
+        }
+    })?;
+
+    let copied = if from_meta.file_type().is_symlink() {
+        Ok(())
+    } else if from_meta.is_dir() {
+        copy_dir_contents(from, &staged)
+    } else {
+        std::fs::copy(from, &staged).map(|_| ())
+    };
+    match copied {
+        Ok(()) => Ok(staged),
+        Err(e) => {
+            let _ = remove_existing_path(&staged);
+            Err(e)
+        }
+    }
+}
+
+fn copy_dir_recursive(from: &Path, to: &Path) -> io::Result<()> {
+    std::fs::create_dir(to)?;
+    copy_dir_contents(from, to)
+}
+
+fn copy_dir_contents(from: &Path, to: &Path) -> io::Result<()> {
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let from_child = entry.path();
+        let to_child = to.join(entry.file_name());
+        let meta = std::fs::symlink_metadata(&from_child)?;
+
+        if meta.file_type().is_symlink() {
+            copy_symlink(&from_child, &to_child)?;
+        } else if meta.is_dir() {
+            copy_dir_recursive(&from_child, &to_child)?;
+        } else {
+            std::fs::copy(&from_child, &to_child)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    let target = std::fs::read_link(from)?;
+    std::os::unix::fs::symlink(target, to)
+}
+
+#[cfg(windows)]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    let target = std::fs::read_link(from)?;
+    match std::fs::metadata(from) {
+        Ok(meta) if meta.is_dir() => std::os::windows::fs::symlink_dir(target, to),
+        _ => std::os::windows::fs::symlink_file(target, to),
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn copy_symlink(_from: &Path, _to: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "symlinks are not supported on this platform",
+    ))
+}
+
+#[cfg(test)]
+#[path = "../../../../../tests/unit/kernel/services/adapters/file/local.rs"]
+mod tests;
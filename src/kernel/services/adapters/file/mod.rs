@@ -0,0 +1,3 @@
+//! 本地文件系统操作。
+
+pub mod local;
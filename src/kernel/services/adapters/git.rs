@@ -0,0 +1,207 @@
+//! Git adapter：通过 `git` 子进程读写仓库状态（阻塞调用，由 `AsyncRuntime` 放到阻塞线程池）。
+
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::kernel::{GitBlameLine, GitBranch, GitStash};
+
+/// 完整 ref 名、`*`（是否为当前 HEAD）。
+const BRANCH_LIST_FORMAT: &str = "--format=%(refname)%1f%(HEAD)";
+
+/// `%gd` → `stash@{N}`，`%ci` → ISO 提交时间，`%gs` → reflog 主题（即 stash 消息）。
+const STASH_LIST_FORMAT: &str = "--format=%gd%x1f%ci%x1f%gs";
+
+pub fn stash_list(root: &Path) -> io::Result<Vec<GitStash>> {
+    let output = run_git(root, &["stash", "list", STASH_LIST_FORMAT])?;
+    Ok(parse_stash_list(&output))
+}
+
+pub fn stash_push(root: &Path, include_untracked: bool) -> io::Result<()> {
+    let mut args = vec!["stash", "push"];
+    if include_untracked {
+        args.push("--include-untracked");
+    }
+    run_git(root, &args).map(|_| ())
+}
+
+pub fn stash_apply(root: &Path, index: usize) -> io::Result<()> {
+    run_git(root, &["stash", "apply", &stash_ref(index)]).map(|_| ())
+}
+
+pub fn stash_drop(root: &Path, index: usize) -> io::Result<()> {
+    run_git(root, &["stash", "drop", &stash_ref(index)]).map(|_| ())
+}
+
+/// 本地分支在前，远程跟踪分支在后。
+pub fn list_branches(root: &Path) -> io::Result<Vec<GitBranch>> {
+    let output = run_git(
+        root,
+        &[
+            "for-each-ref",
+            BRANCH_LIST_FORMAT,
+            "refs/heads",
+            "refs/remotes",
+        ],
+    )?;
+    Ok(parse_branch_list(&output))
+}
+
+/// 切到 `branch`；`create` 时以当前 HEAD 新建。远程分支传去掉远程名后的短名
+/// （见 `GitBranch::local_name`），由 git 自动建立同名跟踪分支。
+pub fn checkout(root: &Path, branch: &str, create: bool) -> io::Result<()> {
+    let mut args = vec!["checkout"];
+    if create {
+        args.push("-b");
+    }
+    args.push(branch);
+    run_git(root, &args).map(|_| ())
+}
+
+/// 单行 blame（`line` 为 0 基）。文件不在仓库里或该行不存在时返回错误。
+pub fn blame_line(path: &Path, line: usize) -> io::Result<GitBlameLine> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let range = format!("{0},{0}", line + 1);
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["blame", "--porcelain", "-L", &range, "--"])
+        .arg(path)
+        .output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::other(stderr.trim().to_string()));
+    }
+    parse_blame_porcelain(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| io::Error::other("unexpected git blame output"))
+}
+
+/// 文件在暂存区里的内容（没有暂存改动时即 HEAD 版本）。未跟踪的文件返回错误。
+pub fn index_content(path: &Path) -> io::Result<String> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::other("path has no file name"))?;
+    run_git(dir, &["show", &format!(":./{}", name.to_string_lossy())])
+}
+
+/// 把只含一个改动块的补丁正文（见 `kernel::git::diff::hunk_patch_body`）写进暂存区，工作区不动。
+pub fn stage_hunk(path: &Path, body: &str) -> io::Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::other("path has no file name"))?;
+    let toplevel = run_git(dir, &["rev-parse", "--show-toplevel"])?;
+    let prefix = run_git(dir, &["rev-parse", "--show-prefix"])?;
+    let rel = format!("{}{}", prefix.trim_end(), name.to_string_lossy());
+    let patch = format!("diff --git a/{rel} b/{rel}\n--- a/{rel}\n+++ b/{rel}\n{body}");
+
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(toplevel.trim_end())
+        .args(["apply", "--cached", "--unidiff-zero", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(patch.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::other(stderr.trim().to_string()));
+    }
+    Ok(())
+}
+
+fn stash_ref(index: usize) -> String {
+    format!("stash@{{{index}}}")
+}
+
+fn run_git(root: &Path, args: &[&str]) -> io::Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(args)
+        .output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::other(stderr.trim().to_string()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+pub(crate) fn parse_stash_list(output: &str) -> Vec<GitStash> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\x1f');
+            let index = fields
+                .next()?
+                .strip_prefix("stash@{")?
+                .strip_suffix('}')?
+                .parse()
+                .ok()?;
+            let datetime = fields.next()?.to_string();
+            let message = fields.next().unwrap_or_default().to_string();
+            Some(GitStash {
+                index,
+                message,
+                datetime,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn parse_branch_list(output: &str) -> Vec<GitBranch> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (refname, head) = line.split_once('\x1f')?;
+            let (name, remote) = if let Some(name) = refname.strip_prefix("refs/heads/") {
+                (name, false)
+            } else {
+                // `refs/remotes/origin/HEAD` 只是指向默认分支的符号引用。
+                let name = refname.strip_prefix("refs/remotes/")?;
+                if name.ends_with("/HEAD") {
+                    return None;
+                }
+                (name, true)
+            };
+            Some(GitBranch {
+                name: name.to_string(),
+                remote,
+                current: head == "*",
+            })
+        })
+        .collect()
+}
+
+/// 首行是 `<sha> <orig-line> <final-line> <count>`，随后是 `key value` 头部，
+/// 以 Tab 开头的源码行结束。
+pub(crate) fn parse_blame_porcelain(output: &str) -> Option<GitBlameLine> {
+    let mut lines = output.lines();
+    let commit = lines.next()?.split(' ').next()?.to_string();
+    if commit.is_empty() {
+        return None;
+    }
+    let mut author = None;
+    let mut timestamp = None;
+    for line in lines.take_while(|line| !line.starts_with('\t')) {
+        if let Some(value) = line.strip_prefix("author ") {
+            author = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("author-time ") {
+            timestamp = value.parse().ok();
+        }
+    }
+    Some(GitBlameLine {
+        commit,
+        author: author?,
+        timestamp: timestamp?,
+    })
+}
+
+#[cfg(test)]
+#[path = "../../../../tests/unit/kernel/services/adapters/git.rs"]
+mod tests;
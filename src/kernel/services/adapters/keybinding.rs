@@ -4,6 +4,8 @@ use crate::core::event::Key;
 use crate::core::event::{KeyCode, KeyModifiers};
 use crate::core::Command;
 use crate::core::Service;
+use crate::kernel::services::adapters::settings::{format_keybinding, parse_keybinding};
+use crate::kernel::services::ports::settings::KeybindingRule;
 use rustc_hash::FxHashMap;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -14,6 +16,8 @@ pub enum KeybindingContext {
     SidebarExplorer,
     CommandLine,
     Overlay,
+    /// 编辑区处于 Vim Normal / Visual 模式；未命中时回退到 Editor、Global。
+    Vim,
 }
 
 impl KeybindingContext {
@@ -26,9 +30,33 @@ impl KeybindingContext {
             "explorer" | "sidebarexplorer" | "sidebar.explorer" => Some(Self::SidebarExplorer),
             "commandline" | "command_line" | "cmdline" => Some(Self::CommandLine),
             "overlay" => Some(Self::Overlay),
+            "vim" | "editor.vim" => Some(Self::Vim),
             _ => None,
         }
     }
+
+    /// `parse` 的规范写法，用于导出。
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Global => "global",
+            Self::Editor => "editor",
+            Self::EditorSearchBar => "searchBar",
+            Self::SidebarExplorer => "explorer",
+            Self::CommandLine => "commandLine",
+            Self::Overlay => "overlay",
+            Self::Vim => "vim",
+        }
+    }
+
+    const ALL: [Self; 7] = [
+        Self::Global,
+        Self::Editor,
+        Self::EditorSearchBar,
+        Self::SidebarExplorer,
+        Self::CommandLine,
+        Self::Overlay,
+        Self::Vim,
+    ];
 }
 
 pub struct KeybindingService {
@@ -38,6 +66,7 @@ pub struct KeybindingService {
     sidebar_explorer: FxHashMap<Key, Command>,
     command_line: FxHashMap<Key, Command>,
     overlay: FxHashMap<Key, Command>,
+    vim: FxHashMap<Key, Command>,
 }
 
 impl KeybindingService {
@@ -53,6 +82,7 @@ impl KeybindingService {
             sidebar_explorer: default_sidebar_explorer_keybindings(),
             command_line: default_command_line_keybindings(),
             overlay: default_overlay_keybindings(),
+            vim: default_vim_keybindings(),
         }
     }
 
@@ -73,6 +103,11 @@ impl KeybindingService {
                 self.command_line.get(key).or_else(|| self.global.get(key))
             }
             KeybindingContext::Overlay => self.overlay.get(key).or_else(|| self.global.get(key)),
+            KeybindingContext::Vim => self
+                .vim
+                .get(key)
+                .or_else(|| self.editor.get(key))
+                .or_else(|| self.global.get(key)),
         }
     }
 
@@ -84,6 +119,7 @@ impl KeybindingService {
             KeybindingContext::SidebarExplorer => &self.sidebar_explorer,
             KeybindingContext::CommandLine => &self.command_line,
             KeybindingContext::Overlay => &self.overlay,
+            KeybindingContext::Vim => &self.vim,
         }
     }
 
@@ -95,6 +131,107 @@ impl KeybindingService {
         self.map_mut(context).remove(key)
     }
 
+    /// 把当前绑定表还原成 settings 里的规则格式。按上下文、按键排序，输出稳定可 diff；
+    /// 名字无法往返的命令（如 `insertChar`）不导出。
+    pub fn export(&self) -> Vec<KeybindingRule> {
+        let mut rules = Vec::new();
+        for context in KeybindingContext::ALL {
+            let mut entries = self
+                .bindings(context)
+                .iter()
+                .filter(|(_, command)| Command::from_name(command.name()) == **command)
+                .map(|(key, command)| (format_keybinding(key), command.name().to_string()))
+                .collect::<Vec<_>>();
+            entries.sort();
+            rules.extend(entries.into_iter().map(|(key, command)| KeybindingRule {
+                key,
+                command,
+                context: (context != KeybindingContext::Global).then(|| context.name().to_string()),
+            }));
+        }
+        rules
+    }
+
+    /// 按 settings 的语义叠加规则：`command` 为空表示解绑，未知上下文归入 Global，
+    /// 无法解析的按键忽略。
+    pub fn import(&mut self, rules: &[KeybindingRule]) {
+        for rule in rules {
+            let Some(key) = parse_keybinding(&rule.key) else {
+                continue;
+            };
+            let context = rule
+                .context
+                .as_deref()
+                .and_then(KeybindingContext::parse)
+                .unwrap_or(KeybindingContext::Global);
+            if rule.command.trim().is_empty() {
+                let _ = self.unbind(context, &key);
+            } else {
+                self.bind(context, key, Command::from_name(&rule.command));
+            }
+        }
+    }
+
+    /// 与默认绑定不同的部分，格式同 `export`：改绑 / 新增的键写出命令，被解绑的默认键写出空命令。
+    /// 用它回写 settings 时只记录用户的改动，以后默认键位调整仍能生效。
+    pub fn non_default_rules(&self) -> Vec<KeybindingRule> {
+        let defaults = Self::with_defaults();
+        let mut rules = Vec::new();
+        for context in KeybindingContext::ALL {
+            let current = self.bindings(context);
+            let default = defaults.bindings(context);
+            let mut entries = current
+                .iter()
+                .filter(|(key, command)| default.get(*key) != Some(*command))
+                .filter(|(_, command)| Command::from_name(command.name()) == **command)
+                .map(|(key, command)| (format_keybinding(key), command.name().to_string()))
+                .chain(
+                    default
+                        .keys()
+                        .filter(|key| !current.contains_key(*key))
+                        .map(|key| (format_keybinding(key), String::new())),
+                )
+                .collect::<Vec<_>>();
+            entries.sort();
+            rules.extend(entries.into_iter().map(|(key, command)| KeybindingRule {
+                key,
+                command,
+                context: (context != KeybindingContext::Global).then(|| context.name().to_string()),
+            }));
+        }
+        rules
+    }
+
+    /// `non_default_rules` 的 JSON 形式，可直接粘进另一台机器 settings 的 `keybindings` 数组。
+    pub fn export_to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.non_default_rules()).unwrap_or_else(|_| "[]".into())
+    }
+
+    /// 解析 `export_to_json` 格式的规则并叠加到当前绑定上。无法解析的按键、不认识的命令名
+    /// 跳过不绑，逐条返回提示。
+    pub fn import_from_json(&mut self, json: &str) -> Result<Vec<String>, serde_json::Error> {
+        let rules: Vec<KeybindingRule> = serde_json::from_str(json)?;
+        let mut warnings = Vec::new();
+        let mut accepted = Vec::with_capacity(rules.len());
+        for rule in rules {
+            if parse_keybinding(&rule.key).is_none() {
+                warnings.push(format!("unrecognized key `{}`", rule.key));
+                continue;
+            }
+            let command = rule.command.trim();
+            if !command.is_empty() && matches!(Command::from_name(command), Command::Custom(_)) {
+                warnings.push(format!(
+                    "unrecognized command `{command}` for key `{}`",
+                    rule.key
+                ));
+                continue;
+            }
+            accepted.push(rule);
+        }
+        self.import(&accepted);
+        Ok(warnings)
+    }
+
     fn map_mut(&mut self, context: KeybindingContext) -> &mut FxHashMap<Key, Command> {
         match context {
             KeybindingContext::Global => &mut self.global,
@@ -103,6 +240,7 @@ impl KeybindingService {
             KeybindingContext::SidebarExplorer => &mut self.sidebar_explorer,
             KeybindingContext::CommandLine => &mut self.command_line,
             KeybindingContext::Overlay => &mut self.overlay,
+            KeybindingContext::Vim => &mut self.vim,
         }
     }
 }
@@ -127,7 +265,12 @@ fn default_global_keybindings() -> FxHashMap<Key, Command> {
 
     bindings.insert(Key::ctrl(KeyCode::Char('q')), Command::Quit);
     bindings.insert(Key::ctrl(KeyCode::Char('s')), Command::Save);
+    bindings.insert(Key::simple(KeyCode::F(5)), Command::ReloadFromDisk);
     bindings.insert(Key::ctrl(KeyCode::Char('w')), Command::CloseTab);
+    bindings.insert(
+        Key::ctrl_shift(KeyCode::Char('t')),
+        Command::ReopenClosedTab,
+    );
     bindings.insert(Key::ctrl(KeyCode::Tab), Command::NextTab);
     bindings.insert(Key::ctrl_shift(KeyCode::Tab), Command::PrevTab);
 
@@ -143,7 +286,6 @@ fn default_global_keybindings() -> FxHashMap<Key, Command> {
     bindings.insert(Key::ctrl(KeyCode::Char('h')), Command::Replace);
     bindings.insert(Key::simple(KeyCode::F(3)), Command::FindNext);
     bindings.insert(Key::shift(KeyCode::F(3)), Command::FindPrev);
-    bindings.insert(Key::ctrl(KeyCode::Char('g')), Command::FindNext);
     bindings.insert(Key::ctrl_shift(KeyCode::Char('g')), Command::FindPrev);
 
     bindings.insert(Key::simple(KeyCode::F(1)), Command::OpenCommandLine);
@@ -151,11 +293,16 @@ fn default_global_keybindings() -> FxHashMap<Key, Command> {
         Key::ctrl_shift(KeyCode::Char('p')),
         Command::OpenCommandLine,
     );
+    bindings.insert(Key::ctrl(KeyCode::Char('p')), Command::QuickOpen);
     bindings.insert(Key::ctrl(KeyCode::Char('b')), Command::ToggleSidebar);
     bindings.insert(Key::ctrl_shift(KeyCode::Char('e')), Command::FocusExplorer);
     bindings.insert(Key::ctrl_shift(KeyCode::Char('f')), Command::FocusSearch);
     bindings.insert(Key::ctrl(KeyCode::Char('j')), Command::OpenDiagnostics);
     bindings.insert(Key::ctrl(KeyCode::Char(',')), Command::OpenSettings);
+    bindings.insert(
+        Key::ctrl(KeyCode::Char('`')),
+        Command::OpenTerminalAtCurrentFilePath,
+    );
 
     bindings
 }
@@ -174,6 +321,7 @@ fn default_editor_keybindings() -> FxHashMap<Key, Command> {
     bindings.insert(Key::ctrl(KeyCode::End), Command::CursorFileEnd);
     bindings.insert(Key::ctrl(KeyCode::Left), Command::CursorWordLeft);
     bindings.insert(Key::ctrl(KeyCode::Right), Command::CursorWordRight);
+    bindings.insert(Key::ctrl(KeyCode::Char('g')), Command::GotoLine);
 
     bindings.insert(Key::simple(KeyCode::Enter), Command::InsertNewline);
     bindings.insert(Key::simple(KeyCode::Tab), Command::InsertTab);
@@ -186,6 +334,22 @@ fn default_editor_keybindings() -> FxHashMap<Key, Command> {
     bindings.insert(Key::ctrl(KeyCode::Char('d')), Command::AddCursorAtNextMatch);
     bindings.insert(Key::ctrl_shift(KeyCode::Char('k')), Command::DeleteLine);
     bindings.insert(Key::ctrl(KeyCode::Char('k')), Command::DeleteToLineEnd);
+    bindings.insert(
+        Key::ctrl_shift(KeyCode::Char('v')),
+        Command::ClipboardHistory,
+    );
+    bindings.insert(Key::alt(KeyCode::Char('y')), Command::PastePrevious);
+    bindings.insert(
+        Key::new(KeyCode::Char('y'), KeyModifiers::SHIFT | KeyModifiers::ALT),
+        Command::PasteNext,
+    );
+    bindings.insert(
+        Key::new(KeyCode::Down, KeyModifiers::SHIFT | KeyModifiers::ALT),
+        Command::DuplicateLine,
+    );
+    bindings.insert(Key::ctrl(KeyCode::Char('/')), Command::ToggleLineComment);
+    // 传统终端把 Ctrl+/ 编码成 0x1F，crossterm 报告为 Ctrl+7。
+    bindings.insert(Key::ctrl(KeyCode::Char('7')), Command::ToggleLineComment);
     bindings.insert(
         Key::new(KeyCode::Up, KeyModifiers::CONTROL | KeyModifiers::ALT),
         Command::AddCursorAbove,
@@ -200,9 +364,19 @@ fn default_editor_keybindings() -> FxHashMap<Key, Command> {
     );
     bindings.insert(Key::simple(KeyCode::F(2)), Command::LspHover);
     bindings.insert(Key::simple(KeyCode::F(12)), Command::LspDefinition);
+    bindings.insert(Key::ctrl(KeyCode::F(12)), Command::LspTypeDefinition);
+    bindings.insert(Key::ctrl_shift(KeyCode::F(12)), Command::LspImplementation);
     bindings.insert(Key::shift(KeyCode::F(12)), Command::LspReferences);
+    bindings.insert(
+        Key::new(KeyCode::Char('h'), KeyModifiers::SHIFT | KeyModifiers::ALT),
+        Command::LspCallHierarchy,
+    );
+    bindings.insert(
+        Key::new(KeyCode::Char('o'), KeyModifiers::SHIFT | KeyModifiers::ALT),
+        Command::LspCallHierarchyOutgoing,
+    );
     bindings.insert(Key::alt(KeyCode::Enter), Command::LspCodeAction);
-    bindings.insert(Key::ctrl(KeyCode::Char('.')), Command::LspCompletion);
+    bindings.insert(Key::ctrl(KeyCode::Char('.')), Command::EditorContextMenu);
     bindings.insert(Key::ctrl(KeyCode::Char(' ')), Command::LspCompletion);
     bindings.insert(Key::ctrl_shift(KeyCode::Char('r')), Command::LspRename);
     bindings.insert(
@@ -240,6 +414,8 @@ fn default_editor_search_bar_keybindings() -> FxHashMap<Key, Command> {
 
     bindings.insert(Key::simple(KeyCode::Enter), Command::FindNext);
     bindings.insert(Key::shift(KeyCode::Enter), Command::FindPrev);
+    // 编辑器里 Ctrl+G 是跳转行；搜索栏打开时仍是查找下一个。
+    bindings.insert(Key::ctrl(KeyCode::Char('g')), Command::FindNext);
     bindings.insert(
         Key::ctrl(KeyCode::Enter),
         Command::EditorSearchBarReplaceCurrent,
@@ -260,6 +436,10 @@ fn default_editor_search_bar_keybindings() -> FxHashMap<Key, Command> {
         Key::alt(KeyCode::Char('x')),
         Command::EditorSearchBarToggleRegex,
     );
+    bindings.insert(
+        Key::alt(KeyCode::Char('w')),
+        Command::EditorSearchBarToggleWholeWord,
+    );
     bindings.insert(
         Key::alt(KeyCode::Char('r')),
         Command::EditorSearchBarToggleReplaceMode,
@@ -319,6 +499,8 @@ fn default_command_line_keybindings() -> FxHashMap<Key, Command> {
     bindings.insert(Key::simple(KeyCode::Up), Command::CommandLineMoveUp);
     bindings.insert(Key::simple(KeyCode::Down), Command::CommandLineMoveDown);
     bindings.insert(Key::simple(KeyCode::Enter), Command::CommandLineConfirm);
+    bindings.insert(Key::alt(KeyCode::Up), Command::CommandLineHistoryPrev);
+    bindings.insert(Key::alt(KeyCode::Down), Command::CommandLineHistoryNext);
 
     bindings
 }
@@ -343,6 +525,61 @@ fn default_overlay_keybindings() -> FxHashMap<Key, Command> {
         Key::simple(KeyCode::PageDown),
         Command::SearchResultsScrollDown,
     );
+    // 与编辑器内搜索栏的替换键位保持一致。
+    bindings.insert(
+        Key::alt(KeyCode::Char('r')),
+        Command::GlobalSearchToggleReplace,
+    );
+    bindings.insert(
+        Key::alt(KeyCode::Char('f')),
+        Command::GlobalSearchToggleFilters,
+    );
+    bindings.insert(Key::simple(KeyCode::Tab), Command::GlobalSearchSwitchField);
+    bindings.insert(
+        Key::new(KeyCode::Enter, KeyModifiers::CONTROL | KeyModifiers::SHIFT),
+        Command::GlobalSearchReplaceAll,
+    );
+
+    bindings
+}
+
+/// Normal / Visual 模式的单键命令。`gg`、`dd`、`dw` 这类双键序列由 reducer 按
+/// `VimPending` 组合，这里只绑第一个键。
+fn default_vim_keybindings() -> FxHashMap<Key, Command> {
+    let mut bindings = FxHashMap::default();
+    bindings.reserve(32);
+
+    bindings.insert(Key::simple(KeyCode::Char('h')), Command::CursorLeft);
+    bindings.insert(Key::simple(KeyCode::Char('j')), Command::CursorDown);
+    bindings.insert(Key::simple(KeyCode::Char('k')), Command::CursorUp);
+    bindings.insert(Key::simple(KeyCode::Char('l')), Command::CursorRight);
+    bindings.insert(Key::simple(KeyCode::Backspace), Command::CursorLeft);
+    bindings.insert(Key::simple(KeyCode::Enter), Command::CursorDown);
+    bindings.insert(Key::simple(KeyCode::Char('w')), Command::CursorWordRight);
+    bindings.insert(Key::simple(KeyCode::Char('b')), Command::CursorWordLeft);
+    bindings.insert(Key::simple(KeyCode::Char('e')), Command::VimWordEnd);
+    bindings.insert(Key::simple(KeyCode::Char('0')), Command::CursorLineStart);
+    // 不同终端报告 `$` 时可能带也可能不带 SHIFT。
+    bindings.insert(Key::simple(KeyCode::Char('$')), Command::CursorLineEnd);
+    bindings.insert(Key::shift(KeyCode::Char('$')), Command::CursorLineEnd);
+    bindings.insert(Key::simple(KeyCode::Char('g')), Command::VimGoto);
+    bindings.insert(Key::shift(KeyCode::Char('g')), Command::CursorFileEnd);
+
+    bindings.insert(Key::simple(KeyCode::Char('i')), Command::VimInsert);
+    bindings.insert(Key::simple(KeyCode::Char('a')), Command::VimAppend);
+    bindings.insert(Key::simple(KeyCode::Char('o')), Command::VimOpenLineBelow);
+    bindings.insert(Key::shift(KeyCode::Char('o')), Command::VimOpenLineAbove);
+    bindings.insert(Key::simple(KeyCode::Char('v')), Command::VimVisual);
+    bindings.insert(Key::shift(KeyCode::Char('v')), Command::VimVisualLine);
+
+    bindings.insert(Key::simple(KeyCode::Char('d')), Command::VimDelete);
+    bindings.insert(Key::simple(KeyCode::Char('c')), Command::VimChange);
+    bindings.insert(Key::simple(KeyCode::Char('y')), Command::VimYank);
+    bindings.insert(Key::simple(KeyCode::Char('u')), Command::Undo);
+    bindings.insert(Key::simple(KeyCode::Char('p')), Command::Paste);
+    // `:` 只接行号跳转；同样兼容带 / 不带 SHIFT 的上报。
+    bindings.insert(Key::simple(KeyCode::Char(':')), Command::GotoLine);
+    bindings.insert(Key::shift(KeyCode::Char(':')), Command::GotoLine);
 
     bindings
 }
@@ -1,10 +1,12 @@
+use crate::kernel::panel::locations::LocationItem;
 use crate::kernel::panel::problems::{ProblemItem, ProblemRange, ProblemSeverity};
 use crate::kernel::panel::symbols::SymbolItem;
 use crate::kernel::services::ports::{
-    LspCodeAction, LspCommand, LspCompletionItem, LspHoverBlock, LspHoverPayload, LspInlayHint,
-    LspInsertTextFormat, LspMarkup, LspPosition, LspPositionEncoding, LspRange, LspResourceOp,
-    LspServerCapabilities, LspSignatureHelpPayload, LspSignatureInfo, LspSignatureParameter,
-    LspSignatureParameterLabel, LspTextChange, LspTextEdit, LspWorkspaceEdit, LspWorkspaceFileEdit,
+    LspCallHierarchyItem, LspCodeAction, LspCommand, LspCompletionItem, LspCompletionLabelDetails,
+    LspDocumentLink, LspHoverBlock, LspHoverPayload, LspInlayHint, LspInsertTextFormat, LspMarkup,
+    LspPosition, LspPositionEncoding, LspRange, LspResourceOp, LspServerCapabilities,
+    LspSignatureHelpPayload, LspSignatureInfo, LspSignatureParameter, LspSignatureParameterLabel,
+    LspTextChange, LspTextEdit, LspWorkspaceEdit, LspWorkspaceFileEdit,
 };
 use rustc_hash::FxHashMap;
 use serde_json::Value;
@@ -39,6 +41,22 @@ pub(super) fn server_capabilities_from_lsp(
         }
     }
 
+    fn type_definition(v: &Option<lsp_types::TypeDefinitionProviderCapability>) -> bool {
+        match v {
+            Some(lsp_types::TypeDefinitionProviderCapability::Simple(enabled)) => *enabled,
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    fn implementation(v: &Option<lsp_types::ImplementationProviderCapability>) -> bool {
+        match v {
+            Some(lsp_types::ImplementationProviderCapability::Simple(enabled)) => *enabled,
+            Some(_) => true,
+            None => false,
+        }
+    }
+
     fn folding(v: &Option<lsp_types::FoldingRangeProviderCapability>) -> bool {
         match v {
             Some(lsp_types::FoldingRangeProviderCapability::Simple(enabled)) => *enabled,
@@ -47,6 +65,21 @@ pub(super) fn server_capabilities_from_lsp(
         }
     }
 
+    fn call_hierarchy(v: &Option<lsp_types::CallHierarchyServerCapability>) -> bool {
+        match v {
+            Some(lsp_types::CallHierarchyServerCapability::Simple(enabled)) => *enabled,
+            Some(lsp_types::CallHierarchyServerCapability::Options(_)) => true,
+            None => false,
+        }
+    }
+
+    fn prepare_rename(v: &Option<lsp_types::OneOf<bool, lsp_types::RenameOptions>>) -> bool {
+        match v {
+            Some(lsp_types::OneOf::Right(options)) => options.prepare_provider.unwrap_or(false),
+            _ => false,
+        }
+    }
+
     fn triggers(v: &Option<Vec<String>>) -> Vec<char> {
         let mut out = Vec::new();
         let Some(v) = v else {
@@ -99,17 +132,28 @@ pub(super) fn server_capabilities_from_lsp(
         position_encoding: encoding,
         hover: hover(&caps.hover_provider),
         definition: one_of_bool(&caps.definition_provider),
+        type_definition: type_definition(&caps.type_definition_provider),
+        implementation: implementation(&caps.implementation_provider),
         references: one_of_bool(&caps.references_provider),
+        document_highlight: one_of_bool(&caps.document_highlight_provider),
         document_symbols: one_of_bool(&caps.document_symbol_provider),
         workspace_symbols: one_of_bool(&caps.workspace_symbol_provider),
         code_action: code_action(&caps.code_action_provider),
         completion: caps.completion_provider.is_some(),
         signature_help: caps.signature_help_provider.is_some(),
         rename: one_of_bool(&caps.rename_provider),
+        prepare_rename: prepare_rename(&caps.rename_provider),
         format: one_of_bool(&caps.document_formatting_provider),
         range_format: one_of_bool(&caps.document_range_formatting_provider),
         inlay_hints: one_of_bool(&caps.inlay_hint_provider),
         folding_range: folding(&caps.folding_range_provider),
+        document_link: caps.document_link_provider.is_some(),
+        document_link_resolve: caps
+            .document_link_provider
+            .as_ref()
+            .and_then(|p| p.resolve_provider)
+            .unwrap_or(false),
+        call_hierarchy: call_hierarchy(&caps.call_hierarchy_provider),
         completion_resolve,
         completion_triggers,
         signature_help_triggers,
@@ -491,6 +535,29 @@ pub(super) fn definition_location(
         .map(|target| (target.path, target.anchor_line, target.anchor_column))
 }
 
+/// 跳转类响应里的全部位置（`definition_location` 只取第一个）。
+pub(super) fn definition_locations(resp: lsp_types::GotoDefinitionResponse) -> Vec<LocationItem> {
+    let targets: Vec<DefinitionPreviewTarget> = match resp {
+        lsp_types::GotoDefinitionResponse::Scalar(loc) => {
+            preview_from_location(&loc).into_iter().collect()
+        }
+        lsp_types::GotoDefinitionResponse::Array(locs) => {
+            locs.iter().filter_map(preview_from_location).collect()
+        }
+        lsp_types::GotoDefinitionResponse::Link(links) => {
+            links.iter().filter_map(preview_from_link).collect()
+        }
+    };
+    targets
+        .into_iter()
+        .map(|target| LocationItem {
+            path: target.path,
+            line: target.anchor_line,
+            column: target.anchor_column,
+        })
+        .collect()
+}
+
 pub(super) fn completion_item_kind_u32(kind: lsp_types::CompletionItemKind) -> u32 {
     use lsp_types::CompletionItemKind as Kind;
 
@@ -575,6 +642,7 @@ pub(super) fn completion_items(
         }
 
         let detail = item.detail;
+        let label_details = item.label_details.and_then(label_details_from_lsp);
         let kind = item.kind.map(completion_item_kind_u32).filter(|k| *k != 0);
         let documentation = item
             .documentation
@@ -630,6 +698,7 @@ pub(super) fn completion_items(
             id,
             label,
             detail,
+            label_details,
             kind,
             documentation,
             insert_text,
@@ -651,6 +720,20 @@ pub(super) fn completion_items(
     (out, is_incomplete)
 }
 
+fn label_details_from_lsp(
+    details: lsp_types::CompletionItemLabelDetails,
+) -> Option<LspCompletionLabelDetails> {
+    let clean = |text: Option<String>| {
+        text.map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+    };
+    let details = LspCompletionLabelDetails {
+        detail: clean(details.detail),
+        description: clean(details.description),
+    };
+    (details != LspCompletionLabelDetails::default()).then_some(details)
+}
+
 pub(super) fn completion_item_kind_from_u32(kind: u32) -> Option<lsp_types::CompletionItemKind> {
     serde_json::from_value(Value::from(kind as i64)).ok()
 }
@@ -659,6 +742,12 @@ pub(super) fn completion_item_to_lsp(item: &LspCompletionItem) -> lsp_types::Com
     let mut out = lsp_types::CompletionItem {
         label: item.label.clone(),
         detail: item.detail.clone(),
+        label_details: item.label_details.as_ref().map(|details| {
+            lsp_types::CompletionItemLabelDetails {
+                detail: details.detail.clone(),
+                description: details.description.clone(),
+            }
+        }),
         kind: item.kind.and_then(completion_item_kind_from_u32),
         sort_text: item.sort_text.clone(),
         filter_text: item.filter_text.clone(),
@@ -934,6 +1023,30 @@ pub(super) fn text_change_event(
     }
 }
 
+pub(super) fn document_link_from_lsp(link: lsp_types::DocumentLink) -> LspDocumentLink {
+    LspDocumentLink {
+        range: range_from_lsp(link.range),
+        target: link.target.map(|url| url.to_string()),
+        data: link.data,
+    }
+}
+
+pub(super) fn call_hierarchy_item_from_lsp(
+    item: lsp_types::CallHierarchyItem,
+) -> Option<LspCallHierarchyItem> {
+    let path = item.uri.to_file_path().ok()?;
+    let raw = serde_json::to_value(&item).ok()?;
+    Some(LspCallHierarchyItem {
+        name: item.name,
+        detail: item.detail,
+        kind: symbol_kind_u32(item.kind),
+        path,
+        line: item.selection_range.start.line,
+        column: item.selection_range.start.character,
+        raw,
+    })
+}
+
 pub(super) fn range_from_lsp(range: lsp_types::Range) -> LspRange {
     LspRange {
         start: LspPosition {
@@ -956,13 +1069,17 @@ pub(super) fn path_to_url(path: &Path) -> Option<lsp_types::Url> {
 }
 
 pub(super) fn workspace_folders_for_root(root: &Path) -> Option<Vec<lsp_types::WorkspaceFolder>> {
-    let uri = path_to_url(root)?;
-    let name = root
+    Some(vec![workspace_folder_for_path(root)?])
+}
+
+pub(super) fn workspace_folder_for_path(path: &Path) -> Option<lsp_types::WorkspaceFolder> {
+    let uri = path_to_url(path)?;
+    let name = path
         .file_name()
         .and_then(|s| s.to_str())
         .unwrap_or("workspace")
         .to_string();
-    Some(vec![lsp_types::WorkspaceFolder { uri, name }])
+    Some(lsp_types::WorkspaceFolder { uri, name })
 }
 
 pub(super) fn client_capabilities() -> lsp_types::ClientCapabilities {
@@ -977,6 +1094,7 @@ pub(super) fn client_capabilities() -> lsp_types::ClientCapabilities {
     let completion = lsp_types::CompletionClientCapabilities {
         completion_item: Some(lsp_types::CompletionItemCapability {
             snippet_support: Some(true),
+            label_details_support: Some(true),
             ..Default::default()
         }),
         ..Default::default()
@@ -994,6 +1112,10 @@ pub(super) fn client_capabilities() -> lsp_types::ClientCapabilities {
         dynamic_registration: Some(false),
         link_support: Some(true),
     };
+    let type_definition = lsp_types::GotoCapability {
+        dynamic_registration: Some(false),
+        link_support: Some(true),
+    };
 
     let document_symbol = lsp_types::DocumentSymbolClientCapabilities {
         hierarchical_document_symbol_support: Some(true),
@@ -1005,6 +1127,21 @@ pub(super) fn client_capabilities() -> lsp_types::ClientCapabilities {
         dynamic_registration: Some(false),
         resolve_support: None,
     };
+    let document_link = lsp_types::DocumentLinkClientCapabilities {
+        dynamic_registration: Some(false),
+        tooltip_support: Some(false),
+    };
+    let call_hierarchy = lsp_types::CallHierarchyClientCapabilities {
+        dynamic_registration: Some(false),
+    };
+    let document_highlight = lsp_types::DocumentHighlightClientCapabilities {
+        dynamic_registration: Some(false),
+    };
+    let rename = lsp_types::RenameClientCapabilities {
+        dynamic_registration: Some(false),
+        prepare_support: Some(true),
+        ..Default::default()
+    };
     let general = lsp_types::GeneralClientCapabilities {
         position_encodings: Some(vec![
             lsp_types::PositionEncodingKind::UTF16,
@@ -1018,6 +1155,7 @@ pub(super) fn client_capabilities() -> lsp_types::ClientCapabilities {
         workspace: Some(lsp_types::WorkspaceClientCapabilities {
             apply_edit: Some(true),
             configuration: Some(true),
+            workspace_folders: Some(true),
             symbol: Some(workspace_symbol),
             ..Default::default()
         }),
@@ -1025,10 +1163,15 @@ pub(super) fn client_capabilities() -> lsp_types::ClientCapabilities {
             hover: Some(hover),
             definition: Some(definition),
             implementation: Some(implementation),
+            type_definition: Some(type_definition),
             completion: Some(completion),
             signature_help: Some(signature_help),
             document_symbol: Some(document_symbol),
             inlay_hint: Some(inlay_hint),
+            document_link: Some(document_link),
+            document_highlight: Some(document_highlight),
+            call_hierarchy: Some(call_hierarchy),
+            rename: Some(rename),
             ..Default::default()
         }),
         general: Some(general),
@@ -1,7 +1,7 @@
 use crate::core::Service;
 use crate::kernel::services::ports::{
-    LspCompletionItem, LspCompletionTriggerContext, LspPosition, LspRange, LspServerKind,
-    LspTextChange,
+    LspCallHierarchyItem, LspCompletionItem, LspCompletionTriggerContext, LspDocumentLink,
+    LspPosition, LspRange, LspServerKind, LspTextChange,
 };
 use crate::kernel::services::KernelServiceContext;
 use lsp_server::RequestId;
@@ -17,11 +17,14 @@ use crate::kernel::language::{LanguageId, LspLaunchContext, LspLaunchPlan};
 use crate::kernel::lsp_registry::language_root_for_file;
 
 mod convert;
+mod pool;
 mod process;
 mod requests;
 mod sync;
 mod wire;
 
+use convert::workspace_folders_for_root;
+use pool::{MultiRootClient, ServedRoots};
 use wire::{LspProcess, LspRequestKind};
 
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -31,6 +34,24 @@ pub(crate) struct LspServerCommandOverride {
     pub initialization_options: Option<Value>,
 }
 
+fn launch_command(
+    command_override: &Option<(String, Vec<String>, Option<Value>)>,
+) -> Option<(&str, &[String])> {
+    command_override
+        .as_ref()
+        .map(|(command, args, _)| (command.as_str(), args.as_slice()))
+}
+
+fn server_launch_commands(
+    overrides: &FxHashMap<LspServerKind, LspServerCommandOverride>,
+) -> FxHashMap<LspServerKind, (Option<&str>, Option<&[String]>)> {
+    overrides
+        .iter()
+        .map(|(kind, cfg)| (*kind, (cfg.command.as_deref(), cfg.args.as_deref())))
+        .filter(|(_, launch)| *launch != (None, None))
+        .collect()
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct HoverRequestOptions {
     pub include_definition_source: bool,
@@ -58,7 +79,9 @@ impl Default for HoverRequestOptions {
 #[cfg(test)]
 use std::time::Duration;
 #[cfg(test)]
-use wire::handle_response;
+use wire::{
+    handle_partial_result, handle_response, handle_server_request, workspace_symbols_partial_token,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct ClientKey {
@@ -69,21 +92,27 @@ struct ClientKey {
 /// LSP hub service: manages per-(language,root) LSP clients.
 pub struct LspService {
     workspace_root: PathBuf,
+    /// 多根工作区的全部文件夹，第 0 项是 `workspace_root`。
+    workspace_folders: Vec<PathBuf>,
     ctx: KernelServiceContext,
     command_override: Option<(String, Vec<String>, Option<Value>)>,
     server_command_overrides: FxHashMap<LspServerKind, LspServerCommandOverride>,
     clients: FxHashMap<ClientKey, LspClient>,
+    /// 支持多根的服务端每种只起一个进程，各 language root 都挂在它上面。
+    pooled: FxHashMap<LspServerKind, MultiRootClient>,
     warned_missing: FxHashSet<LspServerKind>,
 }
 
 impl LspService {
     pub fn new(workspace_root: PathBuf, ctx: KernelServiceContext) -> Self {
         Self {
+            workspace_folders: vec![workspace_root.clone()],
             workspace_root,
             ctx,
             command_override: None,
             server_command_overrides: FxHashMap::default(),
             clients: FxHashMap::default(),
+            pooled: FxHashMap::default(),
             warned_missing: FxHashSet::default(),
         }
     }
@@ -121,31 +150,46 @@ impl LspService {
         self
     }
 
+    /// 换上新的命令覆盖；只有启动命令 / 参数变了才关掉所有进程（之后按需重启），返回是否重启。
+    /// 仅 `initializationOptions` 变化时只记下新值，下次启动服务端时生效。
     pub(crate) fn reconfigure(
         &mut self,
         command_override: Option<(String, Vec<String>, Option<Value>)>,
         server_command_overrides: FxHashMap<LspServerKind, LspServerCommandOverride>,
     ) -> bool {
-        let changed = self.command_override != command_override
-            || self.server_command_overrides != server_command_overrides;
-        if !changed {
-            return false;
-        }
+        let launch_changed = launch_command(&self.command_override)
+            != launch_command(&command_override)
+            || server_launch_commands(&self.server_command_overrides)
+                != server_launch_commands(&server_command_overrides);
 
         self.command_override = command_override;
         self.server_command_overrides = server_command_overrides;
+        if !launch_changed {
+            return false;
+        }
 
-        for client in self.clients.values_mut() {
+        for client in self.all_clients_mut() {
             client.shutdown();
         }
         self.clients.clear();
+        self.pooled.clear();
         self.warned_missing.clear();
         true
     }
 
+    /// 包含 `path` 的最近（最深）工作区文件夹；都不包含时退回主工作区。
+    fn workspace_folder_for_path(&self, path: &Path) -> &Path {
+        self.workspace_folders
+            .iter()
+            .filter(|folder| path.starts_with(folder))
+            .max_by_key(|folder| folder.components().count())
+            .unwrap_or(&self.workspace_root)
+    }
+
     fn client_key_for_path(&self, path: &Path) -> Option<(LanguageId, ClientKey)> {
         let language = LanguageId::from_path(path)?;
-        let root = language_root_for_file(&self.workspace_root, language, path);
+        let folder = self.workspace_folder_for_path(path);
+        let root = language_root_for_file(folder, language, path);
         let server = adapter_for(Some(language)).features().lsp_server?;
         Some((language, ClientKey { server, root }))
     }
@@ -188,26 +232,51 @@ impl LspService {
         Some((command, args, initialization_options))
     }
 
+    fn all_clients_mut(&mut self) -> impl Iterator<Item = &mut LspClient> {
+        self.clients
+            .values_mut()
+            .chain(self.pooled.values_mut().map(MultiRootClient::client_mut))
+    }
+
+    fn spawn_client(&mut self, language: LanguageId, key: &ClientKey) -> Option<LspClient> {
+        let default_launch_plan = self.default_launch_plan(language, key);
+        let Some((command, args, initialization_options)) =
+            self.resolve_server_command(language, key)
+        else {
+            if self.warned_missing.insert(key.server) {
+                tracing::warn!(
+                    language = language.display_name(),
+                    hint = default_launch_plan.install_hint,
+                    "lsp server not found"
+                );
+            }
+            return None;
+        };
+
+        let mut client = LspClient::new(key.root.clone(), key.server, self.ctx.clone())
+            .with_command(command, args)
+            .with_initialization_options(initialization_options);
+        for folder in &self.workspace_folders[1..] {
+            client.add_workspace_folder(folder);
+        }
+        Some(client)
+    }
+
+    /// 支持多根的服务端优先复用已有进程，把新根挂上去；否则按 `(server, root)` 各起一个。
     fn client_for_path_mut(&mut self, path: &Path) -> Option<&mut LspClient> {
         let (language, key) = self.client_key_for_path(path)?;
+        if key.server.supports_multi_root() {
+            if !self.pooled.contains_key(&key.server) {
+                let client = self.spawn_client(language, &key)?;
+                self.pooled.insert(key.server, MultiRootClient::new(client));
+            }
+            let pooled = self.pooled.get_mut(&key.server)?;
+            pooled.attach_root(&key.root);
+            return Some(pooled.client_mut());
+        }
+
         if !self.clients.contains_key(&key) {
-            let default_launch_plan = self.default_launch_plan(language, &key);
-            let Some((command, args, initialization_options)) =
-                self.resolve_server_command(language, &key)
-            else {
-                if self.warned_missing.insert(key.server) {
-                    tracing::warn!(
-                        language = language.display_name(),
-                        hint = default_launch_plan.install_hint,
-                        "lsp server not found"
-                    );
-                }
-                return None;
-            };
-
-            let client = LspClient::new(key.root.clone(), key.server, self.ctx.clone())
-                .with_command(command, args)
-                .with_initialization_options(initialization_options);
+            let client = self.spawn_client(language, &key)?;
             self.clients.insert(key.clone(), client);
         }
 
@@ -247,7 +316,7 @@ impl LspService {
     }
 
     pub fn cancel_hover(&mut self) {
-        for client in self.clients.values_mut() {
+        for client in self.all_clients_mut() {
             client.cancel_hover();
         }
     }
@@ -271,6 +340,20 @@ impl LspService {
         client.request_definition(path, position);
     }
 
+    pub fn request_type_definition(&mut self, path: &Path, position: LspPosition) {
+        let Some(client) = self.client_for_path_mut(path) else {
+            return;
+        };
+        client.request_type_definition(path, position);
+    }
+
+    pub fn request_implementation(&mut self, path: &Path, position: LspPosition) {
+        let Some(client) = self.client_for_path_mut(path) else {
+            return;
+        };
+        client.request_implementation(path, position);
+    }
+
     pub fn request_references(&mut self, path: &Path, position: LspPosition) {
         let Some(client) = self.client_for_path_mut(path) else {
             return;
@@ -278,6 +361,36 @@ impl LspService {
         client.request_references(path, position);
     }
 
+    pub fn request_prepare_call_hierarchy(&mut self, path: &Path, position: LspPosition) {
+        let Some(client) = self.client_for_path_mut(path) else {
+            return;
+        };
+        client.request_prepare_call_hierarchy(path, position);
+    }
+
+    /// 按条目所在文件路由到对应的 client；条目可能来自尚未打开的文件。
+    pub fn request_call_hierarchy_incoming(
+        &mut self,
+        item_index: usize,
+        item: LspCallHierarchyItem,
+    ) {
+        let Some(client) = self.client_for_path_mut(&item.path) else {
+            return;
+        };
+        client.request_call_hierarchy_incoming(item_index, item);
+    }
+
+    pub fn request_call_hierarchy_outgoing(
+        &mut self,
+        item_index: usize,
+        item: LspCallHierarchyItem,
+    ) {
+        let Some(client) = self.client_for_path_mut(&item.path) else {
+            return;
+        };
+        client.request_call_hierarchy_outgoing(item_index, item);
+    }
+
     pub fn request_document_symbols(&mut self, path: &Path) {
         let Some(client) = self.client_for_path_mut(path) else {
             return;
@@ -286,7 +399,7 @@ impl LspService {
     }
 
     pub fn request_workspace_symbols(&mut self, query: String) {
-        for client in self.clients.values_mut() {
+        for client in self.all_clients_mut() {
             client.request_workspace_symbols(query.clone());
         }
     }
@@ -311,7 +424,7 @@ impl LspService {
     }
 
     pub fn request_completion_resolve(&mut self, item: LspCompletionItem) {
-        for client in self.clients.values_mut() {
+        for client in self.all_clients_mut() {
             client.request_completion_resolve(item.clone());
         }
     }
@@ -330,6 +443,32 @@ impl LspService {
         client.request_folding_range(path, version);
     }
 
+    pub fn request_document_link(&mut self, path: &Path, version: u64) {
+        let Some(client) = self.client_for_path_mut(path) else {
+            return;
+        };
+        client.request_document_link(path, version);
+    }
+
+    pub fn request_document_highlight(&mut self, path: &Path, position: LspPosition, version: u64) {
+        let Some(client) = self.client_for_path_mut(path) else {
+            return;
+        };
+        client.request_document_highlight(path, position, version);
+    }
+
+    pub fn request_document_link_resolve(
+        &mut self,
+        path: &Path,
+        version: u64,
+        link: LspDocumentLink,
+    ) {
+        let Some(client) = self.client_for_path_mut(path) else {
+            return;
+        };
+        client.request_document_link_resolve(path, version, link);
+    }
+
     pub fn request_signature_help(&mut self, path: &Path, position: LspPosition) {
         let Some(client) = self.client_for_path_mut(path) else {
             return;
@@ -337,6 +476,13 @@ impl LspService {
         client.request_signature_help(path, position);
     }
 
+    pub fn request_prepare_rename(&mut self, path: &Path, position: LspPosition) {
+        let Some(client) = self.client_for_path_mut(path) else {
+            return;
+        };
+        client.request_prepare_rename(path, position);
+    }
+
     pub fn request_rename(&mut self, path: &Path, position: LspPosition, new_name: String) {
         let Some(client) = self.client_for_path_mut(path) else {
             return;
@@ -358,17 +504,66 @@ impl LspService {
         client.request_range_format(path, range);
     }
 
+    /// 加入一个工作区文件夹并通知所有客户端（`workspace/didChangeWorkspaceFolders`）。
+    pub fn add_workspace_folder(&mut self, path: PathBuf) {
+        if self.workspace_folders.contains(&path) {
+            return;
+        }
+        for client in self.all_clients_mut() {
+            client.add_workspace_folder(&path);
+        }
+        self.workspace_folders.push(path);
+    }
+
+    /// 移除工作区文件夹：根落在该文件夹内的客户端直接关闭，其余客户端收到变更通知。
+    pub fn remove_workspace_folder(&mut self, path: &Path) {
+        if path == self.workspace_root {
+            return;
+        }
+        let Some(index) = self.workspace_folders.iter().position(|f| f == path) else {
+            return;
+        };
+        self.workspace_folders.remove(index);
+
+        self.clients.retain(|key, client| {
+            if key.root.starts_with(path) {
+                client.shutdown();
+                return false;
+            }
+            client.remove_workspace_folder(path);
+            true
+        });
+        self.pooled.retain(|_, pooled| {
+            if pooled.primary_root().starts_with(path) {
+                pooled.client_mut().shutdown();
+                return false;
+            }
+            pooled.detach_roots_under(path);
+            pooled.client_mut().remove_workspace_folder(path);
+            true
+        });
+    }
+
     pub fn execute_command(&mut self, command: String, arguments: Vec<serde_json::Value>) {
-        for client in self.clients.values_mut() {
+        for client in self.all_clients_mut() {
             client.execute_command(command.clone(), arguments.clone());
         }
     }
 
     pub fn shutdown(&mut self) {
-        for client in self.clients.values_mut() {
+        for client in self.all_clients_mut() {
             client.shutdown();
         }
     }
+
+    /// 检查崩溃的服务端并按退避重启；有进程被重新拉起时返回 true，调用方需重新打开文档。
+    pub fn poll_restarts(&mut self) -> bool {
+        let mut restarted = false;
+        for client in self.all_clients_mut() {
+            restarted |= client.poll_restart();
+        }
+        restarted
+    }
 }
 
 impl Service for LspService {
@@ -390,12 +585,17 @@ struct LspClient {
     restart_backoff_until: Option<Instant>,
     next_id: i32,
     doc_versions: FxHashMap<PathBuf, u64>,
+    served_roots: Arc<Mutex<ServedRoots>>,
+    /// 告知服务端的工作区文件夹（自身根 + 额外添加的文件夹），reader 线程据此应答
+    /// `workspace/workspaceFolders`。
+    workspace_folders: Arc<Mutex<Vec<lsp_types::WorkspaceFolder>>>,
     pending_requests: Arc<Mutex<FxHashMap<RequestId, LspRequestKind>>>,
     latest_hover: Arc<AtomicI32>,
     latest_hover_implementation: Arc<AtomicI32>,
     latest_hover_definition: Arc<AtomicI32>,
     latest_definition: Arc<AtomicI32>,
     latest_references: Arc<AtomicI32>,
+    latest_call_hierarchy: Arc<AtomicI32>,
     latest_document_symbols: Arc<AtomicI32>,
     latest_workspace_symbols: Arc<AtomicI32>,
     latest_code_action: Arc<AtomicI32>,
@@ -403,14 +603,23 @@ struct LspClient {
     latest_completion_resolve: Arc<AtomicI32>,
     latest_inlay_hints: Arc<AtomicI32>,
     latest_folding_range: Arc<AtomicI32>,
+    latest_document_link: Arc<AtomicI32>,
+    latest_document_link_resolve: Arc<AtomicI32>,
+    latest_document_highlight: Arc<AtomicI32>,
     latest_signature_help: Arc<AtomicI32>,
     latest_format: Arc<AtomicI32>,
+    latest_prepare_rename: Arc<AtomicI32>,
     latest_rename: Arc<AtomicI32>,
     latest_shutdown: Arc<AtomicI32>,
 }
 
 impl LspClient {
     fn new(root: PathBuf, server: LspServerKind, ctx: KernelServiceContext) -> Self {
+        let workspace_folders = workspace_folders_for_root(&root).unwrap_or_default();
+        let served_roots = ServedRoots {
+            roots: vec![root.clone()],
+            capabilities: None,
+        };
         Self {
             server,
             root,
@@ -424,12 +633,15 @@ impl LspClient {
             restart_backoff_until: None,
             next_id: 1,
             doc_versions: FxHashMap::default(),
+            served_roots: Arc::new(Mutex::new(served_roots)),
+            workspace_folders: Arc::new(Mutex::new(workspace_folders)),
             pending_requests: Arc::new(Mutex::new(FxHashMap::default())),
             latest_hover: Arc::new(AtomicI32::new(0)),
             latest_hover_implementation: Arc::new(AtomicI32::new(0)),
             latest_hover_definition: Arc::new(AtomicI32::new(0)),
             latest_definition: Arc::new(AtomicI32::new(0)),
             latest_references: Arc::new(AtomicI32::new(0)),
+            latest_call_hierarchy: Arc::new(AtomicI32::new(0)),
             latest_document_symbols: Arc::new(AtomicI32::new(0)),
             latest_workspace_symbols: Arc::new(AtomicI32::new(0)),
             latest_code_action: Arc::new(AtomicI32::new(0)),
@@ -437,8 +649,12 @@ impl LspClient {
             latest_completion_resolve: Arc::new(AtomicI32::new(0)),
             latest_inlay_hints: Arc::new(AtomicI32::new(0)),
             latest_folding_range: Arc::new(AtomicI32::new(0)),
+            latest_document_link: Arc::new(AtomicI32::new(0)),
+            latest_document_link_resolve: Arc::new(AtomicI32::new(0)),
+            latest_document_highlight: Arc::new(AtomicI32::new(0)),
             latest_signature_help: Arc::new(AtomicI32::new(0)),
             latest_format: Arc::new(AtomicI32::new(0)),
+            latest_prepare_rename: Arc::new(AtomicI32::new(0)),
             latest_rename: Arc::new(AtomicI32::new(0)),
             latest_shutdown: Arc::new(AtomicI32::new(0)),
         }
@@ -0,0 +1,77 @@
+use super::LspClient;
+use crate::kernel::services::ports::LspServerCapabilities;
+use crate::kernel::Action;
+use std::path::{Path, PathBuf};
+
+/// 共享同一服务端进程的 language root 及最近一次 `initialize` 得到的能力。
+///
+/// reader 线程在 initialize 应答时为列表里每个 root 派发能力；之后才接入的 root
+/// 由 [`MultiRootClient::attach_root`] 补发，二者在同一把锁下进行，不会漏发。
+#[derive(Debug, Default)]
+pub(super) struct ServedRoots {
+    pub(super) roots: Vec<PathBuf>,
+    pub(super) capabilities: Option<LspServerCapabilities>,
+}
+
+/// 支持多根工作区的服务端：一个进程服务多个 language root，新根通过
+/// `workspace/didChangeWorkspaceFolders` 加入。
+pub(super) struct MultiRootClient {
+    client: LspClient,
+}
+
+impl MultiRootClient {
+    pub(super) fn new(client: LspClient) -> Self {
+        Self { client }
+    }
+
+    pub(super) fn client_mut(&mut self) -> &mut LspClient {
+        &mut self.client
+    }
+
+    /// 进程启动时的根（`rootUri`）；它被移除时整个进程都要关闭。
+    pub(super) fn primary_root(&self) -> &Path {
+        &self.client.root
+    }
+
+    /// 接入新的 language root；已知能力时立即为该根补发一次。
+    pub(super) fn attach_root(&mut self, root: &Path) {
+        let capabilities = {
+            let Ok(mut served) = self.client.served_roots.lock() else {
+                return;
+            };
+            if served.roots.iter().any(|r| r == root) {
+                return;
+            }
+            served.roots.push(root.to_path_buf());
+            served.capabilities.clone()
+        };
+
+        self.client.add_workspace_folder(root);
+        if let Some(capabilities) = capabilities {
+            self.client.ctx.dispatch(Action::LspServerCapabilities {
+                server: self.client.server,
+                root: root.to_path_buf(),
+                capabilities,
+            });
+        }
+    }
+
+    /// 移除落在 `path` 内的附加根（主根不在此处理）。
+    pub(super) fn detach_roots_under(&mut self, path: &Path) {
+        let detached: Vec<PathBuf> = {
+            let Ok(mut served) = self.client.served_roots.lock() else {
+                return;
+            };
+            let primary = self.client.root.clone();
+            let (detached, kept) = served
+                .roots
+                .drain(..)
+                .partition(|root| *root != primary && root.starts_with(path));
+            served.roots = kept;
+            detached
+        };
+        for root in detached {
+            self.client.remove_workspace_folder(&root);
+        }
+    }
+}
@@ -1,9 +1,11 @@
-use super::convert::{client_capabilities, path_to_url, workspace_folders_for_root};
+use super::convert::{client_capabilities, path_to_url};
 use super::wire::{
     child_watch_loop, reader_loop, stderr_loop, writer_loop, InitState, LspPending, LspProcess,
     ReaderLoopArgs,
 };
 use super::LspClient;
+use crate::kernel::services::ports::LspConnectionStatus;
+use crate::kernel::Action;
 use lsp_server::{Message, Request, RequestId};
 use lsp_types::request::Request as _;
 use std::collections::VecDeque;
@@ -11,15 +13,102 @@ use std::process::Command;
 use std::sync::{mpsc, Arc, Mutex};
 use std::time::{Duration, Instant};
 
+/// 崩溃后最多自动重启这么多次，之后放弃并标记为失败。
+pub(super) const MAX_RESTART_ATTEMPTS: u32 = 5;
+
+/// 第 `attempts` 次重试前的等待：`min(2^attempts * 100ms, 30s)`。
+pub(super) fn restart_backoff_delay(attempts: u32) -> Duration {
+    let delay_ms = 100u64.saturating_mul(1u64 << attempts.min(20));
+    Duration::from_millis(delay_ms.min(30_000))
+}
+
 impl LspClient {
     pub(super) fn schedule_restart_backoff(&mut self) {
-        let attempt = self.restart_attempts.saturating_add(1);
-        self.restart_attempts = attempt;
+        if self.restart_attempts >= MAX_RESTART_ATTEMPTS {
+            tracing::error!(
+                server = ?self.server,
+                root = %self.root.display(),
+                attempts = self.restart_attempts,
+                "lsp server keeps exiting; giving up on restarts"
+            );
+            self.exiting = true;
+            self.restart_backoff_until = None;
+            self.report_connection(LspConnectionStatus::Failed);
+            return;
+        }
+
+        let retry_at = Instant::now() + restart_backoff_delay(self.restart_attempts);
+        self.restart_attempts = self.restart_attempts.saturating_add(1);
+        self.restart_backoff_until = Some(retry_at);
+        self.report_connection(LspConnectionStatus::Reconnecting {
+            attempt: self.restart_attempts,
+            retry_at,
+        });
+    }
+
+    /// 为该进程服务的每个 root 上报重连状态，与 `initialize` 下发能力的粒度一致。
+    fn report_connection(&self, status: LspConnectionStatus) {
+        let roots = match self.served_roots.lock() {
+            Ok(served) => served.roots.clone(),
+            Err(_) => vec![self.root.clone()],
+        };
+        for root in roots {
+            self.ctx.dispatch(Action::LspConnectionChanged {
+                server: self.server,
+                root,
+                status,
+            });
+        }
+    }
+
+    /// 进程已退出（或 `initialize` 失败）时丢弃它并排下一次重启；返回是否回收了进程。
+    fn reap_failed_process(&mut self) -> bool {
+        let failed = self.process.as_ref().is_some_and(|process| {
+            process
+                .pending
+                .lock()
+                .is_ok_and(|p| matches!(p.state, InitState::Failed))
+        });
+        if !failed {
+            return false;
+        }
 
-        let shift = attempt.saturating_sub(1).min(6);
-        let delay_ms = 200u64.saturating_mul(1u64 << shift);
-        let delay = Duration::from_millis(delay_ms.min(5_000));
-        self.restart_backoff_until = Some(Instant::now() + delay);
+        self.process = None;
+        self.doc_versions.clear();
+        if let Ok(mut map) = self.pending_requests.lock() {
+            map.clear();
+        }
+        self.schedule_restart_backoff();
+        true
+    }
+
+    /// 每帧调用：发现进程崩溃就排重试，退避期满后主动重启；返回是否起了新进程。
+    pub(super) fn poll_restart(&mut self) -> bool {
+        if self.exiting || self.reap_failed_process() {
+            return false;
+        }
+        if let Some(process) = self.process.as_ref() {
+            // 重启后的进程完成 `initialize` 即算重连成功，计数清零。
+            let ready = process
+                .pending
+                .lock()
+                .is_ok_and(|p| matches!(p.state, InitState::Ready));
+            if ready {
+                self.restart_attempts = 0;
+                self.restart_backoff_until = None;
+            }
+            return false;
+        }
+        if self.restart_attempts == 0 {
+            return false;
+        }
+        if self
+            .restart_backoff_until
+            .is_some_and(|until| Instant::now() < until)
+        {
+            return false;
+        }
+        self.ensure_started()
     }
 
     pub(super) fn ensure_started(&mut self) -> bool {
@@ -34,35 +123,25 @@ impl LspClient {
             return false;
         }
 
+        if self.reap_failed_process() {
+            return false;
+        }
+
         if let Some(process) = self.process.as_ref() {
             let state = process.pending.lock().ok().map(|p| p.state);
-
             if matches!(state, Some(InitState::Ready)) {
                 self.restart_attempts = 0;
                 self.restart_backoff_until = None;
-                return true;
-            }
-
-            if !matches!(state, Some(InitState::Failed)) {
-                return true;
-            }
-
-            self.process = None;
-            self.doc_versions.clear();
-            if let Ok(mut map) = self.pending_requests.lock() {
-                map.clear();
             }
-
-            self.schedule_restart_backoff();
-            return false;
+            return true;
         }
 
         self.restart_backoff_until = None;
 
-        let Some(workspace_folders) = workspace_folders_for_root(&self.root) else {
+        if path_to_url(&self.root).is_none() {
             tracing::error!(root = %self.root.display(), "lsp root path is not a valid file:// uri");
             return false;
-        };
+        }
 
         let mut cmd = Command::new(&self.command);
         cmd.args(&self.args)
@@ -148,6 +227,7 @@ impl LspClient {
                 let latest_hover_definition = self.latest_hover_definition.clone();
                 let latest_definition = self.latest_definition.clone();
                 let latest_references = self.latest_references.clone();
+                let latest_call_hierarchy = self.latest_call_hierarchy.clone();
                 let latest_document_symbols = self.latest_document_symbols.clone();
                 let latest_workspace_symbols = self.latest_workspace_symbols.clone();
                 let latest_code_action = self.latest_code_action.clone();
@@ -155,14 +235,19 @@ impl LspClient {
                 let latest_completion_resolve = self.latest_completion_resolve.clone();
                 let latest_inlay_hints = self.latest_inlay_hints.clone();
                 let latest_folding_range = self.latest_folding_range.clone();
+                let latest_document_link = self.latest_document_link.clone();
+                let latest_document_link_resolve = self.latest_document_link_resolve.clone();
+                let latest_document_highlight = self.latest_document_highlight.clone();
                 let latest_signature_help = self.latest_signature_help.clone();
                 let latest_format = self.latest_format.clone();
+                let latest_prepare_rename = self.latest_prepare_rename.clone();
                 let latest_rename = self.latest_rename.clone();
                 let latest_shutdown = self.latest_shutdown.clone();
                 let server = self.server;
                 let root = self.root.clone();
                 let tx = tx.clone();
-                let workspace_folders = Some(workspace_folders);
+                let workspace_folders = self.workspace_folders.clone();
+                let served_roots = self.served_roots.clone();
                 move || {
                     reader_loop(ReaderLoopArgs {
                         server,
@@ -177,6 +262,7 @@ impl LspClient {
                         latest_hover_definition,
                         latest_definition,
                         latest_references,
+                        latest_call_hierarchy,
                         latest_document_symbols,
                         latest_workspace_symbols,
                         latest_code_action,
@@ -184,12 +270,17 @@ impl LspClient {
                         latest_completion_resolve,
                         latest_inlay_hints,
                         latest_folding_range,
+                        latest_document_link,
+                        latest_document_link_resolve,
+                        latest_document_highlight,
                         latest_signature_help,
                         latest_format,
+                        latest_prepare_rename,
                         latest_rename,
                         latest_shutdown,
                         tx,
                         workspace_folders,
+                        served_roots,
                     })
                 }
             })
@@ -240,12 +331,11 @@ impl LspClient {
             return;
         };
 
-        let name = self
-            .root
-            .file_name()
-            .and_then(|s| s.to_str())
-            .unwrap_or("workspace")
-            .to_string();
+        let workspace_folders = self
+            .workspace_folders
+            .lock()
+            .map(|folders| folders.clone())
+            .unwrap_or_default();
 
         let capabilities = client_capabilities();
 
@@ -253,14 +343,11 @@ impl LspClient {
         let params = lsp_types::InitializeParams {
             process_id: Some(std::process::id()),
             root_path: None,
-            root_uri: Some(root_uri.clone()),
+            root_uri: Some(root_uri),
             initialization_options: self.initialization_options.clone(),
             capabilities,
             trace: None,
-            workspace_folders: Some(vec![lsp_types::WorkspaceFolder {
-                uri: root_uri,
-                name,
-            }]),
+            workspace_folders: Some(workspace_folders),
             client_info: Some(lsp_types::ClientInfo {
                 name: "zcode".to_string(),
                 version: Some(env!("CARGO_PKG_VERSION").to_string()),
@@ -1,7 +1,9 @@
 use super::convert::{completion_item_to_lsp, path_to_url};
+use super::wire::workspace_symbols_partial_token;
 use super::{HoverRequestOptions, LspClient, LspRequestKind};
 use crate::kernel::services::ports::{
-    LspCompletionItem, LspCompletionTriggerContext, LspCompletionTriggerKind, LspPosition, LspRange,
+    LspCallHierarchyItem, LspCompletionItem, LspCompletionTriggerContext, LspCompletionTriggerKind,
+    LspDocumentLink, LspPosition, LspRange,
 };
 use lsp_server::{Message, Notification, Request, RequestId};
 use lsp_types::notification::Notification as _;
@@ -207,6 +209,90 @@ impl LspClient {
         self.send_message(msg, true);
     }
 
+    /// 与 `request_definition` 共用 `latest_definition`，两种跳转互相取代。
+    pub fn request_type_definition(&mut self, path: &Path, position: LspPosition) {
+        if !self.ensure_started() {
+            return;
+        }
+
+        if !self.doc_versions.contains_key(path) {
+            return;
+        }
+
+        let Some(uri) = path_to_url(path) else {
+            return;
+        };
+
+        let id = self.next_id();
+        let prev = self.latest_definition.swap(id, Ordering::Relaxed);
+        self.track_request(id, LspRequestKind::TypeDefinition);
+        if prev != 0 && prev != id {
+            self.cancel_request(prev);
+            self.untrack_request(prev);
+        }
+
+        let params = lsp_types::request::GotoTypeDefinitionParams {
+            text_document_position_params: lsp_types::TextDocumentPositionParams {
+                text_document: lsp_types::TextDocumentIdentifier { uri },
+                position: lsp_types::Position {
+                    line: position.line,
+                    character: position.character,
+                },
+            },
+            work_done_progress_params: lsp_types::WorkDoneProgressParams::default(),
+            partial_result_params: lsp_types::PartialResultParams::default(),
+        };
+
+        let msg = Message::Request(Request::new(
+            RequestId::from(id),
+            lsp_types::request::GotoTypeDefinition::METHOD.to_string(),
+            params,
+        ));
+        self.send_message(msg, true);
+    }
+
+    /// 同样占用 `latest_definition` 槽位，与跳定义、跳类型定义互相取代。
+    pub fn request_implementation(&mut self, path: &Path, position: LspPosition) {
+        if !self.ensure_started() {
+            return;
+        }
+
+        if !self.doc_versions.contains_key(path) {
+            return;
+        }
+
+        let Some(uri) = path_to_url(path) else {
+            return;
+        };
+
+        let id = self.next_id();
+        let prev = self.latest_definition.swap(id, Ordering::Relaxed);
+        self.track_request(id, LspRequestKind::Implementation);
+        if prev != 0 && prev != id {
+            self.cancel_request(prev);
+            self.untrack_request(prev);
+        }
+
+        let params = lsp_types::request::GotoImplementationParams {
+            text_document_position_params: lsp_types::TextDocumentPositionParams {
+                text_document: lsp_types::TextDocumentIdentifier { uri },
+                position: lsp_types::Position {
+                    line: position.line,
+                    character: position.character,
+                },
+            },
+            work_done_progress_params: lsp_types::WorkDoneProgressParams::default(),
+            partial_result_params: lsp_types::PartialResultParams::default(),
+        };
+
+        let msg = Message::Request(Request::new(
+            RequestId::from(id),
+            lsp_types::request::GotoImplementation::METHOD.to_string(),
+            params,
+        ));
+        self.send_message(msg, true);
+    }
+
     pub fn request_references(&mut self, path: &Path, position: LspPosition) {
         if !self.ensure_started() {
             return;
@@ -251,6 +337,106 @@ impl LspClient {
         self.send_message(msg, true);
     }
 
+    pub fn request_prepare_call_hierarchy(&mut self, path: &Path, position: LspPosition) {
+        if !self.ensure_started() {
+            return;
+        }
+
+        if !self.doc_versions.contains_key(path) {
+            return;
+        }
+
+        let Some(uri) = path_to_url(path) else {
+            return;
+        };
+
+        let id = self.next_id();
+        let prev = self.latest_call_hierarchy.swap(id, Ordering::Relaxed);
+        self.track_request(id, LspRequestKind::CallHierarchyPrepare);
+        if prev != 0 && prev != id {
+            self.cancel_request(prev);
+            self.untrack_request(prev);
+        }
+
+        let params = lsp_types::CallHierarchyPrepareParams {
+            text_document_position_params: lsp_types::TextDocumentPositionParams {
+                text_document: lsp_types::TextDocumentIdentifier { uri },
+                position: lsp_types::Position {
+                    line: position.line,
+                    character: position.character,
+                },
+            },
+            work_done_progress_params: lsp_types::WorkDoneProgressParams::default(),
+        };
+
+        let msg = Message::Request(Request::new(
+            RequestId::from(id),
+            lsp_types::request::CallHierarchyPrepare::METHOD.to_string(),
+            params,
+        ));
+        self.send_message(msg, true);
+    }
+
+    pub fn request_call_hierarchy_incoming(
+        &mut self,
+        item_index: usize,
+        item: LspCallHierarchyItem,
+    ) {
+        if !self.ensure_started() {
+            return;
+        }
+
+        let Ok(item) = serde_json::from_value::<lsp_types::CallHierarchyItem>(item.raw) else {
+            return;
+        };
+
+        let id = self.next_id();
+        self.track_request(id, LspRequestKind::CallHierarchyIncoming { item_index });
+
+        let params = lsp_types::CallHierarchyIncomingCallsParams {
+            item,
+            work_done_progress_params: lsp_types::WorkDoneProgressParams::default(),
+            partial_result_params: lsp_types::PartialResultParams::default(),
+        };
+
+        let msg = Message::Request(Request::new(
+            RequestId::from(id),
+            lsp_types::request::CallHierarchyIncomingCalls::METHOD.to_string(),
+            params,
+        ));
+        self.send_message(msg, true);
+    }
+
+    pub fn request_call_hierarchy_outgoing(
+        &mut self,
+        item_index: usize,
+        item: LspCallHierarchyItem,
+    ) {
+        if !self.ensure_started() {
+            return;
+        }
+
+        let Ok(item) = serde_json::from_value::<lsp_types::CallHierarchyItem>(item.raw) else {
+            return;
+        };
+
+        let id = self.next_id();
+        self.track_request(id, LspRequestKind::CallHierarchyOutgoing { item_index });
+
+        let params = lsp_types::CallHierarchyOutgoingCallsParams {
+            item,
+            work_done_progress_params: lsp_types::WorkDoneProgressParams::default(),
+            partial_result_params: lsp_types::PartialResultParams::default(),
+        };
+
+        let msg = Message::Request(Request::new(
+            RequestId::from(id),
+            lsp_types::request::CallHierarchyOutgoingCalls::METHOD.to_string(),
+            params,
+        ));
+        self.send_message(msg, true);
+    }
+
     pub fn request_document_symbols(&mut self, path: &Path) {
         if !self.ensure_started() {
             return;
@@ -305,7 +491,9 @@ impl LspClient {
         }
 
         let params = lsp_types::WorkspaceSymbolParams {
-            partial_result_params: lsp_types::PartialResultParams::default(),
+            partial_result_params: lsp_types::PartialResultParams {
+                partial_result_token: Some(workspace_symbols_partial_token(id)),
+            },
             work_done_progress_params: lsp_types::WorkDoneProgressParams::default(),
             query,
         };
@@ -569,6 +757,143 @@ impl LspClient {
         self.send_message(msg, true);
     }
 
+    pub fn request_document_link(&mut self, path: &Path, version: u64) {
+        if !self.ensure_started() {
+            return;
+        }
+
+        if !self.doc_versions.contains_key(path) {
+            return;
+        }
+
+        let Some(uri) = path_to_url(path) else {
+            return;
+        };
+
+        let id = self.next_id();
+        let prev = self.latest_document_link.swap(id, Ordering::Relaxed);
+        self.track_request(
+            id,
+            LspRequestKind::DocumentLink {
+                path: path.to_path_buf(),
+                version,
+            },
+        );
+        if prev != 0 && prev != id {
+            self.cancel_request(prev);
+            self.untrack_request(prev);
+        }
+
+        let params = lsp_types::DocumentLinkParams {
+            text_document: lsp_types::TextDocumentIdentifier { uri },
+            work_done_progress_params: lsp_types::WorkDoneProgressParams::default(),
+            partial_result_params: lsp_types::PartialResultParams::default(),
+        };
+
+        let msg = Message::Request(Request::new(
+            RequestId::from(id),
+            lsp_types::request::DocumentLinkRequest::METHOD.to_string(),
+            params,
+        ));
+        self.send_message(msg, true);
+    }
+
+    pub fn request_document_highlight(&mut self, path: &Path, position: LspPosition, version: u64) {
+        if !self.ensure_started() {
+            return;
+        }
+
+        if !self.doc_versions.contains_key(path) {
+            return;
+        }
+
+        let Some(uri) = path_to_url(path) else {
+            return;
+        };
+
+        let id = self.next_id();
+        let prev = self.latest_document_highlight.swap(id, Ordering::Relaxed);
+        self.track_request(
+            id,
+            LspRequestKind::DocumentHighlight {
+                path: path.to_path_buf(),
+                version,
+            },
+        );
+        if prev != 0 && prev != id {
+            self.cancel_request(prev);
+            self.untrack_request(prev);
+        }
+
+        let params = lsp_types::DocumentHighlightParams {
+            text_document_position_params: lsp_types::TextDocumentPositionParams {
+                text_document: lsp_types::TextDocumentIdentifier { uri },
+                position: lsp_types::Position {
+                    line: position.line,
+                    character: position.character,
+                },
+            },
+            work_done_progress_params: lsp_types::WorkDoneProgressParams::default(),
+            partial_result_params: lsp_types::PartialResultParams::default(),
+        };
+
+        let msg = Message::Request(Request::new(
+            RequestId::from(id),
+            lsp_types::request::DocumentHighlightRequest::METHOD.to_string(),
+            params,
+        ));
+        self.send_message(msg, true);
+    }
+
+    pub fn request_document_link_resolve(
+        &mut self,
+        path: &Path,
+        version: u64,
+        link: LspDocumentLink,
+    ) {
+        if !self.ensure_started() {
+            return;
+        }
+
+        let id = self.next_id();
+        let prev = self
+            .latest_document_link_resolve
+            .swap(id, Ordering::Relaxed);
+        self.track_request(
+            id,
+            LspRequestKind::DocumentLinkResolve {
+                path: path.to_path_buf(),
+                version,
+                range: link.range,
+            },
+        );
+        if prev != 0 && prev != id {
+            self.cancel_request(prev);
+            self.untrack_request(prev);
+        }
+
+        let position = |pos: LspPosition| lsp_types::Position {
+            line: pos.line,
+            character: pos.character,
+        };
+        let params = lsp_types::DocumentLink {
+            range: lsp_types::Range {
+                start: position(link.range.start),
+                end: position(link.range.end),
+            },
+            target: None,
+            tooltip: None,
+            data: link.data,
+        };
+
+        let msg = Message::Request(Request::new(
+            RequestId::from(id),
+            lsp_types::request::DocumentLinkResolve::METHOD.to_string(),
+            params,
+        ));
+        self.send_message(msg, true);
+    }
+
     pub fn request_signature_help(&mut self, path: &Path, position: LspPosition) {
         if !self.ensure_started() {
             return;
@@ -610,6 +935,50 @@ impl LspClient {
         self.send_message(msg, true);
     }
 
+    pub fn request_prepare_rename(&mut self, path: &Path, position: LspPosition) {
+        if !self.ensure_started() {
+            return;
+        }
+
+        if !self.doc_versions.contains_key(path) {
+            return;
+        }
+
+        let Some(uri) = path_to_url(path) else {
+            return;
+        };
+
+        let id = self.next_id();
+        let prev = self.latest_prepare_rename.swap(id, Ordering::Relaxed);
+        self.track_request(
+            id,
+            LspRequestKind::PrepareRename {
+                path: path.to_path_buf(),
+                line: position.line,
+                column: position.character,
+            },
+        );
+        if prev != 0 && prev != id {
+            self.cancel_request(prev);
+            self.untrack_request(prev);
+        }
+
+        let params = lsp_types::TextDocumentPositionParams {
+            text_document: lsp_types::TextDocumentIdentifier { uri },
+            position: lsp_types::Position {
+                line: position.line,
+                character: position.character,
+            },
+        };
+
+        let msg = Message::Request(Request::new(
+            RequestId::from(id),
+            lsp_types::request::PrepareRenameRequest::METHOD.to_string(),
+            params,
+        ));
+        self.send_message(msg, true);
+    }
+
     pub fn request_rename(&mut self, path: &Path, position: LspPosition, new_name: String) {
         if !self.ensure_started() {
             return;
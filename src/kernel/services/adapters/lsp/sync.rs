@@ -1,4 +1,6 @@
-use super::convert::{language_id_for_path, lsp_version, path_to_url, text_change_event};
+use super::convert::{
+    language_id_for_path, lsp_version, path_to_url, text_change_event, workspace_folder_for_path,
+};
 use super::LspClient;
 use crate::kernel::services::ports::LspTextChange;
 use lsp_server::{Message, Notification};
@@ -6,6 +8,59 @@ use lsp_types::notification::Notification as _;
 use std::path::Path;
 
 impl LspClient {
+    /// 已在列表里（例如就是客户端自身的根）时不重复通知。服务端未启动时只更新列表，
+    /// 启动后 `initialize` 会带上完整列表。
+    pub fn add_workspace_folder(&mut self, path: &Path) {
+        let Some(folder) = workspace_folder_for_path(path) else {
+            return;
+        };
+        {
+            let Ok(mut folders) = self.workspace_folders.lock() else {
+                return;
+            };
+            if folders.iter().any(|f| f.uri == folder.uri) {
+                return;
+            }
+            folders.push(folder.clone());
+        }
+        self.did_change_workspace_folders(lsp_types::WorkspaceFoldersChangeEvent {
+            added: vec![folder],
+            removed: Vec::new(),
+        });
+    }
+
+    pub fn remove_workspace_folder(&mut self, path: &Path) {
+        let Some(folder) = workspace_folder_for_path(path) else {
+            return;
+        };
+        {
+            let Ok(mut folders) = self.workspace_folders.lock() else {
+                return;
+            };
+            let before = folders.len();
+            folders.retain(|f| f.uri != folder.uri);
+            if folders.len() == before {
+                return;
+            }
+        }
+        self.did_change_workspace_folders(lsp_types::WorkspaceFoldersChangeEvent {
+            added: Vec::new(),
+            removed: vec![folder],
+        });
+    }
+
+    fn did_change_workspace_folders(&mut self, event: lsp_types::WorkspaceFoldersChangeEvent) {
+        if self.process.is_none() {
+            return;
+        }
+        let params = lsp_types::DidChangeWorkspaceFoldersParams { event };
+        let msg = Message::Notification(Notification::new(
+            lsp_types::notification::DidChangeWorkspaceFolders::METHOD.to_string(),
+            params,
+        ));
+        self.send_message(msg, true);
+    }
+
     pub fn needs_sync(&self, path: &Path, version: u64) -> bool {
         self.doc_versions.get(path).is_none_or(|v| *v != version)
     }
@@ -1,17 +1,19 @@
 use super::convert::{
-    code_actions_from_lsp, command_from_lsp, completion_items, definition_location,
-    definition_preview_target, diagnostics_from_params, documentation_text, hover_payload,
-    inlay_hints_from_lsp, insert_text_format, language_id_for_path, push_document_symbols,
-    range_from_lsp, server_capabilities_from_lsp, signature_help_payload,
-    symbol_item_from_symbol_information, symbol_item_from_workspace_symbol,
-    workspace_edit_from_lsp, DefinitionPreviewTarget,
+    call_hierarchy_item_from_lsp, code_actions_from_lsp, command_from_lsp, completion_items,
+    definition_location, definition_locations, definition_preview_target, diagnostics_from_params,
+    document_link_from_lsp, documentation_text, hover_payload, inlay_hints_from_lsp,
+    insert_text_format, language_id_for_path, push_document_symbols, range_from_lsp,
+    server_capabilities_from_lsp, signature_help_payload, symbol_item_from_symbol_information,
+    symbol_item_from_workspace_symbol, workspace_edit_from_lsp, DefinitionPreviewTarget,
 };
+use super::pool::ServedRoots;
 use super::LspClient;
 use crate::kernel::language::{adapter_for_path, DefinitionPreviewContext};
 use crate::kernel::panel::locations::LocationItem;
+use crate::kernel::panel::symbols::SymbolItem;
 use crate::kernel::services::ports::{
     LspFoldingRange, LspHoverBlock, LspHoverPayload, LspHoverPreviewPayload, LspRange,
-    LspServerKind, LspSignatureHelpPayload, LspTextEdit, LspWorkspaceEdit, LspWorkspaceFileEdit,
+    LspServerKind, LspSignatureHelpPayload, LspTextEdit,
 };
 use crate::kernel::services::KernelServiceContext;
 use crate::kernel::Action;
@@ -68,7 +70,16 @@ pub(super) enum LspRequestKind {
         max_lines: usize,
     },
     Definition,
+    TypeDefinition,
+    Implementation,
     References,
+    CallHierarchyPrepare,
+    CallHierarchyIncoming {
+        item_index: usize,
+    },
+    CallHierarchyOutgoing {
+        item_index: usize,
+    },
     DocumentSymbols {
         path: PathBuf,
     },
@@ -87,7 +98,25 @@ pub(super) enum LspRequestKind {
         path: PathBuf,
         version: u64,
     },
+    DocumentLink {
+        path: PathBuf,
+        version: u64,
+    },
+    DocumentLinkResolve {
+        path: PathBuf,
+        version: u64,
+        range: LspRange,
+    },
+    DocumentHighlight {
+        path: PathBuf,
+        version: u64,
+    },
     SignatureHelp,
+    PrepareRename {
+        path: PathBuf,
+        line: u32,
+        column: u32,
+    },
     Rename,
     Format {
         path: PathBuf,
@@ -235,6 +264,7 @@ pub(super) struct ReaderLoopArgs {
     pub(super) latest_hover_definition: Arc<AtomicI32>,
     pub(super) latest_definition: Arc<AtomicI32>,
     pub(super) latest_references: Arc<AtomicI32>,
+    pub(super) latest_call_hierarchy: Arc<AtomicI32>,
     pub(super) latest_document_symbols: Arc<AtomicI32>,
     pub(super) latest_workspace_symbols: Arc<AtomicI32>,
     pub(super) latest_code_action: Arc<AtomicI32>,
@@ -242,12 +272,17 @@ pub(super) struct ReaderLoopArgs {
     pub(super) latest_completion_resolve: Arc<AtomicI32>,
     pub(super) latest_inlay_hints: Arc<AtomicI32>,
     pub(super) latest_folding_range: Arc<AtomicI32>,
+    pub(super) latest_document_link: Arc<AtomicI32>,
+    pub(super) latest_document_link_resolve: Arc<AtomicI32>,
+    pub(super) latest_document_highlight: Arc<AtomicI32>,
     pub(super) latest_signature_help: Arc<AtomicI32>,
     pub(super) latest_format: Arc<AtomicI32>,
+    pub(super) latest_prepare_rename: Arc<AtomicI32>,
     pub(super) latest_rename: Arc<AtomicI32>,
     pub(super) latest_shutdown: Arc<AtomicI32>,
     pub(super) tx: mpsc::Sender<Message>,
-    pub(super) workspace_folders: Option<Vec<lsp_types::WorkspaceFolder>>,
+    pub(super) workspace_folders: Arc<Mutex<Vec<lsp_types::WorkspaceFolder>>>,
+    pub(super) served_roots: Arc<Mutex<ServedRoots>>,
 }
 
 pub(super) fn reader_loop(args: ReaderLoopArgs) {
@@ -264,6 +299,7 @@ pub(super) fn reader_loop(args: ReaderLoopArgs) {
         latest_hover_definition,
         latest_definition,
         latest_references,
+        latest_call_hierarchy,
         latest_document_symbols,
         latest_workspace_symbols,
         latest_code_action,
@@ -271,12 +307,17 @@ pub(super) fn reader_loop(args: ReaderLoopArgs) {
         latest_completion_resolve,
         latest_inlay_hints,
         latest_folding_range,
+        latest_document_link,
+        latest_document_link_resolve,
+        latest_document_highlight,
         latest_signature_help,
         latest_format,
+        latest_prepare_rename,
         latest_rename,
         latest_shutdown,
         tx,
         workspace_folders,
+        served_roots,
     } = args;
     let mut reader = BufReader::new(stdout);
     let init_req_id = RequestId::from(init_id);
@@ -318,6 +359,13 @@ pub(super) fn reader_loop(args: ReaderLoopArgs) {
                         tracing::debug!(message = %params.message, "lsp log message");
                     }
                 } else if not.method == "$/progress" {
+                    if handle_partial_result(
+                        &not.params,
+                        latest_workspace_symbols.load(Ordering::Relaxed),
+                        &ctx,
+                    ) {
+                        continue;
+                    }
                     if let Ok(params) =
                         serde_json::from_value::<lsp_types::ProgressParams>(not.params)
                     {
@@ -344,11 +392,20 @@ pub(super) fn reader_loop(args: ReaderLoopArgs) {
                             serde_json::from_value::<lsp_types::InitializeResult>(result.clone())
                         {
                             let caps = server_capabilities_from_lsp(&result.capabilities);
-                            ctx.dispatch(Action::LspServerCapabilities {
-                                server,
-                                root: root.clone(),
-                                capabilities: caps,
-                            });
+                            let roots = match served_roots.lock() {
+                                Ok(mut served) => {
+                                    served.capabilities = Some(caps.clone());
+                                    served.roots.clone()
+                                }
+                                Err(_) => vec![root.clone()],
+                            };
+                            for root in roots {
+                                ctx.dispatch(Action::LspServerCapabilities {
+                                    server,
+                                    root,
+                                    capabilities: caps.clone(),
+                                });
+                            }
                         }
                     }
 
@@ -391,12 +448,22 @@ pub(super) fn reader_loop(args: ReaderLoopArgs) {
                             resp.id
                                 == RequestId::from(latest_hover_definition.load(Ordering::Relaxed))
                         }
-                        LspRequestKind::Definition => {
+                        // 跳定义与跳类型定义共用一个槽位：只有最后一次跳转请求生效。
+                        LspRequestKind::Definition
+                        | LspRequestKind::TypeDefinition
+                        | LspRequestKind::Implementation => {
                             resp.id == RequestId::from(latest_definition.load(Ordering::Relaxed))
                         }
                         LspRequestKind::References => {
                             resp.id == RequestId::from(latest_references.load(Ordering::Relaxed))
                         }
+                        LspRequestKind::CallHierarchyPrepare => {
+                            resp.id
+                                == RequestId::from(latest_call_hierarchy.load(Ordering::Relaxed))
+                        }
+                        // 各节点的展开请求互相独立，不做「只保留最新」的取舍。
+                        LspRequestKind::CallHierarchyIncoming { .. }
+                        | LspRequestKind::CallHierarchyOutgoing { .. } => true,
                         LspRequestKind::DocumentSymbols { .. } => {
                             resp.id
                                 == RequestId::from(latest_document_symbols.load(Ordering::Relaxed))
@@ -423,10 +490,29 @@ pub(super) fn reader_loop(args: ReaderLoopArgs) {
                         LspRequestKind::FoldingRange { .. } => {
                             resp.id == RequestId::from(latest_folding_range.load(Ordering::Relaxed))
                         }
+                        LspRequestKind::DocumentLink { .. } => {
+                            resp.id == RequestId::from(latest_document_link.load(Ordering::Relaxed))
+                        }
+                        LspRequestKind::DocumentLinkResolve { .. } => {
+                            resp.id
+                                == RequestId::from(
+                                    latest_document_link_resolve.load(Ordering::Relaxed),
+                                )
+                        }
+                        LspRequestKind::DocumentHighlight { .. } => {
+                            resp.id
+                                == RequestId::from(
+                                    latest_document_highlight.load(Ordering::Relaxed),
+                                )
+                        }
                         LspRequestKind::SignatureHelp => {
                             resp.id
                                 == RequestId::from(latest_signature_help.load(Ordering::Relaxed))
                         }
+                        LspRequestKind::PrepareRename { .. } => {
+                            resp.id
+                                == RequestId::from(latest_prepare_rename.load(Ordering::Relaxed))
+                        }
                         LspRequestKind::Rename => {
                             resp.id == RequestId::from(latest_rename.load(Ordering::Relaxed))
                         }
@@ -499,9 +585,9 @@ pub(super) fn stderr_loop(stderr: std::process::ChildStderr) {
     }
 }
 
-fn handle_server_request(
+pub(super) fn handle_server_request(
     req: Request,
-    workspace_folders: &Option<Vec<lsp_types::WorkspaceFolder>>,
+    workspace_folders: &Mutex<Vec<lsp_types::WorkspaceFolder>>,
     ctx: &KernelServiceContext,
 ) -> Response {
     match req.method.as_str() {
@@ -539,13 +625,17 @@ fn handle_server_request(
             )
         }
         m if m == lsp_types::request::WorkspaceFoldersRequest::METHOD => {
-            Response::new_ok(req.id, workspace_folders.clone())
+            let folders = workspace_folders.lock().ok().map(|folders| folders.clone());
+            Response::new_ok(req.id, folders)
         }
         m if m == lsp_types::request::WorkDoneProgressCreate::METHOD => {
             Response::new_ok(req.id, ())
         }
         m if m == lsp_types::request::RegisterCapability::METHOD => Response::new_ok(req.id, ()),
         m if m == lsp_types::request::UnregisterCapability::METHOD => Response::new_ok(req.id, ()),
+        // 客户端尚未实现 semantic tokens（也未在 capabilities 里声明）：没有需要作废的
+        // token 缓存，直接应答，避免把 MethodNotFound 回给主动刷新的服务端。
+        m if m == lsp_types::request::SemanticTokensRefresh::METHOD => Response::new_ok(req.id, ()),
         m if m == lsp_types::request::ShowMessageRequest::METHOD => {
             Response::new_ok(req.id, Option::<lsp_types::MessageActionItem>::None)
         }
@@ -557,6 +647,57 @@ fn handle_server_request(
     }
 }
 
+/// `workspace/symbol` 部分结果的 `partialResultToken`，按请求 id 区分，
+/// 被取消的旧请求迟到的批次因 token 不匹配而丢弃。
+pub(super) fn workspace_symbols_partial_token(id: i32) -> lsp_types::ProgressToken {
+    lsp_types::NumberOrString::String(format!("zcode/workspaceSymbols/{id}"))
+}
+
+/// 处理携带部分结果的 `$/progress`：`value` 是结果数组而不是 `WorkDoneProgress`，
+/// 需要先按 token 认领。返回是否已被消费。
+pub(super) fn handle_partial_result(
+    params: &Value,
+    latest_workspace_symbols: i32,
+    ctx: &KernelServiceContext,
+) -> bool {
+    let Some(token) = params
+        .get("token")
+        .and_then(|token| serde_json::from_value::<lsp_types::ProgressToken>(token.clone()).ok())
+    else {
+        return false;
+    };
+    if latest_workspace_symbols == 0
+        || token != workspace_symbols_partial_token(latest_workspace_symbols)
+    {
+        return false;
+    }
+
+    let items = params
+        .get("value")
+        .and_then(|value| {
+            serde_json::from_value::<lsp_types::WorkspaceSymbolResponse>(value.clone()).ok()
+        })
+        .map(workspace_symbol_items)
+        .unwrap_or_default();
+    if !items.is_empty() {
+        ctx.dispatch(Action::LspWorkspaceSymbolsPartial { items });
+    }
+    true
+}
+
+fn workspace_symbol_items(resp: lsp_types::WorkspaceSymbolResponse) -> Vec<SymbolItem> {
+    match resp {
+        lsp_types::WorkspaceSymbolResponse::Flat(symbols) => symbols
+            .into_iter()
+            .filter_map(|sym| symbol_item_from_symbol_information(None, sym))
+            .collect(),
+        lsp_types::WorkspaceSymbolResponse::Nested(symbols) => symbols
+            .into_iter()
+            .filter_map(symbol_item_from_workspace_symbol)
+            .collect(),
+    }
+}
+
 fn render_source_preview_payload(
     label: &str,
     target: &DefinitionPreviewTarget,
@@ -613,9 +754,28 @@ fn dispatch_empty_response(kind: &LspRequestKind, ctx: &KernelServiceContext) {
             })
         }
         LspRequestKind::References => ctx.dispatch(Action::LspReferences { items: Vec::new() }),
-        LspRequestKind::DocumentSymbols { .. } | LspRequestKind::WorkspaceSymbols => {
+        LspRequestKind::CallHierarchyPrepare => {
+            ctx.dispatch(Action::LspCallHierarchyPrepared { items: Vec::new() })
+        }
+        LspRequestKind::CallHierarchyIncoming { item_index } => {
+            ctx.dispatch(Action::LspCallHierarchyIncoming {
+                item_index: *item_index,
+                items: Vec::new(),
+            })
+        }
+        LspRequestKind::CallHierarchyOutgoing { item_index } => {
+            ctx.dispatch(Action::LspCallHierarchyOutgoing {
+                item_index: *item_index,
+                items: Vec::new(),
+            })
+        }
+        LspRequestKind::DocumentSymbols { .. } => {
             ctx.dispatch(Action::LspSymbols { items: Vec::new() })
         }
+        // 出错也要结束流式状态，已流入的部分结果保留。
+        LspRequestKind::WorkspaceSymbols => {
+            ctx.dispatch(Action::LspWorkspaceSymbols { items: Vec::new() })
+        }
         LspRequestKind::CodeAction => ctx.dispatch(Action::LspCodeActions { items: Vec::new() }),
         LspRequestKind::Completion => ctx.dispatch(Action::LspCompletion {
             items: Vec::new(),
@@ -627,9 +787,36 @@ fn dispatch_empty_response(kind: &LspRequestKind, ctx: &KernelServiceContext) {
             version: *version,
             ranges: Vec::new(),
         }),
+        LspRequestKind::DocumentLink { path, version } => ctx.dispatch(Action::LspDocumentLinks {
+            path: path.clone(),
+            version: *version,
+            links: Vec::new(),
+        }),
+        LspRequestKind::DocumentHighlight { path, version } => {
+            ctx.dispatch(Action::LspDocumentHighlights {
+                path: path.clone(),
+                version: *version,
+                ranges: Vec::new(),
+            })
+        }
         LspRequestKind::SignatureHelp => ctx.dispatch(Action::LspSignatureHelp {
             payload: LspSignatureHelpPayload::default(),
         }),
+        // 空响应 / 出错都表示光标处不可改名。
+        LspRequestKind::PrepareRename { path, line, column } => {
+            ctx.dispatch(Action::LspPrepareRenameResponse {
+                path: path.clone(),
+                line: *line,
+                column: *column,
+                valid: false,
+                placeholder: None,
+            })
+        }
+        // 保存前的格式化失败时照常保存。
+        LspRequestKind::Format { path } => ctx.dispatch(Action::LspFormatCompleted {
+            path: path.clone(),
+            edits: Vec::new(),
+        }),
         _ => {}
     }
 }
@@ -641,7 +828,12 @@ pub(super) fn handle_response(kind: LspRequestKind, resp: Response, ctx: &Kernel
         LspRequestKind::HoverImplementation { .. } => "hoverImplementation",
         LspRequestKind::HoverDefinition { .. } => "hoverDefinition",
         LspRequestKind::Definition => "definition",
+        LspRequestKind::TypeDefinition => "typeDefinition",
+        LspRequestKind::Implementation => "implementation",
         LspRequestKind::References => "references",
+        LspRequestKind::CallHierarchyPrepare => "prepareCallHierarchy",
+        LspRequestKind::CallHierarchyIncoming { .. } => "callHierarchyIncoming",
+        LspRequestKind::CallHierarchyOutgoing { .. } => "callHierarchyOutgoing",
         LspRequestKind::DocumentSymbols { .. } => "documentSymbols",
         LspRequestKind::WorkspaceSymbols => "workspaceSymbols",
         LspRequestKind::CodeAction => "codeAction",
@@ -649,7 +841,11 @@ pub(super) fn handle_response(kind: LspRequestKind, resp: Response, ctx: &Kernel
         LspRequestKind::CompletionResolve { .. } => "completionResolve",
         LspRequestKind::InlayHints { .. } => "inlayHints",
         LspRequestKind::FoldingRange { .. } => "foldingRange",
+        LspRequestKind::DocumentLink { .. } => "documentLink",
+        LspRequestKind::DocumentLinkResolve { .. } => "documentLinkResolve",
+        LspRequestKind::DocumentHighlight { .. } => "documentHighlight",
         LspRequestKind::SignatureHelp => "signatureHelp",
+        LspRequestKind::PrepareRename { .. } => "prepareRename",
         LspRequestKind::Rename => "rename",
         LspRequestKind::Format { .. } => "format",
         LspRequestKind::ExecuteCommand => "executeCommand",
@@ -659,7 +855,11 @@ pub(super) fn handle_response(kind: LspRequestKind, resp: Response, ctx: &Kernel
     if let Some(err) = resp.error {
         let is_optional_method = matches!(
             &kind,
-            LspRequestKind::InlayHints { .. } | LspRequestKind::FoldingRange { .. }
+            LspRequestKind::InlayHints { .. }
+                | LspRequestKind::FoldingRange { .. }
+                | LspRequestKind::DocumentLink { .. }
+                | LspRequestKind::DocumentLinkResolve { .. }
+                | LspRequestKind::DocumentHighlight { .. }
         );
         if err.code == ErrorCode::MethodNotFound as i32 && is_optional_method {
             tracing::debug!(code = err.code, error = %err.message, "lsp method not supported");
@@ -716,6 +916,22 @@ pub(super) fn handle_response(kind: LspRequestKind, resp: Response, ctx: &Kernel
                 ctx.dispatch(Action::LspDefinition { path, line, column });
             }
         }
+        LspRequestKind::TypeDefinition => {
+            let resp = serde_json::from_value::<Option<lsp_types::GotoDefinitionResponse>>(result)
+                .ok()
+                .flatten();
+            if let Some((path, line, column)) = resp.and_then(definition_location) {
+                ctx.dispatch(Action::LspTypeDefinition { path, line, column });
+            }
+        }
+        LspRequestKind::Implementation => {
+            let items = serde_json::from_value::<Option<lsp_types::GotoDefinitionResponse>>(result)
+                .ok()
+                .flatten()
+                .map(definition_locations)
+                .unwrap_or_default();
+            ctx.dispatch(Action::LspImplementation { items });
+        }
         LspRequestKind::References => {
             let resp = serde_json::from_value::<Option<Vec<lsp_types::Location>>>(result)
                 .ok()
@@ -736,6 +952,38 @@ pub(super) fn handle_response(kind: LspRequestKind, resp: Response, ctx: &Kernel
 
             ctx.dispatch(Action::LspReferences { items });
         }
+        LspRequestKind::CallHierarchyPrepare => {
+            let items = serde_json::from_value::<Option<Vec<lsp_types::CallHierarchyItem>>>(result)
+                .ok()
+                .flatten()
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(call_hierarchy_item_from_lsp)
+                .collect();
+            ctx.dispatch(Action::LspCallHierarchyPrepared { items });
+        }
+        LspRequestKind::CallHierarchyIncoming { item_index } => {
+            let items =
+                serde_json::from_value::<Option<Vec<lsp_types::CallHierarchyIncomingCall>>>(result)
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|call| call_hierarchy_item_from_lsp(call.from))
+                    .collect();
+            ctx.dispatch(Action::LspCallHierarchyIncoming { item_index, items });
+        }
+        LspRequestKind::CallHierarchyOutgoing { item_index } => {
+            let items =
+                serde_json::from_value::<Option<Vec<lsp_types::CallHierarchyOutgoingCall>>>(result)
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|call| call_hierarchy_item_from_lsp(call.to))
+                    .collect();
+            ctx.dispatch(Action::LspCallHierarchyOutgoing { item_index, items });
+        }
         LspRequestKind::DocumentSymbols { path } => {
             let resp = serde_json::from_value::<Option<lsp_types::DocumentSymbolResponse>>(result)
                 .ok()
@@ -762,31 +1010,13 @@ pub(super) fn handle_response(kind: LspRequestKind, resp: Response, ctx: &Kernel
             ctx.dispatch(Action::LspSymbols { items });
         }
         LspRequestKind::WorkspaceSymbols => {
-            let resp = serde_json::from_value::<Option<lsp_types::WorkspaceSymbolResponse>>(result)
-                .ok()
-                .flatten();
-
-            let mut items = Vec::new();
-            if let Some(resp) = resp {
-                match resp {
-                    lsp_types::WorkspaceSymbolResponse::Flat(symbols) => {
-                        for sym in symbols {
-                            if let Some(item) = symbol_item_from_symbol_information(None, sym) {
-                                items.push(item);
-                            }
-                        }
-                    }
-                    lsp_types::WorkspaceSymbolResponse::Nested(symbols) => {
-                        for sym in symbols {
-                            if let Some(item) = symbol_item_from_workspace_symbol(sym) {
-                                items.push(item);
-                            }
-                        }
-                    }
-                }
-            }
-
-            ctx.dispatch(Action::LspSymbols { items });
+            let items =
+                serde_json::from_value::<Option<lsp_types::WorkspaceSymbolResponse>>(result)
+                    .ok()
+                    .flatten()
+                    .map(workspace_symbol_items)
+                    .unwrap_or_default();
+            ctx.dispatch(Action::LspWorkspaceSymbols { items });
         }
         LspRequestKind::CodeAction => {
             let resp =
@@ -951,6 +1181,56 @@ pub(super) fn handle_response(kind: LspRequestKind, resp: Response, ctx: &Kernel
                 ranges,
             });
         }
+        LspRequestKind::DocumentLink { path, version } => {
+            let resp = serde_json::from_value::<Option<Vec<lsp_types::DocumentLink>>>(result)
+                .ok()
+                .flatten()
+                .unwrap_or_default();
+            let links = resp
+                .into_iter()
+                .take(2048)
+                .map(document_link_from_lsp)
+                .collect();
+            ctx.dispatch(Action::LspDocumentLinks {
+                path,
+                version,
+                links,
+            });
+        }
+        LspRequestKind::DocumentLinkResolve {
+            path,
+            version,
+            range,
+        } => {
+            let Some(link) = serde_json::from_value::<lsp_types::DocumentLink>(result).ok() else {
+                return;
+            };
+            let Some(target) = link.target else {
+                return;
+            };
+            ctx.dispatch(Action::LspDocumentLinkResolved {
+                path,
+                version,
+                range,
+                target: target.to_string(),
+            });
+        }
+        LspRequestKind::DocumentHighlight { path, version } => {
+            let resp = serde_json::from_value::<Option<Vec<lsp_types::DocumentHighlight>>>(result)
+                .ok()
+                .flatten()
+                .unwrap_or_default();
+            let ranges = resp
+                .into_iter()
+                .take(2048)
+                .map(|highlight| range_from_lsp(highlight.range))
+                .collect();
+            ctx.dispatch(Action::LspDocumentHighlights {
+                path,
+                version,
+                ranges,
+            });
+        }
         LspRequestKind::SignatureHelp => {
             let resp = serde_json::from_value::<Option<lsp_types::SignatureHelp>>(result)
                 .ok()
@@ -961,6 +1241,29 @@ pub(super) fn handle_response(kind: LspRequestKind, resp: Response, ctx: &Kernel
                 .unwrap_or_default();
             ctx.dispatch(Action::LspSignatureHelp { payload });
         }
+        LspRequestKind::PrepareRename { path, line, column } => {
+            let resp = serde_json::from_value::<Option<lsp_types::PrepareRenameResponse>>(result)
+                .ok()
+                .flatten();
+            let (valid, placeholder) = match resp {
+                Some(lsp_types::PrepareRenameResponse::RangeWithPlaceholder {
+                    placeholder,
+                    ..
+                }) => (true, Some(placeholder)),
+                Some(lsp_types::PrepareRenameResponse::Range(_)) => (true, None),
+                Some(lsp_types::PrepareRenameResponse::DefaultBehavior { default_behavior }) => {
+                    (default_behavior, None)
+                }
+                None => (false, None),
+            };
+            ctx.dispatch(Action::LspPrepareRenameResponse {
+                path,
+                line,
+                column,
+                valid,
+                placeholder,
+            });
+        }
         LspRequestKind::Rename => {
             let edit = serde_json::from_value::<Option<lsp_types::WorkspaceEdit>>(result)
                 .ok()
@@ -981,25 +1284,14 @@ pub(super) fn handle_response(kind: LspRequestKind, resp: Response, ctx: &Kernel
                 .ok()
                 .flatten()
                 .unwrap_or_default();
-            if !resp.is_empty() {
-                let edits = resp
-                    .into_iter()
-                    .map(|edit| LspTextEdit {
-                        range: range_from_lsp(edit.range),
-                        new_text: edit.new_text,
-                    })
-                    .collect();
-
-                ctx.dispatch(Action::LspApplyWorkspaceEdit {
-                    edit: LspWorkspaceEdit {
-                        changes: vec![LspWorkspaceFileEdit {
-                            path: path.clone(),
-                            edits,
-                        }],
-                        ..Default::default()
-                    },
-                });
-            }
+            let edits = resp
+                .into_iter()
+                .map(|edit| LspTextEdit {
+                    range: range_from_lsp(edit.range),
+                    new_text: edit.new_text,
+                })
+                .collect();
+            ctx.dispatch(Action::LspFormatCompleted { path, edits });
         }
         LspRequestKind::ExecuteCommand => {}
         LspRequestKind::Shutdown => {}
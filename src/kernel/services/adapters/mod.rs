@@ -3,26 +3,30 @@
 pub mod backup;
 pub mod clipboard;
 pub mod config;
+pub mod file;
 pub mod file_watcher;
+pub mod git;
 pub mod keybinding;
 pub mod lsp;
 pub mod perf;
 pub mod runtime;
 pub mod search;
 pub mod settings;
+pub mod url;
 
 pub use crate::kernel::services::ports::{LspCompletionItem, LspPosition, LspRange, LspTextChange};
 pub use backup::{
     ensure_backup_dir, ensure_log_dir, get_backup_dir, get_log_dir, get_ops_file_path,
+    get_trash_dir, trash_entry_path,
 };
 pub use clipboard::{ClipboardError, ClipboardService};
 pub use config::ConfigService;
 pub use file_watcher::{FileWatchEvent, FileWatcherService};
 pub use keybinding::{KeybindingContext, KeybindingService};
 pub use lsp::LspService;
-pub use runtime::{AppMessage, AsyncRuntime};
+pub use runtime::{AppMessage, AsyncRuntime, FileError, FilePart};
 pub use search::{
-    search_regex_in_slice, GlobalSearchService, GlobalSearchTask, RopeReader, SearchConfig,
-    SearchService, SearchTask, StreamSearcher,
+    search_regex_in_slice, GlobalSearchFilter, GlobalSearchService, GlobalSearchTask, RopeReader,
+    SearchConfig, SearchService, SearchTask, StreamSearcher,
 };
 pub use settings::{ensure_settings_file, get_settings_path, load_settings, parse_keybinding};
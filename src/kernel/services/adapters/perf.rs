@@ -1,3 +1,5 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
 use std::time::Duration;
 
 #[cfg(feature = "perf")]
@@ -122,3 +124,97 @@ pub fn report_and_reset() -> String {
     reset();
     out
 }
+
+/// 帧耗时环形缓冲保留的最近帧数。
+const FRAME_HISTORY: usize = 1000;
+
+/// 最近 [`FRAME_HISTORY`] 帧渲染耗时的分位数（微秒）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    pub p50_us: u64,
+    pub p95_us: u64,
+    pub p99_us: u64,
+    pub max_us: u64,
+    pub sample_count: usize,
+}
+
+impl FrameStats {
+    /// 状态栏上的一行摘要。
+    pub fn summary(&self) -> String {
+        let ms = |us: u64| us as f64 / 1000.0;
+        format!(
+            "frame p50 {:.1}ms p95 {:.1}ms p99 {:.1}ms max {:.1}ms (n={})",
+            ms(self.p50_us),
+            ms(self.p95_us),
+            ms(self.p99_us),
+            ms(self.max_us),
+            self.sample_count
+        )
+    }
+}
+
+#[derive(Default)]
+struct FrameRing {
+    samples_us: Vec<u64>,
+    next: usize,
+}
+
+/// 帧耗时不分 `perf` feature，由 `--perf` 在运行时打开显示。渲染只在主线程，
+/// 锁基本无竞争，全局一把 `Mutex` 即可。
+static FRAMES: OnceLock<Mutex<FrameRing>> = OnceLock::new();
+static FRAME_STATS_VISIBLE: AtomicBool = AtomicBool::new(false);
+
+fn frames() -> &'static Mutex<FrameRing> {
+    FRAMES.get_or_init(|| Mutex::new(FrameRing::default()))
+}
+
+/// 记录一帧渲染耗时；超过 [`FRAME_HISTORY`] 帧后覆盖最旧的一帧。
+pub fn record_frame(duration: Duration) {
+    let us = u64::try_from(duration.as_micros()).unwrap_or(u64::MAX);
+    let Ok(mut ring) = frames().lock() else {
+        return;
+    };
+    if ring.samples_us.len() < FRAME_HISTORY {
+        ring.samples_us.push(us);
+    } else {
+        let next = ring.next;
+        ring.samples_us[next] = us;
+    }
+    ring.next = (ring.next + 1) % FRAME_HISTORY;
+}
+
+pub fn frame_stats() -> FrameStats {
+    frames()
+        .lock()
+        .map(|ring| stats_from_samples(&ring.samples_us))
+        .unwrap_or_default()
+}
+
+pub fn set_frame_stats_visible(visible: bool) {
+    FRAME_STATS_VISIBLE.store(visible, Ordering::Relaxed);
+}
+
+pub fn frame_stats_visible() -> bool {
+    FRAME_STATS_VISIBLE.load(Ordering::Relaxed)
+}
+
+fn stats_from_samples(samples_us: &[u64]) -> FrameStats {
+    if samples_us.is_empty() {
+        return FrameStats::default();
+    }
+    let mut sorted = samples_us.to_vec();
+    sorted.sort_unstable();
+    // nearest-rank：第 ⌈p·n⌉ 个样本。
+    let percentile = |p: usize| sorted[(p * sorted.len()).div_ceil(100).max(1) - 1];
+    FrameStats {
+        p50_us: percentile(50),
+        p95_us: percentile(95),
+        p99_us: percentile(99),
+        max_us: sorted[sorted.len() - 1],
+        sample_count: sorted.len(),
+    }
+}
+
+#[cfg(test)]
+#[path = "../../../../tests/unit/kernel/services/adapters/perf.rs"]
+mod tests;
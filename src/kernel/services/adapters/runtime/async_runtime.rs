@@ -1,19 +1,26 @@
-use super::message::AppMessage;
+use super::message::{AppMessage, FileError};
 use crate::kernel::editor::ReloadRequest;
 use crate::kernel::editor::TabId;
 use crate::kernel::language::LanguageId;
-use crate::kernel::services::ports::lsp::{line_len_chars, lsp_col_to_char_offset_in_line};
+use crate::kernel::services::adapters::backup::{
+    ensure_backup_dir, get_ops_file_path, prune_trash_dir, MAX_TRASH_ENTRIES,
+};
+use crate::kernel::services::adapters::file::local::{copy_path, move_path};
+use crate::kernel::services::adapters::git;
+use crate::kernel::services::ports::lsp::lsp_text_edits_to_char_ranges;
 use crate::kernel::services::ports::DirEntryInfo;
 use crate::kernel::services::ports::{
     LspPositionEncoding, LspResourceOp, LspTextEdit, LspWorkspaceFileEdit,
 };
-use crate::models::should_ignore;
+use crate::models::{should_ignore, EditHistory, HistoryBackup};
 use ropey::Rope;
 use std::io::{self, Write};
 use std::path::PathBuf;
 use std::sync::mpsc::Sender;
 use tree_sitter::Tree;
 
+const DIR_BATCH_SIZE: usize = 100;
+
 pub struct AsyncRuntime {
     runtime: tokio::runtime::Runtime,
     tx: Sender<AppMessage>,
@@ -62,12 +69,164 @@ impl AsyncRuntime {
         });
     }
 
+    pub fn git_stash_list(&self, root: PathBuf) {
+        self.run_git_stash_op("stash_list", root, |_| Ok(()));
+    }
+
+    pub fn git_stash_push(&self, root: PathBuf, include_untracked: bool) {
+        self.run_git_stash_op("stash_push", root, move |root| {
+            git::stash_push(root, include_untracked)
+        });
+    }
+
+    pub fn git_stash_apply(&self, root: PathBuf, index: usize) {
+        self.run_git_stash_op("stash_apply", root, move |root| {
+            git::stash_apply(root, index)
+        });
+    }
+
+    pub fn git_stash_drop(&self, root: PathBuf, index: usize) {
+        self.run_git_stash_op("stash_drop", root, move |root| git::stash_drop(root, index));
+    }
+
+    pub fn git_branch_list(&self, root: PathBuf) {
+        self.run_git_branch_op("branch_list", root, |_| Ok(()));
+    }
+
+    pub fn git_checkout(&self, root: PathBuf, branch: String, create: bool) {
+        self.run_git_branch_op("checkout", root, move |root| {
+            git::checkout(root, &branch, create)
+        });
+    }
+
+    pub fn git_blame(&self, path: PathBuf, line: usize) {
+        let tx = self.tx.clone();
+        self.runtime
+            .spawn_blocking(move || match git::blame_line(&path, line) {
+                Ok(blame) => {
+                    let _ = tx.send(AppMessage::GitBlameResult {
+                        path,
+                        line,
+                        author: blame.author,
+                        timestamp: blame.timestamp,
+                        commit: blame.commit,
+                    });
+                }
+                // 不在仓库里的文件、新文件都会失败，属于常态，不进日志。
+                Err(e) => tracing::debug!(path = %path.display(), error = %e, "git blame failed"),
+            });
+    }
+
+    pub fn git_load_diff_base(&self, path: PathBuf) {
+        let tx = self.tx.clone();
+        self.runtime
+            .spawn_blocking(move || send_git_diff_base(&tx, path));
+    }
+
+    /// 暂存一个改动块后重新读取暂存区版本，diff 视图随之刷新。
+    pub fn git_stage_hunk(&self, path: PathBuf, body: String) {
+        let tx = self.tx.clone();
+        self.runtime.spawn_blocking(move || {
+            if let Err(e) = git::stage_hunk(&path, &body) {
+                let _ = tx.send(AppMessage::GitOpError {
+                    op: "stage_hunk",
+                    error: e.to_string(),
+                });
+                return;
+            }
+            send_git_diff_base(&tx, path);
+        });
+    }
+
+    /// 执行一次 stash 操作后总是重新列出 stash：失败时列表也可能已部分变化
+    /// （例如 apply 冲突仍会改动工作区），刷新比猜测更可靠。
+    fn run_git_stash_op<F>(&self, op: &'static str, root: PathBuf, f: F)
+    where
+        F: FnOnce(&std::path::Path) -> io::Result<()> + Send + 'static,
+    {
+        let tx = self.tx.clone();
+        self.runtime.spawn_blocking(move || {
+            if let Err(e) = f(&root) {
+                let _ = tx.send(AppMessage::GitOpError {
+                    op,
+                    error: e.to_string(),
+                });
+            }
+            match git::stash_list(&root) {
+                Ok(stashes) => {
+                    let _ = tx.send(AppMessage::GitStashList { stashes });
+                }
+                Err(e) => {
+                    let _ = tx.send(AppMessage::GitOpError {
+                        op: "stash_list",
+                        error: e.to_string(),
+                    });
+                }
+            }
+        });
+    }
+
+    /// 与 stash 操作同理：checkout 后重新列出分支，刷新当前 HEAD 标记。
+    fn run_git_branch_op<F>(&self, op: &'static str, root: PathBuf, f: F)
+    where
+        F: FnOnce(&std::path::Path) -> io::Result<()> + Send + 'static,
+    {
+        let tx = self.tx.clone();
+        self.runtime.spawn_blocking(move || {
+            if let Err(e) = f(&root) {
+                let _ = tx.send(AppMessage::GitOpError {
+                    op,
+                    error: e.to_string(),
+                });
+            }
+            match git::list_branches(&root) {
+                Ok(branches) => {
+                    let _ = tx.send(AppMessage::GitBranchesUpdated { branches });
+                }
+                Err(e) => {
+                    let _ = tx.send(AppMessage::GitOpError {
+                        op: "branch_list",
+                        error: e.to_string(),
+                    });
+                }
+            }
+        });
+    }
+
+    /// 遍历工作区（跳过隐藏文件与 `should_ignore` 目录），返回相对路径，按路径排序。
+    pub fn list_workspace_files(&self, root: PathBuf) {
+        let tx = self.tx.clone();
+        self.runtime.spawn_blocking(move || {
+            let mut files: Vec<PathBuf> = ignore::WalkBuilder::new(&root)
+                .hidden(true)
+                .git_ignore(false)
+                .git_global(false)
+                .git_exclude(false)
+                .filter_entry(|entry| !should_ignore(&entry.file_name().to_string_lossy()))
+                .build()
+                .filter_map(Result::ok)
+                .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+                .filter_map(|entry| {
+                    entry
+                        .path()
+                        .strip_prefix(&root)
+                        .ok()
+                        .map(|p| p.to_path_buf())
+                })
+                .collect();
+            files.sort();
+            let _ = tx.send(AppMessage::WorkspaceFiles { files });
+        });
+    }
+
+    /// 边读边发：每攒满 `DIR_BATCH_SIZE` 条发一次 `DirBatchLoaded`，让资源管理器在大目录
+    /// 枚举完之前就能显示已读到的条目；剩余条目随 `DirLoaded` 一起发出并标记完成。
     pub fn load_dir(&self, path: PathBuf) {
         let tx = self.tx.clone();
         self.runtime.spawn(async move {
             match tokio::fs::read_dir(&path).await {
                 Ok(mut entries) => {
-                    let mut result = Vec::new();
+                    let mut result = Vec::with_capacity(DIR_BATCH_SIZE);
                     loop {
                         let entry = match entries.next_entry().await {
                             Ok(Some(entry)) => entry,
@@ -87,9 +246,20 @@ impl AsyncRuntime {
                         }
 
                         if let Ok(file_type) = entry.file_type().await {
-                            result.push(DirEntryInfo {
-                                name,
-                                is_dir: file_type.is_dir(),
+                            let is_dir = file_type.is_dir();
+                            let size = if is_dir {
+                                None
+                            } else {
+                                entry.metadata().await.ok().map(|meta| meta.len())
+                            };
+                            result.push(DirEntryInfo { name, is_dir, size });
+                        }
+                        if result.len() >= DIR_BATCH_SIZE {
+                            let batch =
+                                std::mem::replace(&mut result, Vec::with_capacity(DIR_BATCH_SIZE));
+                            let _ = tx.send(AppMessage::DirBatchLoaded {
+                                path: path.clone(),
+                                entries: batch,
                             });
                         }
                     }
@@ -108,9 +278,26 @@ impl AsyncRuntime {
         });
     }
 
-    pub fn load_file(&self, path: PathBuf) {
+    /// 读入文件。`max_size_bytes` 为 `Some` 时先看元数据，超限直接回 `FileError::TooLarge`
+    /// 而不读内容；`None` 用于用户确认过的大文件与配置文件。
+    pub fn load_file(&self, path: PathBuf, max_size_bytes: Option<u64>) {
         let tx = self.tx.clone();
         self.runtime.spawn(async move {
+            if let Some(limit) = max_size_bytes {
+                if let Ok(meta) = tokio::fs::metadata(&path).await {
+                    if meta.is_file() && meta.len() > limit {
+                        let _ = tx.send(AppMessage::FileError {
+                            path,
+                            error: FileError::TooLarge {
+                                size: meta.len(),
+                                limit,
+                            },
+                        });
+                        return;
+                    }
+                }
+            }
+
             match tokio::fs::read_to_string(&path).await {
                 Ok(content) => {
                     let _ = tx.send(AppMessage::FileLoaded { path, content });
@@ -118,13 +305,36 @@ impl AsyncRuntime {
                 Err(e) => {
                     let _ = tx.send(AppMessage::FileError {
                         path,
-                        error: e.to_string(),
+                        error: FileError::Io(e.to_string()),
                     });
                 }
             }
         });
     }
 
+    /// 读取文件的 `[start_byte, end_byte)` 一段（大文件分块读入），结果回到 `AppMessage::FileRangeLoaded`。
+    pub fn load_file_range(&self, path: PathBuf, start_byte: u64, end_byte: u64) {
+        let tx = self.tx.clone();
+        self.runtime
+            .spawn_blocking(move || match read_file_range(&path, start_byte, end_byte) {
+                Ok((content, end_byte, file_size)) => {
+                    let _ = tx.send(AppMessage::FileRangeLoaded {
+                        path,
+                        start_byte,
+                        end_byte,
+                        file_size,
+                        content,
+                    });
+                }
+                Err(e) => {
+                    let _ = tx.send(AppMessage::FileError {
+                        path,
+                        error: FileError::Io(e.to_string()),
+                    });
+                }
+            });
+    }
+
     pub fn reload_file(&self, request: ReloadRequest) {
         let tx = self.tx.clone();
         self.runtime.spawn(async move {
@@ -134,6 +344,36 @@ impl AsyncRuntime {
         });
     }
 
+    /// 读回 `path` 的撤销历史备份 `ops_path`，校验其基线与刚加载的 `content` 一致后回传。
+    /// 备份过期或读失败时不发消息（过期备份由 `replay_from_backup` 删除）。
+    pub fn load_edit_history(&self, path: PathBuf, ops_path: PathBuf, content: String) {
+        let tx = self.tx.clone();
+        self.runtime.spawn_blocking(move || {
+            match EditHistory::replay_from_backup(&ops_path, &Rope::from_str(&content)) {
+                Ok(history) => {
+                    let _ = tx.send(AppMessage::EditHistoryLoaded { path, history });
+                }
+                Err(e) => {
+                    tracing::debug!(path = %path.display(), error = %e, "edit history backup dropped");
+                }
+            }
+        });
+    }
+
+    pub fn backup_edit_history(&self, path: PathBuf, backup: HistoryBackup) {
+        self.runtime.spawn_blocking(move || {
+            let result = ensure_backup_dir().and_then(|_| {
+                let ops_path = get_ops_file_path(&path).ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::NotFound, "no backup path for file")
+                })?;
+                backup.write_to(&ops_path)
+            });
+            if let Err(e) = result {
+                tracing::warn!(path = %path.display(), error = %e, "edit history backup failed");
+            }
+        });
+    }
+
     pub fn write_file(
         &self,
         pane: usize,
@@ -141,15 +381,22 @@ impl AsyncRuntime {
         version: u64,
         head: crate::models::OpId,
         rope: Rope,
+        part: Option<FilePart>,
     ) {
         let tx = self.tx.clone();
         self.runtime.spawn(async move {
             let tx_for_error = tx.clone();
             let path_for_error = path.clone();
             let path_for_write = path.clone();
-            let result =
-                tokio::task::spawn_blocking(move || write_rope_to_path(&path_for_write, &rope))
-                    .await;
+            let result = tokio::task::spawn_blocking(move || {
+                if let Some(part) = part {
+                    if write_part_to_path(&path_for_write, &part)? {
+                        return Ok(());
+                    }
+                }
+                write_rope_to_path(&path_for_write, &rope)
+            })
+            .await;
 
             let success = match result {
                 Ok(Ok(())) => true,
@@ -183,16 +430,27 @@ impl AsyncRuntime {
         });
     }
 
-    pub fn create_file(&self, path: PathBuf) {
+    /// 新建文件并写入 `template`（空串即空文件）；目标已存在时报错，不覆盖。
+    pub fn create_file_with_template(&self, path: PathBuf, template: String) {
         let tx = self.tx.clone();
         self.runtime.spawn(async move {
-            match tokio::fs::OpenOptions::new()
-                .create_new(true)
-                .write(true)
-                .open(&path)
-                .await
-            {
-                Ok(_) => {
+            let result = async {
+                use tokio::io::AsyncWriteExt;
+
+                let mut file = tokio::fs::OpenOptions::new()
+                    .create_new(true)
+                    .write(true)
+                    .open(&path)
+                    .await?;
+                if !template.is_empty() {
+                    file.write_all(template.as_bytes()).await?;
+                    file.flush().await?;
+                }
+                io::Result::Ok(())
+            }
+            .await;
+            match result {
+                Ok(()) => {
                     let _ = tx.send(AppMessage::PathCreated {
                         path,
                         is_dir: false,
@@ -331,6 +589,71 @@ impl AsyncRuntime {
         });
     }
 
+    /// 把 `path` 挪进回收站的 `trashed`（父目录按需创建），成功后按删除处理；
+    /// 回收站超过 `MAX_TRASH_ENTRIES` 条时顺手清掉最早的。
+    pub fn trash_path(&self, path: PathBuf, trashed: PathBuf) {
+        let tx = self.tx.clone();
+        self.runtime.spawn(async move {
+            let path_for_work = path.clone();
+            let trashed_for_work = trashed.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                let Some(trash_dir) = trashed_for_work.parent() else {
+                    return move_path(path_for_work.as_path(), trashed_for_work.as_path(), false);
+                };
+                std::fs::create_dir_all(trash_dir)?;
+                move_path(path_for_work.as_path(), trashed_for_work.as_path(), false)?;
+                if let Err(e) = prune_trash_dir(trash_dir, MAX_TRASH_ENTRIES) {
+                    tracing::warn!(error = %e, "prune trash dir failed");
+                }
+                Ok(())
+            })
+            .await
+            .unwrap_or_else(|e| Err(io::Error::other(e.to_string())));
+
+            match result {
+                Ok(()) => {
+                    let _ = tx.send(AppMessage::PathDeleted { path });
+                }
+                Err(e) => {
+                    let _ = tx.send(AppMessage::FsOpError {
+                        op: "trash_path",
+                        path,
+                        to: Some(trashed),
+                        error: e.to_string(),
+                    });
+                }
+            }
+        });
+    }
+
+    /// 把回收站里的 `trashed` 挪回 `path`；原位置已被占用时报错，不覆盖。
+    pub fn restore_from_trash(&self, trashed: PathBuf, path: PathBuf, is_dir: bool) {
+        let tx = self.tx.clone();
+        self.runtime.spawn(async move {
+            let path_for_work = path.clone();
+            let trashed_for_work = trashed.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                move_path(trashed_for_work.as_path(), path_for_work.as_path(), false)
+            })
+            .await
+            .unwrap_or_else(|e| Err(io::Error::other(e.to_string())));
+
+            match result {
+                Ok(()) => {
+                    let _ = tx.send(AppMessage::PathCreated { path, is_dir });
+                }
+                Err(e) => {
+                    let _ = tx.send(AppMessage::FsOpError {
+                        op: "restore_from_trash",
+                        path: trashed,
+                        to: Some(path),
+                        error: e.to_string(),
+                    });
+                }
+            }
+        });
+    }
+
     pub fn apply_file_edits(
         &self,
         position_encoding: LspPositionEncoding,
@@ -504,185 +827,6 @@ impl AsyncRuntime {
     }
 }
 
-fn copy_path(from: &std::path::Path, to: &std::path::Path, overwrite: bool) -> io::Result<()> {
-    if from == to {
-        return Ok(());
-    }
-
-    let from_meta = std::fs::symlink_metadata(from)?;
-    if from_meta.is_dir() && to.starts_with(from) {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "cannot copy a directory into itself",
-        ));
-    }
-
-    let Some(parent) = to.parent() else {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "destination has no parent directory",
-        ));
-    };
-    if !std::fs::metadata(parent).is_ok_and(|m| m.is_dir()) {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "destination parent is not a directory",
-        ));
-    }
-
-    if !overwrite && std::fs::symlink_metadata(to).is_ok() {
-        return Err(io::Error::new(
-            io::ErrorKind::AlreadyExists,
-            "destination exists",
-        ));
-    }
-
-    if overwrite {
-        remove_existing_path(to)?;
-    }
-
-    if from_meta.file_type().is_symlink() {
-        return copy_symlink(from, to);
-    }
-
-    if from_meta.is_dir() {
-        return copy_dir_recursive(from, to);
-    }
-
-    std::fs::copy(from, to)?;
-    Ok(())
-}
-
-fn move_path(from: &std::path::Path, to: &std::path::Path, overwrite: bool) -> io::Result<()> {
-    move_path_impl(from, to, overwrite, |from, to| std::fs::rename(from, to))
-}
-
-fn move_path_impl(
-    from: &std::path::Path,
-    to: &std::path::Path,
-    overwrite: bool,
-    rename_fn: impl FnOnce(&std::path::Path, &std::path::Path) -> io::Result<()>,
-) -> io::Result<()> {
-    if from == to {
-        return Ok(());
-    }
-
-    let from_meta = std::fs::symlink_metadata(from)?;
-    if from_meta.is_dir() && to.starts_with(from) {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "cannot move a directory into itself",
-        ));
-    }
-
-    let Some(parent) = to.parent() else {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "destination has no parent directory",
-        ));
-    };
-    if !std::fs::metadata(parent).is_ok_and(|m| m.is_dir()) {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "destination parent is not a directory",
-        ));
-    }
-
-    if !overwrite && std::fs::symlink_metadata(to).is_ok() {
-        return Err(io::Error::new(
-            io::ErrorKind::AlreadyExists,
-            "destination exists",
-        ));
-    }
-
-    if overwrite {
-        remove_existing_path(to)?;
-    }
-
-    match rename_fn(from, to) {
-        Ok(()) => Ok(()),
-        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => move_across_devices(from, to),
-        Err(e) => Err(e),
-    }
-}
-
-fn remove_existing_path(path: &std::path::Path) -> io::Result<()> {
-    match std::fs::symlink_metadata(path) {
-        Ok(meta) => {
-            if meta.is_dir() {
-                std::fs::remove_dir_all(path)
-            } else {
-                std::fs::remove_file(path)
-            }
-        }
-        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
-        Err(e) => Err(e),
-    }
-}
-
-fn move_across_devices(from: &std::path::Path, to: &std::path::Path) -> io::Result<()> {
-    let from_meta = std::fs::symlink_metadata(from)?;
-    if from_meta.file_type().is_symlink() {
-        copy_symlink(from, to)?;
-        std::fs::remove_file(from)?;
-        return Ok(());
-    }
-
-    if from_meta.is_dir() {
-        copy_dir_recursive(from, to)?;
-        std::fs::remove_dir_all(from)?;
-        return Ok(());
-    }
-
-    std::fs::copy(from, to)?;
-    std::fs::remove_file(from)?;
-    Ok(())
-}
-
-fn copy_dir_recursive(from: &std::path::Path, to: &std::path::Path) -> io::Result<()> {
-    std::fs::create_dir(to)?;
-
-    for entry in std::fs::read_dir(from)? {
-        let entry = entry?;
-        let from_child = entry.path();
-        let to_child = to.join(entry.file_name());
-        let meta = std::fs::symlink_metadata(&from_child)?;
-
-        if meta.file_type().is_symlink() {
-            copy_symlink(&from_child, &to_child)?;
-        } else if meta.is_dir() {
-            copy_dir_recursive(&from_child, &to_child)?;
-        } else {
-            std::fs::copy(&from_child, &to_child)?;
-        }
-    }
-
-    Ok(())
-}
-
-#[cfg(unix)]
-fn copy_symlink(from: &std::path::Path, to: &std::path::Path) -> io::Result<()> {
-    let target = std::fs::read_link(from)?;
-    std::os::unix::fs::symlink(target, to)
-}
-
-#[cfg(windows)]
-fn copy_symlink(from: &std::path::Path, to: &std::path::Path) -> io::Result<()> {
-    let target = std::fs::read_link(from)?;
-    match std::fs::metadata(from) {
-        Ok(meta) if meta.is_dir() => std::os::windows::fs::symlink_dir(target, to),
-        _ => std::os::windows::fs::symlink_file(target, to),
-    }
-}
-
-#[cfg(not(any(unix, windows)))]
-fn copy_symlink(_from: &std::path::Path, _to: &std::path::Path) -> io::Result<()> {
-    Err(io::Error::new(
-        io::ErrorKind::Unsupported,
-        "symlinks are not supported on this platform",
-    ))
-}
-
 fn apply_create_file(
     path: &std::path::Path,
     overwrite: bool,
@@ -768,6 +912,98 @@ fn apply_delete_file(
     }
 }
 
+/// 局部写盘：把文件的 `byte_range` 换成 `replacement`。
+#[derive(Debug, Clone)]
+pub struct FilePart {
+    pub byte_range: std::ops::Range<usize>,
+    pub replacement: String,
+    /// 发起写盘时预期的磁盘文件长度；对不上说明文件被外部改过。
+    pub disk_len: usize,
+}
+
+/// 原地改写文件的一段：长度不变时只覆盖该段，否则连同其后的内容一起重写。
+/// 文件长度与预期不符时不动文件并返回 `Ok(false)`，由调用方整体写入。
+fn write_part_to_path(path: &std::path::Path, part: &FilePart) -> std::io::Result<bool> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)?;
+    if file.metadata()?.len() != part.disk_len as u64
+        || part.byte_range.start > part.byte_range.end
+        || part.byte_range.end > part.disk_len
+    {
+        return Ok(false);
+    }
+
+    let start = part.byte_range.start as u64;
+    if part.replacement.len() == part.byte_range.len() {
+        file.seek(SeekFrom::Start(start))?;
+        file.write_all(part.replacement.as_bytes())?;
+        return file.flush().map(|()| true);
+    }
+
+    let mut tail = Vec::new();
+    file.seek(SeekFrom::Start(part.byte_range.end as u64))?;
+    file.read_to_end(&mut tail)?;
+    file.seek(SeekFrom::Start(start))?;
+    file.write_all(part.replacement.as_bytes())?;
+    file.write_all(&tail)?;
+    file.set_len(start + (part.replacement.len() + tail.len()) as u64)?;
+    file.flush().map(|()| true)
+}
+
+/// 读出 `[start_byte, end_byte)` 并返回 `(内容, 实际结束位置, 文件长度)`。未到文件末尾时
+/// 截到最后一个换行之后（没有换行则只避开被截断的 UTF-8 字符），下一块从实际结束位置接着读。
+fn read_file_range(
+    path: &std::path::Path,
+    start_byte: u64,
+    end_byte: u64,
+) -> std::io::Result<(String, u64, u64)> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = std::fs::File::open(path)?;
+    let file_size = file.metadata()?.len();
+    let end = end_byte.min(file_size);
+    let start = start_byte.min(end);
+    let mut buf = Vec::with_capacity((end - start) as usize);
+    file.seek(SeekFrom::Start(start))?;
+    file.take(end - start).read_to_end(&mut buf)?;
+
+    let mut cut = buf.len();
+    if start + (buf.len() as u64) < file_size {
+        cut = match buf.iter().rposition(|b| *b == b'\n') {
+            Some(pos) => pos + 1,
+            None => utf8_char_boundary(&buf),
+        };
+    }
+    let content = String::from_utf8_lossy(&buf[..cut]).into_owned();
+    Ok((content, start + cut as u64, file_size))
+}
+
+/// `buf` 末尾若是不完整的 UTF-8 字符，返回它的起点，否则返回 `buf.len()`。
+fn utf8_char_boundary(buf: &[u8]) -> usize {
+    let tail_start = buf.len().saturating_sub(4);
+    let Some(lead) = (tail_start..buf.len())
+        .rev()
+        .find(|&i| buf[i] & 0b1100_0000 != 0b1000_0000)
+    else {
+        return buf.len();
+    };
+    let width = match buf[lead] {
+        b if b >= 0b1111_0000 => 4,
+        b if b >= 0b1110_0000 => 3,
+        b if b >= 0b1100_0000 => 2,
+        _ => 1,
+    };
+    if lead + width > buf.len() {
+        lead
+    } else {
+        buf.len()
+    }
+}
+
 fn write_rope_to_path(path: &std::path::Path, rope: &Rope) -> std::io::Result<()> {
     let file = std::fs::File::create(path)?;
     let mut writer = std::io::BufWriter::new(file);
@@ -789,71 +1025,28 @@ fn apply_text_edits_to_path(
 }
 
 fn apply_text_edits_to_rope(rope: &mut Rope, edits: &[LspTextEdit], encoding: LspPositionEncoding) {
-    let mut ordered: Vec<&LspTextEdit> = edits.iter().collect();
-    ordered.sort_by(|a, b| {
-        b.range
-            .start
-            .line
-            .cmp(&a.range.start.line)
-            .then_with(|| b.range.start.character.cmp(&a.range.start.character))
-            .then_with(|| b.range.end.line.cmp(&a.range.end.line))
-            .then_with(|| b.range.end.character.cmp(&a.range.end.character))
-    });
-
-    for edit in ordered {
-        let start_byte = lsp_position_to_byte_offset(
-            rope,
-            edit.range.start.line,
-            edit.range.start.character,
-            encoding,
-        );
-        let end_byte = lsp_position_to_byte_offset(
-            rope,
-            edit.range.end.line,
-            edit.range.end.character,
-            encoding,
-        );
-
-        if start_byte == end_byte && edit.new_text.is_empty() {
-            continue;
-        }
-
-        let len_bytes = rope.len_bytes();
-        let start_byte = start_byte.min(len_bytes);
-        let end_byte = end_byte.min(len_bytes);
-
-        let mut start_char = rope.byte_to_char(start_byte);
-        let mut end_char = rope.byte_to_char(end_byte);
-        if start_char > end_char {
-            std::mem::swap(&mut start_char, &mut end_char);
-        }
-
+    for (start_char, end_char, text) in lsp_text_edits_to_char_ranges(rope, edits, encoding) {
         rope.remove(start_char..end_char);
-        if !edit.new_text.is_empty() {
-            rope.insert(start_char, &edit.new_text);
+        if !text.is_empty() {
+            rope.insert(start_char, text);
         }
     }
 }
 
-fn lsp_position_to_byte_offset(
-    rope: &Rope,
-    line: u32,
-    column: u32,
-    encoding: LspPositionEncoding,
-) -> usize {
-    if rope.len_chars() == 0 {
-        return 0;
-    }
-
-    let line_index = (line as usize).min(rope.len_lines().saturating_sub(1));
-    let line_slice = rope.line(line_index);
-    let col_chars = lsp_col_to_char_offset_in_line(line_slice, column, encoding);
-    let line_start = rope.line_to_char(line_index);
-    let line_len = line_len_chars(line_slice);
-    let char_offset = (line_start + col_chars.min(line_len)).min(rope.len_chars());
-    rope.char_to_byte(char_offset)
-}
-
 #[cfg(test)]
 #[path = "../../../../../tests/unit/kernel/services/adapters/runtime/async_runtime.rs"]
 mod tests;
+
+fn send_git_diff_base(tx: &Sender<AppMessage>, path: PathBuf) {
+    match git::index_content(&path) {
+        Ok(content) => {
+            let _ = tx.send(AppMessage::GitDiffBaseLoaded { path, content });
+        }
+        Err(e) => {
+            let _ = tx.send(AppMessage::GitOpError {
+                op: "show_index",
+                error: e.to_string(),
+            });
+        }
+    }
+}
@@ -1,10 +1,38 @@
 use crate::kernel::editor::ReloadRequest;
 use crate::kernel::editor::{SyntaxHighlightPatch, TabId};
 use crate::kernel::services::ports::DirEntryInfo;
-use crate::models::OpId;
+use crate::kernel::{GitBranch, GitStash};
+use crate::models::{EditHistory, OpId};
 use std::path::PathBuf;
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileError {
+    Io(String),
+    /// 文件超过 `EditorConfig::max_file_size_bytes`，未读取内容。
+    TooLarge {
+        size: u64,
+        limit: u64,
+    },
+}
+
+impl std::fmt::Display for FileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileError::Io(error) => f.write_str(error),
+            FileError::TooLarge { size, limit } => {
+                write!(f, "file is {size} bytes, limit is {limit} bytes")
+            }
+        }
+    }
+}
+
 pub enum AppMessage {
+    /// 大目录读取途中的一批条目；目录仍处于 `LoadState::Loading`。
+    DirBatchLoaded {
+        path: PathBuf,
+        entries: Vec<DirEntryInfo>,
+    },
+    /// 最后一批条目，同时表示读取完成。
     DirLoaded {
         path: PathBuf,
         entries: Vec<DirEntryInfo>,
@@ -17,9 +45,17 @@ pub enum AppMessage {
         path: PathBuf,
         content: String,
     },
+    /// 大文件的一块；`end_byte` 是实际读到的位置（截在行尾）。
+    FileRangeLoaded {
+        path: PathBuf,
+        start_byte: u64,
+        end_byte: u64,
+        file_size: u64,
+        content: String,
+    },
     FileError {
         path: PathBuf,
-        error: String,
+        error: FileError,
     },
     FileSaved {
         pane: usize,
@@ -50,9 +86,38 @@ pub enum AppMessage {
         request: ReloadRequest,
         content: String,
     },
+    /// `.ops` 备份里读回、已校验过基线的撤销历史。
+    EditHistoryLoaded {
+        path: PathBuf,
+        history: EditHistory,
+    },
     SyntaxHighlightsComputed {
         tab_id: TabId,
         version: u64,
         patches: Vec<SyntaxHighlightPatch>,
     },
+    GitStashList {
+        stashes: Vec<GitStash>,
+    },
+    GitBranchesUpdated {
+        branches: Vec<GitBranch>,
+    },
+    GitBlameResult {
+        path: PathBuf,
+        line: usize,
+        author: String,
+        timestamp: i64,
+        commit: String,
+    },
+    GitDiffBaseLoaded {
+        path: PathBuf,
+        content: String,
+    },
+    WorkspaceFiles {
+        files: Vec<PathBuf>,
+    },
+    GitOpError {
+        op: &'static str,
+        error: String,
+    },
 }
@@ -3,5 +3,5 @@
 mod async_runtime;
 mod message;
 
-pub use async_runtime::AsyncRuntime;
-pub use message::AppMessage;
+pub use async_runtime::{AsyncRuntime, FilePart};
+pub use message::{AppMessage, FileError};
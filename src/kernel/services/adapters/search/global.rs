@@ -3,10 +3,14 @@
 //! - Literal 模式：流式搜索，8KB 栈 buffer
 //! - Regex 模式：逐行流式搜索
 //! - 使用 ignore crate 的并行遍历，自动利用多核
+//! - 替换模式：只计算每个文件的编辑并上报，不直接写盘
+//! - include / exclude glob：遍历时先按相对路径过滤，不匹配的文件不读
 
 use super::searcher::{SearchConfig, StreamSearcher};
 use crate::core::Service;
 use crate::kernel::services::ports::search::{FileMatches, GlobalSearchMessage, Match};
+use crate::kernel::services::ports::{LspPosition, LspRange, LspTextEdit};
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use ignore::{WalkBuilder, WalkState};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
@@ -57,6 +61,64 @@ impl Default for GlobalSearchTask {
     }
 }
 
+/// 全局搜索 / 替换的文件过滤。每项可用逗号分隔多个 glob，按相对 root 的路径匹配；
+/// 不含 `/` 的 glob（如 `*.rs`、`target`）匹配任意层级的文件名或目录名。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GlobalSearchFilter {
+    /// 非空时只搜匹配的文件。
+    pub include_glob: Option<String>,
+    /// 匹配的文件一律跳过，优先于 `include_glob`。
+    pub exclude_glob: Option<String>,
+}
+
+/// 编译后的 `GlobalSearchFilter`。
+#[derive(Debug, Clone, Default)]
+struct PathFilter {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+impl PathFilter {
+    fn compile(filter: &GlobalSearchFilter) -> Result<Self, globset::Error> {
+        Ok(Self {
+            include: compile_globs(filter.include_glob.as_deref())?,
+            exclude: compile_globs(filter.exclude_glob.as_deref())?,
+        })
+    }
+
+    fn allows(&self, root: &Path, path: &Path) -> bool {
+        let rel = path.strip_prefix(root).unwrap_or(path);
+        if self.exclude.as_ref().is_some_and(|set| set.is_match(rel)) {
+            return false;
+        }
+        self.include.as_ref().is_none_or(|set| set.is_match(rel))
+    }
+}
+
+fn compile_globs(globs: Option<&str>) -> Result<Option<GlobSet>, globset::Error> {
+    let mut builder = GlobSetBuilder::new();
+    let mut any = false;
+    for glob in globs
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|g| !g.is_empty())
+    {
+        let glob = glob.trim_start_matches("./");
+        if glob.contains('/') {
+            builder.add(Glob::new(glob)?);
+        } else {
+            builder.add(Glob::new(&format!("**/{glob}"))?);
+            builder.add(Glob::new(&format!("**/{glob}/**"))?);
+        }
+        any = true;
+    }
+    if !any {
+        return Ok(None);
+    }
+    builder.build().map(Some)
+}
+
 pub struct GlobalSearchService {
     runtime: tokio::runtime::Handle,
 }
@@ -72,8 +134,125 @@ impl GlobalSearchService {
         pattern: String,
         case_sensitive: bool,
         is_regex: bool,
+        skip_binary: bool,
+        tx: SyncSender<GlobalSearchMessage>,
+    ) -> GlobalSearchTask {
+        self.search_in_dir_filtered(
+            root,
+            pattern,
+            case_sensitive,
+            is_regex,
+            skip_binary,
+            GlobalSearchFilter::default(),
+            tx,
+        )
+    }
+
+    /// `search_in_dir` 加上 include / exclude glob；glob 写错时上报一条 `Error`。
+    #[allow(clippy::too_many_arguments)]
+    pub fn search_in_dir_filtered(
+        &self,
+        root: PathBuf,
+        pattern: String,
+        case_sensitive: bool,
+        is_regex: bool,
+        skip_binary: bool,
+        filter: GlobalSearchFilter,
+        tx: SyncSender<GlobalSearchMessage>,
+    ) -> GlobalSearchTask {
+        self.spawn_walk(
+            pattern,
+            case_sensitive,
+            is_regex,
+            filter,
+            tx,
+            move |config, filter, search_id, cancelled, tx| {
+                search_dir_parallel(
+                    &root,
+                    &config,
+                    &filter,
+                    skip_binary,
+                    search_id,
+                    cancelled,
+                    tx,
+                )
+            },
+        )
+    }
+
+    /// 与 `search_in_dir` 相同的遍历规则；每个命中文件上报一条 `Replaced`，
+    /// 携带按 UTF-8 列计的替换编辑。Regex 模式下 `replacement` 支持 `$1` / `${name}`。
+    #[allow(clippy::too_many_arguments)]
+    pub fn replace_in_dir(
+        &self,
+        root: PathBuf,
+        pattern: String,
+        replacement: String,
+        case_sensitive: bool,
+        is_regex: bool,
+        skip_binary: bool,
+        tx: SyncSender<GlobalSearchMessage>,
+    ) -> GlobalSearchTask {
+        self.replace_in_dir_filtered(
+            root,
+            pattern,
+            replacement,
+            case_sensitive,
+            is_regex,
+            skip_binary,
+            GlobalSearchFilter::default(),
+            tx,
+        )
+    }
+
+    /// `replace_in_dir` 加上 include / exclude glob，过滤规则与 `search_in_dir_filtered` 相同。
+    #[allow(clippy::too_many_arguments)]
+    pub fn replace_in_dir_filtered(
+        &self,
+        root: PathBuf,
+        pattern: String,
+        replacement: String,
+        case_sensitive: bool,
+        is_regex: bool,
+        skip_binary: bool,
+        filter: GlobalSearchFilter,
         tx: SyncSender<GlobalSearchMessage>,
     ) -> GlobalSearchTask {
+        self.spawn_walk(
+            pattern,
+            case_sensitive,
+            is_regex,
+            filter,
+            tx,
+            move |config, filter, search_id, cancelled, tx| {
+                replace_dir_parallel(
+                    &root,
+                    &config,
+                    &filter,
+                    &replacement,
+                    skip_binary,
+                    search_id,
+                    cancelled,
+                    tx,
+                )
+            },
+        )
+    }
+
+    fn spawn_walk<F>(
+        &self,
+        pattern: String,
+        case_sensitive: bool,
+        is_regex: bool,
+        filter: GlobalSearchFilter,
+        tx: SyncSender<GlobalSearchMessage>,
+        walk: F,
+    ) -> GlobalSearchTask
+    where
+        F: FnOnce(SearchConfig, PathFilter, u64, &AtomicBool, &SyncSender<GlobalSearchMessage>)
+            + Send
+            + 'static,
+    {
         let task = GlobalSearchTask::new();
         let search_id = task.id();
         let cancelled = task.cancelled_flag();
@@ -103,13 +282,23 @@ impl GlobalSearchService {
             } else {
                 SearchConfig::literal(&pattern, case_sensitive)
             };
+            let filter = match PathFilter::compile(&filter) {
+                Ok(f) => f,
+                Err(e) => {
+                    let _ = tx.send(GlobalSearchMessage::Error {
+                        search_id,
+                        message: format!("Invalid glob: {}", e),
+                    });
+                    return;
+                }
+            };
 
             let cancelled_for_blocking = cancelled.clone();
             let tx_for_blocking = tx.clone();
             let result = tokio::task::spawn_blocking(move || {
-                search_dir_parallel(
-                    &root,
-                    &config,
+                walk(
+                    config,
+                    filter,
                     search_id,
                     &cancelled_for_blocking,
                     &tx_for_blocking,
@@ -147,32 +336,132 @@ fn is_likely_binary(content: &[u8]) -> bool {
 fn search_dir_parallel(
     root: &Path,
     config: &SearchConfig,
+    filter: &PathFilter,
+    skip_binary: bool,
     search_id: u64,
     cancelled: &AtomicBool,
     tx: &SyncSender<GlobalSearchMessage>,
 ) {
     let files_searched = Arc::new(AtomicUsize::new(0));
-    let files_with_matches = Arc::new(AtomicUsize::new(0));
+    let binary_skipped = Arc::new(AtomicUsize::new(0));
     let total_matches = Arc::new(AtomicUsize::new(0));
+    let files_total = AtomicUsize::new(0);
+    let search_done = AtomicBool::new(false);
+
+    std::thread::scope(|scope| {
+        // 文件总数与搜索并行统计，不推迟首批结果。
+        scope.spawn(|| count_files(root, filter, cancelled, &search_done, &files_total));
+
+        walker_for(root).build_parallel().run(|| {
+            // 每个线程的局部状态
+            let config = config.clone();
+            let tx = tx.clone();
+            let files_searched = files_searched.clone();
+            let total_matches = total_matches.clone();
+            let binary_skipped = binary_skipped.clone();
+            let files_total = &files_total;
+
+            Box::new(move |entry| {
+                // 检查取消
+                if cancelled.load(Ordering::Relaxed) {
+                    return WalkState::Quit;
+                }
 
-    // 不读任何 git 相关的 ignore 文件（git 子系统已移除）；仅隐藏 dotfile。
-    let walker = WalkBuilder::new(root)
-        .hidden(true)
-        .git_ignore(false)
-        .git_global(false)
-        .git_exclude(false)
-        .build_parallel();
+                let entry = match entry {
+                    Ok(e) => e,
+                    Err(_) => return WalkState::Continue,
+                };
+
+                let path = entry.path();
+                if !path.is_file() || !filter.allows(root, path) {
+                    return WalkState::Continue;
+                }
+
+                let matches = match search_file(path, &config, skip_binary, cancelled) {
+                    Ok(m) => m,
+                    Err(_) => return WalkState::Continue,
+                };
+
+                if cancelled.load(Ordering::Relaxed) {
+                    return WalkState::Quit;
+                }
+
+                // 进度按已处理的文件数算（含跳过的二进制文件），才能追上 `files_total`。
+                let searched = files_searched.fetch_add(1, Ordering::Relaxed) + 1;
+
+                match matches {
+                    None => {
+                        binary_skipped.fetch_add(1, Ordering::Relaxed);
+                        let _ = tx.send(GlobalSearchMessage::BinaryFileSkipped {
+                            search_id,
+                            path: path.to_path_buf(),
+                        });
+                    }
+                    Some(matches) if !matches.is_empty() => {
+                        total_matches.fetch_add(matches.len(), Ordering::Relaxed);
+
+                        let _ = tx.send(GlobalSearchMessage::FileMatches {
+                            search_id,
+                            file_matches: FileMatches {
+                                path: path.to_path_buf(),
+                                matches,
+                            },
+                        });
+                    }
+                    Some(_) => {}
+                }
+
+                // 每 100 个文件发送进度
+                if searched.is_multiple_of(100) {
+                    let _ = tx.send(GlobalSearchMessage::Progress {
+                        search_id,
+                        files_searched: searched,
+                        files_total: files_total.load(Ordering::Relaxed).max(searched),
+                        matches_so_far: total_matches.load(Ordering::Relaxed),
+                    });
+                }
 
-    walker.run(|| {
-        // 每个线程的局部状态
-        let config = config.clone();
+                WalkState::Continue
+            })
+        });
+
+        search_done.store(true, Ordering::Relaxed);
+    });
+
+    // 发送完成或取消消息
+    if cancelled.load(Ordering::Relaxed) {
+        let _ = tx.send(GlobalSearchMessage::Cancelled { search_id });
+    } else {
+        let _ = tx.send(GlobalSearchMessage::Complete {
+            search_id,
+            total_files: files_searched.load(Ordering::Relaxed)
+                - binary_skipped.load(Ordering::Relaxed),
+            total_matches: total_matches.load(Ordering::Relaxed),
+        });
+    }
+}
+
+/// 并行计算替换编辑；单个文件读取失败只上报 `ReplaceError`，不影响其余文件。
+#[allow(clippy::too_many_arguments)]
+fn replace_dir_parallel(
+    root: &Path,
+    config: &SearchConfig,
+    filter: &PathFilter,
+    replacement: &str,
+    skip_binary: bool,
+    search_id: u64,
+    cancelled: &AtomicBool,
+    tx: &SyncSender<GlobalSearchMessage>,
+) {
+    let files_replaced = AtomicUsize::new(0);
+    let total_replaced = AtomicUsize::new(0);
+
+    walker_for(root).build_parallel().run(|| {
         let tx = tx.clone();
-        let files_searched = files_searched.clone();
-        let files_with_matches = files_with_matches.clone();
-        let total_matches = total_matches.clone();
+        let files_replaced = &files_replaced;
+        let total_replaced = &total_replaced;
 
         Box::new(move |entry| {
-            // 检查取消
             if cancelled.load(Ordering::Relaxed) {
                 return WalkState::Quit;
             }
@@ -183,66 +472,161 @@ fn search_dir_parallel(
             };
 
             let path = entry.path();
-            if !path.is_file() {
+            if !path.is_file() || !filter.allows(root, path) {
                 return WalkState::Continue;
             }
 
-            let matches = match search_file(path, &config, cancelled) {
-                Ok(m) => m,
-                Err(_) => return WalkState::Continue,
-            };
-
-            if cancelled.load(Ordering::Relaxed) {
-                return WalkState::Quit;
-            }
-
-            let searched = files_searched.fetch_add(1, Ordering::Relaxed) + 1;
-
-            if !matches.is_empty() {
-                files_with_matches.fetch_add(1, Ordering::Relaxed);
-                total_matches.fetch_add(matches.len(), Ordering::Relaxed);
-
-                let _ = tx.send(GlobalSearchMessage::FileMatches {
-                    search_id,
-                    file_matches: FileMatches {
+            match replace_edits_for_file(path, config, replacement, skip_binary) {
+                Ok(edits) if edits.is_empty() => {}
+                Ok(edits) => {
+                    files_replaced.fetch_add(1, Ordering::Relaxed);
+                    total_replaced.fetch_add(edits.len(), Ordering::Relaxed);
+                    let _ = tx.send(GlobalSearchMessage::Replaced {
+                        search_id,
                         path: path.to_path_buf(),
-                        matches,
-                    },
-                });
-            }
-
-            // 每 100 个文件发送进度
-            if searched.is_multiple_of(100) {
-                let _ = tx.send(GlobalSearchMessage::Progress {
-                    search_id,
-                    files_searched: searched,
-                    files_with_matches: files_with_matches.load(Ordering::Relaxed),
-                });
+                        count: edits.len(),
+                        edits,
+                    });
+                }
+                Err(e) => {
+                    let _ = tx.send(GlobalSearchMessage::ReplaceError {
+                        search_id,
+                        path: path.to_path_buf(),
+                        error: e.to_string(),
+                    });
+                }
             }
 
             WalkState::Continue
         })
     });
 
-    // 发送完成或取消消息
     if cancelled.load(Ordering::Relaxed) {
         let _ = tx.send(GlobalSearchMessage::Cancelled { search_id });
     } else {
         let _ = tx.send(GlobalSearchMessage::Complete {
             search_id,
-            total_files: files_searched.load(Ordering::Relaxed),
-            total_matches: total_matches.load(Ordering::Relaxed),
+            total_files: files_replaced.load(Ordering::Relaxed),
+            total_matches: total_replaced.load(Ordering::Relaxed),
         });
     }
 }
 
+/// 二进制与非 UTF-8 文件一律不替换（返回空编辑），避免写坏内容。
+fn replace_edits_for_file(
+    path: &Path,
+    config: &SearchConfig,
+    replacement: &str,
+    skip_binary: bool,
+) -> std::io::Result<Vec<LspTextEdit>> {
+    let bytes = std::fs::read(path)?;
+    if bytes.is_empty() || (skip_binary && is_likely_binary(&bytes)) {
+        return Ok(Vec::new());
+    }
+    let Ok(content) = std::str::from_utf8(&bytes) else {
+        return Ok(Vec::new());
+    };
+    replace_edits(content, config, replacement)
+}
+
+/// 计算 `content` 中每处匹配的替换编辑（行 + UTF-8 列）。重叠的字面量匹配只保留靠前的一处，
+/// 空的正则匹配不替换。
+fn replace_edits(
+    content: &str,
+    config: &SearchConfig,
+    replacement: &str,
+) -> std::io::Result<Vec<LspTextEdit>> {
+    let edit = |line: usize, start_col: usize, end_col: usize, new_text: String| LspTextEdit {
+        range: LspRange {
+            start: LspPosition {
+                line: line as u32,
+                character: start_col as u32,
+            },
+            end: LspPosition {
+                line: line as u32,
+                character: end_col as u32,
+            },
+        },
+        new_text,
+    };
+
+    let mut edits = Vec::new();
+    match config {
+        SearchConfig::Literal { .. } => {
+            let mut last_end = 0usize;
+            for m in StreamSearcher::new(content.as_bytes(), config).search()? {
+                if m.start < last_end {
+                    continue;
+                }
+                last_end = m.end;
+                edits.push(edit(
+                    m.line,
+                    m.col,
+                    m.col + (m.end - m.start),
+                    replacement.to_string(),
+                ));
+            }
+        }
+        SearchConfig::Regex { regex } => {
+            for (line_no, line) in content.split('\n').enumerate() {
+                let line = line.strip_suffix('\r').unwrap_or(line);
+                for caps in regex.captures_iter(line) {
+                    let Some(mat) = caps.get(0) else {
+                        continue;
+                    };
+                    if mat.is_empty() {
+                        continue;
+                    }
+                    let mut new_text = String::new();
+                    caps.expand(replacement, &mut new_text);
+                    edits.push(edit(line_no, mat.start(), mat.end(), new_text));
+                }
+            }
+        }
+    }
+    Ok(edits)
+}
+
+// 不读任何 git 相关的 ignore 文件（git 子系统已移除）；仅隐藏 dotfile。
+fn walker_for(root: &Path) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .hidden(true)
+        .git_ignore(false)
+        .git_global(false)
+        .git_exclude(false);
+    builder
+}
+
+/// 单线程统计待搜索的文件数；搜索先结束或被取消时提前退出。
+fn count_files(
+    root: &Path,
+    filter: &PathFilter,
+    cancelled: &AtomicBool,
+    done: &AtomicBool,
+    total: &AtomicUsize,
+) {
+    for entry in walker_for(root).build() {
+        if cancelled.load(Ordering::Relaxed) || done.load(Ordering::Relaxed) {
+            return;
+        }
+        if entry.is_ok_and(|e| {
+            e.file_type().is_some_and(|t| t.is_file()) && filter.allows(root, e.path())
+        }) {
+            total.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// 搜索单个文件；返回 `None` 表示它是二进制文件且按配置跳过。
 fn search_file(
     path: &Path,
     config: &SearchConfig,
+    skip_binary: bool,
     cancelled: &AtomicBool,
-) -> std::io::Result<Vec<Match>> {
+) -> std::io::Result<Option<Vec<Match>>> {
     if cancelled.load(Ordering::Relaxed) {
-        return Ok(Vec::new());
+        return Ok(Some(Vec::new()));
     }
 
     let file = File::open(path)?;
@@ -250,16 +634,18 @@ fn search_file(
     let file_size = metadata.len() as usize;
 
     if file_size == 0 {
-        return Ok(Vec::new());
+        return Ok(Some(Vec::new()));
     }
 
-    let mut preview = [0u8; 8192];
-    let preview_len = std::io::Read::read(&mut &file, &mut preview)?;
-    if is_likely_binary(&preview[..preview_len]) {
-        return Ok(Vec::new());
+    if skip_binary {
+        let mut preview = [0u8; 8192];
+        let preview_len = std::io::Read::read(&mut &file, &mut preview)?;
+        if is_likely_binary(&preview[..preview_len]) {
+            return Ok(None);
+        }
     }
 
-    match config {
+    let matches = match config {
         SearchConfig::Literal { .. } => {
             // Literal 模式：流式搜索
             let file = File::open(path)?;
@@ -272,16 +658,17 @@ fn search_file(
                 || cancelled.load(Ordering::Relaxed),
             )?;
             if cancelled.load(Ordering::Relaxed) {
-                return Ok(Vec::new());
+                return Ok(Some(Vec::new()));
             }
-            Ok(matches)
+            matches
         }
         SearchConfig::Regex { .. } => {
             // Regex 模式：逐行搜索，避免 mmap 在文件被截断时触发 SIGBUS
             let file = File::open(path)?;
-            search_regex_by_line(file, config, cancelled)
+            search_regex_by_line(file, config, cancelled)?
         }
-    }
+    };
+    Ok(Some(matches))
 }
 
 fn search_regex_by_line(
@@ -19,6 +19,6 @@ fn count_byte(haystack: &[u8], needle: u8) -> usize {
     n
 }
 
-pub use global::{GlobalSearchService, GlobalSearchTask};
+pub use global::{GlobalSearchFilter, GlobalSearchService, GlobalSearchTask};
 pub use searcher::{search_regex_in_slice, RopeReader, SearchConfig, StreamSearcher};
 pub use service::{SearchService, SearchTask};
@@ -42,6 +42,21 @@ impl SearchConfig {
         Ok(Self::Regex { regex })
     }
 
+    /// 全词匹配：字面量先转义，再在两侧加 `\b` 边界编译成正则。
+    /// 流式字面量搜索看不到匹配两侧的字符，判断不了边界，所以统一走正则。
+    pub fn whole_word(
+        pattern: &str,
+        case_sensitive: bool,
+        is_regex: bool,
+    ) -> Result<Self, regex::Error> {
+        let inner = if is_regex {
+            pattern.to_string()
+        } else {
+            regex::escape(pattern)
+        };
+        Self::regex(&format!(r"\b(?:{inner})\b"), case_sensitive)
+    }
+
     pub fn is_regex(&self) -> bool {
         matches!(self, Self::Regex { .. })
     }
@@ -68,6 +68,7 @@ impl SearchService {
         pattern: String,
         case_sensitive: bool,
         is_regex: bool,
+        whole_word: bool,
         tx: SyncSender<SearchMessage>,
     ) -> SearchTask {
         let task = SearchTask::new();
@@ -86,8 +87,13 @@ impl SearchService {
             }
 
             // 编译搜索配置
-            let config = if is_regex {
-                match SearchConfig::regex(&pattern, case_sensitive) {
+            let config = if is_regex || whole_word {
+                let compiled = if whole_word {
+                    SearchConfig::whole_word(&pattern, case_sensitive, is_regex)
+                } else {
+                    SearchConfig::regex(&pattern, case_sensitive)
+                };
+                match compiled {
                     Ok(c) => c,
                     Err(e) => {
                         let _ = tx_for_complete.send(SearchMessage::Error {
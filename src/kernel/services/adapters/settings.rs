@@ -1,15 +1,20 @@
 use crate::core::event::Key;
 use crate::core::event::{KeyCode, KeyModifiers};
 use crate::core::Command;
-use crate::kernel::services::ports::settings::Settings;
+use crate::kernel::services::ports::settings::{KeybindingRule, Settings};
 use crate::kernel::store::CompletionRanker;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const SETTINGS_DIR: &str = ".zcode";
 const SETTINGS_FILE: &str = "setting.json";
 const COMPLETION_RANK_FILE: &str = "completion_rank.json";
+const SESSION_FILE: &str = "session.json";
+const RECENT_FILES_FILE: &str = "recent.json";
+const TEMPLATES_DIR: &str = "templates";
 
 pub fn get_settings_path() -> Option<PathBuf> {
     get_cache_dir().map(|dir| dir.join(SETTINGS_DIR).join(SETTINGS_FILE))
@@ -41,6 +46,56 @@ pub fn load_settings() -> Option<Settings> {
     serde_json::from_str(&data).ok()
 }
 
+/// 只改写 `setting.json` 里的 `ui.sidebar_width`，其余字段（包括本版本不认识的）原样保留。
+/// 文件不是合法 JSON 时拒绝写入，免得覆盖用户手写的配置。
+pub fn save_sidebar_width(path: &Path, width: u16) -> std::io::Result<()> {
+    let mut root = match std::fs::read_to_string(path) {
+        Ok(data) => serde_json::from_str::<serde_json::Value>(&data)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => serde_json::json!({}),
+        Err(err) => return Err(err),
+    };
+    let Some(object) = root.as_object_mut() else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "settings root is not a JSON object",
+        ));
+    };
+    let ui = object.entry("ui").or_insert_with(|| serde_json::json!({}));
+    let Some(ui) = ui.as_object_mut() else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "settings `ui` is not a JSON object",
+        ));
+    };
+    ui.remove("sidebarWidth");
+    ui.insert("sidebar_width".to_string(), serde_json::json!(width));
+
+    let json = serde_json::to_string_pretty(&root).map_err(std::io::Error::other)?;
+    std::fs::write(path, json)
+}
+
+/// 用 `rules` 整体替换 `setting.json` 里的 `keybindings`，其余字段原样保留；非法 JSON 时拒绝写入。
+pub fn save_keybindings(path: &Path, rules: &[KeybindingRule]) -> std::io::Result<()> {
+    let mut root = match std::fs::read_to_string(path) {
+        Ok(data) => serde_json::from_str::<serde_json::Value>(&data)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => serde_json::json!({}),
+        Err(err) => return Err(err),
+    };
+    let Some(object) = root.as_object_mut() else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "settings root is not a JSON object",
+        ));
+    };
+    let rules = serde_json::to_value(rules).map_err(std::io::Error::other)?;
+    object.insert("keybindings".to_string(), rules);
+
+    let json = serde_json::to_string_pretty(&root).map_err(std::io::Error::other)?;
+    std::fs::write(path, json)
+}
+
 pub fn parse_keybinding(value: &str) -> Option<Key> {
     let mut modifiers = KeyModifiers::NONE;
     let mut key_part: Option<&str> = None;
@@ -64,6 +119,48 @@ pub fn parse_keybinding(value: &str) -> Option<Key> {
     Some(Key::new(code, modifiers))
 }
 
+/// `parse_keybinding` 的逆：`Key` → `"ctrl+shift+p"`。
+pub fn format_keybinding(key: &Key) -> String {
+    let mut out = String::new();
+    for (modifier, name) in [
+        (KeyModifiers::CONTROL, "ctrl"),
+        (KeyModifiers::SHIFT, "shift"),
+        (KeyModifiers::ALT, "alt"),
+        (KeyModifiers::SUPER, "super"),
+    ] {
+        if key.modifiers.contains(modifier) {
+            out.push_str(name);
+            out.push('+');
+        }
+    }
+    match key.code {
+        KeyCode::Char(' ') => out.push_str("space"),
+        KeyCode::Char(ch) => out.push(ch),
+        KeyCode::F(n) => {
+            out.push('f');
+            out.push_str(&n.to_string());
+        }
+        code => out.push_str(match code {
+            KeyCode::Enter => "enter",
+            KeyCode::Tab => "tab",
+            KeyCode::BackTab => "backtab",
+            KeyCode::Esc => "esc",
+            KeyCode::Backspace => "backspace",
+            KeyCode::Delete => "delete",
+            KeyCode::Up => "up",
+            KeyCode::Down => "down",
+            KeyCode::Left => "left",
+            KeyCode::Right => "right",
+            KeyCode::Home => "home",
+            KeyCode::End => "end",
+            KeyCode::PageUp => "pageup",
+            KeyCode::PageDown => "pagedown",
+            _ => "unknown",
+        }),
+    }
+    out
+}
+
 pub fn parse_command(value: &str) -> Command {
     Command::from_name(value)
 }
@@ -91,7 +188,7 @@ fn parse_key_code(value: &str) -> Option<KeyCode> {
         "pageup" => KeyCode::PageUp,
         "pagedown" => KeyCode::PageDown,
         "space" => KeyCode::Char(' '),
-        _ if v_lc.starts_with('f') => {
+        _ if v_lc.len() > 1 && v_lc.starts_with('f') => {
             let n = v_lc.strip_prefix('f')?.parse::<u8>().ok()?;
             KeyCode::F(n)
         }
@@ -146,6 +243,143 @@ pub fn save_completion_ranker(ranker: &CompletionRanker) -> bool {
     writer.flush().is_ok()
 }
 
+pub fn recent_files_path() -> Option<PathBuf> {
+    get_cache_dir().map(|dir| dir.join(SETTINGS_DIR).join(RECENT_FILES_FILE))
+}
+
+/// 最近打开的文件列表，队首最新；读不到或格式不对时为空。
+pub fn load_recent_files() -> VecDeque<PathBuf> {
+    let Some(path) = recent_files_path() else {
+        return VecDeque::new();
+    };
+    let Ok(file) = File::open(path) else {
+        return VecDeque::new();
+    };
+    serde_json::from_reader(BufReader::new(file)).unwrap_or_default()
+}
+
+pub fn save_recent_files(files: &VecDeque<PathBuf>) -> bool {
+    let Some(path) = recent_files_path() else {
+        return false;
+    };
+    if let Some(parent) = path.parent() {
+        if !parent.exists() && std::fs::create_dir_all(parent).is_err() {
+            return false;
+        }
+    }
+
+    let Ok(file) = File::create(path) else {
+        return false;
+    };
+    let mut writer = BufWriter::new(file);
+    if serde_json::to_writer(&mut writer, files).is_err() {
+        return false;
+    }
+
+    writer.flush().is_ok()
+}
+
+/// 会话文件按工作区根目录分条保存，各工作区互不覆盖。
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SessionFile {
+    #[serde(default)]
+    workspaces: HashMap<PathBuf, WorkspaceSession>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceSession {
+    #[serde(default)]
+    pub recently_closed_tabs: VecDeque<(PathBuf, usize)>,
+    /// 退出时打开着的文件，按 pane 内标签页顺序排列；`EditorConfig::restore_session` 打开时下次启动重新打开。
+    #[serde(default)]
+    pub open_files: Vec<SessionOpenFile>,
+    /// 各文件上次的光标位置（行, 列），最近记下的在前；重新打开时恢复。
+    #[serde(default)]
+    pub file_positions: VecDeque<(PathBuf, (u32, u32))>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionOpenFile {
+    pub pane: usize,
+    pub path: PathBuf,
+    pub cursor_byte: usize,
+    /// 该 pane 退出时的活动标签页。
+    #[serde(default)]
+    pub active: bool,
+}
+
+pub fn session_path() -> Option<PathBuf> {
+    get_cache_dir().map(|dir| dir.join(SETTINGS_DIR).join(SESSION_FILE))
+}
+
+fn read_session_file(path: &Path) -> SessionFile {
+    let Ok(file) = File::open(path) else {
+        return SessionFile::default();
+    };
+    serde_json::from_reader(BufReader::new(file)).unwrap_or_default()
+}
+
+pub fn load_workspace_session(workspace_root: &Path) -> WorkspaceSession {
+    let Some(path) = session_path() else {
+        return WorkspaceSession::default();
+    };
+    read_session_file(&path)
+        .workspaces
+        .remove(workspace_root)
+        .unwrap_or_default()
+}
+
+pub fn save_workspace_session(workspace_root: &Path, session: &WorkspaceSession) -> bool {
+    let Some(path) = session_path() else {
+        return false;
+    };
+    if let Some(parent) = path.parent() {
+        if !parent.exists() && std::fs::create_dir_all(parent).is_err() {
+            return false;
+        }
+    }
+
+    let mut file_content = read_session_file(&path);
+    file_content
+        .workspaces
+        .insert(workspace_root.to_path_buf(), session.clone());
+
+    // 先写临时文件再 rename，退出时被打断也不会留下半截的会话文件。
+    let tmp_path = path.with_extension("json.tmp");
+    let Ok(file) = File::create(&tmp_path) else {
+        return false;
+    };
+    let mut writer = BufWriter::new(file);
+    if serde_json::to_writer(&mut writer, &file_content).is_err() || writer.flush().is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+        return false;
+    }
+    drop(writer);
+
+    std::fs::rename(&tmp_path, &path).is_ok()
+}
+
+/// 新建文件模板所在目录：`<设置目录>/templates/`。
+pub fn templates_dir() -> Option<PathBuf> {
+    get_cache_dir().map(|dir| dir.join(SETTINGS_DIR).join(TEMPLATES_DIR))
+}
+
+/// 新建 `path` 时套用的模板内容：`templates/<languageId>.<扩展名>`（如 `rust.rs`）。
+/// 语言不认识、没有扩展名或模板不存在时返回 `None`，调用方建空文件。
+pub fn load_file_template(path: &Path) -> Option<String> {
+    read_file_template(&templates_dir()?, path)
+}
+
+fn read_file_template(dir: &Path, path: &Path) -> Option<String> {
+    std::fs::read_to_string(dir.join(template_file_name(path)?)).ok()
+}
+
+fn template_file_name(path: &Path) -> Option<String> {
+    let language = crate::kernel::language::LanguageId::from_path(path)?;
+    let ext = path.extension()?.to_str()?;
+    Some(format!("{}.{ext}", language.language_id()))
+}
+
 fn get_cache_dir() -> Option<PathBuf> {
     #[cfg(target_os = "macos")]
     {
@@ -179,3 +413,7 @@ fn get_cache_dir() -> Option<PathBuf> {
         None
     }
 }
+
+#[cfg(test)]
+#[path = "../../../../tests/unit/kernel/services/adapters/settings.rs"]
+mod tests;
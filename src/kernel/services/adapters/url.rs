@@ -0,0 +1,35 @@
+//! 用系统默认程序打开 URL（文档链接 Ctrl+Click）。
+
+use std::io;
+use std::process::{Command, Stdio};
+
+pub fn open_url(url: &str) -> io::Result<()> {
+    let mut cmd = opener_command(url);
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+#[cfg(target_os = "macos")]
+fn opener_command(url: &str) -> Command {
+    let mut cmd = Command::new("open");
+    cmd.arg(url);
+    cmd
+}
+
+#[cfg(windows)]
+fn opener_command(url: &str) -> Command {
+    // `start` 的第一个带引号参数是窗口标题，留空避免 URL 被当作标题吞掉。
+    let mut cmd = Command::new("cmd");
+    cmd.args(["/C", "start", ""]).arg(url);
+    cmd
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn opener_command(url: &str) -> Command {
+    let mut cmd = Command::new("xdg-open");
+    cmd.arg(url);
+    cmd
+}
@@ -2,6 +2,8 @@ use crate::core::service::Result as ServiceResult;
 use crate::core::{Service, ServiceRegistry};
 
 use super::bus::{kernel_bus, KernelBusReceiver, KernelBusSender, KernelMessage};
+use super::middleware::KernelMiddleware;
+use std::any::Any;
 use std::sync::mpsc::TryRecvError;
 
 use crate::core::wakeup::WakeupSender;
@@ -10,6 +12,7 @@ pub struct KernelServiceHost {
     registry: ServiceRegistry,
     bus: KernelBusSender,
     rx: KernelBusReceiver,
+    middleware: Vec<Box<KernelMiddleware>>,
 }
 
 #[derive(Clone)]
@@ -24,6 +27,7 @@ impl KernelServiceHost {
             registry: ServiceRegistry::new(),
             bus,
             rx,
+            middleware: Vec::new(),
         }
     }
 
@@ -45,6 +49,21 @@ impl KernelServiceHost {
         self.registry.get_mut::<S>()
     }
 
+    /// 中间件按注册顺序串在 dispatch 管线上（见 `Store::dispatch_through`）。
+    pub fn register_middleware(&mut self, middleware: Box<KernelMiddleware>) {
+        self.middleware.push(middleware);
+    }
+
+    pub fn middleware<M: 'static>(&self) -> Option<&M> {
+        self.middleware
+            .iter()
+            .find_map(|m| (m.as_ref() as &dyn Any).downcast_ref::<M>())
+    }
+
+    pub fn middleware_mut(&mut self) -> &mut [Box<KernelMiddleware>] {
+        &mut self.middleware
+    }
+
     pub fn try_recv(&mut self) -> Result<KernelMessage, TryRecvError> {
         self.rx.try_recv()
     }
@@ -0,0 +1,76 @@
+//! 内核分发中间件：挂在 `Store::dispatch_through` 上的横切逻辑（日志、分发统计）。
+
+use std::collections::BTreeMap;
+
+use crate::core::ServiceMiddleware;
+use crate::kernel::store::dispatch_label;
+use crate::kernel::{Action, DispatchResult};
+
+/// 内核里的中间件对象类型，由 `KernelServiceHost::register_middleware` 持有。
+pub type KernelMiddleware = dyn ServiceMiddleware<Action, DispatchResult>;
+
+/// 按动作类型统计分发次数（`RunCommand` 细分到具体命令），供 `Command::ShowMetrics` 输出。
+#[derive(Debug, Default)]
+pub struct MetricsMiddleware {
+    counts: BTreeMap<&'static str, u64>,
+}
+
+impl MetricsMiddleware {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn count(&self, label: &str) -> u64 {
+        self.counts.get(label).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// 按次数降序（同次数按标签）排列的统计。
+    pub fn sorted_counts(&self) -> Vec<(&'static str, u64)> {
+        let mut counts: Vec<_> = self
+            .counts
+            .iter()
+            .map(|(label, count)| (*label, *count))
+            .collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        counts
+    }
+}
+
+impl ServiceMiddleware<Action, DispatchResult> for MetricsMiddleware {
+    fn before_dispatch(&mut self, action: &Action) -> Option<Action> {
+        *self.counts.entry(dispatch_label(action)).or_default() += 1;
+        None
+    }
+}
+
+/// 把每次分发的动作和 `state_changed` 写进日志（target `zcode::dispatch`）。
+#[cfg(feature = "debug")]
+#[derive(Debug, Default)]
+pub struct LoggingMiddleware {
+    pending: Option<String>,
+}
+
+#[cfg(feature = "debug")]
+impl ServiceMiddleware<Action, DispatchResult> for LoggingMiddleware {
+    fn before_dispatch(&mut self, action: &Action) -> Option<Action> {
+        self.pending = Some(format!("{action:?}"));
+        None
+    }
+
+    fn after_dispatch(&mut self, result: &DispatchResult) {
+        let action = self.pending.take().unwrap_or_default();
+        tracing::debug!(
+            target: "zcode::dispatch",
+            state_changed = result.state_changed,
+            "{action}"
+        );
+    }
+}
+
+#[cfg(test)]
+#[path = "../../../tests/unit/kernel/services/middleware.rs"]
+mod tests;
@@ -7,9 +7,13 @@
 pub mod adapters;
 pub mod bus;
 pub mod host;
+pub mod middleware;
 pub mod ports;
 
 pub use bus::{
     kernel_bus, KernelBusReceiver, KernelBusSender, KernelMessage, KernelMessagePayload,
 };
 pub use host::{KernelServiceContext, KernelServiceHost};
+#[cfg(feature = "debug")]
+pub use middleware::LoggingMiddleware;
+pub use middleware::{KernelMiddleware, MetricsMiddleware};
@@ -1,22 +1,132 @@
+use crate::models::LineEndingStyle;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(default)]
 pub struct EditorConfig {
     pub tab_size: u8,
+    /// Tab 键与自动缩进插入硬 Tab 还是 `tab_size` 个空格；打开文件时按前 200 行的缩进自动判断，
+    /// 判断不出时沿用此项。
+    #[serde(default, alias = "tabType")]
+    pub tab_type: TabType,
+    /// 新建文件、以及开头没有换行可供判断的文件保存时使用的换行风格。
+    #[serde(default, alias = "defaultLineEnding")]
+    pub default_line_ending: LineEndingStyle,
     pub default_viewport_height: usize,
     pub double_click_ms: u64,
     pub triple_click_ms: u64,
     pub click_slop: u16,
     pub scroll_lines: usize,
+    #[serde(
+        default = "default_horizontal_scroll_step",
+        alias = "horizontalScrollStep"
+    )]
+    pub horizontal_scroll_step: usize,
     pub show_line_numbers: bool,
     pub auto_indent: bool,
+    /// 键入 `(` / `[` / `{` / 引号时自动补上闭合符；字符串与注释里不补。
+    #[serde(default = "default_auto_pairs", alias = "autoPairs")]
+    pub auto_pairs: bool,
     #[serde(default = "default_show_indent_guides", alias = "showIndentGuides")]
     pub show_indent_guides: bool,
     #[serde(default, alias = "lspInputTiming")]
     pub lsp_input_timing: LspInputTimingConfig,
     #[serde(default, alias = "lspHover")]
     pub lsp_hover: LspHoverConfig,
+    /// 用户自定义 snippet：触发词 → VS Code 风格模板（`$1`、`${2:name}`、`$0`）。
+    /// 在触发词后键入空格或回车时展开。
+    #[serde(default)]
+    pub snippets: HashMap<String, String>,
+    /// 打开文件的大小上限（字节）。超出时先弹确认框，避免一次性读入超大日志撑爆内存。
+    #[serde(default = "default_max_file_size_bytes", alias = "maxFileSizeBytes")]
+    pub max_file_size_bytes: usize,
+    /// 达到该大小（字节）的文件保存时只改写脏区间（`Effect::WriteFilePart`），小文件整体重写。
+    #[serde(
+        default = "default_partial_write_threshold_bytes",
+        alias = "partialWriteThresholdBytes"
+    )]
+    pub partial_write_threshold_bytes: usize,
+    /// 达到该大小（MB）的文件按块读入（`Effect::LoadFileRange`），滚到末尾附近再读下一块；
+    /// 文件树里这类文件的大小以警告色显示。
+    #[serde(
+        default = "default_large_file_threshold_mb",
+        alias = "largeFileThresholdMb"
+    )]
+    pub large_file_threshold_mb: usize,
+    /// 光标停留 1 秒后在当前行末尾以暗色虚拟文本显示 `git blame` 结论。
+    #[serde(default, alias = "gitBlameInline")]
+    pub git_blame_inline: bool,
+    /// 全局搜索跳过二进制文件（前 8KB 含 NUL 字节）。
+    #[serde(default = "default_search_skip_binary", alias = "searchSkipBinary")]
+    pub search_skip_binary: bool,
+    /// Vim 模态编辑：编辑区默认处于 Normal 模式，`i` / `a` / `o` 进入 Insert。
+    #[serde(default, alias = "vimMode")]
+    pub vim_mode: bool,
+    /// 磁盘上的文件被外部改动时，未修改的标签页直接重新加载；关闭后改为在状态栏提示，
+    /// 由用户执行 `reloadFromDisk` 决定是否加载。
+    #[serde(
+        default = "default_auto_reload_on_disk_change",
+        alias = "autoReloadOnDiskChange"
+    )]
+    pub auto_reload_on_disk_change: bool,
+    /// 启动时重新打开上次退出时打开着的文件，并放回光标。
+    #[serde(default = "default_restore_session", alias = "restoreSession")]
+    pub restore_session: bool,
+    /// 补全列表把同类文件里最近接受过的条目排在前面。
+    #[serde(
+        default = "default_completion_boost_recent",
+        alias = "completionBoostRecent"
+    )]
+    pub completion_boost_recent: bool,
+    /// 补全列表最多同时显示的条目数，超出时随选中项滚动。
+    #[serde(
+        default = "default_completion_popup_max_lines",
+        alias = "completionPopupMaxLines"
+    )]
+    pub completion_popup_max_lines: usize,
+    /// 编辑区右侧显示文档缩略图（minimap），点击可跳到对应位置。
+    #[serde(default, alias = "minimapEnabled")]
+    pub minimap_enabled: bool,
+    /// 标签栏下方一行面包屑：文件名 + 光标处的符号作用域链，点击一段选中该符号。
+    #[serde(default, alias = "breadcrumbsEnabled")]
+    pub breadcrumbs_enabled: bool,
+    /// 软换行：长行按编辑区宽度折成多个显示行，不再横向滚动（Markdown 标签页除外）。
+    #[serde(default, alias = "wordWrap")]
+    pub word_wrap: bool,
+    /// 行号旁多占一列，标出相对已保存内容新增 / 修改 / 删除的行。
+    #[serde(default, alias = "showUnsavedGutterMarks")]
+    pub show_unsaved_gutter_marks: bool,
+    /// 不换行时在编辑区底部留一行横向滚动条，并在状态栏显示横向滚动百分比。
+    #[serde(default, alias = "horizScrollIndicatorEnabled")]
+    pub horiz_scroll_indicator_enabled: bool,
+    /// 保存前先请求 LSP 格式化整份文档，格式化结果应用后再写盘；服务端不支持格式化时直接保存。
+    #[serde(default, alias = "formatOnSave")]
+    pub format_on_save: bool,
+    /// 在这些显示列（从 0 起）处画竖向标尺线，例如 `[80, 120]`；画在文字下层。
+    #[serde(default, alias = "rulerColumns")]
+    pub ruler_columns: Vec<u16>,
+    /// 复制走哪条路：系统剪贴板工具，或写 OSC 52 转义序列让终端代为复制（SSH 远程时有用）。
+    #[serde(default, alias = "clipboardBackend")]
+    pub clipboard_backend: ClipboardBackend,
+}
+
+/// `auto`：SSH 会话里先走 OSC 52，否则先走系统剪贴板；首选失败时两者互为兜底。
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ClipboardBackend {
+    #[default]
+    Auto,
+    System,
+    Osc52,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TabType {
+    Spaces,
+    #[default]
+    Tabs,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -61,6 +171,46 @@ fn default_show_indent_guides() -> bool {
     true
 }
 
+fn default_auto_pairs() -> bool {
+    true
+}
+
+fn default_auto_reload_on_disk_change() -> bool {
+    true
+}
+
+fn default_restore_session() -> bool {
+    true
+}
+
+fn default_completion_boost_recent() -> bool {
+    true
+}
+
+fn default_completion_popup_max_lines() -> usize {
+    10
+}
+
+fn default_horizontal_scroll_step() -> usize {
+    4
+}
+
+fn default_max_file_size_bytes() -> usize {
+    10 * 1024 * 1024
+}
+
+fn default_partial_write_threshold_bytes() -> usize {
+    10 * 1024 * 1024
+}
+
+fn default_large_file_threshold_mb() -> usize {
+    50
+}
+
+fn default_search_skip_binary() -> bool {
+    true
+}
+
 fn default_boundary_chars() -> String {
     " \t\n.,;:()[]{}".to_string()
 }
@@ -123,16 +273,39 @@ impl Default for EditorConfig {
     fn default() -> Self {
         Self {
             tab_size: 4,
+            tab_type: TabType::default(),
+            default_line_ending: LineEndingStyle::default(),
             default_viewport_height: 20,
             double_click_ms: 300,
             triple_click_ms: 450,
             click_slop: 2,
             scroll_lines: 1,
+            horizontal_scroll_step: default_horizontal_scroll_step(),
             show_line_numbers: true,
             auto_indent: true,
+            auto_pairs: default_auto_pairs(),
             show_indent_guides: default_show_indent_guides(),
             lsp_input_timing: LspInputTimingConfig::default(),
             lsp_hover: LspHoverConfig::default(),
+            snippets: HashMap::new(),
+            max_file_size_bytes: default_max_file_size_bytes(),
+            partial_write_threshold_bytes: default_partial_write_threshold_bytes(),
+            large_file_threshold_mb: default_large_file_threshold_mb(),
+            git_blame_inline: false,
+            search_skip_binary: default_search_skip_binary(),
+            vim_mode: false,
+            auto_reload_on_disk_change: default_auto_reload_on_disk_change(),
+            restore_session: default_restore_session(),
+            completion_boost_recent: default_completion_boost_recent(),
+            completion_popup_max_lines: default_completion_popup_max_lines(),
+            minimap_enabled: false,
+            breadcrumbs_enabled: false,
+            word_wrap: false,
+            show_unsaved_gutter_marks: false,
+            horiz_scroll_indicator_enabled: false,
+            format_on_save: false,
+            ruler_columns: Vec::new(),
+            clipboard_backend: ClipboardBackend::default(),
         }
     }
 }
@@ -141,6 +314,33 @@ impl EditorConfig {
     pub fn scroll_step(&self) -> usize {
         self.scroll_lines
     }
+
+    pub fn horizontal_scroll_columns(&self) -> usize {
+        self.horizontal_scroll_step.max(1)
+    }
+
+    /// 叠加某个 pane 的覆盖项，得到该 pane 的有效配置。
+    pub fn merged_with(&self, overrides: Option<&PartialEditorConfig>) -> EditorConfig {
+        let mut config = self.clone();
+        if let Some(overrides) = overrides {
+            if let Some(tab_size) = overrides.tab_size {
+                config.tab_size = tab_size;
+            }
+        }
+        config
+    }
+}
+
+/// 单个编辑器 pane 的配置覆盖：`None` 的字段沿用全局 `EditorConfig`。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialEditorConfig {
+    pub tab_size: Option<u8>,
+}
+
+impl PartialEditorConfig {
+    pub fn is_empty(&self) -> bool {
+        self.tab_size.is_none()
+    }
 }
 
 #[cfg(test)]
@@ -1,8 +1,11 @@
-//! 目录加载的传输载荷：`AsyncRuntime::load_dir` 产出，经 `AppMessage::DirLoaded`
-//! / `Action::DirLoaded` 传到 `state.apply_dir_loaded` 消费的最小目录项契约。
+//! 目录加载的传输载荷：`AsyncRuntime::load_dir` 边读边产出，大目录先经若干
+//! `AppMessage::DirBatchLoaded` 分批送达，最后一批由 `AppMessage::DirLoaded` 带上并标记完成；
+//! 两者都转成同名 `Action`，由 `state.apply_dir_batch_loaded` / `apply_dir_loaded` 消费。
 
 #[derive(Debug, Clone)]
 pub struct DirEntryInfo {
     pub name: String,
     pub is_dir: bool,
+    /// 文件大小（字节）；目录或读不到元数据时为 `None`。
+    pub size: Option<u64>,
 }
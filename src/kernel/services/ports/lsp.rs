@@ -1,8 +1,9 @@
 //! LSP data contracts used across kernel + adapters.
 
-use ropey::RopeSlice;
+use ropey::{Rope, RopeSlice};
 use serde_json::Value;
 use std::path::PathBuf;
+use std::time::Instant;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum LspServerKind {
@@ -12,6 +13,19 @@ pub enum LspServerKind {
     TypeScriptLanguageServer,
     Clangd,
     Jdtls,
+    ProtoLanguageServer,
+    GroovyLanguageServer,
+    Regal,
+    NomicFoundryVsCode,
+    CairoLs,
+    Solargraph,
+    LuaLanguageServer,
+    ElixirLs,
+    ErlangLs,
+    KotlinLs,
+    Sourcekit,
+    Nil,
+    DhallLsp,
 }
 
 impl LspServerKind {
@@ -36,9 +50,52 @@ impl LspServerKind {
             "clangd" | "c" | "cpp" | "c++" => Some(Self::Clangd),
             // Java
             "jdtls" | "java" => Some(Self::Jdtls),
+            // Protobuf
+            "protols" | "proto" | "protobuf" => Some(Self::ProtoLanguageServer),
+            // Groovy / Jenkinsfile
+            "groovy-language-server" | "groovyls" | "groovy" => Some(Self::GroovyLanguageServer),
+            // Rego (OPA)
+            "regal" | "rego" | "opa" => Some(Self::Regal),
+            // Solidity
+            "nomicfoundation-solidity-language-server" | "solidity" | "solc" | "sol" => {
+                Some(Self::NomicFoundryVsCode)
+            }
+            // Cairo (Starknet)
+            "cairo-language-server" | "cairo_ls" | "cairo" | "scarb" => Some(Self::CairoLs),
+            // Ruby
+            "solargraph" | "ruby" | "rb" => Some(Self::Solargraph),
+            // Lua
+            "lua-language-server" | "lua_ls" | "lua" => Some(Self::LuaLanguageServer),
+            // Elixir
+            "elixir-ls" | "elixir_ls" | "elixirls" | "elixir" => Some(Self::ElixirLs),
+            // Erlang
+            "erlang_ls" | "erlang-ls" | "erlangls" | "erlang" => Some(Self::ErlangLs),
+            // Kotlin
+            "kotlin-language-server" | "kotlin_language_server" | "kotlin" | "kt" => {
+                Some(Self::KotlinLs)
+            }
+            // Swift
+            "sourcekit-lsp" | "sourcekit" | "swift" => Some(Self::Sourcekit),
+            // Nix
+            "nil" | "nix" => Some(Self::Nil),
+            // Dhall
+            "dhall-lsp-server" | "dhall-lsp" | "dhall_lsp" | "dhall" => Some(Self::DhallLsp),
             _ => None,
         }
     }
+
+    /// 服务端能否在单个进程内通过 `workspace/didChangeWorkspaceFolders` 服务多个根；
+    /// 为真时不同 language root 复用同一个客户端。
+    pub fn supports_multi_root(self) -> bool {
+        matches!(
+            self,
+            Self::RustAnalyzer
+                | Self::Gopls
+                | Self::Pyright
+                | Self::TypeScriptLanguageServer
+                | Self::LuaLanguageServer
+        )
+    }
 }
 
 /// Identifies one LSP client (and its capability set) by `(server kind, root)`.
@@ -51,6 +108,28 @@ pub struct LspClientKey {
     pub root: PathBuf,
 }
 
+/// 服务端进程崩溃后的重连状态；重新拿到 `initialize` 能力即视为已连上。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LspConnectionStatus {
+    /// 第 `attempt` 次重启排在 `retry_at`。
+    Reconnecting { attempt: u32, retry_at: Instant },
+    /// 重试次数用尽，不再自动重启。
+    Failed,
+}
+
+impl LspConnectionStatus {
+    /// 距下次重启还剩几秒（向上取整），状态栏按整秒倒数；`Failed` 为 `None`。
+    pub fn retry_in_secs(&self, now: Instant) -> Option<u64> {
+        match self {
+            Self::Reconnecting { retry_at, .. } => {
+                let wait = retry_at.saturating_duration_since(now);
+                Some(wait.as_secs() + u64::from(wait.subsec_nanos() > 0))
+            }
+            Self::Failed => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum LspPositionEncoding {
     Utf8,
@@ -130,6 +209,69 @@ pub fn column_for_chars(
     }
 }
 
+/// 把 LSP 位置换算成字符偏移；越界行钳到末行，越界列钳到行尾。
+pub fn lsp_position_to_char_offset(
+    rope: &Rope,
+    line: u32,
+    column: u32,
+    encoding: LspPositionEncoding,
+) -> usize {
+    if rope.len_chars() == 0 {
+        return 0;
+    }
+
+    let line_index = (line as usize).min(rope.len_lines().saturating_sub(1));
+    let line_slice = rope.line(line_index);
+    let col_chars = lsp_col_to_char_offset_in_line(line_slice, column, encoding);
+    let line_start = rope.line_to_char(line_index);
+    (line_start + col_chars.min(line_len_chars(line_slice))).min(rope.len_chars())
+}
+
+/// 把一组 LSP 编辑换算成 `(start_char, end_char, new_text)`，按起点降序排列并跳过空操作。
+/// 按返回顺序逐个应用时，后面的偏移不受前面编辑影响；同一位置的多个插入按原数组顺序出现在结果里。
+/// 协议不允许重叠的编辑，真遇到时把前者截断在后者起点，保证结果可直接作为 `OpKind::Batch`。
+pub fn lsp_text_edits_to_char_ranges<'a>(
+    rope: &Rope,
+    edits: &'a [LspTextEdit],
+    encoding: LspPositionEncoding,
+) -> Vec<(usize, usize, &'a str)> {
+    let mut ranges = edits
+        .iter()
+        .enumerate()
+        .filter_map(|(index, edit)| {
+            let start = lsp_position_to_char_offset(
+                rope,
+                edit.range.start.line,
+                edit.range.start.character,
+                encoding,
+            );
+            let end = lsp_position_to_char_offset(
+                rope,
+                edit.range.end.line,
+                edit.range.end.character,
+                encoding,
+            );
+            let (start, end) = (start.min(end), start.max(end));
+            (start != end || !edit.new_text.is_empty()).then_some((
+                index,
+                start,
+                end,
+                edit.new_text.as_str(),
+            ))
+        })
+        .collect::<Vec<_>>();
+    ranges.sort_by(|a, b| (b.1, b.2, b.0).cmp(&(a.1, a.2, a.0)));
+    let mut limit = usize::MAX;
+    ranges
+        .into_iter()
+        .filter_map(|(_, start, end, text)| {
+            let end = end.min(limit);
+            limit = start;
+            (start != end || !text.is_empty()).then_some((start, end, text))
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct LspPosition {
     pub line: u32,
@@ -254,6 +396,25 @@ pub struct LspFoldingRange {
     pub end_line: u32,
 }
 
+#[derive(Debug, Clone)]
+pub struct LspDocumentLink {
+    pub range: LspRange,
+    pub target: Option<String>,
+    pub data: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LspCallHierarchyItem {
+    pub name: String,
+    pub detail: Option<String>,
+    pub kind: u32,
+    pub path: PathBuf,
+    pub line: u32,
+    pub column: u32,
+    /// 服务端下发的原始 `CallHierarchyItem`，请求 incoming / outgoing calls 时原样回传（`data` 由服务端解释）。
+    pub raw: Value,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum LspInsertTextFormat {
     #[default]
@@ -295,6 +456,8 @@ pub struct LspCompletionItem {
     pub id: u64,
     pub label: String,
     pub detail: Option<String>,
+    /// LSP 3.17 `labelDetails`：紧跟标签的签名 / 返回类型与补充说明（如模块路径）。
+    pub label_details: Option<LspCompletionLabelDetails>,
     pub kind: Option<u32>,
     pub documentation: Option<String>,
     pub insert_text: String,
@@ -308,6 +471,12 @@ pub struct LspCompletionItem {
     pub data: Option<Value>,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LspCompletionLabelDetails {
+    pub detail: Option<String>,
+    pub description: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct LspCommand {
     pub command: String,
@@ -328,17 +497,25 @@ pub struct LspServerCapabilities {
     pub position_encoding: LspPositionEncoding,
     pub hover: bool,
     pub definition: bool,
+    pub type_definition: bool,
+    pub implementation: bool,
     pub references: bool,
+    pub document_highlight: bool,
     pub document_symbols: bool,
     pub workspace_symbols: bool,
     pub code_action: bool,
     pub completion: bool,
     pub signature_help: bool,
     pub rename: bool,
+    /// `renameProvider.prepareProvider`：改名前可先用 `textDocument/prepareRename` 校验位置。
+    pub prepare_rename: bool,
     pub format: bool,
     pub range_format: bool,
     pub inlay_hints: bool,
     pub folding_range: bool,
+    pub document_link: bool,
+    pub document_link_resolve: bool,
+    pub call_hierarchy: bool,
     pub completion_resolve: bool,
     pub completion_triggers: Vec<char>,
     pub signature_help_triggers: Vec<char>,
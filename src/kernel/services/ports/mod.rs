@@ -6,11 +6,12 @@ pub mod lsp;
 pub mod search;
 pub mod settings;
 
-pub use config::EditorConfig;
+pub use config::{EditorConfig, PartialEditorConfig, TabType};
 pub use dir_entry::DirEntryInfo;
 pub use lsp::{
-    LspClientKey, LspCodeAction, LspCommand, LspCompletionItem, LspCompletionTriggerContext,
-    LspCompletionTriggerKind, LspFoldingRange, LspHoverBlock, LspHoverPayload,
+    LspCallHierarchyItem, LspClientKey, LspCodeAction, LspCommand, LspCompletionItem,
+    LspCompletionLabelDetails, LspCompletionTriggerContext, LspCompletionTriggerKind,
+    LspConnectionStatus, LspDocumentLink, LspFoldingRange, LspHoverBlock, LspHoverPayload,
     LspHoverPreviewPayload, LspInlayHint, LspInsertTextFormat, LspMarkup, LspPosition,
     LspPositionEncoding, LspRange, LspResourceOp, LspServerCapabilities, LspServerKind,
     LspSignatureHelpPayload, LspSignatureInfo, LspSignatureParameter, LspSignatureParameterLabel,
@@ -19,4 +20,4 @@ pub use lsp::{
 pub use search::{
     FileMatches, GlobalSearchMessage, Match, Result as SearchResult, SearchError, SearchMessage,
 };
-pub use settings::{KeybindingRule, Settings};
+pub use settings::{KeybindingRule, LspSettings, Settings, StatusBarConfig, StatusBarSegment};
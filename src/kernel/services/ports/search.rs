@@ -1,3 +1,4 @@
+use super::lsp::LspTextEdit;
 use std::io;
 use std::path::PathBuf;
 
@@ -83,10 +84,30 @@ pub enum GlobalSearchMessage {
         search_id: u64,
         file_matches: FileMatches,
     },
+    /// 每搜完 100 个文件发送一次。`files_total` 由并行计数逐步得出，搜索早期可能偏小。
     Progress {
         search_id: u64,
         files_searched: usize,
-        files_with_matches: usize,
+        files_total: usize,
+        matches_so_far: usize,
+    },
+    /// 开启 `search_skip_binary` 时，前 8KB 含 NUL 字节的文件不搜索，逐个上报。
+    BinaryFileSkipped {
+        search_id: u64,
+        path: PathBuf,
+    },
+    /// 全局替换：一个文件的全部替换编辑（UTF-8 列），由 kernel 按 workspace edit 应用。
+    Replaced {
+        search_id: u64,
+        path: PathBuf,
+        count: usize,
+        edits: Vec<LspTextEdit>,
+    },
+    /// 全局替换时单个文件读取失败；不中断其余文件。
+    ReplaceError {
+        search_id: u64,
+        path: PathBuf,
+        error: String,
     },
     Complete {
         search_id: u64,
@@ -15,7 +15,7 @@ pub struct Settings {
     pub lsp: LspSettings,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct LspSettings {
     /// LSP server command (e.g. "rust-analyzer" or "/usr/bin/rust-analyzer").
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -28,7 +28,7 @@ pub struct LspSettings {
     pub servers: BTreeMap<String, LspServerConfig>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct LspServerConfig {
     /// LSP server command override for this language server.
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -47,6 +47,69 @@ pub struct LspServerConfig {
 pub struct UiSettings {
     #[serde(default = "default_worktree_bar_visible")]
     pub worktree_bar: bool,
+    /// 拖拽侧边栏分隔线后记下的宽度（列数）；未设置时按终端宽度的固定百分比。
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        alias = "sidebarWidth"
+    )]
+    pub sidebar_width: Option<u16>,
+    #[serde(
+        default,
+        skip_serializing_if = "StatusBarConfig::is_empty",
+        alias = "statusBar"
+    )]
+    pub status_bar: StatusBarConfig,
+}
+
+/// 状态栏由左到右的段；第一个 `Spacer` 之后的段靠右对齐。为空时沿用默认布局。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusBarConfig {
+    #[serde(default)]
+    pub segments: Vec<StatusBarSegment>,
+}
+
+impl StatusBarConfig {
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn segments(&self) -> &[StatusBarSegment] {
+        if self.segments.is_empty() {
+            StatusBarSegment::DEFAULT_LAYOUT
+        } else {
+            &self.segments
+        }
+    }
+}
+
+/// 写法：`"mode"`、`"gitBranch"`、`{ "custom": " text " }`。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StatusBarSegment {
+    Mode,
+    FilePath,
+    CursorPosition,
+    Language,
+    LspStatus,
+    GitBranch,
+    Encoding,
+    LineEnding,
+    /// `Spaces: 4` / `Tab Width: 4`，点击在常用宽度间切换。
+    Indentation,
+    Spacer,
+    Custom(String),
+}
+
+impl StatusBarSegment {
+    /// 模式块 + `focus · 文件名`，右侧缩进风格与 `行:列`。
+    pub const DEFAULT_LAYOUT: &'static [StatusBarSegment] = &[
+        StatusBarSegment::Mode,
+        StatusBarSegment::FilePath,
+        StatusBarSegment::Spacer,
+        StatusBarSegment::Indentation,
+        StatusBarSegment::CursorPosition,
+    ];
 }
 
 fn default_worktree_bar_visible() -> bool {
@@ -57,11 +120,13 @@ impl Default for UiSettings {
     fn default() -> Self {
         Self {
             worktree_bar: default_worktree_bar_visible(),
+            sidebar_width: None,
+            status_bar: StatusBarConfig::default(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct KeybindingRule {
     pub key: String,
     pub command: String,
@@ -0,0 +1,60 @@
+//! `AppState` 的可序列化快照：只保留能从磁盘重建的部分（打开的文件、光标、布局、焦点、
+//! 搜索词），供测试比对前后状态，以及重载工作台后恢复现场。
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::{AppState, EditorLayoutState, FocusTarget};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AppStateSnapshot {
+    pub panes: Vec<PaneSnapshot>,
+    pub focus: FocusTarget,
+    pub editor_layout: EditorLayoutState,
+    pub search_query: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PaneSnapshot {
+    pub tabs: Vec<TabSnapshot>,
+    pub active: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TabSnapshot {
+    pub path: Option<PathBuf>,
+    /// 主光标 (行, 字符列)。
+    pub cursor: (usize, usize),
+    pub dirty: bool,
+}
+
+impl AppStateSnapshot {
+    pub fn capture(state: &AppState) -> Self {
+        Self {
+            panes: state
+                .editor
+                .panes
+                .iter()
+                .map(|pane| PaneSnapshot {
+                    tabs: pane
+                        .tabs
+                        .iter()
+                        .map(|tab| TabSnapshot {
+                            path: tab.path.clone(),
+                            cursor: tab.buffer.cursor(),
+                            dirty: tab.dirty,
+                        })
+                        .collect(),
+                    active: pane.active,
+                })
+                .collect(),
+            focus: state.ui.focus,
+            editor_layout: state.ui.editor_layout.clone(),
+            search_query: state.search.query.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "../../tests/unit/kernel/snapshot.rs"]
+mod tests;
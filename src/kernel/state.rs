@@ -1,4 +1,7 @@
 use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::VecDeque;
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 
@@ -10,15 +13,20 @@ use crate::kernel::language::{
 use crate::kernel::services::ports::DirEntryInfo;
 use crate::kernel::services::ports::EditorConfig;
 use crate::kernel::services::ports::LspClientKey;
+use crate::kernel::services::ports::LspConnectionStatus;
 use crate::kernel::services::ports::LspServerCapabilities;
-use crate::kernel::{CodeActionsState, LocationsState, ProblemsState, SymbolsState};
+use crate::kernel::services::ports::LspSettings;
+use crate::kernel::services::ports::PartialEditorConfig;
+use crate::kernel::{
+    CallHierarchyState, CodeActionsState, GitState, LocationsState, ProblemsState, SymbolsState,
+};
 use crate::models::{should_ignore, FileTree, FileTreeRow, LoadState, NodeId, NodeKind};
 
 use super::editor::EditorState;
 use super::effect::Effect;
 use super::search::SearchState;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FocusTarget {
     Explorer,
     Editor,
@@ -35,12 +43,19 @@ pub enum OverlayKind {
     CodeActions,
     Locations,
     Symbols,
+    Git,
+    /// 活动文件相对暂存区的并排 diff（`GitState::diff`）。
+    GitDiff,
+    CallHierarchy,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EditorLayoutState {
     pub panes: usize,
     pub active_pane: usize,
+    /// 按 pane 下标存放的配置覆盖（如参考窗格用不同的 tab 宽度）；
+    /// 针对某个 pane 的动作在归约时叠加到全局 `EditorConfig` 上。
+    pub pane_config_overrides: Vec<Option<PartialEditorConfig>>,
 }
 
 impl Default for EditorLayoutState {
@@ -48,23 +63,157 @@ impl Default for EditorLayoutState {
         Self {
             panes: 1,
             active_pane: 0,
+            pane_config_overrides: Vec::new(),
+        }
+    }
+}
+
+impl EditorLayoutState {
+    pub fn pane_config_override(&self, pane: usize) -> Option<&PartialEditorConfig> {
+        self.pane_config_overrides
+            .get(pane)
+            .and_then(Option::as_ref)
+    }
+
+    /// 修改某个 pane 的覆盖项；改完为空时清掉该项。返回是否有变化。
+    pub fn update_pane_config_override(
+        &mut self,
+        pane: usize,
+        update: impl FnOnce(&mut PartialEditorConfig),
+    ) -> bool {
+        if self.pane_config_overrides.len() <= pane {
+            self.pane_config_overrides.resize(pane + 1, None);
         }
+        let slot = &mut self.pane_config_overrides[pane];
+        let prev = slot.clone();
+        let mut next = prev.clone().unwrap_or_default();
+        update(&mut next);
+        *slot = (!next.is_empty()).then_some(next);
+        *slot != prev
     }
 }
 
+/// 命令行的两种模式：输入以 `>` 开头时过滤命令，否则模糊搜索工作区文件。
+/// 分支模式不由前缀决定，只能经 `Command::GitCheckoutBranch` 打开；
+/// 剪贴板模式同理，只能经 `Command::ClipboardHistory` 打开。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandLineMode {
+    Command,
+    File,
+    Line,
+    Branch,
+    Clipboard,
+}
+
 /// vim 风格 `:` 命令行：命令与搜索的输入载体，替代命令面板。
-/// `input` 是 `:` 之后键入的内容；`selected` 指向命令名补全列表中的高亮项。
+/// `input` 是 `:` 之后键入的内容；`selected` 指向补全列表中的高亮项。
+/// `files` 与两种模式各自的查询历史跨多次打开保留。
 #[derive(Debug, Clone, Default)]
 pub struct CommandLineState {
     pub active: bool,
     pub input: String,
     pub cursor: usize,
     pub selected: usize,
+    /// 工作区文件（相对 `workspace_root`），每次进入文件模式时异步刷新一次。
+    pub files: Vec<PathBuf>,
+    pub files_requested: bool,
+    /// 作为分支选择器打开：候选换成 `GitState::branches`，关闭即复位。
+    pub branch_picker: bool,
+    /// 作为剪贴板历史选择器打开：候选换成 `clipboard_entries`，关闭即复位。
+    pub clipboard_picker: bool,
+    /// 剪贴板历史，新的在前；每次打开选择器时从剪贴板服务取一次。
+    pub clipboard_entries: Vec<String>,
+    /// 已执行过的查询（不含 `>` 前缀），新的在后。
+    pub command_history: Vec<String>,
+    pub file_history: Vec<String>,
+    /// 正在回显的历史条目下标；编辑输入后清空。
+    pub history_index: Option<usize>,
 }
 
+const COMMAND_LINE_HISTORY_LIMIT: usize = 50;
+
 impl CommandLineState {
     pub fn reset(&mut self) {
-        *self = Self::default();
+        *self = Self {
+            files: std::mem::take(&mut self.files),
+            command_history: std::mem::take(&mut self.command_history),
+            file_history: std::mem::take(&mut self.file_history),
+            ..Self::default()
+        };
+    }
+
+    pub fn mode(&self) -> CommandLineMode {
+        if self.branch_picker {
+            return CommandLineMode::Branch;
+        }
+        if self.clipboard_picker {
+            return CommandLineMode::Clipboard;
+        }
+        crate::kernel::palette::split_query(&self.input).0
+    }
+
+    pub fn query(&self) -> &str {
+        if self.branch_picker || self.clipboard_picker {
+            return self.input.trim();
+        }
+        crate::kernel::palette::split_query(&self.input).1
+    }
+
+    pub fn history(&self, mode: CommandLineMode) -> &[String] {
+        match mode {
+            CommandLineMode::Command => &self.command_history,
+            CommandLineMode::File => &self.file_history,
+            CommandLineMode::Line | CommandLineMode::Branch | CommandLineMode::Clipboard => &[],
+        }
+    }
+
+    /// 记录一次执行过的查询；重复的条目移到最新位置。
+    pub fn push_history(&mut self, mode: CommandLineMode, query: &str) {
+        let query = query.trim();
+        if query.is_empty() {
+            return;
+        }
+        let history = match mode {
+            CommandLineMode::Command => &mut self.command_history,
+            CommandLineMode::File => &mut self.file_history,
+            // 行号与具体文件相关，不值得回溯；分支列表与剪贴板历史本身就是候选。
+            CommandLineMode::Line | CommandLineMode::Branch | CommandLineMode::Clipboard => return,
+        };
+        history.retain(|q| q != query);
+        history.push(query.to_string());
+        if history.len() > COMMAND_LINE_HISTORY_LIMIT {
+            history.remove(0);
+        }
+    }
+
+    /// 在当前模式的历史里移动（`delta < 0` 为更早），把条目连同模式前缀写回输入。
+    pub fn recall_history(&mut self, delta: isize) -> bool {
+        let mode = self.mode();
+        let len = self.history(mode).len();
+        if len == 0 {
+            return false;
+        }
+        let index = match self.history_index {
+            None if delta < 0 => len - 1,
+            None => return false,
+            Some(i) => {
+                let next = i as isize + delta;
+                if next < 0 || next >= len as isize {
+                    return false;
+                }
+                next as usize
+            }
+        };
+        let prefix = match mode {
+            CommandLineMode::Command => crate::kernel::palette::COMMAND_PREFIX,
+            CommandLineMode::File | CommandLineMode::Branch | CommandLineMode::Clipboard => "",
+            CommandLineMode::Line => crate::kernel::palette::LINE_PREFIX,
+        };
+        self.input = format!("{prefix}{}", self.history(mode)[index]);
+        self.cursor = self.input.len();
+        self.selected = 0;
+        self.history_index = Some(index);
+        true
     }
 }
 
@@ -85,6 +234,18 @@ pub enum InputDialogKind {
         column: u32,
     },
     LspWorkspaceSymbols,
+    FilePath {
+        purpose: FilePathPurpose,
+    },
+}
+
+/// `InputDialogKind::FilePath` 确认后要对该路径做的事。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilePathPurpose {
+    ExportKeybindings,
+    ImportKeybindings,
+    AddWorkspaceFolder,
+    ExportAsHtml,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -164,6 +325,13 @@ pub enum PendingAction {
         to: PathBuf,
         overwrite: bool,
     },
+    GitStashDrop {
+        index: usize,
+    },
+    OpenLargeFile {
+        path: PathBuf,
+        size: u64,
+    },
 }
 
 #[derive(Debug, Clone, Default)]
@@ -188,6 +356,8 @@ pub struct CompletionPopupState {
     pub index_by_id: FxHashMap<u64, usize>,
     pub visible_indices: Vec<usize>,
     pub selected: usize,
+    /// 列表顶部显示的条目下标；渲染时仍会校正到能看见选中项。
+    pub scroll_offset: usize,
     pub selection_locked: bool,
     pub filter_cache_prefix: String,
     pub filter_cache_indices: Vec<usize>,
@@ -278,6 +448,19 @@ impl CompletionPopupState {
             || !self.visible_indices.is_empty()
     }
 
+    /// 按每屏 `page` 条滚动到能看见选中项：越过上下边缘时挪到刚好露出，
+    /// `align_page` 时顶部对齐到 `page` 的整数倍（翻页用）。
+    pub fn scroll_to_selected(&mut self, page: usize, align_page: bool) {
+        let page = page.max(1);
+        if align_page {
+            self.scroll_offset = self.selected / page * page;
+        } else if self.selected < self.scroll_offset {
+            self.scroll_offset = self.selected;
+        } else if self.selected >= self.scroll_offset + page {
+            self.scroll_offset = self.selected + 1 - page;
+        }
+    }
+
     pub fn close(&mut self) -> bool {
         if self.is_active() {
             *self = Self::default();
@@ -298,6 +481,8 @@ pub struct SignatureHelpRequestContext {
 #[derive(Debug, Clone, Default)]
 pub struct HoverPopupState {
     pub session: i32,
+    /// 鼠标停在诊断下划线上时的诊断消息，排在悬停内容最前面。
+    pub diagnostics: Option<HoverModel>,
     pub model: Option<HoverModel>,
     pub implementation_preview: Option<HoverSectionModel>,
     pub definition_preview: Option<HoverSectionModel>,
@@ -305,19 +490,26 @@ pub struct HoverPopupState {
 
 impl HoverPopupState {
     pub fn is_active(&self) -> bool {
-        self.model.is_some()
+        self.diagnostics.is_some()
+            || self.model.is_some()
             || self.implementation_preview.is_some()
             || self.definition_preview.is_some()
     }
 
     pub fn clear(&mut self) {
         self.session = 0;
+        self.diagnostics = None;
         self.model = None;
         self.implementation_preview = None;
         self.definition_preview = None;
     }
 
     pub fn display_text(&self) -> Option<String> {
+        let diagnostics = self
+            .diagnostics
+            .as_ref()
+            .map(HoverModel::to_display_text)
+            .unwrap_or_default();
         let base = self
             .model
             .as_ref()
@@ -334,7 +526,10 @@ impl HoverPopupState {
             .map(HoverSectionModel::to_display_text)
             .unwrap_or_default();
 
-        let mut sections = Vec::with_capacity(3);
+        let mut sections = Vec::with_capacity(4);
+        if !diagnostics.trim().is_empty() {
+            sections.push(diagnostics);
+        }
         if !base.trim().is_empty() {
             sections.push(base);
         }
@@ -386,6 +581,7 @@ pub enum ContextMenuRequest {
     Tab { pane: usize, index: usize },
     TabBar { pane: usize },
     EditorArea { pane: usize },
+    GitStash { index: usize },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -409,11 +605,18 @@ pub enum ExplorerMenuAction {
     Paste,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitStashMenuAction {
+    Apply,
+    Drop,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ContextMenuAction {
     RunCommand(Command),
     Tab(TabMenuAction),
     Explorer(ExplorerMenuAction),
+    GitStash(GitStashMenuAction),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -520,42 +723,126 @@ impl Default for UiState {
     }
 }
 
+pub const MAX_RECENTLY_CLOSED_TABS: usize = 10;
+pub const MAX_RECENT_FILES: usize = 50;
+pub const MAX_FILE_POSITIONS: usize = 200;
+
 #[derive(Debug)]
 pub struct AppState {
     pub workspace_root: PathBuf,
+    /// 多根工作区的全部文件夹；第 0 项恒为 `workspace_root`，不可移除。
+    pub workspace_folders: Vec<PathBuf>,
+    /// 最近关闭的标签页（路径 + 光标字节偏移），队尾是最近一次关闭的；供 `ReopenClosedTab` 恢复。
+    pub recently_closed_tabs: VecDeque<(PathBuf, usize)>,
+    /// 文件上次的光标位置（行, 列，列按该文件语言服务器的位置编码计），队首是最近记下的；
+    /// 再次打开时放回去。
+    pub file_positions: VecDeque<(PathBuf, (u32, u32))>,
+    /// 最近打开过的文件（绝对路径），队首是最近一次打开的；跨工作区共享，文件模式命令行排在最前。
+    pub recent_files: VecDeque<PathBuf>,
     pub ui: UiState,
     pub lsp: LspState,
     pub explorer: ExplorerState,
+    /// 资源管理器文件操作的撤销/重做栈。
+    pub explorer_history: ExplorerHistory,
     pub search: SearchState,
     pub editor: EditorState,
     pub problems: ProblemsState,
     pub code_actions: CodeActionsState,
     pub locations: LocationsState,
     pub symbols: SymbolsState,
+    pub git: GitState,
+    pub call_hierarchy: CallHierarchyState,
 }
 
 impl AppState {
     pub fn new(workspace_root: PathBuf, file_tree: FileTree, editor_config: EditorConfig) -> Self {
         let editor = EditorState::new(editor_config);
         Self {
+            workspace_folders: vec![workspace_root.clone()],
+            recently_closed_tabs: VecDeque::new(),
+            file_positions: VecDeque::new(),
+            recent_files: VecDeque::new(),
             workspace_root,
             ui: UiState::default(),
             lsp: LspState::default(),
             explorer: ExplorerState::new(file_tree),
+            explorer_history: ExplorerHistory::default(),
             search: SearchState::default(),
             editor,
             problems: ProblemsState::default(),
             code_actions: CodeActionsState::default(),
             locations: LocationsState::default(),
             symbols: SymbolsState::default(),
+            git: GitState::default(),
+            call_hierarchy: CallHierarchyState::default(),
+        }
+    }
+
+    /// 记录一个刚关闭的标签页。同一路径只保留最新一条，最多保留 `MAX_RECENTLY_CLOSED_TABS` 条。
+    pub fn remember_closed_tab(&mut self, path: PathBuf, byte_offset: usize) {
+        self.recently_closed_tabs.retain(|(p, _)| *p != path);
+        self.recently_closed_tabs.push_back((path, byte_offset));
+        while self.recently_closed_tabs.len() > MAX_RECENTLY_CLOSED_TABS {
+            self.recently_closed_tabs.pop_front();
+        }
+    }
+
+    /// 记下文件的光标位置：移到队首，最多保留 `MAX_FILE_POSITIONS` 条。
+    pub fn remember_file_position(&mut self, path: PathBuf, position: (u32, u32)) {
+        self.file_positions.retain(|(p, _)| *p != path);
+        self.file_positions.push_front((path, position));
+        self.file_positions.truncate(MAX_FILE_POSITIONS);
+    }
+
+    pub fn file_position(&self, path: &Path) -> Option<(u32, u32)> {
+        self.file_positions
+            .iter()
+            .find(|(p, _)| p == path)
+            .map(|&(_, position)| position)
+    }
+
+    /// 载入会话里存的光标位置：已经不存在的文件丢掉，最多保留 `MAX_FILE_POSITIONS` 条。
+    pub fn restore_file_positions(&mut self, saved: &VecDeque<(PathBuf, (u32, u32))>) {
+        self.file_positions = saved
+            .iter()
+            .filter(|(path, _)| path.exists())
+            .take(MAX_FILE_POSITIONS)
+            .cloned()
+            .collect();
+    }
+
+    /// 记录一次打开文件：移到队首，最多保留 `MAX_RECENT_FILES` 条。返回列表是否变化。
+    pub fn remember_recent_file(&mut self, path: &Path) -> bool {
+        if self.recent_files.front().is_some_and(|p| p == path) {
+            return false;
+        }
+        self.recent_files.retain(|p| p != path);
+        self.recent_files.push_front(path.to_path_buf());
+        self.recent_files.truncate(MAX_RECENT_FILES);
+        true
+    }
+
+    /// 某个 pane 的有效编辑器配置；没有覆盖项时直接借用全局配置。
+    pub fn editor_config_for_pane(&self, pane: usize) -> Cow<'_, EditorConfig> {
+        match self.ui.editor_layout.pane_config_override(pane) {
+            Some(overrides) => Cow::Owned(self.editor.config.merged_with(Some(overrides))),
+            None => Cow::Borrowed(&self.editor.config),
         }
     }
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct LspState {
+    /// 最近一次载入的 `settings.lsp`；热重载时与新值比较，变了才发 `Effect::ReconfigureLsp`。
+    pub settings: LspSettings,
     pub server_capabilities: FxHashMap<LspClientKey, LspServerCapabilities>,
+    /// 崩溃后正在重连或已放弃的客户端；连上后移除。
+    pub connection: FxHashMap<LspClientKey, LspConnectionStatus>,
     pub payload_fingerprints: LspPayloadFingerprints,
+    /// 开启 `format_on_save` 时，等待格式化结果后再保存的文件。
+    pub pending_format_on_save: Option<PathBuf>,
+    /// 发起保存那一刻的 `edit_version`；格式化返回时 tab 已被改过则丢弃结果、不写盘。
+    pub pending_format_on_save_version: Option<u64>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -593,6 +880,65 @@ pub struct ExplorerClipboardPayload {
     pub mode: ExplorerClipboardMode,
 }
 
+/// 侧栏内拖动一行：被拖的节点与指针下方可放入的目录。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExplorerDragState {
+    pub dragging_node: NodeId,
+    pub hover_target: Option<NodeId>,
+}
+
+/// 资源管理器文件操作的来源：用户操作和重做的逆操作进撤销栈，撤销的逆操作进重做栈。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExplorerOpOrigin {
+    User,
+    Undo,
+    Redo,
+}
+
+pub const MAX_EXPLORER_HISTORY: usize = 50;
+
+/// 资源管理器的撤销/重做栈，存的是已执行操作的逆操作（`Effect::DeletePath`、
+/// `Effect::RenamePath`、`Effect::RestoreFromTrash` 等）。
+#[derive(Debug, Clone, Default)]
+pub struct ExplorerHistory {
+    undo: Vec<Effect>,
+    redo: Vec<Effect>,
+}
+
+impl ExplorerHistory {
+    /// 记下一次已执行操作的逆操作；新的用户操作会清空重做栈。
+    pub fn record(&mut self, inverse: Effect, origin: ExplorerOpOrigin) {
+        let stack = match origin {
+            ExplorerOpOrigin::User => {
+                self.redo.clear();
+                &mut self.undo
+            }
+            ExplorerOpOrigin::Redo => &mut self.undo,
+            ExplorerOpOrigin::Undo => &mut self.redo,
+        };
+        stack.push(inverse);
+        if stack.len() > MAX_EXPLORER_HISTORY {
+            stack.remove(0);
+        }
+    }
+
+    pub fn pop_undo(&mut self) -> Option<Effect> {
+        self.undo.pop()
+    }
+
+    pub fn pop_redo(&mut self) -> Option<Effect> {
+        self.redo.pop()
+    }
+
+    pub fn undo_len(&self) -> usize {
+        self.undo.len()
+    }
+
+    pub fn redo_len(&self) -> usize {
+        self.redo.len()
+    }
+}
+
 pub struct ExplorerState {
     tree: FileTree,
     pub view_height: usize,
@@ -601,6 +947,9 @@ pub struct ExplorerState {
     index_by_id: FxHashMap<NodeId, usize>,
     last_click: Option<(Instant, NodeId)>,
     clipboard: Option<ExplorerClipboardPayload>,
+    pub drag: Option<ExplorerDragState>,
+    /// 上一次放下被拒绝的原因，显示在侧栏底部，下一次点击或拖动时清除。
+    pub drop_error: Option<String>,
 }
 
 impl std::fmt::Debug for ExplorerState {
@@ -626,6 +975,8 @@ impl ExplorerState {
             index_by_id: FxHashMap::default(),
             last_click: None,
             clipboard: None,
+            drag: None,
+            drop_error: None,
         };
         state.refresh_rows();
         state
@@ -678,6 +1029,63 @@ impl ExplorerState {
         self.tree.selected()
     }
 
+    pub fn begin_drag(&mut self, node: NodeId) -> bool {
+        if node == self.tree.root() || self.path_and_kind_for(node).is_none() {
+            return false;
+        }
+        self.drop_error = None;
+        self.drag = Some(ExplorerDragState {
+            dragging_node: node,
+            hover_target: None,
+        });
+        true
+    }
+
+    /// 只有目录能作为放入目标；指针在文件行上时不高亮。
+    pub fn set_drag_hover(&mut self, target: Option<NodeId>) -> bool {
+        let target = target.filter(|id| self.tree.is_dir(*id));
+        let Some(drag) = self.drag.as_mut() else {
+            return false;
+        };
+        if drag.hover_target == target {
+            return false;
+        }
+        drag.hover_target = target;
+        true
+    }
+
+    pub fn end_drag(&mut self) -> bool {
+        self.drag.take().is_some()
+    }
+
+    /// 把正在拖动的节点放到 `target` 上，返回要执行的 `(from, to)`。
+    /// 目标不是目录或位置不变时为 `Ok(None)`；把目录放进自己的子目录时返回错误信息。
+    pub fn drop_dragged_onto(
+        &mut self,
+        target: NodeId,
+    ) -> Result<Option<(PathBuf, PathBuf)>, String> {
+        let Some(drag) = self.drag.take() else {
+            return Ok(None);
+        };
+        let Some((from, from_is_dir)) = self.path_and_kind_for(drag.dragging_node) else {
+            return Ok(None);
+        };
+        let Some((to_dir, to_is_dir)) = self.path_and_kind_for(target) else {
+            return Ok(None);
+        };
+        if !to_is_dir {
+            return Ok(None);
+        }
+        if from_is_dir && to_dir != from && to_dir.starts_with(&from) {
+            let name = from
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            return Err(format!("Cannot move '{name}' into its own subfolder"));
+        }
+        Ok(compute_explorer_move_target(&from, from_is_dir, &to_dir).map(|to| (from, to)))
+    }
+
     pub fn root_id(&self) -> NodeId {
         self.tree.root()
     }
@@ -782,6 +1190,7 @@ impl ExplorerState {
         if row >= self.rows.len() {
             return (false, Vec::new());
         }
+        let error_cleared = self.drop_error.take().is_some();
 
         let node_id = self.rows[row].id;
 
@@ -810,7 +1219,7 @@ impl ExplorerState {
             self.keep_row_visible(index);
         }
 
-        (prev_selected != Some(node_id), Vec::new())
+        (error_cleared || prev_selected != Some(node_id), Vec::new())
     }
 
     pub fn select_row(&mut self, row: usize) -> bool {
@@ -951,23 +1360,56 @@ impl ExplorerState {
         deleted || created
     }
 
+    /// 额外的工作区文件夹作为顶层条目挂进文件树，展开时按需加载。
+    pub fn add_workspace_folder(&mut self, path: PathBuf) -> bool {
+        if self.tree.add_mount(path).is_err() {
+            return false;
+        }
+        self.refresh_rows();
+        true
+    }
+
+    pub fn remove_workspace_folder(&mut self, path: &Path) -> bool {
+        if self.tree.remove_mount(path).is_err() {
+            return false;
+        }
+        self.refresh_rows();
+        true
+    }
+
+    /// 目录读取途中的一批条目：先插入可见，加载状态保持 `Loading` 直到 `apply_dir_loaded`。
+    pub fn apply_dir_batch_loaded(&mut self, path: PathBuf, entries: Vec<DirEntryInfo>) -> bool {
+        let Some(node_id) = self.tree.find_node_by_path(&path) else {
+            return false;
+        };
+
+        self.insert_dir_entries(node_id, entries);
+        self.refresh_rows();
+        true
+    }
+
     pub fn apply_dir_loaded(&mut self, path: PathBuf, entries: Vec<DirEntryInfo>) -> bool {
         let Some(node_id) = self.tree.find_node_by_path(&path) else {
             return false;
         };
 
+        self.insert_dir_entries(node_id, entries);
+        self.tree.set_load_state(node_id, LoadState::Loaded);
+        self.refresh_rows();
+        true
+    }
+
+    fn insert_dir_entries(&mut self, node_id: NodeId, entries: Vec<DirEntryInfo>) {
         for entry in entries {
             let kind = if entry.is_dir {
                 NodeKind::Dir
             } else {
                 NodeKind::File
             };
-            let _ = self.tree.insert_child(node_id, entry.name.into(), kind);
+            let name = std::ffi::OsString::from(entry.name);
+            let _ = self.tree.insert_child(node_id, name.clone(), kind);
+            self.tree.set_child_file_size(node_id, &name, entry.size);
         }
-
-        self.tree.set_load_state(node_id, LoadState::Loaded);
-        self.refresh_rows();
-        true
     }
 
     pub fn apply_dir_load_error(&mut self, path: PathBuf) -> bool {
@@ -1058,6 +1500,17 @@ impl ExplorerState {
     }
 }
 
+/// 拖放移动的目标路径：目录不能放进自身或其子目录，放回原父目录时不动。
+fn compute_explorer_move_target(from: &Path, from_is_dir: bool, to_dir: &Path) -> Option<PathBuf> {
+    if from_is_dir && (to_dir == from || to_dir.starts_with(from)) {
+        return None;
+    }
+
+    let name = from.file_name()?;
+    let to = to_dir.join(name);
+    (to != from).then_some(to)
+}
+
 #[cfg(test)]
 #[path = "../../tests/unit/kernel/state.rs"]
 mod tests;
@@ -11,7 +11,7 @@ use crate::kernel::{AppState, EditorAction};
 use crate::models::{Granularity, Selection};
 use rustc_hash::FxHashMap;
 
-use super::completion_rank::CompletionRanker;
+use super::completion_rank::{CompletionRanker, RecentlyUsedCompletionRanker};
 
 pub(in crate::kernel::store) fn should_close_completion_on_editor_action(
     action: &EditorAction,
@@ -72,41 +72,65 @@ pub(in crate::kernel::store) fn normalize_completion_record(
     CompletionRecord { entry, raw }
 }
 
+/// `recent` 为 `(最近接受记录, 当前文件后缀)`；关闭 `completion_boost_recent` 时传 `None`。
+///
+/// 排序依次看：最近接受加权 → 服务端 `sortText`（缺省回退到 label，与 LSP 规范一致）→
+/// 本地频次打分 → label。最近接受过的条目是用户自己的选择，排在服务端顺序之前；
+/// 整批都没有 `sortText` 时跳过这一级，直接按本地打分排。
 pub(in crate::kernel::store) fn sort_completion_items(
     items: &mut [CompletionRecord],
     ranker: &CompletionRanker,
     language: Option<LanguageId>,
+    recent: Option<(&RecentlyUsedCompletionRanker, &str)>,
 ) {
+    fn sort_key(item: &CompletionRecord) -> &str {
+        item.entry
+            .sort_text
+            .as_deref()
+            .unwrap_or(item.entry.label.as_str())
+    }
+
     let mut score_by_id = FxHashMap::default();
     for item in items.iter() {
+        let recent_score = recent
+            .map(|(recent, suffix)| recent.score(suffix, &item.entry.label))
+            .unwrap_or(0.0);
         score_by_id.insert(
             item.entry.id,
-            ranker.score(language, &item.entry.label, item.entry.kind),
+            (
+                recent_score,
+                ranker.score(language, &item.entry.label, item.entry.kind),
+            ),
         );
     }
+    let server_ordered = items.iter().any(|item| item.entry.sort_text.is_some());
+    let score = |item: &CompletionRecord| {
+        score_by_id
+            .get(&item.entry.id)
+            .copied()
+            .unwrap_or((0.0, 0.0))
+    };
 
     items.sort_by(|a, b| {
-        let a_score = score_by_id.get(&a.entry.id).copied().unwrap_or(0.0);
-        let b_score = score_by_id.get(&b.entry.id).copied().unwrap_or(0.0);
-        b_score
-            .partial_cmp(&a_score)
+        let (a_recent, a_rank) = score(a);
+        let (b_recent, b_rank) = score(b);
+        b_recent
+            .partial_cmp(&a_recent)
             .unwrap_or(std::cmp::Ordering::Equal)
             .then_with(|| {
-                let a_key = a
-                    .entry
-                    .sort_text
-                    .as_deref()
-                    .unwrap_or(a.entry.label.as_str());
-                let b_key = b
-                    .entry
-                    .sort_text
-                    .as_deref()
-                    .unwrap_or(b.entry.label.as_str());
-                a_key
-                    .cmp(b_key)
-                    .then_with(|| a.entry.label.cmp(&b.entry.label))
-                    .then_with(|| a.entry.detail.cmp(&b.entry.detail))
+                if server_ordered {
+                    sort_key(a).cmp(sort_key(b))
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .then_with(|| {
+                b_rank
+                    .partial_cmp(&a_rank)
+                    .unwrap_or(std::cmp::Ordering::Equal)
             })
+            .then_with(|| a.entry.label.cmp(&b.entry.label))
+            .then_with(|| a.entry.detail.cmp(&b.entry.detail))
     });
 }
 
@@ -312,6 +336,10 @@ impl CompletionInsertion {
         }
     }
 
+    pub(in crate::kernel::store) fn from_snippet(template: &str) -> Self {
+        Self::from_plan(TextEditPlan::from_snippet(template))
+    }
+
     pub(in crate::kernel::store) fn has_cursor_or_selection(&self) -> bool {
         self.cursor.is_some() || self.selection.is_some()
     }
@@ -484,6 +512,23 @@ fn multiline_text_needs_indent_adjustment(text: &str, base_indent: &str) -> bool
     false
 }
 
+/// 把 `[start_char, end_char)` 替换为 snippet 模板的展开结果，并按 tabstop 进入 snippet 会话。
+pub(in crate::kernel::store) fn insert_snippet_into_tab(
+    tab: &mut EditorTabState,
+    start_char: usize,
+    end_char: usize,
+    template: &str,
+    tab_size: u8,
+) -> bool {
+    let insertion = CompletionInsertion::from_snippet(template);
+    let insertion = adjust_completion_multiline_indentation(tab, start_char, insertion);
+    if !tab.replace_range_as_batch(start_char, end_char, &insertion.text, tab_size) {
+        return false;
+    }
+    apply_completion_insertion_cursor(tab, &insertion, tab_size);
+    true
+}
+
 pub(in crate::kernel::store) fn apply_completion_insertion_cursor(
     tab: &mut EditorTabState,
     insertion: &CompletionInsertion,
@@ -575,6 +620,7 @@ mod tests {
             id,
             label: label.to_string(),
             detail: None,
+            label_details: None,
             kind: Some(3),
             documentation: None,
             insert_text: label.to_string(),
@@ -908,6 +954,7 @@ mod tests {
             id: 1,
             label: "print".to_string(),
             detail: None,
+            label_details: None,
             kind: Some(3),
             documentation: None,
             insert_text: "print".to_string(),
@@ -945,6 +992,7 @@ mod tests {
                     id: key as u64,
                     label: format!("item_{key:03}"),
                     detail: None,
+                    label_details: None,
                     kind: Some(3),
                     documentation: None,
                     insert_text: format!("item_{key:03}"),
@@ -962,7 +1010,7 @@ mod tests {
         let mut items: Vec<crate::kernel::language::CompletionRecord> =
             items.into_iter().map(Into::into).collect();
 
-        sort_completion_items(&mut items, &ranker, Some(LanguageId::Rust));
+        sort_completion_items(&mut items, &ranker, Some(LanguageId::Rust), None);
 
         let counters = CompletionRanker::perf_counters();
         eprintln!(
@@ -978,12 +1026,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sort_completion_items_orders_recent_then_sort_text_then_local_rank() {
+        let mut ranker = CompletionRanker::default();
+        for _ in 0..8 {
+            ranker.record(Some(LanguageId::Rust), "as_str", Some(3));
+        }
+        let mut recent = RecentlyUsedCompletionRanker::default();
+        recent.record("rs", "as_bytes");
+        let with_sort_text = |id, label: &str, sort_text: Option<&str>| {
+            let mut item = completion_item(id, label);
+            item.sort_text = sort_text.map(str::to_string);
+            crate::kernel::language::CompletionRecord::from(item)
+        };
+        let sorted = |recent: Option<(&RecentlyUsedCompletionRanker, &str)>| {
+            let mut items = vec![
+                with_sort_text(1, "as_str", Some("80000001")),
+                with_sort_text(2, "String", Some("80000000")),
+                with_sort_text(3, "as_bytes", Some("80000001")),
+                with_sort_text(4, "zeta", None),
+            ];
+            sort_completion_items(&mut items, &ranker, Some(LanguageId::Rust), recent);
+            items
+                .iter()
+                .map(|item| item.entry.label.clone())
+                .collect::<Vec<_>>()
+        };
+
+        // 频次打分不越过 `sortText`，只在键相同时把 `as_str` 排到 `as_bytes` 前。
+        assert_eq!(sorted(None), ["String", "as_str", "as_bytes", "zeta"]);
+        // 最近接受过的条目排在服务端顺序之前。
+        assert_eq!(
+            sorted(Some((&recent, "rs"))),
+            ["as_bytes", "String", "as_str", "zeta"]
+        );
+
+        let mut items = vec![
+            completion_record(1, "as_bytes"),
+            completion_record(2, "as_str"),
+        ];
+        sort_completion_items(&mut items, &ranker, Some(LanguageId::Rust), None);
+        assert_eq!(items[0].entry.label, "as_str");
+    }
+
     #[test]
     fn resolve_plain_non_callable_completion_keeps_plain_text() {
         let item = LspCompletionItem {
             id: 1,
             label: "static".to_string(),
             detail: None,
+            label_details: None,
             kind: Some(14),
             documentation: None,
             insert_text: "static".to_string(),
@@ -1095,6 +1187,7 @@ mod tests {
             id: 1,
             label: "print".to_string(),
             detail: None,
+            label_details: None,
             kind: Some(3),
             documentation: None,
             insert_text: "print".to_string(),
@@ -1153,6 +1246,7 @@ mod tests {
             id: 1,
             label: "self".to_string(),
             detail: None,
+            label_details: None,
             kind: Some(5),
             documentation: None,
             insert_text: "self".to_string(),
@@ -1202,6 +1296,7 @@ mod tests {
             id: 1,
             label: "push_back".to_string(),
             detail: None,
+            label_details: None,
             kind: Some(3),
             documentation: None,
             insert_text: "push_back".to_string(),
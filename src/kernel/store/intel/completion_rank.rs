@@ -1,8 +1,12 @@
 use crate::kernel::language::LanguageId;
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::VecDeque;
+use std::path::Path;
 
 const MAX_ENTRIES_PER_LANGUAGE: usize = 512;
+const RECENT_ACCEPTED_CAPACITY: usize = 50;
+const DEFAULT_RECENT_BOOST: f64 = 1.0;
 
 #[cfg(test)]
 use std::cell::RefCell;
@@ -277,6 +281,60 @@ impl Default for CompletionRanker {
     }
 }
 
+/// 按路径后缀（扩展名）记住最近接受的补全 label，每个后缀环形保留最近 `capacity` 个。
+/// 只在会话内有效，不落盘；打分优先于 `CompletionRanker` 的语言级记录。
+#[derive(Debug, Clone)]
+pub struct RecentlyUsedCompletionRanker {
+    recent_by_suffix: FxHashMap<String, VecDeque<String>>,
+    capacity: usize,
+    boost: f64,
+}
+
+impl RecentlyUsedCompletionRanker {
+    pub fn new(capacity: usize, boost: f64) -> Self {
+        Self {
+            recent_by_suffix: FxHashMap::default(),
+            capacity: capacity.max(1),
+            boost,
+        }
+    }
+
+    /// 扩展名（小写）；没有扩展名时用文件名，如 `Makefile`。
+    pub fn path_suffix(path: &Path) -> Option<String> {
+        path.extension()
+            .or_else(|| path.file_name())
+            .map(|suffix| suffix.to_string_lossy().to_ascii_lowercase())
+    }
+
+    pub fn record(&mut self, suffix: &str, label: &str) {
+        let recent = self.recent_by_suffix.entry(suffix.to_string()).or_default();
+        if let Some(pos) = recent.iter().position(|item| item == label) {
+            recent.remove(pos);
+        }
+        recent.push_back(label.to_string());
+        while recent.len() > self.capacity {
+            recent.pop_front();
+        }
+    }
+
+    /// 未命中为 0；命中按新旧线性加权，最新接受的得满额 `boost`。
+    pub fn score(&self, suffix: &str, label: &str) -> f64 {
+        let Some(recent) = self.recent_by_suffix.get(suffix) else {
+            return 0.0;
+        };
+        let Some(pos) = recent.iter().rposition(|item| item == label) else {
+            return 0.0;
+        };
+        self.boost * (pos + 1) as f64 / recent.len() as f64
+    }
+}
+
+impl Default for RecentlyUsedCompletionRanker {
+    fn default() -> Self {
+        Self::new(RECENT_ACCEPTED_CAPACITY, DEFAULT_RECENT_BOOST)
+    }
+}
+
 impl Serialize for CompletionRanker {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -300,6 +358,34 @@ impl<'de> Deserialize<'de> for CompletionRanker {
 mod tests {
     use super::*;
 
+    #[test]
+    fn recent_ranker_scores_newest_highest_per_suffix() {
+        let mut recent = RecentlyUsedCompletionRanker::new(3, 2.0);
+        recent.record("rs", "push");
+        recent.record("rs", "pop");
+        recent.record("rs", "push");
+
+        assert_eq!(recent.score("rs", "push"), 2.0);
+        assert!(recent.score("rs", "pop") > 0.0);
+        assert!(recent.score("rs", "pop") < recent.score("rs", "push"));
+        assert_eq!(recent.score("py", "push"), 0.0);
+    }
+
+    #[test]
+    fn recent_ranker_drops_oldest_past_capacity() {
+        let mut recent = RecentlyUsedCompletionRanker::new(2, 1.0);
+        for label in ["a", "b", "c"] {
+            recent.record("rs", label);
+        }
+
+        assert_eq!(recent.score("rs", "a"), 0.0);
+        assert!(recent.score("rs", "b") > 0.0);
+        assert_eq!(
+            RecentlyUsedCompletionRanker::path_suffix(Path::new("src/Main.RS")).as_deref(),
+            Some("rs")
+        );
+    }
+
     #[test]
     fn record_and_score() {
         let mut ranker = CompletionRanker::default();
@@ -1,4 +1,5 @@
-use crate::kernel::language::HoverSectionModel;
+use crate::kernel::language::{HoverBlock, HoverModel, HoverSectionModel};
+
 use crate::kernel::services::ports::lsp::{
     column_for_chars, line_len_chars, lsp_col_to_char_offset_in_line,
 };
@@ -11,7 +12,7 @@ use crate::kernel::state::{
     CompletionPopupState, PayloadStamp, RangePayloadStamp, SignatureHelpPopupState,
 };
 use crate::kernel::EditorAction;
-use crate::kernel::{Action, Effect, FocusTarget, OverlayKind};
+use crate::kernel::{Action, Effect, FocusTarget, LocationItem, OverlayKind, ProblemItem};
 use rustc_hash::FxHasher;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
@@ -24,6 +25,7 @@ use super::completion::{
     completion_runtime_context, filtered_completion_indices, language_runtime_context,
     normalize_completion_record, sort_completion_items,
 };
+use super::completion_rank::RecentlyUsedCompletionRanker;
 
 // Test-only counter of `lsp_server_capabilities_for_path` calls (each resolves an
 // `LspClientKey` via a filesystem marker-root walk). Thread-local — like the
@@ -98,6 +100,16 @@ pub(in crate::kernel::store) fn lsp_position_to_byte_offset(
     rope.char_to_byte(char_offset)
 }
 
+fn lsp_range_to_byte_range(
+    tab: &crate::kernel::editor::EditorTabState,
+    range: &LspRange,
+    encoding: LspPositionEncoding,
+) -> (usize, usize) {
+    let start = lsp_position_to_byte_offset(tab, range.start.line, range.start.character, encoding);
+    let end = lsp_position_to_byte_offset(tab, range.end.line, range.end.character, encoding);
+    (start, end)
+}
+
 pub(in crate::kernel::store) fn lsp_request_target(
     state: &crate::kernel::AppState,
 ) -> Option<(usize, std::path::PathBuf, u32, u32, u64)> {
@@ -236,6 +248,27 @@ pub(in crate::kernel::store) fn lsp_position_encoding(
     lsp_position_encoding_for_path(state, path)
 }
 
+/// `pane` 当前标签页里覆盖 `(row, col)` 的诊断。
+fn diagnostics_at(
+    state: &crate::kernel::AppState,
+    pane: usize,
+    row: usize,
+    col: usize,
+) -> Vec<&ProblemItem> {
+    let Some(tab) = state.editor.pane(pane).and_then(|pane| pane.active_tab()) else {
+        return Vec::new();
+    };
+    let Some(path) = tab.path.as_ref() else {
+        return Vec::new();
+    };
+    if row >= tab.buffer.len_lines() {
+        return Vec::new();
+    }
+    let encoding = lsp_position_encoding_for_path(state, path);
+    let (line, character) = lsp_position_from_buffer_pos(tab, (row, col), encoding);
+    state.problems.items_at(path, line, character).collect()
+}
+
 pub(in crate::kernel::store) fn lsp_position_from_cursor(
     tab: &crate::kernel::editor::EditorTabState,
     encoding: LspPositionEncoding,
@@ -383,6 +416,17 @@ impl super::super::Store {
         &mut self,
         edit: LspWorkspaceEdit,
         effects: &mut Vec<crate::kernel::Effect>,
+    ) -> bool {
+        let encoding = lsp_position_encoding(&self.state);
+        self.apply_workspace_edit_with_encoding(edit, encoding, effects)
+    }
+
+    /// 非 LSP 来源（如全局替换）的编辑自带列编码，不跟随当前 LSP 会话。
+    pub(in crate::kernel::store) fn apply_workspace_edit_with_encoding(
+        &mut self,
+        edit: LspWorkspaceEdit,
+        encoding: LspPositionEncoding,
+        effects: &mut Vec<crate::kernel::Effect>,
     ) -> bool {
         let LspWorkspaceEdit {
             changes,
@@ -391,7 +435,6 @@ impl super::super::Store {
         let mut pending_file_edits: Vec<LspWorkspaceFileEdit> = Vec::new();
         let mut any_changed = false;
         let mut open_paths_changed = false;
-        let encoding = lsp_position_encoding(&self.state);
 
         let mut rename_forward: HashMap<std::path::PathBuf, std::path::PathBuf> = HashMap::new();
         let mut rename_backward: HashMap<std::path::PathBuf, std::path::PathBuf> = HashMap::new();
@@ -425,58 +468,19 @@ impl super::super::Store {
                 continue;
             }
 
-            let mut edits: Vec<&LspTextEdit> = file_edit.edits.iter().collect();
-            edits.sort_by(|a, b| {
-                b.range
-                    .start
-                    .line
-                    .cmp(&a.range.start.line)
-                    .then_with(|| b.range.start.character.cmp(&a.range.start.character))
-                    .then_with(|| b.range.end.line.cmp(&a.range.end.line))
-                    .then_with(|| b.range.end.character.cmp(&a.range.end.character))
-            });
-
             for (pane, tab_index) in targets {
-                for edit in &edits {
-                    let (start_byte, end_byte) = {
-                        let Some(tab) = self
-                            .state
-                            .editor
-                            .pane(pane)
-                            .and_then(|pane_state| pane_state.tabs.get(tab_index))
-                        else {
-                            continue;
-                        };
-
-                        let start = lsp_position_to_byte_offset(
-                            tab,
-                            edit.range.start.line,
-                            edit.range.start.character,
-                            encoding,
-                        );
-                        let end = lsp_position_to_byte_offset(
-                            tab,
-                            edit.range.end.line,
-                            edit.range.end.character,
+                let (changed, editor_effects) =
+                    self.state
+                        .editor
+                        .dispatch_action(EditorAction::ApplyLspEditsToTab {
+                            pane,
+                            tab_index,
+                            edits: file_edit.edits.clone(),
                             encoding,
-                        );
-                        (start, end)
-                    };
-
-                    let (changed, editor_effects) =
-                        self.state
-                            .editor
-                            .dispatch_action(EditorAction::ApplyTextEditToTab {
-                                pane,
-                                tab_index,
-                                start_byte,
-                                end_byte,
-                                text: edit.new_text.clone(),
-                            });
-                    effects.extend(editor_effects);
-                    if changed {
-                        any_changed = true;
-                    }
+                        });
+                effects.extend(editor_effects);
+                if changed {
+                    any_changed = true;
                 }
             }
         }
@@ -620,6 +624,16 @@ impl super::super::Store {
         }
     }
 
+    fn show_locations(&mut self, items: Vec<LocationItem>) -> super::super::DispatchResult {
+        let mut changed = self.state.locations.set_items(items);
+        changed |= self.open_overlay(OverlayKind::Locations);
+
+        super::super::DispatchResult {
+            effects: Vec::new(),
+            state_changed: changed,
+        }
+    }
+
     fn handle_definition(
         &mut self,
         path: std::path::PathBuf,
@@ -694,6 +708,7 @@ impl super::super::Store {
         capabilities: LspServerCapabilities,
     ) -> super::super::DispatchResult {
         let key = LspClientKey { server, root };
+        let reconnected = self.state.lsp.connection.remove(&key).is_some();
         let changed = match self.state.lsp.server_capabilities.get(&key) {
             Some(existing) if existing == &capabilities => false,
             _ => {
@@ -758,11 +773,18 @@ impl super::super::Store {
                         version,
                     });
                 }
+
+                if caps.document_link {
+                    effects.push(Effect::LspDocumentLinkRequest {
+                        path: path.clone(),
+                        version,
+                    });
+                }
             }
         }
         super::super::DispatchResult {
             effects,
-            state_changed: changed,
+            state_changed: changed || reconnected,
         }
     }
 
@@ -931,6 +953,188 @@ impl super::super::Store {
         }
     }
 
+    fn handle_document_links(
+        &mut self,
+        path: std::path::PathBuf,
+        version: u64,
+        links: Vec<crate::kernel::services::ports::LspDocumentLink>,
+    ) -> super::super::DispatchResult {
+        let encoding = lsp_position_encoding_for_path(&self.state, &path);
+        let mut changed = false;
+
+        for pane in &mut self.state.editor.panes {
+            for tab in &mut pane.tabs {
+                if tab.path.as_ref() != Some(&path) || tab.edit_version != version {
+                    continue;
+                }
+
+                let mut resolved = Vec::new();
+                let mut unresolved = Vec::new();
+                for link in &links {
+                    let (start, end) = lsp_range_to_byte_range(tab, &link.range, encoding);
+                    if start >= end {
+                        continue;
+                    }
+                    match link.target.as_ref() {
+                        Some(target) => resolved.push((start, end, target.clone())),
+                        None if link.data.is_some() => unresolved.push((start, end, link.clone())),
+                        None => {}
+                    }
+                }
+                resolved.sort_by_key(|(start, end, _)| (*start, *end));
+                unresolved.sort_by_key(|(start, end, _)| (*start, *end));
+                changed |= tab.set_document_links(resolved, unresolved);
+            }
+        }
+
+        super::super::DispatchResult {
+            effects: Vec::new(),
+            state_changed: changed,
+        }
+    }
+
+    fn handle_document_highlights(
+        &mut self,
+        path: std::path::PathBuf,
+        version: u64,
+        ranges: Vec<LspRange>,
+    ) -> super::super::DispatchResult {
+        let encoding = lsp_position_encoding_for_path(&self.state, &path);
+        let mut changed = false;
+
+        for pane in &mut self.state.editor.panes {
+            for tab in &mut pane.tabs {
+                if tab.path.as_ref() != Some(&path) || tab.edit_version != version {
+                    continue;
+                }
+
+                let rope = tab.buffer.rope();
+                let to_utf8 = |byte: usize| {
+                    let line = rope.byte_to_line(byte);
+                    LspPosition {
+                        line: line as u32,
+                        character: (byte - rope.line_to_byte(line)) as u32,
+                    }
+                };
+                let mut highlights = Vec::with_capacity(ranges.len());
+                for range in &ranges {
+                    let (start, end) = lsp_range_to_byte_range(tab, range, encoding);
+                    if start >= end {
+                        continue;
+                    }
+                    highlights.push(LspRange {
+                        start: to_utf8(start),
+                        end: to_utf8(end),
+                    });
+                }
+                highlights.sort_by_key(|r| (r.start.line, r.start.character));
+                changed |= tab.set_document_highlights(highlights);
+            }
+        }
+
+        super::super::DispatchResult {
+            effects: Vec::new(),
+            state_changed: changed,
+        }
+    }
+
+    fn handle_document_link_resolved(
+        &mut self,
+        path: std::path::PathBuf,
+        version: u64,
+        range: LspRange,
+        target: String,
+    ) -> super::super::DispatchResult {
+        let encoding = lsp_position_encoding_for_path(&self.state, &path);
+        let mut changed = false;
+
+        for pane in &mut self.state.editor.panes {
+            for tab in &mut pane.tabs {
+                if tab.path.as_ref() != Some(&path) || tab.edit_version != version {
+                    continue;
+                }
+                let (start, end) = lsp_range_to_byte_range(tab, &range, encoding);
+                changed |= tab.resolve_document_link(start, end, target.clone());
+            }
+        }
+
+        // resolve 只由点击触发：解析成功即打开。
+        let effects = if changed {
+            vec![Effect::OpenTerminalUrl(target)]
+        } else {
+            Vec::new()
+        };
+        super::super::DispatchResult {
+            effects,
+            state_changed: changed,
+        }
+    }
+
+    fn show_diagnostic_hover(
+        &mut self,
+        pane: usize,
+        row: usize,
+        col: usize,
+    ) -> super::super::DispatchResult {
+        let blocks: Vec<HoverBlock> = diagnostics_at(&self.state, pane, row, col)
+            .into_iter()
+            .map(|item| {
+                let mut text = format!("{}: {}", item.severity.label(), item.message.trim());
+                if let Some(source) = item.source.as_deref() {
+                    text.push_str(&format!(" ({source})"));
+                }
+                HoverBlock::PlainText(text)
+            })
+            .collect();
+        let next = (!blocks.is_empty()).then_some(HoverModel {
+            blocks,
+            range: None,
+        });
+        let changed = self.state.ui.hover.diagnostics != next;
+        self.state.ui.hover.diagnostics = next;
+        super::super::DispatchResult {
+            effects: Vec::new(),
+            state_changed: changed,
+        }
+    }
+
+    fn open_document_link_at(
+        &mut self,
+        pane: usize,
+        row: usize,
+        col: usize,
+    ) -> super::super::DispatchResult {
+        let Some(tab) = self.state.editor.pane(pane).and_then(|p| p.active_tab()) else {
+            return super::super::DispatchResult {
+                effects: Vec::new(),
+                state_changed: false,
+            };
+        };
+        let byte = tab
+            .buffer
+            .rope()
+            .char_to_byte(tab.buffer.pos_to_char((row, col)));
+
+        let effect = match tab.document_link_at(byte) {
+            Some(Ok(target)) => Some(Effect::OpenTerminalUrl(target.to_string())),
+            Some(Err(link)) => tab.path.as_ref().and_then(|path| {
+                lsp_server_capabilities_for_path(&self.state, path)
+                    .is_some_and(|c| c.document_link_resolve)
+                    .then(|| Effect::LspDocumentLinkResolveRequest {
+                        path: path.clone(),
+                        version: tab.edit_version,
+                        link: link.clone(),
+                    })
+            }),
+            None => None,
+        };
+
+        super::super::DispatchResult {
+            effects: effect.into_iter().collect(),
+            state_changed: false,
+        }
+    }
+
     fn handle_completion(
         &mut self,
         items: Vec<crate::kernel::services::ports::LspCompletionItem>,
@@ -1004,7 +1208,21 @@ impl super::super::Store {
                 .collect::<Vec<_>>()
         };
         let language = tab.language();
-        sort_completion_items(&mut all_items, &self.completion_ranker, language);
+        let recent_suffix = if self.state.editor.config.completion_boost_recent {
+            tab.path
+                .as_deref()
+                .and_then(RecentlyUsedCompletionRanker::path_suffix)
+        } else {
+            None
+        };
+        sort_completion_items(
+            &mut all_items,
+            &self.completion_ranker,
+            language,
+            recent_suffix
+                .as_deref()
+                .map(|suffix| (&self.recent_completions, suffix)),
+        );
         self.state.ui.completion.all_items = all_items;
         self.state.ui.completion.rebuild_index_by_id();
         self.state.ui.completion.invalidate_filter_cache();
@@ -1191,6 +1409,104 @@ impl super::super::Store {
         }
     }
 
+    /// `format_on_save` 开启且服务端支持格式化时，保存改为先请求格式化，结果返回后再写盘；
+    /// 返回 `Some` 时由它接管这次保存。服务端尚未就绪（能力未知）时不等待，直接保存。
+    /// 同一文件已有格式化在途时不再重复请求：两份结果都基于格式化前的文本，后到的那份会把缓冲区改乱。
+    pub(in crate::kernel::store) fn format_before_save(
+        &mut self,
+        pane: usize,
+    ) -> Option<Vec<Effect>> {
+        if !self.state.editor_config_for_pane(pane).format_on_save {
+            return None;
+        }
+        let tab = self.state.editor.pane(pane)?.active_tab()?;
+        if tab.partial_load.is_some() {
+            return None;
+        }
+        let path = tab.path.clone()?;
+        if !is_lsp_source_path(&path)
+            || !lsp_server_capabilities_for_path(&self.state, &path).is_some_and(|c| c.format)
+        {
+            return None;
+        }
+        if self.state.lsp.pending_format_on_save.as_ref() == Some(&path) {
+            return Some(Vec::new());
+        }
+        self.state.lsp.pending_format_on_save_version = Some(tab.edit_version);
+        self.state.lsp.pending_format_on_save = Some(path.clone());
+        Some(vec![Effect::LspFormatRequest { path }])
+    }
+
+    /// 放弃在途的保存前格式化，直接保存该文件（服务端断开、或结果已过时）。
+    fn save_pending_format_without_formatting(&mut self, effects: &mut Vec<Effect>) -> bool {
+        self.state.lsp.pending_format_on_save_version = None;
+        let Some(path) = self.state.lsp.pending_format_on_save.take() else {
+            return false;
+        };
+        let Some(&(pane, tab_index)) = open_tabs_for_path(&self.state.editor, &path).first() else {
+            return false;
+        };
+        let (changed, save_effects) = self.state.editor.save_tab_at(pane, tab_index);
+        effects.extend(save_effects);
+        changed
+    }
+
+    fn handle_format_completed(
+        &mut self,
+        path: std::path::PathBuf,
+        edits: Vec<LspTextEdit>,
+    ) -> super::super::DispatchResult {
+        let mut effects = Vec::new();
+        if self.state.lsp.pending_format_on_save.as_ref() != Some(&path) {
+            let changed = self.apply_workspace_edit(
+                LspWorkspaceEdit {
+                    changes: vec![LspWorkspaceFileEdit { path, edits }],
+                    ..Default::default()
+                },
+                &mut effects,
+            );
+            return super::super::DispatchResult {
+                effects,
+                state_changed: changed,
+            };
+        }
+
+        let version = self.state.lsp.pending_format_on_save_version;
+        // 请求发出后又有编辑：结果基于旧内容，应用会错位，丢弃格式化结果，照常保存当前内容。
+        let Some((pane, tab_index)) = open_tabs_for_path(&self.state.editor, &path)
+            .into_iter()
+            .find(|&(pane, tab_index)| {
+                self.state
+                    .editor
+                    .pane(pane)
+                    .and_then(|pane| pane.tabs.get(tab_index))
+                    .is_some_and(|tab| Some(tab.edit_version) == version)
+            })
+        else {
+            let changed = self.save_pending_format_without_formatting(&mut effects);
+            return super::super::DispatchResult {
+                effects,
+                state_changed: changed,
+            };
+        };
+        self.state.lsp.pending_format_on_save = None;
+        self.state.lsp.pending_format_on_save_version = None;
+
+        self.apply_workspace_edit(
+            LspWorkspaceEdit {
+                changes: vec![LspWorkspaceFileEdit { path, edits }],
+                ..Default::default()
+            },
+            &mut effects,
+        );
+        let (_, save_effects) = self.state.editor.save_tab_at(pane, tab_index);
+        effects.extend(save_effects);
+        super::super::DispatchResult {
+            effects,
+            state_changed: true,
+        }
+    }
+
     fn handle_signature_help(
         &mut self,
         payload: crate::kernel::services::ports::LspSignatureHelpPayload,
@@ -1263,18 +1579,41 @@ impl super::super::Store {
             Action::LspHoverDefinitionPreview { session, payload } => {
                 self.handle_hover_definition_preview(session, payload)
             }
-            Action::LspDefinition { path, line, column } => {
+            Action::LspDefinition { path, line, column }
+            | Action::LspTypeDefinition { path, line, column } => {
                 self.handle_definition(path, line, column)
             }
-            Action::LspReferences { items } => {
-                let mut changed = self.state.locations.set_items(items);
-                changed |= self.open_overlay(OverlayKind::Locations);
-
+            Action::LspPrepareRenameResponse {
+                path,
+                line,
+                column,
+                valid,
+                placeholder,
+            } => {
+                // 请求期间光标挪动或已打开别的输入框时丢弃结果。
+                let still_at_target = lsp_request_target(&self.state)
+                    .is_some_and(|(_, p, l, c, _)| p == path && l == line && c == column);
+                let state_changed = valid
+                    && still_at_target
+                    && !self.state.ui.input_dialog.visible
+                    && self.open_lsp_rename_dialog(path, line, column, placeholder);
                 super::super::DispatchResult {
                     effects: Vec::new(),
-                    state_changed: changed,
+                    state_changed,
                 }
             }
+            Action::LspImplementation { mut items } => match items.len() {
+                0 => super::super::DispatchResult {
+                    effects: Vec::new(),
+                    state_changed: false,
+                },
+                1 => {
+                    let item = items.remove(0);
+                    self.handle_definition(item.path, item.line, item.column)
+                }
+                _ => self.show_locations(items),
+            },
+            Action::LspReferences { items } => self.show_locations(items),
             Action::LspCodeActions { items } => {
                 let mut changed = self.state.code_actions.set_items(items);
                 changed |= self.open_overlay(OverlayKind::CodeActions);
@@ -1293,11 +1632,55 @@ impl super::super::Store {
                     state_changed: changed,
                 }
             }
+            Action::LspWorkspaceSymbolsPartial { items } => {
+                let mut changed = self.state.symbols.extend_streaming(items);
+                changed |= self.open_overlay(OverlayKind::Symbols);
+
+                super::super::DispatchResult {
+                    effects: Vec::new(),
+                    state_changed: changed,
+                }
+            }
+            Action::LspWorkspaceSymbols { items } => {
+                let mut changed = self.state.symbols.finish_streaming(items);
+                changed |= self.open_overlay(OverlayKind::Symbols);
+
+                super::super::DispatchResult {
+                    effects: Vec::new(),
+                    state_changed: changed,
+                }
+            }
             Action::LspServerCapabilities {
                 server,
                 root,
                 capabilities,
             } => self.handle_server_capabilities(server, root, capabilities),
+            Action::LspConnectionChanged {
+                server,
+                root,
+                status,
+            } => {
+                let key = LspClientKey { server, root };
+                // 旧进程的能力随进程一起失效；重连成功后会重新下发。
+                let mut changed = self.state.lsp.server_capabilities.remove(&key).is_some();
+                changed |= self.state.lsp.connection.insert(key.clone(), status) != Some(status);
+                // 在途请求随进程丢失，等待中的保存前格式化永远等不到结果，直接保存。
+                let mut effects = Vec::new();
+                let pending_on_this_client = self
+                    .state
+                    .lsp
+                    .pending_format_on_save
+                    .as_deref()
+                    .and_then(|path| lsp_client_key_for_path(&self.state, path))
+                    .is_some_and(|pending_key| pending_key.server == key.server);
+                if pending_on_this_client {
+                    changed |= self.save_pending_format_without_formatting(&mut effects);
+                }
+                super::super::DispatchResult {
+                    effects,
+                    state_changed: changed,
+                }
+            }
             Action::LspInlayHints {
                 path,
                 version,
@@ -1309,6 +1692,37 @@ impl super::super::Store {
                 version,
                 ranges,
             } => self.handle_folding_ranges(path, version, ranges),
+            Action::LspDocumentLinks {
+                path,
+                version,
+                links,
+            } => self.handle_document_links(path, version, links),
+            Action::LspDocumentLinkResolved {
+                path,
+                version,
+                range,
+                target,
+            } => self.handle_document_link_resolved(path, version, range, target),
+            Action::LspDocumentHighlights {
+                path,
+                version,
+                ranges,
+            } => self.handle_document_highlights(path, version, ranges),
+            Action::OpenDocumentLinkAt { pane, row, col } => {
+                self.open_document_link_at(pane, row, col)
+            }
+            Action::ShowDiagnosticHover { pane, row, col } => {
+                self.show_diagnostic_hover(pane, row, col)
+            }
+            Action::DiagnosticClicked { pane, row, col } => {
+                if diagnostics_at(&self.state, pane, row, col).is_empty() {
+                    return super::super::DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: false,
+                    };
+                }
+                self.reduce_lsp_command(crate::core::Command::LspCodeAction)
+            }
             Action::LspCompletion {
                 items,
                 is_incomplete,
@@ -1343,6 +1757,7 @@ impl super::super::Store {
                     state_changed: changed,
                 }
             }
+            Action::LspFormatCompleted { path, edits } => self.handle_format_completed(path, edits),
             _ => unreachable!("non-lsp action passed to reduce_lsp_action"),
         }
     }
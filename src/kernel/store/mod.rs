@@ -1,5 +1,7 @@
 use crate::core::Command;
-use crate::kernel::editor::ReloadCause;
+use crate::kernel::editor::{EditorMode, ReloadCause};
+use crate::kernel::services::ports::EditorConfig;
+use crate::kernel::services::KernelMiddleware;
 
 #[cfg(test)]
 use crate::kernel::services::ports::{LspCompletionItem, LspPositionEncoding};
@@ -7,6 +9,12 @@ use crate::kernel::services::ports::{LspCompletionItem, LspPositionEncoding};
 pub(crate) mod intel;
 mod util;
 
+#[path = "reducers/call_hierarchy.rs"]
+mod call_hierarchy;
+#[path = "reducers/closed_tabs.rs"]
+mod closed_tabs;
+#[path = "reducers/command_line.rs"]
+mod command_line;
 #[path = "reducers/completion.rs"]
 mod completion;
 #[path = "reducers/confirm_dialog.rs"]
@@ -19,6 +27,8 @@ mod editor_command;
 mod explorer;
 #[path = "reducers/explorer_command.rs"]
 mod explorer_command;
+#[path = "reducers/git.rs"]
+mod git;
 #[path = "reducers/input_dialog.rs"]
 mod input_dialog;
 #[path = "reducers/lsp_command.rs"]
@@ -27,22 +37,27 @@ mod lsp_command;
 mod search;
 #[path = "reducers/search_command.rs"]
 mod search_command;
+#[path = "reducers/vim.rs"]
+mod vim;
+#[path = "reducers/workspace_folders.rs"]
+mod workspace_folders;
 
 #[cfg(test)]
 use intel::completion::{apply_completion_insertion_cursor, CompletionInsertion};
 use intel::completion::{
-    completion_runtime_context, language_runtime_context_with_syntax,
+    completion_runtime_context, insert_snippet_into_tab, language_runtime_context_with_syntax,
     should_close_completion_on_editor_action,
 };
-pub use intel::completion_rank::CompletionRanker;
+pub use intel::completion_rank::{CompletionRanker, RecentlyUsedCompletionRanker};
 
 #[cfg(test)]
 use intel::lsp::lsp_range_for_full_lines;
 use intel::lsp::{lsp_position_encoding, lsp_position_to_byte_offset};
 
-#[cfg(test)]
-use super::InputDialogKind;
-use super::{Action, AppState, EditorAction, Effect, FocusTarget, OverlayKind};
+use super::{
+    Action, AppState, EditorAction, Effect, FilePathPurpose, FocusTarget, InputDialogKind,
+    OverlayKind,
+};
 use crate::kernel::language::{
     adapter::adapter_for_tab, adapter::SyntaxFacts, adapter_for, CompletionRecord,
     CompletionResolveState,
@@ -53,6 +68,14 @@ pub struct DispatchResult {
     pub state_changed: bool,
 }
 
+/// 分发统计用的动作标签：`RunCommand` 细分到具体命令，其余按动作类型。
+pub(crate) fn dispatch_label(action: &Action) -> &'static str {
+    match action {
+        Action::RunCommand(cmd) => perf_command_label(cmd),
+        action => perf_action_label(action),
+    }
+}
+
 fn perf_action_label(action: &Action) -> &'static str {
     match action {
         Action::RunCommand(_) => "kernel.action.run_command",
@@ -62,6 +85,8 @@ fn perf_action_label(action: &Action) -> &'static str {
         Action::LspCompletionResolved { .. } => "kernel.action.lsp_completion_resolved",
         Action::LspInlayHints { .. } => "kernel.action.lsp_inlay_hints",
         Action::LspFoldingRanges { .. } => "kernel.action.lsp_folding_ranges",
+        Action::LspDocumentLinks { .. } => "kernel.action.lsp_document_links",
+        Action::LspDocumentHighlights { .. } => "kernel.action.lsp_document_highlights",
         Action::LspDiagnostics { .. } => "kernel.action.lsp_diagnostics",
         Action::LspHoverClear => "kernel.action.lsp_hover_clear",
         Action::LspHoverResponse { .. } => "kernel.action.lsp_hover_response",
@@ -70,17 +95,30 @@ fn perf_action_label(action: &Action) -> &'static str {
         }
         Action::LspHoverDefinitionPreview { .. } => "kernel.action.lsp_hover_definition_preview",
         Action::LspDefinition { .. } => "kernel.action.lsp_definition",
+        Action::LspTypeDefinition { .. } => "kernel.action.lsp_type_definition",
+        Action::LspImplementation { .. } => "kernel.action.lsp_implementation",
         Action::LspReferences { .. } => "kernel.action.lsp_references",
+        Action::LspPrepareRenameResponse { .. } => "kernel.action.lsp_prepare_rename_response",
+        Action::LspCallHierarchyPrepared { .. } => "kernel.action.lsp_call_hierarchy_prepared",
+        Action::LspCallHierarchyIncoming { .. } => "kernel.action.lsp_call_hierarchy_incoming",
+        Action::LspCallHierarchyOutgoing { .. } => "kernel.action.lsp_call_hierarchy_outgoing",
         Action::LspCodeActions { .. } => "kernel.action.lsp_code_actions",
         Action::LspSymbols { .. } => "kernel.action.lsp_symbols",
+        Action::LspWorkspaceSymbolsPartial { .. } => "kernel.action.lsp_workspace_symbols_partial",
+        Action::LspWorkspaceSymbols { .. } => "kernel.action.lsp_workspace_symbols",
         Action::LspSignatureHelp { .. } => "kernel.action.lsp_signature_help",
         Action::LspApplyWorkspaceEdit { .. } => "kernel.action.lsp_apply_workspace_edit",
+        Action::LspFormatCompleted { .. } => "kernel.action.lsp_format_completed",
         Action::LspServerCapabilities { .. } => "kernel.action.lsp_server_capabilities",
+        Action::LspConnectionChanged { .. } => "kernel.action.lsp_connection_changed",
         Action::LspProgressEnd => "kernel.action.lsp_progress_end",
         Action::SearchMessage(_) => "kernel.action.search_message",
         Action::SearchStarted { .. } => "kernel.action.search_started",
+        Action::DirBatchLoaded { .. } => "kernel.action.dir_batch_loaded",
         Action::DirLoaded { .. } => "kernel.action.dir_loaded",
         Action::DirLoadError { .. } => "kernel.action.dir_load_error",
+        Action::WorkspaceFolderAdded { .. } => "kernel.action.workspace_folder_added",
+        Action::RestoreUiSnapshot { .. } => "kernel.action.restore_ui_snapshot",
         _ => "kernel.action.other",
     }
 }
@@ -98,6 +136,8 @@ fn perf_command_label(command: &Command) -> &'static str {
         Command::LspCompletion => "kernel.command.lsp_completion",
         Command::LspInlayHints => "kernel.command.lsp_inlay_hints",
         Command::LspFoldingRange => "kernel.command.lsp_folding_range",
+        Command::LspDocumentLink => "kernel.command.lsp_document_link",
+        Command::LspDocumentHighlight => "kernel.command.lsp_document_highlight",
         Command::LspHover => "kernel.command.lsp_hover",
         Command::LspSignatureHelp => "kernel.command.lsp_signature_help",
         Command::EditorSearchBarBackspace => "kernel.command.editor_search_backspace",
@@ -107,6 +147,7 @@ fn perf_command_label(command: &Command) -> &'static str {
         Command::Save => "kernel.command.save",
         Command::OpenFile => "kernel.command.open_file",
         Command::CloseTab => "kernel.command.close_tab",
+        Command::ReopenClosedTab => "kernel.command.reopen_closed_tab",
         Command::FocusEditor => "kernel.command.focus_editor",
         Command::FocusExplorer => "kernel.command.focus_explorer",
         Command::FocusSearch => "kernel.command.focus_search",
@@ -120,6 +161,9 @@ fn perf_command_label(command: &Command) -> &'static str {
 pub struct Store {
     state: AppState,
     completion_ranker: CompletionRanker,
+    recent_completions: RecentlyUsedCompletionRanker,
+    /// 归约 pane 定向动作期间暂存的全局编辑器配置（见 `dispatch`）。
+    pane_config_global: Option<EditorConfig>,
 }
 
 impl Store {
@@ -127,6 +171,8 @@ impl Store {
         Self {
             state,
             completion_ranker: CompletionRanker::default(),
+            recent_completions: RecentlyUsedCompletionRanker::default(),
+            pane_config_global: None,
         }
     }
 
@@ -134,6 +180,8 @@ impl Store {
         Self {
             state,
             completion_ranker,
+            recent_completions: RecentlyUsedCompletionRanker::default(),
+            pane_config_global: None,
         }
     }
 
@@ -245,17 +293,27 @@ impl Store {
         self.completion_ranker.clear_dirty();
     }
 
-    pub fn tick(&mut self) {
+    pub fn tick(&mut self) -> bool {
         let now = std::time::Instant::now();
+        let show_unsaved_marks = self.state.editor.config.show_unsaved_gutter_marks;
+        let mut changed = false;
         for pane in &mut self.state.editor.panes {
-            for tab in &mut pane.tabs {
+            let active = pane.active;
+            for (idx, tab) in pane.tabs.iter_mut().enumerate() {
                 if let crate::kernel::editor::DiskState::ReloadedFromDisk { at } = tab.disk_state {
                     if now.duration_since(at) >= std::time::Duration::from_secs(3) {
                         tab.disk_state = crate::kernel::editor::DiskState::InSync;
                     }
                 }
+                // 只有可见的标签页需要未保存改动标记。
+                changed |= if show_unsaved_marks && idx == active {
+                    tab.refresh_unsaved_marks()
+                } else {
+                    tab.clear_unsaved_marks()
+                };
             }
         }
+        self.state.editor.tick_scroll_animations() || changed
     }
 
     fn reconcile_signature_help_visibility(&mut self) -> bool {
@@ -315,6 +373,53 @@ impl Store {
     }
 
     pub fn dispatch(&mut self, action: Action) -> DispatchResult {
+        let pane = match &action {
+            Action::RunCommand(_) => Some(self.state.ui.editor_layout.active_pane),
+            Action::Editor(editor_action) => editor_action.pane(),
+            _ => None,
+        };
+        // pane 定向动作在该 pane 的有效配置下归约：临时把全局配置换成叠加了覆盖项
+        // 的版本，归约完再换回。嵌套 dispatch 沿用外层已换入的配置。
+        let overrides = pane
+            .filter(|_| self.pane_config_global.is_none())
+            .and_then(|pane| self.state.ui.editor_layout.pane_config_override(pane));
+        let swapped = overrides.is_some();
+        if let Some(overrides) = overrides {
+            let merged = self.state.editor.config.merged_with(Some(overrides));
+            self.pane_config_global =
+                Some(std::mem::replace(&mut self.state.editor.config, merged));
+        }
+
+        let result = self.reduce(action);
+
+        if swapped {
+            if let Some(global) = self.pane_config_global.take() {
+                self.state.editor.config = global;
+            }
+        }
+        result
+    }
+
+    /// 带中间件的分发：动作按顺序经过每个中间件的 `before_dispatch`（可被替换），
+    /// 归约后再把结果交给每个中间件的 `after_dispatch`。
+    pub fn dispatch_through(
+        &mut self,
+        middleware: &mut [Box<KernelMiddleware>],
+        mut action: Action,
+    ) -> DispatchResult {
+        for m in middleware.iter_mut() {
+            if let Some(replaced) = m.before_dispatch(&action) {
+                action = replaced;
+            }
+        }
+        let result = self.dispatch(action);
+        for m in middleware.iter_mut() {
+            m.after_dispatch(&result);
+        }
+        result
+    }
+
+    fn reduce(&mut self, action: Action) -> DispatchResult {
         let _action_scope =
             crate::kernel::services::adapters::perf::scope(perf_action_label(&action));
         match action {
@@ -361,6 +466,16 @@ impl Store {
             }
             Action::Editor(editor_action) => {
                 let prev_active_file = self.active_editor_file_path();
+                // 落盘内容变了，这个文件缓存的行内 blame 全部作废。
+                if let EditorAction::Saved {
+                    path,
+                    success: true,
+                    ..
+                }
+                | EditorAction::FileExternallyModified { path } = &editor_action
+                {
+                    self.state.git.invalidate_blame(path);
+                }
                 let completion_changed = if should_close_completion_on_editor_action(&editor_action)
                 {
                     self.state.ui.completion.close()
@@ -376,6 +491,8 @@ impl Store {
                             content,
                         } => {
                             let opened_path = path.clone();
+                            self.queue_remembered_file_position(pane, &path);
+                            self.state.remember_recent_file(&path);
                             let pending = self
                                 .state
                                 .ui
@@ -465,10 +582,12 @@ impl Store {
                             }
                         }
                         EditorAction::CloseTabAt { pane, index } => {
-                            let (state_changed, effects) = self
+                            let mut effects = self.remember_closed_tabs(pane, &[index]);
+                            let (state_changed, close_effects) = self
                                 .state
                                 .editor
                                 .dispatch_action(EditorAction::CloseTabAt { pane, index });
+                            effects.extend(close_effects);
 
                             DispatchResult {
                                 effects,
@@ -476,10 +595,26 @@ impl Store {
                             }
                         }
                         EditorAction::CloseTabsById { pane, tab_ids } => {
-                            let (state_changed, effects) = self
+                            let indices = self
+                                .state
+                                .editor
+                                .pane(pane)
+                                .map(|pane_state| {
+                                    pane_state
+                                        .tabs
+                                        .iter()
+                                        .enumerate()
+                                        .filter(|(_, tab)| tab_ids.contains(&tab.id.raw()))
+                                        .map(|(index, _)| index)
+                                        .collect::<Vec<_>>()
+                                })
+                                .unwrap_or_default();
+                            let mut effects = self.remember_closed_tabs(pane, &indices);
+                            let (state_changed, close_effects) = self
                                 .state
                                 .editor
                                 .dispatch_action(EditorAction::CloseTabsById { pane, tab_ids });
+                            effects.extend(close_effects);
 
                             DispatchResult {
                                 effects,
@@ -514,19 +649,41 @@ impl Store {
                 effects: Vec::new(),
                 state_changed: false,
             },
-            Action::EditorConfigUpdated { config } => {
+            Action::EditorConfigUpdated { config, lsp } => {
+                let mut effects = Vec::new();
+                if self.state.lsp.settings != lsp {
+                    self.state.lsp.settings = lsp.clone();
+                    effects.push(Effect::ReconfigureLsp { settings: lsp });
+                }
                 if self.state.editor.config == config {
-                    DispatchResult {
-                        effects: Vec::new(),
+                    return DispatchResult {
+                        effects,
                         state_changed: false,
+                    };
+                }
+
+                if self.state.editor.config.vim_mode != config.vim_mode {
+                    for pane in &mut self.state.editor.panes {
+                        pane.set_vim_mode(config.vim_mode);
                     }
-                } else {
-                    self.state.editor.config = config;
-                    DispatchResult {
-                        effects: Vec::new(),
-                        state_changed: true,
+                }
+                let prev = &self.state.editor.config;
+                if prev.word_wrap != config.word_wrap || prev.tab_size != config.tab_size {
+                    for tab in self
+                        .state
+                        .editor
+                        .panes
+                        .iter_mut()
+                        .flat_map(|pane| pane.tabs.iter_mut())
+                    {
+                        tab.set_word_wrap(config.word_wrap, config.tab_size);
                     }
                 }
+                self.state.editor.config = config;
+                DispatchResult {
+                    effects,
+                    state_changed: true,
+                }
             }
             action @ Action::InputDialogAppend(_)
             | action @ Action::InputDialogBackspace
@@ -553,6 +710,59 @@ impl Store {
                         || self.reconcile_signature_help_visibility(),
                 }
             }
+            Action::EditorScrollHorizontal { pane, delta } => {
+                let step = self.state.editor.config.horizontal_scroll_columns() as isize;
+                let delta_columns = (delta as isize).saturating_mul(step);
+                let (state_changed, effects) =
+                    self.state
+                        .editor
+                        .dispatch_action(EditorAction::ScrollHorizontalAnimated {
+                            pane,
+                            delta_columns,
+                        });
+                DispatchResult {
+                    effects,
+                    state_changed,
+                }
+            }
+            Action::EditorInsertSnippet { pane, template } => {
+                let tab_size = self.state.editor.config.tab_size;
+                let Some(tab) = self
+                    .state
+                    .editor
+                    .pane_mut(pane)
+                    .and_then(|p| p.active_tab_mut())
+                else {
+                    return DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: false,
+                    };
+                };
+                if tab.is_multi_cursor() {
+                    return DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: false,
+                    };
+                }
+
+                let (start_char, end_char) = match tab.buffer.selection() {
+                    Some(selection) if !selection.is_empty() => {
+                        let (start, end) = selection.range();
+                        (tab.buffer.pos_to_char(start), tab.buffer.pos_to_char(end))
+                    }
+                    _ => {
+                        let cursor = tab.buffer.pos_to_char(tab.buffer.cursor());
+                        (cursor, cursor)
+                    }
+                };
+                let inserted =
+                    insert_snippet_into_tab(tab, start_char, end_char, &template, tab_size);
+                let completion_changed = inserted && self.state.ui.completion.close();
+                DispatchResult {
+                    effects: Vec::new(),
+                    state_changed: inserted || completion_changed,
+                }
+            }
             Action::SidebarSetWidth { width } => {
                 let width = width.max(1);
                 let prev = self.state.ui.sidebar_width;
@@ -574,7 +784,11 @@ impl Store {
             | action @ Action::ExplorerActivate
             | action @ Action::ExplorerCollapse
             | action @ Action::ExplorerClickRow { .. }
-            | action @ Action::ExplorerMovePath { .. } => self.reduce_explorer_action(action),
+            | action @ Action::ExplorerMovePath { .. }
+            | action @ Action::ExplorerDragStart { .. }
+            | action @ Action::ExplorerDragHover { .. }
+            | action @ Action::ExplorerDrop { .. }
+            | action @ Action::ExplorerDragCancel => self.reduce_explorer_action(action),
             action @ Action::SearchSetViewHeight { .. }
             | action @ Action::SearchAppend(_)
             | action @ Action::SearchBackspace
@@ -613,22 +827,49 @@ impl Store {
                 effects: Vec::new(),
                 state_changed: self.state.symbols.set_view_height(height),
             },
+            action @ Action::GitClickRow { .. }
+            | action @ Action::GitSetViewHeight { .. }
+            | action @ Action::GitStashList { .. }
+            | action @ Action::GitBranchesUpdated { .. }
+            | action @ Action::GitBlameResult { .. }
+            | action @ Action::GitDiffBaseLoaded { .. } => self.reduce_git_action(action),
+            action @ Action::CallHierarchyClickRow { .. }
+            | action @ Action::CallHierarchySetViewHeight { .. }
+            | action @ Action::CallHierarchyExpand { .. }
+            | action @ Action::LspCallHierarchyPrepared { .. }
+            | action @ Action::LspCallHierarchyIncoming { .. }
+            | action @ Action::LspCallHierarchyOutgoing { .. } => {
+                self.reduce_call_hierarchy_action(action)
+            }
             action @ Action::LspDiagnostics { .. }
             | action @ Action::LspHoverClear
             | action @ Action::LspHoverResponse { .. }
             | action @ Action::LspHoverImplementationPreview { .. }
             | action @ Action::LspHoverDefinitionPreview { .. }
             | action @ Action::LspDefinition { .. }
+            | action @ Action::LspTypeDefinition { .. }
+            | action @ Action::LspImplementation { .. }
             | action @ Action::LspReferences { .. }
+            | action @ Action::LspPrepareRenameResponse { .. }
             | action @ Action::LspCodeActions { .. }
             | action @ Action::LspSymbols { .. }
+            | action @ Action::LspWorkspaceSymbolsPartial { .. }
+            | action @ Action::LspWorkspaceSymbols { .. }
             | action @ Action::LspServerCapabilities { .. }
+            | action @ Action::LspConnectionChanged { .. }
             | action @ Action::LspInlayHints { .. }
             | action @ Action::LspFoldingRanges { .. }
+            | action @ Action::LspDocumentLinks { .. }
+            | action @ Action::LspDocumentLinkResolved { .. }
+            | action @ Action::LspDocumentHighlights { .. }
+            | action @ Action::OpenDocumentLinkAt { .. }
+            | action @ Action::ShowDiagnosticHover { .. }
+            | action @ Action::DiagnosticClicked { .. }
             | action @ Action::LspCompletion { .. }
             | action @ Action::LspCompletionResolved { .. }
             | action @ Action::LspSignatureHelp { .. }
-            | action @ Action::LspApplyWorkspaceEdit { .. } => self.reduce_lsp_action(action),
+            | action @ Action::LspApplyWorkspaceEdit { .. }
+            | action @ Action::LspFormatCompleted { .. } => self.reduce_lsp_action(action),
             Action::LspProgressEnd => DispatchResult {
                 effects: Vec::new(),
                 state_changed: true,
@@ -636,6 +877,10 @@ impl Store {
             action @ Action::CompletionClose
             | action @ Action::CompletionMoveSelection { .. }
             | action @ Action::CompletionConfirm => self.reduce_completion_action(action),
+            Action::DirBatchLoaded { path, entries } => DispatchResult {
+                effects: Vec::new(),
+                state_changed: self.state.explorer.apply_dir_batch_loaded(path, entries),
+            },
             Action::DirLoaded { path, entries } => DispatchResult {
                 effects: Vec::new(),
                 state_changed: self.state.explorer.apply_dir_loaded(path, entries),
@@ -644,6 +889,25 @@ impl Store {
                 effects: Vec::new(),
                 state_changed: self.state.explorer.apply_dir_load_error(path),
             },
+            action @ Action::WorkspaceFolderAdded { .. } => {
+                self.reduce_workspace_folder_action(action)
+            }
+            Action::RestoreUiSnapshot {
+                focus,
+                mut editor_layout,
+                search_query,
+            } => {
+                editor_layout.panes = editor_layout.panes.max(1);
+                editor_layout.active_pane = editor_layout.active_pane.min(editor_layout.panes - 1);
+                self.state.editor.ensure_panes(editor_layout.panes);
+                self.state.ui.editor_layout = editor_layout;
+                self.state.ui.focus = focus;
+                self.state.search.query = search_query;
+                DispatchResult {
+                    effects: Vec::new(),
+                    state_changed: true,
+                }
+            }
             Action::ExplorerPathCreated { path, is_dir } => DispatchResult {
                 effects: Vec::new(),
                 state_changed: self.state.explorer.apply_path_created(path, is_dir),
@@ -696,24 +960,18 @@ impl Store {
                     state_changed,
                 }
             }
-            Action::CommandLineAppend(ch) => {
-                if !self.state.ui.command_line.active {
-                    DispatchResult {
-                        effects: Vec::new(),
-                        state_changed: false,
-                    }
-                } else {
-                    let line = &mut self.state.ui.command_line;
-                    let cursor = line.cursor.min(line.input.len());
-                    line.input.insert(cursor, ch);
-                    line.cursor = cursor + ch.len_utf8();
-                    line.selected = 0;
-                    DispatchResult {
-                        effects: Vec::new(),
-                        state_changed: true,
-                    }
+            Action::ExplorerOpApplied { inverse, origin } => {
+                self.state.explorer_history.record(inverse, origin);
+                DispatchResult {
+                    effects: Vec::new(),
+                    state_changed: false,
                 }
             }
+            action @ Action::CommandLineAppend(_)
+            | action @ Action::CommandLineFilesListed { .. }
+            | action @ Action::ClipboardHistoryLoaded { .. } => {
+                self.reduce_command_line_action(action)
+            }
             Action::SetHoveredTab { pane, index } => {
                 let prev = self.state.ui.hovered_tab;
                 self.state.ui.hovered_tab = Some((pane, index));
@@ -802,6 +1060,15 @@ impl Store {
                     };
                 }
 
+                let leaves_vim_mode =
+                    self.state.editor.config.vim_mode
+                        && self.state.editor.pane(pane).is_some_and(|p| {
+                            p.mode != EditorMode::Normal || p.vim_pending.is_some()
+                        });
+                if leaves_vim_mode {
+                    return self.reduce_vim_command(Command::VimNormalMode);
+                }
+
                 let has_selection = self
                     .state
                     .editor
@@ -819,6 +1086,21 @@ impl Store {
                     };
                 }
 
+                // 光标停在空占位符 / `$0` 上时没有选区可清，Esc 直接结束 snippet 会话。
+                if let Some(tab) = self
+                    .state
+                    .editor
+                    .pane_mut(pane)
+                    .and_then(|p| p.active_tab_mut())
+                    .filter(|t| t.snippet_active_range().is_some())
+                {
+                    tab.cancel_snippet_session();
+                    return DispatchResult {
+                        effects,
+                        state_changed: true,
+                    };
+                }
+
                 // 没有任何可关闭的东西时，Esc 是无操作——绝不打开设置文件。
                 return DispatchResult {
                     effects,
@@ -844,6 +1126,137 @@ impl Store {
                     state_changed: false,
                 };
             }
+            Command::ExportKeybindings => {
+                if self.state.ui.input_dialog.visible {
+                    return DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: false,
+                    };
+                }
+                let default_path = self
+                    .state
+                    .workspace_root
+                    .join("keybindings.json")
+                    .to_string_lossy()
+                    .to_string();
+                let dialog = &mut self.state.ui.input_dialog;
+                dialog.reset();
+                dialog.visible = true;
+                dialog.title = "Export Keybindings To".to_string();
+                dialog.cursor = default_path.len();
+                dialog.value = default_path;
+                dialog.kind = Some(InputDialogKind::FilePath {
+                    purpose: FilePathPurpose::ExportKeybindings,
+                });
+                return DispatchResult {
+                    effects: Vec::new(),
+                    state_changed: true,
+                };
+            }
+            Command::ImportKeybindings => {
+                if self.state.ui.input_dialog.visible {
+                    return DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: false,
+                    };
+                }
+                let default_path = self
+                    .state
+                    .workspace_root
+                    .join("keybindings.json")
+                    .to_string_lossy()
+                    .to_string();
+                let dialog = &mut self.state.ui.input_dialog;
+                dialog.reset();
+                dialog.visible = true;
+                dialog.title = "Import Keybindings From".to_string();
+                dialog.cursor = default_path.len();
+                dialog.value = default_path;
+                dialog.kind = Some(InputDialogKind::FilePath {
+                    purpose: FilePathPurpose::ImportKeybindings,
+                });
+                return DispatchResult {
+                    effects: Vec::new(),
+                    state_changed: true,
+                };
+            }
+            // 默认导出到源文件旁边的同名 `.html`；未命名标签页放到工作区根。
+            Command::ExportAsHtml => {
+                let pane = self.state.ui.editor_layout.active_pane;
+                let Some(tab) = self.state.editor.pane(pane).and_then(|p| p.active_tab()) else {
+                    return DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: false,
+                    };
+                };
+                if self.state.ui.input_dialog.visible {
+                    return DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: false,
+                    };
+                }
+                let default_path = match tab.path.as_ref() {
+                    Some(path) => {
+                        let mut name = path.file_name().unwrap_or_default().to_os_string();
+                        name.push(".html");
+                        path.with_file_name(name)
+                    }
+                    None => self.state.workspace_root.join("untitled.html"),
+                }
+                .to_string_lossy()
+                .to_string();
+                let dialog = &mut self.state.ui.input_dialog;
+                dialog.reset();
+                dialog.visible = true;
+                dialog.title = "Export as HTML To".to_string();
+                dialog.cursor = default_path.len();
+                dialog.value = default_path;
+                dialog.kind = Some(InputDialogKind::FilePath {
+                    purpose: FilePathPurpose::ExportAsHtml,
+                });
+                return DispatchResult {
+                    effects: Vec::new(),
+                    state_changed: true,
+                };
+            }
+            // 工作区外的文件退回绝对路径。
+            Command::CopyRelativePath => {
+                let pane = self.state.ui.editor_layout.active_pane;
+                let Some(path) = self
+                    .state
+                    .editor
+                    .pane(pane)
+                    .and_then(|p| p.active_tab())
+                    .and_then(|tab| tab.path.as_ref())
+                else {
+                    return DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: false,
+                    };
+                };
+                let text = path
+                    .strip_prefix(&self.state.workspace_root)
+                    .unwrap_or(path)
+                    .to_string_lossy()
+                    .to_string();
+                return DispatchResult {
+                    effects: vec![Effect::SetClipboardText(text)],
+                    state_changed: false,
+                };
+            }
+            Command::EditorContextMenu => {
+                let pane = self.state.ui.editor_layout.active_pane;
+                if self.state.editor.pane(pane).is_none() {
+                    return DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: false,
+                    };
+                }
+                return DispatchResult {
+                    effects: vec![Effect::OpenEditorContextMenu { pane }],
+                    state_changed: false,
+                };
+            }
             Command::HardReload => {
                 return DispatchResult {
                     effects: vec![Effect::Restart {
@@ -853,6 +1266,21 @@ impl Store {
                     state_changed: false,
                 };
             }
+            Command::ShowMetrics => {
+                return DispatchResult {
+                    effects: vec![Effect::ShowMetrics],
+                    state_changed: false,
+                };
+            }
+            Command::OpenTerminalAtCurrentFilePath => {
+                let cwd = self
+                    .active_editor_file_path()
+                    .and_then(|path| path.parent().map(std::path::Path::to_path_buf));
+                return DispatchResult {
+                    effects: vec![Effect::SpawnTerminal { cwd }],
+                    state_changed: false,
+                };
+            }
             Command::ReloadFromDisk => {
                 let pane = self.state.ui.editor_layout.active_pane;
                 if let Some(request) = self
@@ -888,6 +1316,16 @@ impl Store {
                 self.state.ui.focus = FocusTarget::Editor;
                 state_changed = true;
             }
+            Command::SetPaneTabSize { pane, tab_size } => {
+                if pane < self.state.editor.panes.len() {
+                    state_changed = self.state.ui.editor_layout.update_pane_config_override(
+                        pane,
+                        |overrides| {
+                            overrides.tab_size = (tab_size > 0).then_some(tab_size);
+                        },
+                    );
+                }
+            }
             Command::OpenDiagnostics => {
                 state_changed = self.open_overlay(OverlayKind::Problems);
                 return DispatchResult {
@@ -902,95 +1340,18 @@ impl Store {
                     state_changed,
                 };
             }
-            Command::OpenCommandLine => {
-                self.state.ui.command_line.reset();
-                self.state.ui.command_line.active = true;
-                self.state.ui.focus = FocusTarget::CommandLine;
-                state_changed = true;
-            }
-            Command::CommandLineClose => {
-                if self.state.ui.command_line.active {
-                    self.state.ui.command_line.reset();
-                    if self.state.ui.focus == FocusTarget::CommandLine {
-                        self.state.ui.focus = FocusTarget::Editor;
-                    }
-                    state_changed = true;
-                }
-            }
-            Command::CommandLineBackspace => {
-                if self.state.ui.command_line.active {
-                    let line = &mut self.state.ui.command_line;
-                    if line.cursor > 0 && !line.input.is_empty() {
-                        let prev = line.input[..line.cursor]
-                            .char_indices()
-                            .last()
-                            .map(|(i, _)| i)
-                            .unwrap_or(0);
-                        line.input.drain(prev..line.cursor);
-                        line.cursor = prev;
-                        line.selected = 0;
-                        state_changed = true;
-                    } else {
-                        // 空行退格关闭命令行（vim 习惯）。
-                        self.state.ui.command_line.reset();
-                        if self.state.ui.focus == FocusTarget::CommandLine {
-                            self.state.ui.focus = FocusTarget::Editor;
-                        }
-                        state_changed = true;
-                    }
-                }
-            }
-            Command::CommandLineMoveUp => {
-                if self.state.ui.command_line.active {
-                    let prev = self.state.ui.command_line.selected;
-                    self.state.ui.command_line.selected = prev.saturating_sub(1);
-                    state_changed = self.state.ui.command_line.selected != prev;
-                }
-            }
-            Command::CommandLineMoveDown => {
-                if self.state.ui.command_line.active {
-                    let prev = self.state.ui.command_line.selected;
-                    self.state.ui.command_line.selected = prev.saturating_add(1);
-                    state_changed = self.state.ui.command_line.selected != prev;
-                }
-            }
-            Command::CommandLineConfirm => {
-                if !self.state.ui.command_line.active {
-                    return DispatchResult {
-                        effects,
-                        state_changed: false,
-                    };
-                }
-
-                let input = self.state.ui.command_line.input.trim().to_string();
-                let selected_raw = self.state.ui.command_line.selected;
-                let matches = crate::kernel::palette::match_items(&input);
-
-                self.state.ui.command_line.reset();
-                if self.state.ui.focus == FocusTarget::CommandLine {
-                    self.state.ui.focus = FocusTarget::Editor;
-                }
-
-                // 优先取补全列表的选中项；否则按命令名解析输入。
-                let cmd = if !matches.is_empty() {
-                    let selected = selected_raw.min(matches.len().saturating_sub(1));
-                    Some(matches[selected].command.clone())
-                } else if !input.is_empty() {
-                    Some(Command::from_name(&input))
-                } else {
-                    None
-                };
-
-                let Some(cmd) = cmd else {
-                    return DispatchResult {
-                        effects,
-                        state_changed: true,
-                    };
-                };
-
-                let mut result = self.dispatch_command(cmd);
-                result.state_changed = true;
-                return result;
+            cmd @ Command::OpenCommandLine
+            | cmd @ Command::QuickOpen
+            | cmd @ Command::GotoLine
+            | cmd @ Command::ClipboardHistory
+            | cmd @ Command::CommandLineClose
+            | cmd @ Command::CommandLineBackspace
+            | cmd @ Command::CommandLineMoveUp
+            | cmd @ Command::CommandLineMoveDown
+            | cmd @ Command::CommandLineConfirm
+            | cmd @ Command::CommandLineHistoryPrev
+            | cmd @ Command::CommandLineHistoryNext => {
+                return self.reduce_command_line_command(cmd)
             }
             cmd @ Command::ExplorerUp
             | cmd @ Command::ExplorerDown
@@ -1005,7 +1366,20 @@ impl Store {
             | cmd @ Command::ExplorerCut
             | cmd @ Command::ExplorerCopy
             | cmd @ Command::ExplorerPaste => return self.reduce_explorer_command(cmd),
+            cmd @ (Command::Undo | Command::Redo)
+                if self.state.ui.focus == FocusTarget::Explorer =>
+            {
+                return self.reduce_explorer_command(cmd)
+            }
+            Command::ReopenClosedTab => return self.reopen_closed_tab(),
+            cmd @ Command::AddWorkspaceFolder | cmd @ Command::RemoveWorkspaceFolder(_) => {
+                return self.reduce_workspace_folder_command(cmd)
+            }
             cmd @ Command::GlobalSearchStart
+            | cmd @ Command::GlobalSearchToggleReplace
+            | cmd @ Command::GlobalSearchToggleFilters
+            | cmd @ Command::GlobalSearchSwitchField
+            | cmd @ Command::GlobalSearchReplaceAll
             | cmd @ Command::SearchResultsMoveUp
             | cmd @ Command::SearchResultsMoveDown
             | cmd @ Command::SearchResultsScrollUp
@@ -1014,17 +1388,40 @@ impl Store {
             | cmd @ Command::SearchResultsOpenSelected => return self.reduce_search_command(cmd),
             cmd @ Command::LspHover
             | cmd @ Command::LspDefinition
+            | cmd @ Command::LspTypeDefinition
+            | cmd @ Command::LspImplementation
             | cmd @ Command::LspCompletion
             | cmd @ Command::LspSignatureHelp
             | cmd @ Command::LspFormat
             | cmd @ Command::LspFormatSelection
             | cmd @ Command::LspRename
             | cmd @ Command::LspReferences
+            | cmd @ Command::LspCallHierarchy
+            | cmd @ Command::LspCallHierarchyOutgoing
             | cmd @ Command::LspDocumentSymbols
             | cmd @ Command::LspWorkspaceSymbols
             | cmd @ Command::LspInlayHints
             | cmd @ Command::LspFoldingRange
+            | cmd @ Command::LspDocumentLink
+            | cmd @ Command::LspDocumentHighlight
             | cmd @ Command::LspCodeAction => return self.reduce_lsp_command(cmd),
+            cmd @ Command::OpenGitPanel
+            | cmd @ Command::GitStashPush { .. }
+            | cmd @ Command::GitCheckoutBranch
+            | cmd @ Command::ShowGitDiff => return self.reduce_git_command(cmd),
+            cmd @ Command::VimNormalMode
+            | cmd @ Command::VimInsert
+            | cmd @ Command::VimAppend
+            | cmd @ Command::VimOpenLineBelow
+            | cmd @ Command::VimOpenLineAbove
+            | cmd @ Command::VimVisual
+            | cmd @ Command::VimVisualLine
+            | cmd @ Command::VimDelete
+            | cmd @ Command::VimChange
+            | cmd @ Command::VimYank
+            | cmd @ Command::VimGoto
+            | cmd @ Command::VimWordEnd => return self.reduce_vim_command(cmd),
+            cmd if self.vim_intercepts_motion(&cmd) => return self.reduce_vim_motion(cmd),
             cmd => return self.reduce_editor_command(cmd),
         }
 
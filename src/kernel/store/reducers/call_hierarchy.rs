@@ -0,0 +1,110 @@
+use crate::kernel::{Action, CallHierarchyDirection, Effect, OverlayKind};
+
+use super::DispatchResult;
+
+impl super::Store {
+    pub(super) fn reduce_call_hierarchy_action(&mut self, action: Action) -> DispatchResult {
+        match action {
+            Action::CallHierarchyClickRow { row } => DispatchResult {
+                effects: Vec::new(),
+                state_changed: self.state.call_hierarchy.click_row(row),
+            },
+            Action::CallHierarchySetViewHeight { height } => DispatchResult {
+                effects: Vec::new(),
+                state_changed: self.state.call_hierarchy.set_view_height(height),
+            },
+            Action::CallHierarchyExpand { item_index } => self.expand_call_hierarchy(item_index),
+            // 只有一个根时直接展开它：打开面板的目的就是看调用方 / 被调用方。
+            Action::LspCallHierarchyPrepared { items } => {
+                let single_root = items.len() == 1;
+                let mut changed = self.state.call_hierarchy.set_roots(items);
+                changed |= self.open_overlay(OverlayKind::CallHierarchy);
+                let mut result = if single_root {
+                    self.expand_call_hierarchy(0)
+                } else {
+                    DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: false,
+                    }
+                };
+                result.state_changed |= changed;
+                result
+            }
+            Action::LspCallHierarchyIncoming { item_index, items } => self
+                .set_call_hierarchy_children(CallHierarchyDirection::Incoming, item_index, items),
+            Action::LspCallHierarchyOutgoing { item_index, items } => self
+                .set_call_hierarchy_children(CallHierarchyDirection::Outgoing, item_index, items),
+            _ => unreachable!("non-call-hierarchy action passed to reduce_call_hierarchy_action"),
+        }
+    }
+
+    /// 方向已切换（树被重建）时迟到的响应直接丢弃。
+    fn set_call_hierarchy_children(
+        &mut self,
+        direction: CallHierarchyDirection,
+        item_index: usize,
+        items: Vec<crate::kernel::services::ports::LspCallHierarchyItem>,
+    ) -> DispatchResult {
+        let state_changed = self.state.call_hierarchy.direction() == direction
+            && self.state.call_hierarchy.set_children(item_index, items);
+        DispatchResult {
+            effects: Vec::new(),
+            state_changed,
+        }
+    }
+
+    fn expand_call_hierarchy(&mut self, item_index: usize) -> DispatchResult {
+        let Some(item) = self
+            .state
+            .call_hierarchy
+            .node(item_index)
+            .map(|node| node.item.clone())
+        else {
+            return DispatchResult {
+                effects: Vec::new(),
+                state_changed: false,
+            };
+        };
+
+        let needs_request = self.state.call_hierarchy.begin_expand(item_index);
+        let effects = if !needs_request {
+            Vec::new()
+        } else {
+            let item = Box::new(item);
+            match self.state.call_hierarchy.direction() {
+                CallHierarchyDirection::Incoming => {
+                    vec![Effect::LspCallHierarchyIncomingRequest { item_index, item }]
+                }
+                CallHierarchyDirection::Outgoing => {
+                    vec![Effect::LspCallHierarchyOutgoingRequest { item_index, item }]
+                }
+            }
+        };
+        DispatchResult {
+            effects,
+            state_changed: true,
+        }
+    }
+
+    /// 选中节点的 Enter / 点击：跳到该函数的定义位置，复用 `LspDefinition` 的打开逻辑。
+    pub(super) fn open_selected_call_hierarchy_item(&mut self) -> DispatchResult {
+        let Some(item) = self
+            .state
+            .call_hierarchy
+            .selected_item_index()
+            .and_then(|index| self.state.call_hierarchy.node(index))
+            .map(|node| node.item.clone())
+        else {
+            return DispatchResult {
+                effects: Vec::new(),
+                state_changed: false,
+            };
+        };
+
+        self.dispatch(Action::LspDefinition {
+            path: item.path,
+            line: item.line,
+            column: item.column,
+        })
+    }
+}
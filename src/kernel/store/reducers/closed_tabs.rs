@@ -0,0 +1,114 @@
+use crate::kernel::editor::EditorTabState;
+use crate::kernel::state::{PendingEditorNavigation, PendingEditorNavigationTarget};
+use crate::kernel::{AppState, Effect, FocusTarget};
+use std::path::{Path, PathBuf};
+
+use super::intel::lsp::{lsp_position_encoding_for_path, lsp_position_from_cursor};
+use super::DispatchResult;
+
+/// 标签页光标的 (行, 列)，列按该文件语言服务器的位置编码计，和 `LineColumn` 导航的换算一致。
+fn tab_line_column(state: &AppState, tab: &EditorTabState) -> Option<(PathBuf, (u32, u32))> {
+    let path = tab.path.clone()?;
+    let encoding = lsp_position_encoding_for_path(state, &path);
+    Some((path, lsp_position_from_cursor(tab, encoding)))
+}
+
+impl super::Store {
+    /// 弹出最近关闭的标签页重新打开，文件加载后把光标放回关闭时的位置。
+    pub(super) fn reopen_closed_tab(&mut self) -> DispatchResult {
+        let Some((path, byte_offset)) = self.state.recently_closed_tabs.pop_back() else {
+            return DispatchResult {
+                effects: Vec::new(),
+                state_changed: false,
+            };
+        };
+
+        let pane = self.state.ui.editor_layout.active_pane;
+        self.state.ui.focus = FocusTarget::Editor;
+        self.state.ui.pending_editor_nav = Some(PendingEditorNavigation {
+            pane,
+            path: path.clone(),
+            target: PendingEditorNavigationTarget::ByteOffset { byte_offset },
+        });
+
+        DispatchResult {
+            effects: vec![Effect::LoadFile(path)],
+            state_changed: true,
+        }
+    }
+
+    /// 关闭前记下 `pane` 中这些标签页的路径与光标位置；未命名的标签页不记录。
+    /// 返回把其中干净标签页的撤销历史写进备份的 effect。
+    pub(super) fn remember_closed_tabs(&mut self, pane: usize, indices: &[usize]) -> Vec<Effect> {
+        let Some(pane_state) = self.state.editor.pane(pane) else {
+            return Vec::new();
+        };
+        let backups = indices
+            .iter()
+            .filter_map(|&index| pane_state.tabs.get(index))
+            .filter_map(|tab| tab.edit_history_backup())
+            .map(|(path, backup)| Effect::BackupEditHistory { path, backup })
+            .collect();
+        let closed = indices
+            .iter()
+            .filter_map(|&index| pane_state.tabs.get(index))
+            .filter_map(|tab| {
+                let path = tab.path.clone()?;
+                let rope = tab.buffer.rope();
+                let char_offset = tab.buffer.pos_to_char(tab.buffer.cursor());
+                Some((path, rope.char_to_byte(char_offset.min(rope.len_chars()))))
+            })
+            .collect::<Vec<_>>();
+        let positions = indices
+            .iter()
+            .filter_map(|&index| pane_state.tabs.get(index))
+            .filter_map(|tab| tab_line_column(&self.state, tab))
+            .collect::<Vec<_>>();
+        for (path, byte_offset) in closed {
+            self.state.remember_closed_tab(path, byte_offset);
+        }
+        for (path, position) in positions {
+            self.state.remember_file_position(path, position);
+        }
+        backups
+    }
+
+    /// 把所有打开着的标签页的光标位置记进 `file_positions`，写会话文件前调用。
+    pub fn remember_open_file_positions(&mut self) {
+        let positions = self
+            .state
+            .editor
+            .panes
+            .iter()
+            .flat_map(|pane| pane.tabs.iter())
+            .filter_map(|tab| tab_line_column(&self.state, tab))
+            .collect::<Vec<_>>();
+        for (path, position) in positions {
+            self.state.remember_file_position(path, position);
+        }
+    }
+
+    /// `OpenFile` 新开一个之前打开过的文件时，排队跳回记下的位置；已有导航或文件已在该 pane 打开则不动。
+    pub(super) fn queue_remembered_file_position(&mut self, pane: usize, path: &Path) {
+        if self.state.ui.pending_editor_nav.is_some() {
+            return;
+        }
+        let already_open = self.state.editor.pane(pane).is_some_and(|pane_state| {
+            pane_state
+                .tabs
+                .iter()
+                .any(|tab| tab.path.as_deref() == Some(path))
+        });
+        if already_open {
+            return;
+        }
+        let Some((line, column)) = self.state.file_position(path) else {
+            return;
+        };
+        self.state.ui.pending_editor_nav = Some(PendingEditorNavigation {
+            pane,
+            path: path.to_path_buf(),
+            target: PendingEditorNavigationTarget::LineColumn { line, column },
+        });
+    }
+}
@@ -0,0 +1,339 @@
+use crate::core::Command;
+use crate::kernel::editor::EditorAction;
+use crate::kernel::palette::{
+    file_candidates, match_branches, match_clipboard, match_files, match_items, parse_line_number,
+    BranchCandidate, COMMAND_PREFIX, LINE_PREFIX,
+};
+use crate::kernel::{Action, CommandLineMode, Effect, FocusTarget};
+
+use super::DispatchResult;
+
+impl super::Store {
+    pub(super) fn reduce_command_line_action(&mut self, action: Action) -> DispatchResult {
+        match action {
+            Action::CommandLineAppend(ch) => {
+                if !self.state.ui.command_line.active {
+                    return DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: false,
+                    };
+                }
+                let line = &mut self.state.ui.command_line;
+                let cursor = line.cursor.min(line.input.len());
+                line.input.insert(cursor, ch);
+                line.cursor = cursor + ch.len_utf8();
+                line.selected = 0;
+                line.history_index = None;
+                DispatchResult {
+                    effects: self.request_command_line_files(),
+                    state_changed: true,
+                }
+            }
+            Action::CommandLineFilesListed { files } => {
+                let line = &mut self.state.ui.command_line;
+                let state_changed = line.files != files;
+                line.files = files;
+                DispatchResult {
+                    effects: Vec::new(),
+                    state_changed,
+                }
+            }
+            Action::ClipboardHistoryLoaded { entries } => {
+                let line = &mut self.state.ui.command_line;
+                let state_changed = line.clipboard_entries != entries;
+                line.clipboard_entries = entries;
+                DispatchResult {
+                    effects: Vec::new(),
+                    state_changed,
+                }
+            }
+            _ => unreachable!("non-command-line action passed to reduce_command_line_action"),
+        }
+    }
+
+    pub(super) fn reduce_command_line_command(&mut self, command: Command) -> DispatchResult {
+        let mut effects = Vec::new();
+        let state_changed = match command {
+            // F1 / Ctrl+Shift+P：预填 `>` 直接进入命令模式；退格删掉 `>` 即切到文件模式。
+            Command::OpenCommandLine => {
+                self.open_command_line(COMMAND_PREFIX);
+                true
+            }
+            Command::QuickOpen => {
+                self.open_command_line("");
+                effects = self.request_command_line_files();
+                true
+            }
+            // Ctrl+G / vim `:`：没有打开的文件时无处可跳。
+            Command::GotoLine => {
+                if self.active_line_count().is_none() {
+                    false
+                } else {
+                    self.open_command_line(LINE_PREFIX);
+                    true
+                }
+            }
+            // Ctrl+Shift+V：历史在剪贴板服务里，打开后异步取回候选。
+            Command::ClipboardHistory => {
+                self.open_command_line("");
+                self.state.ui.command_line.clipboard_picker = true;
+                effects.push(Effect::RequestClipboardHistory);
+                true
+            }
+            Command::CommandLineClose => {
+                if self.state.ui.command_line.active {
+                    self.close_command_line();
+                    true
+                } else {
+                    false
+                }
+            }
+            Command::CommandLineBackspace => {
+                if !self.state.ui.command_line.active {
+                    false
+                } else {
+                    let line = &mut self.state.ui.command_line;
+                    if line.cursor > 0 && !line.input.is_empty() {
+                        let prev = line.input[..line.cursor]
+                            .char_indices()
+                            .last()
+                            .map(|(i, _)| i)
+                            .unwrap_or(0);
+                        line.input.drain(prev..line.cursor);
+                        line.cursor = prev;
+                        line.selected = 0;
+                        line.history_index = None;
+                        effects = self.request_command_line_files();
+                    } else {
+                        // 空行退格关闭命令行（vim 习惯）。
+                        self.close_command_line();
+                    }
+                    true
+                }
+            }
+            Command::CommandLineMoveUp => {
+                let line = &mut self.state.ui.command_line;
+                let prev = line.selected;
+                if line.active {
+                    line.selected = prev.saturating_sub(1);
+                }
+                line.selected != prev
+            }
+            Command::CommandLineMoveDown => {
+                let line = &mut self.state.ui.command_line;
+                let prev = line.selected;
+                if line.active {
+                    line.selected = prev.saturating_add(1);
+                }
+                line.selected != prev
+            }
+            Command::CommandLineHistoryPrev => {
+                self.state.ui.command_line.active && self.state.ui.command_line.recall_history(-1)
+            }
+            Command::CommandLineHistoryNext => {
+                self.state.ui.command_line.active && self.state.ui.command_line.recall_history(1)
+            }
+            Command::CommandLineConfirm => return self.confirm_command_line(),
+            _ => unreachable!("non-command-line command passed to reduce_command_line_command"),
+        };
+        DispatchResult {
+            effects,
+            state_changed,
+        }
+    }
+
+    /// 活动标签页的总行数，供跳转行模式校验输入与提示范围。
+    fn active_line_count(&self) -> Option<usize> {
+        self.state
+            .editor
+            .pane(self.state.ui.editor_layout.active_pane)
+            .and_then(|pane| pane.active_tab())
+            .map(|tab| tab.buffer.rope().len_lines())
+    }
+
+    fn open_command_line(&mut self, input: &str) {
+        let line = &mut self.state.ui.command_line;
+        line.reset();
+        line.active = true;
+        line.input = input.to_string();
+        line.cursor = line.input.len();
+        self.state.ui.focus = FocusTarget::CommandLine;
+    }
+
+    pub(super) fn open_branch_picker(&mut self) {
+        self.open_command_line("");
+        self.state.ui.command_line.branch_picker = true;
+    }
+
+    fn close_command_line(&mut self) {
+        self.state.ui.command_line.reset();
+        if self.state.ui.focus == FocusTarget::CommandLine {
+            self.state.ui.focus = FocusTarget::Editor;
+        }
+    }
+
+    /// 每次打开命令行后首次进入文件模式时刷新一次工作区文件列表；
+    /// 刷新返回前沿用上次的列表。
+    fn request_command_line_files(&mut self) -> Vec<Effect> {
+        let line = &mut self.state.ui.command_line;
+        if line.mode() != CommandLineMode::File || line.files_requested {
+            return Vec::new();
+        }
+        line.files_requested = true;
+        vec![Effect::ListWorkspaceFiles]
+    }
+
+    fn confirm_command_line(&mut self) -> DispatchResult {
+        if !self.state.ui.command_line.active {
+            return DispatchResult {
+                effects: Vec::new(),
+                state_changed: false,
+            };
+        }
+
+        let line = &self.state.ui.command_line;
+        let mode = line.mode();
+        let query = line.query().to_string();
+        let selected_raw = line.selected;
+
+        match mode {
+            CommandLineMode::Command => {
+                let matches = match_items(&query);
+                // 优先取补全列表的选中项；否则按命令名解析输入。
+                let cmd = if !matches.is_empty() {
+                    let selected = selected_raw.min(matches.len().saturating_sub(1));
+                    Some(matches[selected].command.clone())
+                } else if !query.is_empty() {
+                    Some(Command::from_name(&query))
+                } else {
+                    None
+                };
+
+                self.state.ui.command_line.push_history(mode, &query);
+                self.close_command_line();
+
+                let Some(cmd) = cmd else {
+                    return DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: true,
+                    };
+                };
+
+                let mut result = self.dispatch_command(cmd);
+                result.state_changed = true;
+                result
+            }
+            CommandLineMode::File => {
+                let path = {
+                    let candidates = file_candidates(
+                        &self.state.recent_files,
+                        &self.state.ui.command_line.files,
+                        &self.state.workspace_root,
+                    );
+                    let matches = match_files(&query, &candidates);
+                    let selected = selected_raw.min(matches.len().saturating_sub(1));
+                    matches
+                        .get(selected)
+                        // 工作区外的最近文件本身就是绝对路径，join 后不变。
+                        .map(|rel| self.state.workspace_root.join(rel))
+                };
+
+                self.state.ui.command_line.push_history(mode, &query);
+                self.close_command_line();
+
+                let Some(path) = path else {
+                    return DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: true,
+                    };
+                };
+
+                let mut result = self.dispatch(Action::OpenPath(path));
+                result.state_changed = true;
+                result
+            }
+            CommandLineMode::Line => {
+                if query.is_empty() {
+                    self.close_command_line();
+                    return DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: true,
+                    };
+                }
+
+                let pane = self.state.ui.editor_layout.active_pane;
+                let byte_offset = self
+                    .state
+                    .editor
+                    .pane(pane)
+                    .and_then(|pane| pane.active_tab())
+                    .and_then(|tab| {
+                        let rope = tab.buffer.rope();
+                        parse_line_number(&query, rope.len_lines())
+                            .map(|line| rope.line_to_byte(line))
+                    });
+                // 非法行号不关闭命令行，提示行已经标红，改了再回车。
+                let Some(byte_offset) = byte_offset else {
+                    return DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: false,
+                    };
+                };
+
+                self.close_command_line();
+                let mut result = self.dispatch(Action::Editor(EditorAction::GotoByteOffset {
+                    pane,
+                    byte_offset,
+                }));
+                result.state_changed = true;
+                result
+            }
+            CommandLineMode::Branch => {
+                let checkout = {
+                    let matches = match_branches(&query, &self.state.git.branches);
+                    let selected = selected_raw.min(matches.len().saturating_sub(1));
+                    match matches.get(selected) {
+                        // 已在该分支上，无事可做。
+                        Some(BranchCandidate::Checkout(branch)) if branch.current => None,
+                        Some(BranchCandidate::Checkout(branch)) => {
+                            Some((branch.local_name().to_string(), false))
+                        }
+                        Some(BranchCandidate::Create(name)) => Some((name.to_string(), true)),
+                        None => None,
+                    }
+                };
+
+                self.close_command_line();
+                DispatchResult {
+                    effects: checkout
+                        .map(|(branch, create)| Effect::GitCheckout { branch, create })
+                        .into_iter()
+                        .collect(),
+                    state_changed: true,
+                }
+            }
+            CommandLineMode::Clipboard => {
+                let text = {
+                    let matches =
+                        match_clipboard(&query, &self.state.ui.command_line.clipboard_entries);
+                    let selected = selected_raw.min(matches.len().saturating_sub(1));
+                    matches.get(selected).map(|entry| entry.to_string())
+                };
+
+                self.close_command_line();
+                let Some(text) = text else {
+                    return DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: true,
+                    };
+                };
+
+                let pane = self.state.ui.editor_layout.active_pane;
+                let mut result =
+                    self.dispatch(Action::Editor(EditorAction::InsertText { pane, text }));
+                result.state_changed = true;
+                result
+            }
+        }
+    }
+}
@@ -6,7 +6,7 @@ use super::intel::completion::{
     completion_replace_range, CompletionInsertion,
 };
 use super::intel::lsp::{lsp_position_encoding_for_path, lsp_position_to_byte_offset};
-use super::DispatchResult;
+use super::{DispatchResult, RecentlyUsedCompletionRanker};
 
 impl super::Store {
     pub(super) fn reduce_completion_action(&mut self, action: Action) -> DispatchResult {
@@ -27,9 +27,21 @@ impl super::Store {
                     };
                 }
                 let len = self.state.ui.completion.visible_len();
+                let page = self.state.editor.config.completion_popup_max_lines.max(1);
                 let prev = self.state.ui.completion.selected;
-                let next = (prev as isize).wrapping_add(delta).rem_euclid(len as isize) as usize;
+                let prev_offset = self.state.ui.completion.scroll_offset;
+                // 整页移动停在两端，不像单步那样绕回。
+                let paging = delta.unsigned_abs() >= page;
+                let next = if paging {
+                    (prev as isize)
+                        .saturating_add(delta)
+                        .clamp(0, len as isize - 1) as usize
+                } else {
+                    (prev as isize).wrapping_add(delta).rem_euclid(len as isize) as usize
+                };
                 self.state.ui.completion.selected = next;
+                self.state.ui.completion.scroll_to_selected(page, paging);
+                let scrolled = self.state.ui.completion.scroll_offset != prev_offset;
 
                 let mut effects = Vec::new();
                 if next != prev {
@@ -41,7 +53,7 @@ impl super::Store {
                 }
                 DispatchResult {
                     effects,
-                    state_changed: next != prev,
+                    state_changed: next != prev || scrolled,
                 }
             }
             Action::CompletionConfirm => {
@@ -100,6 +112,9 @@ impl super::Store {
                         .and_then(|tab| tab.language());
                     self.completion_ranker
                         .record(language, &entry.label, entry.kind);
+                    if let Some(suffix) = RecentlyUsedCompletionRanker::path_suffix(&req.path) {
+                        self.recent_completions.record(&suffix, &entry.label);
+                    }
                 }
 
                 let mut insertion = CompletionInsertion::from_plan(entry.commit.insert.clone());
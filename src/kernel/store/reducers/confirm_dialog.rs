@@ -1,3 +1,4 @@
+use crate::kernel::editor::LARGE_FILE_CHUNK_BYTES;
 use crate::kernel::{Action, EditorAction, Effect, PendingAction};
 
 use super::DispatchResult;
@@ -31,6 +32,30 @@ impl super::Store {
 
                 if let Some(action) = pending {
                     match action {
+                        PendingAction::GitStashDrop { index } => {
+                            return DispatchResult {
+                                effects: vec![Effect::GitStashDrop { index }],
+                                state_changed: true,
+                            };
+                        }
+                        PendingAction::OpenLargeFile { path, size } => {
+                            let threshold = (self.state.editor.config.large_file_threshold_mb
+                                as u64)
+                                .saturating_mul(1024 * 1024);
+                            let effect = if size >= threshold {
+                                Effect::LoadFileRange {
+                                    path,
+                                    start_byte: 0,
+                                    end_byte: LARGE_FILE_CHUNK_BYTES.min(size),
+                                }
+                            } else {
+                                Effect::LoadFileWithoutLimit(path)
+                            };
+                            return DispatchResult {
+                                effects: vec![effect],
+                                state_changed: true,
+                            };
+                        }
                         PendingAction::CloseTab { pane, index } => {
                             let mut result = self
                                 .dispatch(Action::Editor(EditorAction::CloseTabAt { pane, index }));
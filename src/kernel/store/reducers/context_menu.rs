@@ -2,7 +2,7 @@ use crate::core::Command;
 use crate::kernel::editor::EditorAction;
 use crate::kernel::state::{
     ContextMenuAction, ContextMenuEntry, ContextMenuRequest, ContextMenuState,
-    ExplorerClipboardMode, ExplorerMenuAction, PendingAction, TabMenuAction,
+    ExplorerClipboardMode, ExplorerMenuAction, GitStashMenuAction, PendingAction, TabMenuAction,
 };
 use crate::kernel::{Action, Effect, FocusTarget};
 use std::path::{Path, PathBuf};
@@ -246,12 +246,13 @@ impl super::Store {
     }
 
     fn build_editor_area_context_menu_items(&self, pane: usize) -> Vec<ContextMenuEntry> {
-        let has_active_tab = self
+        let active_tab = self
             .state
             .editor
             .pane(pane)
-            .and_then(|pane_state| pane_state.active_tab())
-            .is_some();
+            .and_then(|pane_state| pane_state.active_tab());
+        let has_active_tab = active_tab.is_some();
+        let has_path = active_tab.is_some_and(|tab| tab.path.is_some());
 
         vec![
             action_entry(
@@ -280,11 +281,31 @@ impl super::Store {
                 ContextMenuAction::RunCommand(Command::LspDefinition),
                 has_active_tab,
             ),
+            action_entry(
+                "Go to Type Definition",
+                ContextMenuAction::RunCommand(Command::LspTypeDefinition),
+                has_active_tab,
+            ),
+            action_entry(
+                "Go to Implementation",
+                ContextMenuAction::RunCommand(Command::LspImplementation),
+                has_active_tab,
+            ),
             action_entry(
                 "Find References",
                 ContextMenuAction::RunCommand(Command::LspReferences),
                 has_active_tab,
             ),
+            action_entry(
+                "Show Incoming Calls",
+                ContextMenuAction::RunCommand(Command::LspCallHierarchy),
+                has_active_tab,
+            ),
+            action_entry(
+                "Show Outgoing Calls",
+                ContextMenuAction::RunCommand(Command::LspCallHierarchyOutgoing),
+                has_active_tab,
+            ),
             action_entry(
                 "Rename Symbol",
                 ContextMenuAction::RunCommand(Command::LspRename),
@@ -301,6 +322,27 @@ impl super::Store {
                 ContextMenuAction::RunCommand(Command::LspCodeAction),
                 has_active_tab,
             ),
+            ContextMenuEntry::separator(),
+            action_entry(
+                "Copy Relative Path",
+                ContextMenuAction::RunCommand(Command::CopyRelativePath),
+                has_path,
+            ),
+        ]
+    }
+
+    fn build_git_stash_context_menu_items(&self) -> Vec<ContextMenuEntry> {
+        vec![
+            action_entry(
+                "Apply Stash",
+                ContextMenuAction::GitStash(GitStashMenuAction::Apply),
+                true,
+            ),
+            action_entry(
+                "Drop Stash",
+                ContextMenuAction::GitStash(GitStashMenuAction::Drop),
+                true,
+            ),
         ]
     }
 
@@ -499,6 +541,38 @@ impl super::Store {
         }
     }
 
+    fn dispatch_git_stash_menu_action(
+        &mut self,
+        action: GitStashMenuAction,
+        request: Option<ContextMenuRequest>,
+    ) -> super::DispatchResult {
+        let Some(ContextMenuRequest::GitStash { index }) = request else {
+            return super::DispatchResult {
+                effects: Vec::new(),
+                state_changed: false,
+            };
+        };
+
+        match action {
+            GitStashMenuAction::Apply => super::DispatchResult {
+                effects: vec![Effect::GitStashApply { index }],
+                state_changed: false,
+            },
+            // drop 不可撤销：先确认。
+            GitStashMenuAction::Drop => {
+                self.state.ui.confirm_dialog.visible = true;
+                self.state.ui.confirm_dialog.message =
+                    format!("Drop stash@{{{index}}}? This cannot be undone.");
+                self.state.ui.confirm_dialog.on_confirm =
+                    Some(PendingAction::GitStashDrop { index });
+                super::DispatchResult {
+                    effects: Vec::new(),
+                    state_changed: true,
+                }
+            }
+        }
+    }
+
     pub(super) fn reduce_context_menu_action(&mut self, action: Action) -> super::DispatchResult {
         match action {
             Action::ContextMenuOpen { request, x, y } => {
@@ -587,6 +661,15 @@ impl super::Store {
                         let items = self.build_editor_area_context_menu_items(pane);
                         state_changed |= self.open_context_menu(request_for_menu, x, y, items);
 
+                        super::DispatchResult {
+                            effects: Vec::new(),
+                            state_changed,
+                        }
+                    }
+                    ContextMenuRequest::GitStash { .. } => {
+                        let items = self.build_git_stash_context_menu_items();
+                        state_changed |= self.open_context_menu(request_for_menu, x, y, items);
+
                         super::DispatchResult {
                             effects: Vec::new(),
                             state_changed,
@@ -685,6 +768,9 @@ impl super::Store {
                     ContextMenuAction::Explorer(explorer_action) => {
                         self.dispatch_explorer_menu_action(explorer_action)
                     }
+                    ContextMenuAction::GitStash(git_action) => {
+                        self.dispatch_git_stash_menu_action(git_action, request)
+                    }
                 };
 
                 result.state_changed = true;
@@ -4,7 +4,9 @@ use crate::kernel::services::ports::LspCompletionTriggerContext;
 use crate::kernel::state::{SignatureHelpPopupState, SignatureHelpRequestContext};
 use crate::kernel::Effect;
 
-use super::intel::completion::{completion_runtime_context, sync_completion_items_from_cache};
+use super::intel::completion::{
+    completion_runtime_context, insert_snippet_into_tab, sync_completion_items_from_cache,
+};
 use super::intel::lsp::{lsp_request_target, lsp_server_capabilities_for_path};
 use super::util::is_lsp_source_path;
 use super::DispatchResult;
@@ -14,6 +16,15 @@ impl super::Store {
         let mut state_changed = false;
         let effects = Vec::new();
 
+        if matches!(command, Command::InsertChar(' ') | Command::InsertNewline)
+            && self.expand_user_snippet_trigger()
+        {
+            return DispatchResult {
+                effects,
+                state_changed: true,
+            };
+        }
+
         match command {
             Command::InsertChar(ch) => {
                 let pane = self.state.ui.editor_layout.active_pane;
@@ -244,6 +255,12 @@ impl super::Store {
             }
             Command::Save => {
                 let pane = self.state.ui.editor_layout.active_pane;
+                if let Some(effects) = self.format_before_save(pane) {
+                    return DispatchResult {
+                        effects,
+                        state_changed,
+                    };
+                }
                 let (changed, cmd_effects) = self.state.editor.apply_command(pane, Command::Save);
                 if changed {
                     state_changed = true;
@@ -409,12 +426,17 @@ impl super::Store {
             }
             other => {
                 let pane = self.state.ui.editor_layout.active_pane;
+                let mut effects = effects;
+                if matches!(other, Command::CloseTab) {
+                    if let Some(active) = self.state.editor.pane(pane).map(|p| p.active) {
+                        effects.extend(self.remember_closed_tabs(pane, &[active]));
+                    }
+                }
                 let (changed, cmd_effects) = self.state.editor.apply_command(pane, other);
                 if changed {
                     state_changed = true;
                 }
                 // TODO: avoid allocation by using SmallVec if needed.
-                let mut effects = effects;
                 effects.extend(cmd_effects);
 
                 let had_signature_help = self.state.ui.signature_help.is_active();
@@ -449,4 +471,58 @@ impl super::Store {
             state_changed,
         }
     }
+
+    /// 光标前紧邻的单词命中 `EditorConfig::snippets` 的触发词时，就地展开为对应模板；
+    /// 触发用的空格 / 回车被展开吞掉，不再插入。
+    fn expand_user_snippet_trigger(&mut self) -> bool {
+        let config = &self.state.editor.config;
+        if config.snippets.is_empty() {
+            return false;
+        }
+
+        let pane = self.state.ui.editor_layout.active_pane;
+        let Some(tab) = self.state.editor.pane(pane).and_then(|p| p.active_tab()) else {
+            return false;
+        };
+        if tab.is_multi_cursor()
+            || tab.buffer.has_selection()
+            || tab.is_in_string_or_comment_at_cursor()
+        {
+            return false;
+        }
+
+        let rope = tab.buffer.rope();
+        let cursor_char = tab.buffer.pos_to_char(tab.buffer.cursor());
+        let mut start_char = cursor_char;
+        while start_char > 0 && is_snippet_trigger_char(rope.char(start_char - 1)) {
+            start_char -= 1;
+        }
+        if start_char == cursor_char {
+            return false;
+        }
+        // `obj.fn` / `mod::fn` 是成员访问，不是在写新语句。
+        if start_char > 0 && matches!(rope.char(start_char - 1), '.' | ':') {
+            return false;
+        }
+
+        let trigger = rope.slice(start_char..cursor_char).to_string();
+        let Some(template) = config.snippets.get(&trigger).cloned() else {
+            return false;
+        };
+        let tab_size = config.tab_size;
+
+        let Some(tab) = self
+            .state
+            .editor
+            .pane_mut(pane)
+            .and_then(|p| p.active_tab_mut())
+        else {
+            return false;
+        };
+        insert_snippet_into_tab(tab, start_char, cursor_char, &template, tab_size)
+    }
+}
+
+fn is_snippet_trigger_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
 }
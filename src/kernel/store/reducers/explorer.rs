@@ -58,6 +58,39 @@ impl super::Store {
                     state_changed: false,
                 }
             }
+            Action::ExplorerDragStart { node_id } => super::DispatchResult {
+                effects: Vec::new(),
+                state_changed: self.state.explorer.begin_drag(node_id),
+            },
+            Action::ExplorerDragHover { target } => super::DispatchResult {
+                effects: Vec::new(),
+                state_changed: self.state.explorer.set_drag_hover(target),
+            },
+            Action::ExplorerDragCancel => super::DispatchResult {
+                effects: Vec::new(),
+                state_changed: self.state.explorer.end_drag(),
+            },
+            Action::ExplorerDrop { target } => {
+                match self.state.explorer.drop_dragged_onto(target) {
+                    Ok(Some((from, to))) => {
+                        let mut result =
+                            self.reduce_explorer_action(Action::ExplorerMovePath { from, to });
+                        result.state_changed = true;
+                        result
+                    }
+                    Ok(None) => super::DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: true,
+                    },
+                    Err(error) => {
+                        self.state.explorer.drop_error = Some(error);
+                        super::DispatchResult {
+                            effects: Vec::new(),
+                            state_changed: true,
+                        }
+                    }
+                }
+            }
             Action::DirLoaded { path, entries } => super::DispatchResult {
                 effects: Vec::new(),
                 state_changed: self.state.explorer.apply_dir_loaded(path, entries),
@@ -1,6 +1,6 @@
 use crate::core::Command;
-use crate::kernel::state::ExplorerClipboardMode;
-use crate::kernel::{FocusTarget, InputDialogKind, PendingAction};
+use crate::kernel::state::{ExplorerClipboardMode, ExplorerOpOrigin};
+use crate::kernel::{Effect, FocusTarget, InputDialogKind, PendingAction};
 
 use super::DispatchResult;
 
@@ -44,6 +44,23 @@ impl super::Store {
                     state_changed = self.state.explorer.scroll(3);
                 }
             }
+            Command::Undo | Command::Redo => {
+                let history = &mut self.state.explorer_history;
+                let (op, origin) = if command == Command::Undo {
+                    (history.pop_undo(), ExplorerOpOrigin::Undo)
+                } else {
+                    (history.pop_redo(), ExplorerOpOrigin::Redo)
+                };
+                if let Some(op) = op {
+                    return DispatchResult {
+                        effects: vec![Effect::ReplayExplorerOp {
+                            op: Box::new(op),
+                            origin,
+                        }],
+                        state_changed: false,
+                    };
+                }
+            }
             Command::ExplorerNewFile => {
                 if self.state.ui.input_dialog.visible {
                     return DispatchResult {
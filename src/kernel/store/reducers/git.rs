@@ -0,0 +1,151 @@
+use std::path::PathBuf;
+
+use crate::core::Command;
+use crate::kernel::{Action, Effect, GitDiffView, GitPanelRow, OverlayKind};
+
+use super::DispatchResult;
+
+impl super::Store {
+    pub(super) fn reduce_git_action(&mut self, action: Action) -> DispatchResult {
+        let state_changed = match action {
+            Action::GitClickRow { row } => self.state.git.click_row(row),
+            Action::GitSetViewHeight { height } => self.state.git.set_view_height(height),
+            Action::GitStashList { stashes } => self.state.git.set_stashes(stashes),
+            Action::GitBranchesUpdated { branches } => self.state.git.set_branches(branches),
+            Action::GitBlameResult { path, line, blame } => {
+                self.state.git.set_blame(path, line, blame)
+            }
+            Action::GitDiffBaseLoaded { path, content } => self.show_git_diff(path, content),
+            _ => unreachable!("non-git action passed to reduce_git_action"),
+        };
+        DispatchResult {
+            effects: Vec::new(),
+            state_changed,
+        }
+    }
+
+    pub(super) fn reduce_git_command(&mut self, command: Command) -> DispatchResult {
+        match command {
+            // 打开面板即刷新一次：stash 可能在编辑器外被增删。
+            Command::OpenGitPanel => DispatchResult {
+                effects: vec![Effect::GitStashList],
+                state_changed: self.open_overlay(OverlayKind::Git),
+            },
+            Command::GitStashPush { include_untracked } => DispatchResult {
+                effects: vec![Effect::GitStashPush { include_untracked }],
+                state_changed: false,
+            },
+            // 先用上次的分支列表打开选择器，刷新返回后候选随之更新。
+            Command::GitCheckoutBranch => {
+                self.open_branch_picker();
+                DispatchResult {
+                    effects: vec![Effect::GitBranchList],
+                    state_changed: true,
+                }
+            }
+            Command::ShowGitDiff => {
+                let pane = self.state.ui.editor_layout.active_pane;
+                let Some(path) = self
+                    .state
+                    .editor
+                    .pane(pane)
+                    .and_then(|pane| pane.active_tab())
+                    .and_then(|tab| tab.path.clone())
+                else {
+                    return DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: false,
+                    };
+                };
+                self.state.git.diff = None;
+                DispatchResult {
+                    effects: vec![Effect::GitLoadDiffBase { path }],
+                    state_changed: false,
+                }
+            }
+            _ => unreachable!("non-git command passed to reduce_git_command"),
+        }
+    }
+
+    /// 暂存区版本读回：与该文件打开着的缓冲区比较。同一文件的 diff 已在时是暂存后的刷新，
+    /// 只更新内容；否则新建视图并打开浮层。
+    fn show_git_diff(&mut self, path: PathBuf, content: String) -> bool {
+        let pane = self.state.ui.editor_layout.active_pane;
+        let Some((pane, index)) = super::util::find_open_tab(&self.state.editor, pane, &path)
+        else {
+            return false;
+        };
+        let Some(current) = self.state.editor.panes[pane]
+            .tabs
+            .get(index)
+            .map(|tab| tab.buffer.rope().clone())
+        else {
+            return false;
+        };
+        let original = ropey::Rope::from_str(&content);
+
+        if let Some(diff) = self
+            .state
+            .git
+            .diff
+            .as_mut()
+            .filter(|diff| diff.path == path)
+        {
+            diff.refresh(original, current);
+            return true;
+        }
+        self.state.git.diff = Some(GitDiffView::new(path, original, current));
+        self.open_overlay(OverlayKind::GitDiff);
+        true
+    }
+
+    /// diff 浮层的 Enter：暂存选中的改动块。
+    pub(super) fn stage_selected_git_hunk(&mut self) -> DispatchResult {
+        let effects = self
+            .state
+            .git
+            .diff
+            .as_ref()
+            .and_then(|diff| {
+                Some(Effect::GitStageHunk {
+                    path: diff.path.clone(),
+                    hunk_index: diff.selected_hunk()?,
+                })
+            })
+            .into_iter()
+            .collect();
+        DispatchResult {
+            effects,
+            state_changed: false,
+        }
+    }
+
+    /// Git 面板的 Enter：分区标题切换折叠；stash 行直接 apply（apply 不删除 stash，
+    /// 误触可撤销），drop 只走带确认的右键菜单。
+    pub(super) fn activate_selected_git_row(&mut self) -> DispatchResult {
+        match self.state.git.selected_row() {
+            Some(GitPanelRow::StashesHeader) => DispatchResult {
+                effects: Vec::new(),
+                state_changed: self.state.git.toggle_stashes_expanded(),
+            },
+            Some(GitPanelRow::Stash(i)) => {
+                let effects = self
+                    .state
+                    .git
+                    .stashes
+                    .get(i)
+                    .map(|stash| Effect::GitStashApply { index: stash.index })
+                    .into_iter()
+                    .collect();
+                DispatchResult {
+                    effects,
+                    state_changed: false,
+                }
+            }
+            None => DispatchResult {
+                effects: Vec::new(),
+                state_changed: false,
+            },
+        }
+    }
+}
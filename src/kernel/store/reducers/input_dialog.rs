@@ -1,4 +1,4 @@
-use crate::kernel::{Action, Effect, InputDialogKind, OverlayKind};
+use crate::kernel::{Action, Effect, FilePathPurpose, InputDialogKind, OverlayKind};
 
 impl super::Store {
     pub(super) fn reduce_input_dialog_action(
@@ -158,6 +158,15 @@ impl super::Store {
                             };
                         }
                     }
+                    InputDialogKind::FilePath { .. } => {
+                        if value.is_empty() {
+                            let prev = dialog.error.replace("Path required".to_string());
+                            return super::DispatchResult {
+                                effects: Vec::new(),
+                                state_changed: prev.as_deref() != dialog.error.as_deref(),
+                            };
+                        }
+                    }
                 }
 
                 let value = value.to_string();
@@ -217,9 +226,26 @@ impl super::Store {
                     },
                     InputDialogKind::LspWorkspaceSymbols => {
                         let _ = self.state.symbols.clear();
+                        self.state.symbols.set_query(value.clone());
                         self.open_overlay(OverlayKind::Symbols);
                         Effect::LspWorkspaceSymbolsRequest { query: value }
                     }
+                    InputDialogKind::FilePath { purpose } => {
+                        // 相对路径按工作区根解析。
+                        let path = self.state.workspace_root.join(&value);
+                        match purpose {
+                            FilePathPurpose::ExportKeybindings => {
+                                Effect::ExportKeybindings { path }
+                            }
+                            FilePathPurpose::ImportKeybindings => {
+                                Effect::ImportKeybindings { path }
+                            }
+                            FilePathPurpose::AddWorkspaceFolder => {
+                                Effect::AddWorkspaceFolder { path }
+                            }
+                            FilePathPurpose::ExportAsHtml => Effect::ExportAsHtml { path },
+                        }
+                    }
                 };
 
                 super::DispatchResult {
@@ -1,7 +1,7 @@
 use crate::core::Command;
 use crate::kernel::language::{adapter::adapter_for_tab, CompletionResolveState};
 use crate::kernel::services::ports::{LspCompletionTriggerContext, LspPosition, LspRange};
-use crate::kernel::{Effect, InputDialogKind, OverlayKind};
+use crate::kernel::{CallHierarchyDirection, Effect, InputDialogKind, OverlayKind};
 
 use super::intel::completion::sync_completion_items_from_cache;
 use super::intel::lsp::{
@@ -12,6 +12,24 @@ use super::util::is_lsp_source_path;
 use super::DispatchResult;
 
 impl super::Store {
+    /// 弹出改名输入框，`placeholder` 为服务端 prepareRename 给出的预填名字。
+    pub(super) fn open_lsp_rename_dialog(
+        &mut self,
+        path: std::path::PathBuf,
+        line: u32,
+        column: u32,
+        placeholder: Option<String>,
+    ) -> bool {
+        let dialog = &mut self.state.ui.input_dialog;
+        dialog.reset();
+        dialog.visible = true;
+        dialog.title = "Rename Symbol".to_string();
+        dialog.value = placeholder.unwrap_or_default();
+        dialog.cursor = dialog.value.len();
+        dialog.kind = Some(InputDialogKind::LspRename { path, line, column });
+        true
+    }
+
     pub(super) fn reduce_lsp_command(&mut self, command: Command) -> DispatchResult {
         let mut state_changed = false;
         let effects = Vec::new();
@@ -88,6 +106,42 @@ impl super::Store {
                     };
                 }
             }
+            Command::LspTypeDefinition => {
+                if let Some((_pane, path, line, column, _version)) = lsp_request_target(&self.state)
+                {
+                    let supports_type_definition =
+                        lsp_server_capabilities_for_path(&self.state, &path)
+                            .is_none_or(|c| c.type_definition);
+                    if !supports_type_definition {
+                        return DispatchResult {
+                            effects,
+                            state_changed: false,
+                        };
+                    }
+                    return DispatchResult {
+                        effects: vec![Effect::LspTypeDefinitionRequest { path, line, column }],
+                        state_changed,
+                    };
+                }
+            }
+            Command::LspImplementation => {
+                if let Some((_pane, path, line, column, _version)) = lsp_request_target(&self.state)
+                {
+                    let supports_implementation =
+                        lsp_server_capabilities_for_path(&self.state, &path)
+                            .is_none_or(|c| c.implementation);
+                    if !supports_implementation {
+                        return DispatchResult {
+                            effects,
+                            state_changed: false,
+                        };
+                    }
+                    return DispatchResult {
+                        effects: vec![Effect::LspImplementationRequest { path, line, column }],
+                        state_changed,
+                    };
+                }
+            }
             Command::LspCompletion => {
                 if let Some((pane, path, line, column, version)) = lsp_request_target(&self.state) {
                     let supports_completion = lsp_server_capabilities_for_path(&self.state, &path)
@@ -320,21 +374,23 @@ impl super::Store {
                     };
                 };
 
-                let supports_rename =
-                    lsp_server_capabilities_for_path(&self.state, &path).is_none_or(|c| c.rename);
-                if !supports_rename {
+                let capabilities = lsp_server_capabilities_for_path(&self.state, &path);
+                if !capabilities.is_none_or(|c| c.rename) {
                     return DispatchResult {
                         effects,
                         state_changed: false,
                     };
                 }
 
-                self.state.ui.input_dialog.reset();
-                self.state.ui.input_dialog.visible = true;
-                self.state.ui.input_dialog.title = "Rename Symbol".to_string();
-                self.state.ui.input_dialog.kind =
-                    Some(InputDialogKind::LspRename { path, line, column });
-                state_changed = true;
+                // 服务端支持 prepareRename 时先校验光标处能否改名，响应回来再弹框。
+                if capabilities.is_some_and(|c| c.prepare_rename) {
+                    return DispatchResult {
+                        effects: vec![Effect::LspPrepareRenameRequest { path, line, column }],
+                        state_changed: false,
+                    };
+                }
+
+                state_changed = self.open_lsp_rename_dialog(path, line, column, None);
             }
             Command::LspReferences => {
                 if let Some((_pane, path, line, column, _version)) = lsp_request_target(&self.state)
@@ -357,6 +413,37 @@ impl super::Store {
                     };
                 }
             }
+            Command::LspCallHierarchy | Command::LspCallHierarchyOutgoing => {
+                if let Some((_pane, path, line, column, _version)) = lsp_request_target(&self.state)
+                {
+                    let supports_call_hierarchy =
+                        lsp_server_capabilities_for_path(&self.state, &path)
+                            .is_none_or(|c| c.call_hierarchy);
+                    if !supports_call_hierarchy {
+                        return DispatchResult {
+                            effects,
+                            state_changed: false,
+                        };
+                    }
+
+                    let direction = if matches!(command, Command::LspCallHierarchyOutgoing) {
+                        CallHierarchyDirection::Outgoing
+                    } else {
+                        CallHierarchyDirection::Incoming
+                    };
+                    let mut changed = self.state.call_hierarchy.reset(direction);
+                    changed |= self.open_overlay(OverlayKind::CallHierarchy);
+
+                    return DispatchResult {
+                        effects: vec![Effect::LspPrepareCallHierarchyRequest {
+                            path,
+                            line,
+                            column,
+                        }],
+                        state_changed: state_changed || changed,
+                    };
+                }
+            }
             Command::LspDocumentSymbols => {
                 let pane = self.state.ui.editor_layout.active_pane;
                 let Some(tab) = self.state.editor.pane(pane).and_then(|p| p.active_tab()) else {
@@ -509,6 +596,101 @@ impl super::Store {
                     state_changed,
                 };
             }
+            Command::LspDocumentLink => {
+                let pane = self.state.ui.editor_layout.active_pane;
+                let Some(tab) = self.state.editor.pane(pane).and_then(|p| p.active_tab()) else {
+                    return DispatchResult {
+                        effects,
+                        state_changed,
+                    };
+                };
+                let Some(path) = tab.path.as_ref().cloned() else {
+                    return DispatchResult {
+                        effects,
+                        state_changed,
+                    };
+                };
+                if !is_lsp_source_path(&path) {
+                    return DispatchResult {
+                        effects,
+                        state_changed,
+                    };
+                }
+                let supports_document_link = lsp_server_capabilities_for_path(&self.state, &path)
+                    .is_some_and(|c| c.document_link);
+                if !supports_document_link {
+                    return DispatchResult {
+                        effects,
+                        state_changed: false,
+                    };
+                }
+
+                return DispatchResult {
+                    effects: vec![Effect::LspDocumentLinkRequest {
+                        path,
+                        version: tab.edit_version,
+                    }],
+                    state_changed,
+                };
+            }
+            Command::LspDocumentHighlight => {
+                let pane = self.state.ui.editor_layout.active_pane;
+                let Some(tab) = self.state.editor.pane(pane).and_then(|p| p.active_tab()) else {
+                    return DispatchResult {
+                        effects,
+                        state_changed,
+                    };
+                };
+                // 光标需落在标识符上或紧随其后；不像 hover 那样向前回溯，避免停在空白处时高亮远处的符号。
+                let cursor = tab.buffer.cursor();
+                let target = tab.path.as_ref().cloned().and_then(|path| {
+                    if !is_lsp_source_path(&path) {
+                        return None;
+                    }
+                    let supports_document_highlight =
+                        lsp_server_capabilities_for_path(&self.state, &path)
+                            .is_some_and(|c| c.document_highlight);
+                    if !supports_document_highlight {
+                        return None;
+                    }
+                    let pos = tab.identifier_pos_at(cursor).or_else(|| {
+                        let (row, col) = cursor;
+                        col.checked_sub(1)
+                            .and_then(|col| tab.identifier_pos_at((row, col)))
+                    })?;
+                    if tab.is_in_string_or_comment_at_char(tab.buffer.pos_to_char(pos)) {
+                        return None;
+                    }
+                    let encoding = lsp_position_encoding_for_path(&self.state, &path);
+                    let (line, column) = lsp_position_from_buffer_pos(tab, pos, encoding);
+                    Some((path, line, column, tab.edit_version))
+                });
+
+                let Some((path, line, column, version)) = target else {
+                    if let Some(tab) = self
+                        .state
+                        .editor
+                        .pane_mut(pane)
+                        .and_then(|p| p.active_tab_mut())
+                    {
+                        state_changed |= tab.set_document_highlights(Vec::new());
+                    }
+                    return DispatchResult {
+                        effects,
+                        state_changed,
+                    };
+                };
+
+                return DispatchResult {
+                    effects: vec![Effect::LspDocumentHighlightRequest {
+                        path,
+                        line,
+                        column,
+                        version,
+                    }],
+                    state_changed,
+                };
+            }
             cmd @ (Command::EditorFoldToggle | Command::EditorFold | Command::EditorUnfold) => {
                 let pane = self.state.ui.editor_layout.active_pane;
                 let Some((path, version, needs_request)) = self
@@ -1,7 +1,12 @@
+use crate::kernel::services::ports::{
+    GlobalSearchMessage, LspPositionEncoding, LspWorkspaceEdit, LspWorkspaceFileEdit,
+};
 use crate::kernel::Action;
 use crate::kernel::SearchResultItem;
 use std::path::PathBuf;
 
+use super::util::open_tabs_for_path;
+
 pub(super) fn search_open_target(
     search: &crate::kernel::SearchState,
     item: SearchResultItem,
@@ -32,11 +37,23 @@ impl super::Store {
             },
             Action::SearchAppend(ch) => super::DispatchResult {
                 effects: Vec::new(),
-                state_changed: self.state.search.append_query_char(ch),
+                state_changed: if let Some(field) = self.state.search.filter_focused {
+                    self.state.search.append_filter_char(field, ch)
+                } else if self.state.search.replace_focused {
+                    self.state.search.append_replacement_char(ch)
+                } else {
+                    self.state.search.append_query_char(ch)
+                },
             },
             Action::SearchBackspace => super::DispatchResult {
                 effects: Vec::new(),
-                state_changed: self.state.search.backspace_query(),
+                state_changed: if let Some(field) = self.state.search.filter_focused {
+                    self.state.search.backspace_filter(field)
+                } else if self.state.search.replace_focused {
+                    self.state.search.backspace_replacement()
+                } else {
+                    self.state.search.backspace_query()
+                },
             },
             Action::SearchClickRow { row } => super::DispatchResult {
                 effects: Vec::new(),
@@ -46,11 +63,67 @@ impl super::Store {
                 effects: Vec::new(),
                 state_changed: self.state.search.set_active_search_id(search_id),
             },
-            Action::SearchMessage(msg) => super::DispatchResult {
-                effects: Vec::new(),
-                state_changed: self.state.search.apply_message(msg),
-            },
+            Action::SearchMessage(msg) => self.apply_search_message(msg),
             _ => unreachable!("non-search action passed to reduce_search_action"),
         }
     }
+
+    /// 全局替换的每个文件走 workspace edit：已打开的 tab 进 undo 历史，未打开的文件直接写盘。
+    /// 替换按磁盘内容计算位置，tab 有未保存修改时位置不可信，按失败计。
+    fn apply_search_message(&mut self, msg: GlobalSearchMessage) -> super::DispatchResult {
+        let mut effects = Vec::new();
+        let mut state_changed = false;
+
+        let msg = match msg {
+            GlobalSearchMessage::Replaced {
+                search_id,
+                path,
+                count,
+                edits,
+            } if self.state.search.active_search_id == Some(search_id) => {
+                let has_unsaved_tab = open_tabs_for_path(&self.state.editor, &path)
+                    .into_iter()
+                    .any(|(pane, tab_index)| {
+                        self.state
+                            .editor
+                            .pane(pane)
+                            .and_then(|pane| pane.tabs.get(tab_index))
+                            .is_some_and(|tab| tab.dirty)
+                    });
+                if has_unsaved_tab {
+                    GlobalSearchMessage::ReplaceError {
+                        search_id,
+                        path,
+                        error: "file has unsaved changes".to_string(),
+                    }
+                } else {
+                    let edit = LspWorkspaceEdit {
+                        changes: vec![LspWorkspaceFileEdit {
+                            path: path.clone(),
+                            edits,
+                        }],
+                        resource_ops: Vec::new(),
+                    };
+                    state_changed |= self.apply_workspace_edit_with_encoding(
+                        edit,
+                        LspPositionEncoding::Utf8,
+                        &mut effects,
+                    );
+                    GlobalSearchMessage::Replaced {
+                        search_id,
+                        path,
+                        count,
+                        edits: Vec::new(),
+                    }
+                }
+            }
+            msg => msg,
+        };
+
+        state_changed |= self.state.search.apply_message(msg);
+        super::DispatchResult {
+            effects,
+            state_changed,
+        }
+    }
 }
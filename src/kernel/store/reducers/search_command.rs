@@ -1,6 +1,6 @@
 use crate::core::Command;
 use crate::kernel::state::{PendingEditorNavigation, PendingEditorNavigationTarget};
-use crate::kernel::{EditorAction, Effect, FocusTarget, OverlayKind};
+use crate::kernel::{Action, EditorAction, Effect, FocusTarget, GitPanelRow, OverlayKind};
 
 use super::intel::lsp::{
     lsp_position_encoding_for_path, lsp_position_to_byte_offset, problem_byte_offset,
@@ -21,6 +21,7 @@ impl super::Store {
                 if search_focused && !self.state.search.query.is_empty() {
                     let root = self.state.workspace_root.clone();
                     let pattern = self.state.search.query.clone();
+                    let (include_glob, exclude_glob) = self.state.search.path_filters();
                     let changed = self.state.search.begin_search();
                     return DispatchResult {
                         effects: vec![Effect::StartGlobalSearch {
@@ -29,11 +30,47 @@ impl super::Store {
                             // 全局搜索固定为字面量 + 大小写不敏感（移除分屏搜索后无 UI 暴露开关）。
                             case_sensitive: false,
                             use_regex: false,
+                            skip_binary: self.state.editor.config.search_skip_binary,
+                            include_glob,
+                            exclude_glob,
                         }],
                         state_changed: changed,
                     };
                 }
             }
+            Command::GlobalSearchToggleReplace => {
+                if search_overlay_focused(&self.state.ui) {
+                    state_changed = self.state.search.toggle_replace();
+                }
+            }
+            Command::GlobalSearchToggleFilters => {
+                if search_overlay_focused(&self.state.ui) {
+                    state_changed = self.state.search.toggle_filters();
+                }
+            }
+            Command::GlobalSearchSwitchField => {
+                if search_overlay_focused(&self.state.ui) {
+                    state_changed = self.state.search.switch_field();
+                }
+            }
+            Command::GlobalSearchReplaceAll => {
+                if search_overlay_focused(&self.state.ui) && self.state.search.begin_replace() {
+                    let (include_glob, exclude_glob) = self.state.search.path_filters();
+                    return DispatchResult {
+                        effects: vec![Effect::StartGlobalReplace {
+                            root: self.state.workspace_root.clone(),
+                            pattern: self.state.search.query.clone(),
+                            replacement: self.state.search.replacement.clone(),
+                            case_sensitive: false,
+                            use_regex: false,
+                            skip_binary: self.state.editor.config.search_skip_binary,
+                            include_glob,
+                            exclude_glob,
+                        }],
+                        state_changed: true,
+                    };
+                }
+            }
             Command::SearchResultsMoveUp => {
                 if search_overlay_focused(&self.state.ui) {
                     state_changed = self.state.search.move_selection(-1);
@@ -53,6 +90,23 @@ impl super::Store {
                     && self.state.ui.overlay.active == Some(OverlayKind::Symbols)
                 {
                     state_changed = self.state.symbols.move_selection(-1);
+                } else if self.state.ui.focus == FocusTarget::Overlay
+                    && self.state.ui.overlay.active == Some(OverlayKind::Git)
+                {
+                    state_changed = self.state.git.move_selection(-1);
+                } else if self.state.ui.focus == FocusTarget::Overlay
+                    && self.state.ui.overlay.active == Some(OverlayKind::GitDiff)
+                {
+                    state_changed = self
+                        .state
+                        .git
+                        .diff
+                        .as_mut()
+                        .is_some_and(|diff| diff.move_hunk(-1));
+                } else if self.state.ui.focus == FocusTarget::Overlay
+                    && self.state.ui.overlay.active == Some(OverlayKind::CallHierarchy)
+                {
+                    state_changed = self.state.call_hierarchy.move_selection(-1);
                 }
             }
             Command::SearchResultsMoveDown => {
@@ -74,6 +128,23 @@ impl super::Store {
                     && self.state.ui.overlay.active == Some(OverlayKind::Symbols)
                 {
                     state_changed = self.state.symbols.move_selection(1);
+                } else if self.state.ui.focus == FocusTarget::Overlay
+                    && self.state.ui.overlay.active == Some(OverlayKind::Git)
+                {
+                    state_changed = self.state.git.move_selection(1);
+                } else if self.state.ui.focus == FocusTarget::Overlay
+                    && self.state.ui.overlay.active == Some(OverlayKind::GitDiff)
+                {
+                    state_changed = self
+                        .state
+                        .git
+                        .diff
+                        .as_mut()
+                        .is_some_and(|diff| diff.move_hunk(1));
+                } else if self.state.ui.focus == FocusTarget::Overlay
+                    && self.state.ui.overlay.active == Some(OverlayKind::CallHierarchy)
+                {
+                    state_changed = self.state.call_hierarchy.move_selection(1);
                 }
             }
             Command::SearchResultsScrollUp => {
@@ -95,6 +166,23 @@ impl super::Store {
                     && self.state.ui.overlay.active == Some(OverlayKind::Symbols)
                 {
                     state_changed = self.state.symbols.scroll(-3);
+                } else if self.state.ui.focus == FocusTarget::Overlay
+                    && self.state.ui.overlay.active == Some(OverlayKind::Git)
+                {
+                    state_changed = self.state.git.scroll(-3);
+                } else if self.state.ui.focus == FocusTarget::Overlay
+                    && self.state.ui.overlay.active == Some(OverlayKind::GitDiff)
+                {
+                    state_changed = self
+                        .state
+                        .git
+                        .diff
+                        .as_mut()
+                        .is_some_and(|diff| diff.scroll(-3));
+                } else if self.state.ui.focus == FocusTarget::Overlay
+                    && self.state.ui.overlay.active == Some(OverlayKind::CallHierarchy)
+                {
+                    state_changed = self.state.call_hierarchy.scroll(-3);
                 }
             }
             Command::SearchResultsScrollDown => {
@@ -116,11 +204,39 @@ impl super::Store {
                     && self.state.ui.overlay.active == Some(OverlayKind::Symbols)
                 {
                     state_changed = self.state.symbols.scroll(3);
+                } else if self.state.ui.focus == FocusTarget::Overlay
+                    && self.state.ui.overlay.active == Some(OverlayKind::Git)
+                {
+                    state_changed = self.state.git.scroll(3);
+                } else if self.state.ui.focus == FocusTarget::Overlay
+                    && self.state.ui.overlay.active == Some(OverlayKind::GitDiff)
+                {
+                    state_changed = self
+                        .state
+                        .git
+                        .diff
+                        .as_mut()
+                        .is_some_and(|diff| diff.scroll(3));
+                } else if self.state.ui.focus == FocusTarget::Overlay
+                    && self.state.ui.overlay.active == Some(OverlayKind::CallHierarchy)
+                {
+                    state_changed = self.state.call_hierarchy.scroll(3);
                 }
             }
             Command::SearchResultsToggleExpand => {
                 if search_overlay_focused(&self.state.ui) {
                     state_changed = self.state.search.toggle_selected_file_expanded();
+                } else if self.state.ui.focus == FocusTarget::Overlay
+                    && self.state.ui.overlay.active == Some(OverlayKind::Git)
+                    && self.state.git.selected_row() == Some(GitPanelRow::StashesHeader)
+                {
+                    state_changed = self.state.git.toggle_stashes_expanded();
+                } else if self.state.ui.focus == FocusTarget::Overlay
+                    && self.state.ui.overlay.active == Some(OverlayKind::CallHierarchy)
+                {
+                    if let Some(item_index) = self.state.call_hierarchy.selected_item_index() {
+                        return self.dispatch(Action::CallHierarchyExpand { item_index });
+                    }
                 }
             }
             Command::SearchResultsOpenSelected => {
@@ -457,6 +573,18 @@ impl super::Store {
                         effects: vec![Effect::LoadFile(path)],
                         state_changed: true,
                     };
+                } else if self.state.ui.focus == FocusTarget::Overlay
+                    && self.state.ui.overlay.active == Some(OverlayKind::Git)
+                {
+                    return self.activate_selected_git_row();
+                } else if self.state.ui.focus == FocusTarget::Overlay
+                    && self.state.ui.overlay.active == Some(OverlayKind::GitDiff)
+                {
+                    return self.stage_selected_git_hunk();
+                } else if self.state.ui.focus == FocusTarget::Overlay
+                    && self.state.ui.overlay.active == Some(OverlayKind::CallHierarchy)
+                {
+                    return self.open_selected_call_hierarchy_item();
                 }
             }
             _ => unreachable!("non-search command passed to reduce_search_command"),
@@ -0,0 +1,385 @@
+use crate::core::Command;
+use crate::kernel::editor::{EditorMode, VimOperator, VimPending};
+use crate::kernel::Effect;
+
+use super::DispatchResult;
+
+impl super::Store {
+    /// Visual 模式或有未完成前缀时，光标命令不再直接移动光标，而是扩展选区 / 交给操作符。
+    pub(super) fn vim_intercepts_motion(&self, command: &Command) -> bool {
+        if !self.state.editor.config.vim_mode || !command.is_cursor_command() {
+            return false;
+        }
+        let pane = self.state.ui.editor_layout.active_pane;
+        self.state
+            .editor
+            .pane(pane)
+            .is_some_and(|p| p.mode.is_visual() || p.vim_pending.is_some())
+    }
+
+    pub(super) fn reduce_vim_command(&mut self, command: Command) -> DispatchResult {
+        let pane = self.state.ui.editor_layout.active_pane;
+        if !self.state.editor.config.vim_mode || self.state.editor.pane(pane).is_none() {
+            return DispatchResult {
+                effects: Vec::new(),
+                state_changed: false,
+            };
+        }
+
+        match command {
+            Command::VimNormalMode => self.vim_enter_mode(pane, EditorMode::Normal),
+            Command::VimInsert => self.vim_enter_mode(pane, EditorMode::Insert),
+            Command::VimAppend => {
+                let at_line_end = self.vim_tab(pane).is_none_or(|tab| {
+                    let (row, col) = tab.buffer.cursor();
+                    col >= tab.buffer.line_grapheme_len(row)
+                });
+                let mut result = self.vim_enter_mode(pane, EditorMode::Insert);
+                if !at_line_end {
+                    let (changed, effects) =
+                        self.state.editor.apply_command(pane, Command::CursorRight);
+                    result.state_changed |= changed;
+                    result.effects.extend(effects);
+                }
+                result
+            }
+            Command::VimOpenLineBelow => {
+                let mut result = self.vim_enter_mode(pane, EditorMode::Insert);
+                let _ = self
+                    .state
+                    .editor
+                    .apply_command(pane, Command::CursorLineEnd);
+                let newline = self.reduce_editor_command(Command::InsertNewline);
+                result.state_changed |= newline.state_changed;
+                result.effects.extend(newline.effects);
+                result
+            }
+            Command::VimOpenLineAbove => {
+                let row = self.vim_tab(pane).map_or(0, |tab| tab.buffer.cursor().0);
+                let mut result = self.vim_enter_mode(pane, EditorMode::Insert);
+                // 从上一行行尾换行，新行沿用上一行的缩进；首行没有上一行，只能在行首换行再上移。
+                let newline = if row > 0 {
+                    let _ = self.state.editor.apply_command(pane, Command::CursorUp);
+                    let _ = self
+                        .state
+                        .editor
+                        .apply_command(pane, Command::CursorLineEnd);
+                    self.reduce_editor_command(Command::InsertNewline)
+                } else {
+                    let _ = self
+                        .state
+                        .editor
+                        .apply_command(pane, Command::CursorLineStart);
+                    let newline = self.reduce_editor_command(Command::InsertNewline);
+                    let _ = self.state.editor.apply_command(pane, Command::CursorUp);
+                    newline
+                };
+                result.state_changed |= newline.state_changed;
+                result.effects.extend(newline.effects);
+                result
+            }
+            Command::VimVisual | Command::VimVisualLine => {
+                let target = if command == Command::VimVisual {
+                    EditorMode::Visual
+                } else {
+                    EditorMode::VisualLine
+                };
+                let current = self.state.editor.pane(pane).map(|p| p.mode);
+                // 再按一次同一个键退回 Normal，与 Vim 一致。
+                if current == Some(target) {
+                    return self.vim_enter_mode(pane, EditorMode::Normal);
+                }
+                // `v` ↔ `V` 互切时保留原锚点。
+                let anchor = self.vim_tab(pane).map(|tab| {
+                    let cursor = tab.buffer.cursor();
+                    match tab.buffer.selection() {
+                        Some(selection) if current.is_some_and(EditorMode::is_visual) => {
+                            selection.anchor()
+                        }
+                        _ => cursor,
+                    }
+                });
+                let result = self.vim_enter_mode(pane, target);
+                if let Some(anchor) = anchor {
+                    self.vim_sync_visual_selection(pane, anchor);
+                }
+                result
+            }
+            Command::VimGoto => {
+                let pending = self.vim_take_pending(pane);
+                if pending == Some(VimPending::Goto) {
+                    return self.reduce_vim_motion(Command::CursorFileStart);
+                }
+                self.vim_set_pending(pane, Some(VimPending::Goto))
+            }
+            Command::VimDelete | Command::VimChange | Command::VimYank => {
+                let op = match command {
+                    Command::VimDelete => VimOperator::Delete,
+                    Command::VimChange => VimOperator::Change,
+                    _ => VimOperator::Yank,
+                };
+                self.reduce_vim_operator(pane, op)
+            }
+            Command::VimWordEnd => self.reduce_vim_motion(Command::VimWordEnd),
+            _ => unreachable!("non-vim command passed to reduce_vim_command"),
+        }
+    }
+
+    /// 执行一个移动：Visual 模式下扩展选区，有操作符时把移动范围交给操作符，否则只移动光标。
+    pub(super) fn reduce_vim_motion(&mut self, motion: Command) -> DispatchResult {
+        let pane = self.state.ui.editor_layout.active_pane;
+        let mode = self
+            .state
+            .editor
+            .pane(pane)
+            .map(|p| p.mode)
+            .unwrap_or_default();
+        let pending = self.vim_take_pending(pane);
+        let Some(tab) = self.vim_tab(pane) else {
+            return DispatchResult {
+                effects: Vec::new(),
+                state_changed: pending.is_some(),
+            };
+        };
+        let start = tab.buffer.cursor();
+        let anchor = tab.buffer.selection().map_or(start, |s| s.anchor());
+
+        if mode.is_visual() {
+            if let Some(tab) = self.vim_tab_mut(pane) {
+                tab.buffer.clear_selection();
+            }
+            let (_, effects) = self.vim_move(pane, motion);
+            self.vim_sync_visual_selection(pane, anchor);
+            return DispatchResult {
+                effects,
+                state_changed: true,
+            };
+        }
+
+        let (moved, mut effects) = self.vim_move(pane, motion);
+        let Some(VimPending::Operator(op)) = pending else {
+            return DispatchResult {
+                effects,
+                state_changed: moved || pending.is_some(),
+            };
+        };
+
+        let end = self.vim_tab(pane).map_or(start, |tab| tab.buffer.cursor());
+        if start == end {
+            return DispatchResult {
+                effects,
+                state_changed: true,
+            };
+        }
+        let (from, to) = if start <= end {
+            (start, end)
+        } else {
+            (end, start)
+        };
+        if let Some(tab) = self.vim_tab_mut(pane) {
+            tab.vim_select(from, to);
+        }
+        let result = self.vim_apply_operator(pane, op, from);
+        effects.extend(result.effects);
+        DispatchResult {
+            effects,
+            state_changed: true,
+        }
+    }
+
+    /// `d` / `c` / `y`：Visual 模式下直接作用于选区；Normal 模式下第一次按记为前缀，
+    /// 连按两次（`dd` / `cc` / `yy`）作用于整行。
+    fn reduce_vim_operator(&mut self, pane: usize, op: VimOperator) -> DispatchResult {
+        let mode = self
+            .state
+            .editor
+            .pane(pane)
+            .map(|p| p.mode)
+            .unwrap_or_default();
+        let pending = self.vim_take_pending(pane);
+        let Some(tab) = self.vim_tab(pane) else {
+            return DispatchResult {
+                effects: Vec::new(),
+                state_changed: pending.is_some(),
+            };
+        };
+        let cursor = tab.buffer.cursor();
+
+        let (first, last) = match mode {
+            EditorMode::VisualLine => {
+                let anchor_row = tab.buffer.selection().map_or(cursor.0, |s| s.anchor().0);
+                (anchor_row.min(cursor.0), anchor_row.max(cursor.0))
+            }
+            EditorMode::Visual => {
+                let (from, to) = tab
+                    .buffer
+                    .selection()
+                    .map_or((cursor, cursor), |s| s.range());
+                if from == to {
+                    return self.vim_enter_mode(pane, EditorMode::Normal);
+                }
+                return self.vim_apply_operator(pane, op, from);
+            }
+            _ if pending == Some(VimPending::Operator(op)) => (cursor.0, cursor.0),
+            _ => {
+                let pending = match pending {
+                    // `dy` 这类不同操作符连按视为取消。
+                    Some(VimPending::Operator(_)) => None,
+                    _ => Some(VimPending::Operator(op)),
+                };
+                return self.vim_set_pending(pane, pending);
+            }
+        };
+
+        let Some(tab) = self.vim_tab_mut(pane) else {
+            return self.vim_enter_mode(pane, EditorMode::Normal);
+        };
+        // `cc` 保留换行，清空行内容后直接在原行输入。
+        let (from, to) = if op == VimOperator::Change {
+            ((first, 0), (last, tab.buffer.line_grapheme_len(last)))
+        } else {
+            tab.vim_linewise_range(first, last)
+        };
+        tab.vim_select(from, to);
+        let mut result = self.vim_apply_operator(pane, op, (first, 0));
+        if op == VimOperator::Delete {
+            // 删掉的若是末行，光标落在上一行末尾；Vim 会回到行首。
+            if let Some(tab) = self.vim_tab_mut(pane) {
+                let row = tab.buffer.cursor().0;
+                tab.buffer.set_cursor(row, 0);
+            }
+            result.state_changed = true;
+        }
+        result
+    }
+
+    /// 对当前选区执行操作符。删除与修改都会把内容放进剪贴板（和 Vim 的无名寄存器一致）；
+    /// 复制后取消选区、光标回到 `start`。
+    fn vim_apply_operator(
+        &mut self,
+        pane: usize,
+        op: VimOperator,
+        start: (usize, usize),
+    ) -> DispatchResult {
+        let mut effects: Vec<Effect> = Vec::new();
+        let mut state_changed = true;
+        let next_mode = match op {
+            VimOperator::Delete => {
+                let cut = self.reduce_editor_command(Command::Cut);
+                effects.extend(cut.effects);
+                EditorMode::Normal
+            }
+            VimOperator::Change => {
+                let cut = self.reduce_editor_command(Command::Cut);
+                effects.extend(cut.effects);
+                EditorMode::Insert
+            }
+            VimOperator::Yank => {
+                let (_, copy_effects) = self.state.editor.apply_command(pane, Command::Copy);
+                effects.extend(copy_effects);
+                if let Some(tab) = self.vim_tab_mut(pane) {
+                    tab.buffer.clear_selection();
+                    tab.buffer.set_cursor(start.0, start.1);
+                }
+                EditorMode::Normal
+            }
+        };
+        if let Some(p) = self.state.editor.pane_mut(pane) {
+            p.mode = next_mode;
+            p.vim_pending = None;
+        } else {
+            state_changed = false;
+        }
+        DispatchResult {
+            effects,
+            state_changed,
+        }
+    }
+
+    fn vim_move(&mut self, pane: usize, motion: Command) -> (bool, Vec<Effect>) {
+        if motion == Command::VimWordEnd {
+            let tab_size = self.state.editor.config.tab_size;
+            let moved = self
+                .vim_tab_mut(pane)
+                .is_some_and(|tab| tab.vim_word_end(tab_size));
+            return (moved, Vec::new());
+        }
+        self.state.editor.apply_command(pane, motion)
+    }
+
+    /// 按 Visual 模式重建选区：字符模式从锚点到光标，行模式覆盖锚点行到光标行的整行。
+    fn vim_sync_visual_selection(&mut self, pane: usize, anchor: (usize, usize)) {
+        let mode = self
+            .state
+            .editor
+            .pane(pane)
+            .map(|p| p.mode)
+            .unwrap_or_default();
+        let Some(tab) = self.vim_tab_mut(pane) else {
+            return;
+        };
+        let cursor = tab.buffer.cursor();
+        match mode {
+            EditorMode::Visual => tab.vim_select(anchor, cursor),
+            EditorMode::VisualLine => {
+                let anchor_len = tab.buffer.line_grapheme_len(anchor.0);
+                let cursor_len = tab.buffer.line_grapheme_len(cursor.0);
+                if cursor.0 >= anchor.0 {
+                    tab.vim_select((anchor.0, 0), (cursor.0, cursor_len));
+                } else {
+                    tab.vim_select((anchor.0, anchor_len), (cursor.0, 0));
+                }
+            }
+            EditorMode::Insert | EditorMode::Normal => {}
+        }
+    }
+
+    fn vim_enter_mode(&mut self, pane: usize, mode: EditorMode) -> DispatchResult {
+        let mut state_changed = false;
+        if let Some(p) = self.state.editor.pane_mut(pane) {
+            state_changed = p.mode != mode || p.vim_pending.is_some();
+            p.mode = mode;
+            p.vim_pending = None;
+        }
+        if let Some(tab) = self.vim_tab_mut(pane) {
+            if tab.buffer.selection().is_some() {
+                tab.buffer.clear_selection();
+                state_changed = true;
+            }
+        }
+        DispatchResult {
+            effects: Vec::new(),
+            state_changed,
+        }
+    }
+
+    fn vim_set_pending(&mut self, pane: usize, pending: Option<VimPending>) -> DispatchResult {
+        let state_changed = self.state.editor.pane_mut(pane).is_some_and(|p| {
+            let changed = p.vim_pending != pending;
+            p.vim_pending = pending;
+            changed
+        });
+        DispatchResult {
+            effects: Vec::new(),
+            state_changed,
+        }
+    }
+
+    fn vim_take_pending(&mut self, pane: usize) -> Option<VimPending> {
+        self.state
+            .editor
+            .pane_mut(pane)
+            .and_then(|p| p.vim_pending.take())
+    }
+
+    fn vim_tab(&self, pane: usize) -> Option<&crate::kernel::editor::EditorTabState> {
+        self.state.editor.pane(pane).and_then(|p| p.active_tab())
+    }
+
+    fn vim_tab_mut(&mut self, pane: usize) -> Option<&mut crate::kernel::editor::EditorTabState> {
+        self.state
+            .editor
+            .pane_mut(pane)
+            .and_then(|p| p.active_tab_mut())
+    }
+}
@@ -0,0 +1,70 @@
+use crate::core::Command;
+use crate::kernel::{Action, Effect, FilePathPurpose, InputDialogKind};
+
+use super::DispatchResult;
+
+impl super::Store {
+    pub(super) fn reduce_workspace_folder_action(&mut self, action: Action) -> DispatchResult {
+        match action {
+            Action::WorkspaceFolderAdded { path } => {
+                if self.state.workspace_folders.contains(&path) {
+                    return DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: false,
+                    };
+                }
+                // 与主工作区下已有条目重名时文件树挂不上，但文件夹仍然参与 LSP 根的解析。
+                let _ = self.state.explorer.add_workspace_folder(path.clone());
+                self.state.workspace_folders.push(path);
+                DispatchResult {
+                    effects: Vec::new(),
+                    state_changed: true,
+                }
+            }
+            _ => {
+                unreachable!("non-workspace-folder action passed to reduce_workspace_folder_action")
+            }
+        }
+    }
+
+    pub(super) fn reduce_workspace_folder_command(&mut self, command: Command) -> DispatchResult {
+        match command {
+            Command::AddWorkspaceFolder => {
+                if self.state.ui.input_dialog.visible {
+                    return DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: false,
+                    };
+                }
+                let dialog = &mut self.state.ui.input_dialog;
+                dialog.reset();
+                dialog.visible = true;
+                dialog.title = "Add Folder to Workspace".to_string();
+                dialog.kind = Some(InputDialogKind::FilePath {
+                    purpose: FilePathPurpose::AddWorkspaceFolder,
+                });
+                DispatchResult {
+                    effects: Vec::new(),
+                    state_changed: true,
+                }
+            }
+            Command::RemoveWorkspaceFolder(index) => {
+                if index == 0 || index >= self.state.workspace_folders.len() {
+                    return DispatchResult {
+                        effects: Vec::new(),
+                        state_changed: false,
+                    };
+                }
+                let path = self.state.workspace_folders.remove(index);
+                let _ = self.state.explorer.remove_workspace_folder(&path);
+                DispatchResult {
+                    effects: vec![Effect::RemoveWorkspaceFolder { path }],
+                    state_changed: true,
+                }
+            }
+            _ => unreachable!(
+                "non-workspace-folder command passed to reduce_workspace_folder_command"
+            ),
+        }
+    }
+}
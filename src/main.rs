@@ -13,7 +13,7 @@ mod logging;
 use zcode::app::Workbench;
 use zcode::core::event::InputEvent;
 use zcode::core::wakeup::WakeupSender;
-use zcode::kernel::services::adapters::{AppMessage, AsyncRuntime};
+use zcode::kernel::services::adapters::{perf, AppMessage, AsyncRuntime};
 use zcode::tui::view::{EventResult, View};
 use zcode::tui::{self, terminal_guard::TerminationSignal};
 use zcode::ui::backend::terminal::RatatuiTerminal;
@@ -38,17 +38,21 @@ fn main() -> io::Result<()> {
         }
     }
 
-    let args: Vec<String> = env::args().skip(1).collect();
+    let mut args: Vec<String> = env::args().skip(1).collect();
     if args.iter().any(|a| a == "-h" || a == "--help") {
-        println!("Usage: zcode [path]\n\nIf no path is provided, zcode opens the current directory.\nThe path can be a directory or a file.");
+        println!("Usage: zcode [--perf] [path]\n\nIf no path is provided, zcode opens the current directory.\nThe path can be a directory or a file.\n\n--perf  show frame render latency percentiles in the status bar");
         return Ok(());
     }
+    if let Some(idx) = args.iter().position(|a| a == "--perf") {
+        args.remove(idx);
+        perf::set_frame_stats_visible(true);
+    }
     if args.iter().any(|a| a == "-V" || a == "--version") {
         println!("zcode {}", env!("CARGO_PKG_VERSION"));
         return Ok(());
     }
     if args.len() > 1 {
-        eprintln!("error: too many arguments\n\nUsage: zcode [path]");
+        eprintln!("error: too many arguments\n\nUsage: zcode [--perf] [path]");
         std::process::exit(2);
     }
 
@@ -139,6 +143,27 @@ fn restart_workbench(
     Ok((workbench, rx))
 }
 
+/// 退出 raw mode / alternate screen 跑交互 shell，shell 结束后恢复界面并整屏重绘。
+fn suspend_for_shell(terminal: &mut RatatuiTerminal, cwd: &Path) -> io::Result<()> {
+    use tui::terminal_guard::{CrosstermTerminalOps, TerminalOps};
+
+    let ops = CrosstermTerminalOps;
+    ops.restore()?;
+    match tui::shell::run_shell(cwd) {
+        Ok(status) => tracing::info!(cwd = %cwd.display(), %status, "shell exited"),
+        Err(e) => tracing::error!(cwd = %cwd.display(), error = %e, "shell failed"),
+    }
+    ops.setup()?;
+    terminal.clear()
+}
+
+fn same_workspace(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
 fn run_app(
     terminal: &mut RatatuiTerminal,
     path: &Path,
@@ -148,8 +173,9 @@ fn run_app(
     let mut root_path = path.to_path_buf();
     let (wakeup_tx, wakeup_rx) = zcode::core::wakeup::wakeup_pipe()?;
     let (mut workbench, mut rx) = restart_workbench(root_path.as_path(), &wakeup_tx)?;
+    workbench.restore_session();
     if let Some(path) = startup_file {
-        workbench.runtime().load_file(path);
+        workbench.load_file(path);
     }
 
     let mut dirty = true;
@@ -163,7 +189,11 @@ fn run_app(
         }
 
         if dirty {
+            let frame_start = Instant::now();
             terminal.draw(|backend, area| workbench.render(backend, area))?;
+            if perf::frame_stats_visible() {
+                perf::record_frame(frame_start.elapsed());
+            }
             dirty = workbench.flush_post_render_sync();
         }
 
@@ -183,10 +213,16 @@ fn run_app(
                 match workbench.handle_input(&input_event) {
                     EventResult::Quit => return Ok(()),
                     EventResult::Restart { path, hard } => {
+                        // 原地重载（同一工作区）时把打开的文件、布局等带到新工作台。
+                        let snapshot =
+                            same_workspace(&path, &root_path).then(|| workbench.snapshot_state());
                         root_path = path;
                         let (new_workbench, new_rx) =
                             restart_workbench(root_path.as_path(), &wakeup_tx)?;
                         workbench = new_workbench;
+                        if let Some(snapshot) = snapshot {
+                            workbench.restore_state(snapshot);
+                        }
                         rx = new_rx;
                         dirty = true;
                         last_tick = Instant::now();
@@ -195,6 +231,13 @@ fn run_app(
                         }
                         continue 'app;
                     }
+                    EventResult::Shell { cwd } => {
+                        let cwd = cwd.unwrap_or_else(|| root_path.clone());
+                        suspend_for_shell(terminal, &cwd)?;
+                        dirty = true;
+                        last_tick = Instant::now();
+                        continue 'app;
+                    }
                     EventResult::Ignored => {}
                     _ => dirty = true,
                 }
@@ -213,9 +256,14 @@ fn run_app(
             workbench.handle_message(msg);
             dirty = true;
             if let Some((path, hard)) = workbench.take_pending_restart() {
+                let snapshot =
+                    same_workspace(&path, &root_path).then(|| workbench.snapshot_state());
                 root_path = path;
                 let (new_workbench, new_rx) = restart_workbench(root_path.as_path(), &wakeup_tx)?;
                 workbench = new_workbench;
+                if let Some(snapshot) = snapshot {
+                    workbench.restore_state(snapshot);
+                }
                 rx = new_rx;
                 dirty = true;
                 last_tick = Instant::now();
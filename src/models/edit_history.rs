@@ -1,6 +1,15 @@
-use super::edit_op::{EditOp, OpId};
+use super::edit_op::{EditOp, OpId, OpKind};
 use ropey::Rope;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+
+/// 备份格式版本；结构变化时递增，旧备份读到后直接丢弃。
+const BACKUP_VERSION: u32 = 1;
 
 #[derive(Clone, Debug)]
 pub struct UndoResult {
@@ -9,11 +18,22 @@ pub struct UndoResult {
     pub secondary_cursors: Option<Vec<(usize, usize)>>,
 }
 
-/// 纯内存撤销/重做 DAG。
+/// 已保存内容与当前缓冲区之间唯一不同的一段（字节区间）：磁盘上的 `disk` 被替换成
+/// 缓冲区里的 `buffer`，两侧之外的前缀 / 后缀完全相同。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DirtyRange {
+    pub disk: Range<usize>,
+    pub buffer: Range<usize>,
+    /// 已保存内容的总字节数。
+    pub disk_len: usize,
+}
+
+/// 撤销/重做 DAG。
 ///
-/// 历史只在进程内维护，不落盘——磁盘持久化 / 恢复子系统（备份文件、检查点快照、
-/// reflog/checkout）已整体移除，因为生产从未启用它（`ops_file_path` 恒为 None）。
-/// undo/redo 全程增量：对调用方传入的当前 Rope 应用 `op` 的逆 / 正变换，无需基线快照。
+/// 编辑期间只在内存里维护；关闭干净的标签页时整棵 DAG 写进 `.ops` 备份
+/// （[`HistoryBackup`]），下次打开同一文件且磁盘内容未变时用 [`EditHistory::replay_from_backup`]
+/// 接回撤销链。undo/redo 全程增量：对调用方传入的当前 Rope 应用 `op` 的逆 / 正变换，无需基线快照。
+#[derive(Clone, Debug)]
 pub struct EditHistory {
     ops: FxHashMap<OpId, EditOp>,
     head: OpId,
@@ -115,6 +135,11 @@ impl EditHistory {
         self.head
     }
 
+    /// 获取已保存基线对应的 HEAD
+    pub fn saved_head(&self) -> OpId {
+        self.saved_head
+    }
+
     /// 是否有未保存的修改
     pub fn is_dirty(&self) -> bool {
         self.head != self.saved_head
@@ -149,6 +174,142 @@ impl EditHistory {
         self.saved_head = head;
     }
 
+    /// 从已保存基线走到当前 HEAD（先沿 undo 方向回到公共祖先，再 redo 下来），
+    /// 汇总途经操作触及的区间，得到已保存内容与 `rope` 的差异段。未修改时返回 `None`。
+    pub fn dirty_byte_range(&self, rope: &Rope) -> Option<DirtyRange> {
+        if !self.is_dirty() {
+            return None;
+        }
+
+        let common = self.saved_common_ancestor()?;
+        let mut down = Vec::new();
+        let mut id = self.head;
+        while id != common {
+            let op = self.ops.get(&id)?;
+            down.push(op.kind.clone());
+            id = op.parent;
+        }
+
+        let mut path = Vec::new();
+        let mut id = self.saved_head;
+        while id != common {
+            let op = self.ops.get(&id)?;
+            path.push(op.inverse());
+            id = op.parent;
+        }
+        path.extend(down.into_iter().rev());
+
+        // 逐个操作收窄「相同前缀 / 相同后缀」（字符数），长度从当前文本倒推回基线。
+        let len_chars = rope.len_chars() as isize;
+        let char_delta: isize = path
+            .iter()
+            .flat_map(OpKind::char_edits)
+            .map(|(start, end, inserted)| {
+                inserted.chars().count() as isize - (end as isize - start as isize)
+            })
+            .sum();
+        let mut len = usize::try_from(len_chars - char_delta).ok()?;
+        let saved_len = len;
+        let mut prefix = len;
+        let mut suffix = len;
+        for (start, end, inserted) in path.iter().flat_map(OpKind::char_edits) {
+            prefix = prefix.min(start);
+            suffix = suffix.min(len.checked_sub(end)?);
+            len = len - (end - start) + inserted.chars().count();
+        }
+        // 前后缀不能重叠（例如插入后又删掉同一段）。
+        let suffix = suffix.min(len - prefix).min(saved_len - prefix);
+
+        let byte_delta: isize = path.iter().map(OpKind::byte_delta).sum();
+        let disk_len = usize::try_from(rope.len_bytes() as isize - byte_delta).ok()?;
+        let start = rope.char_to_byte(prefix);
+        let buffer_end = rope.char_to_byte(len - suffix);
+        let suffix_bytes = rope.len_bytes() - buffer_end;
+        Some(DirtyRange {
+            disk: start..disk_len.checked_sub(suffix_bytes)?,
+            buffer: start..buffer_end,
+            disk_len,
+        })
+    }
+
+    /// 由当前文本 `rope` 倒推出已保存基线的内容：沿 undo 方向回到公共祖先，再 redo 到
+    /// 已保存 HEAD。未修改时直接返回 `rope` 的克隆。
+    pub fn saved_rope(&self, rope: &Rope) -> Option<Rope> {
+        let mut out = rope.clone();
+        if !self.is_dirty() {
+            return Some(out);
+        }
+
+        let common = self.saved_common_ancestor()?;
+        let mut id = self.head;
+        while id != common {
+            let op = self.ops.get(&id)?;
+            op.inverse().apply(&mut out);
+            id = op.parent;
+        }
+        let mut down = Vec::new();
+        let mut id = self.saved_head;
+        while id != common {
+            let op = self.ops.get(&id)?;
+            down.push(op);
+            id = op.parent;
+        }
+        for op in down.into_iter().rev() {
+            op.apply(&mut out);
+        }
+        Some(out)
+    }
+
+    /// 已保存 HEAD 与当前 HEAD 在 DAG 上的最近公共祖先。
+    fn saved_common_ancestor(&self) -> Option<OpId> {
+        let mut saved_ancestors = FxHashSet::default();
+        let mut id = self.saved_head;
+        saved_ancestors.insert(id);
+        while !id.is_root() {
+            id = self.ops.get(&id)?.parent;
+            saved_ancestors.insert(id);
+        }
+        let mut id = self.head;
+        while !saved_ancestors.contains(&id) {
+            id = self.ops.get(&id)?.parent;
+        }
+        Some(id)
+    }
+
+    /// 以 `disk_content`（已保存基线对应的磁盘内容）为锚点导出备份。
+    pub fn to_backup(&self, disk_content: &Rope) -> HistoryBackup {
+        let mut ops: Vec<EditOp> = self.ops.values().cloned().collect();
+        ops.sort_by_key(|op| (op.id.timestamp, op.id.counter));
+        HistoryBackup {
+            version: BACKUP_VERSION,
+            disk_len: disk_content.len_bytes(),
+            disk_hash: content_hash(disk_content),
+            saved_head: self.saved_head,
+            ops,
+        }
+    }
+
+    /// 读回 `ops_path` 里的备份，HEAD 与已保存基线都落在磁盘内容上。
+    ///
+    /// 备份损坏、版本不符，或文件在备份之后被外部改过（`disk_content` 对不上）时删除备份并返回
+    /// `InvalidData`，调用方照常用空历史。
+    pub fn replay_from_backup(ops_path: &Path, disk_content: &Rope) -> io::Result<EditHistory> {
+        let bytes = std::fs::read(ops_path)?;
+        let history = serde_json::from_slice::<HistoryBackup>(&bytes)
+            .ok()
+            .and_then(|backup| backup.into_history(disk_content));
+        match history {
+            Some(history) => Ok(history),
+            None => {
+                let _ = std::fs::remove_file(ops_path);
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "stale or unreadable edit history backup",
+                ))
+            }
+        }
+    }
+
     /// 重置历史，丢弃全部已记录操作。
     pub fn clear(&mut self) {
         self.saved_head = OpId::root();
@@ -159,6 +320,62 @@ impl EditHistory {
     }
 }
 
+/// 落盘的撤销历史：全部操作 + 已保存基线，以及基线对应磁盘内容的长度与哈希。
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HistoryBackup {
+    version: u32,
+    disk_len: usize,
+    disk_hash: u64,
+    saved_head: OpId,
+    ops: Vec<EditOp>,
+}
+
+impl HistoryBackup {
+    /// 先写临时文件再 rename，避免留下半截备份。
+    pub fn write_to(&self, ops_path: &Path) -> io::Result<()> {
+        let json = serde_json::to_vec(self).map_err(io::Error::other)?;
+        let tmp_path = ops_path.with_extension("ops.tmp");
+        std::fs::write(&tmp_path, json)?;
+        std::fs::rename(&tmp_path, ops_path)
+    }
+
+    fn into_history(self, disk_content: &Rope) -> Option<EditHistory> {
+        if self.version != BACKUP_VERSION
+            || self.disk_len != disk_content.len_bytes()
+            || self.disk_hash != content_hash(disk_content)
+        {
+            return None;
+        }
+
+        let mut history = EditHistory::new();
+        for op in self.ops {
+            history.push(op);
+        }
+        if !self.saved_head.is_root() && !history.ops.contains_key(&self.saved_head) {
+            return None;
+        }
+        // 重做方向优先走向保存时的那条分支。
+        let mut id = self.saved_head;
+        while let Some(op) = history.ops.get(&id) {
+            history.preferred_child.insert(op.parent, id);
+            id = op.parent;
+        }
+        history.head = self.saved_head;
+        history.saved_head = self.saved_head;
+        Some(history)
+    }
+}
+
+/// 内容哈希。`DefaultHasher::new()` 用固定密钥，跨进程稳定；换编译器版本后对不上只会丢掉备份。
+/// 逐块喂原始字节（不用 `str::hash`，它会在每块后追加分隔符），结果与 Rope 的分块方式无关。
+fn content_hash(rope: &Rope) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for chunk in rope.chunks() {
+        hasher.write(chunk.as_bytes());
+    }
+    hasher.finish()
+}
+
 #[cfg(test)]
 #[path = "../../tests/unit/models/edit_history.rs"]
 mod tests;
@@ -212,6 +212,42 @@ impl EditOp {
 }
 
 impl OpKind {
+    /// 按应用顺序列出本操作的字符区间编辑 `(start, end, inserted)`：先删 `start..end`
+    /// 再在 `start` 处插入 `inserted`。
+    pub fn char_edits(&self) -> Vec<(usize, usize, &str)> {
+        match self {
+            OpKind::Insert { char_offset, text } => vec![(*char_offset, *char_offset, text)],
+            OpKind::Delete { start, end, .. } => vec![(*start, *end, "")],
+            OpKind::Replace {
+                start,
+                end,
+                inserted,
+                ..
+            } => vec![(*start, *end, inserted)],
+            OpKind::Batch { edits } => edits
+                .iter()
+                .map(|edit| (edit.start, edit.end, edit.inserted.as_str()))
+                .collect(),
+        }
+    }
+
+    /// 本操作造成的文本字节长度变化。
+    pub fn byte_delta(&self) -> isize {
+        let delta =
+            |deleted: &str, inserted: &str| inserted.len() as isize - deleted.len() as isize;
+        match self {
+            OpKind::Insert { text, .. } => delta("", text),
+            OpKind::Delete { deleted, .. } => delta(deleted, ""),
+            OpKind::Replace {
+                deleted, inserted, ..
+            } => delta(deleted, inserted),
+            OpKind::Batch { edits } => edits
+                .iter()
+                .map(|edit| delta(&edit.deleted, &edit.inserted))
+                .sum(),
+        }
+    }
+
     pub fn apply(&self, rope: &mut ropey::Rope) {
         match self {
             OpKind::Insert { char_offset, text } => {
@@ -4,7 +4,7 @@ use rustc_hash::FxHashSet;
 use slotmap::{new_key_type, SlotMap};
 use std::{
     collections::{BTreeMap, HashMap},
-    ffi::OsString,
+    ffi::{OsStr, OsString},
     fmt, io,
     path::{Path, PathBuf},
 };
@@ -65,6 +65,8 @@ struct Node {
     parent: Option<NodeId>,
     children: Option<BTreeMap<OsString, NodeId>>,
     load_state: LoadState,
+    /// 文件大小（字节），随目录列表读入；目录与未知时为 `None`。
+    size: Option<u64>,
 }
 
 impl Node {
@@ -75,6 +77,7 @@ impl Node {
             parent,
             children: None,
             load_state: LoadState::Loaded,
+            size: None,
         }
     }
 
@@ -85,6 +88,7 @@ impl Node {
             parent,
             children: Some(BTreeMap::new()),
             load_state,
+            size: None,
         }
     }
 }
@@ -97,6 +101,9 @@ pub struct FileTree {
     absolute_root: PathBuf,
     path_cache: HashMap<NodeId, PathBuf>,
     id_by_path: HashMap<PathBuf, NodeId>,
+    /// 额外工作区文件夹：挂在 root 下的顶层目录节点 -> 它在磁盘上的绝对路径。
+    /// 挂载点以下的路径从挂载路径起算，而不是 `absolute_root`。
+    mounts: HashMap<NodeId, PathBuf>,
 }
 
 impl FileTree {
@@ -115,6 +122,7 @@ impl FileTree {
             absolute_root,
             path_cache: HashMap::new(),
             id_by_path: HashMap::new(),
+            mounts: HashMap::new(),
         }
     }
 
@@ -195,6 +203,24 @@ impl FileTree {
         Ok(id)
     }
 
+    /// 按名字更新 `parent` 下文件的大小；重新读目录时已存在的条目也走这里刷新。
+    pub fn set_child_file_size(&mut self, parent: NodeId, name: &OsStr, size: Option<u64>) {
+        let Some(id) = self
+            .arena
+            .get(parent)
+            .and_then(|node| node.children.as_ref())
+            .and_then(|children| children.get(name))
+            .copied()
+        else {
+            return;
+        };
+        if let Some(node) = self.arena.get_mut(id) {
+            if node.kind == NodeKind::File {
+                node.size = size;
+            }
+        }
+    }
+
     pub fn full_path(&mut self, id: NodeId) -> PathBuf {
         if id == self.root {
             self.id_by_path
@@ -206,11 +232,13 @@ impl FileTree {
             return cached_path.clone();
         }
 
-        let mut path = self.absolute_root.clone();
         let mut current = id;
         let mut components = vec![];
 
         while let Some(node) = self.arena.get(current) {
+            if self.mounts.contains_key(&current) {
+                break;
+            }
             if let Some(parent) = node.parent {
                 components.push(node.name.as_os_str());
                 current = parent;
@@ -219,6 +247,11 @@ impl FileTree {
             }
         }
 
+        let mut path = self
+            .mounts
+            .get(&current)
+            .unwrap_or(&self.absolute_root)
+            .clone();
         for comp in components.iter().rev() {
             path.push(comp);
         }
@@ -238,14 +271,18 @@ impl FileTree {
 
         let mut current = id;
         let mut components: Vec<OsString> = Vec::new();
-        while current != self.root {
+        while current != self.root && !self.mounts.contains_key(&current) {
             let node = self.arena.get(current)?;
             let parent = node.parent?;
             components.push(node.name.clone());
             current = parent;
         }
 
-        let mut path = self.absolute_root.clone();
+        let mut path = self
+            .mounts
+            .get(&current)
+            .unwrap_or(&self.absolute_root)
+            .clone();
         for comp in components.iter().rev() {
             path.push(comp);
         }
@@ -398,6 +435,7 @@ impl FileTree {
             }
 
             self.expanded.remove(&id);
+            self.mounts.remove(&id);
             if let Some(path) = self.path_cache.remove(&id) {
                 self.id_by_path.remove(&path);
             }
@@ -461,6 +499,7 @@ pub struct FileTreeRow {
     pub is_dir: bool,
     pub is_expanded: bool,
     pub load_state: LoadState,
+    pub size: Option<u64>,
 }
 
 impl FileTree {
@@ -480,6 +519,7 @@ impl FileTree {
                         is_dir: node.kind == NodeKind::Dir,
                         is_expanded: self.expanded.contains(&id),
                         load_state: node.load_state,
+                        size: node.size,
                     });
                 }
             }
@@ -525,14 +565,7 @@ impl FileTree {
             return Some(id);
         }
 
-        let relative = path.strip_prefix(&self.absolute_root).ok()?;
-        let mut current = self.root;
-
-        for component in relative.components() {
-            let name = component.as_os_str();
-            let children = self.arena.get(current)?.children.as_ref()?;
-            current = *children.get(name)?;
-        }
+        let current = self.walk_path(path)?;
 
         self.path_cache.insert(current, path.to_path_buf());
         self.id_by_path.insert(path.to_path_buf(), current);
@@ -548,17 +581,56 @@ impl FileTree {
             return Some(id);
         }
 
-        let relative = path.strip_prefix(&self.absolute_root).ok()?;
-        let mut current = self.root;
+        self.walk_path(path)
+    }
+
+    /// 从包含 `path` 的最深挂载点（没有则从 root）开始，按路径分量逐级查找子节点。
+    fn walk_path(&self, path: &Path) -> Option<NodeId> {
+        let (mut current, relative) = self
+            .mounts
+            .iter()
+            .filter_map(|(&id, mount)| Some((id, path.strip_prefix(mount).ok()?)))
+            .min_by_key(|(_, relative)| relative.components().count())
+            .or_else(|| Some((self.root, path.strip_prefix(&self.absolute_root).ok()?)))?;
 
         for component in relative.components() {
             let name = component.as_os_str();
             let children = self.arena.get(current)?.children.as_ref()?;
             current = *children.get(name)?;
+            // 挂载点只能经由它自己的路径到达，不属于 `absolute_root` 下的同名目录。
+            if self.mounts.contains_key(&current) {
+                return None;
+            }
         }
 
         Some(current)
     }
+
+    /// 把另一个目录作为顶层条目挂到 root 下（多根工作区）。条目名取目录名，
+    /// 与 root 下已有条目重名时返回 `NameExists`。
+    pub fn add_mount(&mut self, path: PathBuf) -> Result<NodeId, FileTreeError> {
+        if let Some((&id, _)) = self.mounts.iter().find(|(_, mount)| **mount == path) {
+            return Ok(id);
+        }
+        let name = path
+            .file_name()
+            .map(OsString::from)
+            .unwrap_or_else(|| path.as_os_str().to_os_string());
+        let id =
+            self.insert_child_with_state(self.root, name, NodeKind::Dir, LoadState::NotLoaded)?;
+        self.mounts.insert(id, path);
+        Ok(id)
+    }
+
+    pub fn remove_mount(&mut self, path: &Path) -> Result<(), FileTreeError> {
+        let id = self
+            .mounts
+            .iter()
+            .find(|(_, mount)| mount.as_path() == path)
+            .map(|(&id, _)| id)
+            .ok_or(FileTreeError::InvalidNodeId)?;
+        self.delete(id)
+    }
 }
 
 pub fn should_ignore(name: &str) -> bool {
@@ -576,7 +648,7 @@ pub fn should_ignore(name: &str) -> bool {
     )
 }
 
-fn load_dir_entries(path: &Path) -> io::Result<Vec<(OsString, bool)>> {
+fn load_dir_entries(path: &Path) -> io::Result<Vec<(OsString, bool, Option<u64>)>> {
     let mut entries = Vec::new();
     for entry in std::fs::read_dir(path)? {
         let entry = entry?;
@@ -585,7 +657,12 @@ fn load_dir_entries(path: &Path) -> io::Result<Vec<(OsString, bool)>> {
             continue;
         }
         let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
-        entries.push((name, is_dir));
+        let size = if is_dir {
+            None
+        } else {
+            entry.metadata().ok().map(|meta| meta.len())
+        };
+        entries.push((name, is_dir, size));
     }
     Ok(entries)
 }
@@ -604,18 +681,37 @@ pub fn build_file_tree(root_path: &Path) -> io::Result<FileTree> {
     let mut tree = FileTree::new_with_root(root_name, absolute_root.clone());
 
     let entries = load_dir_entries(&absolute_root)?;
-    for (name, is_dir) in entries {
+    for (name, is_dir, size) in entries {
         let kind = if is_dir {
             NodeKind::Dir
         } else {
             NodeKind::File
         };
-        let _ = tree.insert_child(tree.root, name, kind);
+        let root = tree.root;
+        if tree.insert_child(root, name.clone(), kind).is_ok() {
+            tree.set_child_file_size(root, &name, size);
+        }
     }
 
     Ok(tree)
 }
 
+/// 人类可读的文件大小（1024 进制，保留一位小数；整数时省略小数）。
+pub fn format_file_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 || value.fract() < 0.05 {
+        format!("{:.0} {}", value, UNITS[unit])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
 #[cfg(test)]
 #[path = "../../tests/unit/models/file_tree.rs"]
 mod tests;
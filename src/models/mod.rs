@@ -8,11 +8,14 @@ pub mod selection;
 pub mod text_buffer;
 
 pub use cursor_set::SecondaryCursor;
-pub use edit_history::{EditHistory, UndoResult};
+pub use edit_history::{DirtyRange, EditHistory, HistoryBackup, UndoResult};
 pub use edit_op::{EditOp, OpId, OpKind};
 pub use file_tree::{
-    build_file_tree, should_ignore, FileTree, FileTreeError, FileTreeRow, LoadState, NodeId,
-    NodeKind,
+    build_file_tree, format_file_size, should_ignore, FileTree, FileTreeError, FileTreeRow,
+    LoadState, NodeId, NodeKind,
 };
 pub use selection::{Granularity, Selection};
-pub use text_buffer::{slice_to_cow, TextBuffer};
+pub use text_buffer::{
+    detect_line_endings, is_word_char, normalize_line_endings, scan_line_endings, slice_to_cow,
+    stray_line_endings, word_range_at_byte, LineEndingStyle, TextBuffer,
+};
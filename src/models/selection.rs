@@ -1,9 +1,8 @@
 //! 选区模型：支持字符/单词/整行三种粒度
 
-use super::text_buffer::slice_to_cow;
+use super::text_buffer::{is_word_char, slice_to_cow};
 use ropey::Rope;
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_xid::UnicodeXID;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Granularity {
@@ -110,7 +109,7 @@ impl Selection {
             if let Some(c) = chars.next() {
                 if c.is_whitespace() {
                     CharType::Whitespace
-                } else if c.is_xid_start() || c.is_xid_continue() || c == '_' {
+                } else if is_word_char(c) {
                     CharType::Identifier
                 } else {
                     CharType::Other
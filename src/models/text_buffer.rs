@@ -4,13 +4,18 @@
 //! - 文本存储（Rope）
 //! - 光标和选区管理
 //! - 行列 ↔ 字符偏移映射
+//! - 换行风格检测与归一
 
 use super::edit_op::{EditOp, OpId};
 use super::selection::Selection;
 use compact_str::CompactString;
 use ropey::{Rope, RopeSlice};
+use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::ops::Range;
 use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+use unicode_xid::UnicodeXID;
 
 /// 从 RopeSlice 获取字符串，优先零拷贝
 pub fn slice_to_cow(slice: RopeSlice<'_>) -> Cow<'_, str> {
@@ -20,6 +25,155 @@ pub fn slice_to_cow(slice: RopeSlice<'_>) -> Cow<'_, str> {
     }
 }
 
+/// 单词字符：Unicode 标识符字符（`XID_Continue`，含 CJK）或 `_`。
+/// 标点、空白、emoji 都不算，双击选词、补全前缀、标识符查找统一按这个划界。
+pub fn is_word_char(ch: char) -> bool {
+    ch == '_' || UnicodeXID::is_xid_continue(ch)
+}
+
+/// `byte` 所在单词的字节范围。`byte` 落在单词内或紧贴单词末尾（光标停在词后）都算；
+/// 两侧都不是单词字符时返回 `None`。`byte` 不在字符边界上时按所在字符处理。
+pub fn word_range_at_byte(rope: &Rope, byte: usize) -> Option<Range<usize>> {
+    let byte = byte.min(rope.len_bytes());
+    let cursor = rope.byte_to_char(byte);
+    let len_chars = rope.len_chars();
+
+    let mut start = cursor;
+    while start > 0 && is_word_char(rope.char(start - 1)) {
+        start -= 1;
+    }
+    let mut end = cursor;
+    while end < len_chars && is_word_char(rope.char(end)) {
+        end += 1;
+    }
+
+    (start < end).then(|| rope.char_to_byte(start)..rope.char_to_byte(end))
+}
+
+/// 换行风格判断只看文件开头这么多字节。
+const LINE_ENDING_DETECT_BYTES: usize = 1000;
+
+/// 文件的换行风格；`Mixed` 表示开头一段里出现了不止一种换行。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEndingStyle {
+    #[default]
+    Lf,
+    CrLf,
+    Cr,
+    Mixed,
+}
+
+impl LineEndingStyle {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Lf => "LF",
+            Self::CrLf => "CRLF",
+            Self::Cr => "CR",
+            Self::Mixed => "Mixed",
+        }
+    }
+
+    /// 写盘时用的换行符；`Mixed` 没有统一的换行符。
+    pub fn as_str(self) -> Option<&'static str> {
+        match self {
+            Self::Lf => Some("\n"),
+            Self::CrLf => Some("\r\n"),
+            Self::Cr => Some("\r"),
+            Self::Mixed => None,
+        }
+    }
+}
+
+/// 按前 1000 字节判断换行风格；一个换行都没有时为 `None`，由调用方决定默认值。
+pub fn scan_line_endings(rope: &Rope) -> Option<LineEndingStyle> {
+    let mut found: Option<LineEndingStyle> = None;
+    // 多取一个字节，免得把截在边界上的 `\r\n` 当成单独的 `\r`。
+    let mut bytes = rope
+        .bytes()
+        .take(LINE_ENDING_DETECT_BYTES + 1)
+        .enumerate()
+        .peekable();
+    while let Some((i, b)) = bytes.next() {
+        if i >= LINE_ENDING_DETECT_BYTES {
+            break;
+        }
+        let style = match b {
+            b'\n' => LineEndingStyle::Lf,
+            b'\r' if bytes.next_if(|&(_, next)| next == b'\n').is_some() => LineEndingStyle::CrLf,
+            b'\r' => LineEndingStyle::Cr,
+            _ => continue,
+        };
+        match found {
+            None => found = Some(style),
+            Some(prev) if prev != style => return Some(LineEndingStyle::Mixed),
+            Some(_) => {}
+        }
+    }
+    found
+}
+
+/// 按前 1000 字节判断换行风格；没有换行时按 `Lf` 算。
+pub fn detect_line_endings(rope: &Rope) -> LineEndingStyle {
+    scan_line_endings(rope).unwrap_or_default()
+}
+
+/// 把所有换行统一成 `target`；返回是否有改动。`target` 为 `Mixed` 时不动。
+/// 只替换不一致的那些换行，不重建整个 rope。
+pub fn normalize_line_endings(rope: &mut Rope, target: LineEndingStyle) -> bool {
+    let Some(newline) = target.as_str() else {
+        return false;
+    };
+    let stray: Vec<_> = stray_line_endings(rope, 0..rope.len_chars(), target).collect();
+    for range in stray.iter().rev() {
+        rope.remove(range.clone());
+        rope.insert(range.start, newline);
+    }
+    !stray.is_empty()
+}
+
+/// `char_range` 里与 `target` 不同的换行（字符区间，升序）；跨在区间边界上的 `\r\n` 整个算进来。
+/// `target` 为 `Mixed` 时没有统一的换行，什么也不给。
+pub fn stray_line_endings(
+    rope: &Rope,
+    char_range: Range<usize>,
+    target: LineEndingStyle,
+) -> impl Iterator<Item = Range<usize>> + '_ {
+    let len = rope.len_chars();
+    let mut start = char_range.start.min(len);
+    let mut end = char_range.end.clamp(start, len);
+    if start > 0 && start < len && rope.char(start - 1) == '\r' && rope.char(start) == '\n' {
+        start -= 1;
+    }
+    if end > 0 && end < len && rope.char(end - 1) == '\r' && rope.char(end) == '\n' {
+        end += 1;
+    }
+
+    let newline = target.as_str();
+    let mut chars = rope.slice(start..end).chars().peekable();
+    let mut pos = start;
+    std::iter::from_fn(move || {
+        let newline = newline?;
+        while let Some(ch) = chars.next() {
+            let at = pos;
+            pos += 1;
+            let ending = match ch {
+                '\n' => "\n",
+                '\r' if chars.next_if_eq(&'\n').is_some() => {
+                    pos += 1;
+                    "\r\n"
+                }
+                '\r' => "\r",
+                _ => continue,
+            };
+            if ending != newline {
+                return Some(at..pos);
+            }
+        }
+        None
+    })
+}
+
 fn slice_to_compact(slice: RopeSlice<'_>) -> CompactString {
     match slice.as_str() {
         Some(s) => CompactString::new(s),
@@ -241,6 +395,11 @@ impl TextBuffer {
         (row, col_graphemes)
     }
 
+    /// 见 [`word_range_at_byte`]。
+    pub fn word_range_at_byte(&self, byte: usize) -> Option<Range<usize>> {
+        word_range_at_byte(&self.rope, byte)
+    }
+
     pub fn line_grapheme_len(&self, row: usize) -> usize {
         let slice = self.rope.line(row);
         if slice.len_bytes() == slice.len_chars() {
@@ -254,6 +413,21 @@ impl TextBuffer {
         without_newline.graphemes(true).count()
     }
 
+    /// 字符串的显示宽度：逐 grapheme 累加 [`TextBuffer::display_width_of_grapheme`]，不展开 Tab。
+    pub fn display_width_of_str(s: &str) -> usize {
+        s.graphemes(true).map(Self::display_width_of_grapheme).sum()
+    }
+
+    /// 单个 grapheme 的显示宽度。emoji 序列（ZWJ、VS16、肤色、国旗、keycap）
+    /// 在终端里总是占两格，`unicode_width` 对其中一部分会给出 1，这里统一按 2 计。
+    pub fn display_width_of_grapheme(g: &str) -> usize {
+        if is_emoji_sequence(g) {
+            2
+        } else {
+            UnicodeWidthStr::width(g)
+        }
+    }
+
     // ==================== 原子操作方法（返回 EditOp）====================
 
     /// 插入字符，返回 EditOp
@@ -294,6 +468,19 @@ impl TextBuffer {
         )
     }
 
+    /// 在文末追加文本（大文件分块读入），光标不动；返回的 EditOp 只用于增量更新语法树，不进编辑历史。
+    pub fn append_str_op(&mut self, s: &str) -> EditOp {
+        let char_offset = self.rope.len_chars();
+        self.rope.insert(char_offset, s);
+        EditOp::insert(
+            OpId::root(),
+            char_offset,
+            CompactString::new(s),
+            self.cursor,
+            self.cursor,
+        )
+    }
+
     pub fn insert_str_op_with_cursor_after_char_offset(
         &mut self,
         s: &str,
@@ -317,6 +504,29 @@ impl TextBuffer {
         )
     }
 
+    /// 在 `char_offset` 处插入字符串，光标移到 `cursor_after`；返回 EditOp
+    pub fn insert_str_at_op(
+        &mut self,
+        char_offset: usize,
+        s: &str,
+        cursor_after: (usize, usize),
+        parent: OpId,
+    ) -> EditOp {
+        let cursor_before = self.cursor;
+
+        self.rope.insert(char_offset, s);
+        self.cursor = cursor_after;
+        self.invalidate_char_pos_cache();
+
+        EditOp::insert(
+            parent,
+            char_offset,
+            CompactString::new(s),
+            cursor_before,
+            cursor_after,
+        )
+    }
+
     /// 向后删除（Backspace），返回 EditOp
     pub fn delete_backward_op(&mut self, parent: OpId) -> Option<EditOp> {
         let (row, col) = self.cursor;
@@ -583,6 +793,33 @@ fn ascii_line_len_no_newline(slice: RopeSlice<'_>) -> usize {
     len
 }
 
+/// 多码位且以图形符号开头、带 emoji 连接/修饰码位的 grapheme；keycap（`1️⃣`）的基字符是 ASCII，单独判断。
+fn is_emoji_sequence(g: &str) -> bool {
+    let mut chars = g.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    let rest = chars.as_str();
+    if rest.is_empty() {
+        return false;
+    }
+    if rest.contains('\u{20E3}') {
+        return true;
+    }
+    let pictographic = matches!(
+        first,
+        '\u{00A9}' | '\u{00AE}' | '\u{2190}'..='\u{2BFF}' | '\u{3030}' | '\u{303D}' | '\u{3297}'
+            | '\u{3299}' | '\u{1F000}'..='\u{1FAFF}'
+    );
+    pictographic
+        && rest.chars().any(|c| {
+            matches!(
+                c,
+                '\u{200D}' | '\u{FE0F}' | '\u{1F1E6}'..='\u{1F1FF}' | '\u{1F3FB}'..='\u{1F3FF}'
+            )
+        })
+}
+
 impl Default for TextBuffer {
     fn default() -> Self {
         Self::new()
@@ -5,5 +5,6 @@
 
 pub mod crossterm;
 pub mod osc52;
+pub mod shell;
 pub mod terminal_guard;
 pub mod view;
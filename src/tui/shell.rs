@@ -0,0 +1,49 @@
+//! 挂起 TUI、在指定目录里跑一个交互 shell（`Command::OpenTerminalAtCurrentFilePath`）。
+//!
+//! 编辑器本身没有内嵌终端面板，shell 直接接管当前终端；退出 shell 后由调用方重新进入
+//! alternate screen 并整屏重绘。
+
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, ExitStatus};
+
+/// `$SHELL`（Windows 上是 `%COMSPEC%`），都没有时退回 `/bin/sh` / `cmd.exe`。
+pub fn default_shell() -> OsString {
+    let var = if cfg!(windows) { "COMSPEC" } else { "SHELL" };
+    std::env::var_os(var)
+        .filter(|shell| !shell.is_empty())
+        .unwrap_or_else(|| {
+            if cfg!(windows) {
+                OsString::from("cmd.exe")
+            } else {
+                OsString::from("/bin/sh")
+            }
+        })
+}
+
+/// 终端窗口 / 标签页标题，带上 shell 的工作目录。
+pub fn shell_title(cwd: &Path) -> String {
+    format!("zcode: {}", cwd.display())
+}
+
+/// 在 `cwd` 里阻塞地跑交互 shell，继承当前终端的 stdin/stdout/stderr。
+/// 调用前终端须已退出 raw mode 与 alternate screen。
+pub fn run_shell(cwd: &Path) -> io::Result<ExitStatus> {
+    let mut stdout = io::stdout();
+    crossterm::execute!(stdout, crossterm::terminal::SetTitle(shell_title(cwd)))?;
+    writeln!(
+        stdout,
+        "zcode: shell in {} (exit to return to the editor)",
+        cwd.display()
+    )?;
+    stdout.flush()?;
+
+    let status = Command::new(default_shell()).current_dir(cwd).status();
+    let _ = crossterm::execute!(io::stdout(), crossterm::terminal::SetTitle("zcode"));
+    status
+}
+
+#[cfg(test)]
+#[path = "../../tests/unit/tui/shell.rs"]
+mod tests;
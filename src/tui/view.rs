@@ -18,7 +18,14 @@ pub enum EventResult {
     Consumed,
     Ignored,
     Quit,
-    Restart { path: PathBuf, hard: bool },
+    Restart {
+        path: PathBuf,
+        hard: bool,
+    },
+    /// 挂起界面跑交互 shell；`cwd` 为 `None` 时用工作区根目录。
+    Shell {
+        cwd: Option<PathBuf>,
+    },
 }
 
 impl EventResult {
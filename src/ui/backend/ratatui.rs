@@ -1,3 +1,4 @@
+use crate::models::TextBuffer;
 use crate::ui::backend::Backend;
 use crate::ui::core::geom::{Pos, Rect};
 use crate::ui::core::painter::{BorderKind, PaintCmd};
@@ -11,7 +12,6 @@ use ratatui::Frame;
 use ratatui::Terminal;
 use std::io;
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::UnicodeWidthStr;
 
 pub struct RatatuiBackend<'a, 'f> {
     frame: &'a mut Frame<'f>,
@@ -87,6 +87,11 @@ impl RatatuiTerminal {
         })?;
         Ok(())
     }
+
+    /// 丢掉前后帧缓冲并清屏，下一次 `draw` 整屏重绘（例如从挂起的 shell 回来后）。
+    pub fn clear(&mut self) -> io::Result<()> {
+        self.terminal.clear()
+    }
 }
 
 struct PaintWidget<'a> {
@@ -214,7 +219,7 @@ fn draw_text(buf: &mut Buffer, pos: Pos, text: &str, style: Style, clip: Option<
         return;
     }
     for g in text.graphemes(true) {
-        let w = UnicodeWidthStr::width(g) as u16;
+        let w = TextBuffer::display_width_of_grapheme(g) as u16;
         if w == 0 {
             continue;
         }
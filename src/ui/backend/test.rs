@@ -1,11 +1,11 @@
 //! Headless backend for tests and benchmarks.
 
+use crate::models::TextBuffer;
 use crate::ui::backend::Backend;
 use crate::ui::core::geom::{Pos, Rect};
 use crate::ui::core::painter::{BorderKind, PaintCmd};
 use crate::ui::core::style::Style;
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::UnicodeWidthStr;
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Cell {
@@ -196,7 +196,7 @@ fn draw_text(buf: &mut TestBuffer, pos: Pos, text: &str, style: Style, clip: Opt
         return;
     }
     for g in text.graphemes(true) {
-        let w = UnicodeWidthStr::width(g) as u16;
+        let w = TextBuffer::display_width_of_grapheme(g) as u16;
         if w == 0 {
             continue;
         }
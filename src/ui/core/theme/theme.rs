@@ -10,6 +10,8 @@ pub struct Theme {
     pub syntax_colors: [Color; SyntaxColorGroup::COUNT],
     pub error_fg: Color,
     pub warning_fg: Color,
+    /// 提示 / 信息级诊断的下划线颜色。
+    pub hint_fg: Color,
     pub header_fg: Color,
     pub palette_fg: Color,
     pub palette_selected_bg: Color,
@@ -20,8 +22,10 @@ pub struct Theme {
     pub sidebar_bg: Color,
     pub popup_bg: Color,
     pub statusbar_bg: Color,
-    /// 状态栏模式块底色 + 块上文字色（暂只用 INSERT；模态编辑落地后补其余）。
+    /// 状态栏模式块底色（INSERT / NORMAL / VISUAL）+ 块上文字色。
     pub mode_insert_bg: Color,
+    pub mode_normal_bg: Color,
+    pub mode_visual_bg: Color,
     pub mode_text_fg: Color,
     pub md_heading1_fg: Color,
     pub md_heading2_fg: Color,
@@ -40,6 +44,20 @@ pub struct Theme {
     pub search_current_match_bg: Color,
     /// 光标所贴括号与其配对括号的前景色（加粗渲染）。
     pub bracket_match_fg: Color,
+    /// 配对括号格的背景，窄字符上只靠前景色不够显眼。
+    pub bracket_match_bg: Color,
+    /// 补全条目右对齐的 `labelDetails.detail`（签名 / 返回类型）。
+    pub completion_detail_fg: Color,
+    /// 补全条目标签下方的 `labelDetails.description`（模块路径等）。
+    pub completion_description_fg: Color,
+    /// 行号旁未保存改动标记：新增 / 修改 / 删除。
+    pub diff_added_fg: Color,
+    pub diff_modified_fg: Color,
+    pub diff_deleted_fg: Color,
+    /// 标签栏下方面包屑的文字色。
+    pub breadcrumb_fg: Color,
+    /// 编辑区列标尺（`ruler_columns`）的竖线色。
+    pub ruler_fg: Color,
 }
 
 impl Theme {
@@ -60,9 +78,6 @@ impl Default for Theme {
             syntax_colors[group as usize] = Color::Rgb(r, g, b);
         }
 
-        syntax_colors[SyntaxColorGroup::Operator as usize] = palette_fg;
-        syntax_colors[SyntaxColorGroup::Tag as usize] =
-            syntax_colors[SyntaxColorGroup::Keyword as usize];
         Self {
             focus_border: Color::Indexed(6), // Cyan
             separator: Color::Indexed(8),    // DarkGray
@@ -70,6 +85,7 @@ impl Default for Theme {
             syntax_colors,
             error_fg: Color::Indexed(1),   // Red
             warning_fg: Color::Indexed(3), // Yellow
+            hint_fg: Color::Indexed(6),    // Cyan
             header_fg: Color::Indexed(6),  // Cyan
             palette_fg,
             palette_selected_bg: Color::Indexed(8), // DarkGray
@@ -81,6 +97,8 @@ impl Default for Theme {
             popup_bg: Color::Reset,
             statusbar_bg: Color::Reset,
             mode_insert_bg: Color::Indexed(2),             // Green
+            mode_normal_bg: Color::Indexed(4),             // Blue
+            mode_visual_bg: Color::Indexed(5),             // Magenta
             mode_text_fg: Color::Indexed(0),               // Black（彩色块上的字）
             md_heading1_fg: Color::Rgb(0x56, 0x9C, 0xD6),  // Blue
             md_heading2_fg: Color::Rgb(0x4E, 0xC9, 0xB0),  // Teal
@@ -98,6 +116,14 @@ impl Default for Theme {
             search_match_bg: Color::Rgb(0x5A, 0x4A, 0x1E), // Soft amber
             search_current_match_bg: Color::Rgb(0x80, 0x60, 0x10), // Bright amber
             bracket_match_fg: Color::Rgb(0xFF, 0xA5, 0x00), // Bright orange
+            bracket_match_bg: Color::Rgb(0x3A, 0x3D, 0x41), // Slate gray
+            completion_detail_fg: Color::Rgb(0x9C, 0xDC, 0xFE), // Light blue
+            completion_description_fg: Color::Indexed(8),  // DarkGray
+            diff_added_fg: Color::Indexed(2),              // Green
+            diff_modified_fg: Color::Indexed(4),           // Blue
+            diff_deleted_fg: Color::Indexed(1),            // Red
+            breadcrumb_fg: Color::Indexed(8),              // DarkGray
+            ruler_fg: Color::Rgb(0x40, 0x40, 0x40),
         }
     }
 }
@@ -17,6 +17,7 @@ pub fn adapt_theme(theme: &Theme, support: TerminalColorSupport) -> Theme {
             .map(|c| map_color_for_support(c, support)),
         error_fg: map_color_for_support(theme.error_fg, support),
         warning_fg: map_color_for_support(theme.warning_fg, support),
+        hint_fg: map_color_for_support(theme.hint_fg, support),
         header_fg: map_color_for_support(theme.header_fg, support),
         palette_fg: map_color_for_support(theme.palette_fg, support),
         palette_selected_bg: map_color_for_support(theme.palette_selected_bg, support),
@@ -28,6 +29,8 @@ pub fn adapt_theme(theme: &Theme, support: TerminalColorSupport) -> Theme {
         popup_bg: map_color_for_support(theme.popup_bg, support),
         statusbar_bg: map_color_for_support(theme.statusbar_bg, support),
         mode_insert_bg: map_color_for_support(theme.mode_insert_bg, support),
+        mode_normal_bg: map_color_for_support(theme.mode_normal_bg, support),
+        mode_visual_bg: map_color_for_support(theme.mode_visual_bg, support),
         mode_text_fg: map_color_for_support(theme.mode_text_fg, support),
         md_heading1_fg: map_color_for_support(theme.md_heading1_fg, support),
         md_heading2_fg: map_color_for_support(theme.md_heading2_fg, support),
@@ -45,6 +48,14 @@ pub fn adapt_theme(theme: &Theme, support: TerminalColorSupport) -> Theme {
         search_match_bg: map_color_for_support(theme.search_match_bg, support),
         search_current_match_bg: map_color_for_support(theme.search_current_match_bg, support),
         bracket_match_fg: map_color_for_support(theme.bracket_match_fg, support),
+        bracket_match_bg: map_color_for_support(theme.bracket_match_bg, support),
+        completion_detail_fg: map_color_for_support(theme.completion_detail_fg, support),
+        completion_description_fg: map_color_for_support(theme.completion_description_fg, support),
+        diff_added_fg: map_color_for_support(theme.diff_added_fg, support),
+        diff_modified_fg: map_color_for_support(theme.diff_modified_fg, support),
+        diff_deleted_fg: map_color_for_support(theme.diff_deleted_fg, support),
+        breadcrumb_fg: map_color_for_support(theme.breadcrumb_fg, support),
+        ruler_fg: map_color_for_support(theme.ruler_fg, support),
     };
 
     apply_non_truecolor_syntax_palette(&mut adapted, theme, support);
@@ -72,7 +83,8 @@ fn apply_non_truecolor_syntax_palette(
         (SyntaxColorGroup::Variable, 81, 6),
         (SyntaxColorGroup::Constant, 39, 12),
         (SyntaxColorGroup::Regex, 167, 9),
-        (SyntaxColorGroup::Tag, 33, 4),
+        (SyntaxColorGroup::Operator, 188, 7),
+        (SyntaxColorGroup::Tag, 44, 6),
     ];
 
     for &(group, ansi256, ansi16) in FALLBACKS {
@@ -167,7 +179,7 @@ fn rgb_to_ansi16_index(r: u8, g: u8, b: u8) -> u8 {
     best_index
 }
 
-fn ansi256_index_to_rgb(index: u8) -> (u8, u8, u8) {
+pub(crate) fn ansi256_index_to_rgb(index: u8) -> (u8, u8, u8) {
     if index <= 15 {
         return ANSI16_RGB[index as usize];
     }
@@ -0,0 +1,51 @@
+use crate::kernel::editor::EditorTabState;
+use crate::ui::core::geom::Rect;
+use unicode_width::UnicodeWidthStr;
+
+const PADDING_LEFT: u16 = 1;
+pub(super) const SEPARATOR: &str = " › ";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BreadcrumbSlot {
+    pub label: String,
+    pub x: u16,
+    pub width: u16,
+    /// 对应 `tab.breadcrumbs` 的下标；`None` 是打头的文件名段。
+    pub index: Option<usize>,
+}
+
+/// 面包屑各段在 `area` 行内的位置：文件名在前，之后是由外到内的符号；超出右边界的截掉。
+pub fn compute_breadcrumb_slots(area: Rect, tab: &EditorTabState) -> Vec<BreadcrumbSlot> {
+    if area.is_empty() {
+        return Vec::new();
+    }
+
+    let separator_w = SEPARATOR.width() as u16;
+    let right = area.right();
+    let mut x = area.x.saturating_add(PADDING_LEFT);
+    let labels = std::iter::once((tab.title.as_str(), None)).chain(
+        tab.breadcrumbs
+            .iter()
+            .enumerate()
+            .map(|(index, crumb)| (crumb.label.as_str(), Some(index))),
+    );
+
+    let mut slots = Vec::new();
+    for (label, index) in labels {
+        if index.is_some() {
+            x = x.saturating_add(separator_w);
+        }
+        if x >= right {
+            break;
+        }
+        let width = (label.width().min(u16::MAX as usize) as u16).min(right - x);
+        slots.push(BreadcrumbSlot {
+            label: label.to_string(),
+            x,
+            width,
+            index,
+        });
+        x = x.saturating_add(width);
+    }
+    slots
+}
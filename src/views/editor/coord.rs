@@ -3,11 +3,10 @@
 //! All display-coordinate logic lives here, keeping the kernel free of
 //! screen/viewport concerns.
 
-use crate::kernel::editor::{EditorTabState, EditorViewportState};
+use crate::kernel::editor::{display_x_abs, DisplayRow, EditorTabState, EditorViewportState};
 use crate::models::{slice_to_cow, TextBuffer};
 use crate::views::editor::markdown::{self, MarkdownDocument};
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::UnicodeWidthStr;
 
 /// Convert a screen x position to a source column for a regular (non-markdown) line.
 pub fn screen_to_col(
@@ -27,9 +26,13 @@ pub fn screen_to_col(
         return None;
     }
 
+    col_at_display_x(buffer, tab_size, row, viewport.horiz_offset + x as u32)
+}
+
+/// Source column nearest to the absolute display column `target_x` on `row`.
+fn col_at_display_x(buffer: &TextBuffer, tab_size: u8, row: usize, target_x: u32) -> Option<usize> {
     let slice = buffer.line_slice(row)?;
     let line = slice_to_cow(slice);
-    let target_x = viewport.horiz_offset + x as u32;
     let mut display_col = 0u32;
     let mut col = 0usize;
 
@@ -46,7 +49,7 @@ pub fn screen_to_col(
                 tab - rem
             }
         } else {
-            g.width() as u32
+            TextBuffer::display_width_of_grapheme(g) as u32
         };
 
         // 把点击的单元格按其“中心”归边：落在字形左半 → 光标在字形前，右半 → 字形后。
@@ -80,7 +83,7 @@ fn screen_to_col_markdown(
     let mut display_byte: usize = 0;
 
     for g in rendered.text.graphemes(true) {
-        let w = g.width() as u32;
+        let w = TextBuffer::display_width_of_grapheme(g) as u32;
         // 同 screen_to_col：按单元格中心归最近边界，(w-1)/2 消除宽字形的左偏一格。
         if display_col + w.saturating_sub(1) / 2 >= target_x {
             break;
@@ -133,3 +136,54 @@ pub fn resolve_source_col(
         screen_to_col(&tab.viewport, &tab.buffer, tab_size, row, x)
     }
 }
+
+fn display_row_at(tab: &EditorTabState, y: u16) -> Option<DisplayRow> {
+    tab.display_rows_in_viewport(tab.viewport.line_offset, tab.viewport.height.max(1))
+        .get(y as usize)
+        .copied()
+}
+
+/// Map a content-area cell to a source position, following soft-wrapped
+/// display rows when word wrap is on.
+pub fn resolve_source_pos(
+    tab: &EditorTabState,
+    md: Option<&MarkdownDocument>,
+    x: u16,
+    y: u16,
+    tab_size: u8,
+) -> Option<(usize, usize)> {
+    let row = display_row_at(tab, y)?;
+    if row.start_col == 0 && row.end_col.is_none() {
+        let col = resolve_source_col(tab, md, row.line, x, tab_size)?;
+        return Some((row.line, col));
+    }
+    if x as usize >= tab.viewport.width {
+        return None;
+    }
+
+    // 一个折行段：从段首的显示列起算，结果限制在本段内（段尾列属于下一段）。
+    let segment_x = display_x_abs(&tab.buffer, row.line, row.start_col, tab_size);
+    let col = col_at_display_x(&tab.buffer, tab_size, row.line, segment_x + x as u32)?;
+    let col = row
+        .end_col
+        .map_or(col, |end| col.min(end.saturating_sub(1)))
+        .max(row.start_col);
+    Some((row.line, col))
+}
+
+/// Source position at the end of the display row `y`, for drags past the right edge.
+pub fn resolve_row_end(tab: &EditorTabState, y: u16) -> Option<(usize, usize)> {
+    let row = display_row_at(tab, y)?;
+    let col = row
+        .end_col
+        .unwrap_or_else(|| tab.buffer.line_grapheme_len(row.line));
+    Some((row.line, col))
+}
+
+/// Logical line whose first display row is at `y` (continuation rows of a
+/// wrapped line have no gutter).
+pub fn line_starting_at_row(tab: &EditorTabState, y: u16) -> Option<usize> {
+    display_row_at(tab, y)
+        .filter(|row| row.start_col == 0)
+        .map(|row| row.line)
+}
@@ -1,6 +1,7 @@
 use crate::kernel::editor::{EditorPaneState, SearchBarState};
 use crate::ui::core::geom::Pos;
 
+use super::breadcrumbs::compute_breadcrumb_slots;
 use super::layout::{EditorPaneLayout, VerticalScrollbarMetrics};
 use super::search_bar_layout::search_bar_nav_origin;
 use super::tab_row::compute_tab_row_layout;
@@ -24,6 +25,25 @@ pub enum EditorVerticalScrollbarHitResult {
     Track { row: u16 },
 }
 
+/// 点中面包屑行时返回 `Some`，内层是被点中的符号段下标（文件名段与空白处为 `None`）。
+pub fn hit_test_breadcrumbs(
+    layout: &EditorPaneLayout,
+    pane: &EditorPaneState,
+    column: u16,
+    row: u16,
+) -> Option<Option<usize>> {
+    let area = layout.breadcrumb_area?;
+    if !area.contains(Pos::new(column, row)) {
+        return None;
+    }
+    let tab = pane.active_tab()?;
+    let index = compute_breadcrumb_slots(area, tab)
+        .into_iter()
+        .find(|slot| column >= slot.x && column < slot.x.saturating_add(slot.width))
+        .and_then(|slot| slot.index);
+    Some(index)
+}
+
 pub fn hit_test_search_bar(
     layout: &EditorPaneLayout,
     state: &SearchBarState,
@@ -199,6 +219,14 @@ pub fn tab_insertion_x(
     Some(last_end.min(right.saturating_sub(1)).max(area.x))
 }
 
+/// 行号栏（含最右列的折叠标记）内的点击，返回相对行号栏顶部的行偏移。
+pub fn hit_test_editor_gutter(layout: &EditorPaneLayout, column: u16, row: u16) -> Option<u16> {
+    if layout.gutter_area.is_empty() || !layout.gutter_area.contains(Pos::new(column, row)) {
+        return None;
+    }
+    Some(row.saturating_sub(layout.gutter_area.y))
+}
+
 pub fn hit_test_editor_mouse(
     layout: &EditorPaneLayout,
     column: u16,
@@ -2,6 +2,8 @@ use crate::kernel::editor::EditorPaneState;
 use crate::kernel::services::ports::EditorConfig;
 use crate::ui::core::geom::Rect;
 
+use super::minimap::MINIMAP_WIDTH;
+
 /// 编辑器 pane 的外层/内层矩形（纯几何）。单编辑区，`outer`/`inner` 恒等于铺满 `area`。
 /// render 消费 `outer`、interaction 消费 `inner`，保留 Vec 形态以兼容既有消费点。
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -49,15 +51,26 @@ impl VerticalScrollbarMetrics {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HorizontalScrollbarMetrics {
+    pub track_area: Rect,
+    pub thumb_area: Rect,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct EditorPaneLayout {
     pub area: Rect,
     pub tab_area: Rect,
+    /// 标签栏下方的面包屑行，仅在开启 `breadcrumbs_enabled` 且有活动标签页时存在。
+    pub breadcrumb_area: Option<Rect>,
     pub search_area: Option<Rect>,
     pub editor_area: Rect,
     pub gutter_area: Rect,
     pub content_area: Rect,
     pub v_scrollbar_area: Option<Rect>,
+    /// 编辑区下方一行，仅在开启 `horiz_scroll_indicator_enabled` 且不换行时存在。
+    pub h_scrollbar_area: Option<Rect>,
+    pub minimap_area: Option<Rect>,
     pub gutter_width: u16,
 }
 
@@ -72,11 +85,14 @@ pub fn compute_editor_pane_layout(
         return EditorPaneLayout {
             area,
             tab_area: Rect::default(),
+            breadcrumb_area: None,
             search_area: None,
             editor_area: Rect::default(),
             gutter_area: Rect::default(),
             content_area: Rect::default(),
             v_scrollbar_area: None,
+            h_scrollbar_area: None,
+            minimap_area: None,
             gutter_width: 0,
         };
     }
@@ -84,37 +100,62 @@ pub fn compute_editor_pane_layout(
     let tab_height = TAB_HEIGHT.min(area.h);
     let tab_area = Rect::new(area.x, area.y, area.w, tab_height);
 
+    let breadcrumb_height = u16::from(
+        config.breadcrumbs_enabled
+            && pane.active_tab().is_some()
+            && area.h.saturating_sub(tab_height) > 1,
+    );
+    let breadcrumb_area = (breadcrumb_height > 0).then_some(Rect::new(
+        area.x,
+        area.y.saturating_add(tab_height),
+        area.w,
+        breadcrumb_height,
+    ));
+
     let search_h = pane.search_bar.height();
-    let available_after_tabs = area.h.saturating_sub(tab_height);
+    let available_after_tabs = area.h.saturating_sub(tab_height + breadcrumb_height);
     let search_height = search_h.min(available_after_tabs);
 
     let search_area = (search_height > 0).then_some(Rect::new(
         area.x,
-        area.y.saturating_add(tab_height),
+        area.y.saturating_add(tab_height + breadcrumb_height),
         area.w,
         search_height,
     ));
 
-    let chrome_h = tab_height.saturating_add(search_height);
-    let editor_area = Rect::new(
+    let chrome_h = tab_height + breadcrumb_height + search_height;
+    let mut editor_area = Rect::new(
         area.x,
         area.y.saturating_add(chrome_h),
         area.w,
         area.h.saturating_sub(chrome_h),
     );
+    // 常驻一行而不是溢出时才出现，避免横向滚动时视口高度来回变化。
+    let reserve_h_scrollbar = config.horiz_scroll_indicator_enabled
+        && editor_area.h > 1
+        && pane.active_tab().is_some_and(|tab| !tab.viewport.word_wrap);
+    if reserve_h_scrollbar {
+        editor_area.h -= 1;
+    }
 
     let (gutter_width, gutter_area, content_area) = compute_gutter(editor_area, pane, config);
     let (content_area, v_scrollbar_area) =
         compute_vertical_scrollbar(editor_area, content_area, pane);
+    let (content_area, minimap_area) = compute_minimap(content_area, pane, config);
+    let h_scrollbar_area = (reserve_h_scrollbar && !content_area.is_empty())
+        .then(|| Rect::new(content_area.x, editor_area.bottom(), content_area.w, 1));
 
     EditorPaneLayout {
         area,
         tab_area,
+        breadcrumb_area,
         search_area,
         editor_area,
         gutter_area,
         content_area,
         v_scrollbar_area,
+        h_scrollbar_area,
+        minimap_area,
         gutter_width,
     }
 }
@@ -142,8 +183,9 @@ fn compute_gutter(
 
     let total_lines = tab.buffer.len_lines().max(1);
     let digits = total_lines.to_string().len();
-    // 行号位 + 1 列折叠标记。
-    let gutter_width = ((digits + 1) as u16).min(editor_area.w);
+    // 行号位 + 1 列折叠标记（+ 1 列未保存改动标记）。
+    let marks_width = usize::from(config.show_unsaved_gutter_marks);
+    let gutter_width = ((digits + 1 + marks_width) as u16).min(editor_area.w);
 
     if gutter_width == 0 || gutter_width >= editor_area.w {
         return (
@@ -199,6 +241,35 @@ fn compute_vertical_scrollbar(
     (content, Some(scrollbar_area))
 }
 
+/// 在正文右侧（滚动条左侧）切出 minimap；正文剩不到两倍 minimap 宽时不显示。
+fn compute_minimap(
+    content_area: Rect,
+    pane: &EditorPaneState,
+    config: &EditorConfig,
+) -> (Rect, Option<Rect>) {
+    if !config.minimap_enabled
+        || pane.active_tab().is_none()
+        || content_area.w < MINIMAP_WIDTH.saturating_mul(3)
+    {
+        return (content_area, None);
+    }
+
+    let minimap_area = Rect::new(
+        content_area.right() - MINIMAP_WIDTH,
+        content_area.y,
+        MINIMAP_WIDTH,
+        content_area.h,
+    );
+    let content = Rect::new(
+        content_area.x,
+        content_area.y,
+        content_area.w - MINIMAP_WIDTH,
+        content_area.h,
+    );
+
+    (content, Some(minimap_area))
+}
+
 pub fn vertical_scrollbar_metrics(
     layout: &EditorPaneLayout,
     total_lines: usize,
@@ -245,6 +316,37 @@ pub fn vertical_scrollbar_metrics(
     })
 }
 
+/// 滑块宽度按可见列占内容总宽的比例，位置按 `horiz_offset / max_offset` 在轨道内线性换算。
+/// 内容没有超出视口时返回 `None`。
+pub fn horizontal_scrollbar_metrics(
+    layout: &EditorPaneLayout,
+    max_offset: u32,
+    horiz_offset: u32,
+) -> Option<HorizontalScrollbarMetrics> {
+    let track_area = layout.h_scrollbar_area?;
+    if track_area.is_empty() || max_offset == 0 {
+        return None;
+    }
+
+    let viewport_cols = track_area.w as u64;
+    let total_cols = viewport_cols + max_offset as u64;
+    let track_w = track_area.w as u64;
+    let thumb_w = ((track_w * viewport_cols).div_ceil(total_cols))
+        .max(1)
+        .min(track_w) as u16;
+    let thumb_range = track_area.w.saturating_sub(thumb_w) as u64;
+
+    let max_offset = max_offset as u64;
+    let horiz_offset = (horiz_offset as u64).min(max_offset);
+    let left = ((horiz_offset * thumb_range + max_offset / 2) / max_offset) as u16;
+
+    let thumb_area = Rect::new(track_area.x.saturating_add(left), track_area.y, thumb_w, 1);
+    Some(HorizontalScrollbarMetrics {
+        track_area,
+        thumb_area,
+    })
+}
+
 #[cfg(test)]
 #[path = "../../../tests/unit/views/editor/layout.rs"]
 mod tests;
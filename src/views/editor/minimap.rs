@@ -0,0 +1,181 @@
+//! Minimap：编辑区右侧的文档缩略图。
+//!
+//! 每个单元格用半块字符（`▀` / `▄`）画上下两行文档，每列代表 `CHARS_PER_COLUMN` 个显示列；
+//! 墨色取该行覆盖字节最多的语法色组，只读已缓存的高亮，不额外跑 tree-sitter 查询。
+//! 视口所在的行铺一条底色带。
+
+use crate::kernel::editor::{EditorTabState, SyntaxColorGroup};
+use crate::ui::core::geom::{Pos, Rect};
+use crate::ui::core::painter::Painter;
+use crate::ui::core::style::{Color, Style};
+use crate::ui::core::theme::Theme;
+use unicode_width::UnicodeWidthChar;
+
+use super::layout::EditorPaneLayout;
+
+pub const MINIMAP_WIDTH: u16 = 12;
+const CHARS_PER_COLUMN: usize = 4;
+const LINES_PER_ROW: usize = 2;
+
+const UPPER_HALF_BLOCK: &str = "\u{2580}";
+const LOWER_HALF_BLOCK: &str = "\u{2584}";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinimapMetrics {
+    pub area: Rect,
+    /// 第一行单元格上半格对应的文档行。
+    pub first_line: usize,
+    pub total_lines: usize,
+    pub viewport_lines: usize,
+    pub line_offset: usize,
+}
+
+/// 文档装不下 minimap 时，minimap 随视口按比例滑动，保证视口带始终可见。
+pub fn minimap_metrics(
+    layout: &EditorPaneLayout,
+    total_lines: usize,
+    viewport_lines: usize,
+    line_offset: usize,
+) -> Option<MinimapMetrics> {
+    let area = layout.minimap_area?;
+    if area.is_empty() || total_lines == 0 {
+        return None;
+    }
+
+    let capacity = area.h as usize * LINES_PER_ROW;
+    let max_offset = total_lines.saturating_sub(viewport_lines);
+    let first_line = if total_lines <= capacity || max_offset == 0 {
+        0
+    } else {
+        let line_offset = line_offset.min(max_offset) as u128;
+        let range = (total_lines - capacity) as u128;
+        let max_offset = max_offset as u128;
+        ((line_offset * range + max_offset / 2) / max_offset) as usize
+    };
+
+    Some(MinimapMetrics {
+        area,
+        first_line,
+        total_lines,
+        viewport_lines,
+        line_offset,
+    })
+}
+
+impl MinimapMetrics {
+    /// 屏幕行 `row` 上半格对应的文档行。
+    pub fn line_at_row(&self, row: u16) -> usize {
+        let rel = row.saturating_sub(self.area.y) as usize;
+        (self.first_line + rel * LINES_PER_ROW).min(self.total_lines.saturating_sub(1))
+    }
+
+    /// 点击 `row` 后的视口起始行：让被点中的行落在视口中间。
+    pub fn line_offset_for_row(&self, row: u16) -> usize {
+        let max_offset = self.total_lines.saturating_sub(self.viewport_lines);
+        self.line_at_row(row)
+            .saturating_sub(self.viewport_lines / 2)
+            .min(max_offset)
+    }
+
+    fn in_viewport(&self, line: usize) -> bool {
+        line >= self.line_offset && line < self.line_offset.saturating_add(self.viewport_lines)
+    }
+}
+
+/// 一行的缩略墨迹：每列是否有非空白字符，以及该行的主导语法色组。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct LineInk {
+    pub(super) columns: u16,
+    pub(super) group: Option<SyntaxColorGroup>,
+}
+
+pub(super) fn line_ink(tab: &EditorTabState, line: usize, tab_size: u8) -> LineInk {
+    let tab_size = tab_size.max(1) as usize;
+    let limit = MINIMAP_WIDTH as usize * CHARS_PER_COLUMN;
+    let mut columns = 0u16;
+    let mut x = 0usize;
+    for ch in tab.buffer.rope().line(line).chars() {
+        if x >= limit || ch == '\n' || ch == '\r' {
+            break;
+        }
+        if ch == '\t' {
+            x += tab_size - x % tab_size;
+            continue;
+        }
+        if !ch.is_whitespace() {
+            columns |= 1 << (x / CHARS_PER_COLUMN);
+        }
+        x += UnicodeWidthChar::width(ch).unwrap_or(0).max(1);
+    }
+
+    let group = tab.cached_highlight_line(line).and_then(|spans| {
+        let mut weights = [0usize; SyntaxColorGroup::COUNT];
+        for span in spans {
+            weights[span.kind.color_group() as usize] += span.end.saturating_sub(span.start);
+        }
+        SyntaxColorGroup::CONFIGURABLE
+            .iter()
+            .copied()
+            .filter(|group| weights[*group as usize] > 0)
+            .max_by_key(|group| weights[*group as usize])
+    });
+
+    LineInk { columns, group }
+}
+
+pub(super) fn paint_minimap(
+    painter: &mut Painter,
+    metrics: &MinimapMetrics,
+    tab: &EditorTabState,
+    tab_size: u8,
+    theme: &Theme,
+) {
+    let area = metrics.area;
+    painter.fill_rect(area, Style::default().bg(theme.editor_bg));
+
+    let half = |line: usize| -> Option<(LineInk, Color, Color)> {
+        if line >= metrics.total_lines {
+            return None;
+        }
+        let ink = line_ink(tab, line, tab_size);
+        let fg = ink
+            .group
+            .map_or(theme.palette_fg, |group| theme.syntax_fg(group));
+        let bg = if metrics.in_viewport(line) {
+            theme.palette_selected_bg
+        } else {
+            theme.editor_bg
+        };
+        Some((ink, fg, bg))
+    };
+
+    for row in 0..area.h {
+        let top_line = metrics.first_line + row as usize * LINES_PER_ROW;
+        let Some((top_ink, top_fg, top_bg)) = half(top_line) else {
+            break;
+        };
+        let bottom = half(top_line + 1);
+        let bottom_bg = bottom.map_or(theme.editor_bg, |(_, _, bg)| bg);
+
+        for col in 0..area.w {
+            let bit = 1u16 << col;
+            let top = (top_ink.columns & bit != 0).then_some(top_fg);
+            let bottom_ink = bottom
+                .filter(|(ink, _, _)| ink.columns & bit != 0)
+                .map(|(_, fg, _)| fg);
+            let (symbol, style) = match (top, bottom_ink) {
+                (Some(top), Some(bottom)) => {
+                    (UPPER_HALF_BLOCK, Style::default().fg(top).bg(bottom))
+                }
+                (Some(top), None) => (UPPER_HALF_BLOCK, Style::default().fg(top).bg(bottom_bg)),
+                (None, Some(bottom)) => (LOWER_HALF_BLOCK, Style::default().fg(bottom).bg(top_bg)),
+                (None, None) => (UPPER_HALF_BLOCK, Style::default().fg(top_bg).bg(bottom_bg)),
+            };
+            painter.text(Pos::new(area.x + col, area.y + row), symbol, style);
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "../../../tests/unit/views/editor/minimap.rs"]
+mod tests;
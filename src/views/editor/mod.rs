@@ -1,24 +1,28 @@
 //! Editor UI: pure render + hit-test over kernel state.
 
+mod breadcrumbs;
 pub mod coord;
 mod hit_test;
 mod layout;
 pub mod markdown;
 pub mod markdown_cache;
+mod minimap;
 mod render;
 mod search_bar_layout;
 mod tab_row;
 
 pub use hit_test::{
-    hit_test_editor_mouse, hit_test_editor_mouse_drag, hit_test_editor_tab,
-    hit_test_editor_vertical_scrollbar, hit_test_search_bar, hit_test_tab_hover,
-    tab_insertion_index, tab_insertion_x, DragHitResult, EditorVerticalScrollbarHitResult,
-    SearchBarHitResult, TabHitResult,
+    hit_test_breadcrumbs, hit_test_editor_gutter, hit_test_editor_mouse,
+    hit_test_editor_mouse_drag, hit_test_editor_tab, hit_test_editor_vertical_scrollbar,
+    hit_test_search_bar, hit_test_tab_hover, tab_insertion_index, tab_insertion_x, DragHitResult,
+    EditorVerticalScrollbarHitResult, SearchBarHitResult, TabHitResult,
 };
 pub use layout::{
-    compute_editor_pane_layout, compute_pane_rects, vertical_scrollbar_metrics, EditorPaneLayout,
-    PaneRects, VerticalScrollbarMetrics,
+    compute_editor_pane_layout, compute_pane_rects, horizontal_scrollbar_metrics,
+    vertical_scrollbar_metrics, EditorPaneLayout, HorizontalScrollbarMetrics, PaneRects,
+    VerticalScrollbarMetrics,
 };
+pub use minimap::{minimap_metrics, MinimapMetrics};
 pub use render::{
     cursor_position_editor, paint_editor_pane, EditorPaneRenderOptions, TransientRowHighlight,
 };
@@ -1,10 +1,12 @@
 use crate::core::text_window;
 use crate::kernel::editor::{
-    cursor_display_x_abs, EditorPaneState, EditorTabState, HighlightKind, HighlightSpan,
-    SearchBarField, SearchBarMode, SearchBarState,
+    cursor_display_x_abs, display_x_abs, DisplayRow, EditorPaneState, EditorTabState,
+    GutterMarkKind, HighlightKind, HighlightSpan, SearchBarField, SearchBarMode, SearchBarState,
 };
-use crate::kernel::services::ports::{EditorConfig, Match};
-use crate::models::{cursor_set, slice_to_cow};
+use crate::kernel::services::ports::lsp::{line_len_chars, lsp_col_to_char_offset_in_line};
+use crate::kernel::services::ports::{EditorConfig, LspPositionEncoding, Match};
+use crate::kernel::{ProblemItem, ProblemSeverity};
+use crate::models::{cursor_set, slice_to_cow, TextBuffer};
 use crate::ui::core::geom::{Pos, Rect};
 use crate::ui::core::painter::Painter;
 use crate::ui::core::style::{Color, Mod, Style};
@@ -13,22 +15,30 @@ use crate::views::doc::{self, DocLine, DocSpan, DocSpanKind};
 use memchr::memchr;
 use std::sync::Arc;
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::UnicodeWidthStr;
 
-use super::layout::{vertical_scrollbar_metrics, EditorPaneLayout, VerticalScrollbarMetrics};
+use super::breadcrumbs::{self, compute_breadcrumb_slots};
+use super::layout::{
+    horizontal_scrollbar_metrics, vertical_scrollbar_metrics, EditorPaneLayout,
+    HorizontalScrollbarMetrics, VerticalScrollbarMetrics,
+};
 use super::markdown::{self, MarkdownDocument};
+use super::minimap::{minimap_metrics, paint_minimap};
 use super::search_bar_layout::{
-    search_bar_match_info, search_bar_nav_origin, windowed_search_text, SEARCH_NAV_BUTTONS_WIDTH,
+    search_bar_indicators, search_bar_match_info, search_bar_nav_origin, windowed_search_text,
+    SEARCH_NAV_BUTTONS_WIDTH,
 };
 use super::tab_row::{compute_tab_row_layout, ellipsize_title};
 
 // U+250A "BOX DRAWINGS LIGHT QUADRUPLE DASH VERTICAL" keeps guides subtle.
 const INDENT_GUIDE_SYMBOL: &str = "\u{250A}";
+const RULER_SYMBOL: &str = "\u{2502}";
 
 /// Width of the search bar navigation buttons: " ▲ ▼ ✕"
 const SEARCH_NAV_BUTTONS: &str = " \u{25B2} \u{25BC} \u{2715}";
 const V_SCROLL_TRACK_SYMBOL: char = '│';
 const V_SCROLL_THUMB_SYMBOL: char = '█';
+const H_SCROLL_TRACK_SYMBOL: &str = "─";
+const H_SCROLL_THUMB_SYMBOL: &str = "▀";
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct TransientRowHighlight {
@@ -36,11 +46,16 @@ pub struct TransientRowHighlight {
 }
 
 #[derive(Debug, Clone, Copy, Default)]
-pub struct EditorPaneRenderOptions {
+pub struct EditorPaneRenderOptions<'a> {
     pub hovered_tab: Option<usize>,
     pub workspace_empty: bool,
     pub show_vertical_scrollbar: bool,
     pub transient_row_highlight: Option<TransientRowHighlight>,
+    /// 光标行末尾的 git blame 虚拟文本（已格式化）。
+    pub inline_blame: Option<&'a str>,
+    /// 当前标签页文件的诊断，按严重级别画下划线。
+    pub diagnostics: &'a [ProblemItem],
+    pub diagnostics_encoding: LspPositionEncoding,
 }
 
 pub fn paint_editor_pane(
@@ -49,7 +64,7 @@ pub fn paint_editor_pane(
     pane: &EditorPaneState,
     config: &EditorConfig,
     theme: &Theme,
-    options: EditorPaneRenderOptions,
+    options: EditorPaneRenderOptions<'_>,
     markdown: Option<&MarkdownDocument>,
 ) {
     if layout.area.is_empty() {
@@ -58,6 +73,10 @@ pub fn paint_editor_pane(
 
     paint_tabs(painter, layout.tab_area, pane, theme, options.hovered_tab);
 
+    if let (Some(area), Some(tab)) = (layout.breadcrumb_area, pane.active_tab()) {
+        paint_breadcrumbs(painter, area, tab, theme);
+    }
+
     if let Some(search_area) = layout.search_area {
         paint_search_bar(painter, search_area, &pane.search_bar, theme);
     }
@@ -76,7 +95,7 @@ pub fn cursor_position_editor(
     }
 
     let tab = pane.active_tab()?;
-    let (row, _col) = tab.buffer.cursor();
+    let (row, col) = tab.buffer.cursor();
     let (line_offset, horiz_offset) = effective_viewport(tab, layout, config);
 
     if layout.content_area.is_empty() {
@@ -84,8 +103,11 @@ pub fn cursor_position_editor(
     }
 
     let height = (layout.editor_area.h as usize).max(1);
-    let visible_lines = tab.visible_lines_in_viewport(line_offset, height);
-    let screen_row = visible_lines.iter().position(|&line| line == row)?;
+    let rows = tab.display_rows_in_viewport(line_offset, height);
+    let screen_row = rows
+        .iter()
+        .position(|r| r.line == row && r.contains_col(col))?;
+    let horiz_offset = row_horiz_offset(tab, rows[screen_row], horiz_offset, config.tab_size);
 
     let cursor_x_abs = cursor_display_x_abs(&tab.buffer, config.tab_size);
     let cursor_x_rel = cursor_x_abs.saturating_sub(horiz_offset);
@@ -102,6 +124,15 @@ pub fn cursor_position_editor(
     Some((x, y))
 }
 
+/// 显示行的横向起点：软换行的后续段从段首所在的显示列开始画，否则沿用视口的 `horiz_offset`。
+fn row_horiz_offset(tab: &EditorTabState, row: DisplayRow, horiz_offset: u32, tab_size: u8) -> u32 {
+    if row.start_col == 0 {
+        horiz_offset
+    } else {
+        display_x_abs(&tab.buffer, row.line, row.start_col, tab_size)
+    }
+}
+
 fn effective_viewport(
     tab: &EditorTabState,
     layout: &EditorPaneLayout,
@@ -113,6 +144,10 @@ fn effective_viewport(
     if !tab.viewport.follow_cursor || layout.editor_area.h == 0 || layout.content_area.w == 0 {
         return (line_offset, horiz_offset);
     }
+    // 软换行时视口已由 kernel 按显示行跟随，这里再按逻辑行推算只会算错。
+    if tab.viewport.word_wrap {
+        return (line_offset, 0);
+    }
 
     let (row, _) = tab.buffer.cursor();
     let height = (layout.editor_area.h as usize).max(1);
@@ -203,6 +238,21 @@ fn paint_tabs(
     }
 }
 
+fn paint_breadcrumbs(painter: &mut Painter, area: Rect, tab: &EditorTabState, theme: &Theme) {
+    let style = Style::default().bg(theme.editor_bg).fg(theme.breadcrumb_fg);
+    painter.fill_rect(area, style);
+
+    let separator_w = TextBuffer::display_width_of_str(breadcrumbs::SEPARATOR) as u16;
+    for slot in compute_breadcrumb_slots(area, tab) {
+        if slot.index.is_some() {
+            let sep_x = slot.x.saturating_sub(separator_w);
+            painter.text_clipped(Pos::new(sep_x, area.y), breadcrumbs::SEPARATOR, style, area);
+        }
+        let clip = Rect::new(slot.x, area.y, slot.width, 1);
+        painter.text_clipped(Pos::new(slot.x, area.y), &slot.label, style, clip);
+    }
+}
+
 fn paint_search_bar(painter: &mut Painter, area: Rect, state: &SearchBarState, theme: &Theme) {
     if !state.visible || area.is_empty() || area.h == 0 {
         return;
@@ -212,20 +262,11 @@ fn paint_search_bar(painter: &mut Painter, area: Rect, state: &SearchBarState, t
     painter.fill_rect(area, base);
 
     let match_info = search_bar_match_info(state);
-    let case_indicator = if state.case_sensitive { "[Aa]" } else { "[aa]" };
-    let regex_indicator = if state.use_regex { "[.*]" } else { "[  ]" };
+    let indicators = search_bar_indicators(state);
 
     // Find 行：Search 整条、Replace 顶行共用；Replace 仅多画一行替换框。
     let find_row = Rect::new(area.x, area.y, area.w, 1);
-    paint_find_row(
-        painter,
-        find_row,
-        state,
-        case_indicator,
-        regex_indicator,
-        &match_info,
-        theme,
-    );
+    paint_find_row(painter, find_row, state, &indicators, &match_info, theme);
 
     if matches!(state.mode, SearchBarMode::Replace) && area.h >= 2 {
         let replace_area = Rect::new(area.x, area.y.saturating_add(1), area.w, 1);
@@ -250,7 +291,7 @@ fn paint_search_bar(painter: &mut Painter, area: Rect, state: &SearchBarState, t
             label_style,
             replace_area,
         );
-        x = x.saturating_add("Replace: ".width() as u16);
+        x = x.saturating_add(TextBuffer::display_width_of_str("Replace: ") as u16);
         painter.text_clipped(
             Pos::new(x, replace_area.y),
             visible_replace,
@@ -266,8 +307,7 @@ fn paint_find_row(
     painter: &mut Painter,
     row: Rect,
     state: &SearchBarState,
-    case_indicator: &str,
-    regex_indicator: &str,
+    indicators: &str,
     match_info: &str,
     theme: &Theme,
 ) {
@@ -279,8 +319,7 @@ fn paint_find_row(
         state.cursor_pos,
         state.focused_field == SearchBarField::Search,
         row.w,
-        case_indicator,
-        regex_indicator,
+        indicators,
         match_info,
     );
     let input_style = if state.focused_field == SearchBarField::Search {
@@ -291,19 +330,19 @@ fn paint_find_row(
 
     let mut x = row.x;
     painter.text_clipped(Pos::new(x, row.y), "Find: ", label_style, row);
-    x = x.saturating_add("Find: ".width() as u16);
+    x = x.saturating_add(TextBuffer::display_width_of_str("Find: ") as u16);
 
     painter.text_clipped(Pos::new(x, row.y), visible_text, input_style, row);
-    x = x.saturating_add(visible_text.width().min(u16::MAX as usize) as u16);
+    x = x.saturating_add(
+        TextBuffer::display_width_of_str(visible_text).min(u16::MAX as usize) as u16,
+    );
 
     painter.text_clipped(Pos::new(x, row.y), " ", Style::default(), row);
     x = x.saturating_add(1);
 
-    painter.text_clipped(Pos::new(x, row.y), case_indicator, muted_style, row);
-    x = x.saturating_add(case_indicator.width().min(u16::MAX as usize) as u16);
-
-    painter.text_clipped(Pos::new(x, row.y), regex_indicator, muted_style, row);
-    x = x.saturating_add(regex_indicator.width().min(u16::MAX as usize) as u16);
+    painter.text_clipped(Pos::new(x, row.y), indicators, muted_style, row);
+    x = x
+        .saturating_add(TextBuffer::display_width_of_str(indicators).min(u16::MAX as usize) as u16);
 
     painter.text_clipped(Pos::new(x, row.y), " ", Style::default(), row);
     x = x.saturating_add(1);
@@ -335,7 +374,7 @@ fn windowed_replace_text(
     area_width: u16,
 ) -> (&str, usize) {
     let prefix = "Replace: ";
-    let prefix_w = prefix.width() as u16;
+    let prefix_w = TextBuffer::display_width_of_str(prefix) as u16;
     let available = area_width.saturating_sub(prefix_w) as usize;
     let cursor = if focused { cursor_pos } else { text.len() }.min(text.len());
     let (start, end) = text_window::window(text, cursor, available);
@@ -348,7 +387,7 @@ fn paint_editor_body(
     pane: &EditorPaneState,
     config: &EditorConfig,
     theme: &Theme,
-    options: EditorPaneRenderOptions,
+    options: EditorPaneRenderOptions<'_>,
     markdown: Option<&MarkdownDocument>,
 ) {
     if layout.editor_area.is_empty() {
@@ -372,7 +411,7 @@ fn paint_editor_body(
             .editor_area
             .y
             .saturating_add(layout.editor_area.h / 2);
-        let msg_w = UnicodeWidthStr::width(msg).min(u16::MAX as usize) as u16;
+        let msg_w = TextBuffer::display_width_of_str(msg).min(u16::MAX as usize) as u16;
         let x = layout
             .editor_area
             .x
@@ -392,14 +431,23 @@ fn paint_editor_body(
         )
     });
     let height = layout.editor_area.h as usize;
-    let visible_lines = tab.visible_lines_in_viewport(line_offset, height.max(1));
+    let rows = tab.display_rows_in_viewport(line_offset, height.max(1));
+    let mut visible_lines: Vec<usize> = rows.iter().map(|r| r.line).collect();
+    visible_lines.dedup();
 
     if config.show_line_numbers && !layout.gutter_area.is_empty() {
+        let mut gutter_area = layout.gutter_area;
+        if config.show_unsaved_gutter_marks && gutter_area.w > 1 {
+            // 未保存改动标记占贴着正文的最内侧一列。
+            gutter_area.w -= 1;
+            let marks_area = Rect::new(gutter_area.right(), gutter_area.y, 1, gutter_area.h);
+            paint_unsaved_marks(painter, marks_area, tab, &rows, theme);
+        }
         paint_gutter(
             painter,
-            layout.gutter_area,
+            gutter_area,
             tab,
-            &visible_lines,
+            &rows,
             tab.buffer.cursor().0,
             theme,
         );
@@ -416,22 +464,42 @@ fn paint_editor_body(
         tab,
         ContentPaintCtx {
             area: layout.content_area,
-            visible_lines: &visible_lines,
+            rows: &rows,
             horiz_offset,
             highlight_lines: syntax.as_ref(),
             tab_size: config.tab_size,
             theme,
             show_indent_guides: config.show_indent_guides,
+            ruler_columns: &config.ruler_columns,
             search_matches: &pane.search_bar.matches,
             current_match_index: pane.search_bar.current_match_index,
             markdown,
             transient_row_highlight: options.transient_row_highlight,
+            inline_blame: options.inline_blame,
+            diagnostics: options.diagnostics,
+            diagnostics_encoding: options.diagnostics_encoding,
         },
     );
 
+    if let Some(metrics) =
+        minimap_metrics(layout, tab.buffer.len_lines().max(1), height, line_offset)
+    {
+        paint_minimap(painter, &metrics, tab, config.tab_size, theme);
+    }
+
     if let Some(metrics) = scrollbar_metrics.flatten() {
         paint_vertical_scrollbar(painter, &metrics, theme);
     }
+
+    if let Some(track_area) = layout.h_scrollbar_area {
+        // 滚动条那一行不在 `editor_area` 内，行号栏下方也要铺底色。
+        let row = Rect::new(layout.editor_area.x, track_area.y, layout.editor_area.w, 1);
+        painter.fill_rect(row, base_style);
+        let max_offset = tab.max_horiz_offset(config.tab_size).max(horiz_offset);
+        if let Some(metrics) = horizontal_scrollbar_metrics(layout, max_offset, horiz_offset) {
+            paint_horizontal_scrollbar(painter, &metrics, theme);
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -496,7 +564,7 @@ fn paint_gutter(
     painter: &mut Painter,
     area: Rect,
     tab: &EditorTabState,
-    lines: &[usize],
+    rows: &[DisplayRow],
     active_row: usize,
     theme: &Theme,
 ) {
@@ -523,7 +591,8 @@ fn paint_gutter(
     let bottom = area.bottom();
     for y in area.y..bottom {
         let row = (y - area.y) as usize;
-        let Some(&line) = lines.get(row) else {
+        // 软换行的后续段不重复行号与折叠标记。
+        let Some(line) = rows.get(row).filter(|r| r.start_col == 0).map(|r| r.line) else {
             continue;
         };
 
@@ -569,6 +638,34 @@ fn paint_gutter(
     }
 }
 
+fn paint_unsaved_marks(
+    painter: &mut Painter,
+    area: Rect,
+    tab: &EditorTabState,
+    rows: &[DisplayRow],
+    theme: &Theme,
+) {
+    painter.fill_rect(area, Style::default().bg(theme.editor_bg));
+    for y in area.y..area.bottom() {
+        let Some(row) = rows.get((y - area.y) as usize) else {
+            continue;
+        };
+        // 软换行的后续段延续本行的新增 / 修改标记；删除标记只画在首段。
+        let (glyph, fg) = match tab.unsaved_mark(row.line) {
+            Some(GutterMarkKind::Added) => ("▎", theme.diff_added_fg),
+            Some(GutterMarkKind::Modified) => ("▎", theme.diff_modified_fg),
+            Some(GutterMarkKind::Deleted) if row.start_col == 0 => ("▔", theme.diff_deleted_fg),
+            _ => continue,
+        };
+        painter.text_clipped(
+            Pos::new(area.x, y),
+            glyph,
+            Style::default().bg(theme.editor_bg).fg(fg),
+            Rect::new(area.x, y, 1, 1),
+        );
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 struct TextSegment {
     start: usize,
@@ -599,31 +696,39 @@ fn flush_text_segment(
 
 struct ContentPaintCtx<'a> {
     area: Rect,
-    visible_lines: &'a [usize],
+    rows: &'a [DisplayRow],
     horiz_offset: u32,
     highlight_lines: Option<&'a SyntaxHighlightLines>,
     tab_size: u8,
     theme: &'a Theme,
     show_indent_guides: bool,
+    ruler_columns: &'a [u16],
     search_matches: &'a [Match],
     current_match_index: Option<usize>,
     markdown: Option<&'a MarkdownDocument>,
     transient_row_highlight: Option<TransientRowHighlight>,
+    inline_blame: Option<&'a str>,
+    diagnostics: &'a [ProblemItem],
+    diagnostics_encoding: LspPositionEncoding,
 }
 
 fn paint_content(painter: &mut Painter, tab: &EditorTabState, ctx: ContentPaintCtx<'_>) {
     let ContentPaintCtx {
         area,
-        visible_lines,
+        rows,
         horiz_offset,
         highlight_lines,
         tab_size,
         theme,
         show_indent_guides,
+        ruler_columns,
         search_matches,
         current_match_index,
         markdown,
         transient_row_highlight,
+        inline_blame,
+        diagnostics,
+        diagnostics_encoding,
     } = ctx;
     if area.is_empty() {
         return;
@@ -649,30 +754,61 @@ fn paint_content(painter: &mut Painter, tab: &EditorTabState, ctx: ContentPaintC
     let cursor_row = tab.buffer.cursor().0;
     // 光标贴着的括号与其配对括号——渲染成亮橙加粗，盖掉原本的语法色。
     let bracket_match = tab.matching_bracket();
-    let mut match_cursor = visible_lines
+    let mut match_cursor = rows
         .first()
-        .map(|first_line| search_matches.partition_point(|m| m.line < *first_line))
+        .map(|first| search_matches.partition_point(|m| m.line < first.line))
         .unwrap_or(0);
+    let mut line_match_start = match_cursor;
+    // 高亮按逻辑行缓存：同一行的各个软换行段共用一行 spans。
+    let mut line_index = 0usize;
 
     let bottom = area.bottom();
     for y in area.y..bottom {
         let screen_row = (y - area.y) as usize;
-        let Some(&row) = visible_lines.get(screen_row) else {
+        let Some(&display_row) = rows.get(screen_row) else {
             continue;
         };
-
-        while match_cursor < search_matches.len() && search_matches[match_cursor].line < row {
-            match_cursor += 1;
+        let row = display_row.line;
+        let continues_line = screen_row > 0 && rows[screen_row - 1].line == row;
+        if screen_row > 0 && !continues_line {
+            line_index += 1;
         }
-        let line_match_start = match_cursor;
-        while match_cursor < search_matches.len() && search_matches[match_cursor].line == row {
-            match_cursor += 1;
+        let horiz_offset = row_horiz_offset(tab, display_row, horiz_offset, tab_size_u8);
+        let segment_end = display_row.end_col;
+
+        if !continues_line {
+            while match_cursor < search_matches.len() && search_matches[match_cursor].line < row {
+                match_cursor += 1;
+            }
+            line_match_start = match_cursor;
+            while match_cursor < search_matches.len() && search_matches[match_cursor].line == row {
+                match_cursor += 1;
+            }
         }
         let line_matches = &search_matches[line_match_start..match_cursor];
         // 拖拽落点的临时行高亮（不做常驻当前行高亮）。
         let row_bg = transient_row_bg(theme, transient_row_highlight, row);
         let row_base_style = row_bg.map_or(base_style, |bg| base_style.bg(bg));
 
+        // 标尺画在文字之前：行内有字的格子会被文字覆盖，只在空白处露出竖线。
+        if !ruler_columns.is_empty() {
+            let ruler_style = row_base_style.fg(theme.ruler_fg);
+            for &column in ruler_columns {
+                let Some(rel) = u32::from(column).checked_sub(horiz_offset) else {
+                    continue;
+                };
+                if rel >= u32::from(area.w) {
+                    continue;
+                }
+                painter.text_clipped(
+                    Pos::new(area.x + rel as u16, y),
+                    RULER_SYMBOL,
+                    ruler_style,
+                    Rect::new(area.x, y, area.w, 1),
+                );
+            }
+        }
+
         // For markdown non-cursor lines, use WYSIWYG rendering
         if is_markdown && row != cursor_row {
             if let Some(md) = markdown {
@@ -704,12 +840,9 @@ fn paint_content(painter: &mut Painter, tab: &EditorTabState, ctx: ContentPaintC
         let snippet_range = snippet_range_for_row(snippet_span, row).unwrap_or((0, 0));
         let has_snippet = snippet_range.0 != snippet_range.1;
 
-        // For markdown cursor lines, use markdown source highlighting if no tree-sitter
-        let highlight_spans = if is_markdown {
-            None // We'll use md_source_spans below
-        } else {
-            highlight_lines.and_then(|lines| lines.line(screen_row))
-        };
+        // Markdown cursor lines show the raw source with the kernel's syntax highlighting;
+        // the renderer's marker dimming is only a fallback when no spans were computed.
+        let highlight_spans = highlight_lines.and_then(|lines| lines.line(line_index));
 
         let md_source_spans: Vec<HighlightSpan>;
         let highlight_spans = if is_markdown && highlight_spans.is_none() {
@@ -728,6 +861,10 @@ fn paint_content(painter: &mut Painter, tab: &EditorTabState, ctx: ContentPaintC
         };
 
         let inlay_hints = tab.inlay_hint_line(row);
+        let mut underline_ranges = document_link_ranges_for_row(tab, row);
+        underline_ranges.extend(document_highlight_ranges_for_row(tab, row));
+        let diagnostic_ranges =
+            diagnostic_ranges_for_row(tab, diagnostics, diagnostics_encoding, row);
 
         let line = tab
             .buffer
@@ -777,13 +914,18 @@ fn paint_content(painter: &mut Painter, tab: &EditorTabState, ctx: ContentPaintC
                     tab_size - rem
                 }
             } else {
-                g.width() as u32
+                TextBuffer::display_width_of_grapheme(g) as u32
             };
 
             if width == 0 {
                 continue;
             }
 
+            if segment_end.is_some_and(|end| g_idx >= end) {
+                flush_text_segment(painter, line, y, row_clip, &mut seg);
+                break;
+            }
+
             if display_col < horiz_offset {
                 display_col += width;
                 continue;
@@ -837,9 +979,28 @@ fn paint_content(painter: &mut Painter, tab: &EditorTabState, ctx: ContentPaintC
                     style = style.patch(snippet_style);
                 }
 
+                if underline_ranges
+                    .iter()
+                    .any(|&(start, end)| g_start >= start && g_start < end)
+                {
+                    style = style.add_mod(Mod::UNDERLINE);
+                }
+
+                if let Some(&(_, _, severity)) = diagnostic_ranges
+                    .iter()
+                    .find(|&&(start, end, _)| g_start >= start && g_start < end)
+                {
+                    style = style
+                        .fg(diagnostic_fg(theme, severity))
+                        .add_mod(Mod::UNDERLINE);
+                }
+
                 if let Some(cells) = bracket_match {
                     if cells.iter().any(|&(br, bc)| br == row && bc == g_idx) {
-                        style = style.fg(theme.bracket_match_fg).add_mod(Mod::BOLD);
+                        style = style
+                            .fg(theme.bracket_match_fg)
+                            .bg(theme.bracket_match_bg)
+                            .add_mod(Mod::BOLD);
                     }
                 }
             }
@@ -895,7 +1056,7 @@ fn paint_content(painter: &mut Painter, tab: &EditorTabState, ctx: ContentPaintC
         flush_text_segment(painter, line, y, row_clip, &mut seg);
 
         // Draw indent guides after the line has been rendered so we can overlay on whitespace.
-        if show_indent_guides {
+        if show_indent_guides && display_row.start_col == 0 {
             let indent_len = line.len().saturating_sub(line.trim_start().len());
             if indent_len > 0 {
                 let indent_prefix = &line[..indent_len];
@@ -977,7 +1138,7 @@ fn paint_content(painter: &mut Painter, tab: &EditorTabState, ctx: ContentPaintC
             }
         }
 
-        if let Some(hints) = inlay_hints {
+        if let Some(hints) = inlay_hints.filter(|_| display_row.is_last_segment()) {
             if x < right {
                 let mut hint_text = String::new();
                 for hint in hints {
@@ -1004,15 +1165,43 @@ fn paint_content(painter: &mut Painter, tab: &EditorTabState, ctx: ContentPaintC
                         hint_style = hint_style.bg(bg);
                     }
                     painter.text_clipped(Pos::new(x, y), visible_hint, hint_style, row_clip);
+                    let hint_w =
+                        TextBuffer::display_width_of_str(visible_hint).min(u16::MAX as usize);
+                    x = x.saturating_add(hint_w as u16);
                 }
             }
         }
 
+        if let Some(blame) =
+            inline_blame.filter(|_| row == cursor_row && display_row.is_last_segment())
+        {
+            if x < right {
+                let blame_text = format!("   {blame}");
+                let avail = right.saturating_sub(x) as usize;
+                let end = text_window::truncate_to_width(&blame_text, avail);
+                let mut blame_style = Style::default()
+                    .fg(theme.palette_muted_fg)
+                    .add_mod(Mod::DIM);
+                if let Some(bg) = row_bg {
+                    blame_style = blame_style.bg(bg);
+                }
+                painter.text_clipped(
+                    Pos::new(x, y),
+                    blame_text.get(..end).unwrap_or_default(),
+                    blame_style,
+                    row_clip,
+                );
+            }
+        }
+
         if !tab.secondary_cursors.is_empty() {
             let cursor_style = Style::default()
                 .bg(theme.palette_selected_fg)
                 .fg(theme.palette_selected_bg);
             for col in cursor_set::secondary_positions_for_row(&tab.secondary_cursors, row) {
+                if !display_row.contains_col(col) {
+                    continue;
+                }
                 let x_abs = display_x_abs_for_grapheme_col(
                     line,
                     col.min(tab.buffer.line_grapheme_len(row)),
@@ -1124,7 +1313,8 @@ fn display_x_abs_for_grapheme_col(line: &str, col: usize, tab_size: u32) -> u32
             let rem = display_col % tab_size;
             display_col += if rem == 0 { tab_size } else { tab_size - rem };
         } else {
-            display_col = display_col.saturating_add(g.width() as u32);
+            display_col =
+                display_col.saturating_add(TextBuffer::display_width_of_grapheme(g) as u32);
         }
     }
     display_col
@@ -1158,6 +1348,150 @@ fn paint_vertical_scrollbar(
     );
 }
 
+fn paint_horizontal_scrollbar(
+    painter: &mut Painter,
+    metrics: &HorizontalScrollbarMetrics,
+    theme: &Theme,
+) {
+    if metrics.track_area.is_empty() || metrics.thumb_area.is_empty() {
+        return;
+    }
+
+    let track_style = Style::default()
+        .bg(theme.editor_bg)
+        .fg(theme.palette_muted_fg);
+    painter.text_clipped(
+        Pos::new(metrics.track_area.x, metrics.track_area.y),
+        H_SCROLL_TRACK_SYMBOL.repeat(metrics.track_area.w as usize),
+        track_style,
+        metrics.track_area,
+    );
+
+    let thumb_style = Style::default().bg(theme.editor_bg).fg(theme.header_fg);
+    painter.text_clipped(
+        Pos::new(metrics.thumb_area.x, metrics.thumb_area.y),
+        H_SCROLL_THUMB_SYMBOL.repeat(metrics.thumb_area.w as usize),
+        thumb_style,
+        metrics.track_area,
+    );
+}
+
+/// 文档链接落在 `row` 上的部分，换算成行内字节区间。
+fn document_link_ranges_for_row(tab: &EditorTabState, row: usize) -> Vec<(usize, usize)> {
+    if tab.document_links.is_empty() && tab.unresolved_document_links.is_empty() {
+        return Vec::new();
+    }
+    let rope = tab.buffer.rope();
+    let line_start = rope.line_to_byte(row);
+    let line_end = line_start + rope.line(row).len_bytes();
+    tab.document_links
+        .iter()
+        .map(|(start, end, _)| (*start, *end))
+        .chain(
+            tab.unresolved_document_links
+                .iter()
+                .map(|(start, end, _)| (*start, *end)),
+        )
+        .filter(|&(start, end)| start < line_end && end > line_start)
+        .map(|(start, end)| {
+            (
+                start.saturating_sub(line_start),
+                end.min(line_end) - line_start,
+            )
+        })
+        .collect()
+}
+
+/// documentHighlight 区间落在 `row` 上的部分（行内字节区间），跨行区间取到行尾。
+fn document_highlight_ranges_for_row(tab: &EditorTabState, row: usize) -> Vec<(usize, usize)> {
+    if tab.document_highlights.is_empty() {
+        return Vec::new();
+    }
+    let line_len = tab.buffer.rope().line(row).len_bytes();
+    let row = row as u32;
+    tab.document_highlights
+        .iter()
+        .filter(|range| range.start.line <= row && range.end.line >= row)
+        .map(|range| {
+            let start = if range.start.line == row {
+                range.start.character as usize
+            } else {
+                0
+            };
+            let end = if range.end.line == row {
+                range.end.character as usize
+            } else {
+                line_len
+            };
+            (start, end)
+        })
+        .filter(|&(start, end)| start < end)
+        .collect()
+}
+
+/// 诊断落在 `row` 上的部分（行内字节区间），最严重的排在前面；空区间扩成一个字符，
+/// 否则行尾的 “缺少分号” 之类诊断看不到。
+fn diagnostic_ranges_for_row(
+    tab: &EditorTabState,
+    diagnostics: &[ProblemItem],
+    encoding: LspPositionEncoding,
+    row: usize,
+) -> Vec<(usize, usize, ProblemSeverity)> {
+    if diagnostics.is_empty() || row >= tab.buffer.len_lines() {
+        return Vec::new();
+    }
+    let line = tab.buffer.rope().line(row);
+    let line_chars = line_len_chars(line);
+    let row = row as u32;
+    let mut ranges: Vec<(usize, usize, ProblemSeverity)> = diagnostics
+        .iter()
+        .filter(|item| item.range.start_line <= row && item.range.end_line >= row)
+        .filter_map(|item| {
+            let start = if item.range.start_line == row {
+                lsp_col_to_char_offset_in_line(line, item.range.start_col, encoding)
+            } else {
+                0
+            };
+            let end = if item.range.end_line == row {
+                lsp_col_to_char_offset_in_line(line, item.range.end_col, encoding)
+            } else {
+                line_chars
+            };
+            let end = if end <= start {
+                (start + 1).min(line_chars)
+            } else {
+                end
+            };
+            (start < end).then(|| {
+                (
+                    line.char_to_byte(start),
+                    line.char_to_byte(end),
+                    item.severity,
+                )
+            })
+        })
+        .collect();
+    ranges.sort_by_key(|&(_, _, severity)| severity_rank(severity));
+    ranges
+}
+
+fn severity_rank(severity: ProblemSeverity) -> u8 {
+    match severity {
+        ProblemSeverity::Error => 0,
+        ProblemSeverity::Warning => 1,
+        ProblemSeverity::Information => 2,
+        ProblemSeverity::Hint => 3,
+    }
+}
+
+fn diagnostic_fg(theme: &Theme, severity: ProblemSeverity) -> Color {
+    match severity {
+        ProblemSeverity::Error => theme.error_fg,
+        ProblemSeverity::Warning => theme.warning_fg,
+        ProblemSeverity::Information | ProblemSeverity::Hint => theme.hint_fg,
+    }
+}
+
 fn snippet_range_for_row(
     snippet: Option<((usize, usize), (usize, usize))>,
     row: usize,
@@ -1263,8 +1597,7 @@ fn cursor_position_search_bar(area: Rect, state: &SearchBarState) -> Option<(u16
     }
 
     let match_info = search_bar_match_info(state);
-    let case_indicator = if state.case_sensitive { "[Aa]" } else { "[aa]" };
-    let regex_indicator = if state.use_regex { "[.*]" } else { "[  ]" };
+    let indicators = search_bar_indicators(state);
 
     match state.focused_field {
         SearchBarField::Search => {
@@ -1275,24 +1608,22 @@ fn cursor_position_search_bar(area: Rect, state: &SearchBarState) -> Option<(u16
                 cursor,
                 true,
                 area.w,
-                case_indicator,
-                regex_indicator,
+                &indicators,
                 &match_info,
             );
             let before = state.search_text.get(start..cursor).unwrap_or_default();
 
-            let prefix_w = "Find: ".width() as u16;
+            let prefix_w = TextBuffer::display_width_of_str("Find: ") as u16;
             let suffix_w = 1u16
-                .saturating_add(case_indicator.width() as u16)
-                .saturating_add(regex_indicator.width() as u16)
+                .saturating_add(TextBuffer::display_width_of_str(&indicators) as u16)
                 .saturating_add(1)
-                .saturating_add(match_info.width() as u16)
+                .saturating_add(TextBuffer::display_width_of_str(&match_info) as u16)
                 .saturating_add(SEARCH_NAV_BUTTONS_WIDTH);
 
             let x = area
                 .x
                 .saturating_add(prefix_w)
-                .saturating_add(before.width() as u16)
+                .saturating_add(TextBuffer::display_width_of_str(before) as u16)
                 .min(area.x.saturating_add(area.w.saturating_sub(suffix_w)));
             Some((x, y))
         }
@@ -1307,11 +1638,11 @@ fn cursor_position_search_bar(area: Rect, state: &SearchBarState) -> Option<(u16
                 windowed_replace_text(state.replace_text.as_str(), cursor, true, area.w);
             let before = state.replace_text.get(start..cursor).unwrap_or_default();
 
-            let prefix_w = "Replace: ".width() as u16;
+            let prefix_w = TextBuffer::display_width_of_str("Replace: ") as u16;
             let x = area
                 .x
                 .saturating_add(prefix_w)
-                .saturating_add(before.width() as u16)
+                .saturating_add(TextBuffer::display_width_of_str(before) as u16)
                 .min(area.x.saturating_add(area.w.saturating_sub(1)));
             Some((x, y))
         }
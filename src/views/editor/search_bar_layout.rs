@@ -28,19 +28,25 @@ pub(super) fn search_bar_match_info(state: &SearchBarState) -> String {
     }
 }
 
+/// 搜索选项指示：大小写 `[Aa]`、正则 `[.*]`、全词 `[W]`；关闭时保持同宽，布局不跳动。
+pub(super) fn search_bar_indicators(state: &SearchBarState) -> String {
+    let case_indicator = if state.case_sensitive { "[Aa]" } else { "[aa]" };
+    let regex_indicator = if state.use_regex { "[.*]" } else { "[  ]" };
+    let whole_word_indicator = if state.whole_word { "[W]" } else { "[ ]" };
+    format!("{case_indicator}{regex_indicator}{whole_word_indicator}")
+}
+
 pub(super) fn windowed_search_text<'a>(
     text: &'a str,
     cursor_pos: usize,
     focused: bool,
     area_width: u16,
-    case_indicator: &str,
-    regex_indicator: &str,
+    indicators: &str,
     match_info: &str,
 ) -> (&'a str, usize) {
     let prefix = "Find: ";
     let suffix_w = 1u16
-        .saturating_add(case_indicator.width() as u16)
-        .saturating_add(regex_indicator.width() as u16)
+        .saturating_add(indicators.width() as u16)
         .saturating_add(1)
         .saturating_add(match_info.width() as u16)
         .saturating_add(SEARCH_NAV_BUTTONS_WIDTH);
@@ -59,15 +65,13 @@ pub(super) fn search_bar_nav_origin(
     state: &SearchBarState,
 ) -> (u16, u16) {
     let match_info = search_bar_match_info(state);
-    let case_indicator = if state.case_sensitive { "[Aa]" } else { "[aa]" };
-    let regex_indicator = if state.use_regex { "[.*]" } else { "[  ]" };
+    let indicators = search_bar_indicators(state);
     let (visible_text, _start) = windowed_search_text(
         state.search_text.as_str(),
         state.cursor_pos,
         state.focused_field == SearchBarField::Search,
         area_w,
-        case_indicator,
-        regex_indicator,
+        &indicators,
         &match_info,
     );
 
@@ -75,8 +79,7 @@ pub(super) fn search_bar_nav_origin(
     x = x.saturating_add("Find: ".width() as u16);
     x = x.saturating_add(visible_text.width().min(u16::MAX as usize) as u16);
     x = x.saturating_add(1);
-    x = x.saturating_add(case_indicator.width().min(u16::MAX as usize) as u16);
-    x = x.saturating_add(regex_indicator.width().min(u16::MAX as usize) as u16);
+    x = x.saturating_add(indicators.width().min(u16::MAX as usize) as u16);
     x = x.saturating_add(1);
     x = x.saturating_add(match_info.width().min(u16::MAX as usize) as u16);
     (x, area_y)
@@ -1,7 +1,7 @@
 //! 文件浏览器视图（纯渲染 + 命中测试）
 
 use crate::core::text_window;
-use crate::models::{FileTreeRow, NodeId};
+use crate::models::{FileTreeRow, LoadState, NodeId};
 use crate::ui::core::geom::{Pos, Rect};
 use crate::ui::core::painter::Painter;
 use crate::ui::core::style::{Mod, Style};
@@ -17,7 +17,13 @@ pub struct ExplorerPaintCtx<'a> {
     pub rows: &'a [FileTreeRow],
     pub selected_id: Option<NodeId>,
     pub active_open_file_id: Option<NodeId>,
+    /// 侧栏内拖动中的行（`▶` 前缀）与指针下方的目标目录（高亮带）。
+    pub dragging_id: Option<NodeId>,
+    pub drop_target_id: Option<NodeId>,
+    pub drop_error: Option<&'a str>,
     pub scroll_offset: usize,
+    /// 达到该大小的文件，右侧的大小以警告色显示。
+    pub large_file_bytes: u64,
     pub theme: &'a Theme,
 }
 
@@ -40,12 +46,16 @@ impl ExplorerView {
         row: &FileTreeRow,
         is_selected: bool,
         is_active_open_file: bool,
+        is_dragging: bool,
         width: u16,
         theme: &Theme,
     ) -> (String, Style) {
         let indent = "  ".repeat(row.depth as usize);
+        // 大目录分批加载期间（已展开、仍在读）显示加载标记。
         let icon = if row.is_dir {
-            if row.is_expanded {
+            if row.load_state == LoadState::Loading {
+                "… "
+            } else if row.is_expanded {
                 "▾ "
             } else {
                 "▸ "
@@ -74,8 +84,9 @@ impl ExplorerView {
             return (String::new(), row_style);
         }
 
-        // 前导空格，与 demo 的 " {indent}{icon}{name}" 对齐。
-        let mut left = format!(" {indent}{icon}{name}");
+        // 前导空格，与 demo 的 " {indent}{icon}{name}" 对齐；拖动中的行换成 `▶`。
+        let lead = if is_dragging { "▶" } else { " " };
+        let mut left = format!("{lead}{indent}{icon}{name}");
         let end = text_window::truncate_to_width(&left, width);
         left.truncate(end);
         let left_width = left.width();
@@ -90,7 +101,11 @@ impl ExplorerView {
             rows,
             selected_id,
             active_open_file_id,
+            dragging_id,
+            drop_target_id,
+            drop_error,
             scroll_offset,
+            large_file_bytes,
             theme,
         } = ctx;
         self.area = Some(area);
@@ -120,12 +135,62 @@ impl ExplorerView {
 
             let is_selected = selected_id == Some(row.id);
             let is_active_open_file = active_open_file_id == Some(row.id);
-            let (left_pad, row_style) =
-                self.render_row_parts(row, is_selected, is_active_open_file, area.w, theme);
+            let (left_pad, mut row_style) = self.render_row_parts(
+                row,
+                is_selected,
+                is_active_open_file,
+                dragging_id == Some(row.id),
+                area.w,
+                theme,
+            );
+            if drop_target_id == Some(row.id) {
+                row_style = row_style
+                    .bg(theme.palette_selected_bg)
+                    .fg(theme.palette_selected_fg);
+            }
 
             let row_clip = Rect::new(area.x, y, area.w, 1);
             painter.text_clipped(Pos::new(area.x, y), left_pad, row_style, row_clip);
+            self.paint_file_size(painter, row, row_clip, row_style, large_file_bytes, theme);
         }
+
+        if let Some(error) = drop_error {
+            let y = area.bottom().saturating_sub(1);
+            let row_clip = Rect::new(area.x, y, area.w, 1);
+            let style = Style::default().bg(theme.sidebar_bg).fg(theme.error_fg);
+            painter.fill_rect(row_clip, style);
+            painter.text_clipped(Pos::new(area.x, y), format!(" {error}"), style, row_clip);
+        }
+    }
+}
+
+impl ExplorerView {
+    /// 文件行右侧的大小；与文件名之间至少留一格，放不下就不画。
+    fn paint_file_size(
+        &self,
+        painter: &mut Painter,
+        row: &FileTreeRow,
+        row_clip: Rect,
+        row_style: Style,
+        large_file_bytes: u64,
+        theme: &Theme,
+    ) {
+        let Some(size) = row.size.filter(|_| !row.is_dir) else {
+            return;
+        };
+        let label = format!("{} ", crate::models::format_file_size(size));
+        let name_w = 1 + 2 * row.depth as usize + 2 + row.name.to_string_lossy().width();
+        let label_w = label.width();
+        if name_w + 1 + label_w > row_clip.w as usize {
+            return;
+        }
+        let fg = if size >= large_file_bytes {
+            theme.warning_fg
+        } else {
+            theme.palette_muted_fg
+        };
+        let x = row_clip.right().saturating_sub(label_w as u16);
+        painter.text_clipped(Pos::new(x, row_clip.y), label, row_style.fg(fg), row_clip);
     }
 }
 
@@ -0,0 +1,95 @@
+//! 把标签页内容导出成独立的 HTML 文档：每个高亮片段包一层带前景色的 `<span>`，
+//! 背景 / 前景色取自当前主题，方便把带语法高亮的代码贴到网页或文档里。
+
+use crate::kernel::editor::EditorTabState;
+use crate::models::slice_to_cow;
+use crate::ui::core::style::Color;
+use crate::ui::core::theme::Theme;
+use crate::ui::core::theme_adapter::ansi256_index_to_rgb;
+
+/// 主题色为终端默认色（`Color::Reset`）时在 HTML 里的替代。
+const FALLBACK_BG: (u8, u8, u8) = (0x1E, 0x1E, 0x1E);
+const FALLBACK_FG: (u8, u8, u8) = (0xD4, 0xD4, 0xD4);
+
+const FONT_FAMILY: &str = "ui-monospace, SFMono-Regular, Menlo, Consolas, monospace";
+
+pub fn export_as_html(tab: &EditorTabState, theme: &Theme) -> String {
+    let rope = tab.buffer.rope();
+    let total_lines = rope.len_lines().max(1);
+    let highlights = tab.highlight_lines_shared(0, total_lines);
+
+    let bg = css_color(theme.editor_bg, FALLBACK_BG);
+    let fg = css_color(theme.palette_fg, FALLBACK_FG);
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>");
+    push_escaped(&mut out, &tab.title);
+    out.push_str("</title>\n</head>\n<body style=\"margin:0;background:");
+    out.push_str(&bg);
+    out.push_str("\">\n<pre style=\"margin:0;padding:1em;background:");
+    out.push_str(&bg);
+    out.push_str(";color:");
+    out.push_str(&fg);
+    out.push_str(";font-family:");
+    out.push_str(FONT_FAMILY);
+    out.push_str("\"><code>");
+
+    for line_index in 0..rope.len_lines() {
+        let line = slice_to_cow(rope.line(line_index));
+        let content = line.trim_end_matches(['\n', '\r']);
+        if content.is_empty() && line_index + 1 == rope.len_lines() {
+            break;
+        }
+        let spans = highlights
+            .as_ref()
+            .and_then(|lines| lines.get(line_index))
+            .map(|spans| spans.as_slice())
+            .unwrap_or_default();
+
+        let mut pos = 0;
+        for span in spans {
+            let start = span.start.max(pos).min(content.len());
+            let end = span.end.min(content.len());
+            if start >= end || !content.is_char_boundary(start) || !content.is_char_boundary(end) {
+                continue;
+            }
+            push_escaped(&mut out, &content[pos..start]);
+            let color = css_color(theme.syntax_fg(span.kind.color_group()), FALLBACK_FG);
+            out.push_str("<span style=\"color:");
+            out.push_str(&color);
+            out.push_str("\">");
+            push_escaped(&mut out, &content[start..end]);
+            out.push_str("</span>");
+            pos = end;
+        }
+        push_escaped(&mut out, &content[pos..]);
+        out.push('\n');
+    }
+
+    out.push_str("</code></pre>\n</body>\n</html>\n");
+    out
+}
+
+fn css_color(color: Color, fallback: (u8, u8, u8)) -> String {
+    let (r, g, b) = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(index) => ansi256_index_to_rgb(index),
+        Color::Reset => fallback,
+    };
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn push_escaped(out: &mut String, text: &str) {
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(ch),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "../../../tests/unit/views/export/html.rs"]
+mod tests;
@@ -0,0 +1,5 @@
+//! 导出：把编辑器内容转成可在编辑器外分享的格式。
+
+pub mod html;
+
+pub use html::export_as_html;
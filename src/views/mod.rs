@@ -4,18 +4,21 @@
 //! - ExplorerView: 文件浏览器
 //! - Editor: 纯渲染/命中测试
 //! - doc: 文档/帮助渲染
+//! - export: 导出为 HTML 等外部格式
 
 pub mod doc;
 pub mod editor;
 pub mod explorer;
+pub mod export;
 
 pub use editor::{
     compute_editor_pane_layout, compute_pane_rects, compute_tab_row_layout, cursor_position_editor,
-    ellipsize_title, hit_test_editor_mouse, hit_test_editor_mouse_drag, hit_test_editor_tab,
-    hit_test_editor_vertical_scrollbar, hit_test_search_bar, hit_test_tab_hover, paint_editor_pane,
+    ellipsize_title, hit_test_breadcrumbs, hit_test_editor_gutter, hit_test_editor_mouse,
+    hit_test_editor_mouse_drag, hit_test_editor_tab, hit_test_editor_vertical_scrollbar,
+    hit_test_search_bar, hit_test_tab_hover, minimap_metrics, paint_editor_pane,
     tab_insertion_index, tab_insertion_x, vertical_scrollbar_metrics, DragHitResult,
-    EditorPaneLayout, EditorPaneRenderOptions, EditorVerticalScrollbarHitResult, PaneRects,
-    SearchBarHitResult, TabHitResult, TabRowLayout, TabRowSlot, TransientRowHighlight,
+    EditorPaneLayout, EditorPaneRenderOptions, EditorVerticalScrollbarHitResult, MinimapMetrics,
+    PaneRects, SearchBarHitResult, TabHitResult, TabRowLayout, TabRowSlot, TransientRowHighlight,
     VerticalScrollbarMetrics,
 };
 pub use explorer::{ExplorerPaintCtx, ExplorerView};
@@ -191,6 +191,7 @@ fn test_lsp_spawn_sync_requests_and_diagnostics_are_wired() {
     let dir = tempdir().unwrap();
     let a_path = dir.path().join("a.rs");
     let def_path = dir.path().join("definition_target.rs");
+    let type_def_path = dir.path().join("type_definition_target.rs");
     let trace_path = dir.path().join("lsp_trace.txt");
 
     let _env = EnvGuard::set_str("ZCODE_DISABLE_SETTINGS", "1")
@@ -205,7 +206,9 @@ fn test_lsp_spawn_sync_requests_and_diagnostics_are_wired() {
 
     std::fs::write(&a_path, "fn main() {}\n").unwrap();
     std::fs::write(&def_path, "pub fn target() {}\n").unwrap();
+    std::fs::write(&type_def_path, "pub struct Target;\n").unwrap();
     let def_path_canon = std::fs::canonicalize(&def_path).unwrap();
+    let type_def_path_canon = std::fs::canonicalize(&type_def_path).unwrap();
 
     let (runtime, rx) = create_runtime();
     let mut workbench = Workbench::new(dir.path(), runtime, None).unwrap();
@@ -313,6 +316,23 @@ fn test_lsp_spawn_sync_requests_and_diagnostics_are_wired() {
             .and_then(|p| std::fs::canonicalize(p).ok())
             .is_some_and(|p| p == def_path_canon)
     });
+
+    let type_def = KeyEvent {
+        code: KeyCode::F(12),
+        modifiers: KeyModifiers::CONTROL,
+        kind: KeyEventKind::Press,
+    };
+    let _ = workbench.handle_input(&InputEvent::Key(type_def));
+
+    drive_until(&mut workbench, &rx, Duration::from_secs(3), |w| {
+        w.state()
+            .editor
+            .pane(0)
+            .and_then(|p| p.active_tab())
+            .and_then(|t| t.path.as_ref())
+            .and_then(|p| std::fs::canonicalize(p).ok())
+            .is_some_and(|p| p == type_def_path_canon)
+    });
 }
 
 #[test]
@@ -1005,7 +1025,7 @@ fn test_real_pyright_hover_and_completion_smoke() {
     }));
 
     let completion = KeyEvent {
-        code: KeyCode::Char('.'),
+        code: KeyCode::Char(' '),
         modifiers: KeyModifiers::CONTROL,
         kind: KeyEventKind::Press,
     };
@@ -2153,7 +2173,7 @@ fn test_lsp_completion_auto_import_uses_full_sync_after_multi_edit_apply() {
         })
         .unwrap_or((-1, false));
 
-    assert_eq!(first_change, (2, true));
+    assert_eq!(first_change, (1, true));
 
     let _ = workbench.handle_input(&InputEvent::Key(KeyEvent {
         code: KeyCode::Char('x'),
@@ -2168,7 +2188,7 @@ fn test_lsp_completion_auto_import_uses_full_sync_after_multi_edit_apply() {
             .filter_map(|line| line.strip_prefix("didChange detail "))
             .filter_map(|payload| serde_json::from_str::<Value>(payload).ok())
             .any(|parsed| {
-                parsed.get("version").and_then(Value::as_i64) == Some(4)
+                parsed.get("version").and_then(Value::as_i64) == Some(3)
                     && parsed
                         .get("changes")
                         .and_then(Value::as_array)
@@ -2192,7 +2212,7 @@ fn test_lsp_completion_auto_import_uses_full_sync_after_multi_edit_apply() {
             .filter_map(|line| line.strip_prefix("didChange detail "))
             .filter_map(|payload| serde_json::from_str::<Value>(payload).ok())
             .any(|parsed| {
-                parsed.get("version").and_then(Value::as_i64) == Some(5)
+                parsed.get("version").and_then(Value::as_i64) == Some(4)
                     && parsed
                         .get("changes")
                         .and_then(Value::as_array)
@@ -2,7 +2,7 @@ use super::*;
 use crate::core::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use crate::core::event::{MouseButton, MouseEvent, MouseEventKind};
 use crate::kernel::editor::{ReloadCause, ReloadRequest};
-use crate::models::NodeId;
+use crate::models::{LineEndingStyle, NodeId};
 use crate::ui::backend::test::TestBackend;
 use crate::ui::core::geom::Rect;
 use crate::ui::core::id::IdPath;
@@ -144,6 +144,68 @@ fn test_workbench_new() {
     assert!(workbench.sidebar_visible());
 }
 
+#[test]
+fn test_restore_session_reopens_files_at_saved_cursor() {
+    let dir = tempdir().unwrap();
+    let first = dir.path().join("first.rs");
+    let second = dir.path().join("second.rs");
+    std::fs::write(&first, "fn first() {}\n").unwrap();
+    std::fs::write(&second, "fn second() {}\nlet x = 1;\n").unwrap();
+
+    let (runtime, _rx) = create_test_runtime();
+    let mut workbench = Workbench::new(dir.path(), runtime, None).unwrap();
+    workbench.saved_session.open_files = vec![
+        SessionOpenFile {
+            pane: 0,
+            path: first.clone(),
+            cursor_byte: 3,
+            active: true,
+        },
+        SessionOpenFile {
+            pane: 3,
+            path: second.clone(),
+            cursor_byte: 20,
+            active: false,
+        },
+        SessionOpenFile {
+            pane: 0,
+            path: dir.path().join("missing.rs"),
+            cursor_byte: 0,
+            active: false,
+        },
+    ];
+    workbench.restore_session();
+
+    let state = workbench.store.state();
+    let pane = state.editor.pane(0).expect("pane 0");
+    assert_eq!(pane.tabs.len(), 2);
+    assert_eq!(
+        pane.active_tab().and_then(|tab| tab.path.clone()),
+        Some(first.clone())
+    );
+    assert_eq!(pane.tabs[0].buffer.cursor(), (0, 3));
+    assert_eq!(pane.tabs[1].buffer.cursor(), (1, 5));
+
+    let recorded = session_open_files(state);
+    assert_eq!(
+        recorded,
+        vec![
+            SessionOpenFile {
+                pane: 0,
+                path: first,
+                cursor_byte: 3,
+                active: true,
+            },
+            SessionOpenFile {
+                pane: 0,
+                path: second,
+                cursor_byte: 20,
+                active: false,
+            },
+        ]
+    );
+}
+
 #[test]
 fn test_file_watcher_tracks_workspace_and_syncs_open_files() {
     let dir = tempdir().unwrap();
@@ -345,7 +407,10 @@ fn test_command_line_opens_and_runs_command() {
             kind: KeyEventKind::Press,
         }));
     }
-    assert_eq!(workbench.store.state().ui.command_line.input, "diagnostics");
+    assert_eq!(
+        workbench.store.state().ui.command_line.input,
+        ">diagnostics"
+    );
 
     let _ = workbench.handle_input(&InputEvent::Key(KeyEvent {
         code: KeyCode::Enter,
@@ -1006,7 +1071,7 @@ fn test_drag_explorer_file_into_folder_conflict_accept_overwrites() {
 }
 
 #[test]
-fn test_drag_explorer_file_onto_file_row_moves_into_that_files_parent_dir() {
+fn test_drag_explorer_file_onto_file_row_is_noop() {
     let dir = tempdir().unwrap();
     let root = dir
         .path()
@@ -1112,30 +1177,21 @@ fn test_drag_explorer_file_onto_file_row_moves_into_that_files_parent_dir() {
         drop_x,
         drop_y,
     ));
+    let drag = workbench.store.state().explorer.drag.expect("drag state");
+    assert_eq!(drag.dragging_node, NodeId::from_raw(file_id));
+    assert_eq!(drag.hover_target, None, "file rows are not drop targets");
+
     let _ = workbench.handle_input(&mouse(
         MouseEventKind::Up(MouseButton::Left),
         drop_x,
         drop_y,
     ));
+    drain_runtime_messages(&mut workbench, &rx);
 
-    drive_until(&mut workbench, &rx, Duration::from_secs(2), |w| {
-        to.exists()
-            && !from.exists()
-            && w.store
-                .state()
-                .explorer
-                .path_and_kind_for(NodeId::from_raw(file_id))
-                .is_some_and(|(path, is_dir)| !is_dir && path == to)
-    });
-    assert!(root_target.exists(), "drop target file should not be moved");
-    assert_eq!(
-        workbench
-            .store
-            .state()
-            .explorer
-            .path_and_kind_for(NodeId::from_raw(file_id)),
-        Some((to, false))
-    );
+    assert!(workbench.store.state().explorer.drag.is_none());
+    assert!(from.exists(), "dropping onto a file row should not move");
+    assert!(!to.exists());
+    assert!(root_target.exists());
 }
 
 #[test]
@@ -1450,6 +1506,70 @@ fn test_open_file_and_save_runs_async_runtime() {
     assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "Xhello\n");
 }
 
+#[test]
+fn test_save_normalizes_line_endings_to_detected_style() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("a.txt");
+    std::fs::write(&file_path, "hello\r\nworld\r\n").unwrap();
+
+    let (runtime, rx) = create_test_runtime();
+    let mut workbench = Workbench::new(dir.path(), runtime, None).unwrap();
+
+    let _ = workbench.dispatch_kernel(KernelAction::OpenPath(file_path.clone()));
+    drive_until(&mut workbench, &rx, Duration::from_secs(2), |w| {
+        w.store
+            .state()
+            .editor
+            .pane(0)
+            .and_then(|p| p.active_tab())
+            .is_some_and(|t| t.path.as_ref() == Some(&file_path))
+    });
+    let tab = workbench
+        .store
+        .state()
+        .editor
+        .pane(0)
+        .unwrap()
+        .active_tab()
+        .unwrap();
+    assert_eq!(tab.line_ending, LineEndingStyle::CrLf);
+
+    // Enter 只插入 `\n`，保存时统一成 CRLF。
+    let enter = KeyEvent {
+        code: KeyCode::Enter,
+        modifiers: KeyModifiers::NONE,
+        kind: KeyEventKind::Press,
+    };
+    let _ = workbench.handle_input(&InputEvent::Key(enter));
+    let save = KeyEvent {
+        code: KeyCode::Char('s'),
+        modifiers: KeyModifiers::CONTROL,
+        kind: KeyEventKind::Press,
+    };
+    let _ = workbench.handle_input(&InputEvent::Key(save));
+
+    drive_until(&mut workbench, &rx, Duration::from_secs(2), |w| {
+        let Some(tab) = w.store.state().editor.pane(0).and_then(|p| p.active_tab()) else {
+            return false;
+        };
+        !tab.dirty
+    });
+
+    assert_eq!(
+        std::fs::read_to_string(&file_path).unwrap(),
+        "\r\nhello\r\nworld\r\n"
+    );
+    // 干净的标签页必须和磁盘内容一致。
+    let tab = workbench
+        .store
+        .state()
+        .editor
+        .pane(0)
+        .and_then(|p| p.active_tab())
+        .unwrap();
+    assert_eq!(tab.buffer.text(), "\r\nhello\r\nworld\r\n");
+}
+
 #[test]
 fn test_editor_search_runs_async_task_and_updates_matches() {
     let dir = tempdir().unwrap();
@@ -1647,6 +1767,48 @@ fn test_global_search_runs_async_task_and_populates_results() {
     assert!(!s.items.is_empty());
 }
 
+#[test]
+fn test_search_overlay_filter_button_toggles_filter_rows() {
+    let dir = tempdir().unwrap();
+    let (runtime, _rx) = create_test_runtime();
+    let mut workbench = Workbench::new(dir.path(), runtime, None).unwrap();
+
+    let _ = workbench.dispatch_kernel(KernelAction::RunCommand(Command::FocusSearch));
+    render_once(&mut workbench, 80, 24);
+    let button = workbench
+        .frame_layout
+        .search_filters_button_area
+        .expect("filter button should be laid out after render");
+
+    let result = workbench.handle_input(&mouse(
+        MouseEventKind::Down(MouseButton::Left),
+        button.x + 1,
+        button.y,
+    ));
+    assert!(result.is_consumed());
+    assert!(workbench.store.state().search.filters_visible);
+
+    for ch in "*.rs".chars() {
+        let _ = workbench.handle_input(&InputEvent::Key(KeyEvent {
+            code: KeyCode::Char(ch),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+        }));
+    }
+    assert_eq!(workbench.store.state().search.include_glob, "*.rs");
+    assert!(workbench.store.state().search.query.is_empty());
+
+    let mut backend = TestBackend::new(80, 24);
+    workbench.render(&mut backend, Rect::new(0, 0, 80, 24));
+    let row = |y: u16| {
+        (0..80)
+            .map(|x| backend.buffer().cell(x, y).unwrap().symbol.clone())
+            .collect::<String>()
+    };
+    assert!(row(button.y + 1).contains("+ *.rs"));
+    assert!(row(button.y + 2).contains("- "));
+}
+
 #[test]
 fn test_explorer_create_file_runs_async_fs_and_updates_tree() {
     let dir = tempdir().unwrap();
@@ -2420,6 +2582,103 @@ fn test_editor_vertical_scrollbar_shows_only_on_right_edge_hover() {
     );
 }
 
+#[test]
+fn test_editor_minimap_paints_document_and_click_scrolls_to_line() {
+    let dir = tempdir().unwrap();
+    let (runtime, _rx) = create_test_runtime();
+    let mut workbench = Workbench::new(dir.path(), runtime, None).unwrap();
+
+    let mut config = workbench.store.state().editor.config.clone();
+    config.minimap_enabled = true;
+    let lsp = workbench.store.state().lsp.settings.clone();
+    let _ = workbench.dispatch_kernel(KernelAction::EditorConfigUpdated { config, lsp });
+
+    let path = dir.path().join("long.rs");
+    let content = (0..200)
+        .map(|i| format!("line {i}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let _ = workbench.dispatch_kernel(KernelAction::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path,
+        content,
+    }));
+
+    let mut backend = TestBackend::new(120, 40);
+    workbench.render(&mut backend, Rect::new(0, 0, 120, 40));
+
+    let minimap = {
+        let area = *workbench
+            .frame_layout
+            .editor
+            .inner_areas
+            .first()
+            .expect("editor area");
+        let state = workbench.store.state();
+        let pane = state.editor.pane(0).expect("pane");
+        let layout = compute_editor_pane_layout(area, pane, &state.editor.config);
+        layout.minimap_area.expect("minimap area")
+    };
+    let first = &backend
+        .buffer()
+        .cell(minimap.x, minimap.y)
+        .expect("minimap cell should exist")
+        .symbol;
+    assert_eq!(first, "\u{2580}");
+
+    let _ = workbench.handle_input(&mouse(
+        MouseEventKind::Down(MouseButton::Left),
+        minimap.x,
+        minimap.bottom() - 1,
+    ));
+    let line_offset = workbench
+        .store
+        .state()
+        .editor
+        .pane(0)
+        .and_then(|pane| pane.active_tab())
+        .map(|tab| tab.viewport.line_offset)
+        .expect("active tab");
+    assert!(line_offset > 0, "minimap click should scroll the editor");
+}
+
+#[test]
+fn test_status_bar_indentation_click_cycles_tab_size() {
+    let dir = tempdir().unwrap();
+    let (runtime, _rx) = create_test_runtime();
+    let mut workbench = Workbench::new(dir.path(), runtime, None).unwrap();
+
+    let _ = workbench.dispatch_kernel(KernelAction::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: dir.path().join("a.rs"),
+        content: "fn a() {\n    b();\n}\n".to_string(),
+    }));
+
+    let status_row = |workbench: &mut Workbench| {
+        let mut backend = TestBackend::new(120, 40);
+        workbench.render(&mut backend, Rect::new(0, 0, 120, 40));
+        (0..120)
+            .map(|x| backend.buffer().cell(x, 39).unwrap().symbol.clone())
+            .collect::<String>()
+    };
+    assert!(status_row(&mut workbench).contains("Spaces: 4"));
+
+    let area = workbench
+        .frame_layout
+        .status_indentation_area
+        .expect("indentation segment");
+    let _ = workbench.handle_input(&mouse(
+        MouseEventKind::Down(MouseButton::Left),
+        area.x + 1,
+        area.y,
+    ));
+    assert_eq!(
+        workbench.store.state().editor_config_for_pane(0).tab_size,
+        8
+    );
+    assert!(status_row(&mut workbench).contains("Spaces: 8"));
+}
+
 #[test]
 fn test_editor_vertical_scrollbar_drag_updates_line_offset_without_selection() {
     let dir = tempdir().unwrap();
@@ -2668,3 +2927,84 @@ fn click_cursor_alignment_tab_splits_symmetrically() {
         "Tab 右半应落到终点（光标偏右），实际 {first_tab:?}"
     );
 }
+
+#[test]
+fn restore_state_reopens_files_with_cursor_focus_and_search_query() {
+    let dir = tempdir().unwrap();
+    let first = dir.path().join("first.rs");
+    let second = dir.path().join("second.rs");
+    std::fs::write(&first, "fn first() {}\n").unwrap();
+    std::fs::write(&second, "fn second() {\n    body();\n}\n").unwrap();
+
+    let (runtime, _rx) = create_test_runtime();
+    let mut workbench = Workbench::new(dir.path(), runtime, None).unwrap();
+    for path in [&first, &second] {
+        let _ = workbench.dispatch_kernel(KernelAction::Editor(EditorAction::OpenFile {
+            pane: 0,
+            path: path.clone(),
+            content: std::fs::read_to_string(path).unwrap(),
+        }));
+    }
+    let _ = workbench.dispatch_kernel(KernelAction::Editor(EditorAction::GotoByteOffset {
+        pane: 0,
+        byte_offset: 18,
+    }));
+    let _ = workbench.dispatch_kernel(KernelAction::Editor(EditorAction::SetActiveTab {
+        pane: 0,
+        index: 0,
+    }));
+    let _ = workbench.dispatch_kernel(KernelAction::SearchAppend('x'));
+    let _ = workbench.dispatch_kernel(KernelAction::RunCommand(Command::FocusExplorer));
+    let snapshot = workbench.snapshot_state();
+    assert_eq!(snapshot.panes[0].tabs[1].cursor, (1, 4));
+    assert_eq!(snapshot.search_query, "x");
+
+    let (runtime, _rx) = create_test_runtime();
+    let mut reloaded = Workbench::new(dir.path(), runtime, None).unwrap();
+    reloaded.restore_state(snapshot.clone());
+
+    assert_eq!(reloaded.snapshot_state(), snapshot);
+    let json = serde_json::to_string(&snapshot).unwrap();
+    assert_eq!(
+        serde_json::from_str::<AppStateSnapshot>(&json).unwrap(),
+        snapshot
+    );
+}
+
+#[test]
+fn test_explorer_undo_records_inverse_only_after_the_op_succeeds() {
+    let dir = tempdir().unwrap();
+    let existing = dir.path().join("existing.txt");
+    std::fs::write(&existing, "keep").unwrap();
+
+    let (runtime, rx) = create_test_runtime();
+    let mut workbench = Workbench::new(dir.path(), runtime, None).unwrap();
+    let undo_len = |workbench: &Workbench| workbench.store.state().explorer_history.undo_len();
+
+    // 目标已存在，新建失败：撤销时不能去删用户原有的 existing.txt。
+    workbench.run_effect(KernelEffect::CreateFile(existing.clone()));
+    drive_until(&mut workbench, &rx, Duration::from_secs(5), |workbench| {
+        workbench.pending_explorer_ops.is_empty()
+    });
+    assert_eq!(undo_len(&workbench), 0);
+
+    let created = dir.path().join("created.txt");
+    workbench.run_effect(KernelEffect::CreateFile(created.clone()));
+    assert_eq!(undo_len(&workbench), 0);
+    drive_until(&mut workbench, &rx, Duration::from_secs(5), |workbench| {
+        undo_len(workbench) == 1
+    });
+
+    // 覆盖式改名找不回被覆盖的文件，不进撤销栈。
+    workbench.run_effect(KernelEffect::RenamePath {
+        from: created.clone(),
+        to: existing.clone(),
+        overwrite: true,
+    });
+    drive_until(&mut workbench, &rx, Duration::from_secs(5), |_| {
+        !created.exists()
+    });
+    drain_runtime_messages(&mut workbench, &rx);
+    assert!(workbench.pending_explorer_ops.is_empty());
+    assert_eq!(undo_len(&workbench), 1);
+}
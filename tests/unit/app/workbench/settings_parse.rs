@@ -74,3 +74,40 @@ fn empty_settings_produce_no_overrides() {
     assert!(parsed.lsp_settings_override.is_none());
     assert!(parsed.lsp_server_overrides.is_empty());
 }
+
+#[test]
+fn parses_sidebar_width_and_ignores_zero() {
+    let settings: Settings = serde_json::from_str(r#"{"ui": {"sidebarWidth": 32}}"#).unwrap();
+    assert_eq!(parse_settings(settings).sidebar_width, Some(32));
+
+    let settings: Settings = serde_json::from_str(r#"{"ui": {"sidebar_width": 0}}"#).unwrap();
+    assert_eq!(parse_settings(settings).sidebar_width, None);
+    assert_eq!(parse_settings(Settings::default()).sidebar_width, None);
+}
+
+#[test]
+fn parses_status_bar_segments_and_defaults_when_empty() {
+    use crate::kernel::services::ports::StatusBarSegment;
+
+    let settings: Settings = serde_json::from_str(
+        r#"{"ui": {"statusBar": {"segments": ["gitBranch", "mode", "spacer", {"custom": " hi "}, "lineEnding"]}}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        parse_settings(settings).status_bar.segments(),
+        &[
+            StatusBarSegment::GitBranch,
+            StatusBarSegment::Mode,
+            StatusBarSegment::Spacer,
+            StatusBarSegment::Custom(" hi ".to_string()),
+            StatusBarSegment::LineEnding,
+        ]
+    );
+
+    let parsed = parse_settings(Settings::default());
+    assert!(parsed.status_bar.is_empty());
+    assert_eq!(
+        parsed.status_bar.segments(),
+        StatusBarSegment::DEFAULT_LAYOUT
+    );
+}
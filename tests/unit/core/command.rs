@@ -8,6 +8,14 @@ fn test_command_names() {
     assert_eq!(Command::Custom("myCommand".to_string()).name(), "myCommand");
 }
 
+#[test]
+fn git_stash_push_names_round_trip() {
+    for include_untracked in [false, true] {
+        let command = Command::GitStashPush { include_untracked };
+        assert_eq!(Command::from_name(command.name()), command);
+    }
+}
+
 #[test]
 fn test_is_edit_command() {
     assert!(Command::InsertChar('a').is_edit_command());
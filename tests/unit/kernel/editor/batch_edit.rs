@@ -0,0 +1,62 @@
+use super::*;
+use crate::core::Command;
+use crate::kernel::editor::TabId;
+use crate::kernel::services::ports::{EditorConfig, LspPosition, LspRange};
+use std::path::PathBuf;
+
+fn edit(start: (u32, u32), end: (u32, u32), text: &str) -> LspTextEdit {
+    LspTextEdit {
+        range: LspRange {
+            start: LspPosition {
+                line: start.0,
+                character: start.1,
+            },
+            end: LspPosition {
+                line: end.0,
+                character: end.1,
+            },
+        },
+        new_text: text.to_string(),
+    }
+}
+
+#[test]
+fn lsp_edits_apply_as_one_undo_step_and_keep_cursor_on_its_text() {
+    let config = EditorConfig::default();
+    let mut tab = EditorTabState::from_file(
+        TabId::new(1),
+        PathBuf::from("a.rs"),
+        "fn  main( ){\nlet x=1;\n}\n",
+        &config,
+    );
+    tab.buffer.set_cursor(1, 4);
+
+    let edits = [
+        edit((0, 2), (0, 4), " "),
+        edit((0, 9), (0, 10), ""),
+        edit((0, 11), (0, 11), " "),
+        edit((1, 0), (1, 0), "    "),
+        edit((1, 5), (1, 6), " = "),
+    ];
+    assert!(tab.apply_lsp_edits(&edits, LspPositionEncoding::Utf16, config.tab_size));
+    assert_eq!(tab.buffer.text(), "fn main() {\n    let x = 1;\n}\n");
+    assert_eq!(tab.buffer.cursor(), (1, 8));
+
+    let _ = tab.apply_command(Command::Undo, 0, &config);
+    assert_eq!(tab.buffer.text(), "fn  main( ){\nlet x=1;\n}\n");
+}
+
+#[test]
+fn lsp_inserts_at_the_same_position_keep_array_order() {
+    let config = EditorConfig::default();
+    let mut tab = EditorTabState::from_file(TabId::new(1), PathBuf::from("a.rs"), "x\n", &config);
+
+    let edits = [edit((0, 0), (0, 0), "a"), edit((0, 0), (0, 0), "b")];
+    assert!(tab.apply_lsp_edits(&edits, LspPositionEncoding::Utf16, config.tab_size));
+    assert_eq!(tab.buffer.text(), "abx\n");
+    assert!(!tab.apply_lsp_edits(
+        &[edit((0, 1), (0, 1), "")],
+        LspPositionEncoding::Utf16,
+        config.tab_size
+    ));
+}
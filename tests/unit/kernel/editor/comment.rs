@@ -0,0 +1,83 @@
+use super::*;
+use crate::core::Command;
+use crate::kernel::editor::TabId;
+use crate::kernel::services::ports::EditorConfig;
+use crate::models::Granularity;
+use std::path::PathBuf;
+
+fn tab(name: &str, text: &str) -> (EditorTabState, EditorConfig) {
+    let config = EditorConfig::default();
+    let tab = EditorTabState::from_file(TabId::new(1), PathBuf::from(name), text, &config);
+    (tab, config)
+}
+
+fn select_rows(tab: &mut EditorTabState, first: usize, last: usize) {
+    let mut selection = Selection::new((first, 0), Granularity::Char);
+    let end = (last, tab.buffer.line_grapheme_len(last));
+    selection.update_cursor(end, tab.buffer.rope());
+    tab.buffer.set_selection(Some(selection));
+    tab.buffer.set_cursor(end.0, end.1);
+}
+
+#[test]
+fn line_comment_token_follows_language() {
+    assert_eq!(line_comment_token(LanguageId::Rust), Some("//"));
+    assert_eq!(line_comment_token(LanguageId::Python), Some("#"));
+    assert_eq!(line_comment_token(LanguageId::Lua), Some("--"));
+    assert_eq!(line_comment_token(LanguageId::Wat), Some(";;"));
+    assert_eq!(line_comment_token(LanguageId::Css), None);
+}
+
+#[test]
+fn toggle_comments_selected_lines_at_common_indent_as_one_undo_step() {
+    let (mut tab, config) = tab("a.rs", "fn f() {\n\tlet a = 1;\n\n\t\tlet b = 2;\n}\n");
+    select_rows(&mut tab, 1, 3);
+
+    let (changed, _) = tab.apply_command(Command::ToggleLineComment, 0, &config);
+    assert!(changed);
+    assert_eq!(
+        tab.buffer.text(),
+        "fn f() {\n\t// let a = 1;\n\n\t// \tlet b = 2;\n}\n"
+    );
+    assert!(tab.dirty);
+
+    let _ = tab.apply_command(Command::Undo, 0, &config);
+    assert_eq!(
+        tab.buffer.text(),
+        "fn f() {\n\tlet a = 1;\n\n\t\tlet b = 2;\n}\n"
+    );
+}
+
+#[test]
+fn toggle_uncomments_all_lines_when_any_line_is_commented() {
+    let (mut tab, config) = tab("a.py", "# a\nb\n#c\n");
+    select_rows(&mut tab, 0, 2);
+
+    let _ = tab.apply_command(Command::ToggleLineComment, 0, &config);
+    assert_eq!(tab.buffer.text(), "a\nb\nc\n");
+
+    let _ = tab.apply_command(Command::ToggleLineComment, 0, &config);
+    assert_eq!(tab.buffer.text(), "# a\n# b\n# c\n");
+}
+
+#[test]
+fn toggle_keeps_cursor_on_the_same_text() {
+    let (mut tab, config) = tab("a.lua", "local x = 1\n");
+    tab.buffer.set_cursor(0, 6);
+
+    let _ = tab.apply_command(Command::ToggleLineComment, 0, &config);
+    assert_eq!(tab.buffer.text(), "-- local x = 1\n");
+    assert_eq!(tab.buffer.cursor(), (0, 9));
+
+    let _ = tab.apply_command(Command::ToggleLineComment, 0, &config);
+    assert_eq!(tab.buffer.text(), "local x = 1\n");
+    assert_eq!(tab.buffer.cursor(), (0, 6));
+}
+
+#[test]
+fn toggle_does_nothing_without_a_line_comment_token() {
+    let (mut tab, config) = tab("a.css", "a { color: red; }\n");
+    let (changed, _) = tab.apply_command(Command::ToggleLineComment, 0, &config);
+    assert!(!changed);
+    assert_eq!(tab.buffer.text(), "a { color: red; }\n");
+}
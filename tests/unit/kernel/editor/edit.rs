@@ -105,6 +105,39 @@ fn test_tab_key_inserts_indent_unit() {
     assert_eq!(cpp.buffer.cursor(), (0, 2));
 }
 
+#[test]
+fn test_tab_key_follows_detected_tab_type() {
+    let config = EditorConfig {
+        tab_size: 2,
+        ..Default::default()
+    };
+
+    // 以空格缩进为主的文件：Tab 与自动缩进都插入 tab_size 个空格。
+    let mut tab = EditorTabState::from_file(
+        TabId::new(1),
+        PathBuf::from("test.rs"),
+        "fn main() {\n  let a = 1;\n}",
+        &config,
+    );
+    assert_eq!(tab.tab_type, TabType::Spaces);
+    tab.buffer.set_cursor(2, 1);
+    let _ = tab.apply_command(Command::InsertTab, 0, &config);
+    assert_eq!(tab.buffer.text(), "fn main() {\n  let a = 1;\n}  ");
+
+    let mut cpp = EditorTabState::from_file(
+        TabId::new(2),
+        PathBuf::from("test.cpp"),
+        "int main() {}",
+        &config,
+    );
+    cpp.tab_type = TabType::Spaces;
+    cpp.buffer.set_cursor(0, "int main() {".len());
+    let _ = cpp.apply_command(Command::InsertNewline, 0, &config);
+    // C/C++ 一级缩进两个 Tab，换成空格就是 2 * tab_size 个。
+    assert_eq!(cpp.buffer.text(), "int main() {\n    \n}");
+    assert_eq!(cpp.buffer.cursor(), (1, 4));
+}
+
 #[test]
 fn test_replace_is_undoable() {
     let config = EditorConfig::default();
@@ -154,6 +187,110 @@ fn test_paste_over_selection_single_undo() {
     assert_eq!(tab.buffer.text(), "abc");
 }
 
+#[test]
+fn test_duplicate_line_keeps_column_and_undoes_in_one_step() {
+    let config = EditorConfig::default();
+    let mut tab = EditorTabState::from_file(
+        TabId::new(1),
+        PathBuf::from("test.txt"),
+        "abc\ndef",
+        &config,
+    );
+
+    tab.buffer.set_cursor(0, 2);
+    let (changed, _) = tab.apply_command(Command::DuplicateLine, 0, &config);
+    assert!(changed);
+    assert_eq!(tab.buffer.text(), "abc\nabc\ndef");
+    assert_eq!(tab.buffer.cursor(), (1, 2));
+
+    // 末行没有换行符。
+    tab.buffer.set_cursor(2, 1);
+    let (changed, _) = tab.apply_command(Command::DuplicateLine, 0, &config);
+    assert!(changed);
+    assert_eq!(tab.buffer.text(), "abc\nabc\ndef\ndef");
+    assert_eq!(tab.buffer.cursor(), (3, 1));
+
+    let (changed, _) = tab.apply_command(Command::Undo, 0, &config);
+    assert!(changed);
+    assert_eq!(tab.buffer.text(), "abc\nabc\ndef");
+}
+
+#[test]
+fn test_duplicate_line_copies_selected_block() {
+    let config = EditorConfig::default();
+    let mut tab = EditorTabState::from_file(
+        TabId::new(1),
+        PathBuf::from("test.txt"),
+        "a\nb\nc\n",
+        &config,
+    );
+
+    tab.buffer
+        .set_selection(Some(Selection::new((0, 0), Granularity::Char)));
+    tab.buffer.update_selection_cursor((1, 1));
+    tab.buffer.set_cursor(1, 1);
+
+    let (changed, _) = tab.apply_command(Command::DuplicateLine, 0, &config);
+    assert!(changed);
+    assert_eq!(tab.buffer.text(), "a\nb\na\nb\nc\n");
+    assert_eq!(tab.buffer.cursor(), (3, 1));
+    let selection = tab.buffer.selection().expect("selection moves to the copy");
+    assert_eq!(selection.anchor(), (2, 0));
+    assert_eq!(selection.cursor(), (3, 1));
+}
+
+#[test]
+fn test_sort_lines_selected_rows_single_undo() {
+    let config = EditorConfig::default();
+    let mut tab = EditorTabState::from_file(
+        TabId::new(1),
+        PathBuf::from("test.txt"),
+        "head\npear\napple\nfig\ntail\n",
+        &config,
+    );
+
+    // 选区止于第 4 行行首：第 4 行不参与排序。
+    tab.buffer
+        .set_selection(Some(Selection::new((1, 0), Granularity::Char)));
+    tab.buffer.update_selection_cursor((4, 0));
+    tab.buffer.set_cursor(4, 0);
+
+    let (changed, _) = tab.apply_command(Command::SortLines { descending: false }, 0, &config);
+    assert!(changed);
+    assert_eq!(tab.buffer.text(), "head\napple\nfig\npear\ntail\n");
+
+    let (changed, _) = tab.apply_command(Command::SortLines { descending: true }, 0, &config);
+    assert!(changed);
+    assert_eq!(tab.buffer.text(), "head\npear\nfig\napple\ntail\n");
+
+    let (changed, _) = tab.apply_command(Command::Undo, 0, &config);
+    assert!(changed);
+    assert_eq!(tab.buffer.text(), "head\napple\nfig\npear\ntail\n");
+}
+
+#[test]
+fn test_sort_lines_numeric_when_all_lines_are_numbers() {
+    let config = EditorConfig::default();
+    let mut tab = EditorTabState::from_file(
+        TabId::new(1),
+        PathBuf::from("test.txt"),
+        "10\n9\n-1.5\n100",
+        &config,
+    );
+
+    tab.buffer
+        .set_selection(Some(Selection::new((0, 0), Granularity::Char)));
+    tab.buffer.update_selection_cursor((3, 3));
+    tab.buffer.set_cursor(3, 3);
+
+    let (changed, _) = tab.apply_command(Command::SortLines { descending: false }, 0, &config);
+    assert!(changed);
+    assert_eq!(tab.buffer.text(), "-1.5\n9\n10\n100");
+
+    let (changed, _) = tab.apply_command(Command::SortLines { descending: false }, 0, &config);
+    assert!(!changed);
+}
+
 #[test]
 fn test_cursor_left_does_not_extend_empty_char_selection() {
     let config = EditorConfig::default();
@@ -294,6 +431,39 @@ fn test_cursor_up_restores_original_column_after_multiple_short_lines() {
     assert_eq!(tab.buffer.cursor(), (0, long_col));
 }
 
+#[test]
+fn test_cursor_up_down_move_by_display_line_in_word_wrap() {
+    let config = EditorConfig {
+        word_wrap: true,
+        ..EditorConfig::default()
+    };
+    let mut tab = EditorTabState::from_file(
+        TabId::new(1),
+        PathBuf::from("test.txt"),
+        "abcdefghijklmnopqrstuvwxy\nxyz",
+        &config,
+    );
+    tab.viewport.width = 10;
+    tab.set_word_wrap(true, config.tab_size);
+    tab.buffer.set_cursor(0, 8);
+
+    let _ = tab.apply_command(Command::CursorDown, 0, &config);
+    assert_eq!(tab.buffer.cursor(), (0, 18));
+    let _ = tab.apply_command(Command::CursorDown, 0, &config);
+    assert_eq!(tab.buffer.cursor(), (0, 25));
+    let _ = tab.apply_command(Command::CursorDown, 0, &config);
+    assert_eq!(tab.buffer.cursor(), (1, 3));
+
+    let _ = tab.apply_command(Command::CursorUp, 0, &config);
+    assert_eq!(tab.buffer.cursor(), (0, 25));
+    let _ = tab.apply_command(Command::CursorUp, 0, &config);
+    assert_eq!(tab.buffer.cursor(), (0, 18));
+    let _ = tab.apply_command(Command::CursorUp, 0, &config);
+    assert_eq!(tab.buffer.cursor(), (0, 8));
+    let (changed, _) = tab.apply_command(Command::CursorUp, 0, &config);
+    assert!(!changed);
+}
+
 #[test]
 fn test_horizontal_move_resets_vertical_goal_column() {
     let config = EditorConfig::default();
@@ -351,6 +521,51 @@ fn test_auto_pair_and_skip_closing() {
     assert_eq!(tab.buffer.cursor(), (0, 2));
 }
 
+#[test]
+fn test_backspace_inside_empty_pair_deletes_both() {
+    let config = EditorConfig::default();
+    let mut tab = EditorTabState::from_file(TabId::new(1), PathBuf::from("test.rs"), "", &config);
+
+    let _ = tab.apply_command(Command::InsertChar('['), 0, &config);
+    assert_eq!(tab.buffer.text(), "[]");
+
+    let _ = tab.apply_command(Command::DeleteBackward, 0, &config);
+    assert_eq!(tab.buffer.text(), "");
+    assert_eq!(tab.buffer.cursor(), (0, 0));
+
+    let mut tab =
+        EditorTabState::from_file(TabId::new(2), PathBuf::from("test.rs"), "(x)", &config);
+    tab.buffer.set_cursor(0, 2);
+    let _ = tab.apply_command(Command::DeleteBackward, 0, &config);
+    assert_eq!(tab.buffer.text(), "()");
+    let _ = tab.apply_command(Command::DeleteBackward, 0, &config);
+    assert_eq!(tab.buffer.text(), "");
+}
+
+#[test]
+fn test_auto_pairs_disabled_and_selection_skip_pairing() {
+    let config = EditorConfig {
+        auto_pairs: false,
+        ..Default::default()
+    };
+    let mut tab = EditorTabState::from_file(TabId::new(1), PathBuf::from("test.rs"), "", &config);
+
+    let _ = tab.apply_command(Command::InsertChar('('), 0, &config);
+    assert_eq!(tab.buffer.text(), "(");
+    let _ = tab.apply_command(Command::InsertChar(')'), 0, &config);
+    assert_eq!(tab.buffer.text(), "()");
+    let _ = tab.apply_command(Command::DeleteBackward, 0, &config);
+    assert_eq!(tab.buffer.text(), "(");
+
+    let config = EditorConfig::default();
+    let mut tab =
+        EditorTabState::from_file(TabId::new(2), PathBuf::from("test.rs"), "abc", &config);
+    let _ = tab.apply_command(Command::SelectAll, 0, &config);
+    let _ = tab.apply_command(Command::InsertChar('('), 0, &config);
+    assert_eq!(tab.buffer.text(), "(");
+    assert_eq!(tab.buffer.cursor(), (0, 1));
+}
+
 #[test]
 fn test_c_auto_pair_and_electric_enter() {
     let config = EditorConfig::default();
@@ -396,6 +611,42 @@ fn test_python_auto_pair_and_colon_indent() {
     assert_eq!(tab.buffer.cursor(), (1, 1));
 }
 
+#[test]
+fn test_smart_indent_follows_syntax_tree() {
+    let config = EditorConfig::default();
+    let newline_at = |text: &str, row: usize, col: usize| {
+        let mut tab =
+            EditorTabState::from_file(TabId::new(1), PathBuf::from("test.rs"), text, &config);
+        tab.buffer.set_cursor(row, col);
+        let _ = tab.apply_command(Command::InsertNewline, 0, &config);
+        tab.buffer.text()
+    };
+
+    // 行中间打开的参数列表：比起始行多一级。
+    assert_eq!(
+        newline_at(
+            "fn main() {\n\tlet x = foo(a, b);\n}",
+            1,
+            "\tlet x = foo(a, ".len()
+        ),
+        "fn main() {\n\tlet x = foo(a, \n\t\tb);\n}"
+    );
+    // 光标后紧跟闭合符：与块的起始行对齐。
+    assert_eq!(
+        newline_at("fn main() {\n\tlet a = 1;}", 1, "\tlet a = 1;".len()),
+        "fn main() {\n\tlet a = 1;\n}"
+    );
+    // 续行保留当前行缩进。
+    assert_eq!(
+        newline_at(
+            "fn main() {\n\tlet x = a\n\t\t.b();\n}",
+            2,
+            "\t\t.b();".len()
+        ),
+        "fn main() {\n\tlet x = a\n\t\t.b();\n\t\t\n}"
+    );
+}
+
 #[test]
 fn test_plain_text_does_not_auto_pair() {
     let config = EditorConfig::default();
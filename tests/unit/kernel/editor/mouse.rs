@@ -1,3 +1,4 @@
+use crate::core::Command;
 use crate::kernel::editor::{EditorTabState, TabId};
 use crate::kernel::services::ports::EditorConfig;
 use crate::models::Granularity;
@@ -113,3 +114,26 @@ fn add_cursor_at_demotes_old_primary() {
     assert_eq!(tab.secondary_cursors.len(), 1);
     assert_eq!(tab.secondary_cursors[0].pos, (0, 1));
 }
+
+#[test]
+fn select_block_puts_one_cursor_per_row_and_edits_each_row() {
+    let mut tab = tab_with_content("abcdef\nab\n\tcdef\nabcdef\n", 10);
+    let config = EditorConfig::default();
+    let tab_size = config.tab_size;
+
+    // 第 1 行够不着左边被跳过；Tab 行按显示列切（tab_size = 4 时 `\t` 占 0..4 列）。
+    tab.select_block((0, 4), (3, 5), tab_size);
+    assert_eq!(tab.buffer.cursor(), (3, 5));
+    assert_eq!(tab.secondary_cursors.len(), 2);
+    assert_eq!(tab.buffer.selection().map(|s| s.anchor()), Some((3, 4)));
+
+    let _ = tab.apply_command(Command::InsertChar('X'), 0, &config);
+    assert_eq!(tab.buffer.text(), "abcdXf\nab\n\tXdef\nabcdXf\n");
+
+    let _ = tab.apply_command(Command::Undo, 0, &config);
+    assert_eq!(tab.buffer.text(), "abcdef\nab\n\tcdef\nabcdef\n");
+
+    tab.select_block((3, 1), (0, 3), tab_size);
+    let _ = tab.apply_command(Command::DeleteSelection, 0, &config);
+    assert_eq!(tab.buffer.text(), "adef\na\n\tcdef\nadef\n");
+}
@@ -7,6 +7,7 @@ use crate::kernel::editor::TabId;
 use crate::kernel::editor::{DiskState, ReloadCause, ReloadRequest};
 use crate::kernel::services::ports::EditorConfig;
 use crate::kernel::Effect;
+use crate::models::LineEndingStyle;
 use ropey::Rope;
 use std::path::PathBuf;
 
@@ -108,6 +109,105 @@ fn test_saved_with_stale_head_does_not_clear_dirty_when_newer_edits_exist() {
     assert!(!editor.pane(0).unwrap().active_tab().unwrap().dirty);
 }
 
+#[test]
+fn test_save_above_partial_write_threshold_writes_only_the_dirty_range() {
+    let config = EditorConfig {
+        partial_write_threshold_bytes: 8,
+        ..EditorConfig::default()
+    };
+    let mut editor = EditorState::new(config);
+    let path = PathBuf::from("test.txt");
+    let _ = editor.dispatch_action(EditorAction::OpenFile {
+        pane: 0,
+        path: path.clone(),
+        content: "hello world".to_string(),
+    });
+    let _ = editor.apply_command(0, Command::CursorRight);
+    let _ = editor.apply_command(0, Command::InsertChar('x'));
+
+    let (_, effects) = editor.apply_command(0, Command::Save);
+    match effects.as_slice() {
+        [Effect::WriteFilePart {
+            byte_range,
+            replacement,
+            disk_len,
+            ..
+        }] => {
+            assert_eq!(byte_range, &(1..1));
+            assert_eq!(replacement, "x");
+            assert_eq!(*disk_len, 11);
+        }
+        other => panic!("expected WriteFilePart effect, got {other:?}"),
+    }
+
+    // 小于阈值的文件整体重写。
+    let mut editor = EditorState::new(EditorConfig::default());
+    let _ = editor.dispatch_action(EditorAction::OpenFile {
+        pane: 0,
+        path,
+        content: "hello world".to_string(),
+    });
+    let _ = editor.apply_command(0, Command::InsertChar('x'));
+    let (_, effects) = editor.apply_command(0, Command::Save);
+    assert!(matches!(effects.as_slice(), [Effect::WriteFile { .. }]));
+}
+
+#[test]
+fn test_save_normalizes_inserted_line_endings_as_an_undoable_edit() {
+    let config = EditorConfig {
+        partial_write_threshold_bytes: 8,
+        ..EditorConfig::default()
+    };
+    let mut editor = EditorState::new(config);
+    let _ = editor.dispatch_action(EditorAction::OpenFile {
+        pane: 0,
+        path: PathBuf::from("test.txt"),
+        content: "hello\r\nworld\r\n".to_string(),
+    });
+    assert_eq!(
+        editor.pane(0).unwrap().active_tab().unwrap().line_ending,
+        LineEndingStyle::CrLf
+    );
+
+    // 插入的 `\n` 在缓冲区里就改成 CRLF，写出去的只是脏区间，和缓冲区一致。
+    let _ = editor.apply_command(0, Command::InsertNewline);
+    let (changed, effects) = editor.apply_command(0, Command::Save);
+    assert!(changed);
+    assert!(matches!(
+        effects.as_slice(),
+        [Effect::WriteFilePart { replacement, .. }] if replacement == "\r\n"
+    ));
+    let tab = editor.pane(0).unwrap().active_tab().unwrap();
+    assert_eq!(tab.buffer.text(), "\r\nhello\r\nworld\r\n");
+
+    let _ = editor.apply_command(0, Command::Undo);
+    let tab = editor.pane(0).unwrap().active_tab().unwrap();
+    assert_eq!(tab.buffer.text(), "\nhello\r\nworld\r\n");
+}
+
+#[test]
+fn test_save_normalizes_foreign_line_endings_read_from_disk() {
+    let mut editor = EditorState::new(EditorConfig::default());
+    // 前 1000 字节只有 LF，后面混进了 CRLF。
+    let content = format!("{}tail\r\n", "x\n".repeat(600));
+    let _ = editor.dispatch_action(EditorAction::OpenFile {
+        pane: 0,
+        path: PathBuf::from("test.txt"),
+        content,
+    });
+    assert_eq!(
+        editor.pane(0).unwrap().active_tab().unwrap().line_ending,
+        LineEndingStyle::Lf
+    );
+
+    let (changed, effects) = editor.apply_command(0, Command::Save);
+    assert!(changed);
+    assert!(matches!(effects.as_slice(), [Effect::WriteFile { .. }]));
+    let tab = editor.pane(0).unwrap().active_tab().unwrap();
+    assert!(tab.buffer.text().ends_with("x\ntail\n"));
+    assert!(!tab.buffer.text().contains('\r'));
+}
+
 #[test]
 fn test_saved_clears_dirty_when_undo_redo_returns_to_written_content() {
     // 复现「Ctrl+S 保存不掉」的假 dirty：保存发起后、异步写盘完成前，
@@ -695,6 +795,33 @@ fn test_file_externally_modified_emits_reload_for_clean_and_marks_dirty_conflict
     assert!(matches!(pane1_tab.disk_state, DiskState::InSync));
 }
 
+#[test]
+fn test_file_externally_modified_without_auto_reload_marks_clean_tab_changed() {
+    let config = EditorConfig {
+        auto_reload_on_disk_change: false,
+        ..Default::default()
+    };
+    let mut editor = EditorState::new(config);
+    let path = PathBuf::from("watched.txt");
+    let _ = editor.dispatch_action(EditorAction::OpenFile {
+        pane: 0,
+        path: path.clone(),
+        content: "old".to_string(),
+    });
+
+    let (changed, effects) =
+        editor.dispatch_action(EditorAction::FileExternallyModified { path: path.clone() });
+    assert!(changed);
+    assert!(effects.is_empty());
+
+    let tab = editor
+        .pane(0)
+        .and_then(|pane| pane.active_tab())
+        .expect("tab");
+    assert!(matches!(tab.disk_state, DiskState::ChangedOnDisk));
+    assert_eq!(tab.buffer.text(), "old");
+}
+
 #[test]
 #[cfg(unix)]
 fn test_file_externally_modified_with_path_representation_mismatch_is_supported() {
@@ -883,3 +1010,185 @@ fn test_scroll_vertical_allows_scrolling_beyond_last_visible_line() {
     );
     assert!(!tab.viewport.follow_cursor);
 }
+
+#[test]
+fn test_scroll_horizontal_animated_converges_on_tick_and_yields_to_cursor() {
+    let config = EditorConfig::default();
+    let mut editor = EditorState::new(config);
+    let path = PathBuf::from("wide.txt");
+    let content = format!("{}\n", "x".repeat(200));
+
+    let _ = editor.dispatch_action(EditorAction::OpenFile {
+        pane: 0,
+        path,
+        content,
+    });
+    let _ = editor.dispatch_action(EditorAction::SetViewportSize {
+        pane: 0,
+        width: 20,
+        height: 5,
+    });
+
+    let (changed, _) = editor.dispatch_action(EditorAction::ScrollHorizontalAnimated {
+        pane: 0,
+        delta_columns: 16,
+    });
+    assert!(changed);
+    let (changed, _) = editor.dispatch_action(EditorAction::ScrollHorizontalAnimated {
+        pane: 0,
+        delta_columns: 16,
+    });
+    assert!(
+        changed,
+        "consecutive scrolls accumulate onto the pending target"
+    );
+
+    let tab = editor.pane(0).and_then(|p| p.active_tab()).unwrap();
+    assert_eq!(tab.viewport.horiz_offset, 0);
+    assert!(tab.is_horiz_scroll_animating());
+
+    assert!(editor.tick_scroll_animations());
+    let first = editor
+        .pane(0)
+        .unwrap()
+        .active_tab()
+        .unwrap()
+        .viewport
+        .horiz_offset;
+    assert_eq!(first, 16);
+
+    while editor.tick_scroll_animations() {}
+    let tab = editor.pane(0).and_then(|p| p.active_tab()).unwrap();
+    assert_eq!(tab.viewport.horiz_offset, 32);
+    assert!(!tab.is_horiz_scroll_animating());
+
+    let _ = editor.dispatch_action(EditorAction::ScrollHorizontalAnimated {
+        pane: 0,
+        delta_columns: 10_000,
+    });
+    let _ = editor.apply_command(0, Command::CursorRight);
+    assert!(editor.tick_scroll_animations());
+    let tab = editor.pane(0).and_then(|p| p.active_tab()).unwrap();
+    assert!(!tab.is_horiz_scroll_animating());
+    assert!(tab.viewport.follow_cursor);
+}
+
+#[test]
+fn test_restore_edit_history_only_applies_to_unedited_tab() {
+    let config = EditorConfig::default();
+    let mut editor = EditorState::new(config.clone());
+    let path = PathBuf::from("undo.txt");
+    assert!(editor.ensure_panes(2));
+    for pane in 0..2 {
+        let _ = editor.dispatch_action(EditorAction::OpenFile {
+            pane,
+            path: path.clone(),
+            content: "ab".to_string(),
+        });
+    }
+    let _ = editor.apply_command(1, Command::InsertChar('x'));
+
+    let mut donor = EditorTabState::from_file(TabId::new(9), path.clone(), "a", &config);
+    donor.buffer.set_cursor(0, 1);
+    let _ = donor.apply_command(Command::InsertChar('b'), 0, &config);
+    donor.history.on_save();
+    donor.dirty = false;
+    let (backup_path, _) = donor.edit_history_backup().expect("clean tab with edits");
+    assert_eq!(backup_path, path);
+
+    let (changed, _) = editor.dispatch_action(EditorAction::RestoreEditHistory {
+        path: path.clone(),
+        history: donor.history.clone(),
+    });
+    assert!(changed);
+
+    let pane0 = editor.pane(0).and_then(|p| p.active_tab()).unwrap();
+    assert!(pane0.history.can_undo());
+    assert!(!pane0.history.is_dirty());
+    let pane1 = editor.pane(1).and_then(|p| p.active_tab()).unwrap();
+    assert!(pane1.dirty);
+    assert_ne!(pane1.history.head(), donor.history.head());
+}
+
+#[test]
+fn test_partial_load_requests_next_chunk_and_defers_save_until_complete() {
+    let mut editor = EditorState::new(EditorConfig::default());
+    let path = PathBuf::from("huge.log");
+    let (_, effects) = editor.dispatch_action(EditorAction::OpenFileRange {
+        pane: 0,
+        path: path.clone(),
+        content: "a\nb\nc\n".to_string(),
+        loaded_bytes: 6,
+        file_size: 12,
+    });
+    assert!(matches!(
+        effects.as_slice(),
+        [Effect::LoadFileRange { path: p, start_byte: 6, end_byte: 12 }] if p == &path
+    ));
+
+    // 请求在途时不重复发出；保存先记下，等读完再写盘。
+    let (_, effects) = editor.apply_command(0, Command::CursorDown);
+    assert!(!effects
+        .iter()
+        .any(|effect| matches!(effect, Effect::LoadFileRange { .. })));
+    let (changed, effects) = editor.apply_command(0, Command::Save);
+    assert!(changed);
+    assert!(effects.is_empty());
+    let tab = editor.pane(0).and_then(|p| p.active_tab()).unwrap();
+    assert!(tab.partial_load.is_some_and(|p| p.save_when_loaded));
+
+    // 起点对不上的结果被忽略。
+    let (changed, _) = editor.dispatch_action(EditorAction::AppendFileRange {
+        path: path.clone(),
+        start_byte: 0,
+        end_byte: 6,
+        file_size: 12,
+        content: "a\nb\nc\n".to_string(),
+    });
+    assert!(!changed);
+
+    let (changed, effects) = editor.dispatch_action(EditorAction::AppendFileRange {
+        path: path.clone(),
+        start_byte: 6,
+        end_byte: 12,
+        file_size: 12,
+        content: "d\ne\nf\n".to_string(),
+    });
+    assert!(changed);
+    assert!(matches!(
+        effects.as_slice(),
+        [Effect::WriteFile { path: p, .. }] if p == &path
+    ));
+    let tab = editor.pane(0).and_then(|p| p.active_tab()).unwrap();
+    assert_eq!(tab.buffer.text(), "a\nb\nc\nd\ne\nf\n");
+    assert!(tab.partial_load.is_none());
+    assert!(!tab.dirty);
+    assert_eq!(tab.buffer.cursor(), (1, 0));
+}
+
+#[test]
+fn test_save_during_partial_load_keeps_reading_past_the_viewport() {
+    let mut editor = EditorState::new(EditorConfig::default());
+    let path = PathBuf::from("huge.log");
+    let head = "line\n".repeat(200);
+    let _ = editor.dispatch_action(EditorAction::SetViewportSize {
+        pane: 0,
+        width: 80,
+        height: 10,
+    });
+    let (_, effects) = editor.dispatch_action(EditorAction::OpenFileRange {
+        pane: 0,
+        path: path.clone(),
+        content: head.clone(),
+        loaded_bytes: head.len() as u64,
+        file_size: head.len() as u64 + 5,
+    });
+    // 视口在文件开头，离已读末尾还远，不会自己去读下一块。
+    assert!(effects.is_empty());
+
+    let (_, effects) = editor.apply_command(0, Command::Save);
+    assert!(matches!(
+        effects.as_slice(),
+        [Effect::LoadFileRange { path: p, .. }] if p == &path
+    ));
+}
@@ -272,3 +272,112 @@ fn identifier_pos_at_is_exact_while_identifier_pos_at_or_before_backtracks() {
 
     assert_eq!(tab.identifier_pos_at_or_before((0, 3)), Some((0, 2)));
 }
+
+#[test]
+fn highlight_lines_shared_uses_lexical_highlighting_without_syntax_tree() {
+    use crate::kernel::services::ports::EditorConfig;
+    use std::path::PathBuf;
+
+    let config = EditorConfig::default();
+    let tab = EditorTabState::from_file(
+        TabId::new(1),
+        PathBuf::from("api.proto"),
+        "message Foo { // note\n  string name = 1;\n}\n",
+        &config,
+    );
+    assert!(tab.syntax.is_none());
+
+    let rendered = tab
+        .highlight_lines_shared(0, 2)
+        .expect("lexical highlighting");
+    assert_eq!(rendered.len(), 2);
+    assert!(rendered[0].contains(&span(0, 7, HighlightKind::Keyword)));
+    assert!(rendered[1].contains(&span(2, 8, HighlightKind::TypeBuiltin)));
+
+    let comment = tab.buffer.rope().line_to_char(0) + "message Foo { // no".len();
+    assert!(tab.is_in_string_or_comment_at_char(comment));
+    assert!(!tab.is_in_string_or_comment_at_char(3));
+}
+
+#[test]
+fn fold_toggle_at_line_only_toggles_fold_starts_and_moves_hidden_cursor() {
+    use crate::kernel::services::ports::{EditorConfig, LspFoldingRange};
+    use std::path::PathBuf;
+
+    let config = EditorConfig::default();
+    let mut tab = EditorTabState::from_file(
+        TabId::new(1),
+        PathBuf::from("test.rs"),
+        "fn main() {\n    let a = 1;\n    let b = 2;\n}\n",
+        &config,
+    );
+    let version = tab.edit_version;
+    tab.set_folding_ranges_from_slice(
+        version,
+        &[LspFoldingRange {
+            start_line: 0,
+            end_line: 2,
+        }],
+    );
+    tab.buffer.set_cursor(2, 4);
+
+    assert!(!tab.fold_toggle_at_line(1, config.tab_size));
+    assert_eq!(tab.fold_marker_char(0), Some('▾'));
+
+    assert!(tab.fold_toggle_at_line(0, config.tab_size));
+    assert_eq!(tab.fold_marker_char(0), Some('▸'));
+    assert_eq!(tab.buffer.cursor(), (0, 4));
+    assert_eq!(tab.visible_lines_in_viewport(0, 10), vec![0, 3, 4]);
+
+    assert!(tab.fold_toggle_at_line(0, config.tab_size));
+    assert_eq!(tab.visible_lines_in_viewport(0, 10), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn detect_tab_type_follows_dominant_indentation() {
+    use crate::kernel::services::ports::TabType;
+
+    assert_eq!(
+        detect_tab_type("fn a() {\n\tx();\n\ty();\n    z();\n}\n"),
+        Some(TabType::Tabs)
+    );
+    assert_eq!(
+        detect_tab_type("def a():\n    x()\n    y()\n\tz()\n"),
+        Some(TabType::Spaces)
+    );
+    // 块注释的 ` *` 不算空格缩进；没有缩进行时判断不出。
+    assert_eq!(detect_tab_type("/**\n * doc\n */\nint x;\n"), None);
+
+    // 只看前 200 行。
+    let mut content = "\tx\n".repeat(200);
+    content.push_str(&"    y\n".repeat(300));
+    assert_eq!(detect_tab_type(&content), Some(TabType::Tabs));
+}
+
+#[test]
+fn breadcrumbs_refresh_on_cursor_move_and_select_symbol_range() {
+    use crate::kernel::services::ports::EditorConfig;
+    use std::path::PathBuf;
+
+    let config = EditorConfig::default();
+    let src = "struct A;\nimpl A {\n    fn run(&self) {}\n}\n";
+    let mut tab = EditorTabState::from_file(TabId::new(1), PathBuf::from("test.rs"), src, &config);
+    assert!(tab.breadcrumbs_stale());
+    assert!(tab.refresh_breadcrumbs());
+    assert_eq!(tab.breadcrumbs[0].label, "struct A");
+    assert!(!tab.breadcrumbs_stale());
+    assert!(!tab.refresh_breadcrumbs());
+
+    tab.buffer.set_cursor(2, 10);
+    assert!(tab.breadcrumbs_stale());
+    assert!(tab.refresh_breadcrumbs());
+    let labels: Vec<&str> = tab.breadcrumbs.iter().map(|c| c.label.as_str()).collect();
+    assert_eq!(labels, vec!["impl A", "fn run"]);
+
+    assert!(tab.select_breadcrumb(0, config.tab_size));
+    assert_eq!(
+        tab.buffer.get_selection_text().as_deref(),
+        Some("impl A {\n    fn run(&self) {}\n}")
+    );
+    assert!(!tab.select_breadcrumb(5, config.tab_size));
+}
@@ -1802,3 +1802,898 @@ fn syntax_line_directive_context_tracks_hash_and_include_without_delimiter() {
         }
     );
 }
+
+fn lexical_kind_at(spans: &[HighlightSpan], line: &str, needle: &str) -> Option<HighlightKind> {
+    let idx = line.find(needle)?;
+    spans
+        .iter()
+        .find(|s| s.start <= idx && idx < s.end)
+        .map(|s| s.kind)
+}
+
+#[test]
+fn test_highlight_protobuf_keywords_types_options_and_comments() {
+    let src = r#"syntax = "proto3";
+package demo.v1;
+// service docs
+message Request {
+  repeated string names = 1 [deprecated = true];
+  map<string, int64> counts = 2;
+  Status status = 3;
+  reserved 4 to max;
+}
+enum Status { STATUS_OK = 0; }
+service Greeter {
+  rpc Greet(Request) returns (stream Request);
+}
+"#;
+    assert!(SyntaxDocument::for_path(Path::new("api.proto"), &Rope::from_str(src)).is_none());
+
+    let spans = highlight_snippet(LanguageId::Protobuf, src);
+    let lines: Vec<&str> = src.lines().collect();
+    let kind = |line: usize, needle: &str| lexical_kind_at(&spans[line], lines[line], needle);
+
+    assert_eq!(kind(0, "syntax"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(0, "\"proto3\""), Some(HighlightKind::String));
+    assert_eq!(kind(1, "demo"), Some(HighlightKind::Namespace));
+    assert_eq!(kind(2, "// service"), Some(HighlightKind::Comment));
+    assert_eq!(kind(3, "message"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(3, "Request"), Some(HighlightKind::Type));
+    assert_eq!(kind(4, "repeated"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(4, "string"), Some(HighlightKind::TypeBuiltin));
+    assert_eq!(kind(4, "names"), Some(HighlightKind::Property));
+    assert_eq!(kind(4, "1"), Some(HighlightKind::Number));
+    assert_eq!(kind(4, "deprecated"), Some(HighlightKind::Attribute));
+    assert_eq!(kind(4, "true"), Some(HighlightKind::Boolean));
+    assert_eq!(kind(5, "map"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(5, "int64"), Some(HighlightKind::TypeBuiltin));
+    assert_eq!(kind(6, "Status"), Some(HighlightKind::Type));
+    assert_eq!(kind(7, "reserved"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(7, "to"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(7, "max"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(9, "STATUS_OK"), Some(HighlightKind::EnumMember));
+    assert_eq!(kind(10, "Greeter"), Some(HighlightKind::Type));
+    assert_eq!(kind(11, "Greet"), Some(HighlightKind::Function));
+    assert_eq!(kind(11, "returns"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(11, "stream"), Some(HighlightKind::KeywordControl));
+}
+
+#[test]
+fn test_lexical_highlight_carries_block_comment_into_requested_range() {
+    let src = "/* header\n   still comment */ message A {}\nmessage B {}\n";
+    let rope = Rope::from_str(src);
+
+    let lines = lexical_highlight_lines(LanguageId::Protobuf, &rope, 1, 3).expect("lexical");
+    assert_eq!(lines.len(), 2);
+    assert_eq!(
+        lexical_kind_at(&lines[0], "   still comment */ message A {}", "still"),
+        Some(HighlightKind::Comment)
+    );
+    assert_eq!(
+        lexical_kind_at(&lines[0], "   still comment */ message A {}", "message"),
+        Some(HighlightKind::Keyword)
+    );
+    assert_eq!(
+        lexical_kind_at(&lines[1], "message B {}", "B"),
+        Some(HighlightKind::Type)
+    );
+    assert!(lexical_highlight_lines(LanguageId::Rust, &rope, 0, 1).is_none());
+}
+
+#[test]
+fn test_highlight_groovy_gstrings_annotations_and_jenkins_dsl() {
+    let src = r#"@Library('shared') _
+class Builder extends Base implements Runnable {
+  def run(String name) {
+    println "Hello ${name}, $USER \$HOME"
+  }
+}
+def banner = """
+  build ${env.BUILD_ID}
+"""
+pipeline {
+  stages {
+    stage('Build') {
+      steps { sh 'make' }
+    }
+  }
+}
+"#;
+    assert!(SyntaxDocument::for_path(Path::new("Jenkinsfile"), &Rope::from_str(src)).is_none());
+
+    let spans = highlight_snippet(LanguageId::Groovy, src);
+    let lines: Vec<&str> = src.lines().collect();
+    let kind = |line: usize, needle: &str| lexical_kind_at(&spans[line], lines[line], needle);
+
+    assert_eq!(kind(0, "@Library"), Some(HighlightKind::Attribute));
+    assert_eq!(kind(1, "class"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(1, "Builder"), Some(HighlightKind::Type));
+    assert_eq!(kind(1, "extends"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(1, "Runnable"), Some(HighlightKind::Type));
+    assert_eq!(kind(2, "def"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(2, "run"), Some(HighlightKind::Function));
+    assert_eq!(kind(3, "\"Hello"), Some(HighlightKind::String));
+    assert_eq!(kind(3, "${name}"), Some(HighlightKind::Variable));
+    assert_eq!(kind(3, "$USER"), Some(HighlightKind::Variable));
+    assert_eq!(kind(3, "\\$HOME"), Some(HighlightKind::String));
+    assert_eq!(kind(7, "build"), Some(HighlightKind::String));
+    assert_eq!(kind(7, "${env"), Some(HighlightKind::Variable));
+    assert_eq!(kind(9, "pipeline"), Some(HighlightKind::Function));
+    assert_eq!(kind(11, "stage"), Some(HighlightKind::Function));
+    assert_eq!(kind(12, "sh"), Some(HighlightKind::Function));
+    assert_eq!(kind(12, "'make'"), Some(HighlightKind::String));
+}
+
+#[test]
+fn test_groovy_single_quoted_strings_do_not_interpolate() {
+    let line = "def s = '${literal}' + '''$also'''";
+    let spans = highlight_snippet(LanguageId::Groovy, line);
+    assert_eq!(
+        lexical_kind_at(&spans[0], line, "${literal}"),
+        Some(HighlightKind::String)
+    );
+    assert_eq!(
+        lexical_kind_at(&spans[0], line, "$also"),
+        Some(HighlightKind::String)
+    );
+}
+
+#[test]
+fn test_highlight_wat_keywords_instructions_and_comments() {
+    let src = r#"(module
+  ;; adds two numbers
+  (func $add (export "add") (param $a i32) (param $b i32) (result i32)
+    local.get $a
+    (i32.add (local.get $b) (i32.const 42)))
+  (; outer (; nested ;) still ;) (memory 1)
+  (func $loop (block (loop br 0)) drop)
+)
+"#;
+    assert!(SyntaxDocument::for_path(Path::new("a.wat"), &Rope::from_str(src)).is_none());
+
+    let spans = highlight_snippet(LanguageId::Wat, src);
+    let lines: Vec<&str> = src.lines().collect();
+    let kind = |line: usize, needle: &str| lexical_kind_at(&spans[line], lines[line], needle);
+
+    assert_eq!(kind(0, "module"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(1, ";; adds"), Some(HighlightKind::Comment));
+    assert_eq!(kind(2, "func"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(2, "$add"), Some(HighlightKind::Variable));
+    assert_eq!(kind(2, "export"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(2, "\"add\""), Some(HighlightKind::String));
+    assert_eq!(kind(2, "param"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(2, "i32"), Some(HighlightKind::TypeBuiltin));
+    assert_eq!(kind(2, "result"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(3, "local.get"), Some(HighlightKind::Function));
+    assert_eq!(kind(4, "i32.add"), Some(HighlightKind::Function));
+    assert_eq!(kind(4, "i32.const"), Some(HighlightKind::Function));
+    assert_eq!(kind(4, "42"), Some(HighlightKind::Number));
+    assert_eq!(kind(5, "still"), Some(HighlightKind::Comment));
+    assert_eq!(kind(5, "memory"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(6, "block"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(6, "loop br"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(6, "br 0"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(6, "drop"), Some(HighlightKind::Function));
+}
+
+#[test]
+fn test_highlight_rego_rules_keywords_and_builtins() {
+    let src = r#"package authz.rules
+
+import future.keywords.if
+import data.lib.utils
+
+# deny by default
+default allow := false
+
+allow if {
+    some role in input.user.roles
+    not blocked[role]
+    count(input.user.groups) > 0
+}
+
+deny contains msg if {
+    msg := sprintf("denied: %v", [input.user.name])
+}
+
+names := {n | some u in data.users; n := u.name}
+query := `raw
+string`
+"#;
+    let spans = highlight_snippet(LanguageId::Rego, src);
+    let lines: Vec<&str> = src.lines().collect();
+    let kind = |line: usize, needle: &str| lexical_kind_at(&spans[line], lines[line], needle);
+
+    assert_eq!(kind(0, "package"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(0, "authz"), Some(HighlightKind::Namespace));
+    assert_eq!(kind(3, "data"), Some(HighlightKind::Namespace));
+    assert_eq!(kind(5, "# deny"), Some(HighlightKind::Comment));
+    assert_eq!(kind(6, "default"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(6, "allow"), Some(HighlightKind::Function));
+    assert_eq!(kind(6, "false"), Some(HighlightKind::Boolean));
+    assert_eq!(kind(8, "allow"), Some(HighlightKind::Function));
+    assert_eq!(kind(8, "if"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(9, "some"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(9, "in "), Some(HighlightKind::KeywordOperator));
+    assert_eq!(kind(9, "input"), Some(HighlightKind::Variable));
+    assert_eq!(kind(9, "roles"), Some(HighlightKind::Property));
+    assert_eq!(kind(10, "not"), Some(HighlightKind::KeywordOperator));
+    assert_eq!(kind(11, "count"), Some(HighlightKind::Function));
+    assert_eq!(kind(14, "deny"), Some(HighlightKind::Function));
+    assert_eq!(kind(14, "contains"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(15, "sprintf"), Some(HighlightKind::Function));
+    assert_eq!(kind(15, "\"denied"), Some(HighlightKind::String));
+    assert_eq!(kind(18, "names"), Some(HighlightKind::Function));
+    assert_eq!(kind(18, "data"), Some(HighlightKind::Variable));
+    assert_eq!(kind(19, "`raw"), Some(HighlightKind::String));
+    assert_eq!(kind(20, "string`"), Some(HighlightKind::String));
+}
+
+#[test]
+fn test_highlight_makefile_rules_variables_and_functions() {
+    let src = "# build rules
+CC := gcc
+SRCS ?= $(wildcard src/*.c)
+OBJS = $(patsubst %.c,%.o,$(SRCS))
+export PATH += /opt/bin
+
+.PHONY: all clean
+all: app
+
+%.o: %.c
+\t$(CC) -c $< -o $@ # compile
+\t@echo done
+
+ifeq ($(DEBUG),1)
+endif
+";
+    let spans = highlight_snippet(LanguageId::Makefile, src);
+    let lines: Vec<&str> = src.lines().collect();
+    let kind = |line: usize, needle: &str| lexical_kind_at(&spans[line], lines[line], needle);
+
+    assert_eq!(kind(0, "# build"), Some(HighlightKind::Comment));
+    assert_eq!(kind(1, "CC"), Some(HighlightKind::Variable));
+    assert_eq!(kind(1, ":="), Some(HighlightKind::Operator));
+    assert_eq!(kind(1, "gcc"), None);
+    assert_eq!(kind(2, "?="), Some(HighlightKind::Operator));
+    assert_eq!(kind(2, "$(wildcard"), Some(HighlightKind::Function));
+    assert_eq!(kind(3, "$(patsubst"), Some(HighlightKind::Function));
+    assert_eq!(kind(3, "%.c"), None);
+    assert_eq!(kind(3, "$(SRCS)"), Some(HighlightKind::Variable));
+    assert_eq!(kind(4, "export"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(4, "PATH"), Some(HighlightKind::Variable));
+    assert_eq!(kind(4, "+="), Some(HighlightKind::Operator));
+    assert_eq!(kind(6, ".PHONY"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(6, "all"), None);
+    assert_eq!(kind(7, "all"), Some(HighlightKind::Function));
+    assert_eq!(kind(7, "app"), None);
+    assert_eq!(kind(9, "%.o"), Some(HighlightKind::Function));
+    assert_eq!(kind(10, "$(CC)"), Some(HighlightKind::Variable));
+    assert_eq!(kind(10, "$<"), Some(HighlightKind::Constant));
+    assert_eq!(kind(10, "$@"), Some(HighlightKind::Constant));
+    assert_eq!(kind(11, "@echo"), None);
+    assert_eq!(kind(13, "ifeq"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(13, "$(DEBUG)"), Some(HighlightKind::Variable));
+    assert_eq!(kind(14, "endif"), Some(HighlightKind::KeywordControl));
+}
+
+#[test]
+fn test_highlight_dockerfile_instructions_comments_and_variables() {
+    let src = r#"# syntax=docker/dockerfile:1
+ARG BASE=alpine
+FROM ${BASE}:3.19 AS builder
+ENV APP_HOME="/srv/${APP_NAME}/app" MODE='${RAW}'
+RUN apk add --no-cache \
+    curl \
+    user
+WORKDIR $APP_HOME
+onbuild COPY . /src
+CMD ["./app", "--port", "8080"] # not a comment
+"#;
+    let spans = highlight_snippet(LanguageId::Dockerfile, src);
+    let lines: Vec<&str> = src.lines().collect();
+    let kind = |line: usize, needle: &str| lexical_kind_at(&spans[line], lines[line], needle);
+
+    assert_eq!(kind(0, "# syntax"), Some(HighlightKind::Comment));
+    assert_eq!(kind(1, "ARG"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(1, "BASE"), None);
+    assert_eq!(kind(2, "FROM"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(2, "${BASE}"), Some(HighlightKind::Variable));
+    assert_eq!(kind(2, ":3.19"), None);
+    assert_eq!(kind(2, "AS builder"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(2, "builder"), None);
+    assert_eq!(kind(3, "ENV"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(3, "\"/srv/"), Some(HighlightKind::String));
+    assert_eq!(kind(3, "${APP_NAME}"), Some(HighlightKind::Variable));
+    assert_eq!(kind(3, "/app\""), Some(HighlightKind::String));
+    assert_eq!(kind(3, "'${RAW}'"), Some(HighlightKind::String));
+    assert_eq!(kind(4, "RUN"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(5, "curl"), None);
+    // 续行上的 `user` 是包名，不是 USER 指令。
+    assert_eq!(kind(6, "user"), None);
+    assert_eq!(kind(7, "WORKDIR"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(7, "$APP_HOME"), Some(HighlightKind::Variable));
+    assert_eq!(kind(8, "onbuild"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(8, "COPY"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(9, "CMD"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(9, "\"./app\""), Some(HighlightKind::String));
+    assert_eq!(kind(9, "# not"), None);
+}
+
+#[test]
+fn test_highlight_solidity_contracts_functions_and_events() {
+    let src = r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+contract Token {
+    error Unauthorized(address caller);
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    mapping(address => uint256) private balances;
+
+    function transfer(address to, uint256 amount) external returns (bool) {
+        require(amount > 0, "zero amount");
+        if (to == address(0)) revert Unauthorized(msg.sender);
+        balances[to] += amount;
+        emit Transfer(msg.sender, to, amount);
+        return true;
+    }
+}
+"#;
+    let spans = highlight_snippet(LanguageId::Solidity, src);
+    let lines: Vec<&str> = src.lines().collect();
+    let kind = |line: usize, needle: &str| lexical_kind_at(&spans[line], lines[line], needle);
+
+    assert_eq!(kind(0, "// SPDX"), Some(HighlightKind::Comment));
+    assert_eq!(kind(1, "pragma"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(3, "contract"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(3, "Token"), Some(HighlightKind::Type));
+    assert_eq!(kind(4, "Unauthorized"), Some(HighlightKind::Function));
+    assert_eq!(kind(4, "address"), Some(HighlightKind::TypeBuiltin));
+    assert_eq!(kind(5, "Transfer"), Some(HighlightKind::Function));
+    assert_eq!(kind(5, "uint256"), Some(HighlightKind::TypeBuiltin));
+    assert_eq!(kind(6, "mapping"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(6, "private"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(8, "function"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(8, "transfer"), Some(HighlightKind::Function));
+    assert_eq!(kind(8, "external"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(9, "require"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(9, "\"zero"), Some(HighlightKind::String));
+    assert_eq!(kind(10, "if"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(10, "revert"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(10, "Unauthorized"), Some(HighlightKind::Function));
+    assert_eq!(kind(12, "emit"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(12, "Transfer"), Some(HighlightKind::Function));
+    assert_eq!(kind(13, "return"), Some(HighlightKind::KeywordControl));
+}
+
+#[test]
+fn test_highlight_cairo_items_attributes_and_literals() {
+    let src = r#"// Simple counter contract
+use starknet::storage::Map;
+
+#[starknet::contract]
+pub mod counter {
+    const MAX: u256 = 0x100_u256;
+
+    #[derive(Drop, Serde)]
+    struct Point { x: felt252, y: u32 }
+
+    impl PointImpl of PointTrait {
+        fn bump(ref self: Point, by: felt252) -> bool {
+            let total = self.x + by + 10_felt252;
+            loop {
+                if total == 'done' { break; } else { return true; }
+            };
+            assert!(total != 0, "overflow");
+            match self.y { _ => core::panic_with_felt252(0) }
+        }
+    }
+}
+"#;
+    let spans = highlight_snippet(LanguageId::Cairo, src);
+    let lines: Vec<&str> = src.lines().collect();
+    let kind = |line: usize, needle: &str| lexical_kind_at(&spans[line], lines[line], needle);
+
+    assert_eq!(kind(0, "// Simple"), Some(HighlightKind::Comment));
+    assert_eq!(kind(1, "use"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(1, "starknet"), Some(HighlightKind::Namespace));
+    assert_eq!(kind(1, "storage"), Some(HighlightKind::Namespace));
+    assert_eq!(kind(1, "Map"), Some(HighlightKind::Type));
+    assert_eq!(
+        kind(3, "#[starknet::contract]"),
+        Some(HighlightKind::Attribute)
+    );
+    assert_eq!(kind(4, "pub"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(4, "mod"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(4, "counter"), Some(HighlightKind::Namespace));
+    assert_eq!(kind(5, "const"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(5, "MAX"), Some(HighlightKind::Constant));
+    assert_eq!(kind(5, "u256 ="), Some(HighlightKind::TypeBuiltin));
+    assert_eq!(kind(5, "0x100_u256"), Some(HighlightKind::Number));
+    assert_eq!(
+        kind(7, "#[derive(Drop, Serde)]"),
+        Some(HighlightKind::Attribute)
+    );
+    assert_eq!(kind(8, "struct"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(8, "Point"), Some(HighlightKind::Type));
+    assert_eq!(kind(8, "felt252"), Some(HighlightKind::TypeBuiltin));
+    assert_eq!(kind(10, "impl"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(10, "PointImpl"), Some(HighlightKind::Type));
+    assert_eq!(kind(10, "of"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(10, "PointTrait"), Some(HighlightKind::Type));
+    assert_eq!(kind(11, "fn"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(11, "bump"), Some(HighlightKind::Function));
+    assert_eq!(kind(11, "ref"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(11, "bool"), Some(HighlightKind::TypeBuiltin));
+    assert_eq!(kind(12, "let"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(12, "10_felt252"), Some(HighlightKind::Number));
+    assert_eq!(kind(13, "loop"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(14, "if"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(14, "'done'"), Some(HighlightKind::String));
+    assert_eq!(kind(14, "else"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(14, "return"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(14, "true"), Some(HighlightKind::Boolean));
+    assert_eq!(kind(16, "assert"), Some(HighlightKind::Macro));
+    assert_eq!(kind(16, "\"overflow\""), Some(HighlightKind::String));
+    assert_eq!(kind(17, "match"), Some(HighlightKind::KeywordControl));
+    assert_eq!(
+        kind(17, "panic_with_felt252"),
+        Some(HighlightKind::Function)
+    );
+}
+
+#[test]
+fn test_highlight_markdown_blocks_and_inline_spans() {
+    let src = r#"# Title here
+> quoted **bold** text
+- [x] see [docs](https://example.com) and `code`
+```rust
+let x = *y;
+```
+1. *emphasis* but snake_case_name
+<!-- note -->
+---
+Sub title
+=========
+![logo](logo.png "Logo") [ref][label] <https://example.com>
+"#;
+    let spans = highlight_snippet(LanguageId::Markdown, src);
+    let lines: Vec<&str> = src.lines().collect();
+    let kind = |line: usize, needle: &str| lexical_kind_at(&spans[line], lines[line], needle);
+
+    assert_eq!(kind(0, "#"), Some(HighlightKind::Operator));
+    assert_eq!(kind(0, "Title"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(1, ">"), Some(HighlightKind::Operator));
+    assert_eq!(kind(1, "**bold**"), Some(HighlightKind::Constant));
+    assert_eq!(kind(1, "text"), None);
+    assert_eq!(kind(2, "-"), Some(HighlightKind::Operator));
+    assert_eq!(kind(2, "[x]"), Some(HighlightKind::Operator));
+    assert_eq!(kind(2, "[docs]"), Some(HighlightKind::Tag));
+    assert_eq!(kind(2, "https"), Some(HighlightKind::TagAttribute));
+    assert_eq!(kind(2, "`code`"), Some(HighlightKind::String));
+    assert_eq!(kind(3, "```"), Some(HighlightKind::Operator));
+    assert_eq!(kind(3, "rust"), Some(HighlightKind::Type));
+    // 围栏内是原样代码，`*y` 不是强调。
+    assert_eq!(kind(4, "let"), Some(HighlightKind::String));
+    assert_eq!(kind(4, "*y"), Some(HighlightKind::String));
+    assert_eq!(kind(5, "```"), Some(HighlightKind::Operator));
+    assert_eq!(kind(6, "1."), Some(HighlightKind::Operator));
+    assert_eq!(kind(6, "*emphasis*"), Some(HighlightKind::Parameter));
+    assert_eq!(kind(6, "_case"), None);
+    assert_eq!(kind(7, "<!--"), Some(HighlightKind::Comment));
+    assert_eq!(kind(8, "---"), Some(HighlightKind::Operator));
+    assert_eq!(kind(9, "Sub"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(10, "==="), Some(HighlightKind::Operator));
+    assert_eq!(kind(11, "![logo]"), Some(HighlightKind::Tag));
+    assert_eq!(kind(11, "logo.png"), Some(HighlightKind::TagAttribute));
+    assert_eq!(kind(11, "\"Logo\""), Some(HighlightKind::String));
+    assert_eq!(kind(11, "[ref]"), Some(HighlightKind::Tag));
+    assert_eq!(kind(11, "label"), Some(HighlightKind::TagAttribute));
+    assert_eq!(kind(11, "https"), Some(HighlightKind::TagAttribute));
+}
+
+#[test]
+fn test_markdown_syntax_document_reparses_code_fence_incrementally() {
+    let src = "intro\n~~~\n# not a heading\n~~~\n# heading\n";
+    let mut rope = Rope::from_str(src);
+    let mut doc = SyntaxDocument::for_path(Path::new("README.md"), &rope).expect("md syntax");
+    let lines = highlight_lines(&doc, &rope, 2, 5);
+    assert_eq!(lines[0][0].kind, HighlightKind::String);
+    assert_eq!(lines[1][0].kind, HighlightKind::Operator);
+    assert_eq!(lines[2][1].kind, HighlightKind::Keyword);
+
+    // 删掉开围栏后，原先的代码行变成真正的标题。
+    let start = src.find("~~~").unwrap();
+    let op = EditOp::delete(
+        OpId::root(),
+        start,
+        start + 4,
+        CompactString::new("~~~\n"),
+        (1, 0),
+        (1, 0),
+    );
+    op.apply(&mut rope);
+    let delta = doc.apply_edit(&rope, &op);
+    assert!(!delta.reparsed);
+
+    let lines = highlight_lines(&doc, &rope, 1, 2);
+    assert_eq!(lines[0][0].kind, HighlightKind::Operator);
+    assert_eq!(lines[0][1].kind, HighlightKind::Keyword);
+}
+
+#[test]
+fn test_highlight_ruby_definitions_sigils_and_block_comments() {
+    let src = r##"# User model
+require "json"
+
+=begin
+block docs
+=end
+module Accounts
+  class User < Base
+    MAX_NAME = 64
+
+    def full_name(prefix)
+      return nil unless @first
+      if valid? then "#{prefix} #{@first}" else $default end
+    end
+  end
+end
+"##;
+    let spans = highlight_snippet(LanguageId::Ruby, src);
+    let lines: Vec<&str> = src.lines().collect();
+    let kind = |line: usize, needle: &str| lexical_kind_at(&spans[line], lines[line], needle);
+
+    assert_eq!(kind(0, "# User"), Some(HighlightKind::Comment));
+    assert_eq!(kind(1, "require"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(1, "\"json\""), Some(HighlightKind::String));
+    assert_eq!(kind(3, "=begin"), Some(HighlightKind::Comment));
+    assert_eq!(kind(4, "block"), Some(HighlightKind::Comment));
+    assert_eq!(kind(5, "=end"), Some(HighlightKind::Comment));
+    assert_eq!(kind(6, "module"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(6, "Accounts"), Some(HighlightKind::Namespace));
+    assert_eq!(kind(7, "User"), Some(HighlightKind::Type));
+    assert_eq!(kind(7, "Base"), Some(HighlightKind::Type));
+    assert_eq!(kind(8, "MAX_NAME"), Some(HighlightKind::Constant));
+    assert_eq!(kind(8, "64"), Some(HighlightKind::Number));
+    assert_eq!(kind(10, "def"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(10, "full_name"), Some(HighlightKind::Function));
+    assert_eq!(kind(11, "return"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(11, "nil"), Some(HighlightKind::Constant));
+    assert_eq!(kind(11, "unless"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(11, "@first"), Some(HighlightKind::Variable));
+    assert_eq!(kind(12, "valid?"), None);
+    assert_eq!(kind(12, "then"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(12, "$default"), Some(HighlightKind::Variable));
+    assert_eq!(kind(13, "end"), Some(HighlightKind::Keyword));
+}
+
+#[test]
+fn test_highlight_lua_functions_tables_and_long_brackets() {
+    let src = r#"-- config
+local M = {}
+--[[ long
+comment ]]
+local DEFAULT_WIDTH = 80
+
+function M.setup(opts)
+  local text = [[multi
+line]]
+  for k, v in pairs(opts) do
+    if v == nil then break end
+    M.options[k] = v
+  end
+  return self:render("done", true)
+end
+"#;
+    let spans = highlight_snippet(LanguageId::Lua, src);
+    let lines: Vec<&str> = src.lines().collect();
+    let kind = |line: usize, needle: &str| lexical_kind_at(&spans[line], lines[line], needle);
+
+    assert_eq!(kind(0, "-- config"), Some(HighlightKind::Comment));
+    assert_eq!(kind(1, "local"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(2, "--[[ long"), Some(HighlightKind::Comment));
+    assert_eq!(kind(3, "comment"), Some(HighlightKind::Comment));
+    assert_eq!(kind(4, "DEFAULT_WIDTH"), Some(HighlightKind::Constant));
+    assert_eq!(kind(4, "80"), Some(HighlightKind::Number));
+    assert_eq!(kind(6, "function"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(6, "setup"), Some(HighlightKind::Method));
+    assert_eq!(kind(7, "[[multi"), Some(HighlightKind::String));
+    assert_eq!(kind(8, "line"), Some(HighlightKind::String));
+    assert_eq!(kind(9, "for"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(9, "pairs"), Some(HighlightKind::Function));
+    assert_eq!(kind(9, "do"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(10, "nil"), Some(HighlightKind::Constant));
+    assert_eq!(kind(10, "break"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(11, "options"), Some(HighlightKind::Property));
+    assert_eq!(kind(13, "return"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(13, "self"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(13, "render"), Some(HighlightKind::Method));
+    assert_eq!(kind(13, "\"done\""), Some(HighlightKind::String));
+    assert_eq!(kind(13, "true"), Some(HighlightKind::Boolean));
+}
+
+#[test]
+fn test_highlight_elixir_modules_atoms_and_sigils() {
+    let src = r#"defmodule Accounts.User do
+  @moduledoc """
+  Users.
+  """
+  alias Accounts.Repo
+
+  def fetch!(id, opts \\ []) do
+    case Repo.get(id) do
+      nil -> {:error, :not_found}
+      user -> {:ok, user.name, ~r/^[a-z]+$/i, ~w[a b]}
+    end
+  end
+end
+"#;
+    let spans = highlight_snippet(LanguageId::Elixir, src);
+    let lines: Vec<&str> = src.lines().collect();
+    let kind = |line: usize, needle: &str| lexical_kind_at(&spans[line], lines[line], needle);
+
+    assert_eq!(kind(0, "defmodule"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(0, "Accounts"), Some(HighlightKind::Namespace));
+    assert_eq!(kind(0, "User"), Some(HighlightKind::Namespace));
+    assert_eq!(kind(1, "@moduledoc"), Some(HighlightKind::Attribute));
+    assert_eq!(kind(1, "\"\"\""), Some(HighlightKind::String));
+    assert_eq!(kind(2, "Users"), Some(HighlightKind::String));
+    assert_eq!(kind(4, "alias"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(4, "Repo"), Some(HighlightKind::Namespace));
+    assert_eq!(kind(6, "fetch!"), Some(HighlightKind::Function));
+    assert_eq!(kind(7, "case"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(7, "get"), Some(HighlightKind::Function));
+    assert_eq!(kind(8, "nil"), Some(HighlightKind::Constant));
+    assert_eq!(kind(8, ":error"), Some(HighlightKind::Constant));
+    assert_eq!(kind(8, ":not_found"), Some(HighlightKind::Constant));
+    assert_eq!(kind(9, ":ok"), Some(HighlightKind::Constant));
+    assert_eq!(kind(9, "name"), Some(HighlightKind::Property));
+    assert_eq!(kind(9, "~r/^[a-z]+$/i"), Some(HighlightKind::String));
+    assert_eq!(kind(9, "~w[a b]"), Some(HighlightKind::String));
+    assert_eq!(kind(11, "end"), Some(HighlightKind::Keyword));
+}
+
+#[test]
+fn test_highlight_elixir_keyword_lists_and_type_specs_are_not_atoms() {
+    let src = "@spec run(opts :: keyword) :: :ok\nrun(timeout: 5, mode: :fast)\n";
+    let spans = highlight_snippet(LanguageId::Elixir, src);
+    let lines: Vec<&str> = src.lines().collect();
+    let kind = |line: usize, needle: &str| lexical_kind_at(&spans[line], lines[line], needle);
+
+    assert_eq!(kind(0, "@spec"), Some(HighlightKind::Attribute));
+    assert_eq!(kind(0, "keyword"), None);
+    assert_eq!(kind(0, ":ok"), Some(HighlightKind::Constant));
+    assert_eq!(kind(1, "timeout"), None);
+    assert_eq!(kind(1, ":fast"), Some(HighlightKind::Constant));
+}
+
+#[test]
+fn test_highlight_erlang_attributes_variables_and_atoms() {
+    let src = r#"%% server
+-module(app_server).
+-export([start/1]).
+
+start(Name) when is_atom(Name) ->
+    case lists:member(Name, ?MODULE:names()) of
+        true -> {ok, Name};
+        false -> {error, 'not found'}
+    end.
+"#;
+    let spans = highlight_snippet(LanguageId::Erlang, src);
+    let lines: Vec<&str> = src.lines().collect();
+    let kind = |line: usize, needle: &str| lexical_kind_at(&spans[line], lines[line], needle);
+
+    assert_eq!(kind(0, "%% server"), Some(HighlightKind::Comment));
+    assert_eq!(kind(1, "module"), Some(HighlightKind::Attribute));
+    assert_eq!(kind(1, "app_server"), Some(HighlightKind::Constant));
+    assert_eq!(kind(2, "export"), Some(HighlightKind::Attribute));
+    assert_eq!(kind(4, "start"), Some(HighlightKind::Function));
+    assert_eq!(kind(4, "Name"), Some(HighlightKind::Variable));
+    assert_eq!(kind(4, "when"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(5, "lists"), Some(HighlightKind::Namespace));
+    assert_eq!(kind(5, "member"), Some(HighlightKind::Function));
+    assert_eq!(kind(5, "MODULE"), Some(HighlightKind::Constant));
+    assert_eq!(kind(6, "true"), Some(HighlightKind::Boolean));
+    assert_eq!(kind(6, "ok"), Some(HighlightKind::Constant));
+    assert_eq!(kind(7, "'not found'"), Some(HighlightKind::String));
+    assert_eq!(kind(8, "end"), Some(HighlightKind::Keyword));
+}
+
+#[test]
+fn breadcrumbs_at_lists_enclosing_rust_scopes_outermost_first() {
+    let src = "mod net {\n    impl Display for Addr {\n        fn fmt(&self) {\n            let x = 1;\n        }\n    }\n}\n";
+    let rope = Rope::from_str(src);
+    let doc = SyntaxDocument::for_path(Path::new("a.rs"), &rope).unwrap();
+
+    let crumbs = doc.breadcrumbs_at(&rope, src.find("let x").unwrap());
+    let labels: Vec<&str> = crumbs.iter().map(|c| c.label.as_str()).collect();
+    assert_eq!(labels, vec!["mod net", "impl Display for Addr", "fn fmt"]);
+    let fn_start = src.find("fn fmt").unwrap();
+    assert_eq!(crumbs[2].start_char, fn_start);
+    assert_eq!(&src[crumbs[2].end_char - 1..crumbs[2].end_char], "}");
+
+    assert!(doc.breadcrumbs_at(&rope, src.len()).is_empty());
+}
+
+#[test]
+fn breadcrumbs_at_names_python_classes_and_functions() {
+    let src = "class Greeter:\n    def hello(self):\n        return 1\n";
+    let rope = Rope::from_str(src);
+    let doc = SyntaxDocument::for_path(Path::new("a.py"), &rope).unwrap();
+
+    let labels: Vec<String> = doc
+        .breadcrumbs_at(&rope, src.find("return").unwrap())
+        .into_iter()
+        .map(|c| c.label)
+        .collect();
+    assert_eq!(labels, vec!["class Greeter", "fn hello"]);
+}
+
+#[test]
+fn test_highlight_kotlin_declarations_and_templates() {
+    let src = r#"package app.core
+
+@Serializable
+data class User(val id: Int, var name: String?) {
+    companion object {
+        const val MAX = 3
+    }
+
+    fun greet(): String = "Hi ${name}"
+}
+
+fun main() {
+    val users = listOf(User(1, null))
+    when (users.size) {
+        0 -> return
+        else -> users.forEach { println(it.name) }
+    }
+}
+"#;
+    let spans = highlight_snippet(LanguageId::Kotlin, src);
+    let lines: Vec<&str> = src.lines().collect();
+    let kind = |line: usize, needle: &str| lexical_kind_at(&spans[line], lines[line], needle);
+
+    assert_eq!(kind(0, "package"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(0, "app"), Some(HighlightKind::Namespace));
+    assert_eq!(kind(2, "@Serializable"), Some(HighlightKind::Attribute));
+    assert_eq!(kind(3, "data"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(3, "User"), Some(HighlightKind::Type));
+    assert_eq!(kind(3, "val"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(3, "var"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(3, "Int"), Some(HighlightKind::Type));
+    assert_eq!(kind(4, "companion"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(4, "object"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(5, "MAX"), Some(HighlightKind::Constant));
+    assert_eq!(kind(5, "3"), Some(HighlightKind::Number));
+    assert_eq!(kind(8, "fun"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(8, "greet"), Some(HighlightKind::Function));
+    assert_eq!(kind(8, "\"Hi "), Some(HighlightKind::String));
+    assert_eq!(kind(12, "listOf"), Some(HighlightKind::Function));
+    assert_eq!(kind(12, "null"), Some(HighlightKind::Constant));
+    assert_eq!(kind(13, "when"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(13, "size"), Some(HighlightKind::Property));
+    assert_eq!(kind(14, "return"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(15, "forEach"), Some(HighlightKind::Method));
+}
+
+#[test]
+fn test_highlight_swift_declarations_and_nested_comments() {
+    let src = r#"import SwiftUI
+
+/* outer /* inner */ still comment */
+@MainActor
+actor Store {
+    private var items: [Item] = []
+
+    func load(id: Int) async throws -> Item? {
+        guard let item = items.first(where: { $0.id == id }) else { return nil }
+        return item
+    }
+}
+"#;
+    let spans = highlight_snippet(LanguageId::Swift, src);
+    let lines: Vec<&str> = src.lines().collect();
+    let kind = |line: usize, needle: &str| lexical_kind_at(&spans[line], lines[line], needle);
+
+    assert_eq!(kind(0, "import"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(0, "SwiftUI"), Some(HighlightKind::Namespace));
+    assert_eq!(kind(2, "still"), Some(HighlightKind::Comment));
+    assert_eq!(kind(3, "@MainActor"), Some(HighlightKind::Attribute));
+    assert_eq!(kind(4, "actor"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(4, "Store"), Some(HighlightKind::Type));
+    assert_eq!(kind(5, "var"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(5, "Item"), Some(HighlightKind::Type));
+    assert_eq!(kind(7, "func"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(7, "load"), Some(HighlightKind::Function));
+    assert_eq!(kind(7, "async"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(8, "guard"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(8, "let"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(8, "first"), Some(HighlightKind::Method));
+    assert_eq!(kind(8, "nil"), Some(HighlightKind::Constant));
+    assert_eq!(kind(9, "return"), Some(HighlightKind::KeywordControl));
+}
+
+#[test]
+fn test_highlight_nix_keywords_attrsets_and_indented_strings() {
+    let src = r#"{ pkgs ? import <nixpkgs> {} }:
+# shell for hacking
+let
+  inherit (pkgs) lib;
+  name = "demo-${lib.version}";
+in rec {
+  shellHook = ''
+    echo hi
+  '';
+  enable = if builtins.pathExists ./x then true else null;
+  env = with pkgs; [ hello ];
+}
+"#;
+    let spans = highlight_snippet(LanguageId::Nix, src);
+    let lines: Vec<&str> = src.lines().collect();
+    let kind = |line: usize, needle: &str| lexical_kind_at(&spans[line], lines[line], needle);
+
+    assert_eq!(kind(0, "import"), Some(HighlightKind::Function));
+    assert_eq!(kind(1, "shell"), Some(HighlightKind::Comment));
+    assert_eq!(kind(2, "let"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(3, "inherit"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(4, "name"), Some(HighlightKind::Property));
+    assert_eq!(kind(4, "\"demo"), Some(HighlightKind::String));
+    assert_eq!(kind(5, "in"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(5, "rec"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(7, "echo"), Some(HighlightKind::String));
+    assert_eq!(kind(9, "if"), Some(HighlightKind::KeywordControl));
+    assert_eq!(kind(9, "builtins"), Some(HighlightKind::Namespace));
+    assert_eq!(kind(9, "pathExists"), Some(HighlightKind::Property));
+    assert_eq!(kind(9, "true"), Some(HighlightKind::Boolean));
+    assert_eq!(kind(9, "null"), Some(HighlightKind::Constant));
+    assert_eq!(kind(10, "with"), Some(HighlightKind::Keyword));
+}
+
+#[test]
+fn test_highlight_dhall_keywords_builtin_types_and_nested_comments() {
+    let src = r#"{- outer {- inner -} still comment -}
+let Config = { name : Text, port : Natural, debug : Bool }
+let render = \(c : Config) -> "${c.name}:${Natural/show c.port}"
+in  assert : merge { Some = \(x : Text) -> x, None = "" } (Some "a") === "a"
+-- forall (a : Type) -> List a
+"#;
+    let spans = highlight_snippet(LanguageId::Dhall, src);
+    let lines: Vec<&str> = src.lines().collect();
+    let kind = |line: usize, needle: &str| lexical_kind_at(&spans[line], lines[line], needle);
+
+    assert_eq!(kind(0, "still"), Some(HighlightKind::Comment));
+    assert_eq!(kind(1, "let"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(1, "Config"), Some(HighlightKind::Type));
+    assert_eq!(kind(1, "Text"), Some(HighlightKind::TypeBuiltin));
+    assert_eq!(kind(1, "Natural"), Some(HighlightKind::TypeBuiltin));
+    assert_eq!(kind(1, "Bool"), Some(HighlightKind::TypeBuiltin));
+    assert_eq!(kind(2, "\"${"), Some(HighlightKind::String));
+    assert_eq!(kind(3, "in"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(3, "assert"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(3, "merge"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(3, "Some"), Some(HighlightKind::Keyword));
+    assert_eq!(kind(3, "None"), Some(HighlightKind::Constant));
+    assert_eq!(kind(4, "forall"), Some(HighlightKind::Comment));
+}
+
+#[test]
+fn test_highlight_dhall_builtin_functions_outside_strings() {
+    let src = "let n = Natural/show 3 in List/length Text [ n ]\n";
+    let spans = highlight_snippet(LanguageId::Dhall, src);
+    let line = src.lines().next().unwrap();
+
+    assert_eq!(
+        lexical_kind_at(&spans[0], line, "Natural/show"),
+        Some(HighlightKind::Function)
+    );
+    assert_eq!(
+        lexical_kind_at(&spans[0], line, "List/length"),
+        Some(HighlightKind::Function)
+    );
+    assert_eq!(
+        lexical_kind_at(&spans[0], line, "Text"),
+        Some(HighlightKind::TypeBuiltin)
+    );
+}
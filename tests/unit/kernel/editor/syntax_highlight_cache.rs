@@ -1,4 +1,5 @@
 use super::*;
+use crate::kernel::editor::syntax::SyntaxEditDelta;
 use crate::kernel::editor::{HighlightKind, HighlightSpan};
 use ropey::Rope;
 use std::sync::Arc;
@@ -170,3 +171,54 @@ fn dirty_segments_with_budget_advances_after_patch() {
     let second = cache.dirty_segments_with_budget(0, 3);
     assert_eq!(second, vec![(3, 6)]);
 }
+
+#[test]
+fn invalidate_ranges_marks_only_changed_lines() {
+    let rope = Rope::from_str("a\nb\nc");
+    let mut cache = AsyncSyntaxHighlightCache::new_for_rope(&rope);
+    cache.apply_patch(0, vec![dummy_span(), dummy_span(), dummy_span()]);
+
+    let line2 = rope.line_to_byte(2);
+    let delta = SyntaxEditDelta {
+        input_edit: Some(InputEdit {
+            start_byte: 2,
+            old_end_byte: 3,
+            new_end_byte: 3,
+            start_position: Point { row: 1, column: 0 },
+            old_end_position: Point { row: 1, column: 1 },
+            new_end_position: Point { row: 1, column: 1 },
+        }),
+        changed_ranges: vec![Range {
+            start_byte: line2,
+            end_byte: line2 + 1,
+            start_point: Point { row: 2, column: 0 },
+            end_point: Point { row: 2, column: 1 },
+        }],
+        reparsed: false,
+    };
+    cache.invalidate_ranges(&rope, &delta);
+
+    assert!(!cache.is_line_dirty(0));
+    assert!(cache.is_line_dirty(1));
+    assert!(cache.is_line_dirty(2));
+    assert!(cache.line(0).is_some());
+    assert!(cache.line(2).is_some());
+}
+
+#[test]
+fn invalidate_ranges_on_reparse_keeps_spans_but_marks_all_dirty() {
+    let rope = Rope::from_str("a\nb");
+    let mut cache = AsyncSyntaxHighlightCache::new_for_rope(&rope);
+    cache.apply_patch(0, vec![dummy_span(), dummy_span()]);
+    let before = cache.line(0).cloned().expect("line 0 spans");
+
+    let delta = SyntaxEditDelta {
+        input_edit: None,
+        changed_ranges: Vec::new(),
+        reparsed: true,
+    };
+    cache.invalidate_ranges(&rope, &delta);
+
+    assert_eq!(cache.dirty_segments(), vec![(0, 2)]);
+    assert!(Arc::ptr_eq(cache.line(0).expect("line 0 spans"), &before));
+}
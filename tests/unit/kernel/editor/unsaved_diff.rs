@@ -0,0 +1,92 @@
+use super::*;
+use crate::models::EditOp;
+use compact_str::CompactString;
+
+fn edit(rope: &mut Rope, history: &mut EditHistory, start: usize, end: usize, inserted: &str) {
+    let deleted = rope.slice(start..end).to_string();
+    let op = EditOp::replace(
+        history.head(),
+        start,
+        end,
+        CompactString::new(deleted),
+        CompactString::new(inserted),
+        (0, 0),
+        (0, 0),
+    );
+    op.apply(rope);
+    history.push(op);
+}
+
+fn marks_after(saved: &str, edits: &[(usize, usize, &str)]) -> Vec<(usize, GutterMarkKind)> {
+    let mut rope = Rope::from_str(saved);
+    let mut history = EditHistory::new();
+    for (start, end, inserted) in edits {
+        edit(&mut rope, &mut history, *start, *end, inserted);
+    }
+    unsaved_marks(&history, &rope)
+}
+
+#[test]
+fn clean_buffer_has_no_marks() {
+    assert!(marks_after("a\nb\n", &[]).is_empty());
+}
+
+#[test]
+fn inserted_lines_are_added() {
+    // "a\nb\n" → "a\nx\ny\nb\n"
+    assert_eq!(
+        marks_after("a\nb\n", &[(2, 2, "x\ny\n")]),
+        vec![(1, GutterMarkKind::Added), (2, GutterMarkKind::Added)]
+    );
+}
+
+#[test]
+fn changed_line_is_modified() {
+    // "a\nb\nc\n" → "a\nB\nc\n"
+    assert_eq!(
+        marks_after("a\nb\nc\n", &[(2, 3, "B")]),
+        vec![(1, GutterMarkKind::Modified)]
+    );
+}
+
+#[test]
+fn deleted_lines_mark_the_following_line() {
+    // "a\nb\nc\nd\n" → "a\nd\n"
+    assert_eq!(
+        marks_after("a\nb\nc\nd\n", &[(2, 6, "")]),
+        vec![(1, GutterMarkKind::Deleted)]
+    );
+}
+
+#[test]
+fn replacing_one_line_with_two_is_modified_then_added() {
+    // "a\nb\nc\n" → "a\nB1\nB2\nc\n"
+    assert_eq!(
+        marks_after("a\nb\nc\n", &[(2, 3, "B1\nB2")]),
+        vec![(1, GutterMarkKind::Modified), (2, GutterMarkKind::Added)]
+    );
+}
+
+#[test]
+fn separate_hunks_get_separate_marks() {
+    // "a\nb\nc\nd\ne\n" → "A\nb\nc\ne\n"
+    assert_eq!(
+        marks_after("a\nb\nc\nd\ne\n", &[(6, 8, ""), (0, 1, "A")]),
+        vec![(0, GutterMarkKind::Modified), (3, GutterMarkKind::Deleted)]
+    );
+}
+
+#[test]
+fn edit_reverted_by_hand_leaves_no_marks() {
+    assert!(marks_after("a\nb\n", &[(2, 3, "x"), (2, 3, "b")]).is_empty());
+}
+
+#[test]
+fn myers_finds_the_shortest_script() {
+    let lines = |s: &str| s.chars().map(String::from).collect::<Vec<_>>();
+    let steps = myers_steps(&lines("abcabba"), &lines("cbabac")).unwrap();
+    let edits = steps.iter().filter(|s| **s != Step::Equal).count();
+    assert_eq!(edits, 5);
+    assert_eq!(steps.iter().filter(|s| **s != Step::Insert).count(), 7);
+    assert_eq!(steps.iter().filter(|s| **s != Step::Delete).count(), 6);
+}
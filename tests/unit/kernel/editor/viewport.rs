@@ -29,6 +29,7 @@ fn test_clamp_and_follow_allows_scroll_beyond_last_line() {
         line_offset: 9,
         horiz_offset: 0,
         follow_cursor: false,
+        ..Default::default()
     };
 
     clamp_and_follow(&mut viewport, &buffer, 4);
@@ -47,8 +48,119 @@ fn test_clamp_and_follow_does_not_snap_manual_offset_when_cursor_visible() {
         line_offset: 90,
         horiz_offset: 0,
         follow_cursor: true,
+        ..Default::default()
     };
 
     clamp_and_follow(&mut viewport, &buffer, 4);
     assert_eq!(viewport.line_offset, 90);
 }
+
+#[test]
+fn test_wrap_line_starts_splits_ascii_and_wide_graphemes() {
+    let buffer = TextBuffer::from_text("abcdefghij\n你好世界\n\tab\n\n");
+
+    assert_eq!(wrap_line_starts(&buffer, 0, 4, 4), vec![0, 4, 8]);
+    assert_eq!(wrap_line_starts(&buffer, 0, 10, 4), vec![0]);
+    // 宽字符放不下时整体挪到下一段，不拆半个字。
+    assert_eq!(wrap_line_starts(&buffer, 1, 3, 4), vec![0, 1, 2, 3]);
+    assert_eq!(wrap_line_starts(&buffer, 1, 4, 4), vec![0, 2]);
+    assert_eq!(wrap_line_starts(&buffer, 2, 5, 4), vec![0, 2]);
+    assert_eq!(wrap_line_starts(&buffer, 3, 4, 4), vec![0]);
+}
+
+#[test]
+fn test_display_to_logical_maps_wrapped_rows() {
+    let buffer = TextBuffer::from_text("abcdefghij\nxy\n");
+    let mut viewport = EditorViewportState {
+        width: 4,
+        height: 10,
+        word_wrap: true,
+        horiz_offset: 3,
+        ..Default::default()
+    };
+
+    clamp_and_follow(&mut viewport, &buffer, 4);
+    assert_eq!(
+        viewport.display_to_logical,
+        vec![(0, 0), (0, 4), (0, 8), (1, 0), (2, 0)]
+    );
+    assert_eq!(viewport.horiz_offset, 0);
+    assert_eq!(viewport.display_row_of((0, 5)), Some(1));
+    assert_eq!(viewport.display_row_of((0, 8)), Some(2));
+    assert_eq!(viewport.display_row_of((1, 2)), Some(3));
+    assert_eq!(viewport.first_display_row_of(1), 3);
+    assert_eq!(viewport.wrap_segment_end(0), Some(4));
+    assert_eq!(viewport.wrap_segment_end(2), None);
+
+    viewport.word_wrap = false;
+    clamp_and_follow(&mut viewport, &buffer, 4);
+    assert!(viewport.display_to_logical.is_empty());
+}
+
+#[test]
+fn test_clamp_and_follow_counts_wrapped_rows() {
+    let content = "a".repeat(40) + "\nb\nc\n";
+    let mut buffer = TextBuffer::from_text(&content);
+    buffer.set_cursor(2, 0);
+
+    let mut viewport = EditorViewportState {
+        width: 10,
+        height: 5,
+        word_wrap: true,
+        follow_cursor: true,
+        ..Default::default()
+    };
+
+    // 第 0 行折成 4 个显示行，光标在第 6 个显示行，视口需要下移到第 1 行才看得见。
+    clamp_and_follow(&mut viewport, &buffer, 4);
+    assert_eq!(viewport.line_offset, 1);
+}
+
+fn display_grapheme() -> impl proptest::strategy::Strategy<Value = &'static str> {
+    proptest::sample::select(vec![
+        "a",
+        " ",
+        "\t",
+        "中",
+        "ｱ",
+        "e\u{301}",
+        "😀",
+        "✈",
+        "✈\u{fe0f}",
+        "❤\u{fe0f}",
+        "👍\u{1f3fd}",
+        "🇨🇳",
+        "1\u{fe0f}\u{20e3}",
+        "👨\u{200d}👩\u{200d}👧",
+        "🏳\u{fe0f}\u{200d}🌈",
+        "👩\u{1f3fb}\u{200d}💻",
+    ])
+}
+
+proptest::proptest! {
+    #[test]
+    fn display_x_and_col_round_trip_for_mixed_unicode(
+        graphemes in proptest::collection::vec(display_grapheme(), 0..24),
+        tab_size in 1u8..=8,
+    ) {
+        let line = graphemes.concat();
+        let buffer = TextBuffer::from_text(&line);
+        let len = buffer.line_grapheme_len(0);
+        proptest::prop_assert_eq!(len, graphemes.len());
+
+        let viewport = EditorViewportState {
+            width: usize::from(u16::MAX),
+            height: 1,
+            ..Default::default()
+        };
+        let mut prev_x = 0u32;
+        for col in 0..=len {
+            let x = display_x_abs(&buffer, 0, col, tab_size);
+            proptest::prop_assert!(col == 0 || x > prev_x);
+            prev_x = x;
+            proptest::prop_assert_eq!(col_at_display_x_abs(&buffer, 0, x, tab_size), col);
+            proptest::prop_assert_eq!(screen_to_col(&viewport, &buffer, tab_size, 0, x as u16), Some(col));
+        }
+        proptest::prop_assert_eq!(line_display_width(&buffer, 0, tab_size), prev_x);
+    }
+}
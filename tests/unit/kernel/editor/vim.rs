@@ -0,0 +1,31 @@
+use crate::kernel::editor::{EditorTabState, TabId};
+use crate::kernel::services::ports::EditorConfig;
+use std::path::PathBuf;
+
+fn tab(content: &str) -> EditorTabState {
+    let config = EditorConfig::default();
+    EditorTabState::from_file(TabId::new(1), PathBuf::from("test.rs"), content, &config)
+}
+
+#[test]
+fn word_end_moves_to_last_grapheme_of_word() {
+    let mut t = tab("foo bar_baz\nqux");
+    assert!(t.vim_word_end(4));
+    assert_eq!(t.buffer.cursor(), (0, 2));
+    // 已在词尾时跳到下一个词的词尾。
+    assert!(t.vim_word_end(4));
+    assert_eq!(t.buffer.cursor(), (0, 10));
+    assert!(t.vim_word_end(4));
+    assert_eq!(t.buffer.cursor(), (1, 2));
+    assert!(!t.vim_word_end(4));
+}
+
+#[test]
+fn linewise_range_takes_trailing_newline_or_previous_one_on_last_line() {
+    let t = tab("a\nbb\nccc");
+    assert_eq!(t.vim_linewise_range(0, 1), ((0, 0), (2, 0)));
+    assert_eq!(t.vim_linewise_range(2, 2), ((1, 2), (2, 3)));
+
+    let single = tab("only");
+    assert_eq!(single.vim_linewise_range(0, 0), ((0, 0), (0, 4)));
+}
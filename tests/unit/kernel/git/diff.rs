@@ -0,0 +1,93 @@
+use super::*;
+
+fn diff(old: &str, new: &str) -> LineDiff {
+    line_diff(&Rope::from_str(old), &Rope::from_str(new))
+}
+
+fn row(old: Option<usize>, new: Option<usize>, changed: bool) -> DiffRow {
+    DiffRow { old, new, changed }
+}
+
+#[test]
+fn identical_texts_have_no_hunks() {
+    let d = diff("a\nb\n", "a\nb\n");
+    assert!(d.hunks.is_empty());
+    assert_eq!(
+        d.rows,
+        vec![row(Some(0), Some(0), false), row(Some(1), Some(1), false)]
+    );
+}
+
+#[test]
+fn replaced_lines_pair_up_side_by_side() {
+    // b → x、y：一块，较短的左侧补空行。
+    let d = diff("a\nb\nc\n", "a\nx\ny\nc\n");
+    assert_eq!(
+        d.rows,
+        vec![
+            row(Some(0), Some(0), false),
+            row(Some(1), Some(1), true),
+            row(None, Some(2), true),
+            row(Some(2), Some(3), false),
+        ]
+    );
+    assert_eq!(
+        d.hunks,
+        vec![DiffHunk {
+            old_start: 1,
+            old_len: 1,
+            new_start: 1,
+            new_len: 2,
+            rows: 1..3,
+        }]
+    );
+}
+
+#[test]
+fn separate_changes_become_separate_hunks() {
+    let d = diff("a\nb\nc\nd\n", "a\nc\nd\ne\n");
+    assert_eq!(d.hunks.len(), 2);
+    assert_eq!((d.hunks[0].old_start, d.hunks[0].old_len), (1, 1));
+    assert_eq!(d.hunks[0].new_len, 0);
+    assert_eq!((d.hunks[1].new_start, d.hunks[1].new_len), (3, 1));
+    assert_eq!(d.hunks[1].old_len, 0);
+}
+
+#[test]
+fn missing_trailing_newline_changes_the_last_line() {
+    let d = diff("a\nb", "a\nb\n");
+    assert_eq!(d.hunks.len(), 1);
+    assert_eq!((d.hunks[0].old_start, d.hunks[0].old_len), (1, 1));
+    assert_eq!(d.hunks[0].new_len, 1);
+}
+
+#[test]
+fn hunk_patch_body_uses_zero_context_ranges() {
+    let old = Rope::from_str("a\nb\nc\n");
+    let new = Rope::from_str("a\nx\ny\nc\n");
+    let d = line_diff(&old, &new);
+    assert_eq!(
+        hunk_patch_body(&old, &new, &d.hunks[0]),
+        "@@ -2,1 +2,2 @@\n-b\n+x\n+y\n"
+    );
+
+    // 纯插入：原文区间写成插入点之前的行号、长度 0。
+    let old = Rope::from_str("a\nb\n");
+    let new = Rope::from_str("a\nn\nb\n");
+    let d = line_diff(&old, &new);
+    assert_eq!(
+        hunk_patch_body(&old, &new, &d.hunks[0]),
+        "@@ -1,0 +2,1 @@\n+n\n"
+    );
+}
+
+#[test]
+fn hunk_patch_body_marks_missing_trailing_newline() {
+    let old = Rope::from_str("a\nb");
+    let new = Rope::from_str("a\nc");
+    let d = line_diff(&old, &new);
+    assert_eq!(
+        hunk_patch_body(&old, &new, &d.hunks[0]),
+        "@@ -2,1 +2,1 @@\n-b\n\\ No newline at end of file\n+c\n\\ No newline at end of file\n"
+    );
+}
@@ -42,6 +42,35 @@ fn from_path_maps_all_supported_extensions() {
         ("a.sh", Some(LanguageId::Bash)),
         ("a.bash", Some(LanguageId::Bash)),
         ("a.zsh", Some(LanguageId::Bash)),
+        ("a.proto", Some(LanguageId::Protobuf)),
+        ("a.groovy", Some(LanguageId::Groovy)),
+        ("a.gvy", Some(LanguageId::Groovy)),
+        ("ci/Jenkinsfile", Some(LanguageId::Groovy)),
+        ("a.wat", Some(LanguageId::Wat)),
+        ("a.wast", Some(LanguageId::Wat)),
+        ("policy/authz.rego", Some(LanguageId::Rego)),
+        ("Makefile", Some(LanguageId::Makefile)),
+        ("src/GNUmakefile", Some(LanguageId::Makefile)),
+        ("rules.mk", Some(LanguageId::Makefile)),
+        ("Dockerfile", Some(LanguageId::Dockerfile)),
+        ("deploy/Containerfile", Some(LanguageId::Dockerfile)),
+        ("api.dockerfile", Some(LanguageId::Dockerfile)),
+        ("contracts/Token.sol", Some(LanguageId::Solidity)),
+        ("src/lib.cairo", Some(LanguageId::Cairo)),
+        ("app/models/user.rb", Some(LanguageId::Ruby)),
+        ("Gemfile", Some(LanguageId::Ruby)),
+        ("lib/tasks/db.rake", Some(LanguageId::Ruby)),
+        ("config.ru", Some(LanguageId::Ruby)),
+        ("nvim/init.lua", Some(LanguageId::Lua)),
+        ("lib/app/user.ex", Some(LanguageId::Elixir)),
+        ("mix.exs", Some(LanguageId::Elixir)),
+        ("src/app_server.erl", Some(LanguageId::Erlang)),
+        ("include/records.hrl", Some(LanguageId::Erlang)),
+        ("app/src/Main.kt", Some(LanguageId::Kotlin)),
+        ("build.gradle.kts", Some(LanguageId::Kotlin)),
+        ("Sources/App/main.swift", Some(LanguageId::Swift)),
+        ("flake.nix", Some(LanguageId::Nix)),
+        ("config/package.dhall", Some(LanguageId::Dhall)),
         ("a.txt", None),
     ];
 
@@ -67,6 +96,26 @@ fn from_code_fence_maps_union_of_view_and_adapter_aliases() {
         ("sql", Some(LanguageId::Sql)),
         ("markdown", Some(LanguageId::Markdown)),
         ("md", Some(LanguageId::Markdown)),
+        ("proto3", Some(LanguageId::Protobuf)),
+        ("groovy", Some(LanguageId::Groovy)),
+        ("wat", Some(LanguageId::Wat)),
+        ("rego", Some(LanguageId::Rego)),
+        ("makefile", Some(LanguageId::Makefile)),
+        ("dockerfile", Some(LanguageId::Dockerfile)),
+        ("solidity", Some(LanguageId::Solidity)),
+        ("sol", Some(LanguageId::Solidity)),
+        ("cairo", Some(LanguageId::Cairo)),
+        ("ruby", Some(LanguageId::Ruby)),
+        ("rb", Some(LanguageId::Ruby)),
+        ("lua", Some(LanguageId::Lua)),
+        ("elixir", Some(LanguageId::Elixir)),
+        ("exs", Some(LanguageId::Elixir)),
+        ("erlang", Some(LanguageId::Erlang)),
+        ("kotlin", Some(LanguageId::Kotlin)),
+        ("kts", Some(LanguageId::Kotlin)),
+        ("swift", Some(LanguageId::Swift)),
+        ("nix", Some(LanguageId::Nix)),
+        ("dhall", Some(LanguageId::Dhall)),
         // Case-insensitive + surrounding whitespace are normalized.
         ("  RuSt  ", Some(LanguageId::Rust)),
         ("nonsense", None),
@@ -94,6 +143,22 @@ fn lsp_language_id_mapping_is_correct() {
         (LanguageId::C, "c"),
         (LanguageId::Cpp, "cpp"),
         (LanguageId::Java, "java"),
+        (LanguageId::Protobuf, "proto"),
+        (LanguageId::Groovy, "groovy"),
+        (LanguageId::Wat, "wat"),
+        (LanguageId::Rego, "rego"),
+        (LanguageId::Makefile, "makefile"),
+        (LanguageId::Dockerfile, "dockerfile"),
+        (LanguageId::Solidity, "solidity"),
+        (LanguageId::Cairo, "cairo"),
+        (LanguageId::Ruby, "ruby"),
+        (LanguageId::Lua, "lua"),
+        (LanguageId::Elixir, "elixir"),
+        (LanguageId::Erlang, "erlang"),
+        (LanguageId::Kotlin, "kotlin"),
+        (LanguageId::Swift, "swift"),
+        (LanguageId::Nix, "nix"),
+        (LanguageId::Dhall, "dhall"),
     ];
 
     for (language, expected) in cases {
@@ -126,6 +191,24 @@ fn server_kind_mapping_is_correct() {
         (LanguageId::C, Some(LspServerKind::Clangd)),
         (LanguageId::Cpp, Some(LspServerKind::Clangd)),
         (LanguageId::Java, Some(LspServerKind::Jdtls)),
+        (
+            LanguageId::Protobuf,
+            Some(LspServerKind::ProtoLanguageServer),
+        ),
+        (
+            LanguageId::Groovy,
+            Some(LspServerKind::GroovyLanguageServer),
+        ),
+        (LanguageId::Rego, Some(LspServerKind::Regal)),
+        (
+            LanguageId::Solidity,
+            Some(LspServerKind::NomicFoundryVsCode),
+        ),
+        (LanguageId::Cairo, Some(LspServerKind::CairoLs)),
+        (LanguageId::Kotlin, Some(LspServerKind::KotlinLs)),
+        (LanguageId::Swift, Some(LspServerKind::Sourcekit)),
+        (LanguageId::Nix, Some(LspServerKind::Nil)),
+        (LanguageId::Dhall, Some(LspServerKind::DhallLsp)),
     ];
 
     for (language, expected) in cases {
@@ -248,6 +331,7 @@ fn callable_item(label: &str) -> LspCompletionItem {
         id: 1,
         label: label.to_string(),
         detail: None,
+        label_details: None,
         kind: Some(3),
         documentation: None,
         insert_text: label.to_string(),
@@ -267,6 +351,7 @@ fn keyword_item(label: &str) -> LspCompletionItem {
         id: 2,
         label: label.to_string(),
         detail: None,
+        label_details: None,
         kind: Some(14),
         documentation: None,
         insert_text: label.to_string(),
@@ -363,6 +448,7 @@ fn rust_adapter_enhances_fn_keyword_snippet_with_return_placeholder() {
         id: 3,
         label: "fn".to_string(),
         detail: None,
+        label_details: None,
         kind: Some(14),
         documentation: None,
         insert_text: "fn $1($2) {\n    $0\n}".to_string(),
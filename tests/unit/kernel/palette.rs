@@ -0,0 +1,109 @@
+use super::*;
+
+#[test]
+fn split_query_uses_prefix_to_select_mode() {
+    assert_eq!(split_query(">"), (CommandLineMode::Command, ""));
+    assert_eq!(split_query("> fold"), (CommandLineMode::Command, "fold"));
+    assert_eq!(split_query(">fold"), (CommandLineMode::Command, "fold"));
+    assert_eq!(split_query(" main.rs "), (CommandLineMode::File, "main.rs"));
+    assert_eq!(split_query(""), (CommandLineMode::File, ""));
+    assert_eq!(split_query(": 42"), (CommandLineMode::Line, "42"));
+}
+
+#[test]
+fn parse_line_number_is_one_based_and_bounded() {
+    assert_eq!(parse_line_number("1", 10), Some(0));
+    assert_eq!(parse_line_number(" 10 ", 10), Some(9));
+    assert_eq!(parse_line_number("0", 10), None);
+    assert_eq!(parse_line_number("11", 10), None);
+    assert_eq!(parse_line_number("3a", 10), None);
+    assert_eq!(parse_line_number("", 10), None);
+}
+
+#[test]
+fn fuzzy_score_requires_subsequence_and_prefers_substrings() {
+    assert!(fuzzy_score("xyz", "editor: fold").is_none());
+    let subsequence = fuzzy_score("edfd", "editor: fold").unwrap();
+    let substring = fuzzy_score("fold", "editor: fold").unwrap();
+    assert!(substring > subsequence);
+}
+
+#[test]
+fn match_items_matches_labels_and_command_names() {
+    let by_label = match_items("diagnostics");
+    assert_eq!(by_label[0].command, &Command::OpenDiagnostics);
+
+    let by_name = match_items("openDiagnostics");
+    assert_eq!(by_name[0].command, &Command::OpenDiagnostics);
+
+    assert_eq!(match_items("").len(), PALETTE_ITEMS.len());
+}
+
+#[test]
+fn match_files_ranks_tighter_matches_first() {
+    let files = vec![
+        PathBuf::from("docs/main_readme.md"),
+        PathBuf::from("src/main.rs"),
+        PathBuf::from("src/kernel/store/mod.rs"),
+    ];
+    let matches = match_files("main.rs", &files);
+    assert_eq!(matches[0], Path::new("src/main.rs"));
+    assert!(match_files("zzz", &files).is_empty());
+    assert_eq!(match_files("", &files).len(), 3);
+}
+
+#[test]
+fn file_candidates_put_recent_files_first_without_duplicates() {
+    let root = Path::new("/work");
+    let recent = VecDeque::from([
+        PathBuf::from("/work/src/lib.rs"),
+        PathBuf::from("/other/notes.md"),
+    ]);
+    let files = vec![PathBuf::from("README.md"), PathBuf::from("src/lib.rs")];
+    assert_eq!(
+        file_candidates(&recent, &files, root),
+        [
+            Path::new("src/lib.rs"),
+            Path::new("/other/notes.md"),
+            Path::new("README.md"),
+        ]
+    );
+}
+
+#[test]
+fn match_branches_offers_create_only_for_unknown_names() {
+    let branch = |name: &str, remote: bool| GitBranch {
+        name: name.to_string(),
+        remote,
+        current: false,
+    };
+    let branches = vec![branch("main", false), branch("origin/feature/x", true)];
+
+    assert_eq!(match_branches("", &branches).len(), 2);
+    assert_eq!(
+        match_branches("feat", &branches),
+        vec![
+            BranchCandidate::Checkout(&branches[1]),
+            BranchCandidate::Create("feat")
+        ]
+    );
+    // 远程分支的短名也算已存在。
+    assert_eq!(
+        match_branches("feature/x", &branches),
+        vec![BranchCandidate::Checkout(&branches[1])]
+    );
+}
+
+#[test]
+fn match_clipboard_keeps_newest_first_and_previews_first_line() {
+    let entries = vec![
+        "let x = 1;".to_string(),
+        "\n    fn main() {\n    }\n".to_string(),
+        "x".to_string(),
+    ];
+
+    assert_eq!(match_clipboard("", &entries).len(), 3);
+    assert_eq!(match_clipboard("main", &entries), vec![entries[1].as_str()]);
+    assert_eq!(clipboard_preview(&entries[1]), ("fn main() {", 1));
+    assert_eq!(clipboard_preview("x"), ("x", 0));
+}
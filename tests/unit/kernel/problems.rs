@@ -200,6 +200,54 @@ fn test_update_path_remove_middle_shifts_following_ranges() {
     ));
 }
 
+#[test]
+fn test_items_for_path_returns_only_that_files_problems() {
+    let mut state = ProblemsState::default();
+    let path_a = PathBuf::from("src/a.rs");
+    let path_b = PathBuf::from("src/b.rs");
+    state.update_path(path_a.clone(), vec![mk_problem(&path_a, 1, 0, "a")]);
+    state.update_path(
+        path_b.clone(),
+        vec![
+            mk_problem(&path_b, 4, 0, "b2"),
+            mk_problem(&path_b, 2, 0, "b1"),
+        ],
+    );
+
+    let messages: Vec<&str> = state
+        .items_for_path(&path_b)
+        .iter()
+        .map(|item| item.message.as_str())
+        .collect();
+    assert_eq!(messages, vec!["b1", "b2"]);
+    assert!(state.items_for_path(Path::new("src/c.rs")).is_empty());
+}
+
+#[test]
+fn test_items_at_matches_range_and_empty_range_start() {
+    let mut state = ProblemsState::default();
+    let path = PathBuf::from("src/a.rs");
+    let mut multi_line = mk_problem(&path, 1, 4, "span");
+    multi_line.range.end_line = 2;
+    multi_line.range.end_col = 3;
+    let mut empty = mk_problem(&path, 5, 7, "missing semicolon");
+    empty.range.end_col = 7;
+    state.update_path(path.clone(), vec![multi_line, empty]);
+
+    let at = |line, character| -> Vec<&str> {
+        state
+            .items_at(&path, line, character)
+            .map(|item| item.message.as_str())
+            .collect()
+    };
+    assert_eq!(at(1, 4), vec!["span"]);
+    assert_eq!(at(2, 0), vec!["span"]);
+    assert!(at(1, 3).is_empty());
+    assert!(at(2, 3).is_empty());
+    assert_eq!(at(5, 7), vec!["missing semicolon"]);
+    assert!(at(5, 8).is_empty());
+}
+
 #[test]
 fn experiment_problems_update_path_scale_baseline() {
     let mut state = ProblemsState::default();
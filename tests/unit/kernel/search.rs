@@ -50,6 +50,154 @@ fn test_query_editing() {
     assert!(!state.backspace_query());
 }
 
+#[test]
+fn test_progress_tracks_files_and_clears_total_on_complete() {
+    let mut state = SearchState {
+        query: "needle".to_string(),
+        ..SearchState::default()
+    };
+    assert!(state.begin_search());
+    assert!(state.set_active_search_id(7));
+
+    assert!(state.apply_message(GlobalSearchMessage::Progress {
+        search_id: 7,
+        files_searched: 100,
+        files_total: 400,
+        matches_so_far: 3,
+    }));
+    assert_eq!((state.files_searched, state.files_total), (100, 400));
+
+    // 过期搜索的进度不生效。
+    assert!(!state.apply_message(GlobalSearchMessage::Progress {
+        search_id: 6,
+        files_searched: 200,
+        files_total: 400,
+        matches_so_far: 0,
+    }));
+
+    assert!(state.apply_message(GlobalSearchMessage::Complete {
+        search_id: 7,
+        total_files: 400,
+        total_matches: 3,
+    }));
+    assert!(!state.searching);
+    assert_eq!(state.files_searched, 400);
+    assert_eq!(state.files_total, 0);
+}
+
+#[test]
+fn test_binary_skips_are_counted_and_reset_per_search() {
+    let mut state = SearchState {
+        query: "needle".to_string(),
+        ..SearchState::default()
+    };
+    assert!(state.begin_search());
+    assert!(state.set_active_search_id(3));
+
+    for name in ["a.png", "b.pdf"] {
+        state.apply_message(GlobalSearchMessage::BinaryFileSkipped {
+            search_id: 3,
+            path: PathBuf::from(name),
+        });
+    }
+    assert!(
+        !state.apply_message(GlobalSearchMessage::BinaryFileSkipped {
+            search_id: 2,
+            path: PathBuf::from("c.bin"),
+        })
+    );
+    assert_eq!(state.binary_files_skipped, 2);
+    assert!(state.items.is_empty());
+    assert_eq!(state.snapshot().binary_files_skipped, 2);
+
+    assert!(state.begin_search());
+    assert_eq!(state.binary_files_skipped, 0);
+}
+
+#[test]
+fn test_filter_fields_join_the_tab_cycle_and_take_input() {
+    let mut state = SearchState::default();
+    assert!(state.toggle_filters());
+    assert_eq!(state.filter_focused, Some(SearchFilterField::Include));
+    assert_eq!(state.input_rows(), 3);
+
+    for ch in " *.rs ".chars() {
+        state.append_filter_char(SearchFilterField::Include, ch);
+    }
+    assert!(state.switch_field());
+    assert_eq!(state.filter_focused, Some(SearchFilterField::Exclude));
+    assert!(!state.backspace_filter(SearchFilterField::Exclude));
+    assert_eq!(state.path_filters(), (Some("*.rs".to_string()), None));
+
+    // 展开替换后：查询 → 替换 → include → exclude → 查询。
+    assert!(state.toggle_replace());
+    assert!(state.replace_focused);
+    assert_eq!(state.filter_focused, None);
+    assert!(state.switch_field());
+    assert_eq!(state.filter_focused, Some(SearchFilterField::Include));
+    assert!(!state.replace_focused);
+    assert!(state.switch_field());
+    assert!(state.switch_field());
+    assert_eq!(state.filter_focused, None);
+    assert!(!state.replace_focused);
+    assert!(state.switch_field());
+    assert!(state.replace_focused);
+    assert_eq!(state.input_rows(), 4);
+
+    // 收起后 glob 仍然生效。
+    assert!(state.toggle_filters());
+    assert_eq!(state.filter_focused, None);
+    assert_eq!(state.path_filters().0.as_deref(), Some("*.rs"));
+}
+
+#[test]
+fn test_replace_field_takes_input_and_counts_replacements() {
+    let mut state = SearchState {
+        query: "needle".to_string(),
+        ..SearchState::default()
+    };
+    assert!(!state.begin_replace());
+    assert!(!state.switch_field());
+
+    assert!(state.toggle_replace());
+    assert!(state.replace_focused);
+    assert!(state.switch_field());
+    assert!(!state.replace_focused);
+
+    assert!(state.begin_replace());
+    assert!(state.set_active_search_id(4));
+    state.apply_message(GlobalSearchMessage::Replaced {
+        search_id: 4,
+        path: PathBuf::from("a.rs"),
+        count: 3,
+        edits: Vec::new(),
+    });
+    state.apply_message(GlobalSearchMessage::ReplaceError {
+        search_id: 4,
+        path: PathBuf::from("b.rs"),
+        error: "denied".to_string(),
+    });
+    assert!(state.apply_message(GlobalSearchMessage::Complete {
+        search_id: 4,
+        total_files: 1,
+        total_matches: 3,
+    }));
+    assert!(!state.searching);
+    assert!(state.items.is_empty());
+    assert_eq!(
+        state.snapshot().replace_summary,
+        Some(ReplaceSummary {
+            files: 1,
+            occurrences: 3,
+            failures: 1,
+        })
+    );
+
+    // 普通搜索会清掉替换摘要。
+    assert!(state.begin_search());
+    assert_eq!(state.replace_summary, None);
+}
+
 #[test]
 fn test_selection_wraps() {
     let mut state = SearchState::default();
@@ -29,6 +29,16 @@ fn test_get_log_dir() {
     assert!(dir.to_string_lossy().contains(LOG_DIR));
 }
 
+#[test]
+fn test_trash_entry_path_keeps_file_name() {
+    let dir = std::path::Path::new("/home/u/.config/zcode/trash");
+    let entry = trash_entry_path(dir, std::path::Path::new("/ws/src/main.rs"));
+
+    assert_eq!(entry.parent(), Some(dir));
+    let name = entry.file_name().unwrap().to_string_lossy().to_string();
+    assert!(name.ends_with("-main.rs"), "{name}");
+}
+
 #[test]
 fn test_get_ops_file_path() {
     let file_path = std::path::Path::new("/tmp/test.txt");
@@ -38,3 +48,23 @@ fn test_get_ops_file_path() {
     let ops_path = ops_path.unwrap();
     assert!(ops_path.to_string_lossy().ends_with(".ops"));
 }
+
+#[test]
+fn test_prune_trash_dir_drops_oldest_entries_only() {
+    let dir = tempfile::tempdir().unwrap();
+    for stamp in 1..=4u32 {
+        std::fs::write(dir.path().join(format!("{stamp:x}-file.txt")), "x").unwrap();
+    }
+    std::fs::create_dir(dir.path().join("5-folder")).unwrap();
+    std::fs::write(dir.path().join("5-folder/inner.txt"), "x").unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "not ours").unwrap();
+
+    prune_trash_dir(dir.path(), 2).unwrap();
+
+    let mut names: Vec<String> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["4-file.txt", "5-folder", "notes.txt"]);
+}
@@ -17,3 +17,75 @@ fn wl_paste_uses_no_newline_flag() {
 
     assert_eq!(args, vec!["--no-newline"]);
 }
+
+#[test]
+fn history_keeps_latest_entries_without_duplicates() {
+    let mut service = ClipboardService::new();
+    for i in 0..CLIPBOARD_HISTORY_LIMIT + 5 {
+        service.remember(&format!("entry {i}"));
+    }
+    service.remember("");
+    service.remember("entry 10");
+
+    let history = service.history();
+    assert_eq!(history.len(), CLIPBOARD_HISTORY_LIMIT);
+    assert_eq!(history[0], "entry 10");
+    assert_eq!(history[1], format!("entry {}", CLIPBOARD_HISTORY_LIMIT + 4));
+    assert_eq!(history.iter().filter(|e| *e == "entry 10").count(), 1);
+    assert!(!history.contains(&"entry 0".to_string()));
+}
+
+#[test]
+fn cycle_history_wraps_in_both_directions() {
+    let mut service = ClipboardService::new();
+    assert_eq!(service.cycle_history(1), None);
+
+    for text in ["a", "b", "c"] {
+        service.remember(text);
+    }
+    assert_eq!(service.cycle_history(1), Some("b"));
+    assert_eq!(service.cycle_history(1), Some("a"));
+    assert_eq!(service.cycle_history(1), Some("c"));
+    assert_eq!(service.cycle_history(-1), Some("a"));
+
+    service.reset_history_index();
+    assert_eq!(service.cycle_history(-1), Some("a"));
+}
+
+#[test]
+fn osc52_is_preferred_by_config_or_inside_ssh_on_auto() {
+    assert!(prefers_osc52(ClipboardBackend::Auto, true));
+    assert!(!prefers_osc52(ClipboardBackend::Auto, false));
+    assert!(!prefers_osc52(ClipboardBackend::System, true));
+    assert!(prefers_osc52(ClipboardBackend::Osc52, false));
+
+    let mut service = ClipboardService::new();
+    assert!(!service.prefers_osc52());
+    service.set_backend(ClipboardBackend::Osc52);
+    assert!(service.prefers_osc52());
+}
+
+#[test]
+fn osc52_copy_then_paste_reads_back_the_copied_text_without_a_clipboard_tool() {
+    let mut service = ClipboardService::new();
+    service.provider = ClipboardProvider::None;
+    service.prefers_osc52 = true;
+    assert!(matches!(
+        service.paste_text(),
+        Err(ClipboardError::NotAvailable)
+    ));
+
+    service.copied_via_osc52("first").unwrap();
+    service.copied_via_osc52("second").unwrap();
+    assert_eq!(service.paste_text().unwrap(), "second");
+    assert_eq!(
+        service.history().front().map(String::as_str),
+        Some("second")
+    );
+
+    service.prefers_osc52 = false;
+    assert!(matches!(
+        service.paste_text(),
+        Err(ClipboardError::NotAvailable)
+    ));
+}
@@ -5,3 +5,14 @@ fn test_service_trait() {
     let service = ConfigService::new();
     assert_eq!(service.name(), "ConfigService");
 }
+
+#[test]
+fn test_editor_for_pane_merges_overrides() {
+    let service = ConfigService::new();
+    let global_tab_size = service.editor_for_pane(None).tab_size;
+    let overrides = PartialEditorConfig { tab_size: Some(2) };
+    let merged = service.editor_for_pane(Some(&overrides));
+    assert_eq!(merged.tab_size, 2);
+    assert_eq!(global_tab_size, EditorConfig::default().tab_size);
+    assert_eq!(merged.auto_indent, EditorConfig::default().auto_indent);
+}
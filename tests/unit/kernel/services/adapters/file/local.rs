@@ -0,0 +1,254 @@
+use super::*;
+use tempfile::tempdir;
+
+#[test]
+fn move_path_rejects_overwrite_by_default() {
+    let dir = tempdir().unwrap();
+    let from = dir.path().join("from.txt");
+    let to = dir.path().join("to.txt");
+    std::fs::write(&from, "FROM").unwrap();
+    std::fs::write(&to, "TO").unwrap();
+
+    let err = move_path(&from, &to, false).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+
+    assert!(from.exists());
+    assert_eq!(std::fs::read_to_string(&to).unwrap(), "TO");
+}
+
+#[test]
+fn move_path_overwrite_replaces_destination() {
+    let dir = tempdir().unwrap();
+    let from = dir.path().join("from.txt");
+    let to = dir.path().join("to.txt");
+    std::fs::write(&from, "FROM").unwrap();
+    std::fs::write(&to, "TO").unwrap();
+
+    move_path(&from, &to, true).unwrap();
+
+    assert!(!from.exists());
+    assert_eq!(std::fs::read_to_string(&to).unwrap(), "FROM");
+}
+
+#[test]
+fn move_path_cross_device_falls_back_to_copy_remove() {
+    let dir = tempdir().unwrap();
+    let from = dir.path().join("from.txt");
+    let to = dir.path().join("to.txt");
+    std::fs::write(&from, "FROM").unwrap();
+
+    move_path_impl(&from, &to, false, |_from, _to| {
+        Err(std::io::Error::from(std::io::ErrorKind::CrossesDevices))
+    })
+    .unwrap();
+
+    assert!(!from.exists());
+    assert_eq!(std::fs::read_to_string(&to).unwrap(), "FROM");
+}
+
+#[test]
+fn move_path_cross_device_stages_next_to_destination_and_cleans_up() {
+    let dir = tempdir().unwrap();
+    let from = dir.path().join("from.txt");
+    let to = dir.path().join("to.txt");
+    std::fs::write(&from, "FROM").unwrap();
+
+    move_path_impl(&from, &to, false, |_from, _to| {
+        Err(std::io::Error::from(std::io::ErrorKind::CrossesDevices))
+    })
+    .unwrap();
+
+    let names: Vec<_> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(names, vec![std::ffi::OsString::from("to.txt")]);
+}
+
+#[test]
+fn move_path_cross_device_never_touches_existing_staging_names() {
+    let dir = tempdir().unwrap();
+    let from = dir.path().join("from.txt");
+    let to = dir.path().join("to.txt");
+    std::fs::write(&from, "FROM").unwrap();
+    // 用户碰巧占着第一个临时名：要换一个名字，原有内容保持不动。
+    let taken = staging_path(&to, 0);
+    std::fs::create_dir(&taken).unwrap();
+    std::fs::write(taken.join("keep"), "x").unwrap();
+
+    move_path_impl(&from, &to, false, |_from, _to| {
+        Err(std::io::Error::from(std::io::ErrorKind::CrossesDevices))
+    })
+    .unwrap();
+
+    assert_eq!(std::fs::read_to_string(&to).unwrap(), "FROM");
+    assert!(!from.exists());
+    assert_eq!(std::fs::read_to_string(taken.join("keep")).unwrap(), "x");
+    assert!(!staging_path(&to, 1).exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn move_path_cross_device_failure_keeps_source_and_describes_error() {
+    let dir = tempdir().unwrap();
+    let from = dir.path().join("src");
+    let to = dir.path().join("dst");
+    std::fs::create_dir(&from).unwrap();
+    std::fs::write(from.join("a.txt"), "hello").unwrap();
+    // socket 文件打不开来读，拷贝必然在中途失败。
+    let _listener = std::os::unix::net::UnixListener::bind(from.join("sock")).unwrap();
+
+    let err = move_path_impl(&from, &to, false, |_from, _to| {
+        Err(std::io::Error::from(std::io::ErrorKind::CrossesDevices))
+    })
+    .unwrap_err();
+
+    assert!(err.to_string().contains("cross-device move"), "{err}");
+    assert_eq!(
+        std::fs::read_to_string(from.join("a.txt")).unwrap(),
+        "hello"
+    );
+    assert!(!to.exists());
+    assert!(!staging_path(&to, 0).exists());
+}
+
+fn dir_names(dir: &std::path::Path) -> Vec<std::ffi::OsString> {
+    let mut names: Vec<_> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    names.sort();
+    names
+}
+
+#[cfg(unix)]
+#[test]
+fn move_path_cross_device_overwrite_failure_keeps_destination() {
+    let dir = tempdir().unwrap();
+    let from = dir.path().join("src");
+    let to = dir.path().join("dst");
+    std::fs::create_dir(&from).unwrap();
+    std::fs::write(from.join("a.txt"), "new").unwrap();
+    let _listener = std::os::unix::net::UnixListener::bind(from.join("sock")).unwrap();
+    std::fs::create_dir(&to).unwrap();
+    std::fs::write(to.join("a.txt"), "old").unwrap();
+
+    move_path_impl(&from, &to, true, |_from, _to| {
+        Err(std::io::Error::from(std::io::ErrorKind::CrossesDevices))
+    })
+    .unwrap_err();
+
+    assert_eq!(std::fs::read_to_string(to.join("a.txt")).unwrap(), "old");
+    assert_eq!(std::fs::read_to_string(from.join("a.txt")).unwrap(), "new");
+    assert_eq!(dir_names(dir.path()), ["dst", "src"]);
+}
+
+#[test]
+fn move_path_overwrite_restores_destination_when_rename_fails() {
+    let dir = tempdir().unwrap();
+    let from = dir.path().join("src");
+    let to = dir.path().join("dst");
+    std::fs::create_dir(&from).unwrap();
+    std::fs::create_dir(&to).unwrap();
+    std::fs::write(to.join("a.txt"), "old").unwrap();
+
+    move_path_impl(&from, &to, true, |_from, _to| {
+        Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied))
+    })
+    .unwrap_err();
+
+    assert_eq!(std::fs::read_to_string(to.join("a.txt")).unwrap(), "old");
+    assert!(from.is_dir());
+    assert_eq!(dir_names(dir.path()), ["dst", "src"]);
+}
+
+#[test]
+fn move_path_cross_device_overwrite_replaces_directory_after_staging() {
+    let dir = tempdir().unwrap();
+    let from = dir.path().join("from.txt");
+    let to = dir.path().join("dst");
+    std::fs::write(&from, "FROM").unwrap();
+    std::fs::create_dir(&to).unwrap();
+    std::fs::write(to.join("a.txt"), "old").unwrap();
+
+    move_path_impl(&from, &to, true, |_from, _to| {
+        Err(std::io::Error::from(std::io::ErrorKind::CrossesDevices))
+    })
+    .unwrap();
+
+    assert_eq!(std::fs::read_to_string(&to).unwrap(), "FROM");
+    assert_eq!(dir_names(dir.path()), ["dst"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn copy_path_overwrite_failure_keeps_destination() {
+    let dir = tempdir().unwrap();
+    let from = dir.path().join("src");
+    let to = dir.path().join("dst");
+    std::fs::create_dir(&from).unwrap();
+    let _listener = std::os::unix::net::UnixListener::bind(from.join("sock")).unwrap();
+    std::fs::write(&to, "old").unwrap();
+
+    copy_path(&from, &to, true).unwrap_err();
+
+    assert_eq!(std::fs::read_to_string(&to).unwrap(), "old");
+    assert_eq!(dir_names(dir.path()), ["dst", "src"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn same_device_is_true_within_one_directory() {
+    let dir = tempdir().unwrap();
+    let from = dir.path().join("from.txt");
+    std::fs::write(&from, "FROM").unwrap();
+
+    assert!(same_device(&from, dir.path()));
+}
+
+#[test]
+fn move_dir_cross_device_falls_back_to_copy_remove() {
+    let dir = tempdir().unwrap();
+    let from = dir.path().join("src");
+    let to = dir.path().join("dst");
+    std::fs::create_dir_all(&from).unwrap();
+    std::fs::write(from.join("a.txt"), "hello").unwrap();
+
+    move_path_impl(&from, &to, false, |_from, _to| {
+        Err(std::io::Error::from(std::io::ErrorKind::CrossesDevices))
+    })
+    .unwrap();
+
+    assert!(!from.exists());
+    assert_eq!(std::fs::read_to_string(to.join("a.txt")).unwrap(), "hello");
+}
+
+#[test]
+fn copy_path_rejects_overwrite_by_default() {
+    let dir = tempdir().unwrap();
+    let from = dir.path().join("from.txt");
+    let to = dir.path().join("to.txt");
+    std::fs::write(&from, "FROM").unwrap();
+    std::fs::write(&to, "TO").unwrap();
+
+    let err = copy_path(&from, &to, false).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+
+    assert!(from.exists());
+    assert_eq!(std::fs::read_to_string(&to).unwrap(), "TO");
+}
+
+#[test]
+fn copy_path_overwrite_replaces_destination_and_keeps_source() {
+    let dir = tempdir().unwrap();
+    let from = dir.path().join("from.txt");
+    let to = dir.path().join("to.txt");
+    std::fs::write(&from, "FROM").unwrap();
+    std::fs::write(&to, "TO").unwrap();
+
+    copy_path(&from, &to, true).unwrap();
+
+    assert!(from.exists());
+    assert_eq!(std::fs::read_to_string(&from).unwrap(), "FROM");
+    assert_eq!(std::fs::read_to_string(&to).unwrap(), "FROM");
+}
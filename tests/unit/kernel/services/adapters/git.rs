@@ -0,0 +1,96 @@
+use super::*;
+
+#[test]
+fn parse_stash_list_reads_index_datetime_and_message() {
+    let output = "stash@{0}\x1f2026-10-01 12:00:00 +0800\x1fWIP on main: 1a2b3c4 fix parser\n\
+                  stash@{1}\x1f2026-09-30 08:30:00 +0800\x1fOn main: spike\n";
+
+    let stashes = parse_stash_list(output);
+    assert_eq!(
+        stashes,
+        vec![
+            GitStash {
+                index: 0,
+                message: "WIP on main: 1a2b3c4 fix parser".to_string(),
+                datetime: "2026-10-01 12:00:00 +0800".to_string(),
+            },
+            GitStash {
+                index: 1,
+                message: "On main: spike".to_string(),
+                datetime: "2026-09-30 08:30:00 +0800".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn parse_stash_list_skips_malformed_lines() {
+    let output = "\nnot a stash\nstash@{x}\x1fdate\x1fmsg\nstash@{2}\x1fdate\x1f\n";
+
+    let stashes = parse_stash_list(output);
+    assert_eq!(stashes.len(), 1);
+    assert_eq!(stashes[0].index, 2);
+    assert!(stashes[0].message.is_empty());
+}
+
+#[test]
+fn parse_blame_porcelain_reads_commit_author_and_time() {
+    let output = "1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b 12 12 1\n\
+                  author Jane Doe\n\
+                  author-mail <jane@example.com>\n\
+                  author-time 1760000000\n\
+                  author-tz +0800\n\
+                  summary fix parser\n\
+                  filename src/main.rs\n\
+                  \tlet x = 1;\n";
+
+    let blame = parse_blame_porcelain(output).expect("blame");
+    assert_eq!(blame.commit, "1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b");
+    assert_eq!(blame.author, "Jane Doe");
+    assert_eq!(blame.timestamp, 1_760_000_000);
+    assert!(!blame.is_uncommitted());
+    assert_eq!(
+        blame.annotation(1_760_000_000 + 3 * 3600 + 59),
+        "⏎ Jane Doe • 3 hours ago"
+    );
+    assert_eq!(
+        blame.annotation(1_760_000_000 + 86_400),
+        "⏎ Jane Doe • 1 day ago"
+    );
+
+    assert!(parse_blame_porcelain("").is_none());
+    assert!(parse_blame_porcelain("abc 1 1 1\n\tcode\n").is_none());
+}
+
+#[test]
+fn parse_branch_list_splits_local_and_remote_and_skips_remote_head() {
+    let output = "refs/heads/main\x1f*\n\
+                  refs/heads/feature/x\x1f \n\
+                  refs/remotes/origin/HEAD\x1f \n\
+                  refs/remotes/origin/feature/y\x1f \n\
+                  refs/tags/v1\x1f \n";
+
+    let branches = parse_branch_list(output);
+    assert_eq!(
+        branches,
+        vec![
+            GitBranch {
+                name: "main".to_string(),
+                remote: false,
+                current: true,
+            },
+            GitBranch {
+                name: "feature/x".to_string(),
+                remote: false,
+                current: false,
+            },
+            GitBranch {
+                name: "origin/feature/y".to_string(),
+                remote: true,
+                current: false,
+            },
+        ]
+    );
+    assert_eq!(branches[1].local_name(), "feature/x");
+    assert_eq!(branches[2].local_name(), "feature/y");
+}
@@ -18,6 +18,19 @@ fn global_commands_fall_through_in_editor() {
     );
 }
 
+#[test]
+fn ctrl_dot_opens_editor_context_menu_and_ctrl_space_completes() {
+    let service = KeybindingService::new();
+    assert_eq!(
+        service.resolve(KeybindingContext::Editor, &Key::ctrl(KeyCode::Char('.'))),
+        Some(&Command::EditorContextMenu)
+    );
+    assert_eq!(
+        service.resolve(KeybindingContext::Editor, &Key::ctrl(KeyCode::Char(' '))),
+        Some(&Command::LspCompletion)
+    );
+}
+
 #[test]
 fn esc_resolves_to_escape_in_all_contexts() {
     let service = KeybindingService::new();
@@ -51,3 +64,137 @@ fn searchbar_overrides_backspace() {
         Some(&Command::EditorSearchBarBackspace)
     );
 }
+
+#[test]
+fn export_then_import_round_trips_default_bindings() {
+    let service = KeybindingService::new();
+    let rules = service.export();
+    assert!(rules.iter().any(|rule| rule.key == "ctrl+shift+p"
+        && rule.command == "openCommandLine"
+        && rule.context.is_none()));
+    assert!(rules
+        .iter()
+        .any(|rule| rule.key == "left" && rule.context.as_deref() == Some("editor")));
+
+    let mut imported = KeybindingService::new();
+    for context in KeybindingContext::ALL {
+        let keys = imported
+            .bindings(context)
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        for key in keys {
+            imported.unbind(context, &key);
+        }
+    }
+    imported.import(&rules);
+    for context in KeybindingContext::ALL {
+        assert_eq!(imported.bindings(context), service.bindings(context));
+    }
+}
+
+#[test]
+fn import_unbinds_on_empty_command_and_defaults_to_global_context() {
+    let mut service = KeybindingService::new();
+    service.import(&[
+        KeybindingRule {
+            key: "ctrl+b".to_string(),
+            command: String::new(),
+            context: None,
+        },
+        KeybindingRule {
+            key: "ctrl+alt+e".to_string(),
+            command: "focusExplorer".to_string(),
+            context: Some("nowhere".to_string()),
+        },
+    ]);
+
+    assert_eq!(
+        service.resolve(KeybindingContext::Global, &Key::ctrl(KeyCode::Char('b'))),
+        None
+    );
+    assert_eq!(
+        service.resolve(
+            KeybindingContext::Global,
+            &Key::new(
+                KeyCode::Char('e'),
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            )
+        ),
+        Some(&Command::FocusExplorer)
+    );
+}
+
+#[test]
+fn export_to_json_only_lists_changes_from_defaults() {
+    let mut service = KeybindingService::new();
+    assert_eq!(service.non_default_rules(), Vec::new());
+
+    service.import(&[
+        KeybindingRule {
+            key: "ctrl+b".to_string(),
+            command: String::new(),
+            context: None,
+        },
+        KeybindingRule {
+            key: "ctrl+alt+e".to_string(),
+            command: "focusExplorer".to_string(),
+            context: None,
+        },
+    ]);
+    let rules: Vec<KeybindingRule> = serde_json::from_str(&service.export_to_json()).unwrap();
+    assert_eq!(
+        rules,
+        vec![
+            KeybindingRule {
+                key: "ctrl+alt+e".to_string(),
+                command: "focusExplorer".to_string(),
+                context: None,
+            },
+            KeybindingRule {
+                key: "ctrl+b".to_string(),
+                command: String::new(),
+                context: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn import_from_json_warns_and_skips_unknown_commands() {
+    let mut service = KeybindingService::new();
+    let warnings = service
+        .import_from_json(
+            r#"[
+                {"key": "ctrl+alt+e", "command": "focusExplorer"},
+                {"key": "ctrl+alt+q", "command": "noSuchCommand"},
+                {"key": "not a key", "command": "focusExplorer"}
+            ]"#,
+        )
+        .unwrap();
+
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings[0].contains("noSuchCommand"));
+    assert!(warnings[1].contains("not a key"));
+    assert_eq!(
+        service.resolve(
+            KeybindingContext::Global,
+            &Key::new(
+                KeyCode::Char('e'),
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            )
+        ),
+        Some(&Command::FocusExplorer)
+    );
+    assert_eq!(
+        service.resolve(
+            KeybindingContext::Global,
+            &Key::new(
+                KeyCode::Char('q'),
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            )
+        ),
+        None
+    );
+    assert!(service.import_from_json("{").is_err());
+}
@@ -128,21 +128,94 @@ fn lsp_restart_backoff_blocks_ensure_started() {
 }
 
 #[test]
-fn lsp_restart_backoff_is_capped() {
-    let host = KernelServiceHost::new();
+fn lsp_restart_backoff_delay_doubles_and_is_capped() {
+    assert_eq!(
+        super::process::restart_backoff_delay(0),
+        Duration::from_millis(100)
+    );
+    assert_eq!(
+        super::process::restart_backoff_delay(1),
+        Duration::from_millis(200)
+    );
+    assert_eq!(
+        super::process::restart_backoff_delay(4),
+        Duration::from_millis(1_600)
+    );
+    assert_eq!(
+        super::process::restart_backoff_delay(9),
+        Duration::from_secs(30)
+    );
+    assert_eq!(
+        super::process::restart_backoff_delay(u32::MAX),
+        Duration::from_secs(30)
+    );
+}
+
+#[test]
+fn lsp_restart_gives_up_after_max_attempts() {
+    let mut host = KernelServiceHost::new();
     let mut client = LspClient::new(
-        PathBuf::from("."),
+        PathBuf::from("/tmp/zcode-restart"),
         LspServerKind::RustAnalyzer,
         host.context(),
     );
 
-    for _ in 0..16 {
+    for attempt in 1..=super::process::MAX_RESTART_ATTEMPTS {
         client.schedule_restart_backoff();
+        assert_eq!(client.restart_attempts, attempt);
+        let until = client.restart_backoff_until.expect("backoff set");
+        let delay = until.saturating_duration_since(Instant::now());
+        assert!(delay <= super::process::restart_backoff_delay(attempt - 1));
+
+        let msg = host.try_recv().expect("reconnecting action");
+        match msg.payload {
+            crate::kernel::services::KernelMessagePayload::Action(
+                crate::kernel::Action::LspConnectionChanged { status, .. },
+            ) => assert!(matches!(
+                status,
+                crate::kernel::services::ports::LspConnectionStatus::Reconnecting { attempt: a, .. }
+                    if a == attempt
+            )),
+            other => panic!("expected connection action, got {other:?}"),
+        }
+    }
+    assert!(!client.exiting);
+
+    client.schedule_restart_backoff();
+    assert!(client.exiting);
+    assert!(client.restart_backoff_until.is_none());
+    assert!(!client.ensure_started());
+    assert!(!client.poll_restart());
+    let msg = host.try_recv().expect("failed action");
+    assert!(matches!(
+        msg.payload,
+        crate::kernel::services::KernelMessagePayload::Action(
+            crate::kernel::Action::LspConnectionChanged {
+                status: crate::kernel::services::ports::LspConnectionStatus::Failed,
+                ..
+            }
+        )
+    ));
+}
+
+#[test]
+#[cfg(unix)]
+fn lsp_poll_restart_reaps_exited_process_and_schedules_retry() {
+    let (mut client, _rx) = ready_lsp_client_for_request_tests(LspServerKind::RustAnalyzer);
+    client.doc_versions.insert(PathBuf::from("main.rs"), 3);
+    if let Some(process) = client.process.as_ref() {
+        if let Ok(mut child) = process.child.lock() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        process.pending.lock().expect("pending").state = super::wire::InitState::Failed;
     }
 
-    let until = client.restart_backoff_until.expect("backoff set");
-    let delay = until.saturating_duration_since(Instant::now());
-    assert!(delay <= Duration::from_secs(5));
+    assert!(!client.poll_restart());
+    assert!(client.process.is_none());
+    assert!(client.doc_versions.is_empty());
+    assert_eq!(client.restart_attempts, 1);
+    assert!(client.restart_backoff_until.is_some());
 }
 
 #[test]
@@ -386,6 +459,23 @@ fn hover_payload_preserves_block_kinds_and_range() {
     assert_eq!(range.end.character, 9);
 }
 
+#[test]
+fn completion_items_keep_sort_text() {
+    let resp: lsp_types::CompletionResponse = serde_json::from_value(serde_json::json!({
+        "isIncomplete": true,
+        "items": [
+            { "label": "to_string", "kind": 2, "sortText": "7fffffff" },
+            { "label": "String", "kind": 22 }
+        ]
+    }))
+    .expect("completion response");
+
+    let (items, is_incomplete) = super::convert::completion_items(resp);
+    assert!(is_incomplete);
+    assert_eq!(items[0].sort_text.as_deref(), Some("7fffffff"));
+    assert_eq!(items[1].sort_text, None);
+}
+
 #[test]
 fn signature_help_payload_preserves_markup_and_parameter_offsets() {
     let help = lsp_types::SignatureHelp {
@@ -444,6 +534,46 @@ fn client_capabilities_enable_definition_link_support() {
     assert_eq!(implementation.link_support, Some(true));
 }
 
+#[test]
+fn completion_items_keep_label_details_and_advertise_support() {
+    let caps = super::convert::client_capabilities();
+    let completion_item = caps
+        .text_document
+        .and_then(|doc| doc.completion)
+        .and_then(|completion| completion.completion_item)
+        .expect("completion item capability");
+    assert_eq!(completion_item.label_details_support, Some(true));
+
+    let items = vec![
+        lsp_types::CompletionItem {
+            label: "read_to_string".to_string(),
+            label_details: Some(lsp_types::CompletionItemLabelDetails {
+                detail: Some("(path: P) -> io::Result<String>".to_string()),
+                description: Some(" std::fs ".to_string()),
+            }),
+            ..Default::default()
+        },
+        lsp_types::CompletionItem {
+            label: "empty".to_string(),
+            label_details: Some(lsp_types::CompletionItemLabelDetails {
+                detail: Some("  ".to_string()),
+                description: None,
+            }),
+            ..Default::default()
+        },
+    ];
+    let (items, _) = super::convert::completion_items(lsp_types::CompletionResponse::Array(items));
+
+    assert_eq!(
+        items[0].label_details,
+        Some(crate::kernel::services::ports::LspCompletionLabelDetails {
+            detail: Some("(path: P) -> io::Result<String>".to_string()),
+            description: Some("std::fs".to_string()),
+        })
+    );
+    assert_eq!(items[1].label_details, None);
+}
+
 #[test]
 fn documentation_text_preserves_markup_content_payload() {
     let doc = lsp_types::Documentation::MarkupContent(lsp_types::MarkupContent {
@@ -479,6 +609,35 @@ fn definition_preview_target_uses_location_link_target_range() {
     assert_eq!(range.end.line, 14);
 }
 
+#[test]
+fn definition_locations_keeps_every_target() {
+    let a = lsp_types::Url::parse("file:///tmp/a.rs").expect("uri");
+    let b = lsp_types::Url::parse("file:///tmp/b.rs").expect("uri");
+    let response = lsp_types::GotoDefinitionResponse::Array(vec![
+        lsp_types::Location::new(
+            a,
+            lsp_types::Range::new(
+                lsp_types::Position::new(3, 4),
+                lsp_types::Position::new(3, 9),
+            ),
+        ),
+        lsp_types::Location::new(
+            b,
+            lsp_types::Range::new(
+                lsp_types::Position::new(7, 0),
+                lsp_types::Position::new(7, 2),
+            ),
+        ),
+    ]);
+
+    let items = super::convert::definition_locations(response);
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].path, PathBuf::from("/tmp/a.rs"));
+    assert_eq!((items[0].line, items[0].column), (3, 4));
+    assert_eq!(items[1].path, PathBuf::from("/tmp/b.rs"));
+    assert_eq!((items[1].line, items[1].column), (7, 0));
+}
+
 #[test]
 fn hover_definition_preview_for_trait_method_stops_before_next_top_level_item() {
     let mut host = KernelServiceHost::new();
@@ -1054,3 +1213,392 @@ fn hover_request_with_definition_preview_sends_hover_and_definition_requests() {
     }
     assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
 }
+
+#[test]
+#[cfg(unix)]
+fn call_hierarchy_incoming_request_sends_back_raw_item() {
+    let (mut client, rx) = ready_lsp_client_for_request_tests(LspServerKind::RustAnalyzer);
+    let path = PathBuf::from("/tmp/lib.rs");
+    let raw = serde_json::json!({
+        "name": "parse",
+        "kind": 12,
+        "uri": "file:///tmp/lib.rs",
+        "range": { "start": { "line": 2, "character": 0 }, "end": { "line": 5, "character": 1 } },
+        "selectionRange": { "start": { "line": 2, "character": 3 }, "end": { "line": 2, "character": 8 } },
+        "data": { "opaque": 7 }
+    });
+    let item = super::convert::call_hierarchy_item_from_lsp(
+        serde_json::from_value(raw).expect("call hierarchy item"),
+    )
+    .expect("file item");
+    assert_eq!(item.path, path);
+    assert_eq!((item.line, item.column), (2, 3));
+
+    client.request_call_hierarchy_incoming(4, item);
+
+    match rx.recv().expect("incoming calls request") {
+        Message::Request(req) => {
+            assert_eq!(req.method, "callHierarchy/incomingCalls");
+            assert_eq!(
+                req.params["item"]["data"],
+                serde_json::json!({ "opaque": 7 })
+            );
+        }
+        other => panic!("expected incoming calls request, got {other:?}"),
+    }
+}
+
+#[test]
+fn call_hierarchy_incoming_response_dispatches_callers_for_item() {
+    let mut host = KernelServiceHost::new();
+    let ctx = host.context();
+
+    let caller = serde_json::json!({
+        "from": {
+            "name": "main",
+            "kind": 12,
+            "uri": "file:///tmp/main.rs",
+            "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 3, "character": 1 } },
+            "selectionRange": { "start": { "line": 0, "character": 3 }, "end": { "line": 0, "character": 7 } }
+        },
+        "fromRanges": []
+    });
+    handle_response(
+        LspRequestKind::CallHierarchyIncoming { item_index: 4 },
+        lsp_server::Response {
+            id: lsp_server::RequestId::from(9),
+            result: Some(serde_json::json!([caller])),
+            error: None,
+        },
+        &ctx,
+    );
+
+    let msg = host.try_recv().expect("incoming calls action");
+    match msg.payload {
+        crate::kernel::services::KernelMessagePayload::Action(
+            crate::kernel::Action::LspCallHierarchyIncoming { item_index, items },
+        ) => {
+            assert_eq!(item_index, 4);
+            assert_eq!(items.len(), 1);
+            assert_eq!(items[0].name, "main");
+            assert_eq!(items[0].path, PathBuf::from("/tmp/main.rs"));
+        }
+        other => panic!("unexpected message: {other:?}"),
+    }
+}
+
+#[test]
+fn call_hierarchy_outgoing_response_dispatches_callees_for_item() {
+    let mut host = KernelServiceHost::new();
+    let ctx = host.context();
+
+    let callee = serde_json::json!({
+        "to": {
+            "name": "load",
+            "kind": 12,
+            "uri": "file:///tmp/loader.rs",
+            "range": { "start": { "line": 4, "character": 0 }, "end": { "line": 9, "character": 1 } },
+            "selectionRange": { "start": { "line": 4, "character": 3 }, "end": { "line": 4, "character": 7 } }
+        },
+        "fromRanges": []
+    });
+    handle_response(
+        LspRequestKind::CallHierarchyOutgoing { item_index: 2 },
+        lsp_server::Response {
+            id: lsp_server::RequestId::from(11),
+            result: Some(serde_json::json!([callee])),
+            error: None,
+        },
+        &ctx,
+    );
+
+    let msg = host.try_recv().expect("outgoing calls action");
+    match msg.payload {
+        crate::kernel::services::KernelMessagePayload::Action(
+            crate::kernel::Action::LspCallHierarchyOutgoing { item_index, items },
+        ) => {
+            assert_eq!(item_index, 2);
+            assert_eq!(items.len(), 1);
+            assert_eq!(items[0].name, "load");
+            assert_eq!((items[0].line, items[0].column), (4, 3));
+        }
+        other => panic!("unexpected message: {other:?}"),
+    }
+}
+
+#[test]
+fn semantic_tokens_refresh_request_is_acknowledged_without_dispatch() {
+    let mut host = KernelServiceHost::new();
+    let ctx = host.context();
+
+    let resp = handle_server_request(
+        lsp_server::Request {
+            id: lsp_server::RequestId::from(3),
+            method: "workspace/semanticTokens/refresh".to_string(),
+            params: serde_json::Value::Null,
+        },
+        &std::sync::Mutex::new(Vec::new()),
+        &ctx,
+    );
+
+    assert_eq!(resp.id, lsp_server::RequestId::from(3));
+    assert!(resp.error.is_none());
+    assert!(host.try_recv().is_err());
+}
+
+#[test]
+fn workspace_symbol_partial_results_are_claimed_by_token() {
+    let mut host = KernelServiceHost::new();
+    let ctx = host.context();
+    let batch = serde_json::json!([{
+        "name": "Widget",
+        "kind": 5,
+        "location": {
+            "uri": "file:///tmp/widget.rs",
+            "range": {
+                "start": { "line": 3, "character": 4 },
+                "end": { "line": 3, "character": 10 }
+            }
+        }
+    }]);
+
+    // 被取消的旧请求迟到的批次不认领。
+    let stale = serde_json::json!({
+        "token": workspace_symbols_partial_token(6),
+        "value": batch.clone(),
+    });
+    assert!(!handle_partial_result(&stale, 7, &ctx));
+    assert!(matches!(host.try_recv(), Err(TryRecvError::Empty)));
+
+    let current = serde_json::json!({
+        "token": workspace_symbols_partial_token(7),
+        "value": batch,
+    });
+    assert!(handle_partial_result(&current, 7, &ctx));
+    let msg = host.try_recv().expect("partial symbols action");
+    match msg.payload {
+        crate::kernel::services::KernelMessagePayload::Action(
+            crate::kernel::Action::LspWorkspaceSymbolsPartial { items },
+        ) => {
+            assert_eq!(items.len(), 1);
+            assert_eq!(items[0].name, "Widget");
+            assert_eq!(items[0].line, 3);
+        }
+        _ => panic!("unexpected message"),
+    }
+
+    // 普通的 work-done 进度不属于部分结果。
+    let work_done = serde_json::json!({
+        "token": "rustAnalyzer/indexing",
+        "value": { "kind": "end" },
+    });
+    assert!(!handle_partial_result(&work_done, 7, &ctx));
+}
+
+#[test]
+#[cfg(unix)]
+fn workspace_folder_changes_notify_running_server_and_answer_folder_requests() {
+    let (mut client, rx) = ready_lsp_client_for_request_tests(LspServerKind::RustAnalyzer);
+    let extra = PathBuf::from("/tmp/zcode-extra-folder");
+
+    client.add_workspace_folder(&extra);
+    client.add_workspace_folder(&extra);
+    match rx.recv().expect("didChangeWorkspaceFolders") {
+        Message::Notification(not) => {
+            assert_eq!(not.method, "workspace/didChangeWorkspaceFolders");
+            assert_eq!(
+                not.params["event"]["added"][0]["name"],
+                serde_json::json!("zcode-extra-folder")
+            );
+        }
+        other => panic!("expected notification, got {other:?}"),
+    }
+    assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+
+    let host = KernelServiceHost::new();
+    let resp = handle_server_request(
+        lsp_server::Request {
+            id: lsp_server::RequestId::from(7),
+            method: "workspace/workspaceFolders".to_string(),
+            params: serde_json::Value::Null,
+        },
+        &client.workspace_folders,
+        &host.context(),
+    );
+    let folders = resp.result.expect("folders result");
+    assert!(folders
+        .as_array()
+        .expect("folder list")
+        .iter()
+        .any(|folder| folder["name"] == "zcode-extra-folder"));
+
+    client.remove_workspace_folder(&extra);
+    match rx.recv().expect("didChangeWorkspaceFolders") {
+        Message::Notification(not) => {
+            assert_eq!(
+                not.params["event"]["removed"][0]["name"],
+                serde_json::json!("zcode-extra-folder")
+            );
+        }
+        other => panic!("expected notification, got {other:?}"),
+    }
+}
+
+#[test]
+fn prepare_rename_is_advertised_and_read_from_rename_options() {
+    let caps = super::convert::client_capabilities();
+    let rename = caps
+        .text_document
+        .and_then(|doc| doc.rename)
+        .expect("rename capability");
+    assert_eq!(rename.prepare_support, Some(true));
+
+    let with_prepare = lsp_types::ServerCapabilities {
+        rename_provider: Some(lsp_types::OneOf::Right(lsp_types::RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: Default::default(),
+        })),
+        ..Default::default()
+    };
+    let caps = super::convert::server_capabilities_from_lsp(&with_prepare);
+    assert!(caps.rename);
+    assert!(caps.prepare_rename);
+
+    let plain = lsp_types::ServerCapabilities {
+        rename_provider: Some(lsp_types::OneOf::Left(true)),
+        ..Default::default()
+    };
+    let caps = super::convert::server_capabilities_from_lsp(&plain);
+    assert!(caps.rename);
+    assert!(!caps.prepare_rename);
+}
+
+#[test]
+fn multi_root_support_is_limited_to_known_servers() {
+    assert!(LspServerKind::RustAnalyzer.supports_multi_root());
+    assert!(LspServerKind::Gopls.supports_multi_root());
+    assert!(!LspServerKind::Clangd.supports_multi_root());
+    assert!(!LspServerKind::Jdtls.supports_multi_root());
+}
+
+#[test]
+fn multi_root_server_reuses_one_client_for_several_language_roots() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let root = dir.path();
+    for name in ["a", "b"] {
+        let crate_root = root.join(name);
+        std::fs::create_dir_all(crate_root.join("src")).expect("mkdir");
+        std::fs::write(crate_root.join("Cargo.toml"), "[package]\n").expect("cargo");
+    }
+
+    let host = KernelServiceHost::new();
+    let mut service = LspService::new(root.to_path_buf(), host.context())
+        .with_command("rust-analyzer".to_string(), Vec::new());
+    assert!(service
+        .client_for_path_mut(&root.join("a/src/lib.rs"))
+        .is_some());
+    assert!(service
+        .client_for_path_mut(&root.join("b/src/lib.rs"))
+        .is_some());
+
+    assert!(service.clients.is_empty());
+    assert_eq!(service.pooled.len(), 1);
+    let pooled = service
+        .pooled
+        .get_mut(&LspServerKind::RustAnalyzer)
+        .expect("pooled client");
+    assert_eq!(pooled.primary_root(), root.join("a"));
+    let roots = pooled
+        .client_mut()
+        .served_roots
+        .lock()
+        .expect("served roots")
+        .roots
+        .clone();
+    assert_eq!(roots, vec![root.join("a"), root.join("b")]);
+
+    service.remove_workspace_folder(&root.join("b"));
+    assert_eq!(service.pooled.len(), 1);
+}
+
+#[test]
+fn attaching_root_after_initialize_reports_known_capabilities() {
+    let mut host = KernelServiceHost::new();
+    let client = LspClient::new(
+        PathBuf::from("/tmp/zcode-pool-a"),
+        LspServerKind::RustAnalyzer,
+        host.context(),
+    );
+    client
+        .served_roots
+        .lock()
+        .expect("served roots")
+        .capabilities = Some(crate::kernel::services::ports::LspServerCapabilities::default());
+    let mut pooled = MultiRootClient::new(client);
+
+    let extra = PathBuf::from("/tmp/zcode-pool-b");
+    pooled.attach_root(&extra);
+    pooled.attach_root(&extra);
+
+    let msg = host.try_recv().expect("capabilities action");
+    match msg.payload {
+        crate::kernel::services::KernelMessagePayload::Action(
+            crate::kernel::Action::LspServerCapabilities { server, root, .. },
+        ) => {
+            assert_eq!(server, LspServerKind::RustAnalyzer);
+            assert_eq!(root, extra);
+        }
+        other => panic!("expected capabilities action, got {other:?}"),
+    }
+    assert!(matches!(host.try_recv(), Err(TryRecvError::Empty)));
+
+    pooled.detach_roots_under(&extra);
+    let roots = pooled
+        .client_mut()
+        .served_roots
+        .lock()
+        .expect("served roots")
+        .roots
+        .clone();
+    assert_eq!(roots, vec![PathBuf::from("/tmp/zcode-pool-a")]);
+}
+
+#[test]
+fn reconfigure_restarts_only_when_launch_command_changes() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let host = KernelServiceHost::new();
+    let mut service = LspService::new(temp.path().to_path_buf(), host.context())
+        .with_command("custom-lsp".to_string(), vec!["--stdio".to_string()]);
+
+    let with_init_options = Some((
+        "custom-lsp".to_string(),
+        vec!["--stdio".to_string()],
+        Some(serde_json::json!({ "inlayHints": true })),
+    ));
+    assert!(!service.reconfigure(with_init_options, FxHashMap::default()));
+    assert_eq!(
+        service.command_config(),
+        ("custom-lsp", &["--stdio".to_string()][..])
+    );
+
+    let mut servers = FxHashMap::default();
+    servers.insert(
+        LspServerKind::Gopls,
+        LspServerCommandOverride {
+            initialization_options: Some(serde_json::json!({ "semanticTokens": true })),
+            ..Default::default()
+        },
+    );
+    let same_command = Some(("custom-lsp".to_string(), vec!["--stdio".to_string()], None));
+    assert!(!service.reconfigure(same_command.clone(), servers.clone()));
+
+    servers.insert(
+        LspServerKind::Gopls,
+        LspServerCommandOverride {
+            command: Some("/bin/gopls".to_string()),
+            ..Default::default()
+        },
+    );
+    assert!(service.reconfigure(same_command, servers));
+    assert!(service.reconfigure(None, FxHashMap::default()));
+}
@@ -0,0 +1,30 @@
+use super::*;
+
+#[test]
+fn stats_use_nearest_rank_percentiles() {
+    assert_eq!(stats_from_samples(&[]), FrameStats::default());
+
+    let samples: Vec<u64> = (1..=100).rev().collect();
+    assert_eq!(
+        stats_from_samples(&samples),
+        FrameStats {
+            p50_us: 50,
+            p95_us: 95,
+            p99_us: 99,
+            max_us: 100,
+            sample_count: 100,
+        }
+    );
+    assert_eq!(stats_from_samples(&[7]).p99_us, 7);
+}
+
+#[test]
+fn record_frame_keeps_only_the_latest_frames() {
+    for us in 0..(FRAME_HISTORY as u64 + 200) {
+        record_frame(Duration::from_micros(us));
+    }
+    let stats = frame_stats();
+    assert_eq!(stats.sample_count, FRAME_HISTORY);
+    assert_eq!(stats.max_us, FRAME_HISTORY as u64 + 199);
+    assert_eq!(stats.p50_us, 200 + FRAME_HISTORY as u64 / 2 - 1);
+}
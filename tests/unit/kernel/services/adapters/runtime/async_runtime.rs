@@ -136,93 +136,99 @@ fn apply_text_edits_to_rope_sorts_edits_from_bottom_to_top() {
 }
 
 #[test]
-fn move_path_rejects_overwrite_by_default() {
-    let dir = tempdir().unwrap();
-    let from = dir.path().join("from.txt");
-    let to = dir.path().join("to.txt");
-    std::fs::write(&from, "FROM").unwrap();
-    std::fs::write(&to, "TO").unwrap();
-
-    let err = move_path(&from, &to, false).unwrap_err();
-    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
-
-    assert!(from.exists());
-    assert_eq!(std::fs::read_to_string(&to).unwrap(), "TO");
-}
-
-#[test]
-fn move_path_overwrite_replaces_destination() {
-    let dir = tempdir().unwrap();
-    let from = dir.path().join("from.txt");
-    let to = dir.path().join("to.txt");
-    std::fs::write(&from, "FROM").unwrap();
-    std::fs::write(&to, "TO").unwrap();
-
-    move_path(&from, &to, true).unwrap();
-
-    assert!(!from.exists());
-    assert_eq!(std::fs::read_to_string(&to).unwrap(), "FROM");
+fn load_dir_streams_large_directories_in_batches() {
+    let dir = tempfile::tempdir().unwrap();
+    let total = DIR_BATCH_SIZE * 2 + 5;
+    for i in 0..total {
+        std::fs::write(dir.path().join(format!("f{i}.txt")), "").unwrap();
+    }
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let runtime = AsyncRuntime::new(tx).unwrap();
+    runtime.load_dir(dir.path().to_path_buf());
+
+    let mut batches = Vec::new();
+    let finished = loop {
+        match rx.recv_timeout(std::time::Duration::from_secs(5)).unwrap() {
+            AppMessage::DirBatchLoaded { entries, .. } => batches.push(entries.len()),
+            AppMessage::DirLoaded { entries, .. } => break entries.len(),
+            _ => panic!("unexpected message while loading dir"),
+        }
+    };
+
+    assert_eq!(batches, vec![DIR_BATCH_SIZE, DIR_BATCH_SIZE]);
+    assert_eq!(finished, 5);
 }
 
 #[test]
-fn move_path_cross_device_falls_back_to_copy_remove() {
+fn write_part_to_path_replaces_only_the_given_range() {
     let dir = tempdir().unwrap();
-    let from = dir.path().join("from.txt");
-    let to = dir.path().join("to.txt");
-    std::fs::write(&from, "FROM").unwrap();
-
-    move_path_impl(&from, &to, false, |_from, _to| {
-        Err(std::io::Error::from(std::io::ErrorKind::CrossesDevices))
-    })
-    .unwrap();
-
-    assert!(!from.exists());
-    assert_eq!(std::fs::read_to_string(&to).unwrap(), "FROM");
+    let path = dir.path().join("big.txt");
+    std::fs::write(&path, "head\nmiddle\ntail\n").unwrap();
+
+    // 等长替换：原地覆盖。
+    let same_len = FilePart {
+        byte_range: 5..11,
+        replacement: "MIDDLE".to_string(),
+        disk_len: 17,
+    };
+    assert!(write_part_to_path(&path, &same_len).unwrap());
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "head\nMIDDLE\ntail\n"
+    );
+
+    // 变短：其后的内容前移，文件截短。
+    let shorter = FilePart {
+        byte_range: 5..11,
+        replacement: "m".to_string(),
+        disk_len: 17,
+    };
+    assert!(write_part_to_path(&path, &shorter).unwrap());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "head\nm\ntail\n");
+
+    // 变长。
+    let longer = FilePart {
+        byte_range: 7..11,
+        replacement: "much longer tail".to_string(),
+        disk_len: 12,
+    };
+    assert!(write_part_to_path(&path, &longer).unwrap());
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "head\nm\nmuch longer tail\n"
+    );
 }
 
 #[test]
-fn move_dir_cross_device_falls_back_to_copy_remove() {
+fn write_part_to_path_leaves_externally_changed_files_alone() {
     let dir = tempdir().unwrap();
-    let from = dir.path().join("src");
-    let to = dir.path().join("dst");
-    std::fs::create_dir_all(&from).unwrap();
-    std::fs::write(from.join("a.txt"), "hello").unwrap();
-
-    move_path_impl(&from, &to, false, |_from, _to| {
-        Err(std::io::Error::from(std::io::ErrorKind::CrossesDevices))
-    })
-    .unwrap();
-
-    assert!(!from.exists());
-    assert_eq!(std::fs::read_to_string(to.join("a.txt")).unwrap(), "hello");
+    let path = dir.path().join("big.txt");
+    std::fs::write(&path, "changed on disk").unwrap();
+
+    let part = FilePart {
+        byte_range: 0..4,
+        replacement: "X".to_string(),
+        disk_len: 4,
+    };
+    assert!(!write_part_to_path(&path, &part).unwrap());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "changed on disk");
 }
 
 #[test]
-fn copy_path_rejects_overwrite_by_default() {
+fn read_file_range_cuts_chunks_at_line_boundaries() {
     let dir = tempdir().unwrap();
-    let from = dir.path().join("from.txt");
-    let to = dir.path().join("to.txt");
-    std::fs::write(&from, "FROM").unwrap();
-    std::fs::write(&to, "TO").unwrap();
-
-    let err = copy_path(&from, &to, false).unwrap_err();
-    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    let path = dir.path().join("huge.log");
+    std::fs::write(&path, "one\ntwo\nthree\n").unwrap();
 
-    assert!(from.exists());
-    assert_eq!(std::fs::read_to_string(&to).unwrap(), "TO");
-}
-
-#[test]
-fn copy_path_overwrite_replaces_destination_and_keeps_source() {
-    let dir = tempdir().unwrap();
-    let from = dir.path().join("from.txt");
-    let to = dir.path().join("to.txt");
-    std::fs::write(&from, "FROM").unwrap();
-    std::fs::write(&to, "TO").unwrap();
+    let (content, end, size) = read_file_range(&path, 0, 10).unwrap();
+    assert_eq!((content.as_str(), end, size), ("one\ntwo\n", 8, 14));
 
-    copy_path(&from, &to, true).unwrap();
+    let (content, end, _) = read_file_range(&path, end, end + 10).unwrap();
+    assert_eq!((content.as_str(), end), ("three\n", 14));
 
-    assert!(from.exists());
-    assert_eq!(std::fs::read_to_string(&from).unwrap(), "FROM");
-    assert_eq!(std::fs::read_to_string(&to).unwrap(), "FROM");
+    // 没有换行时只避开被截断的多字节字符。
+    std::fs::write(&path, "ab中文").unwrap();
+    let (content, end, _) = read_file_range(&path, 0, 4).unwrap();
+    assert_eq!((content.as_str(), end), ("ab", 2));
 }
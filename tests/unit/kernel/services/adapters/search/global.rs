@@ -31,6 +31,7 @@ fn test_global_search_literal() {
         "hello".to_string(),
         true,
         false,
+        true,
         tx,
     );
 
@@ -47,8 +48,12 @@ fn test_global_search_literal() {
                 break;
             }
             Ok(GlobalSearchMessage::Progress { .. }) => continue,
+            Ok(GlobalSearchMessage::BinaryFileSkipped { path, .. }) => {
+                panic!("Unexpected skip: {}", path.display())
+            }
             Ok(GlobalSearchMessage::Cancelled { .. }) => panic!("Unexpected cancel"),
             Ok(GlobalSearchMessage::Error { message, .. }) => panic!("Error: {}", message),
+            Ok(other) => panic!("Unexpected message: {:?}", other),
             Err(_) => panic!("Timeout"),
         }
     }
@@ -74,6 +79,7 @@ fn test_global_search_regex() {
         r"hello\d+".to_string(),
         true,
         true,
+        true,
         tx,
     );
 
@@ -89,8 +95,12 @@ fn test_global_search_regex() {
                 break;
             }
             Ok(GlobalSearchMessage::Progress { .. }) => continue,
+            Ok(GlobalSearchMessage::BinaryFileSkipped { path, .. }) => {
+                panic!("Unexpected skip: {}", path.display())
+            }
             Ok(GlobalSearchMessage::Cancelled { .. }) => panic!("Unexpected cancel"),
             Ok(GlobalSearchMessage::Error { message, .. }) => panic!("Error: {}", message),
+            Ok(other) => panic!("Unexpected message: {:?}", other),
             Err(_) => panic!("Timeout"),
         }
     }
@@ -114,23 +124,63 @@ fn test_skip_binary_files() {
         "hello".to_string(),
         true,
         false,
+        true,
         tx,
     );
 
     let mut found_files = Vec::new();
-    loop {
+    let mut skipped = Vec::new();
+    let total_files = loop {
         match rx.recv_timeout(std::time::Duration::from_secs(5)) {
             Ok(GlobalSearchMessage::FileMatches { file_matches, .. }) => {
                 found_files.push(file_matches.path);
             }
-            Ok(GlobalSearchMessage::Complete { .. }) => break,
+            Ok(GlobalSearchMessage::BinaryFileSkipped { path, .. }) => skipped.push(path),
+            Ok(GlobalSearchMessage::Complete { total_files, .. }) => break total_files,
             Ok(_) => continue,
             Err(_) => panic!("Timeout"),
         }
-    }
+    };
 
     assert_eq!(found_files.len(), 1);
     assert!(found_files[0].ends_with("text.txt"));
+    assert_eq!(skipped, vec![binary_file]);
+    assert_eq!(total_files, 1);
+}
+
+#[test]
+fn test_binary_files_searched_when_skip_disabled() {
+    let rt = create_runtime();
+    let service = GlobalSearchService::new(rt.handle().clone());
+    let (tx, rx) = mpsc::sync_channel(64);
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("text.txt"), "hello world").unwrap();
+    fs::write(dir.path().join("binary.bin"), b"hello\x00world").unwrap();
+
+    let _task = service.search_in_dir(
+        dir.path().to_path_buf(),
+        "hello".to_string(),
+        true,
+        false,
+        false,
+        tx,
+    );
+
+    let mut found_files = 0;
+    loop {
+        match rx.recv_timeout(std::time::Duration::from_secs(5)) {
+            Ok(GlobalSearchMessage::FileMatches { .. }) => found_files += 1,
+            Ok(GlobalSearchMessage::BinaryFileSkipped { path, .. }) => {
+                panic!("unexpected skip: {}", path.display())
+            }
+            Ok(GlobalSearchMessage::Complete { .. }) => break,
+            Ok(_) => continue,
+            Err(_) => panic!("Timeout"),
+        }
+    }
+
+    assert_eq!(found_files, 2);
 }
 
 #[test]
@@ -150,6 +200,7 @@ fn test_cancel_search() {
         "hello".to_string(),
         true,
         false,
+        true,
         tx,
     );
 
@@ -164,3 +215,211 @@ fn test_cancel_search() {
         }
     }
 }
+
+#[test]
+fn test_global_search_reports_progress_with_file_total() {
+    let rt = create_runtime();
+    let service = GlobalSearchService::new(rt.handle().clone());
+    let (tx, rx) = mpsc::sync_channel(1024);
+
+    let dir = tempdir().unwrap();
+    for i in 0..250 {
+        fs::write(dir.path().join(format!("f{i:03}.txt")), "hello").unwrap();
+    }
+
+    let _task = service.search_in_dir(
+        dir.path().to_path_buf(),
+        "hello".to_string(),
+        true,
+        false,
+        true,
+        tx,
+    );
+
+    let mut progress = Vec::new();
+    loop {
+        match rx.recv_timeout(std::time::Duration::from_secs(5)) {
+            Ok(GlobalSearchMessage::Progress {
+                files_searched,
+                files_total,
+                matches_so_far,
+                ..
+            }) => progress.push((files_searched, files_total, matches_so_far)),
+            Ok(GlobalSearchMessage::Complete { total_files, .. }) => {
+                assert_eq!(total_files, 250);
+                break;
+            }
+            Ok(GlobalSearchMessage::FileMatches { .. }) => continue,
+            Ok(other) => panic!("unexpected message: {other:?}"),
+            Err(_) => panic!("Timeout"),
+        }
+    }
+
+    assert_eq!(progress.len(), 2);
+    for (searched, total, matches) in progress {
+        assert!(searched.is_multiple_of(100));
+        assert!(total >= searched && total <= 250);
+        assert!(matches <= searched);
+    }
+}
+
+#[test]
+fn test_replace_edits_literal_skips_overlapping_matches() {
+    let config = SearchConfig::literal("aa", true);
+    let edits = replace_edits("aaa\nxaa", &config, "b").unwrap();
+
+    let ranges: Vec<_> = edits
+        .iter()
+        .map(|e| {
+            (
+                e.range.start.line,
+                e.range.start.character,
+                e.range.end.character,
+            )
+        })
+        .collect();
+    assert_eq!(ranges, vec![(0, 0, 2), (1, 1, 3)]);
+    assert!(edits.iter().all(|e| e.new_text == "b"));
+}
+
+#[test]
+fn test_replace_edits_regex_expands_captures_with_utf8_columns() {
+    let config = SearchConfig::regex(r"(\w+)=(\d+)", true).unwrap();
+    let edits = replace_edits("é a=1\r\nb=22", &config, "$2=$1").unwrap();
+
+    assert_eq!(edits.len(), 2);
+    assert_eq!(edits[0].range.start.character, 3);
+    assert_eq!(edits[0].range.end.character, 6);
+    assert_eq!(edits[0].new_text, "1=a");
+    assert_eq!(edits[1].range.start.line, 1);
+    assert_eq!(edits[1].new_text, "22=b");
+}
+
+#[test]
+fn test_global_replace_streams_per_file_edits() {
+    let rt = create_runtime();
+    let service = GlobalSearchService::new(rt.handle().clone());
+    let (tx, rx) = mpsc::sync_channel(64);
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "foo foo").unwrap();
+    fs::write(dir.path().join("b.txt"), "foo").unwrap();
+    fs::write(dir.path().join("c.txt"), "bar").unwrap();
+
+    let _task = service.replace_in_dir(
+        dir.path().to_path_buf(),
+        "foo".to_string(),
+        "baz".to_string(),
+        true,
+        false,
+        true,
+        tx,
+    );
+
+    let mut replaced = Vec::new();
+    loop {
+        match rx.recv_timeout(std::time::Duration::from_secs(5)) {
+            Ok(GlobalSearchMessage::Replaced {
+                path, count, edits, ..
+            }) => {
+                assert_eq!(count, edits.len());
+                replaced.push((path.file_name().unwrap().to_owned(), count));
+            }
+            Ok(GlobalSearchMessage::Complete {
+                total_files,
+                total_matches,
+                ..
+            }) => {
+                assert_eq!((total_files, total_matches), (2, 3));
+                break;
+            }
+            Ok(other) => panic!("Unexpected message: {:?}", other),
+            Err(_) => panic!("Timeout"),
+        }
+    }
+
+    replaced.sort();
+    assert_eq!(replaced, vec![("a.txt".into(), 2), ("b.txt".into(), 1)]);
+    // 服务只计算编辑，不写盘。
+    assert_eq!(
+        fs::read_to_string(dir.path().join("a.txt")).unwrap(),
+        "foo foo"
+    );
+}
+
+#[test]
+fn test_global_search_include_and_exclude_globs() {
+    let rt = create_runtime();
+    let service = GlobalSearchService::new(rt.handle().clone());
+    let (tx, rx) = mpsc::sync_channel(64);
+
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("src")).unwrap();
+    fs::create_dir_all(dir.path().join("target")).unwrap();
+    fs::write(dir.path().join("src/main.rs"), "hello").unwrap();
+    fs::write(dir.path().join("src/main_test.rs"), "hello").unwrap();
+    fs::write(dir.path().join("src/notes.txt"), "hello").unwrap();
+    fs::write(dir.path().join("target/gen.rs"), "hello").unwrap();
+
+    let _task = service.search_in_dir_filtered(
+        dir.path().to_path_buf(),
+        "hello".to_string(),
+        true,
+        false,
+        true,
+        GlobalSearchFilter {
+            include_glob: Some("*.rs".to_string()),
+            exclude_glob: Some("*_test.rs, target".to_string()),
+        },
+        tx,
+    );
+
+    let mut found = Vec::new();
+    loop {
+        match rx.recv_timeout(std::time::Duration::from_secs(5)) {
+            Ok(GlobalSearchMessage::FileMatches { file_matches, .. }) => {
+                found.push(file_matches.path);
+            }
+            Ok(GlobalSearchMessage::Complete { total_files, .. }) => {
+                assert_eq!(total_files, 1);
+                break;
+            }
+            Ok(GlobalSearchMessage::Progress { .. }) => continue,
+            Ok(GlobalSearchMessage::Error { message, .. }) => panic!("Error: {}", message),
+            Ok(other) => panic!("Unexpected message: {:?}", other),
+            Err(_) => panic!("Timeout"),
+        }
+    }
+
+    assert_eq!(found, vec![dir.path().join("src/main.rs")]);
+}
+
+#[test]
+fn test_global_search_invalid_glob_reports_error() {
+    let rt = create_runtime();
+    let service = GlobalSearchService::new(rt.handle().clone());
+    let (tx, rx) = mpsc::sync_channel(64);
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.rs"), "hello").unwrap();
+
+    let _task = service.search_in_dir_filtered(
+        dir.path().to_path_buf(),
+        "hello".to_string(),
+        true,
+        false,
+        true,
+        GlobalSearchFilter {
+            include_glob: Some("src/[".to_string()),
+            exclude_glob: None,
+        },
+        tx,
+    );
+
+    match rx.recv_timeout(std::time::Duration::from_secs(5)) {
+        Ok(GlobalSearchMessage::Error { message, .. }) => {
+            assert!(message.starts_with("Invalid glob"), "{message}");
+        }
+        other => panic!("Unexpected message: {:?}", other),
+    }
+}
@@ -47,6 +47,33 @@ fn test_regex_search() {
     assert_eq!(matches.len(), 2);
 }
 
+#[test]
+fn test_whole_word_skips_matches_inside_identifiers() {
+    let data = b"foo foobar a.foo(foo_x) Foo";
+    let config = SearchConfig::whole_word("foo", true, false).unwrap();
+    let SearchConfig::Regex { regex } = &config else {
+        panic!("expected Regex config");
+    };
+    let starts: Vec<usize> = search_regex_in_slice(data, regex)
+        .iter()
+        .map(|m| m.start)
+        .collect();
+    assert_eq!(starts, vec![0, 13]);
+
+    // 字面量里的正则元字符按原样匹配；正则模式整体包进边界，`|` 两侧都受约束。
+    let config = SearchConfig::whole_word("a.foo", false, false).unwrap();
+    let SearchConfig::Regex { regex } = &config else {
+        panic!("expected Regex config");
+    };
+    assert_eq!(search_regex_in_slice(data, regex).len(), 1);
+
+    let config = SearchConfig::whole_word("foo|bar", false, true).unwrap();
+    let SearchConfig::Regex { regex } = &config else {
+        panic!("expected Regex config");
+    };
+    assert_eq!(search_regex_in_slice(data, regex).len(), 3);
+}
+
 #[test]
 fn test_rope_reader() {
     let rope = ropey::Rope::from_str("hello world hello");
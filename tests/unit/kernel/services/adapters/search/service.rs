@@ -54,7 +54,7 @@ fn test_async_search() {
     let (tx, rx) = mpsc::sync_channel(64);
 
     let rope = Rope::from_str("hello world hello");
-    let _task = service.search_in_rope(rope, "hello".to_string(), true, false, tx);
+    let _task = service.search_in_rope(rope, "hello".to_string(), true, false, false, tx);
 
     let mut total_matches = 0;
     loop {
@@ -89,7 +89,7 @@ fn test_cancel_search() {
     let text = "hello ".repeat(10000);
     let rope = Rope::from_str(&text);
 
-    let task = service.search_in_rope(rope, "hello".to_string(), true, false, tx);
+    let task = service.search_in_rope(rope, "hello".to_string(), true, false, false, tx);
     task.cancel();
 
     loop {
@@ -0,0 +1,57 @@
+use super::*;
+
+#[test]
+fn save_sidebar_width_keeps_other_settings() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(SETTINGS_FILE);
+    std::fs::write(
+        &path,
+        r#"{"ui": {"worktree_bar": false, "sidebarWidth": 10}, "future_key": [1, 2]}"#,
+    )
+    .unwrap();
+
+    save_sidebar_width(&path, 42).unwrap();
+
+    let value: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(value["ui"]["sidebar_width"], 42);
+    assert_eq!(value["ui"]["worktree_bar"], false);
+    assert!(value["ui"].get("sidebarWidth").is_none());
+    assert_eq!(value["future_key"], serde_json::json!([1, 2]));
+    let settings: Settings = serde_json::from_value(value).unwrap();
+    assert_eq!(settings.ui.sidebar_width, Some(42));
+}
+
+#[test]
+fn save_sidebar_width_creates_missing_file_and_refuses_invalid_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(SETTINGS_FILE);
+    save_sidebar_width(&path, 30).unwrap();
+    let settings: Settings =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(settings.ui.sidebar_width, Some(30));
+
+    std::fs::write(&path, "{ not json").unwrap();
+    assert!(save_sidebar_width(&path, 31).is_err());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "{ not json");
+}
+
+#[test]
+fn file_template_is_looked_up_by_language_and_extension() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("rust.rs"), "fn main() {}\n").unwrap();
+
+    assert_eq!(
+        template_file_name(Path::new("/w/src/lib.rs")).as_deref(),
+        Some("rust.rs")
+    );
+    assert_eq!(
+        read_file_template(dir.path(), Path::new("/w/src/lib.rs")).as_deref(),
+        Some("fn main() {}\n")
+    );
+    assert_eq!(
+        read_file_template(dir.path(), Path::new("/w/main.py")),
+        None
+    );
+    assert_eq!(template_file_name(Path::new("/w/notes.unknown")), None);
+}
@@ -0,0 +1,61 @@
+use super::*;
+use crate::core::Command;
+use crate::kernel::services::ports::EditorConfig;
+use crate::kernel::services::KernelServiceHost;
+use crate::kernel::{AppState, FocusTarget, Store};
+use crate::models::FileTree;
+use std::ffi::OsString;
+
+fn new_store() -> Store {
+    let root = std::env::temp_dir();
+    let tree = FileTree::new_with_root_for_test(OsString::from("root"), root.clone());
+    Store::new(AppState::new(root, tree, EditorConfig::default()))
+}
+
+/// 把所有 `FocusEditor` 改写成 `FocusExplorer`，并记下看到的 `state_changed`。
+#[derive(Default)]
+struct RewriteFocus {
+    seen: Vec<bool>,
+}
+
+impl ServiceMiddleware<Action, DispatchResult> for RewriteFocus {
+    fn before_dispatch(&mut self, action: &Action) -> Option<Action> {
+        matches!(action, Action::RunCommand(Command::FocusEditor))
+            .then(|| Action::RunCommand(Command::FocusExplorer))
+    }
+
+    fn after_dispatch(&mut self, result: &DispatchResult) {
+        self.seen.push(result.state_changed);
+    }
+}
+
+#[test]
+fn middleware_runs_in_registration_order_and_sees_rewritten_actions() {
+    let mut host = KernelServiceHost::new();
+    host.register_middleware(Box::new(RewriteFocus::default()));
+    host.register_middleware(Box::new(MetricsMiddleware::new()));
+    let mut store = new_store();
+
+    let result = store.dispatch_through(
+        host.middleware_mut(),
+        Action::RunCommand(Command::FocusEditor),
+    );
+    assert!(result.state_changed);
+    assert_eq!(store.state().ui.focus, FocusTarget::Explorer);
+
+    let _ = store.dispatch_through(host.middleware_mut(), Action::Tick);
+
+    let metrics = host.middleware::<MetricsMiddleware>().unwrap();
+    // 统计排在改写之后，记到的是改写后的命令。
+    assert_eq!(
+        metrics.count(dispatch_label(&Action::RunCommand(Command::FocusExplorer))),
+        1
+    );
+    assert_eq!(
+        metrics.count(dispatch_label(&Action::RunCommand(Command::FocusEditor))),
+        0
+    );
+    assert_eq!(metrics.count(dispatch_label(&Action::Tick)), 1);
+    assert_eq!(metrics.total(), 2);
+    assert_eq!(host.middleware::<RewriteFocus>().unwrap().seen.len(), 2);
+}
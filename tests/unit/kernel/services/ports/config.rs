@@ -63,6 +63,25 @@ fn test_default_config() {
     assert!(config.show_indent_guides);
     assert!(config.lsp_hover.show_definition_source);
     assert_eq!(config.lsp_hover.definition_max_lines, 400);
+    assert_eq!(config.max_file_size_bytes, 10 * 1024 * 1024);
+    assert_eq!(config.partial_write_threshold_bytes, 10 * 1024 * 1024);
+    assert_eq!(config.large_file_threshold_mb, 50);
+}
+
+#[test]
+fn test_max_file_size_bytes_can_be_configured_from_settings_json() {
+    #[derive(serde::Deserialize)]
+    struct Wrapper {
+        editor: EditorConfig,
+    }
+
+    let parsed: Wrapper = serde_json::from_str(r#"{ "editor": { "max_file_size_bytes": 1024 } }"#)
+        .expect("parse settings snake_case");
+    assert_eq!(parsed.editor.max_file_size_bytes, 1024);
+
+    let parsed: Wrapper = serde_json::from_str(r#"{ "editor": { "maxFileSizeBytes": 2048 } }"#)
+        .expect("parse settings camelCase");
+    assert_eq!(parsed.editor.max_file_size_bytes, 2048);
 }
 
 #[test]
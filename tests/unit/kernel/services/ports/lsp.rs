@@ -1,5 +1,6 @@
 use super::*;
 use ropey::Rope;
+use std::time::Duration;
 
 // "a中😀b": chars a/中/😀/b = 4。utf8 字节 1/3/4/1，utf16 单元 1/1/2/1，utf32 各 1。
 
@@ -83,3 +84,22 @@ fn col_and_char_offset_roundtrip() {
         }
     }
 }
+
+#[test]
+fn lsp_connection_retry_in_secs_counts_down_in_whole_seconds() {
+    let now = Instant::now();
+    let status = |ms| LspConnectionStatus::Reconnecting {
+        attempt: 1,
+        retry_at: now + Duration::from_millis(ms),
+    };
+
+    assert_eq!(status(2500).retry_in_secs(now), Some(3));
+    assert_eq!(status(2000).retry_in_secs(now), Some(2));
+    assert_eq!(status(1).retry_in_secs(now), Some(1));
+    assert_eq!(status(0).retry_in_secs(now), Some(0));
+    assert_eq!(
+        status(800).retry_in_secs(now + Duration::from_secs(5)),
+        Some(0)
+    );
+    assert_eq!(LspConnectionStatus::Failed.retry_in_secs(now), None);
+}
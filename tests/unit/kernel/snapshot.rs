@@ -0,0 +1,70 @@
+use super::*;
+use crate::core::Command;
+use crate::kernel::services::ports::EditorConfig;
+use crate::kernel::{Action, EditorAction, Store};
+use crate::models::FileTree;
+use proptest::prelude::*;
+use std::ffi::OsString;
+
+const CONTENT: &str =
+    "fn main() {\n    let value = compute(1, 2);\n\n    println!(\"{value}\");\n}\n";
+
+fn store_with_file() -> Store {
+    let root = std::env::temp_dir();
+    let tree = FileTree::new_with_root_for_test(OsString::from("root"), root.clone());
+    let mut store = Store::new(AppState::new(root.clone(), tree, EditorConfig::default()));
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: root.join("snapshot.rs"),
+        content: CONTENT.to_string(),
+    }));
+    store
+}
+
+fn undoable_command() -> impl Strategy<Value = Command> {
+    prop_oneof![
+        proptest::char::range('!', '~').prop_map(Command::InsertChar),
+        Just(Command::InsertChar(' ')),
+        Just(Command::InsertNewline),
+        Just(Command::InsertTab),
+        Just(Command::DeleteBackward),
+        Just(Command::DeleteForward),
+        Just(Command::DeleteLine),
+    ]
+}
+
+/// 自动配对的 type-through：在同一个闭合符前敲它只移动光标、不改文本，也不进撤销栈，
+/// 不属于这里要验证的“可撤销编辑”。
+fn is_auto_pair_type_through(byte_offset: usize, command: &Command) -> bool {
+    matches!(command, Command::InsertChar(c @ (')' | ']' | '}' | '"' | '\''))
+        if CONTENT[byte_offset..].starts_with(*c))
+}
+
+fn undoable_edit() -> impl Strategy<Value = (usize, Command)> {
+    (0..CONTENT.len(), undoable_command()).prop_filter(
+        "auto-pair type-through only moves the cursor",
+        |(byte_offset, command)| !is_auto_pair_type_through(*byte_offset, command),
+    )
+}
+
+proptest! {
+    #[test]
+    fn undo_restores_the_snapshot_taken_before_an_edit(
+        (byte_offset, command) in undoable_edit(),
+    ) {
+        let mut store = store_with_file();
+        let _ = store.dispatch(Action::Editor(EditorAction::GotoByteOffset {
+            pane: 0,
+            byte_offset,
+        }));
+        let before = AppStateSnapshot::capture(store.state());
+
+        let _ = store.dispatch(Action::RunCommand(command));
+        let after = AppStateSnapshot::capture(store.state());
+        let _ = store.dispatch(Action::RunCommand(Command::Undo));
+
+        prop_assert_eq!(AppStateSnapshot::capture(store.state()), before.clone());
+        // 真正改了文本的命令会把标签页标脏，撤销后又回到干净状态。
+        prop_assert!(after == before || after.panes[0].tabs[0].dirty);
+    }
+}
@@ -15,3 +15,62 @@ fn explorer_move_selection_selects_first_row_when_root_selected() {
     assert!(explorer.move_selection(1));
     assert_eq!(explorer.selected(), Some(file_id));
 }
+
+#[test]
+fn compute_explorer_move_target_moves_file_into_dir() {
+    let from = PathBuf::from("/ws/src/main.rs");
+    let to_dir = PathBuf::from("/ws/tests");
+    let to = compute_explorer_move_target(&from, false, &to_dir).unwrap();
+    assert_eq!(to, PathBuf::from("/ws/tests/main.rs"));
+}
+
+#[test]
+fn compute_explorer_move_target_noops_when_dropping_into_same_parent() {
+    let from = PathBuf::from("/ws/tests/main.rs");
+    let to_dir = PathBuf::from("/ws/tests");
+    assert!(compute_explorer_move_target(&from, false, &to_dir).is_none());
+}
+
+#[test]
+fn compute_explorer_move_target_rejects_moving_dir_into_itself_or_descendant() {
+    let from = PathBuf::from("/ws/foo");
+
+    // Into itself.
+    assert!(compute_explorer_move_target(&from, true, &from).is_none());
+
+    // Into descendant.
+    let to_dir = PathBuf::from("/ws/foo/bar");
+    assert!(compute_explorer_move_target(&from, true, &to_dir).is_none());
+}
+
+#[test]
+fn explorer_drop_moves_into_dirs_and_rejects_own_subfolder() {
+    let root = PathBuf::from("/ws");
+    let mut tree = FileTree::new_with_root_for_test(OsString::from("ws"), root.clone());
+    let dir_a = tree
+        .insert_child(tree.root(), OsString::from("a"), NodeKind::Dir)
+        .unwrap();
+    let dir_b = tree
+        .insert_child(dir_a, OsString::from("b"), NodeKind::Dir)
+        .unwrap();
+    let file = tree
+        .insert_child(tree.root(), OsString::from("f.txt"), NodeKind::File)
+        .unwrap();
+    let mut explorer = ExplorerState::new(tree);
+
+    assert!(explorer.begin_drag(file));
+    assert!(!explorer.set_drag_hover(Some(file)));
+    assert!(explorer.set_drag_hover(Some(dir_a)));
+    assert_eq!(
+        explorer.drop_dragged_onto(dir_a),
+        Ok(Some((root.join("f.txt"), root.join("a/f.txt"))))
+    );
+    assert!(explorer.drag.is_none());
+
+    // 放到文件行上不做任何事。
+    assert!(explorer.begin_drag(dir_a));
+    assert_eq!(explorer.drop_dragged_onto(file), Ok(None));
+
+    assert!(explorer.begin_drag(dir_a));
+    assert!(explorer.drop_dragged_onto(dir_b).is_err());
+}
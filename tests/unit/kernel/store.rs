@@ -1,18 +1,22 @@
 use super::*;
+use crate::kernel::editor::{VimOperator, VimPending};
 use crate::kernel::language::adapter::{
     adapter_for_tab, reset_syntax_facts_descent_counter, syntax_facts_descent_counter,
 };
 use crate::kernel::services::ports::EditorConfig;
 use crate::kernel::services::ports::{
-    LspCompletionTriggerKind, LspHoverBlock, LspHoverPayload, LspInsertTextFormat, LspPosition,
-    LspRange, LspTextEdit, LspWorkspaceEdit, LspWorkspaceFileEdit,
+    GlobalSearchMessage, LspCompletionTriggerKind, LspConnectionStatus, LspDocumentLink,
+    LspHoverBlock, LspHoverPayload, LspInsertTextFormat, LspPosition, LspRange,
+    LspServerCapabilities, LspServerKind, LspTextEdit, LspWorkspaceEdit, LspWorkspaceFileEdit,
 };
 use crate::kernel::state::{
     CompletionRequestContext, ContextMenuRequest, PendingAction, PendingEditorNavigation,
-    PendingEditorNavigationTarget,
+    PendingEditorNavigationTarget, MAX_FILE_POSITIONS,
 };
+use crate::kernel::{CallHierarchyDirection, CommandLineMode, LocationItem};
 use crate::models::{FileTree, Granularity, LoadState, NodeKind, Selection};
 use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 use tempfile::tempdir;
 
@@ -155,11 +159,35 @@ fn explorer_dir_changed_triggers_dir_reload_when_loaded() {
         .expect("docs row visible");
     assert_eq!(docs_row.load_state, LoadState::Loading);
 
+    // 分批到达的条目立即可见，目录仍保持 Loading。
+    let _ = store.dispatch(Action::DirBatchLoaded {
+        path: docs_path.clone(),
+        entries: vec![DirEntryInfo {
+            name: "guide.md".to_string(),
+            is_dir: false,
+            size: None,
+        }],
+    });
+    assert!(store
+        .state
+        .explorer
+        .node_id_for_path(docs_path.join("guide.md").as_path())
+        .is_some());
+    let docs_row = store
+        .state
+        .explorer
+        .rows
+        .iter()
+        .find(|row| row.name == "docs")
+        .expect("docs row visible");
+    assert_eq!(docs_row.load_state, LoadState::Loading);
+
     let _ = store.dispatch(Action::DirLoaded {
         path: docs_path.clone(),
         entries: vec![DirEntryInfo {
             name: "api.yaml".to_string(),
             is_dir: false,
+            size: Some(2048),
         }],
     });
 
@@ -184,6 +212,7 @@ fn test_completion_item(id: u64, label: &str) -> LspCompletionItem {
         id,
         label: label.to_string(),
         detail: None,
+        label_details: None,
         kind: None,
         documentation: None,
         insert_text: label.to_string(),
@@ -315,6 +344,172 @@ fn escape_closes_command_line_first() {
     assert_eq!(store.state.ui.focus, FocusTarget::Editor);
 }
 
+fn type_into_command_line(store: &mut Store, text: &str) {
+    for ch in text.chars() {
+        let _ = store.dispatch(Action::CommandLineAppend(ch));
+    }
+}
+
+#[test]
+fn quick_open_lists_workspace_files_and_opens_fuzzy_match() {
+    let mut store = new_store();
+
+    let result = store.dispatch(Action::RunCommand(Command::QuickOpen));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::ListWorkspaceFiles]
+    ));
+    assert_eq!(store.state.ui.command_line.mode(), CommandLineMode::File);
+
+    let _ = store.dispatch(Action::CommandLineFilesListed {
+        files: vec![
+            PathBuf::from("README.md"),
+            PathBuf::from("src/kernel/store/mod.rs"),
+            PathBuf::from("src/main.rs"),
+        ],
+    });
+    type_into_command_line(&mut store, "kstmod");
+    // 同一次打开内不重复列目录。
+    assert!(store
+        .dispatch(Action::CommandLineAppend('.'))
+        .effects
+        .is_empty());
+
+    let expected = store.state.workspace_root.join("src/kernel/store/mod.rs");
+    let result = store.dispatch(Action::RunCommand(Command::CommandLineConfirm));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::LoadFile(path)] if path == &expected
+    ));
+    assert!(!store.state.ui.command_line.active);
+    assert_eq!(store.state.ui.command_line.files.len(), 3);
+}
+
+#[test]
+fn quick_open_lists_recently_opened_files_first() {
+    let mut store = new_store();
+    let root = store.state.workspace_root.clone();
+    let outside = PathBuf::from("/elsewhere/notes.md");
+    for path in [root.join("src/main.rs"), outside.clone()] {
+        let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+            pane: 0,
+            path,
+            content: String::new(),
+        }));
+    }
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: root.join("src/main.rs"),
+        content: String::new(),
+    }));
+    assert_eq!(
+        store.state.recent_files.iter().collect::<Vec<_>>(),
+        [&root.join("src/main.rs"), &outside]
+    );
+
+    let _ = store.dispatch(Action::RunCommand(Command::QuickOpen));
+    let _ = store.dispatch(Action::CommandLineFilesListed {
+        files: vec![PathBuf::from("README.md"), PathBuf::from("src/main.rs")],
+    });
+    let _ = store.dispatch(Action::RunCommand(Command::CommandLineMoveDown));
+    let result = store.dispatch(Action::RunCommand(Command::CommandLineConfirm));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::LoadFile(path)] if *path == outside
+    ));
+}
+
+#[test]
+fn goto_line_validates_input_and_moves_cursor_on_confirm() {
+    let mut store = new_store();
+    assert!(
+        !store
+            .dispatch(Action::RunCommand(Command::GotoLine))
+            .state_changed,
+        "no open file means nothing to jump to"
+    );
+
+    let path = store.state.workspace_root.join("lines.txt");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path,
+        content: "one\ntwo\nthree\nfour".to_string(),
+    }));
+    let cursor = |store: &Store| {
+        store
+            .state
+            .editor
+            .pane(0)
+            .unwrap()
+            .active_tab()
+            .unwrap()
+            .buffer
+            .cursor()
+    };
+
+    let _ = store.dispatch(Action::RunCommand(Command::GotoLine));
+    assert_eq!(store.state.ui.command_line.input, ":");
+    assert_eq!(store.state.ui.command_line.mode(), CommandLineMode::Line);
+
+    // 超出范围：命令行保持打开，光标不动。
+    type_into_command_line(&mut store, "9");
+    assert!(
+        !store
+            .dispatch(Action::RunCommand(Command::CommandLineConfirm))
+            .state_changed
+    );
+    assert!(store.state.ui.command_line.active);
+    assert_eq!(cursor(&store), (0, 0));
+
+    let _ = store.dispatch(Action::RunCommand(Command::CommandLineBackspace));
+    type_into_command_line(&mut store, "3");
+    let _ = store.dispatch(Action::RunCommand(Command::CommandLineConfirm));
+    assert!(!store.state.ui.command_line.active);
+    assert_eq!(store.state.ui.focus, FocusTarget::Editor);
+    assert_eq!(cursor(&store), (2, 0));
+
+    // Esc 关闭不移动光标。
+    let _ = store.dispatch(Action::RunCommand(Command::GotoLine));
+    type_into_command_line(&mut store, "1");
+    let _ = store.dispatch(Action::RunCommand(Command::CommandLineClose));
+    assert!(!store.state.ui.command_line.active);
+    assert_eq!(cursor(&store), (2, 0));
+}
+
+#[test]
+fn command_line_prefix_selects_mode_and_history_is_per_mode() {
+    let mut store = new_store();
+
+    let _ = store.dispatch(Action::RunCommand(Command::OpenCommandLine));
+    assert_eq!(store.state.ui.command_line.input, ">");
+    assert_eq!(store.state.ui.command_line.mode(), CommandLineMode::Command);
+    type_into_command_line(&mut store, " diagnostics");
+    let _ = store.dispatch(Action::RunCommand(Command::CommandLineConfirm));
+    assert_eq!(store.state.ui.overlay.active, Some(OverlayKind::Problems));
+
+    let _ = store.dispatch(Action::RunCommand(Command::QuickOpen));
+    let _ = store.dispatch(Action::CommandLineFilesListed {
+        files: vec![PathBuf::from("src/main.rs")],
+    });
+    type_into_command_line(&mut store, "main");
+    let _ = store.dispatch(Action::RunCommand(Command::CommandLineConfirm));
+
+    // 删掉 `>` 回到文件模式；翻历史只看当前模式。
+    let _ = store.dispatch(Action::RunCommand(Command::OpenCommandLine));
+    let _ = store.dispatch(Action::RunCommand(Command::CommandLineHistoryPrev));
+    assert_eq!(store.state.ui.command_line.input, ">diagnostics");
+    let _ = store.dispatch(Action::RunCommand(Command::CommandLineClose));
+
+    let _ = store.dispatch(Action::RunCommand(Command::QuickOpen));
+    let _ = store.dispatch(Action::RunCommand(Command::CommandLineHistoryPrev));
+    assert_eq!(store.state.ui.command_line.input, "main");
+    assert!(
+        !store
+            .dispatch(Action::RunCommand(Command::CommandLineHistoryPrev))
+            .state_changed
+    );
+}
+
 #[test]
 fn escape_focuses_editor_when_in_other_panel() {
     let mut store = new_store();
@@ -343,6 +538,36 @@ fn escape_closes_editor_search_bar() {
     assert!(!store.state.editor.pane(0).unwrap().search_bar.visible);
 }
 
+#[test]
+fn search_bar_toggle_whole_word_restarts_search_with_boundaries() {
+    let mut store = new_store();
+    store.state.ui.focus = FocusTarget::Editor;
+    let path = store.state.workspace_root.join("test.txt");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path,
+        content: "foo foobar".to_string(),
+    }));
+    let search_bar = &mut store.state.editor.pane_mut(0).unwrap().search_bar;
+    search_bar.visible = true;
+    search_bar.search_text = "foo".to_string();
+
+    let result = store.dispatch(Action::Editor(EditorAction::SearchBarToggleWholeWord {
+        pane: 0,
+    }));
+
+    assert!(result.state_changed);
+    assert!(store.state.editor.pane(0).unwrap().search_bar.whole_word);
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::StartEditorSearch {
+            pane: 0,
+            whole_word: true,
+            ..
+        }]
+    ));
+}
+
 #[test]
 fn escape_clears_editor_selection() {
     let mut store = new_store();
@@ -379,6 +604,29 @@ fn escape_clears_editor_selection() {
         .is_none());
 }
 
+#[test]
+fn open_terminal_uses_active_file_directory() {
+    let mut store = new_store();
+    let result = store.dispatch(Action::RunCommand(Command::OpenTerminalAtCurrentFilePath));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::SpawnTerminal { cwd: None }]
+    ));
+
+    let dir = store.state.workspace_root.join("src");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: dir.join("main.rs"),
+        content: "fn main() {}".to_string(),
+    }));
+    let result = store.dispatch(Action::RunCommand(Command::OpenTerminalAtCurrentFilePath));
+    assert!(!result.state_changed);
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::SpawnTerminal { cwd: Some(cwd) }] if *cwd == dir
+    ));
+}
+
 #[test]
 fn explorer_new_file_flow_creates_effect() {
     let mut store = new_store();
@@ -395,6 +643,27 @@ fn explorer_new_file_flow_creates_effect() {
     assert!(!store.state.ui.input_dialog.visible);
 }
 
+#[test]
+fn import_keybindings_prompts_for_path_and_emits_import_effect() {
+    let mut store = new_store();
+    let result = store.dispatch(Action::RunCommand(Command::ImportKeybindings));
+    assert!(result.state_changed);
+    assert!(matches!(
+        store.state.ui.input_dialog.kind,
+        Some(InputDialogKind::FilePath {
+            purpose: FilePathPurpose::ImportKeybindings
+        })
+    ));
+
+    store.state.ui.input_dialog.value = "keys.json".to_string();
+    let result = store.dispatch(Action::InputDialogAccept);
+    let expected = store.state.workspace_root.join("keys.json");
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::ImportKeybindings { path }] if *path == expected
+    ));
+}
+
 #[test]
 fn explorer_move_path_rejects_out_of_workspace_paths() {
     let ws = tempdir().unwrap();
@@ -449,6 +718,46 @@ fn confirm_dialog_rejects_out_of_workspace_file_operations() {
     assert!(result.effects.is_empty());
 }
 
+#[test]
+fn confirm_dialog_accept_opens_large_file_without_limit() {
+    let mut store = new_store();
+    let path = PathBuf::from("/tmp/huge.log");
+
+    let _ = store.dispatch(Action::ShowConfirmDialog {
+        message: "File too large (42 MB). Limit is 10 MB. Open anyway? [Y/n]".to_string(),
+        on_confirm: PendingAction::OpenLargeFile {
+            path: path.clone(),
+            size: 42 * 1024 * 1024,
+        },
+    });
+    let result = store.dispatch(Action::ConfirmDialogAccept);
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::LoadFileWithoutLimit(p)] if p == &path
+    ));
+    assert!(!store.state().ui.confirm_dialog.visible);
+}
+
+#[test]
+fn confirm_dialog_accept_loads_file_over_large_threshold_in_chunks() {
+    let mut store = new_store();
+    let path = PathBuf::from("/tmp/huge.log");
+
+    let _ = store.dispatch(Action::ShowConfirmDialog {
+        message: "Large file (200 MB). Open? [Y/n]".to_string(),
+        on_confirm: PendingAction::OpenLargeFile {
+            path: path.clone(),
+            size: 200 * 1024 * 1024,
+        },
+    });
+    let result = store.dispatch(Action::ConfirmDialogAccept);
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::LoadFileRange { path: p, start_byte: 0, end_byte }]
+            if p == &path && *end_byte == crate::kernel::editor::LARGE_FILE_CHUNK_BYTES
+    ));
+}
+
 #[test]
 fn explorer_context_menu_root_items_include_disabled_actions() {
     let mut store = new_store();
@@ -708,6 +1017,82 @@ fn editor_area_context_menu_open_sets_items() {
     );
 }
 
+#[test]
+fn editor_context_menu_command_and_copy_relative_path() {
+    let mut store = new_store();
+    store.state.ui.focus = FocusTarget::Editor;
+    let result = store.dispatch(Action::RunCommand(Command::EditorContextMenu));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::OpenEditorContextMenu { pane: 0 }]
+    ));
+
+    let path = store.state.workspace_root.join("src").join("lib.rs");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path,
+        content: "fn main() {}".to_string(),
+    }));
+    let _ = store.dispatch(Action::ContextMenuOpen {
+        request: ContextMenuRequest::EditorArea { pane: 0 },
+        x: 10,
+        y: 5,
+    });
+    let menu = &store.state.ui.context_menu;
+    let index = menu
+        .items
+        .iter()
+        .position(|item| item.label == "Copy Relative Path")
+        .unwrap();
+    assert!(menu.items[index].is_selectable());
+
+    let _ = store.dispatch(Action::ContextMenuSetSelected { index });
+    let result = store.dispatch(Action::ContextMenuConfirm);
+    let expected = std::path::Path::new("src")
+        .join("lib.rs")
+        .to_string_lossy()
+        .to_string();
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::SetClipboardText(text)] if *text == expected
+    ));
+    assert!(!store.state.ui.context_menu.visible);
+}
+
+#[test]
+fn completion_move_selection_scrolls_and_pages_within_max_lines() {
+    let mut store = new_store();
+    store.state.editor.config.completion_popup_max_lines = 10;
+    store.state.ui.completion.visible = true;
+    store.state.ui.completion.visible_indices = (0..25).collect();
+
+    for _ in 0..9 {
+        let _ = store.dispatch(Action::CompletionMoveSelection { delta: 1 });
+    }
+    assert_eq!(store.state.ui.completion.scroll_offset, 0);
+    let result = store.dispatch(Action::CompletionMoveSelection { delta: 1 });
+    assert!(result.state_changed);
+    assert_eq!(store.state.ui.completion.selected, 10);
+    assert_eq!(store.state.ui.completion.scroll_offset, 1);
+
+    let _ = store.dispatch(Action::CompletionMoveSelection { delta: 10 });
+    assert_eq!(store.state.ui.completion.selected, 20);
+    assert_eq!(store.state.ui.completion.scroll_offset, 20);
+    let _ = store.dispatch(Action::CompletionMoveSelection { delta: 10 });
+    assert_eq!(store.state.ui.completion.selected, 24);
+    assert_eq!(store.state.ui.completion.scroll_offset, 20);
+
+    let _ = store.dispatch(Action::CompletionMoveSelection { delta: -10 });
+    assert_eq!(store.state.ui.completion.selected, 14);
+    assert_eq!(store.state.ui.completion.scroll_offset, 10);
+
+    // 单步越过末尾仍然绕回顶部。
+    store.state.ui.completion.selected = 24;
+    let _ = store.dispatch(Action::CompletionMoveSelection { delta: 1 });
+    assert_eq!(store.state.ui.completion.selected, 0);
+    assert_eq!(store.state.ui.completion.scroll_offset, 0);
+}
+
 #[test]
 fn tab_context_menu_confirm_close_closes_tab_when_clean() {
     let mut store = new_store();
@@ -814,49 +1199,178 @@ fn explorer_delete_confirm_produces_delete_effect() {
 }
 
 #[test]
-fn open_file_applies_pending_editor_nav_byte_offset_and_clears_it() {
+fn lsp_implementation_with_one_location_jumps_directly() {
     let mut store = new_store();
-    let path = store.state.workspace_root.join("test.txt");
-    let content = "a😀b".to_string();
-    let byte_offset_after_emoji = "a😀".len();
-    store.state.ui.pending_editor_nav = Some(PendingEditorNavigation {
+    let path = store.state.workspace_root.join("main.rs");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
         pane: 0,
         path: path.clone(),
-        target: PendingEditorNavigationTarget::ByteOffset {
-            byte_offset: byte_offset_after_emoji,
-        },
+        content: "trait T {}\nimpl T for () {}\n".to_string(),
+    }));
+
+    let _ = store.dispatch(Action::LspImplementation {
+        items: vec![LocationItem {
+            path,
+            line: 1,
+            column: 5,
+        }],
     });
 
-    let result = store.dispatch(Action::Editor(EditorAction::OpenFile {
-        pane: 0,
-        path: path.clone(),
-        content,
-    }));
+    let tab = store.state.editor.pane(0).unwrap().active_tab().unwrap();
+    assert_eq!(tab.buffer.cursor(), (1, 5));
+    assert_eq!(store.state.ui.overlay.active, None);
+}
+
+#[test]
+fn lsp_implementation_with_several_locations_opens_locations_panel() {
+    let mut store = new_store();
+    let root = store.state.workspace_root.clone();
+    let items = vec![
+        LocationItem {
+            path: root.join("a.rs"),
+            line: 1,
+            column: 0,
+        },
+        LocationItem {
+            path: root.join("b.rs"),
+            line: 2,
+            column: 0,
+        },
+    ];
 
+    let result = store.dispatch(Action::LspImplementation { items });
     assert!(result.state_changed);
-    assert!(result.effects.is_empty());
-    assert!(store.state.ui.pending_editor_nav.is_none());
-    assert_eq!(
-        store
-            .state
-            .editor
-            .pane(0)
-            .unwrap()
-            .active_tab()
-            .unwrap()
-            .buffer
-            .cursor(),
-        (0, 2)
-    );
+    assert_eq!(store.state.locations.items().len(), 2);
+    assert_eq!(store.state.ui.overlay.active, Some(OverlayKind::Locations));
 }
 
 #[test]
-fn open_file_applies_pending_editor_nav_line_column_utf16_and_clears_it() {
+fn explorer_undo_replays_inverse_and_redo_replays_it_back() {
     let mut store = new_store();
-    let path = store.state.workspace_root.join("test.txt");
-    store.state.ui.pending_editor_nav = Some(PendingEditorNavigation {
-        pane: 0,
-        path: path.clone(),
+    let from = store.state.workspace_root.join("a.txt");
+    let to = store.state.workspace_root.join("b.txt");
+    store.state.ui.focus = FocusTarget::Explorer;
+    let _ = store.dispatch(Action::ExplorerOpApplied {
+        inverse: Effect::RenamePath {
+            from: to.clone(),
+            to: from.clone(),
+            overwrite: false,
+        },
+        origin: crate::kernel::ExplorerOpOrigin::User,
+    });
+
+    let result = store.dispatch(Action::RunCommand(Command::Undo));
+    let [Effect::ReplayExplorerOp { op, origin }] = result.effects.as_slice() else {
+        panic!("expected replay effect, got {:?}", result.effects);
+    };
+    assert_eq!(*origin, crate::kernel::ExplorerOpOrigin::Undo);
+    assert!(matches!(
+        op.as_ref(),
+        Effect::RenamePath { from: f, to: t, .. } if *f == to && *t == from
+    ));
+    assert_eq!(store.state.explorer_history.undo_len(), 0);
+
+    // 撤销执行后，运行时把它的逆操作记回重做栈。
+    let _ = store.dispatch(Action::ExplorerOpApplied {
+        inverse: Effect::RenamePath {
+            from: from.clone(),
+            to: to.clone(),
+            overwrite: false,
+        },
+        origin: crate::kernel::ExplorerOpOrigin::Undo,
+    });
+    assert_eq!(store.state.explorer_history.redo_len(), 1);
+
+    let result = store.dispatch(Action::RunCommand(Command::Redo));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::ReplayExplorerOp { op, origin: crate::kernel::ExplorerOpOrigin::Redo }]
+            if matches!(op.as_ref(), Effect::RenamePath { to: t, .. } if *t == to)
+    ));
+}
+
+#[test]
+fn explorer_user_op_clears_redo_stack() {
+    let mut store = new_store();
+    let path = store.state.workspace_root.join("new.txt");
+    store.state.explorer_history.record(
+        Effect::CreateFile(path.clone()),
+        crate::kernel::ExplorerOpOrigin::Undo,
+    );
+    assert_eq!(store.state.explorer_history.redo_len(), 1);
+
+    let _ = store.dispatch(Action::ExplorerOpApplied {
+        inverse: Effect::DeletePath {
+            path,
+            is_dir: false,
+        },
+        origin: crate::kernel::ExplorerOpOrigin::User,
+    });
+    assert_eq!(store.state.explorer_history.redo_len(), 0);
+    assert_eq!(store.state.explorer_history.undo_len(), 1);
+}
+
+#[test]
+fn undo_outside_explorer_leaves_explorer_history_alone() {
+    let mut store = new_store();
+    store.state.ui.focus = FocusTarget::Editor;
+    store.state.explorer_history.record(
+        Effect::CreateDir(store.state.workspace_root.join("d")),
+        crate::kernel::ExplorerOpOrigin::User,
+    );
+
+    let result = store.dispatch(Action::RunCommand(Command::Undo));
+    assert!(!result
+        .effects
+        .iter()
+        .any(|effect| matches!(effect, Effect::ReplayExplorerOp { .. })));
+    assert_eq!(store.state.explorer_history.undo_len(), 1);
+}
+
+#[test]
+fn open_file_applies_pending_editor_nav_byte_offset_and_clears_it() {
+    let mut store = new_store();
+    let path = store.state.workspace_root.join("test.txt");
+    let content = "a😀b".to_string();
+    let byte_offset_after_emoji = "a😀".len();
+    store.state.ui.pending_editor_nav = Some(PendingEditorNavigation {
+        pane: 0,
+        path: path.clone(),
+        target: PendingEditorNavigationTarget::ByteOffset {
+            byte_offset: byte_offset_after_emoji,
+        },
+    });
+
+    let result = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: path.clone(),
+        content,
+    }));
+
+    assert!(result.state_changed);
+    assert!(result.effects.is_empty());
+    assert!(store.state.ui.pending_editor_nav.is_none());
+    assert_eq!(
+        store
+            .state
+            .editor
+            .pane(0)
+            .unwrap()
+            .active_tab()
+            .unwrap()
+            .buffer
+            .cursor(),
+        (0, 2)
+    );
+}
+
+#[test]
+fn open_file_applies_pending_editor_nav_line_column_utf16_and_clears_it() {
+    let mut store = new_store();
+    let path = store.state.workspace_root.join("test.txt");
+    store.state.ui.pending_editor_nav = Some(PendingEditorNavigation {
+        pane: 0,
+        path: path.clone(),
         target: PendingEditorNavigationTarget::LineColumn { line: 0, column: 3 },
     });
 
@@ -1134,6 +1648,7 @@ fn lsp_completion_items_are_filtered_and_sorted() {
             id: 1,
             label: "self::".to_string(),
             detail: None,
+            label_details: None,
             kind: None,
             documentation: None,
             insert_text: "self::".to_string(),
@@ -1150,6 +1665,7 @@ fn lsp_completion_items_are_filtered_and_sorted() {
             id: 2,
             label: "Alignment".to_string(),
             detail: None,
+            label_details: None,
             kind: None,
             documentation: None,
             insert_text: "Alignment".to_string(),
@@ -1166,6 +1682,7 @@ fn lsp_completion_items_are_filtered_and_sorted() {
             id: 3,
             label: "println!".to_string(),
             detail: None,
+            label_details: None,
             kind: None,
             documentation: None,
             insert_text: "println!".to_string(),
@@ -1182,6 +1699,7 @@ fn lsp_completion_items_are_filtered_and_sorted() {
             id: 4,
             label: "Print".to_string(),
             detail: None,
+            label_details: None,
             kind: None,
             documentation: None,
             insert_text: "Print".to_string(),
@@ -1241,6 +1759,7 @@ fn completion_selected_id_stays_stable_when_visible_indices_change() {
             id: 1,
             label: "print".to_string(),
             detail: None,
+            label_details: None,
             kind: None,
             documentation: Some("doc".to_string()),
             insert_text: "print".to_string(),
@@ -1257,6 +1776,7 @@ fn completion_selected_id_stays_stable_when_visible_indices_change() {
             id: 2,
             label: "println!".to_string(),
             detail: None,
+            label_details: None,
             kind: None,
             documentation: Some("doc".to_string()),
             insert_text: "println!".to_string(),
@@ -1273,6 +1793,7 @@ fn completion_selected_id_stays_stable_when_visible_indices_change() {
             id: 3,
             label: "probe".to_string(),
             detail: None,
+            label_details: None,
             kind: None,
             documentation: Some("doc".to_string()),
             insert_text: "probe".to_string(),
@@ -1289,6 +1810,7 @@ fn completion_selected_id_stays_stable_when_visible_indices_change() {
             id: 4,
             label: "prio".to_string(),
             detail: None,
+            label_details: None,
             kind: None,
             documentation: Some("doc".to_string()),
             insert_text: "prio".to_string(),
@@ -1537,6 +2059,7 @@ fn lsp_completion_resolve_updates_insert_payload_fields() {
             id: 1,
             label: "print".to_string(),
             detail: None,
+            label_details: None,
             kind: None,
             documentation: None,
             insert_text: "print".to_string(),
@@ -1618,6 +2141,7 @@ fn lsp_completion_requests_resolve_for_documented_unresolved_selected_item() {
             id: 7,
             label: "insert".to_string(),
             detail: Some("fn insert".to_string()),
+            label_details: None,
             kind: Some(2),
             documentation: Some("docs already present".to_string()),
             insert_text: "insert($1, $2)$0".to_string(),
@@ -1739,6 +2263,7 @@ fn completion_does_not_close_on_viewport_resize() {
         id: 1,
         label: "println!".to_string(),
         detail: None,
+        label_details: None,
         kind: None,
         documentation: None,
         insert_text: "println!".to_string(),
@@ -1787,6 +2312,7 @@ fn completion_does_not_close_on_editor_search_messages() {
         id: 1,
         label: "println!".to_string(),
         detail: None,
+        label_details: None,
         kind: None,
         documentation: None,
         insert_text: "println!".to_string(),
@@ -2806,6 +3332,126 @@ fn completion_plain_text_keeps_server_text_literal() {
     assert!(insertion.selection.is_none());
 }
 
+#[test]
+fn user_snippet_trigger_expands_on_space_as_single_undo_step() {
+    let mut store = new_store();
+    store.state.ui.focus = FocusTarget::Editor;
+    store
+        .state
+        .editor
+        .config
+        .snippets
+        .insert("fn".to_string(), "fn ${1:name}() {\n\t$0\n}".to_string());
+
+    let path = store.state.workspace_root.join("user_snippet.rs");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path,
+        content: String::new(),
+    }));
+    let _ = store.dispatch(Action::RunCommand(Command::InsertTab));
+    let _ = store.dispatch(Action::RunCommand(Command::InsertChar('f')));
+    let _ = store.dispatch(Action::RunCommand(Command::InsertChar('n')));
+    let result = store.dispatch(Action::RunCommand(Command::InsertChar(' ')));
+    assert!(result.state_changed);
+
+    let tab = store
+        .state
+        .editor
+        .pane(0)
+        .and_then(|pane| pane.active_tab())
+        .expect("tab exists");
+    assert_eq!(tab.buffer.text(), "\tfn name() {\n\t\t\n\t}");
+    assert!(tab
+        .buffer
+        .selection()
+        .is_some_and(|sel| sel.range() == ((0, 4), (0, 8))));
+    assert!(tab.snippet_active_range().is_some());
+
+    let _ = store.dispatch(Action::RunCommand(Command::Undo));
+    let tab = store
+        .state
+        .editor
+        .pane(0)
+        .and_then(|pane| pane.active_tab())
+        .expect("tab exists");
+    assert_eq!(tab.buffer.text(), "\tfn");
+}
+
+#[test]
+fn user_snippet_trigger_ignores_member_access_and_unknown_words() {
+    let mut store = new_store();
+    store.state.ui.focus = FocusTarget::Editor;
+    store
+        .state
+        .editor
+        .config
+        .snippets
+        .insert("fn".to_string(), "fn $1()".to_string());
+
+    let path = store.state.workspace_root.join("user_snippet_member.rs");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path,
+        content: String::new(),
+    }));
+    for ch in "a.fn fun".chars() {
+        let _ = store.dispatch(Action::RunCommand(Command::InsertChar(ch)));
+    }
+    let _ = store.dispatch(Action::RunCommand(Command::InsertNewline));
+
+    let tab = store
+        .state
+        .editor
+        .pane(0)
+        .and_then(|pane| pane.active_tab())
+        .expect("tab exists");
+    assert_eq!(tab.buffer.text(), "a.fn fun\n");
+}
+
+#[test]
+fn insert_snippet_action_replaces_selection() {
+    let mut store = new_store();
+    store.state.ui.focus = FocusTarget::Editor;
+
+    let path = store.state.workspace_root.join("insert_snippet.rs");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path,
+        content: "let x = value;".to_string(),
+    }));
+    {
+        let tab = store
+            .state
+            .editor
+            .pane_mut(0)
+            .and_then(|pane| pane.active_tab_mut())
+            .expect("tab exists");
+        tab.buffer
+            .set_selection(Some(Selection::new((0, 8), Granularity::Char)));
+        tab.buffer.update_selection_cursor((0, 13));
+        tab.buffer.set_cursor(0, 13);
+    }
+
+    let result = store.dispatch(Action::EditorInsertSnippet {
+        pane: 0,
+        template: "Some(${1:value})".to_string(),
+    });
+    assert!(result.state_changed);
+
+    let tab = store
+        .state
+        .editor
+        .pane(0)
+        .and_then(|pane| pane.active_tab())
+        .expect("tab exists");
+    assert_eq!(tab.buffer.text(), "let x = Some(value);");
+    assert!(tab
+        .buffer
+        .selection()
+        .is_some_and(|sel| sel.range() == ((0, 13), (0, 18))));
+}
+
 #[test]
 fn snippet_tab_navigation_moves_between_placeholders() {
     let mut store = new_store();
@@ -2909,6 +3555,49 @@ fn snippet_tab_navigation_moves_between_placeholders() {
     assert_eq!(tab.buffer.text(), "fn name(arg) { x\t }");
 }
 
+#[test]
+fn escape_on_empty_snippet_placeholder_ends_the_session() {
+    let mut store = new_store();
+    store.state.ui.focus = FocusTarget::Editor;
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: store.state.workspace_root.join("snippet_escape.rs"),
+        content: String::new(),
+    }));
+
+    let tab_size = store.state.editor.config.tab_size;
+    let insertion = CompletionInsertion::from_plan(
+        crate::kernel::language::adapter::TextEditPlan::from_snippet("call($1)$0"),
+    );
+    {
+        let tab = store
+            .state
+            .editor
+            .pane_mut(0)
+            .and_then(|pane| pane.active_tab_mut())
+            .expect("tab exists");
+        assert!(tab.insert_text(&insertion.text, tab_size));
+        apply_completion_insertion_cursor(tab, &insertion, tab_size);
+        assert!(tab.buffer.selection().is_none());
+        assert!(tab.snippet_active_range().is_some());
+    }
+
+    let result = store.dispatch(Action::RunCommand(Command::Escape));
+    assert!(result.state_changed);
+    let tab = store.state.editor.pane(0).unwrap().active_tab().unwrap();
+    assert!(tab.snippet_active_range().is_none());
+    assert!(
+        !store
+            .dispatch(Action::RunCommand(Command::Escape))
+            .state_changed
+    );
+
+    // 会话结束后 Tab 回到普通缩进。
+    let _ = store.dispatch(Action::RunCommand(Command::InsertTab));
+    let tab = store.state.editor.pane(0).unwrap().active_tab().unwrap();
+    assert_eq!(tab.buffer.text(), "call(\t)");
+}
+
 #[test]
 fn lsp_position_to_byte_offset_handles_emoji_crlf_and_out_of_bounds() {
     let mut store = new_store();
@@ -3190,3 +3879,1573 @@ fn context_menu_move_selection_skips_disabled_entries() {
         "selection should skip separator/disabled rows"
     );
 }
+
+fn open_rust_tab_with_document_link_caps(store: &mut Store, content: &str) -> std::path::PathBuf {
+    let path = store.state.workspace_root.join("links.rs");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: path.clone(),
+        content: content.to_string(),
+    }));
+    let _ = store.dispatch(Action::LspServerCapabilities {
+        server: LspServerKind::RustAnalyzer,
+        root: store.state.workspace_root.clone(),
+        capabilities: LspServerCapabilities {
+            document_link: true,
+            document_link_resolve: true,
+            ..Default::default()
+        },
+    });
+    path
+}
+
+fn document_link(line: u32, start: u32, end: u32, target: Option<&str>) -> LspDocumentLink {
+    LspDocumentLink {
+        range: LspRange {
+            start: LspPosition {
+                line,
+                character: start,
+            },
+            end: LspPosition {
+                line,
+                character: end,
+            },
+        },
+        target: target.map(str::to_string),
+        data: target.is_none().then(|| serde_json::json!({ "id": 1 })),
+    }
+}
+
+#[test]
+fn document_links_store_byte_ranges_and_ctrl_click_opens_target() {
+    let mut store = new_store();
+    let content = "// docs\n// see https://example.com/x\n";
+    let path = open_rust_tab_with_document_link_caps(&mut store, content);
+
+    let result = store.dispatch(Action::LspDocumentLinks {
+        path,
+        version: 0,
+        links: vec![document_link(1, 7, 28, Some("https://example.com/x"))],
+    });
+    assert!(result.state_changed);
+
+    let tab = store.state.editor.pane(0).unwrap().active_tab().unwrap();
+    let line_start = "// docs\n".len();
+    assert_eq!(
+        tab.document_links,
+        vec![(
+            line_start + 7,
+            line_start + 28,
+            "https://example.com/x".to_string()
+        )]
+    );
+
+    let result = store.dispatch(Action::OpenDocumentLinkAt {
+        pane: 0,
+        row: 1,
+        col: 10,
+    });
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::OpenTerminalUrl(url)] if url == "https://example.com/x"
+    ));
+
+    let result = store.dispatch(Action::OpenDocumentLinkAt {
+        pane: 0,
+        row: 0,
+        col: 1,
+    });
+    assert!(result.effects.is_empty());
+}
+
+#[test]
+fn document_link_without_target_resolves_lazily_then_opens() {
+    let mut store = new_store();
+    let content = "// see docs\n";
+    let path = open_rust_tab_with_document_link_caps(&mut store, content);
+
+    let link = document_link(0, 7, 11, None);
+    let _ = store.dispatch(Action::LspDocumentLinks {
+        path: path.clone(),
+        version: 0,
+        links: vec![link.clone()],
+    });
+
+    let result = store.dispatch(Action::OpenDocumentLinkAt {
+        pane: 0,
+        row: 0,
+        col: 8,
+    });
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::LspDocumentLinkResolveRequest { version: 0, .. }]
+    ));
+
+    let result = store.dispatch(Action::LspDocumentLinkResolved {
+        path,
+        version: 0,
+        range: link.range,
+        target: "https://example.com/docs".to_string(),
+    });
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::OpenTerminalUrl(url)] if url == "https://example.com/docs"
+    ));
+    let tab = store.state.editor.pane(0).unwrap().active_tab().unwrap();
+    assert!(tab.unresolved_document_links.is_empty());
+    assert_eq!(tab.document_links.len(), 1);
+}
+
+#[test]
+fn document_links_are_dropped_on_edit() {
+    let mut store = new_store();
+    let path = open_rust_tab_with_document_link_caps(&mut store, "// https://a.b\n");
+    let _ = store.dispatch(Action::LspDocumentLinks {
+        path,
+        version: 0,
+        links: vec![document_link(0, 3, 14, Some("https://a.b"))],
+    });
+
+    let _ = store.dispatch(Action::RunCommand(Command::InsertChar('x')));
+
+    let tab = store.state.editor.pane(0).unwrap().active_tab().unwrap();
+    assert!(tab.document_links.is_empty());
+}
+
+#[test]
+fn document_highlights_follow_cursor_identifier_and_clear_on_edit() {
+    let mut store = new_store();
+    let path = store.state.workspace_root.join("highlight.rs");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: path.clone(),
+        content: "let foo = foo;\n".to_string(),
+    }));
+    let _ = store.dispatch(Action::LspServerCapabilities {
+        server: LspServerKind::RustAnalyzer,
+        root: store.state.workspace_root.clone(),
+        capabilities: LspServerCapabilities {
+            document_highlight: true,
+            ..Default::default()
+        },
+    });
+
+    let result = store.dispatch(Action::RunCommand(Command::LspDocumentHighlight));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::LspDocumentHighlightRequest {
+            line: 0,
+            column: 0,
+            version: 0,
+            ..
+        }]
+    ));
+
+    let range = |start: u32, end: u32| LspRange {
+        start: LspPosition {
+            line: 0,
+            character: start,
+        },
+        end: LspPosition {
+            line: 0,
+            character: end,
+        },
+    };
+    let result = store.dispatch(Action::LspDocumentHighlights {
+        path: path.clone(),
+        version: 0,
+        ranges: vec![range(10, 13), range(4, 7)],
+    });
+    assert!(result.state_changed);
+    let tab = store.state.editor.pane(0).unwrap().active_tab().unwrap();
+    assert_eq!(tab.document_highlights, vec![range(4, 7), range(10, 13)]);
+
+    let result = store.dispatch(Action::LspDocumentHighlights {
+        path,
+        version: 7,
+        ranges: vec![range(0, 3)],
+    });
+    assert!(!result.state_changed);
+
+    let _ = store.dispatch(Action::RunCommand(Command::InsertChar('x')));
+    let tab = store.state.editor.pane(0).unwrap().active_tab().unwrap();
+    assert!(tab.document_highlights.is_empty());
+}
+
+#[test]
+fn export_keybindings_prompts_for_path_and_emits_export_effect() {
+    let mut store = new_store();
+    let result = store.dispatch(Action::RunCommand(Command::ExportKeybindings));
+    assert!(result.state_changed);
+    let dialog = &store.state.ui.input_dialog;
+    assert!(dialog.visible);
+    assert!(matches!(
+        dialog.kind,
+        Some(InputDialogKind::FilePath {
+            purpose: FilePathPurpose::ExportKeybindings
+        })
+    ));
+
+    store.state.ui.input_dialog.value = "keys.json".to_string();
+    let result = store.dispatch(Action::InputDialogAccept);
+    let expected = store.state.workspace_root.join("keys.json");
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::ExportKeybindings { path }] if *path == expected
+    ));
+    assert!(!store.state.ui.input_dialog.visible);
+}
+
+#[test]
+fn export_as_html_prefills_path_next_to_the_file() {
+    let mut store = new_store();
+    let result = store.dispatch(Action::RunCommand(Command::ExportAsHtml));
+    assert!(!result.state_changed);
+
+    let path = store.state.workspace_root.join("src").join("main.rs");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: path.clone(),
+        content: "fn main() {}\n".to_string(),
+    }));
+    let result = store.dispatch(Action::RunCommand(Command::ExportAsHtml));
+    assert!(result.state_changed);
+    let dialog = &store.state.ui.input_dialog;
+    assert!(dialog.visible);
+    let expected = path.with_file_name("main.rs.html");
+    assert_eq!(dialog.value, expected.to_string_lossy());
+
+    let result = store.dispatch(Action::InputDialogAccept);
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::ExportAsHtml { path }] if *path == expected
+    ));
+}
+
+#[test]
+fn workspace_folders_are_added_via_dialog_and_removed_by_index() {
+    let mut store = new_store();
+    let _ = store.dispatch(Action::RunCommand(Command::AddWorkspaceFolder));
+    store.state.ui.input_dialog.value = "/srv/shared".to_string();
+    let result = store.dispatch(Action::InputDialogAccept);
+    let requested = PathBuf::from("/srv/shared");
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::AddWorkspaceFolder { path }] if *path == requested
+    ));
+
+    let result = store.dispatch(Action::WorkspaceFolderAdded {
+        path: requested.clone(),
+    });
+    assert!(result.state_changed);
+    assert_eq!(store.state.workspace_folders.len(), 2);
+    assert!(store.state.explorer.node_id_for_path(&requested).is_some());
+    assert!(store
+        .state
+        .explorer
+        .rows
+        .iter()
+        .any(|row| row.name == "shared"));
+
+    let duplicate = store.dispatch(Action::WorkspaceFolderAdded {
+        path: requested.clone(),
+    });
+    assert!(!duplicate.state_changed);
+
+    let primary = store.dispatch(Action::RunCommand(Command::RemoveWorkspaceFolder(0)));
+    assert!(!primary.state_changed);
+
+    let result = store.dispatch(Action::RunCommand(Command::RemoveWorkspaceFolder(1)));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::RemoveWorkspaceFolder { path }] if *path == requested
+    ));
+    assert_eq!(
+        store.state.workspace_folders,
+        vec![store.state.workspace_root.clone()]
+    );
+    assert!(store.state.explorer.node_id_for_path(&requested).is_none());
+}
+
+#[test]
+fn git_blame_results_are_cached_until_the_file_changes_on_disk() {
+    let mut store = new_store();
+    let path = store.state.workspace_root.join("blame.rs");
+    let blame = crate::kernel::GitBlameLine {
+        commit: "1a2b3c4".to_string(),
+        author: "Jane Doe".to_string(),
+        timestamp: 1_760_000_000,
+    };
+
+    let result = store.dispatch(Action::GitBlameResult {
+        path: path.clone(),
+        line: 3,
+        blame: blame.clone(),
+    });
+    assert!(result.state_changed);
+    assert_eq!(store.state.git.blame_for(&path, 3), Some(&blame));
+    assert_eq!(store.state.git.blame_for(&path, 4), None);
+
+    let _ = store.dispatch(Action::Editor(EditorAction::FileExternallyModified {
+        path: path.clone(),
+    }));
+    assert_eq!(store.state.git.blame_for(&path, 3), None);
+}
+
+fn test_stash(index: usize, message: &str) -> crate::kernel::GitStash {
+    crate::kernel::GitStash {
+        index,
+        message: message.to_string(),
+        datetime: "2026-10-01 12:00:00 +0800".to_string(),
+    }
+}
+
+#[test]
+fn git_panel_lists_stashes_and_enter_toggles_section_or_applies() {
+    let mut store = new_store();
+    let result = store.dispatch(Action::RunCommand(Command::OpenGitPanel));
+    assert!(result.state_changed);
+    assert!(matches!(result.effects.as_slice(), [Effect::GitStashList]));
+    assert_eq!(store.state.ui.overlay.active, Some(OverlayKind::Git));
+
+    let result = store.dispatch(Action::GitStashList {
+        stashes: vec![
+            test_stash(0, "WIP on main"),
+            test_stash(1, "On main: spike"),
+        ],
+    });
+    assert!(result.state_changed);
+    assert_eq!(store.state.git.rows().len(), 3);
+
+    let _ = store.dispatch(Action::RunCommand(Command::SearchResultsMoveDown));
+    let _ = store.dispatch(Action::RunCommand(Command::SearchResultsMoveDown));
+    let result = store.dispatch(Action::RunCommand(Command::SearchResultsOpenSelected));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::GitStashApply { index: 1 }]
+    ));
+
+    let _ = store.dispatch(Action::GitClickRow { row: 0 });
+    let result = store.dispatch(Action::RunCommand(Command::SearchResultsOpenSelected));
+    assert!(result.state_changed);
+    assert!(!store.state.git.stashes_expanded());
+    assert_eq!(
+        store.state.git.rows(),
+        &[crate::kernel::GitPanelRow::StashesHeader]
+    );
+}
+
+#[test]
+fn git_stash_context_menu_drop_requires_confirmation() {
+    let mut store = new_store();
+    let _ = store.dispatch(Action::RunCommand(Command::OpenGitPanel));
+    let _ = store.dispatch(Action::GitStashList {
+        stashes: vec![test_stash(0, "WIP on main")],
+    });
+
+    let _ = store.dispatch(Action::ContextMenuOpen {
+        request: ContextMenuRequest::GitStash { index: 0 },
+        x: 10,
+        y: 5,
+    });
+    let drop_index = store
+        .state
+        .ui
+        .context_menu
+        .items
+        .iter()
+        .position(|item| item.label == "Drop Stash")
+        .expect("drop item exists");
+    let _ = store.dispatch(Action::ContextMenuSetSelected { index: drop_index });
+
+    let result = store.dispatch(Action::ContextMenuConfirm);
+    assert!(result.effects.is_empty());
+    assert!(store.state.ui.confirm_dialog.visible);
+    assert!(matches!(
+        store.state.ui.confirm_dialog.on_confirm,
+        Some(PendingAction::GitStashDrop { index: 0 })
+    ));
+
+    let result = store.dispatch(Action::ConfirmDialogAccept);
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::GitStashDrop { index: 0 }]
+    ));
+}
+
+#[test]
+fn git_checkout_branch_picker_checks_out_selection_or_creates_typed_name() {
+    let mut store = new_store();
+    let result = store.dispatch(Action::RunCommand(Command::GitCheckoutBranch));
+    assert!(matches!(result.effects.as_slice(), [Effect::GitBranchList]));
+    assert_eq!(store.state.ui.command_line.mode(), CommandLineMode::Branch);
+
+    let _ = store.dispatch(Action::GitBranchesUpdated {
+        branches: vec![
+            crate::kernel::GitBranch {
+                name: "main".to_string(),
+                remote: false,
+                current: true,
+            },
+            crate::kernel::GitBranch {
+                name: "origin/feature/x".to_string(),
+                remote: true,
+                current: false,
+            },
+        ],
+    });
+
+    // 当前分支不需要 checkout。
+    let result = store.dispatch(Action::RunCommand(Command::CommandLineConfirm));
+    assert!(result.effects.is_empty());
+    assert!(!store.state.ui.command_line.active);
+
+    let _ = store.dispatch(Action::RunCommand(Command::GitCheckoutBranch));
+    let _ = store.dispatch(Action::RunCommand(Command::CommandLineMoveDown));
+    let result = store.dispatch(Action::RunCommand(Command::CommandLineConfirm));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::GitCheckout { branch, create: false }] if branch == "feature/x"
+    ));
+
+    let _ = store.dispatch(Action::RunCommand(Command::GitCheckoutBranch));
+    type_into_command_line(&mut store, "fix/typo");
+    let result = store.dispatch(Action::RunCommand(Command::CommandLineConfirm));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::GitCheckout { branch, create: true }] if branch == "fix/typo"
+    ));
+    assert!(!store.state.ui.command_line.branch_picker);
+}
+
+#[test]
+fn clipboard_history_picker_pastes_selected_entry() {
+    let mut store = new_store();
+    let path = store.state.workspace_root.join("clip.rs");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path,
+        content: String::new(),
+    }));
+
+    let result = store.dispatch(Action::RunCommand(Command::PastePrevious));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::CycleClipboardHistory { pane: 0, delta: 1 }]
+    ));
+
+    let result = store.dispatch(Action::RunCommand(Command::ClipboardHistory));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::RequestClipboardHistory]
+    ));
+    assert_eq!(
+        store.state.ui.command_line.mode(),
+        CommandLineMode::Clipboard
+    );
+
+    let _ = store.dispatch(Action::ClipboardHistoryLoaded {
+        entries: vec!["newest".to_string(), "older\nline".to_string()],
+    });
+    type_into_command_line(&mut store, "old");
+    let _ = store.dispatch(Action::RunCommand(Command::CommandLineConfirm));
+
+    assert!(!store.state.ui.command_line.active);
+    assert!(!store.state.ui.command_line.clipboard_picker);
+    assert_eq!(store.state.ui.focus, FocusTarget::Editor);
+    let tab = store.state.editor.pane(0).unwrap().active_tab().unwrap();
+    assert_eq!(tab.buffer.text(), "older\nline");
+}
+
+#[test]
+fn git_diff_view_loads_index_base_and_stages_selected_hunk() {
+    let mut store = new_store();
+    let path = store.state.workspace_root.join("diff.rs");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: path.clone(),
+        content: "a\nx\nc\nd\ny\n".to_string(),
+    }));
+
+    let result = store.dispatch(Action::RunCommand(Command::ShowGitDiff));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::GitLoadDiffBase { path: p }] if *p == path
+    ));
+    assert_eq!(store.state.ui.overlay.active, None);
+
+    let result = store.dispatch(Action::GitDiffBaseLoaded {
+        path: path.clone(),
+        content: "a\nb\nc\nd\n".to_string(),
+    });
+    assert!(result.state_changed);
+    assert_eq!(store.state.ui.overlay.active, Some(OverlayKind::GitDiff));
+    let view = store.state.git.diff.as_ref().expect("diff view");
+    assert_eq!(view.diff.hunks.len(), 2);
+    assert_eq!(view.selected_hunk(), Some(0));
+
+    let _ = store.dispatch(Action::RunCommand(Command::SearchResultsMoveDown));
+    let _ = store.dispatch(Action::RunCommand(Command::SearchResultsMoveDown));
+    assert_eq!(
+        store
+            .state
+            .git
+            .diff
+            .as_ref()
+            .and_then(|v| v.selected_hunk()),
+        Some(1)
+    );
+    let result = store.dispatch(Action::RunCommand(Command::SearchResultsOpenSelected));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::GitStageHunk { path: p, hunk_index: 1 }] if *p == path
+    ));
+
+    // 暂存后暂存区版本回来，视图原地刷新，选中块夹到剩下的范围里。
+    let _ = store.dispatch(Action::GitDiffBaseLoaded {
+        path: path.clone(),
+        content: "a\nb\nc\nd\ny\n".to_string(),
+    });
+    let view = store.state.git.diff.as_ref().expect("diff view");
+    assert_eq!(view.diff.hunks.len(), 1);
+    assert_eq!(view.selected_hunk(), Some(0));
+    assert_eq!(store.state.ui.overlay.active, Some(OverlayKind::GitDiff));
+}
+
+#[test]
+fn diagnostic_under_mouse_shows_in_hover_and_click_requests_code_actions() {
+    let mut store = new_store();
+    let path = store.state.workspace_root.join("main.rs");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: path.clone(),
+        content: "fn main() {\n    let x = 1\n}\n".to_string(),
+    }));
+    let _ = store.dispatch(Action::LspDiagnostics {
+        path: path.clone(),
+        items: vec![crate::kernel::ProblemItem {
+            path: path.clone(),
+            range: crate::kernel::ProblemRange {
+                start_line: 1,
+                start_col: 8,
+                end_line: 1,
+                end_col: 9,
+            },
+            severity: crate::kernel::ProblemSeverity::Warning,
+            message: "unused variable: `x`".to_string(),
+            source: Some("rustc".to_string()),
+        }],
+    });
+
+    let result = store.dispatch(Action::ShowDiagnosticHover {
+        pane: 0,
+        row: 1,
+        col: 2,
+    });
+    assert!(!result.state_changed);
+    assert!(!store.state.ui.hover.is_active());
+
+    let result = store.dispatch(Action::ShowDiagnosticHover {
+        pane: 0,
+        row: 1,
+        col: 8,
+    });
+    assert!(result.state_changed);
+    assert_eq!(
+        store.state.ui.hover.display_text().as_deref(),
+        Some("warning: unused variable: `x` (rustc)")
+    );
+
+    let result = store.dispatch(Action::DiagnosticClicked {
+        pane: 0,
+        row: 0,
+        col: 3,
+    });
+    assert!(result.effects.is_empty());
+    assert_eq!(store.state.ui.overlay.active, None);
+
+    let _ = store.dispatch(Action::Editor(EditorAction::PlaceCursor {
+        pane: 0,
+        row: 1,
+        col: 8,
+        granularity: Granularity::Char,
+    }));
+    let result = store.dispatch(Action::DiagnosticClicked {
+        pane: 0,
+        row: 1,
+        col: 8,
+    });
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::LspCodeActionRequest { path: p, line: 1, column: 8 }] if *p == path
+    ));
+    assert_eq!(
+        store.state.ui.overlay.active,
+        Some(OverlayKind::CodeActions)
+    );
+}
+
+fn test_call_item(
+    name: &str,
+    file: &str,
+    line: u32,
+) -> crate::kernel::services::ports::LspCallHierarchyItem {
+    crate::kernel::services::ports::LspCallHierarchyItem {
+        name: name.to_string(),
+        detail: None,
+        kind: 12,
+        path: std::env::temp_dir().join(file),
+        line,
+        column: 3,
+        raw: serde_json::json!({ "name": name }),
+    }
+}
+
+#[test]
+fn call_hierarchy_expands_incoming_calls_recursively_and_opens_caller() {
+    let mut store = new_store();
+    let result = store.dispatch(Action::LspCallHierarchyPrepared {
+        items: vec![test_call_item("parse", "parser.rs", 10)],
+    });
+    assert!(result.state_changed);
+    assert_eq!(
+        store.state.ui.overlay.active,
+        Some(OverlayKind::CallHierarchy)
+    );
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::LspCallHierarchyIncomingRequest { item_index: 0, item }] if item.name == "parse"
+    ));
+
+    let result = store.dispatch(Action::LspCallHierarchyIncoming {
+        item_index: 0,
+        items: vec![
+            test_call_item("load", "loader.rs", 4),
+            test_call_item("main", "main.rs", 1),
+        ],
+    });
+    assert!(result.state_changed);
+    assert_eq!(store.state.call_hierarchy.rows(), &[0, 1, 2]);
+    assert_eq!(store.state.call_hierarchy.node(2).map(|n| n.depth), Some(1));
+
+    // 展开第二层：`load` 的调用方挂在它下面，`main` 仍排在其后。
+    let result = store.dispatch(Action::CallHierarchyExpand { item_index: 1 });
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::LspCallHierarchyIncomingRequest { item_index: 1, .. }]
+    ));
+    let _ = store.dispatch(Action::LspCallHierarchyIncoming {
+        item_index: 1,
+        items: vec![test_call_item("run", "app.rs", 7)],
+    });
+    assert_eq!(store.state.call_hierarchy.rows(), &[0, 1, 3, 2]);
+    assert_eq!(store.state.call_hierarchy.node(3).map(|n| n.depth), Some(2));
+
+    // 已加载的节点再次展开只折叠，不重复请求；迟到的响应被丢弃。
+    let result = store.dispatch(Action::CallHierarchyExpand { item_index: 1 });
+    assert!(result.effects.is_empty());
+    assert_eq!(store.state.call_hierarchy.rows(), &[0, 1, 2]);
+    let result = store.dispatch(Action::LspCallHierarchyIncoming {
+        item_index: 1,
+        items: vec![test_call_item("stale", "stale.rs", 0)],
+    });
+    assert!(!result.state_changed);
+
+    let _ = store.dispatch(Action::CallHierarchyClickRow { row: 2 });
+    let result = store.dispatch(Action::RunCommand(Command::SearchResultsOpenSelected));
+    let main_path = std::env::temp_dir().join("main.rs");
+    assert!(matches!(result.effects.as_slice(), [Effect::LoadFile(path)] if *path == main_path));
+    assert_eq!(store.state.ui.focus, FocusTarget::Editor);
+    assert!(matches!(
+        store
+            .state
+            .ui
+            .pending_editor_nav
+            .as_ref()
+            .map(|nav| &nav.target),
+        Some(PendingEditorNavigationTarget::LineColumn { line: 1, column: 3 })
+    ));
+}
+
+#[test]
+fn outgoing_call_hierarchy_requests_callees_and_ignores_incoming_responses() {
+    let mut store = new_store();
+    let path = store.state.workspace_root.join("calls.rs");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: path.clone(),
+        content: "fn parse() { load(); }".to_string(),
+    }));
+
+    let result = store.dispatch(Action::RunCommand(Command::LspCallHierarchyOutgoing));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::LspPrepareCallHierarchyRequest { path: p, .. }] if *p == path
+    ));
+    assert_eq!(
+        store.state.call_hierarchy.direction(),
+        CallHierarchyDirection::Outgoing
+    );
+
+    let result = store.dispatch(Action::LspCallHierarchyPrepared {
+        items: vec![test_call_item("parse", "calls.rs", 0)],
+    });
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::LspCallHierarchyOutgoingRequest { item_index: 0, item }] if item.name == "parse"
+    ));
+
+    // 方向不符的响应（切换方向前发出的 incoming 请求）不能挂到树上。
+    let result = store.dispatch(Action::LspCallHierarchyIncoming {
+        item_index: 0,
+        items: vec![test_call_item("main", "main.rs", 1)],
+    });
+    assert!(!result.state_changed);
+
+    let result = store.dispatch(Action::LspCallHierarchyOutgoing {
+        item_index: 0,
+        items: vec![test_call_item("load", "loader.rs", 4)],
+    });
+    assert!(result.state_changed);
+    assert_eq!(store.state.call_hierarchy.rows(), &[0, 1]);
+    assert_eq!(
+        store
+            .state
+            .call_hierarchy
+            .node(1)
+            .map(|n| n.item.name.as_str()),
+        Some("load")
+    );
+
+    // 回到 incoming 时树清空、方向复位。
+    let _ = store.dispatch(Action::RunCommand(Command::LspCallHierarchy));
+    assert!(store.state.call_hierarchy.rows().is_empty());
+    assert_eq!(
+        store.state.call_hierarchy.direction(),
+        CallHierarchyDirection::Incoming
+    );
+}
+
+#[test]
+fn pane_tab_size_override_applies_only_to_that_pane() {
+    let mut store = new_store();
+    store.state.editor.ensure_panes(2);
+    store.state.ui.editor_layout.panes = 2;
+    let path = store.state.workspace_root.join("indent.txt");
+    for pane in 0..2 {
+        let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+            pane,
+            path: path.clone(),
+            content: "x".to_string(),
+        }));
+    }
+
+    let result = store.dispatch(Action::RunCommand(Command::SetPaneTabSize {
+        pane: 1,
+        tab_size: 2,
+    }));
+    assert!(result.state_changed);
+    assert_eq!(store.state.editor_config_for_pane(0).tab_size, 4);
+    assert_eq!(store.state.editor_config_for_pane(1).tab_size, 2);
+
+    // 两个 pane 各缩进三级：光标的显示列按各自的 tab 宽度计算，决定视口是否需要横向跟随。
+    for pane in 0..2 {
+        let _ = store.dispatch(Action::EditorSetActivePane { pane });
+        let _ = store.dispatch(Action::Editor(EditorAction::SetViewportSize {
+            pane,
+            width: 10,
+            height: 5,
+        }));
+        for _ in 0..3 {
+            let _ = store.dispatch(Action::RunCommand(Command::InsertTab));
+        }
+    }
+    let horiz_offset = |store: &Store, pane: usize| {
+        store
+            .state
+            .editor
+            .pane(pane)
+            .and_then(|pane| pane.active_tab())
+            .map(|tab| (tab.buffer.text(), tab.viewport.horiz_offset))
+    };
+    assert_eq!(horiz_offset(&store, 0), Some(("\t\t\tx".to_string(), 3)));
+    assert_eq!(horiz_offset(&store, 1), Some(("\t\t\tx".to_string(), 0)));
+    // 全局配置在归约后恢复原值。
+    assert_eq!(store.state.editor.config.tab_size, 4);
+
+    let result = store.dispatch(Action::RunCommand(Command::SetPaneTabSize {
+        pane: 1,
+        tab_size: 0,
+    }));
+    assert!(result.state_changed);
+    assert!(store
+        .state
+        .ui
+        .editor_layout
+        .pane_config_override(1)
+        .is_none());
+}
+
+fn test_symbol_item(name: &str, line: u32) -> crate::kernel::SymbolItem {
+    crate::kernel::SymbolItem {
+        name: name.to_string(),
+        detail: None,
+        kind: 12,
+        level: 0,
+        path: PathBuf::from("/tmp/lib.rs"),
+        line,
+        column: 0,
+    }
+}
+
+#[test]
+fn workspace_symbols_stream_in_sorted_batches_and_merge_final_response() {
+    let mut store = new_store();
+    let _ = store.dispatch(Action::LspWorkspaceSymbolsPartial {
+        items: vec![test_symbol_item("zeta", 1), test_symbol_item("alpha", 2)],
+    });
+    assert!(store.state.symbols.is_streaming());
+    assert_eq!(store.state.ui.overlay.active, Some(OverlayKind::Symbols));
+
+    let _ = store.dispatch(Action::LspWorkspaceSymbolsPartial {
+        items: vec![test_symbol_item("Beta", 3)],
+    });
+    let names = |store: &Store| {
+        store
+            .state
+            .symbols
+            .items()
+            .iter()
+            .map(|item| item.name.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(&store), ["alpha", "Beta", "zeta"]);
+
+    // 最终响应与部分结果重叠的条目只保留一份。
+    let result = store.dispatch(Action::LspWorkspaceSymbols {
+        items: vec![test_symbol_item("alpha", 2), test_symbol_item("gamma", 4)],
+    });
+    assert!(result.state_changed);
+    assert!(!store.state.symbols.is_streaming());
+    assert_eq!(names(&store), ["alpha", "Beta", "gamma", "zeta"]);
+
+    // 新一轮流式结果替换上一次查询的条目。
+    let _ = store.dispatch(Action::LspWorkspaceSymbolsPartial {
+        items: vec![test_symbol_item("delta", 5)],
+    });
+    assert_eq!(names(&store), ["delta"]);
+}
+
+#[test]
+fn reopen_closed_tab_restores_the_cursor_it_was_closed_with() {
+    let mut store = new_store();
+    let path = store.state.workspace_root.join("reopen.rs");
+    let content = "fn main() {\n    let x = 1;\n}\n".to_string();
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: path.clone(),
+        content: content.clone(),
+    }));
+    let _ = store.dispatch(Action::Editor(EditorAction::GotoByteOffset {
+        pane: 0,
+        byte_offset: 20,
+    }));
+
+    let _ = store.dispatch(Action::RunCommand(Command::CloseTab));
+    assert!(store.state.editor.panes[0].tabs.is_empty());
+    assert_eq!(
+        store.state.recently_closed_tabs.iter().collect::<Vec<_>>(),
+        vec![&(path.clone(), 20)]
+    );
+
+    let result = store.dispatch(Action::RunCommand(Command::ReopenClosedTab));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::LoadFile(p)] if *p == path
+    ));
+    assert!(store.state.recently_closed_tabs.is_empty());
+
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path,
+        content,
+    }));
+    let tab = store.state.editor.panes[0].active_tab().unwrap();
+    assert_eq!(tab.buffer.cursor(), (1, 8));
+    assert!(store.state.ui.pending_editor_nav.is_none());
+
+    let nothing = store.dispatch(Action::RunCommand(Command::ReopenClosedTab));
+    assert!(!nothing.state_changed);
+}
+
+#[test]
+fn file_positions_keep_only_the_most_recent_entries() {
+    let mut store = new_store();
+    for i in 0..MAX_FILE_POSITIONS + 5 {
+        store
+            .state
+            .remember_file_position(PathBuf::from(format!("/f{i}.rs")), (i as u32, 0));
+    }
+    store
+        .state
+        .remember_file_position(PathBuf::from("/f10.rs"), (99, 1));
+
+    let positions = &store.state.file_positions;
+    assert_eq!(positions.len(), MAX_FILE_POSITIONS);
+    assert_eq!(positions[0], (PathBuf::from("/f10.rs"), (99, 1)));
+    assert_eq!(store.state.file_position(Path::new("/f4.rs")), None);
+    assert_eq!(store.state.file_position(Path::new("/f5.rs")), Some((5, 0)));
+
+    // 载入会话时丢掉已经不存在的文件。
+    let dir = tempdir().unwrap();
+    let kept = dir.path().join("kept.rs");
+    std::fs::write(&kept, "").unwrap();
+    let saved = std::collections::VecDeque::from([
+        (dir.path().join("gone.rs"), (1, 1)),
+        (kept.clone(), (2, 2)),
+    ]);
+    store.state.restore_file_positions(&saved);
+    assert_eq!(
+        store.state.file_positions,
+        std::collections::VecDeque::from([(kept, (2, 2))])
+    );
+}
+
+#[test]
+fn reopening_a_file_restores_its_remembered_position() {
+    let mut store = new_store();
+    let path = store.state.workspace_root.join("positions.rs");
+    let content = "fn main() {\n    let x = 1;\n}\n".to_string();
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: path.clone(),
+        content: content.clone(),
+    }));
+    let _ = store.dispatch(Action::Editor(EditorAction::GotoByteOffset {
+        pane: 0,
+        byte_offset: 20,
+    }));
+
+    store.remember_open_file_positions();
+    assert_eq!(store.state.file_position(&path), Some((1, 8)));
+
+    // 已经打开的文件再次 OpenFile 不跳转。
+    let _ = store.dispatch(Action::Editor(EditorAction::GotoByteOffset {
+        pane: 0,
+        byte_offset: 0,
+    }));
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: path.clone(),
+        content: content.clone(),
+    }));
+    assert_eq!(
+        store.state.editor.panes[0]
+            .active_tab()
+            .unwrap()
+            .buffer
+            .cursor(),
+        (0, 0)
+    );
+
+    let _ = store.dispatch(Action::Editor(EditorAction::GotoByteOffset {
+        pane: 0,
+        byte_offset: 27,
+    }));
+    let _ = store.dispatch(Action::RunCommand(Command::CloseTab));
+    assert_eq!(store.state.file_position(&path), Some((2, 0)));
+
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path,
+        content,
+    }));
+    let tab = store.state.editor.panes[0].active_tab().unwrap();
+    assert_eq!(tab.buffer.cursor(), (2, 0));
+    assert!(store.state.ui.pending_editor_nav.is_none());
+}
+
+#[test]
+fn recently_closed_tabs_keep_the_latest_ten_unique_paths() {
+    let mut store = new_store();
+    for i in 0..12 {
+        store
+            .state
+            .remember_closed_tab(PathBuf::from(format!("/tmp/{i}.rs")), i);
+    }
+    store
+        .state
+        .remember_closed_tab(PathBuf::from("/tmp/5.rs"), 99);
+
+    let tabs = &store.state.recently_closed_tabs;
+    assert_eq!(tabs.len(), 10);
+    assert_eq!(tabs.front(), Some(&(PathBuf::from("/tmp/2.rs"), 2)));
+    assert_eq!(tabs.back(), Some(&(PathBuf::from("/tmp/5.rs"), 99)));
+    assert_eq!(
+        tabs.iter()
+            .filter(|(p, _)| p.as_path() == std::path::Path::new("/tmp/5.rs"))
+            .count(),
+        1
+    );
+}
+
+fn new_vim_store(content: &str) -> Store {
+    let root = std::env::temp_dir();
+    let tree = FileTree::new_with_root_for_test(OsString::from("root"), root.clone());
+    let config = EditorConfig {
+        vim_mode: true,
+        ..Default::default()
+    };
+    let mut store = Store::new(AppState::new(root.clone(), tree, config));
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: root.join("vim.txt"),
+        content: content.to_string(),
+    }));
+    store
+}
+
+fn vim_pane(store: &Store) -> &crate::kernel::editor::EditorPaneState {
+    store.state.editor.pane(0).unwrap()
+}
+
+#[test]
+fn vim_commands_are_noops_when_vim_mode_is_off() {
+    let mut store = new_store();
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: store.state.workspace_root.join("a.txt"),
+        content: "abc".to_string(),
+    }));
+    let result = store.dispatch(Action::RunCommand(Command::VimNormalMode));
+    assert!(!result.state_changed);
+    assert_eq!(vim_pane(&store).mode, EditorMode::Insert);
+}
+
+#[test]
+fn vim_escape_enters_normal_and_insert_returns() {
+    let mut store = new_vim_store("abc");
+    assert_eq!(vim_pane(&store).mode, EditorMode::Normal);
+
+    let _ = store.dispatch(Action::RunCommand(Command::VimAppend));
+    assert_eq!(vim_pane(&store).mode, EditorMode::Insert);
+    assert_eq!(
+        vim_pane(&store).active_tab().unwrap().buffer.cursor(),
+        (0, 1)
+    );
+
+    let _ = store.dispatch(Action::RunCommand(Command::Escape));
+    assert_eq!(vim_pane(&store).mode, EditorMode::Normal);
+}
+
+#[test]
+fn vim_dd_deletes_line_into_clipboard() {
+    let mut store = new_vim_store("one\ntwo\nthree");
+    let _ = store.dispatch(Action::RunCommand(Command::CursorDown));
+
+    let result = store.dispatch(Action::RunCommand(Command::VimDelete));
+    assert!(result.effects.is_empty());
+    assert_eq!(
+        vim_pane(&store).vim_pending,
+        Some(VimPending::Operator(VimOperator::Delete))
+    );
+
+    let result = store.dispatch(Action::RunCommand(Command::VimDelete));
+    assert!(result
+        .effects
+        .iter()
+        .any(|e| matches!(e, Effect::SetClipboardText(text) if text == "two\n")));
+    let tab = vim_pane(&store).active_tab().unwrap();
+    assert_eq!(tab.buffer.text(), "one\nthree");
+    assert_eq!(tab.buffer.cursor(), (1, 0));
+    assert_eq!(vim_pane(&store).mode, EditorMode::Normal);
+    assert_eq!(vim_pane(&store).vim_pending, None);
+}
+
+#[test]
+fn vim_operator_with_motion_deletes_range() {
+    let mut store = new_vim_store("foo bar");
+    let _ = store.dispatch(Action::RunCommand(Command::VimDelete));
+    let _ = store.dispatch(Action::RunCommand(Command::CursorWordRight));
+    let tab = vim_pane(&store).active_tab().unwrap();
+    assert!(tab.buffer.text().ends_with("bar"));
+    assert!(!tab.buffer.text().starts_with("foo"));
+    assert_eq!(vim_pane(&store).vim_pending, None);
+}
+
+#[test]
+fn vim_visual_extends_selection_and_yank_restores_cursor() {
+    let mut store = new_vim_store("hello");
+    let _ = store.dispatch(Action::RunCommand(Command::VimVisual));
+    assert_eq!(vim_pane(&store).mode, EditorMode::Visual);
+    let _ = store.dispatch(Action::RunCommand(Command::CursorRight));
+    let _ = store.dispatch(Action::RunCommand(Command::CursorRight));
+    let tab = vim_pane(&store).active_tab().unwrap();
+    assert_eq!(tab.buffer.selection().unwrap().range(), ((0, 0), (0, 2)));
+
+    let result = store.dispatch(Action::RunCommand(Command::VimYank));
+    assert!(result
+        .effects
+        .iter()
+        .any(|e| matches!(e, Effect::SetClipboardText(text) if text == "he")));
+    let tab = vim_pane(&store).active_tab().unwrap();
+    assert!(tab.buffer.selection().is_none());
+    assert_eq!(tab.buffer.cursor(), (0, 0));
+    assert_eq!(vim_pane(&store).mode, EditorMode::Normal);
+}
+
+#[test]
+fn vim_gg_jumps_to_file_start() {
+    let mut store = new_vim_store("a\nb\nc");
+    let _ = store.dispatch(Action::RunCommand(Command::CursorFileEnd));
+    let _ = store.dispatch(Action::RunCommand(Command::VimGoto));
+    assert_eq!(vim_pane(&store).vim_pending, Some(VimPending::Goto));
+    let _ = store.dispatch(Action::RunCommand(Command::VimGoto));
+    let tab = vim_pane(&store).active_tab().unwrap();
+    assert_eq!(tab.buffer.cursor(), (0, 0));
+    assert_eq!(vim_pane(&store).vim_pending, None);
+}
+
+#[test]
+fn lsp_rename_waits_for_prepare_rename_before_opening_dialog() {
+    let mut store = new_store();
+    let path = store.state.workspace_root.join("rename.rs");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: path.clone(),
+        content: "fn old_name() {}".to_string(),
+    }));
+    let _ = store.dispatch(Action::LspServerCapabilities {
+        server: LspServerKind::RustAnalyzer,
+        root: store.state.workspace_root.clone(),
+        capabilities: LspServerCapabilities {
+            rename: true,
+            prepare_rename: true,
+            ..Default::default()
+        },
+    });
+
+    let result = store.dispatch(Action::RunCommand(Command::LspRename));
+    assert!(!store.state.ui.input_dialog.visible);
+    let (line, column) = match result.effects.as_slice() {
+        [Effect::LspPrepareRenameRequest {
+            path: p,
+            line,
+            column,
+        }] if *p == path => (*line, *column),
+        other => panic!("unexpected effects: {other:?}"),
+    };
+
+    let result = store.dispatch(Action::LspPrepareRenameResponse {
+        path: path.clone(),
+        line,
+        column,
+        valid: false,
+        placeholder: None,
+    });
+    assert!(!result.state_changed);
+    assert!(!store.state.ui.input_dialog.visible);
+
+    let result = store.dispatch(Action::LspPrepareRenameResponse {
+        path: path.clone(),
+        line,
+        column,
+        valid: true,
+        placeholder: Some("old_name".to_string()),
+    });
+    assert!(result.state_changed);
+    let dialog = &store.state.ui.input_dialog;
+    assert!(dialog.visible);
+    assert_eq!(dialog.value, "old_name");
+    assert_eq!(dialog.cursor, "old_name".len());
+    assert!(matches!(
+        dialog.kind,
+        Some(InputDialogKind::LspRename { path: ref p, .. }) if *p == path
+    ));
+}
+
+fn utf8_edit(line: u32, start: u32, end: u32, new_text: &str) -> LspTextEdit {
+    LspTextEdit {
+        range: LspRange {
+            start: LspPosition {
+                line,
+                character: start,
+            },
+            end: LspPosition {
+                line,
+                character: end,
+            },
+        },
+        new_text: new_text.to_string(),
+    }
+}
+
+#[test]
+fn global_search_passes_filter_globs_to_effect() {
+    let mut store = new_store();
+    let _ = store.dispatch(Action::RunCommand(Command::FocusSearch));
+    let _ = store.dispatch(Action::RunCommand(Command::GlobalSearchToggleFilters));
+    for ch in "*.rs".chars() {
+        let _ = store.dispatch(Action::SearchAppend(ch));
+    }
+    let _ = store.dispatch(Action::RunCommand(Command::GlobalSearchSwitchField));
+    for ch in "target".chars() {
+        let _ = store.dispatch(Action::SearchAppend(ch));
+    }
+    assert!(store.state.search.query.is_empty());
+    assert_eq!(store.state.search.include_glob, "*.rs");
+    assert_eq!(store.state.search.exclude_glob, "target");
+
+    let _ = store.dispatch(Action::RunCommand(Command::GlobalSearchSwitchField));
+    let _ = store.dispatch(Action::SearchAppend('x'));
+    let result = store.dispatch(Action::RunCommand(Command::GlobalSearchStart));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::StartGlobalSearch { pattern, include_glob, exclude_glob, .. }]
+            if pattern == "x"
+                && include_glob.as_deref() == Some("*.rs")
+                && exclude_glob.as_deref() == Some("target")
+    ));
+}
+
+#[test]
+fn global_replace_edits_open_tabs_and_writes_closed_files() {
+    let mut store = new_store();
+    let open_path = store.state.workspace_root.join("open.txt");
+    let closed_path = store.state.workspace_root.join("closed.txt");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: open_path.clone(),
+        content: "foo foo\n".to_string(),
+    }));
+
+    let _ = store.dispatch(Action::RunCommand(Command::FocusSearch));
+    for ch in "foo".chars() {
+        let _ = store.dispatch(Action::SearchAppend(ch));
+    }
+    // 替换行未展开时不触发替换。
+    let result = store.dispatch(Action::RunCommand(Command::GlobalSearchReplaceAll));
+    assert!(result.effects.is_empty());
+
+    let _ = store.dispatch(Action::RunCommand(Command::GlobalSearchToggleReplace));
+    for ch in "bar".chars() {
+        let _ = store.dispatch(Action::SearchAppend(ch));
+    }
+    assert_eq!(store.state.search.query, "foo");
+    assert_eq!(store.state.search.replacement, "bar");
+
+    let result = store.dispatch(Action::RunCommand(Command::GlobalSearchReplaceAll));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::StartGlobalReplace { pattern, replacement, .. }]
+            if pattern == "foo" && replacement == "bar"
+    ));
+    let _ = store.dispatch(Action::SearchStarted { search_id: 7 });
+
+    let result = store.dispatch(Action::SearchMessage(GlobalSearchMessage::Replaced {
+        search_id: 7,
+        path: open_path,
+        count: 2,
+        edits: vec![utf8_edit(0, 0, 3, "bar"), utf8_edit(0, 4, 7, "bar")],
+    }));
+    assert!(result.state_changed);
+    let tab = store.state.editor.pane(0).unwrap().active_tab().unwrap();
+    assert_eq!(tab.buffer.text(), "bar bar\n");
+
+    let result = store.dispatch(Action::SearchMessage(GlobalSearchMessage::Replaced {
+        search_id: 7,
+        path: closed_path.clone(),
+        count: 1,
+        edits: vec![utf8_edit(2, 1, 4, "bar")],
+    }));
+    assert!(result.effects.iter().any(|effect| matches!(
+        effect,
+        Effect::ApplyFileEdits { position_encoding: LspPositionEncoding::Utf8, edits, .. }
+            if edits.len() == 1 && edits[0].path == closed_path
+    )));
+
+    let _ = store.dispatch(Action::SearchMessage(GlobalSearchMessage::Complete {
+        search_id: 7,
+        total_files: 2,
+        total_matches: 3,
+    }));
+    let summary = store.state.search.replace_summary.unwrap();
+    assert_eq!((summary.files, summary.occurrences), (2, 3));
+    assert!(!store.state.search.searching);
+}
+
+#[test]
+fn global_replace_skips_tabs_with_unsaved_changes() {
+    let mut store = new_store();
+    let path = store.state.workspace_root.join("dirty.txt");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: path.clone(),
+        content: "foo\n".to_string(),
+    }));
+    store
+        .state
+        .editor
+        .pane_mut(0)
+        .unwrap()
+        .active_tab_mut()
+        .unwrap()
+        .dirty = true;
+    store.state.search.query = "foo".to_string();
+    store.state.search.replace_visible = true;
+    assert!(store.state.search.begin_replace());
+    store.state.search.set_active_search_id(1);
+
+    let _ = store.dispatch(Action::SearchMessage(GlobalSearchMessage::Replaced {
+        search_id: 1,
+        path,
+        count: 1,
+        edits: vec![utf8_edit(0, 0, 3, "bar")],
+    }));
+
+    let tab = store.state.editor.pane(0).unwrap().active_tab().unwrap();
+    assert_eq!(tab.buffer.text(), "foo\n");
+    let summary = store.state.search.replace_summary.unwrap();
+    assert_eq!((summary.files, summary.failures), (0, 1));
+}
+
+#[test]
+fn tick_refreshes_unsaved_gutter_marks_only_when_enabled() {
+    use crate::kernel::editor::GutterMarkKind;
+
+    let mut store = new_store();
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: store.state.workspace_root.join("marks.txt"),
+        content: "a\nb\n".to_string(),
+    }));
+    let _ = store.dispatch(Action::RunCommand(Command::InsertChar('x')));
+    let mark = |store: &Store| {
+        store
+            .state
+            .editor
+            .pane(0)
+            .and_then(|pane| pane.active_tab())
+            .and_then(|tab| tab.unsaved_mark(0))
+    };
+    assert!(!store.tick());
+    assert_eq!(mark(&store), None);
+
+    store.state.editor.config.show_unsaved_gutter_marks = true;
+    assert!(store.tick());
+    assert_eq!(mark(&store), Some(GutterMarkKind::Modified));
+    assert!(!store.tick());
+
+    let _ = store.dispatch(Action::RunCommand(Command::Undo));
+    assert!(store.tick());
+    assert_eq!(mark(&store), None);
+}
+
+#[test]
+fn editor_config_updated_reconfigures_lsp_only_when_lsp_settings_change() {
+    use crate::kernel::services::ports::LspSettings;
+
+    let mut store = new_store();
+    let config = store.state.editor.config.clone();
+
+    let result = store.dispatch(Action::EditorConfigUpdated {
+        config: config.clone(),
+        lsp: LspSettings::default(),
+    });
+    assert!(result.effects.is_empty());
+    assert!(!result.state_changed);
+
+    let lsp = LspSettings {
+        command: Some("custom-lsp".to_string()),
+        ..Default::default()
+    };
+    let mut tab_config = config.clone();
+    tab_config.tab_size = 2;
+    let result = store.dispatch(Action::EditorConfigUpdated {
+        config: tab_config.clone(),
+        lsp: lsp.clone(),
+    });
+    assert!(result.state_changed);
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::ReconfigureLsp { settings }] if *settings == lsp
+    ));
+    assert_eq!(store.state.editor.config.tab_size, 2);
+
+    let result = store.dispatch(Action::EditorConfigUpdated {
+        config: tab_config,
+        lsp,
+    });
+    assert!(result.effects.is_empty());
+}
+
+fn open_rust_tab_with_format_on_save(store: &mut Store, content: &str) -> PathBuf {
+    store.state.editor.config.format_on_save = true;
+    let path = store.state.workspace_root.join("fmt.rs");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: path.clone(),
+        content: content.to_string(),
+    }));
+    let _ = store.dispatch(Action::LspServerCapabilities {
+        server: LspServerKind::RustAnalyzer,
+        root: store.state.workspace_root.clone(),
+        capabilities: LspServerCapabilities {
+            format: true,
+            ..Default::default()
+        },
+    });
+    path
+}
+
+fn insert_at_start(text: &str) -> LspTextEdit {
+    let start = LspPosition {
+        line: 0,
+        character: 0,
+    };
+    LspTextEdit {
+        range: LspRange { start, end: start },
+        new_text: text.to_string(),
+    }
+}
+
+#[test]
+fn format_on_save_formats_before_writing() {
+    let mut store = new_store();
+    let path = open_rust_tab_with_format_on_save(&mut store, "fn main() {}\n");
+
+    let result = store.dispatch(Action::RunCommand(Command::Save));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::LspFormatRequest { path: p }] if *p == path
+    ));
+    assert_eq!(store.state.lsp.pending_format_on_save.as_ref(), Some(&path));
+    assert_eq!(store.state.lsp.pending_format_on_save_version, Some(0));
+
+    let result = store.dispatch(Action::LspFormatCompleted {
+        path: path.clone(),
+        edits: vec![insert_at_start("// fmt\n")],
+    });
+    assert!(result
+        .effects
+        .iter()
+        .any(|effect| matches!(effect, Effect::WriteFile { path: p, .. } if *p == path)));
+    let tab = store.state.editor.pane(0).unwrap().active_tab().unwrap();
+    assert_eq!(tab.buffer.text(), "// fmt\nfn main() {}\n");
+    assert!(store.state.lsp.pending_format_on_save.is_none());
+    assert!(store.state.lsp.pending_format_on_save_version.is_none());
+}
+
+#[test]
+fn format_on_save_saves_unformatted_after_further_edits() {
+    let mut store = new_store();
+    let path = open_rust_tab_with_format_on_save(&mut store, "fn main() {}\n");
+
+    let _ = store.dispatch(Action::RunCommand(Command::Save));
+    let _ = store.dispatch(Action::RunCommand(Command::InsertChar('x')));
+
+    let result = store.dispatch(Action::LspFormatCompleted {
+        path: path.clone(),
+        edits: vec![insert_at_start("// fmt\n")],
+    });
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::WriteFile { path: p, .. }] if *p == path
+    ));
+    let tab = store.state.editor.pane(0).unwrap().active_tab().unwrap();
+    assert_eq!(tab.buffer.text(), "xfn main() {}\n");
+    assert!(store.state.lsp.pending_format_on_save.is_none());
+}
+
+#[test]
+fn format_on_save_does_not_request_again_while_pending() {
+    let mut store = new_store();
+    let path = open_rust_tab_with_format_on_save(&mut store, "fn main() {}\n");
+
+    let _ = store.dispatch(Action::RunCommand(Command::Save));
+    let result = store.dispatch(Action::RunCommand(Command::Save));
+    assert!(result.effects.is_empty());
+    assert_eq!(store.state.lsp.pending_format_on_save.as_ref(), Some(&path));
+
+    let _ = store.dispatch(Action::LspFormatCompleted {
+        path: path.clone(),
+        edits: vec![insert_at_start("// fmt\n")],
+    });
+    let tab = store.state.editor.pane(0).unwrap().active_tab().unwrap();
+    assert_eq!(tab.buffer.text(), "// fmt\nfn main() {}\n");
+}
+
+#[test]
+fn format_on_save_saves_unformatted_when_server_connection_drops() {
+    let mut store = new_store();
+    let path = open_rust_tab_with_format_on_save(&mut store, "fn main() {}\n");
+    let _ = store.dispatch(Action::RunCommand(Command::Save));
+
+    let result = store.dispatch(Action::LspConnectionChanged {
+        server: LspServerKind::RustAnalyzer,
+        root: store.state.workspace_root.clone(),
+        status: LspConnectionStatus::Failed,
+    });
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::WriteFile { path: p, .. }] if *p == path
+    ));
+    assert!(store.state.lsp.pending_format_on_save.is_none());
+}
+
+#[test]
+fn format_on_save_without_format_capability_writes_directly() {
+    let mut store = new_store();
+    store.state.editor.config.format_on_save = true;
+    let path = store.state.workspace_root.join("plain.rs");
+    let _ = store.dispatch(Action::Editor(EditorAction::OpenFile {
+        pane: 0,
+        path: path.clone(),
+        content: "fn main() {}\n".to_string(),
+    }));
+
+    let result = store.dispatch(Action::RunCommand(Command::Save));
+    assert!(matches!(
+        result.effects.as_slice(),
+        [Effect::WriteFile { path: p, .. }] if *p == path
+    ));
+    assert!(store.state.lsp.pending_format_on_save.is_none());
+}
+
+#[test]
+fn lsp_connection_changes_drop_stale_capabilities_until_reconnected() {
+    let mut store = new_store();
+    let root = store.state.workspace_root.clone();
+    let key = crate::kernel::services::ports::LspClientKey {
+        server: LspServerKind::RustAnalyzer,
+        root: root.clone(),
+    };
+    let _ = store.dispatch(Action::LspServerCapabilities {
+        server: LspServerKind::RustAnalyzer,
+        root: root.clone(),
+        capabilities: LspServerCapabilities::default(),
+    });
+    assert!(store.state.lsp.server_capabilities.contains_key(&key));
+
+    let status = LspConnectionStatus::Reconnecting {
+        attempt: 1,
+        retry_at: Instant::now(),
+    };
+    let result = store.dispatch(Action::LspConnectionChanged {
+        server: LspServerKind::RustAnalyzer,
+        root: root.clone(),
+        status,
+    });
+    assert!(result.state_changed);
+    assert!(!store.state.lsp.server_capabilities.contains_key(&key));
+    assert_eq!(store.state.lsp.connection.get(&key), Some(&status));
+
+    let result = store.dispatch(Action::LspServerCapabilities {
+        server: LspServerKind::RustAnalyzer,
+        root,
+        capabilities: LspServerCapabilities::default(),
+    });
+    assert!(result.state_changed);
+    assert!(store.state.lsp.connection.is_empty());
+    assert!(store.state.lsp.server_capabilities.contains_key(&key));
+}
@@ -86,6 +86,7 @@ fn test_completion_item(id: u64) -> LspCompletionItem {
         id,
         label: format!("item_{id:05}"),
         detail: None,
+        label_details: None,
         kind: Some(3),
         documentation: None,
         insert_text: format!("item_{id:05}"),
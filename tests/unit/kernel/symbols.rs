@@ -0,0 +1,57 @@
+use super::*;
+
+fn symbol(name: &str, path: &str) -> SymbolItem {
+    SymbolItem {
+        name: name.to_string(),
+        detail: None,
+        kind: 12,
+        level: 0,
+        path: PathBuf::from(path),
+        line: 0,
+        column: 0,
+    }
+}
+
+fn names(state: &SymbolsState) -> Vec<&str> {
+    state
+        .items()
+        .iter()
+        .map(|item| item.name.as_str())
+        .collect()
+}
+
+#[test]
+fn workspace_symbols_are_ranked_by_fuzzy_score() {
+    let mut state = SymbolsState::default();
+    state.set_query("parse".to_string());
+
+    assert!(state.finish_streaming(vec![
+        symbol("compare_strings", "a.rs"),
+        symbol("parse_args", "b.rs"),
+        symbol("Parser", "c.rs"),
+        symbol("p_a_r_s_e", "d.rs"),
+        symbol("unrelated", "e.rs"),
+    ]));
+
+    assert_eq!(
+        names(&state),
+        vec![
+            "parse_args",
+            "Parser",
+            "p_a_r_s_e",
+            "compare_strings",
+            "unrelated"
+        ]
+    );
+}
+
+#[test]
+fn workspace_symbols_without_query_keep_name_order() {
+    let mut state = SymbolsState::default();
+    state.set_query("zz".to_string());
+    let _ = state.clear();
+    assert_eq!(state.query(), "");
+
+    let _ = state.extend_streaming(vec![symbol("beta", "a.rs"), symbol("Alpha", "b.rs")]);
+    assert_eq!(names(&state), vec!["Alpha", "beta"]);
+}
@@ -130,3 +130,169 @@ fn clear_resets_history() {
     assert!(!history.is_dirty());
     assert!(history.head().is_root());
 }
+
+#[test]
+fn dirty_byte_range_spans_undo_back_past_the_save_point_and_a_new_branch() {
+    let saved_text = "héllo wörld, bye";
+    let mut rope = Rope::from_str("héllo, bye");
+    let mut history = EditHistory::new();
+    assert_eq!(history.dirty_byte_range(&rope), None);
+
+    let op = EditOp::insert(
+        history.head(),
+        5,
+        CompactString::new(" wörld"),
+        (0, 5),
+        (0, 11),
+    );
+    op.apply(&mut rope);
+    history.push(op);
+    history.on_save();
+    assert_eq!(rope.to_string(), saved_text);
+    assert_eq!(history.dirty_byte_range(&rope), None);
+
+    // undo 到保存点之前，再在另一条分支上改开头和结尾。
+    rope = history.undo(&rope).unwrap().rope;
+    let op = EditOp::replace(
+        history.head(),
+        0,
+        1,
+        CompactString::new("h"),
+        CompactString::new("J"),
+        (0, 0),
+        (0, 1),
+    );
+    op.apply(&mut rope);
+    history.push(op);
+    let op = EditOp::delete(
+        history.head(),
+        5,
+        10,
+        CompactString::new(", bye"),
+        (0, 10),
+        (0, 5),
+    );
+    op.apply(&mut rope);
+    history.push(op);
+    assert_eq!(rope.to_string(), "Jéllo");
+
+    let dirty = history.dirty_byte_range(&rope).unwrap();
+    assert_eq!(dirty.disk_len, saved_text.len());
+    let mut rebuilt = saved_text.as_bytes()[..dirty.disk.start].to_vec();
+    rebuilt.extend_from_slice(rope.byte_slice(dirty.buffer.clone()).to_string().as_bytes());
+    rebuilt.extend_from_slice(&saved_text.as_bytes()[dirty.disk.end..]);
+    assert_eq!(String::from_utf8(rebuilt).unwrap(), "Jéllo");
+    assert_eq!(dirty.disk.start, 0);
+    assert_eq!(history.saved_rope(&rope).unwrap().to_string(), saved_text);
+}
+
+#[test]
+fn saved_rope_rebuilds_the_saved_content() {
+    let mut rope = Rope::from_str("one\ntwo\n");
+    let mut history = EditHistory::new();
+    assert_eq!(history.saved_rope(&rope).unwrap().to_string(), "one\ntwo\n");
+
+    let op = EditOp::insert(
+        history.head(),
+        4,
+        CompactString::new("new\n"),
+        (1, 0),
+        (2, 0),
+    );
+    op.apply(&mut rope);
+    history.push(op);
+    history.on_save();
+    let op = EditOp::delete(
+        history.head(),
+        0,
+        4,
+        CompactString::new("one\n"),
+        (0, 0),
+        (0, 0),
+    );
+    op.apply(&mut rope);
+    history.push(op);
+
+    assert_eq!(rope.to_string(), "new\ntwo\n");
+    assert_eq!(
+        history.saved_rope(&rope).unwrap().to_string(),
+        "one\nnew\ntwo\n"
+    );
+}
+
+#[test]
+fn dirty_byte_range_covers_only_the_edited_middle() {
+    let mut rope = Rope::from_str("aaaa\nbbbb\ncccc\n");
+    let mut history = EditHistory::new();
+    let op = EditOp::replace(
+        history.head(),
+        5,
+        9,
+        CompactString::new("bbbb"),
+        CompactString::new("BB"),
+        (1, 0),
+        (1, 2),
+    );
+    op.apply(&mut rope);
+    history.push(op);
+
+    let dirty = history.dirty_byte_range(&rope).unwrap();
+    assert_eq!(dirty.disk, 5..9);
+    assert_eq!(dirty.buffer, 5..7);
+    assert_eq!(dirty.disk_len, 15);
+}
+
+#[test]
+fn backup_roundtrip_restores_undo_chain_at_saved_head() {
+    let dir = tempfile::tempdir().unwrap();
+    let ops_path = dir.path().join("file.ops");
+
+    let mut history = EditHistory::new();
+    let mut rope = Rope::from_str("hello");
+    let op = EditOp::insert(
+        history.head(),
+        5,
+        CompactString::new(" world"),
+        (0, 5),
+        (0, 11),
+    );
+    op.apply(&mut rope);
+    history.push(op);
+    history.on_save();
+
+    history.to_backup(&rope).write_to(&ops_path).unwrap();
+
+    // 换一种分块方式构造同样的内容，哈希仍然对得上。
+    let mut disk = Rope::from_str("hello");
+    disk.insert(5, " world");
+    let mut restored = EditHistory::replay_from_backup(&ops_path, &disk).unwrap();
+    assert!(!restored.is_dirty());
+    assert!(restored.can_undo());
+    assert!(!restored.can_redo());
+
+    let undo = restored.undo(&disk).unwrap();
+    assert_eq!(undo.rope.to_string(), "hello");
+    assert!(restored.is_dirty());
+}
+
+#[test]
+fn replay_from_backup_drops_stale_backup() {
+    let dir = tempfile::tempdir().unwrap();
+    let ops_path = dir.path().join("file.ops");
+
+    let mut history = EditHistory::new();
+    let mut rope = Rope::from_str("a");
+    let op = EditOp::insert(history.head(), 1, CompactString::new("b"), (0, 1), (0, 2));
+    op.apply(&mut rope);
+    history.push(op);
+    history.to_backup(&rope).write_to(&ops_path).unwrap();
+
+    // 文件在备份之后被外部改过。
+    let err = EditHistory::replay_from_backup(&ops_path, &Rope::from_str("abc")).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(!ops_path.exists());
+
+    std::fs::write(&ops_path, b"{ not json").unwrap();
+    assert!(EditHistory::replay_from_backup(&ops_path, &rope).is_err());
+    assert!(!ops_path.exists());
+}
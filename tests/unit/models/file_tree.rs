@@ -178,3 +178,43 @@ fn test_flatten_for_view_hides_root_so_top_level_depth_is_zero() {
     assert_eq!(depth_of("app"), Some(1), "二层目录应为 depth 1");
     assert_eq!(depth_of("main.rs"), Some(2), "三层文件应为 depth 2");
 }
+
+#[test]
+fn test_mount_resolves_paths_from_its_own_root() {
+    let mut tree = FileTree::new_with_root("app".into(), PathBuf::from("/work/app"));
+    let root = tree.root();
+    tree.insert_child(root, "src".into(), NodeKind::Dir)
+        .unwrap();
+
+    let lib = tree.add_mount(PathBuf::from("/elsewhere/lib")).unwrap();
+    let file = tree
+        .insert_child(lib, "mod.rs".into(), NodeKind::File)
+        .unwrap();
+
+    assert_eq!(tree.get_name(lib), Some(&OsString::from("lib")));
+    assert_eq!(
+        tree.full_path_ro(file),
+        Some(PathBuf::from("/elsewhere/lib/mod.rs"))
+    );
+    assert_eq!(tree.full_path(lib), PathBuf::from("/elsewhere/lib"));
+    assert_eq!(
+        tree.find_node_by_path_ro(Path::new("/elsewhere/lib/mod.rs")),
+        Some(file)
+    );
+    assert_eq!(
+        tree.find_node_by_path_ro(Path::new("/work/app/lib/mod.rs")),
+        None
+    );
+
+    let names: Vec<_> = tree
+        .flatten_for_view()
+        .into_iter()
+        .filter(|row| row.depth == 0)
+        .map(|row| row.name)
+        .collect();
+    assert_eq!(names, vec![OsString::from("lib"), OsString::from("src")]);
+
+    tree.remove_mount(Path::new("/elsewhere/lib")).unwrap();
+    assert!(tree.get_name(file).is_none());
+    assert_eq!(tree.find_node_by_path_ro(Path::new("/elsewhere/lib")), None);
+}
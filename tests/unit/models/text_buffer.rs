@@ -208,3 +208,129 @@ fn replace_range_op_adjust_cursor_moves_cursor_after_insertion_at_cursor() {
     assert_eq!(buffer.text(), "aXYZbc");
     assert_eq!(buffer.cursor(), (0, 4));
 }
+
+fn word_at(text: &str, byte: usize) -> Option<&str> {
+    let buffer = TextBuffer::from_text(text);
+    buffer.word_range_at_byte(byte).map(|range| &text[range])
+}
+
+#[test]
+fn word_range_at_byte_ascii() {
+    let text = "let foo_bar = baz2;";
+    assert_eq!(word_at(text, 0), Some("let"));
+    assert_eq!(word_at(text, 4), Some("foo_bar"));
+    assert_eq!(word_at(text, 8), Some("foo_bar"));
+    // 紧贴词尾（光标停在词后）仍属于该词。
+    assert_eq!(word_at(text, 11), Some("foo_bar"));
+    assert_eq!(word_at(text, 12), None);
+    assert_eq!(word_at(text, 14), Some("baz2"));
+    assert_eq!(word_at(text, 18), Some("baz2"));
+    assert_eq!(word_at(text, 19), None);
+}
+
+#[test]
+fn word_range_at_byte_cjk_is_one_word() {
+    let text = "变量 名字_1 。";
+    assert_eq!(word_at(text, 0), Some("变量"));
+    assert_eq!(word_at(text, 3), Some("变量"));
+    assert_eq!(word_at(text, "变量 ".len()), Some("名字_1"));
+    let full_stop = text.find('。').unwrap();
+    assert_eq!(word_at(text, full_stop + '。'.len_utf8()), None);
+}
+
+#[test]
+fn word_range_at_byte_rust_lifetime_excludes_apostrophe() {
+    let text = "fn f<'a>(x: &'a str)";
+    let tick = text.find("'a").unwrap();
+    assert_eq!(word_at(text, tick), None);
+    assert_eq!(word_at(text, tick + 1), Some("a"));
+    assert_eq!(
+        TextBuffer::from_text(text).word_range_at_byte(tick + 1),
+        Some(tick + 1..tick + 2)
+    );
+}
+
+#[test]
+fn word_range_at_byte_emoji_and_punctuation_are_not_words() {
+    let text = "a👍b + c";
+    assert_eq!(word_at(text, 0), Some("a"));
+    // emoji 本身不是单词字符，但紧贴左侧的 `a` 之后。
+    assert_eq!(word_at(text, 1), Some("a"));
+    assert_eq!(word_at(text, 1 + '👍'.len_utf8()), Some("b"));
+    assert_eq!(word_at(text, text.find('+').unwrap()), None);
+    // 字节偏移落在 emoji 内部时按该字符处理。
+    assert_eq!(word_at(text, 2), Some("a"));
+    assert_eq!(word_at("", 0), None);
+    assert_eq!(word_at("abc", 99), Some("abc"));
+}
+
+#[test]
+fn detect_line_endings_reads_the_first_kilobyte() {
+    assert_eq!(
+        detect_line_endings(&Rope::from_str("a\nb\n")),
+        LineEndingStyle::Lf
+    );
+    assert_eq!(
+        detect_line_endings(&Rope::from_str("a\r\nb\r\n")),
+        LineEndingStyle::CrLf
+    );
+    assert_eq!(
+        detect_line_endings(&Rope::from_str("a\rb\r")),
+        LineEndingStyle::Cr
+    );
+    assert_eq!(
+        detect_line_endings(&Rope::from_str("a\r\nb\nc")),
+        LineEndingStyle::Mixed
+    );
+    assert_eq!(scan_line_endings(&Rope::from_str("no newline")), None);
+    assert_eq!(
+        detect_line_endings(&Rope::from_str("")),
+        LineEndingStyle::Lf
+    );
+
+    // `\r\n` 跨过 1000 字节边界仍算 CRLF；边界之后的 LF 不参与判断。
+    let mut text = "x".repeat(999);
+    text.push_str("\r\n");
+    text.push_str("tail\n");
+    assert_eq!(
+        detect_line_endings(&Rope::from_str(&text)),
+        LineEndingStyle::CrLf
+    );
+}
+
+#[test]
+fn normalize_line_endings_rewrites_every_ending() {
+    let mut rope = Rope::from_str("a\r\nb\nc\rd");
+    assert!(normalize_line_endings(&mut rope, LineEndingStyle::CrLf));
+    assert_eq!(rope.to_string(), "a\r\nb\r\nc\r\nd");
+    assert!(!normalize_line_endings(&mut rope, LineEndingStyle::CrLf));
+
+    assert!(normalize_line_endings(&mut rope, LineEndingStyle::Lf));
+    assert_eq!(rope.to_string(), "a\nb\nc\nd");
+
+    let mut mixed = Rope::from_str("a\r\nb\n");
+    assert!(!normalize_line_endings(&mut mixed, LineEndingStyle::Mixed));
+    assert_eq!(mixed.to_string(), "a\r\nb\n");
+}
+
+#[test]
+fn stray_line_endings_lists_endings_that_differ_from_target() {
+    let rope = Rope::from_str("a\r\nb\nc\rd");
+    let stray = |range: std::ops::Range<usize>, target| {
+        stray_line_endings(&rope, range, target).collect::<Vec<_>>()
+    };
+    assert_eq!(
+        stray(0..rope.len_chars(), LineEndingStyle::CrLf),
+        vec![4..5, 6..7]
+    );
+    assert_eq!(
+        stray(0..rope.len_chars(), LineEndingStyle::Lf),
+        vec![1..3, 6..7]
+    );
+    assert!(stray(0..rope.len_chars(), LineEndingStyle::Mixed).is_empty());
+
+    // 区间只切到 `\r\n` 的一半时整对算进来，不会把它拆成 `\r` + `\n`。
+    assert_eq!(stray(2..3, LineEndingStyle::Lf), vec![1..3]);
+    assert_eq!(stray(0..2, LineEndingStyle::Lf), vec![1..3]);
+    assert!(stray(2..3, LineEndingStyle::CrLf).is_empty());
+}
@@ -0,0 +1,13 @@
+use super::*;
+use std::path::PathBuf;
+
+#[test]
+fn default_shell_is_never_empty() {
+    assert!(!default_shell().is_empty());
+}
+
+#[test]
+fn shell_title_shows_working_directory() {
+    let cwd = PathBuf::from("/work/project/src");
+    assert_eq!(shell_title(&cwd), format!("zcode: {}", cwd.display()));
+}
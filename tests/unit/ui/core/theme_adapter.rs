@@ -58,6 +58,11 @@ fn ansi256_fallback_converts_syntax_colors_to_indexed_palette() {
         adapted.syntax_fg(SyntaxColorGroup::Regex),
         Color::Indexed(167)
     );
+    assert_eq!(
+        adapted.syntax_fg(SyntaxColorGroup::Operator),
+        Color::Indexed(188)
+    );
+    assert_eq!(adapted.syntax_fg(SyntaxColorGroup::Tag), Color::Indexed(44));
 }
 
 #[test]
@@ -114,6 +119,11 @@ fn ansi16_fallback_converts_syntax_colors_to_indexed_palette() {
         adapted.syntax_fg(SyntaxColorGroup::Regex),
         Color::Indexed(9)
     );
+    assert_eq!(
+        adapted.syntax_fg(SyntaxColorGroup::Operator),
+        Color::Indexed(7)
+    );
+    assert_eq!(adapted.syntax_fg(SyntaxColorGroup::Tag), Color::Indexed(6));
 }
 
 #[test]
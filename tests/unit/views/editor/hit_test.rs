@@ -24,11 +24,14 @@ fn layout_with_content_area(content_area: Rect) -> crate::views::EditorPaneLayou
     crate::views::EditorPaneLayout {
         area: Rect::new(0, 0, 80, 24),
         tab_area: Rect::new(0, 0, 80, 1),
+        breadcrumb_area: None,
         search_area: None,
         editor_area: Rect::new(0, 1, 80, 23),
         gutter_area: Rect::new(0, 1, content_area.x, content_area.h),
         content_area,
         v_scrollbar_area: None,
+        h_scrollbar_area: None,
+        minimap_area: None,
         gutter_width: content_area.x,
     }
 }
@@ -390,3 +393,38 @@ fn editor_vertical_scrollbar_hit_test_distinguishes_thumb_and_track() {
         Some(EditorVerticalScrollbarHitResult::Track { .. })
     ));
 }
+
+#[test]
+fn gutter_hit_test_returns_row_offset_only_inside_gutter() {
+    let layout = layout_with_content_area(Rect::new(5, 2, 40, 20));
+    assert_eq!(hit_test_editor_gutter(&layout, 4, 3), Some(2));
+    assert_eq!(hit_test_editor_gutter(&layout, 0, 1), Some(0));
+    assert_eq!(hit_test_editor_gutter(&layout, 5, 3), None);
+}
+
+#[test]
+fn hit_test_breadcrumbs_maps_columns_to_symbol_segments() {
+    let config = EditorConfig {
+        breadcrumbs_enabled: true,
+        ..EditorConfig::default()
+    };
+    let mut pane = EditorPaneState::new();
+    let mut tab = EditorTabState::from_file(
+        TabId::new(1),
+        std::path::PathBuf::from("a.rs"),
+        "impl A {\n    fn run() {}\n}\n",
+        &config,
+    );
+    tab.buffer.set_cursor(1, 8);
+    tab.refresh_breadcrumbs();
+    pane.tabs.push(tab);
+    pane.active = 0;
+    let layout = crate::views::compute_editor_pane_layout(Rect::new(0, 0, 40, 10), &pane, &config);
+
+    // " a.rs › impl A › fn run"
+    assert_eq!(hit_test_breadcrumbs(&layout, &pane, 2, 1), Some(None));
+    assert_eq!(hit_test_breadcrumbs(&layout, &pane, 8, 1), Some(Some(0)));
+    assert_eq!(hit_test_breadcrumbs(&layout, &pane, 18, 1), Some(Some(1)));
+    assert_eq!(hit_test_breadcrumbs(&layout, &pane, 35, 1), Some(None));
+    assert_eq!(hit_test_breadcrumbs(&layout, &pane, 8, 2), None);
+}
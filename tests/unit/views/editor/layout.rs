@@ -52,6 +52,39 @@ fn layout_shows_vertical_scrollbar_when_file_exceeds_viewport() {
     );
 }
 
+#[test]
+fn layout_reserves_horizontal_scrollbar_row_and_thumb_tracks_offset() {
+    let config = EditorConfig {
+        horiz_scroll_indicator_enabled: true,
+        ..EditorConfig::default()
+    };
+    let pane = pane_with_text(&config, "short\n");
+    let layout = compute_editor_pane_layout(Rect::new(0, 0, 40, 10), &pane, &config);
+
+    let track = layout
+        .h_scrollbar_area
+        .expect("horizontal scrollbar row should be reserved");
+    assert_eq!(track.y, layout.editor_area.bottom());
+    assert_eq!(
+        (track.x, track.w),
+        (layout.content_area.x, layout.content_area.w)
+    );
+    assert!(horizontal_scrollbar_metrics(&layout, 0, 0).is_none());
+
+    // 内容是视口的两倍宽：滑块占一半，滚到底时贴住右端。
+    let max_offset = track.w as u32;
+    let start = horizontal_scrollbar_metrics(&layout, max_offset, 0).unwrap();
+    assert_eq!(start.thumb_area.w, track.w / 2);
+    assert_eq!(start.thumb_area.x, track.x);
+    let end = horizontal_scrollbar_metrics(&layout, max_offset, max_offset).unwrap();
+    assert_eq!(end.thumb_area.right(), track.right());
+
+    let disabled =
+        compute_editor_pane_layout(Rect::new(0, 0, 40, 10), &pane, &EditorConfig::default());
+    assert!(disabled.h_scrollbar_area.is_none());
+    assert_eq!(disabled.editor_area.h, layout.editor_area.h + 1);
+}
+
 // ---- compute_pane_rects: 单编辑区，恒返回铺满 area 的单一矩形 ----
 
 #[test]
@@ -68,3 +101,20 @@ fn pane_rects_fills_area() {
         assert_eq!(rects.outer, rects.inner);
     }
 }
+
+#[test]
+fn layout_reserves_breadcrumb_row_below_tabs_when_enabled() {
+    let config = EditorConfig {
+        breadcrumbs_enabled: true,
+        ..EditorConfig::default()
+    };
+    let pane = pane_with_text(&config, "fn main() {}\n");
+    let layout = compute_editor_pane_layout(Rect::new(0, 0, 40, 10), &pane, &config);
+
+    assert_eq!(layout.breadcrumb_area, Some(Rect::new(0, 1, 40, 1)));
+    assert_eq!(layout.editor_area, Rect::new(0, 2, 40, 8));
+
+    let empty = EditorPaneState::new();
+    let layout = compute_editor_pane_layout(Rect::new(0, 0, 40, 10), &empty, &config);
+    assert!(layout.breadcrumb_area.is_none());
+}
@@ -0,0 +1,75 @@
+use super::*;
+use crate::kernel::editor::{EditorPaneState, TabId};
+use crate::kernel::services::ports::EditorConfig;
+use crate::views::editor::layout::compute_editor_pane_layout;
+use std::path::PathBuf;
+
+fn pane_with_lines(config: &EditorConfig, lines: usize) -> EditorPaneState {
+    let text = (0..lines)
+        .map(|i| format!("line {i}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut pane = EditorPaneState::new();
+    pane.tabs.push(EditorTabState::from_file(
+        TabId::new(1),
+        PathBuf::from("test.txt"),
+        &text,
+        config,
+    ));
+    pane.active = 0;
+    pane
+}
+
+#[test]
+fn layout_carves_minimap_only_when_enabled() {
+    let mut config = EditorConfig::default();
+    let pane = pane_with_lines(&config, 10);
+    let area = Rect::new(0, 0, 80, 20);
+
+    let plain = compute_editor_pane_layout(area, &pane, &config);
+    assert!(plain.minimap_area.is_none());
+
+    config.minimap_enabled = true;
+    let layout = compute_editor_pane_layout(area, &pane, &config);
+    let minimap = layout.minimap_area.expect("minimap area");
+    assert_eq!(minimap.w, MINIMAP_WIDTH);
+    assert_eq!(minimap.x, layout.content_area.right());
+    assert_eq!(layout.content_area.w, plain.content_area.w - MINIMAP_WIDTH);
+}
+
+#[test]
+fn minimap_slides_with_viewport_and_click_centers_line() {
+    let config = EditorConfig {
+        minimap_enabled: true,
+        ..EditorConfig::default()
+    };
+    let pane = pane_with_lines(&config, 200);
+    let layout = compute_editor_pane_layout(Rect::new(0, 0, 80, 21), &pane, &config);
+    let viewport = layout.editor_area.h as usize;
+
+    let top = minimap_metrics(&layout, 200, viewport, 0).expect("metrics");
+    assert_eq!(top.first_line, 0);
+    assert_eq!(top.line_at_row(top.area.y + 3), 6);
+    assert_eq!(top.line_offset_for_row(top.area.y + 10), 20 - viewport / 2);
+
+    let bottom = minimap_metrics(&layout, 200, viewport, 200 - viewport).expect("metrics");
+    assert_eq!(bottom.first_line, 200 - top.area.h as usize * 2);
+    let last_row = bottom.area.bottom() - 1;
+    assert_eq!(bottom.line_offset_for_row(last_row), 200 - viewport);
+}
+
+#[test]
+fn line_ink_marks_non_blank_columns_and_expands_tabs() {
+    let config = EditorConfig::default();
+    let tab = EditorTabState::from_file(
+        TabId::new(1),
+        PathBuf::from("a.txt"),
+        "ab      cd\n\tx\n   \n",
+        &config,
+    );
+
+    assert_eq!(line_ink(&tab, 0, 4).columns, 0b101);
+    assert_eq!(line_ink(&tab, 1, 4).columns, 0b10);
+    assert_eq!(line_ink(&tab, 2, 4).columns, 0);
+    assert_eq!(line_ink(&tab, 0, 4).group, None);
+}
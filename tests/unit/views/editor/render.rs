@@ -12,7 +12,9 @@ use crate::ui::core::painter::{PaintCmd, Painter};
 use crate::ui::core::theme::Theme;
 use std::path::PathBuf;
 
-fn default_render_options(show_vertical_scrollbar: bool) -> crate::views::EditorPaneRenderOptions {
+fn default_render_options(
+    show_vertical_scrollbar: bool,
+) -> crate::views::EditorPaneRenderOptions<'static> {
     crate::views::EditorPaneRenderOptions {
         show_vertical_scrollbar,
         ..Default::default()
@@ -82,6 +84,160 @@ fn paint_editor_pane_gutter_renders_full_two_digit_line_number() {
     assert_eq!(buf.cell(last_digit_x, y).unwrap().symbol, "0");
 }
 
+#[test]
+fn paint_editor_pane_unsaved_marks_take_the_innermost_gutter_column() {
+    let config = EditorConfig {
+        show_unsaved_gutter_marks: true,
+        ..EditorConfig::default()
+    };
+    let mut pane = EditorPaneState::new();
+    let tab = EditorTabState::from_file(
+        TabId::new(1),
+        PathBuf::from("test.txt"),
+        "a\nb\nc\n",
+        &config,
+    );
+    pane.tabs.push(tab);
+    pane.active = 0;
+    let tab = &mut pane.tabs[0];
+    tab.buffer.set_cursor(1, 1);
+    assert!(tab.insert_text("\nnew", config.tab_size));
+    assert!(tab.refresh_unsaved_marks());
+
+    let layout = crate::views::compute_editor_pane_layout(Rect::new(0, 0, 40, 10), &pane, &config);
+    assert_eq!(layout.gutter_width, 3);
+    let theme = Theme::default();
+    let mut painter = Painter::new();
+    paint_editor_pane(
+        &mut painter,
+        &layout,
+        &pane,
+        &config,
+        &theme,
+        default_render_options(false),
+        None,
+    );
+
+    let mut backend = TestBackend::new(layout.area.w, layout.area.h);
+    backend.draw(layout.area, painter.cmds());
+    let buf = backend.buffer();
+    let mark_x = layout.content_area.x - 1;
+    let y = layout.content_area.y;
+    assert_eq!(buf.cell(mark_x, y).unwrap().symbol, " ");
+    let added = buf.cell(mark_x, y + 2).unwrap();
+    assert_eq!(added.symbol, "▎");
+    assert_eq!(added.style.fg, Some(theme.diff_added_fg));
+    // 行号仍在标记列左侧。
+    assert_eq!(buf.cell(mark_x - 2, y + 2).unwrap().symbol, "3");
+}
+
+#[test]
+fn paint_editor_pane_rulers_sit_behind_text() {
+    let config = EditorConfig {
+        ruler_columns: vec![4, 200],
+        ..EditorConfig::default()
+    };
+    let mut pane = EditorPaneState::new();
+    let tab = EditorTabState::from_file(
+        TabId::new(1),
+        PathBuf::from("test.txt"),
+        "ab\nabcdefgh\n",
+        &config,
+    );
+    pane.tabs.push(tab);
+    pane.active = 0;
+
+    let layout = crate::views::compute_editor_pane_layout(Rect::new(0, 0, 40, 10), &pane, &config);
+    let theme = Theme::default();
+    let mut painter = Painter::new();
+    paint_editor_pane(
+        &mut painter,
+        &layout,
+        &pane,
+        &config,
+        &theme,
+        default_render_options(false),
+        None,
+    );
+
+    let mut backend = TestBackend::new(layout.area.w, layout.area.h);
+    backend.draw(layout.area, painter.cmds());
+    let buf = backend.buffer();
+    let x = layout.content_area.x + 4;
+    let y = layout.content_area.y;
+    let ruler = buf.cell(x, y).unwrap();
+    assert_eq!(ruler.symbol, RULER_SYMBOL);
+    assert_eq!(ruler.style.fg, Some(theme.ruler_fg));
+    assert_eq!(buf.cell(x, y + 1).unwrap().symbol, "e");
+    assert_eq!(buf.cell(x + 1, y).unwrap().symbol, " ");
+}
+
+#[test]
+fn paint_editor_pane_word_wrap_continues_long_line_on_next_rows() {
+    let config = EditorConfig {
+        word_wrap: true,
+        ..EditorConfig::default()
+    };
+    let mut pane = EditorPaneState::new();
+    let content = format!("{}\nend\n", "abcdefghij".repeat(3));
+    let tab =
+        EditorTabState::from_file(TabId::new(1), PathBuf::from("test.txt"), &content, &config);
+    pane.tabs.push(tab);
+    pane.active = 0;
+
+    let layout = crate::views::compute_editor_pane_layout(Rect::new(0, 0, 20, 10), &pane, &config);
+    let width = layout.content_area.w as usize;
+    assert!(width < 30);
+    let tab = &mut pane.tabs[0];
+    tab.viewport.width = width;
+    tab.set_word_wrap(true, config.tab_size);
+    tab.buffer.set_cursor(0, width + 2);
+
+    let theme = Theme::default();
+    let mut painter = Painter::new();
+    paint_editor_pane(
+        &mut painter,
+        &layout,
+        &pane,
+        &config,
+        &theme,
+        default_render_options(false),
+        None,
+    );
+
+    let mut backend = TestBackend::new(layout.area.w, layout.area.h);
+    backend.draw(layout.area, painter.cmds());
+    let buf = backend.buffer();
+    let row_text = |y: u16| -> String {
+        (0..layout.content_area.w)
+            .map(|dx| {
+                buf.cell(layout.content_area.x + dx, y)
+                    .unwrap()
+                    .symbol
+                    .clone()
+            })
+            .collect::<String>()
+    };
+
+    let y = layout.content_area.y;
+    let line = "abcdefghij".repeat(3);
+    assert_eq!(row_text(y), line[..width]);
+    assert!(row_text(y + 1).starts_with(&line[width..(2 * width).min(30)]));
+    let line_rows = 30usize.div_ceil(width) as u16;
+    assert!(row_text(y + line_rows).starts_with("end"));
+
+    // 后续段的行号栏留空，下一逻辑行的行号照常画在它的首段上。
+    let digit_x = layout.content_area.x - 2;
+    assert_eq!(buf.cell(digit_x, y).unwrap().symbol, "1");
+    assert_eq!(buf.cell(digit_x, y + 1).unwrap().symbol, " ");
+    assert_eq!(buf.cell(digit_x, y + line_rows).unwrap().symbol, "2");
+
+    assert_eq!(
+        cursor_position_editor(&layout, &pane, &config),
+        Some((layout.content_area.x + 2, y + 1))
+    );
+}
+
 #[test]
 fn paint_editor_pane_search_bar_draws_find_label() {
     let config = EditorConfig::default();
@@ -193,6 +349,46 @@ fn paint_editor_pane_search_matches_use_match_and_current_match_backgrounds() {
     );
 }
 
+#[test]
+fn paint_editor_pane_bracket_match_uses_bracket_match_colors() {
+    let config = EditorConfig::default();
+    let mut pane = EditorPaneState::new();
+    let mut tab = EditorTabState::from_file(
+        TabId::new(1),
+        PathBuf::from("test.rs"),
+        "f(a, b)\n",
+        &config,
+    );
+    tab.buffer.set_cursor(0, 1);
+    pane.tabs.push(tab);
+    pane.active = 0;
+
+    let layout = crate::views::compute_editor_pane_layout(Rect::new(0, 0, 40, 6), &pane, &config);
+    let theme = Theme::default();
+    let mut painter = Painter::new();
+    paint_editor_pane(
+        &mut painter,
+        &layout,
+        &pane,
+        &config,
+        &theme,
+        default_render_options(true),
+        None,
+    );
+
+    let mut backend = TestBackend::new(layout.area.w, layout.area.h);
+    backend.draw(layout.area, painter.cmds());
+    let buf = backend.buffer();
+
+    let y = layout.content_area.y;
+    let close_cell = buf.cell(layout.content_area.x + 6, y).unwrap();
+    assert_eq!(close_cell.symbol, ")");
+    assert_eq!(close_cell.style.fg, Some(theme.bracket_match_fg));
+    assert_eq!(close_cell.style.bg, Some(theme.bracket_match_bg));
+    let plain_cell = buf.cell(layout.content_area.x + 2, y).unwrap();
+    assert_ne!(plain_cell.style.bg, Some(theme.bracket_match_bg));
+}
+
 #[test]
 fn paint_editor_pane_transient_row_highlight_applies_destination_background() {
     let config = EditorConfig::default();
@@ -234,6 +430,52 @@ fn paint_editor_pane_transient_row_highlight_applies_destination_background() {
     assert_ne!(untouched.style.bg, Some(theme.search_current_match_bg));
 }
 
+#[test]
+fn paint_editor_pane_inline_blame_follows_cursor_line_in_muted_style() {
+    let config = EditorConfig::default();
+    let mut pane = EditorPaneState::new();
+    pane.tabs.push(EditorTabState::from_file(
+        TabId::new(1),
+        PathBuf::from("test.txt"),
+        "alpha\nbeta\n",
+        &config,
+    ));
+    pane.active = 0;
+
+    let layout = crate::views::compute_editor_pane_layout(Rect::new(0, 0, 60, 6), &pane, &config);
+    let theme = Theme::default();
+    let mut painter = Painter::new();
+    paint_editor_pane(
+        &mut painter,
+        &layout,
+        &pane,
+        &config,
+        &theme,
+        crate::views::EditorPaneRenderOptions {
+            inline_blame: Some("⏎ Jane Doe • 3 hours ago"),
+            ..default_render_options(false)
+        },
+        None,
+    );
+
+    let mut backend = TestBackend::new(layout.area.w, layout.area.h);
+    backend.draw(layout.area, painter.cmds());
+    let buf = backend.buffer();
+
+    let row_text = |y: u16| -> String {
+        (layout.content_area.x..layout.content_area.right())
+            .map(|x| buf.cell(x, y).unwrap().symbol.clone())
+            .collect()
+    };
+    let cursor_row = row_text(layout.content_area.y);
+    assert!(cursor_row.starts_with("alpha   ⏎ Jane Doe • 3 hours ago"));
+    assert!(!row_text(layout.content_area.y + 1).contains("Jane"));
+
+    let blame_x = layout.content_area.x + "alpha   ".len() as u16;
+    let cell = buf.cell(blame_x, layout.content_area.y).unwrap();
+    assert_eq!(cell.style.fg, Some(theme.palette_muted_fg));
+}
+
 #[test]
 fn paint_editor_pane_selection_background_overrides_search_match_background() {
     let config = EditorConfig::default();
@@ -736,3 +978,69 @@ fn build_syntax_highlights_returns_owned_for_sparse_visible_lines() {
     let syntax = build_syntax_highlights(&tab, &visible).expect("syntax available");
     assert!(matches!(syntax, SyntaxHighlightLines::Owned(_)));
 }
+
+#[test]
+fn paint_editor_pane_diagnostics_underline_with_severity_colors() {
+    use crate::kernel::{ProblemItem, ProblemRange, ProblemSeverity};
+    use crate::ui::core::style::Mod;
+
+    let config = EditorConfig::default();
+    let mut pane = EditorPaneState::new();
+    let path = PathBuf::from("test.rs");
+    pane.tabs.push(EditorTabState::from_file(
+        TabId::new(1),
+        path.clone(),
+        "let x = 1\nfoo()\n",
+        &config,
+    ));
+    pane.active = 0;
+
+    let problem = |line, start_col, end_col, severity| ProblemItem {
+        path: path.clone(),
+        range: ProblemRange {
+            start_line: line,
+            start_col,
+            end_line: line,
+            end_col,
+        },
+        severity,
+        message: String::new(),
+        source: None,
+    };
+    let diagnostics = vec![
+        problem(0, 4, 5, ProblemSeverity::Error),
+        problem(1, 0, 3, ProblemSeverity::Warning),
+        problem(1, 4, 4, ProblemSeverity::Hint),
+    ];
+
+    let layout = crate::views::compute_editor_pane_layout(Rect::new(0, 0, 40, 6), &pane, &config);
+    let theme = Theme::default();
+    let mut painter = Painter::new();
+    let options = crate::views::EditorPaneRenderOptions {
+        diagnostics: &diagnostics,
+        ..default_render_options(true)
+    };
+    paint_editor_pane(&mut painter, &layout, &pane, &config, &theme, options, None);
+
+    let mut backend = TestBackend::new(layout.area.w, layout.area.h);
+    backend.draw(layout.area, painter.cmds());
+    let buf = backend.buffer();
+    let x = layout.content_area.x;
+    let y = layout.content_area.y;
+
+    let error_cell = buf.cell(x + 4, y).unwrap();
+    assert_eq!(error_cell.symbol, "x");
+    assert_eq!(error_cell.style.fg, Some(theme.error_fg));
+    assert!(error_cell.style.mods.contains(Mod::UNDERLINE));
+    assert!(!buf.cell(x, y).unwrap().style.mods.contains(Mod::UNDERLINE));
+
+    let warning_cell = buf.cell(x + 2, y + 1).unwrap();
+    assert_eq!(warning_cell.style.fg, Some(theme.warning_fg));
+    assert!(warning_cell.style.mods.contains(Mod::UNDERLINE));
+
+    // 空区间扩成一个字符。
+    let hint_cell = buf.cell(x + 4, y + 1).unwrap();
+    assert_eq!(hint_cell.symbol, ")");
+    assert_eq!(hint_cell.style.fg, Some(theme.hint_fg));
+    assert!(hint_cell.style.mods.contains(Mod::UNDERLINE));
+}
@@ -19,9 +19,10 @@ fn test_explorer_active_open_file_uses_header_bold_style() {
         is_dir: false,
         is_expanded: false,
         load_state: LoadState::Loaded,
+        size: None,
     };
 
-    let (_left_pad, row_style) = view.render_row_parts(&row, false, true, 20, &theme);
+    let (_left_pad, row_style) = view.render_row_parts(&row, false, true, false, 20, &theme);
 
     assert_eq!(row_style.fg, Some(theme.header_fg));
     assert!(row_style.mods.contains(crate::ui::core::style::Mod::BOLD));
@@ -38,9 +39,10 @@ fn test_explorer_selected_style_overrides_active_open_file_style() {
         is_dir: false,
         is_expanded: false,
         load_state: LoadState::Loaded,
+        size: None,
     };
 
-    let (_left_pad, row_style) = view.render_row_parts(&row, true, true, 20, &theme);
+    let (_left_pad, row_style) = view.render_row_parts(&row, true, true, false, 20, &theme);
 
     assert_eq!(row_style.bg, Some(theme.palette_selected_bg));
     assert_eq!(row_style.fg, Some(theme.palette_selected_fg));
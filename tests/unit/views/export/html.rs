@@ -0,0 +1,42 @@
+use super::*;
+use crate::kernel::editor::TabId;
+use crate::kernel::services::ports::EditorConfig;
+use std::path::PathBuf;
+
+fn tab(name: &str, text: &str) -> EditorTabState {
+    EditorTabState::from_file(
+        TabId::new(1),
+        PathBuf::from(name),
+        text,
+        &EditorConfig::default(),
+    )
+}
+
+#[test]
+fn export_wraps_highlight_spans_in_colored_spans() {
+    let theme = Theme::default();
+    let html = export_as_html(&tab("app.rb", "def run\n  nil\nend\n"), &theme);
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>app.rb</title>"));
+    let keyword = css_color(
+        theme.syntax_fg(crate::kernel::editor::HighlightKind::Keyword.color_group()),
+        FALLBACK_FG,
+    );
+    assert!(html.contains(&format!("<span style=\"color:{keyword}\">def</span>")));
+    assert!(html.ends_with("</code></pre>\n</body>\n</html>\n"));
+}
+
+#[test]
+fn export_escapes_markup_and_uses_theme_colors() {
+    let theme = Theme {
+        editor_bg: Color::Rgb(0x10, 0x20, 0x30),
+        palette_fg: Color::Indexed(15),
+        ..Theme::default()
+    };
+    let html = export_as_html(&tab("notes.txt", "a < b && \"c\" > d\n"), &theme);
+
+    assert!(html.contains("a &lt; b &amp;&amp; &quot;c&quot; &gt; d\n</code>"));
+    assert!(html.contains("background:#102030"));
+    assert!(html.contains("color:#ffffff"));
+}